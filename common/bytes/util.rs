@@ -134,6 +134,10 @@ impl<'a> Base64Formatter<'a> {
     pub fn format(&self) -> String {
         base64::engine::general_purpose::STANDARD.encode(&self.0)
     }
+
+    pub fn parse(text: &str) -> Option<Vec<u8>> {
+        base64::engine::general_purpose::STANDARD.decode(text).ok()
+    }
 }
 
 impl fmt::Display for Base64Formatter<'_> {
@@ -4,17 +4,32 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+//! The options of transactions and queries, shared by every endpoint. The protocol has no field for some of them,
+//! such as cascading type deletion, deferred validation, dry runs and omitting null columns, so only HTTP requests can
+//! set those: gRPC requests leave them at their defaults.
+
 use resource::constants::server::{
-    DEFAULT_ANSWER_COUNT_LIMIT_GRPC, DEFAULT_ANSWER_COUNT_LIMIT_HTTP, DEFAULT_INCLUDE_INSTANCE_TYPES,
-    DEFAULT_INCLUDE_STRUCTURE_GRPC, DEFAULT_INCLUDE_STRUCTURE_HTTP, DEFAULT_PREFETCH_SIZE,
+    DEFAULT_ANSWER_BATCH_SIZE, DEFAULT_ANSWER_COUNT_LIMIT_GRPC, DEFAULT_ANSWER_COUNT_LIMIT_HTTP,
+    DEFAULT_CASCADE_TYPE_DELETION, DEFAULT_DEFER_VALIDATION, DEFAULT_DRY_RUN, DEFAULT_INCLUDE_INSTANCE_IIDS,
+    DEFAULT_INCLUDE_INSTANCE_TYPES, DEFAULT_INCLUDE_QUERY_STATS, DEFAULT_INCLUDE_STRUCTURE_GRPC,
+    DEFAULT_INCLUDE_STRUCTURE_HTTP, DEFAULT_INCLUDE_VALUE_TYPES, DEFAULT_OMIT_NULL_COLUMNS, DEFAULT_PREFETCH_SIZE,
     DEFAULT_SCHEMA_LOCK_ACQUIRE_TIMEOUT_MILLIS, DEFAULT_TRANSACTION_PARALLEL, DEFAULT_TRANSACTION_TIMEOUT_MILLIS,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TransactionOptions {
     pub parallel: bool,
     pub schema_lock_acquire_timeout_millis: u64,
     pub transaction_timeout_millis: u64,
+    /// Whether undefining a type in a schema transaction also deletes its instances, instead of failing.
+    pub cascade_type_deletion: bool,
+    /// Whether write and schema transactions validate the abstractness and the value and uniqueness constraints of
+    /// written data on commit instead of on each write, to speed up bulk loads. Such errors are then only reported on
+    /// commit. Imports can set this too
+    pub defer_validation: bool,
+    /// The commit sequence number to read the database at, instead of the latest. Only valid for read transactions
+    pub read_at_sequence_number: Option<u64>,
+    pub isolation_level: IsolationLevel,
 }
 
 impl Default for TransactionOptions {
@@ -23,34 +38,91 @@ impl Default for TransactionOptions {
             parallel: DEFAULT_TRANSACTION_PARALLEL,
             schema_lock_acquire_timeout_millis: DEFAULT_SCHEMA_LOCK_ACQUIRE_TIMEOUT_MILLIS,
             transaction_timeout_millis: DEFAULT_TRANSACTION_TIMEOUT_MILLIS,
+            cascade_type_deletion: DEFAULT_CASCADE_TYPE_DELETION,
+            defer_validation: DEFAULT_DEFER_VALIDATION,
+            read_at_sequence_number: None,
+            isolation_level: IsolationLevel::default(),
         }
     }
 }
 
+/// Which commits the queries of a transaction observe
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum IsolationLevel {
+    /// All queries read the database as it was when the transaction was opened
+    #[default]
+    Snapshot,
+    /// Each query reads the latest committed state, so long-lived read transactions observe the commits made since
+    /// their previous query. Only valid for read transactions that read the latest data
+    ReadCommitted,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct QueryOptions {
     pub include_instance_types: bool,
+    /// Whether JSON answers include the IIDs of instances. Protocol answers always carry them
+    pub include_instance_iids: bool,
+    /// Whether JSON answers tag values with their value type. Protocol answers always carry them
+    pub include_value_types: bool,
+    /// How JSON answers render temporal values. Protocol answers carry them typed, so this does not apply
+    pub temporal_format: TemporalFormat,
     pub answer_count_limit: Option<usize>,
     pub prefetch_size: usize,
+    /// The maximum number of answers sent in one streamed response part
+    pub answer_batch_size: usize,
     pub include_query_structure: bool,
+    /// Whether row answers leave out the variables that are empty in every row, such as those bound only in
+    /// optional patterns that never matched. Otherwise, empty variables are answered as explicit nulls.
+    pub omit_null_columns: bool,
+    /// Whether answers include execution statistics, such as planning and execution times and storage reads.
+    /// Collecting them enables profiling of the query, which has a small overhead
+    pub include_query_stats: bool,
+    /// Whether a schema query is only validated, including the commit-time checks against existing data,
+    /// and answers with the schema changes it would make instead of making them
+    pub dry_run: bool,
 }
 
 impl QueryOptions {
     pub fn default_grpc() -> Self {
         Self {
             include_instance_types: DEFAULT_INCLUDE_INSTANCE_TYPES,
+            include_instance_iids: DEFAULT_INCLUDE_INSTANCE_IIDS,
+            include_value_types: DEFAULT_INCLUDE_VALUE_TYPES,
+            temporal_format: TemporalFormat::default(),
             answer_count_limit: DEFAULT_ANSWER_COUNT_LIMIT_GRPC,
             prefetch_size: DEFAULT_PREFETCH_SIZE,
+            answer_batch_size: DEFAULT_ANSWER_BATCH_SIZE,
             include_query_structure: DEFAULT_INCLUDE_STRUCTURE_GRPC,
+            omit_null_columns: DEFAULT_OMIT_NULL_COLUMNS,
+            include_query_stats: DEFAULT_INCLUDE_QUERY_STATS,
+            dry_run: DEFAULT_DRY_RUN,
         }
     }
 
     pub fn default_http() -> Self {
         Self {
             include_instance_types: DEFAULT_INCLUDE_INSTANCE_TYPES,
+            include_instance_iids: DEFAULT_INCLUDE_INSTANCE_IIDS,
+            include_value_types: DEFAULT_INCLUDE_VALUE_TYPES,
+            temporal_format: TemporalFormat::default(),
             answer_count_limit: DEFAULT_ANSWER_COUNT_LIMIT_HTTP,
             prefetch_size: DEFAULT_PREFETCH_SIZE,
+            answer_batch_size: DEFAULT_ANSWER_BATCH_SIZE,
             include_query_structure: DEFAULT_INCLUDE_STRUCTURE_HTTP,
+            omit_null_columns: DEFAULT_OMIT_NULL_COLUMNS,
+            include_query_stats: DEFAULT_INCLUDE_QUERY_STATS,
+            dry_run: DEFAULT_DRY_RUN,
         }
     }
 }
+
+/// How temporal values are rendered in textual (JSON) answers
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum TemporalFormat {
+    /// ISO-8601 strings, matching TypeQL literals
+    #[default]
+    Iso8601,
+    /// Milliseconds since the Unix epoch. Dates are taken at midnight UTC, and datetimes without a timezone as UTC.
+    /// Durations have no epoch representation and remain ISO-8601 strings.
+    EpochMillis,
+}
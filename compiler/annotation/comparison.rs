@@ -0,0 +1,96 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::{HashMap, HashSet};
+
+use answer::{variable::Variable, Type};
+use concept::type_::type_manager::TypeManager;
+use encoding::value::value_type::ValueTypeCategory;
+use ir::{
+    pattern::{conjunction::Conjunction, nested_pattern::NestedPattern, Vertex},
+    pipeline::ParameterRegistry,
+};
+use storage::snapshot::ReadableSnapshot;
+
+use crate::annotation::{
+    expression::compiled_expression::ExpressionValueType,
+    type_annotations::{BlockAnnotations, ComparisonValueTypes, TypeAnnotations},
+    AnnotationError,
+};
+
+// Attribute variables may take types with different value types, so the value types a comparison is evaluated in
+// are resolved per pair of operand value types. Comparisons where no pair is comparable can never be satisfied,
+// which is reported as a warning: like any other pattern that cannot match, they simply produce no answers.
+pub(crate) fn annotate_comparisons(
+    snapshot: &impl ReadableSnapshot,
+    type_manager: &TypeManager,
+    parameters: &ParameterRegistry,
+    conjunction: &Conjunction,
+    block_annotations: &mut BlockAnnotations,
+) -> Result<(), AnnotationError> {
+    let type_annotations = block_annotations.type_annotations_of(conjunction).unwrap();
+    let mut comparison_value_types = HashMap::new();
+    for comparison in conjunction.constraints().iter().filter_map(|constraint| constraint.as_comparison()) {
+        let lhs_value_types =
+            operand_value_types(snapshot, type_manager, parameters, type_annotations, comparison.lhs())?;
+        let rhs_value_types =
+            operand_value_types(snapshot, type_manager, parameters, type_annotations, comparison.rhs())?;
+        let (Some(lhs_value_types), Some(rhs_value_types)) = (lhs_value_types, rhs_value_types) else {
+            continue;
+        };
+        let value_types = ComparisonValueTypes::new(comparison.comparator(), &lhs_value_types, &rhs_value_types);
+        comparison_value_types.insert(comparison.clone(), value_types);
+    }
+    block_annotations.type_annotations_mut_of(conjunction).unwrap().set_comparison_value_types(comparison_value_types);
+
+    conjunction.nested_patterns().iter().try_for_each(|nested| match nested {
+        NestedPattern::Disjunction(disjunction) => disjunction
+            .conjunctions()
+            .iter()
+            .try_for_each(|branch| annotate_comparisons(snapshot, type_manager, parameters, branch, block_annotations)),
+        NestedPattern::Negation(negation) => {
+            annotate_comparisons(snapshot, type_manager, parameters, negation.conjunction(), block_annotations)
+        }
+        NestedPattern::Optional(optional) => {
+            annotate_comparisons(snapshot, type_manager, parameters, optional.conjunction(), block_annotations)
+        }
+    })
+}
+
+fn operand_value_types(
+    snapshot: &impl ReadableSnapshot,
+    type_manager: &TypeManager,
+    parameters: &ParameterRegistry,
+    type_annotations: &TypeAnnotations,
+    operand: &Vertex<Variable>,
+) -> Result<Option<HashSet<ValueTypeCategory>>, AnnotationError> {
+    match operand {
+        Vertex::Variable(_) => {
+            if let Some(types) = type_annotations.vertex_annotations_of(operand) {
+                let mut value_types = HashSet::new();
+                for attribute_type in types.iter().filter_map(|type_| match type_ {
+                    Type::Attribute(attribute_type) => Some(attribute_type),
+                    _ => None,
+                }) {
+                    let value_type = attribute_type
+                        .get_value_type_without_source(snapshot, type_manager)
+                        .map_err(|typedb_source| AnnotationError::ConceptRead { typedb_source })?;
+                    value_types.extend(value_type.map(|value_type| value_type.category()));
+                }
+                Ok((!value_types.is_empty()).then_some(value_types))
+            } else {
+                match type_annotations.value_type_annotations_of(operand) {
+                    Some(ExpressionValueType::Single(value_type)) => Ok(Some(HashSet::from([value_type.category()]))),
+                    Some(ExpressionValueType::List(_)) | None => Ok(None),
+                }
+            }
+        }
+        Vertex::Parameter(parameter) => {
+            Ok(parameters.value(parameter).map(|value| HashSet::from([value.value_type().category()])))
+        }
+        Vertex::Label(_) => Ok(None),
+    }
+}
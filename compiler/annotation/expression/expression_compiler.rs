@@ -27,8 +27,8 @@ use crate::annotation::expression::{
     compiled_expression::{ExecutableExpression, ExpressionValueType},
     instructions::{
         binary::{
-            MathMaxDecimalDecimal, MathMaxDoubleDouble, MathMaxIntegerInteger, MathMinDecimalDecimal,
-            MathMinDoubleDouble, MathMinIntegerInteger,
+            InTimezoneDateTimeTZString, MathMaxDecimalDecimal, MathMaxDoubleDouble, MathMaxIntegerInteger,
+            MathMinDecimalDecimal, MathMinDoubleDouble, MathMinIntegerInteger,
         },
         list_operations,
         load_cast::{
@@ -644,6 +644,29 @@ impl<'this> ExpressionCompilationContext<'this> {
                     })?,
                 }
             }
+            BuiltinValueFunctionID::InTimezone => {
+                self.compile_recursive(self.expression_tree.get(builtin.argument_expression_ids()[0]))?;
+                let arg_1_category = self.peek_type_single()?.category();
+                self.compile_recursive(self.expression_tree.get(builtin.argument_expression_ids()[1]))?;
+                let arg_2_category = self.peek_type_single()?.category();
+                match (arg_1_category, arg_2_category) {
+                    (ValueTypeCategory::DateTimeTZ, ValueTypeCategory::String) => {
+                        InTimezoneDateTimeTZString::validate_and_append(self)?
+                    }
+                    (ValueTypeCategory::DateTimeTZ, _) => {
+                        Err(ExpressionCompileError::UnsupportedArgumentsForBuiltin {
+                            function: builtin.function_id(),
+                            category: arg_2_category,
+                            source_span: builtin.source_span(),
+                        })?
+                    }
+                    _ => Err(ExpressionCompileError::UnsupportedArgumentsForBuiltin {
+                        function: builtin.function_id(),
+                        category: arg_1_category,
+                        source_span: builtin.source_span(),
+                    })?,
+                }
+            }
         }
         Ok(())
     }
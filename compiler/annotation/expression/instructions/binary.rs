@@ -3,9 +3,12 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
-use std::{cmp, marker::PhantomData, ops::Rem};
+use std::{borrow::Cow, cmp, marker::PhantomData, ops::Rem};
 
-use encoding::value::{decimal_value::Decimal, value::NativeValueConvertible, value_type::ValueTypeCategory};
+use chrono::DateTime;
+use encoding::value::{
+    decimal_value::Decimal, timezone::TimeZone, value::NativeValueConvertible, value_type::ValueTypeCategory,
+};
 
 use crate::annotation::expression::{
     expression_compiler::ExpressionCompilationContext,
@@ -105,4 +108,10 @@ binary_instruction! { 'a
     MathMaxIntegerInteger = MathMaxIntegerIntegerImpl(a1: i64, a2: i64) -> i64 { Ok(cmp::max(a1, a2)) }
     MathMaxDoubleDouble = MathMaxDoubleDoubleImpl(a1: f64, a2: f64) -> f64 { Ok(f64::max(a1, a2)) }
     MathMaxDecimalDecimal = MathMaxDecimalDecimalImpl(a1: Decimal, a2: Decimal) -> Decimal { Ok(cmp::max(a1, a2)) }
+
+    InTimezoneDateTimeTZString = InTimezoneDateTimeTZStringImpl(a1: DateTime<TimeZone>, a2: Cow<'a, str>) -> DateTime<TimeZone> {
+        let time_zone = TimeZone::parse(&a2)
+            .ok_or_else(|| ExpressionEvaluationError::InvalidTimeZone { name: a2.into_owned() })?;
+        Ok(a1.with_timezone(&time_zone))
+    }
 }
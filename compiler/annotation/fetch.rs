@@ -29,6 +29,7 @@ use typeql::common::Span;
 use crate::annotation::{
     expression::compiled_expression::ExpressionValueType,
     function::{annotate_anonymous_function, AnnotatedFunction, AnnotatedFunctionSignatures},
+    limits::CompileLimits,
     pipeline::{annotate_stages_and_fetch, AnnotatedStage},
     AnnotationError,
 };
@@ -75,6 +76,7 @@ pub(crate) fn annotate_fetch(
     annotated_function_signatures: &dyn AnnotatedFunctionSignatures,
     input_type_annotations: &BTreeMap<Variable, Arc<BTreeSet<Type>>>,
     input_value_type_annotations: &BTreeMap<Variable, ExpressionValueType>,
+    compile_limits: &CompileLimits,
 ) -> Result<AnnotatedFetch, AnnotationError> {
     let object = annotate_object(
         fetch,
@@ -85,6 +87,7 @@ pub(crate) fn annotate_fetch(
         annotated_function_signatures,
         input_type_annotations,
         input_value_type_annotations,
+        compile_limits,
     )?;
     Ok(AnnotatedFetch { object })
 }
@@ -98,6 +101,7 @@ fn annotate_object(
     annotated_function_signatures: &dyn AnnotatedFunctionSignatures,
     input_type_annotations: &BTreeMap<Variable, Arc<BTreeSet<Type>>>,
     input_value_type_annotations: &BTreeMap<Variable, ExpressionValueType>,
+    compile_limits: &CompileLimits,
 ) -> Result<AnnotatedFetchObject, AnnotationError> {
     match object {
         FetchObject::Entries(entries, source_spans) => {
@@ -111,6 +115,7 @@ fn annotate_object(
                 annotated_function_signatures,
                 input_type_annotations,
                 input_value_type_annotations,
+                compile_limits,
             )?;
             Ok(AnnotatedFetchObject::Entries(annotated_entries))
         }
@@ -128,6 +133,7 @@ fn annotated_object_entries(
     annotated_function_signatures: &dyn AnnotatedFunctionSignatures,
     input_type_annotations: &BTreeMap<Variable, Arc<BTreeSet<Type>>>,
     input_value_type_annotations: &BTreeMap<Variable, ExpressionValueType>,
+    compile_limits: &CompileLimits,
 ) -> Result<HashMap<ParameterID, AnnotatedFetchSome>, AnnotationError> {
     let mut annotated_entries = HashMap::new();
     for (key, value) in entries.into_iter() {
@@ -142,6 +148,7 @@ fn annotated_object_entries(
             input_type_annotations,
             input_value_type_annotations,
             source_span,
+            compile_limits,
         )
         .map_err(|err| AnnotationError::FetchEntry {
             key: parameters.fetch_key(&key).unwrap().clone(),
@@ -162,6 +169,7 @@ fn annotate_some(
     input_type_annotations: &BTreeMap<Variable, Arc<BTreeSet<Type>>>,
     input_value_type_annotations: &BTreeMap<Variable, ExpressionValueType>,
     source_span: Option<Span>,
+    compile_limits: &CompileLimits,
 ) -> Result<AnnotatedFetchSome, AnnotationError> {
     match some {
         FetchSome::SingleVar(var) => Ok(AnnotatedFetchSome::SingleVar(var)),
@@ -197,6 +205,7 @@ fn annotate_some(
                 input_type_annotations,
                 input_value_type_annotations,
                 source_span,
+                compile_limits,
             )
             .map_err(|err| AnnotationError::FetchBlockFunctionInferenceError { typedb_source: err })?;
             Ok(AnnotatedFetchSome::SingleFunction(annotated_function))
@@ -211,6 +220,7 @@ fn annotate_some(
                 annotated_function_signatures,
                 input_type_annotations,
                 input_value_type_annotations,
+                compile_limits,
             )?;
             Ok(AnnotatedFetchSome::Object(Box::new(object)))
         }
@@ -223,6 +233,7 @@ fn annotate_some(
                 input_type_annotations,
                 input_value_type_annotations,
                 source_span,
+                compile_limits,
             )
             .map_err(|err| AnnotationError::FetchBlockFunctionInferenceError { typedb_source: err })?;
             Ok(AnnotatedFetchSome::ListFunction(annotated_function))
@@ -236,6 +247,7 @@ fn annotate_some(
                 sub_fetch,
                 input_type_annotations,
                 input_value_type_annotations,
+                compile_limits,
             );
             Ok(AnnotatedFetchSome::ListSubFetch(annotated_sub_fetch?))
         }
@@ -357,6 +369,7 @@ fn annotate_sub_fetch(
     sub_fetch: FetchListSubFetch,
     input_type_annotations: &BTreeMap<Variable, Arc<BTreeSet<Type>>>,
     input_value_type_annotations: &BTreeMap<Variable, ExpressionValueType>,
+    compile_limits: &CompileLimits,
 ) -> Result<AnnotatedFetchListSubFetch, AnnotationError> {
     let FetchListSubFetch { context, input_variables, stages, fetch } = sub_fetch;
     let PipelineTranslationContext { mut variable_registry, .. } = context;
@@ -370,6 +383,8 @@ fn annotate_sub_fetch(
         Some(fetch),
         input_type_annotations.clone(),
         input_value_type_annotations.clone(),
+        compile_limits,
+        None,
     )?;
     Ok(AnnotatedFetchListSubFetch {
         variable_registry,
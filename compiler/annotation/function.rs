@@ -37,6 +37,7 @@ use typeql::{
 use crate::{
     annotation::{
         expression::compiled_expression::ExpressionValueType,
+        limits::CompileLimits,
         pipeline::{annotate_pipeline_stages, resolve_reducer_by_value_type, AnnotatedStage},
         type_seeder, FunctionAnnotationError, TypeInferenceError,
     },
@@ -171,7 +172,14 @@ pub fn annotate_stored_functions(
     let preliminary_signature_annotations = functions
         .iter_mut()
         .map(|(function_id, function)| {
-            Ok((function_id.clone(), annotate_named_function(function, snapshot, type_manager, &declared_annotations)?))
+            let annotated_function = annotate_named_function(
+                function,
+                snapshot,
+                type_manager,
+                &declared_annotations,
+                &CompileLimits::UNLIMITED,
+            )?;
+            Ok((function_id.clone(), annotated_function))
         })
         .collect::<Result<_, Box<FunctionAnnotationError>>>()?;
     let preliminary_signature_annotations =
@@ -182,7 +190,13 @@ pub fn annotate_stored_functions(
         .map(|(id, function)| {
             Ok((
                 id.clone(),
-                annotate_named_function(function, snapshot, type_manager, &preliminary_signature_annotations)?,
+                annotate_named_function(
+                    function,
+                    snapshot,
+                    type_manager,
+                    &preliminary_signature_annotations,
+                    &CompileLimits::UNLIMITED,
+                )?,
             ))
         })
         .collect::<Result<_, Box<FunctionAnnotationError>>>()?;
@@ -199,6 +213,7 @@ pub fn annotate_preamble_functions(
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
     schema_function_signatures: Arc<AnnotatedSchemaFunctions>,
+    compile_limits: &CompileLimits,
 ) -> Result<AnnotatedPreambleFunctions, Box<FunctionAnnotationError>> {
     let preamble_annotations_from_labels_as_map = functions
         .iter()
@@ -208,14 +223,30 @@ pub fn annotate_preamble_functions(
         AnnotatedFunctionSignaturesImpl::new(&schema_function_signatures, &preamble_annotations_from_labels_as_map);
     let preliminary_signature_annotations_as_map = functions
         .iter_mut()
-        .map(|function| annotate_named_function(function, snapshot, type_manager, &label_based_signature_annotations))
+        .map(|function| {
+            annotate_named_function(
+                function,
+                snapshot,
+                type_manager,
+                &label_based_signature_annotations,
+                compile_limits,
+            )
+        })
         .collect::<Result<_, Box<FunctionAnnotationError>>>()?;
     // In the second round, finer annotations are available at the function calls so the annotations in function bodies can be refined.
     let preliminary_signature_annotations =
         AnnotatedFunctionSignaturesImpl::new(&schema_function_signatures, &preliminary_signature_annotations_as_map);
     let annotated_functions = functions
         .iter_mut()
-        .map(|function| annotate_named_function(function, snapshot, type_manager, &preliminary_signature_annotations))
+        .map(|function| {
+            annotate_named_function(
+                function,
+                snapshot,
+                type_manager,
+                &preliminary_signature_annotations,
+                compile_limits,
+            )
+        })
         .collect::<Result<_, Box<FunctionAnnotationError>>>()?;
 
     // TODO: ^Optimise. There's no reason to do all of type inference again. We can re-use the graphs, and restart at the source of any SCC.
@@ -233,6 +264,7 @@ pub(crate) fn annotate_anonymous_function(
     caller_type_annotations: &BTreeMap<Variable, Arc<BTreeSet<Type>>>,
     caller_value_type_annotations: &BTreeMap<Variable, ExpressionValueType>,
     _source_span: Option<Span>,
+    compile_limits: &CompileLimits,
 ) -> Result<AnnotatedFunction, Box<FunctionAnnotationError>> {
     let Function { arguments, argument_labels, .. } = function;
     debug_assert!(argument_labels.is_none());
@@ -254,6 +286,7 @@ pub(crate) fn annotate_anonymous_function(
         annotated_function_signatures,
         argument_concept_variable_types,
         argument_value_variable_types,
+        compile_limits,
     )
 }
 
@@ -262,6 +295,7 @@ pub(super) fn annotate_named_function(
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
     annotated_function_signatures: &dyn AnnotatedFunctionSignatures,
+    compile_limits: &CompileLimits,
 ) -> Result<AnnotatedFunction, Box<FunctionAnnotationError>> {
     let Function { arguments, argument_labels, .. } = function;
     debug_assert!(argument_labels.is_some());
@@ -316,6 +350,7 @@ pub(super) fn annotate_named_function(
         annotated_function_signatures,
         argument_concept_variable_types,
         argument_value_variable_types,
+        compile_limits,
     )
 }
 
@@ -326,6 +361,7 @@ fn annotate_function_impl(
     annotated_function_signatures: &dyn AnnotatedFunctionSignatures,
     argument_concept_variable_types: BTreeMap<Variable, Arc<BTreeSet<Type>>>,
     argument_value_variable_types: BTreeMap<Variable, ExpressionValueType>,
+    compile_limits: &CompileLimits,
 ) -> Result<AnnotatedFunction, Box<FunctionAnnotationError>> {
     let Function {
         name, context, parameters, function_body: FunctionBody { stages, return_operation }, arguments, ..
@@ -341,6 +377,8 @@ fn annotate_function_impl(
         argument_concept_variable_types.clone(),
         argument_value_variable_types.clone(),
         Some(return_operation.variables().as_ref()),
+        compile_limits,
+        None,
     )
     .map_err(|err| {
         Box::new(FunctionAnnotationError::TypeInference { name: name.to_string(), typedb_source: Box::new(err) })
@@ -0,0 +1,146 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use ir::{
+    pattern::{conjunction::Conjunction, nested_pattern::NestedPattern},
+    pipeline::function::Function,
+    translation::pipeline::TranslatedStage,
+};
+use resource::constants::compiler::{
+    MAX_DISJUNCTION_BRANCHES, MAX_PATTERN_CONSTRAINTS, MAX_REDUCE_LIST_SIZE, MAX_TYPE_COMBINATIONS,
+};
+
+use crate::annotation::AnnotationError;
+
+/// Bounds on the queries accepted for annotation, so that pathological queries are rejected
+/// instead of exhausting memory during type inference, and on what they may collect once executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompileLimits {
+    /// The most constraints across all patterns of a query, including its preamble functions.
+    pub max_pattern_constraints: usize,
+    /// The most combinations of disjunction branches a query may be executed through, including its preamble
+    /// functions. Branches of nested disjunctions multiply with the branches they are nested in.
+    pub max_disjunction_branches: usize,
+    /// The most pairs of types the schema admits for the constraints of any one conjunction of a match stage.
+    /// Checked once the types are seeded from the schema, before type inference narrows them down.
+    pub max_type_combinations: usize,
+    /// The most values a list reducer collects for one group. Checked during execution, which fails past it.
+    pub max_reduce_list_size: usize,
+}
+
+impl Default for CompileLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl CompileLimits {
+    pub const DEFAULT: Self = Self {
+        max_pattern_constraints: MAX_PATTERN_CONSTRAINTS,
+        max_disjunction_branches: MAX_DISJUNCTION_BRANCHES,
+        max_type_combinations: MAX_TYPE_COMBINATIONS,
+        max_reduce_list_size: MAX_REDUCE_LIST_SIZE,
+    };
+
+    /// Schema functions are annotated when the schema is committed, rather than for a query, so are not bounded.
+    pub const UNLIMITED: Self = Self {
+        max_pattern_constraints: usize::MAX,
+        max_disjunction_branches: usize::MAX,
+        max_type_combinations: usize::MAX,
+        max_reduce_list_size: usize::MAX,
+    };
+
+    pub(crate) fn check_pattern_size(
+        &self,
+        preamble: &[Function],
+        stages: &[TranslatedStage],
+    ) -> Result<(), AnnotationError> {
+        let mut size = PatternSize::default();
+        preamble
+            .iter()
+            .flat_map(|function| function.function_body.stages().iter())
+            .chain(stages)
+            .for_each(|stage| size.add_stage(stage));
+        if size.constraints > self.max_pattern_constraints {
+            return Err(AnnotationError::PatternSizeLimitExceeded {
+                constraints: size.constraints,
+                limit: self.max_pattern_constraints,
+            });
+        }
+        if size.disjunction_branches > self.max_disjunction_branches {
+            return Err(AnnotationError::DisjunctionBranchLimitExceeded {
+                branches: size.disjunction_branches,
+                limit: self.max_disjunction_branches,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct PatternSize {
+    constraints: usize,
+    disjunction_branches: usize,
+}
+
+impl PatternSize {
+    fn add_stage(&mut self, stage: &TranslatedStage) {
+        match stage {
+            TranslatedStage::Match { block, .. }
+            | TranslatedStage::Insert { block, .. }
+            | TranslatedStage::Update { block, .. }
+            | TranslatedStage::Put { block, .. }
+            | TranslatedStage::Delete { block, .. } => self.add_conjunction(block.conjunction()),
+            TranslatedStage::Select(_)
+            | TranslatedStage::Sort(_)
+            | TranslatedStage::Offset(_)
+            | TranslatedStage::Limit(_)
+            | TranslatedStage::Require(_)
+            | TranslatedStage::Reduce(_)
+            | TranslatedStage::Distinct(_) => {}
+        }
+    }
+
+    fn add_conjunction(&mut self, conjunction: &Conjunction) {
+        self.add_constraints(conjunction);
+        self.disjunction_branches = self.disjunction_branches.saturating_add(disjunction_branches(conjunction));
+    }
+
+    fn add_constraints(&mut self, conjunction: &Conjunction) {
+        self.constraints += conjunction.constraints().len();
+        for nested in conjunction.nested_patterns() {
+            match nested {
+                NestedPattern::Disjunction(disjunction) => {
+                    disjunction.conjunctions().iter().for_each(|branch| self.add_constraints(branch))
+                }
+                NestedPattern::Negation(negation) => self.add_constraints(negation.conjunction()),
+                NestedPattern::Optional(optional) => self.add_constraints(optional.conjunction()),
+            }
+        }
+    }
+}
+
+/// The combinations of branches a conjunction is executed through: the branches of sibling disjunctions multiply, as
+/// does each branch with the disjunctions nested in it. Disjunctions in negations and optionals are counted as if they
+/// were siblings, which overestimates. Conjunctions without disjunctions count as none.
+fn disjunction_branches(conjunction: &Conjunction) -> usize {
+    let mut combinations: Option<usize> = None;
+    for nested in conjunction.nested_patterns() {
+        let nested_combinations = match nested {
+            NestedPattern::Disjunction(disjunction) => disjunction
+                .conjunctions()
+                .iter()
+                .map(|branch| disjunction_branches(branch).max(1))
+                .fold(0, usize::saturating_add),
+            NestedPattern::Negation(negation) => disjunction_branches(negation.conjunction()),
+            NestedPattern::Optional(optional) => disjunction_branches(optional.conjunction()),
+        };
+        if nested_combinations > 0 {
+            combinations = Some(combinations.map_or(nested_combinations, |c| c.saturating_mul(nested_combinations)));
+        }
+    }
+    combinations.unwrap_or(0)
+}
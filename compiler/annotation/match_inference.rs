@@ -30,6 +30,7 @@ use crate::annotation::{
     function::AnnotatedFunctionSignatures,
     type_annotations::{
         BlockAnnotations, ConstraintTypeAnnotations, LeftRightAnnotations, LinksAnnotations, TypeAnnotations,
+        UnsatisfiableConstraint,
     },
     type_seeder::TypeGraphSeedingContext,
     TypeInferenceError,
@@ -112,6 +113,30 @@ pub fn infer_types(
     previous_stage_variable_annotations: &BTreeMap<Variable, Arc<BTreeSet<TypeAnnotation>>>,
     annotated_function_signatures: &dyn AnnotatedFunctionSignatures,
     is_write_stage: bool,
+) -> Result<BlockAnnotations, TypeInferenceError> {
+    infer_types_within_limit(
+        snapshot,
+        block,
+        variable_registry,
+        type_manager,
+        previous_stage_variable_annotations,
+        annotated_function_signatures,
+        is_write_stage,
+        usize::MAX,
+    )
+}
+
+/// Fails as soon as the types are seeded from the schema if any conjunction of the block admits more than
+/// `max_type_combinations` pairs of types across its constraints, before they are pruned.
+pub fn infer_types_within_limit(
+    snapshot: &impl ReadableSnapshot,
+    block: &Block,
+    variable_registry: &VariableRegistry,
+    type_manager: &TypeManager,
+    previous_stage_variable_annotations: &BTreeMap<Variable, Arc<BTreeSet<TypeAnnotation>>>,
+    annotated_function_signatures: &dyn AnnotatedFunctionSignatures,
+    is_write_stage: bool,
+    max_type_combinations: usize,
 ) -> Result<BlockAnnotations, TypeInferenceError> {
     let mut type_annotations_by_scope = HashMap::new();
     let input_annotations = previous_stage_variable_annotations
@@ -127,6 +152,7 @@ pub fn infer_types(
         &input_annotations,
         annotated_function_signatures,
         is_write_stage,
+        max_type_combinations,
         &mut type_annotations_by_scope,
     )?;
     // Copy over any input variables that haven't been included (and refined)
@@ -157,9 +183,10 @@ fn infer_types_impl(
     input_annotations: &BTreeMap<Vertex<Variable>, BTreeSet<TypeAnnotation>>,
     annotated_function_signatures: &dyn AnnotatedFunctionSignatures,
     is_write_stage: bool,
+    max_type_combinations: usize,
     type_annotations_by_scope: &mut HashMap<ScopeId, TypeAnnotations>,
 ) -> Result<(), TypeInferenceError> {
-    let mut graph = compute_type_inference_graph(
+    let mut graph = compute_type_inference_graph_within_limit(
         snapshot,
         block_context,
         conjunction,
@@ -168,6 +195,7 @@ fn infer_types_impl(
         input_annotations,
         annotated_function_signatures,
         is_write_stage,
+        max_type_combinations,
     )?;
 
     infer_types_in_negations_and_conjunctions(
@@ -178,6 +206,7 @@ fn infer_types_impl(
         &mut graph,
         annotated_function_signatures,
         is_write_stage,
+        max_type_combinations,
         type_annotations_by_scope,
     )?;
 
@@ -193,6 +222,7 @@ fn infer_types_in_negations_and_conjunctions(
     parent_conjunction_graph: &mut TypeInferenceGraph<'_>,
     annotated_function_signatures: &dyn AnnotatedFunctionSignatures,
     is_write_stage: bool,
+    max_type_combinations: usize,
     type_annotations_by_scope: &mut HashMap<ScopeId, TypeAnnotations>,
 ) -> Result<(), TypeInferenceError> {
     let TypeInferenceGraph { conjunction, vertices, nested_disjunctions, .. } = parent_conjunction_graph;
@@ -214,6 +244,7 @@ fn infer_types_in_negations_and_conjunctions(
                 nested,
                 annotated_function_signatures,
                 is_write_stage,
+                max_type_combinations,
                 type_annotations_by_scope,
             )
         },
@@ -231,6 +262,7 @@ fn infer_types_in_negations_and_conjunctions(
                     &vertices,
                     annotated_function_signatures,
                     is_write_stage,
+                    max_type_combinations,
                     type_annotations_by_scope,
                 )?;
             }
@@ -244,6 +276,7 @@ fn infer_types_in_negations_and_conjunctions(
                     &vertices,
                     annotated_function_signatures,
                     is_write_stage,
+                    max_type_combinations,
                     type_annotations_by_scope,
                 )?;
                 let optional_root_annotations =
@@ -300,6 +333,30 @@ pub(crate) fn compute_type_inference_graph<'graph>(
     input_annotations: &BTreeMap<Vertex<Variable>, BTreeSet<TypeAnnotation>>,
     annotated_function_signatures: &dyn AnnotatedFunctionSignatures,
     is_write_stage: bool,
+) -> Result<TypeInferenceGraph<'graph>, TypeInferenceError> {
+    compute_type_inference_graph_within_limit(
+        snapshot,
+        block_context,
+        conjunction,
+        variable_registry,
+        type_manager,
+        input_annotations,
+        annotated_function_signatures,
+        is_write_stage,
+        usize::MAX,
+    )
+}
+
+fn compute_type_inference_graph_within_limit<'graph>(
+    snapshot: &impl ReadableSnapshot,
+    block_context: &BlockContext,
+    conjunction: &'graph Conjunction,
+    variable_registry: &VariableRegistry,
+    type_manager: &TypeManager,
+    input_annotations: &BTreeMap<Vertex<Variable>, BTreeSet<TypeAnnotation>>,
+    annotated_function_signatures: &dyn AnnotatedFunctionSignatures,
+    is_write_stage: bool,
+    max_type_combinations: usize,
 ) -> Result<TypeInferenceGraph<'graph>, TypeInferenceError> {
     let mut graph = TypeGraphSeedingContext::new(
         snapshot,
@@ -309,6 +366,10 @@ pub(crate) fn compute_type_inference_graph<'graph>(
         is_write_stage,
     )
     .create_graph(block_context, input_annotations, conjunction)?;
+    let combinations = graph.type_combinations();
+    if combinations > max_type_combinations {
+        return Err(TypeInferenceError::TypeCombinationLimitExceeded { combinations, limit: max_type_combinations });
+    }
     pre_check_edges_for_trivial_unsatisfiability(&graph).map_err(|(graph, edge)| {
         construct_error_message_for_unsatisfiable_edge(snapshot, type_manager, variable_registry, graph, edge)
     })?;
@@ -384,6 +445,17 @@ pub(crate) struct TypeInferenceGraph<'this> {
 }
 
 impl TypeInferenceGraph<'_> {
+    fn type_combinations(&self) -> usize {
+        let edge_combinations =
+            self.edges.iter().map(|edge| edge.left_to_right.values().map(BTreeSet::len).sum::<usize>());
+        let nested_combinations = self
+            .nested_disjunctions
+            .iter()
+            .flat_map(|nested| nested.disjunction.iter())
+            .map(|nested_graph| nested_graph.type_combinations());
+        edge_combinations.chain(nested_combinations).fold(0, usize::saturating_add)
+    }
+
     fn prune_constraints_from_vertices(&mut self) {
         for edge in &mut self.edges {
             edge.prune_self_from_vertices(&self.vertices)
@@ -406,6 +478,16 @@ impl TypeInferenceGraph<'_> {
 
     pub(crate) fn collect_type_annotations(self, type_annotations_by_scope: &mut HashMap<ScopeId, TypeAnnotations>) {
         let TypeInferenceGraph { vertices, edges, nested_disjunctions, conjunction } = self;
+        let unsatisfiable_constraint = edges.iter().find_map(|edge| {
+            let (left_types, right_types) = edge.conflicting_types.as_ref()?;
+            Some(UnsatisfiableConstraint::new(
+                edge.constraint.clone(),
+                edge.left.clone(),
+                edge.right.clone(),
+                left_types.clone(),
+                right_types.clone(),
+            ))
+        });
         let mut constraint_annotations = HashMap::new();
         let mut combine_links_edges = HashMap::new();
         edges.into_iter().for_each(|edge| {
@@ -434,7 +516,8 @@ impl TypeInferenceGraph<'_> {
             .map(|(variable, types)| (variable.into(), Arc::new(types)))
             .collect::<BTreeMap<_, _>>();
 
-        let type_annotations = TypeAnnotations::new(vertex_annotations, constraint_annotations);
+        let mut type_annotations = TypeAnnotations::new(vertex_annotations, constraint_annotations);
+        type_annotations.set_unsatisfiable_constraint(unsatisfiable_constraint);
         type_annotations_by_scope.insert(conjunction.scope_id(), type_annotations);
 
         nested_disjunctions
@@ -473,6 +556,8 @@ pub struct TypeInferenceEdge<'this> {
     pub(crate) right: Vertex<Variable>,
     pub(crate) left_to_right: BTreeMap<TypeAnnotation, BTreeSet<TypeAnnotation>>,
     pub(crate) right_to_left: BTreeMap<TypeAnnotation, BTreeSet<TypeAnnotation>>,
+    // The types of either side when the edge was first found to admit no pair of types, if it ever was
+    pub(crate) conflicting_types: Option<(BTreeSet<TypeAnnotation>, BTreeSet<TypeAnnotation>)>,
 }
 
 impl<'this> TypeInferenceEdge<'this> {
@@ -501,6 +586,7 @@ impl<'this> TypeInferenceEdge<'this> {
             right,
             left_to_right: initial_left_to_right,
             right_to_left: initial_right_to_left,
+            conflicting_types: None,
         }
     }
 
@@ -519,7 +605,10 @@ impl<'this> TypeInferenceEdge<'this> {
         }
     }
 
-    fn prune_vertices_from_self(&self, vertices: &mut VertexAnnotations) -> bool {
+    fn prune_vertices_from_self(&mut self, vertices: &mut VertexAnnotations) -> bool {
+        if self.left_to_right.is_empty() && self.conflicting_types.is_none() {
+            self.record_conflicting_types(vertices);
+        }
         let mut is_modified = false;
         {
             let left_vertex_annotations = vertices.get_mut(&self.left).unwrap();
@@ -536,6 +625,15 @@ impl<'this> TypeInferenceEdge<'this> {
         is_modified
     }
 
+    fn record_conflicting_types(&mut self, vertices: &VertexAnnotations) {
+        // Only the edge that empties non-empty vertices is a cause; the others just propagate emptiness.
+        let left_types = vertices.get(&self.left).unwrap();
+        let right_types = vertices.get(&self.right).unwrap();
+        if !left_types.is_empty() && !right_types.is_empty() {
+            self.conflicting_types = Some((left_types.clone(), right_types.clone()));
+        }
+    }
+
     fn prune_self_from_vertices(&mut self, vertices: &VertexAnnotations) {
         let TypeInferenceEdge { left_to_right, right_to_left, .. } = self;
         {
@@ -10,13 +10,16 @@ use error::typedb_error;
 use expression::ExpressionCompileError;
 use typeql::common::Span;
 
+pub(crate) mod comparison;
 pub mod expression;
 pub mod fetch;
 pub mod function;
+pub mod limits;
 pub mod match_inference;
 pub mod pipeline;
 pub mod type_annotations;
 pub mod type_inference;
+pub mod type_inference_cache;
 mod type_seeder;
 pub(crate) mod write_type_check;
 
@@ -111,6 +114,18 @@ typedb_error!(
             variable: String,
             source_span: Option<Span>,
         ),
+        PatternSizeLimitExceeded(
+            18,
+            "The query has {constraints} constraints, which exceeds the limit of {limit}.",
+            constraints: usize,
+            limit: usize,
+        ),
+        DisjunctionBranchLimitExceeded(
+            19,
+            "The query has {branches} combinations of disjunction branches, which exceeds the limit of {limit}.",
+            branches: usize,
+            limit: usize,
+        ),
     }
 );
 
@@ -212,6 +227,12 @@ typedb_error!(
             constraint_type: String,
             source_span: Option<Span>,
         ),
+        TypeCombinationLimitExceeded(
+            12,
+            "The schema admits {combinations} type combinations for the constraints of a pattern, which exceeds the limit of {limit}. Consider constraining the types of the variables involved.",
+            combinations: usize,
+            limit: usize,
+        ),
         OptionalTypesUnsupported(255, "Optional types are not yet supported."),
         ListTypesUnsupported(256, "List types are not yet supported."),
     }
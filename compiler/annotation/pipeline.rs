@@ -37,6 +37,7 @@ use typeql::common::Span;
 
 use crate::{
     annotation::{
+        comparison::annotate_comparisons,
         expression::{
             block_compiler::compile_expressions,
             compiled_expression::{ExecutableExpression, ExpressionValueType},
@@ -47,10 +48,12 @@ use crate::{
             annotate_preamble_functions, AnnotatedFunctionSignatures, AnnotatedFunctionSignaturesImpl,
             AnnotatedPreambleFunctions, AnnotatedSchemaFunctions, FunctionParameterAnnotation,
         },
-        match_inference::infer_types,
+        limits::CompileLimits,
+        match_inference::{infer_types, infer_types_within_limit},
         type_annotations::{BlockAnnotations, ConstraintTypeAnnotations, TypeAnnotations},
         type_inference::resolve_value_types,
-        write_type_check::check_type_combinations_for_write,
+        type_inference_cache::TypeInferenceCache,
+        write_type_check::{check_type_combinations_for_delete, check_type_combinations_for_write},
         AnnotationError,
     },
     executable::{reduce::ReduceInstruction, update},
@@ -135,10 +138,18 @@ pub fn annotate_preamble_and_pipeline(
     translated_preamble: Vec<Function>,
     translated_stages: Vec<TranslatedStage>,
     translated_fetch: Option<FetchObject>,
+    compile_limits: &CompileLimits,
+    type_inference_cache: Option<&TypeInferenceCache>,
 ) -> Result<AnnotatedPipeline, AnnotationError> {
-    let annotated_preamble =
-        annotate_preamble_functions(translated_preamble, snapshot, type_manager, schema_function_annotations.clone())
-            .map_err(|typedb_source| AnnotationError::PreambleTypeInference { typedb_source })?;
+    compile_limits.check_pattern_size(&translated_preamble, &translated_stages)?;
+    let annotated_preamble = annotate_preamble_functions(
+        translated_preamble,
+        snapshot,
+        type_manager,
+        schema_function_annotations.clone(),
+        compile_limits,
+    )
+    .map_err(|typedb_source| AnnotationError::PreambleTypeInference { typedb_source })?;
     let combined_signature_annotations =
         AnnotatedFunctionSignaturesImpl::new(&schema_function_annotations, &annotated_preamble);
     let (annotated_stages, annotated_fetch) = annotate_stages_and_fetch(
@@ -151,6 +162,8 @@ pub fn annotate_preamble_and_pipeline(
         translated_fetch,
         BTreeMap::new(),
         BTreeMap::new(),
+        compile_limits,
+        type_inference_cache,
     )?;
     Ok(AnnotatedPipeline { annotated_stages, annotated_fetch, annotated_preamble })
 }
@@ -165,6 +178,8 @@ pub(crate) fn annotate_stages_and_fetch(
     translated_fetch: Option<FetchObject>,
     input_type_annotations: BTreeMap<Variable, Arc<BTreeSet<Type>>>,
     input_value_type_annotations: BTreeMap<Variable, ExpressionValueType>,
+    compile_limits: &CompileLimits,
+    type_inference_cache: Option<&TypeInferenceCache>,
 ) -> Result<(Vec<AnnotatedStage>, Option<AnnotatedFetch>), AnnotationError> {
     let (annotated_stages, running_variable_annotations, running_value_variable_types) = annotate_pipeline_stages(
         snapshot,
@@ -176,6 +191,8 @@ pub(crate) fn annotate_stages_and_fetch(
         input_type_annotations,
         input_value_type_annotations,
         None,
+        compile_limits,
+        type_inference_cache,
     )?;
     let annotated_fetch = match translated_fetch {
         None => None,
@@ -189,6 +206,7 @@ pub(crate) fn annotate_stages_and_fetch(
                 annotated_function_signatures,
                 &running_variable_annotations,
                 &running_value_variable_types,
+                compile_limits,
             );
             Some(annotated?)
         }
@@ -206,6 +224,8 @@ pub(crate) fn annotate_pipeline_stages(
     input_type_annotations: BTreeMap<Variable, Arc<BTreeSet<Type>>>,
     input_value_type_annotations: BTreeMap<Variable, ExpressionValueType>,
     return_variables: Option<&[Variable]>, // Remove if anonymous vars can't cross stage boundaries
+    compile_limits: &CompileLimits,
+    type_inference_cache: Option<&TypeInferenceCache>,
 ) -> Result<
     (Vec<AnnotatedStage>, BTreeMap<Variable, Arc<BTreeSet<Type>>>, BTreeMap<Variable, ExpressionValueType>),
     AnnotationError,
@@ -235,6 +255,8 @@ pub(crate) fn annotate_pipeline_stages(
             annotated_function_signatures,
             running_constraint_annotations,
             stage,
+            compile_limits,
+            type_inference_cache,
         )?;
 
         let retain_running_var_fn =
@@ -259,18 +281,34 @@ fn annotate_stage(
     annotated_function_signatures: &dyn AnnotatedFunctionSignatures,
     running_constraint_annotations: &HashMap<Constraint<Variable>, ConstraintTypeAnnotations>,
     stage: TranslatedStage,
+    compile_limits: &CompileLimits,
+    type_inference_cache: Option<&TypeInferenceCache>,
 ) -> Result<AnnotatedStage, AnnotationError> {
     match stage {
         TranslatedStage::Match { block, source_span } => {
-            let mut block_annotations = infer_types(
-                snapshot,
-                &block,
-                variable_registry,
-                type_manager,
-                running_variable_annotations,
-                annotated_function_signatures,
-                false,
-            )
+            let infer = || {
+                infer_types_within_limit(
+                    snapshot,
+                    &block,
+                    variable_registry,
+                    type_manager,
+                    running_variable_annotations,
+                    annotated_function_signatures,
+                    false,
+                    compile_limits.max_type_combinations,
+                )
+            };
+            let mut block_annotations = match type_inference_cache {
+                Some(cache) => cache.get_or_infer(
+                    type_manager,
+                    &block,
+                    variable_registry,
+                    running_variable_annotations,
+                    false,
+                    infer,
+                ),
+                None => infer(),
+            }
             .map_err(|typedb_source| AnnotationError::TypeInference { typedb_source })?;
             let root_annotations = block_annotations.type_annotations_of(block.conjunction()).unwrap();
             root_annotations.vertex_annotations().iter().for_each(|(vertex, types)| {
@@ -307,6 +345,7 @@ fn annotate_stage(
                 variable_registry,
                 running_value_variable_assigned_types,
             )?;
+            annotate_comparisons(snapshot, type_manager, parameters, block.conjunction(), &mut block_annotations)?;
             Ok(AnnotatedStage::Match {
                 block,
                 block_annotations,
@@ -422,7 +461,7 @@ fn annotate_stage(
                 annotated_function_signatures,
                 &block,
             )?;
-            check_type_combinations_for_write(
+            check_type_combinations_for_delete(
                 snapshot,
                 type_manager,
                 variable_registry,
@@ -470,8 +509,7 @@ fn annotate_stage(
                     running_value_variable_assigned_types,
                     reduce.source_span(),
                 )?;
-                running_value_variable_assigned_types
-                    .insert(assigned, ExpressionValueType::Single(typed_reduce.output_type().clone()));
+                running_value_variable_assigned_types.insert(assigned, typed_reduce.output_expression_value_type());
                 reduce_instructions.push(typed_reduce);
             }
             Ok(AnnotatedStage::Reduce(reduce, reduce_instructions))
@@ -660,7 +698,8 @@ pub fn resolve_reducer_by_value_type(
         | Reducer::Mean(variable)
         | Reducer::Median(variable)
         | Reducer::Min(variable)
-        | Reducer::Std(variable) => {
+        | Reducer::Std(variable)
+        | Reducer::List(variable) => {
             let value_type = determine_value_type_for_reducer(
                 reducer,
                 variable,
@@ -750,6 +789,7 @@ pub fn resolve_reduce_instruction_by_value_type(
             Reducer::Mean(var) => Ok(ReduceInstruction::MeanInteger(var)),
             Reducer::Median(var) => Ok(ReduceInstruction::MedianInteger(var)),
             Reducer::Std(var) => Ok(ReduceInstruction::StdInteger(var)),
+            Reducer::List(var) => Ok(ReduceInstruction::List(var, value_type.clone())),
         },
 
         ValueTypeCategory::Double => match reducer {
@@ -761,6 +801,7 @@ pub fn resolve_reduce_instruction_by_value_type(
             Reducer::Mean(var) => Ok(ReduceInstruction::MeanDouble(var)),
             Reducer::Median(var) => Ok(ReduceInstruction::MedianDouble(var)),
             Reducer::Std(var) => Ok(ReduceInstruction::StdDouble(var)),
+            Reducer::List(var) => Ok(ReduceInstruction::List(var, value_type.clone())),
         },
 
         ValueTypeCategory::Decimal => match reducer {
@@ -772,6 +813,7 @@ pub fn resolve_reduce_instruction_by_value_type(
             Reducer::Mean(var) => Ok(ReduceInstruction::MeanDecimal(var)),
             Reducer::Median(var) => Ok(ReduceInstruction::MedianDecimal(var)),
             Reducer::Std(var) => Ok(ReduceInstruction::StdDecimal(var)),
+            Reducer::List(var) => Ok(ReduceInstruction::List(var, value_type.clone())),
         },
 
         ValueTypeCategory::String => match reducer {
@@ -779,6 +821,7 @@ pub fn resolve_reduce_instruction_by_value_type(
             Reducer::CountVar(var) => Ok(ReduceInstruction::CountVar(var)),
             Reducer::Max(var) => Ok(ReduceInstruction::MaxString(var)),
             Reducer::Min(var) => Ok(ReduceInstruction::MinString(var)),
+            Reducer::List(var) => Ok(ReduceInstruction::List(var, value_type.clone())),
             _ => err(),
         },
 
@@ -787,6 +830,7 @@ pub fn resolve_reduce_instruction_by_value_type(
             Reducer::CountVar(var) => Ok(ReduceInstruction::CountVar(var)),
             Reducer::Max(var) => Ok(ReduceInstruction::MaxDate(var)),
             Reducer::Min(var) => Ok(ReduceInstruction::MinDate(var)),
+            Reducer::List(var) => Ok(ReduceInstruction::List(var, value_type.clone())),
             _ => err(),
         },
 
@@ -795,6 +839,7 @@ pub fn resolve_reduce_instruction_by_value_type(
             Reducer::CountVar(var) => Ok(ReduceInstruction::CountVar(var)),
             Reducer::Max(var) => Ok(ReduceInstruction::MaxDateTime(var)),
             Reducer::Min(var) => Ok(ReduceInstruction::MinDateTime(var)),
+            Reducer::List(var) => Ok(ReduceInstruction::List(var, value_type.clone())),
             _ => err(),
         },
 
@@ -803,10 +848,14 @@ pub fn resolve_reduce_instruction_by_value_type(
             Reducer::CountVar(var) => Ok(ReduceInstruction::CountVar(var)),
             Reducer::Max(var) => Ok(ReduceInstruction::MaxDateTimeTZ(var)),
             Reducer::Min(var) => Ok(ReduceInstruction::MinDateTimeTZ(var)),
+            Reducer::List(var) => Ok(ReduceInstruction::List(var, value_type.clone())),
             _ => err(),
         },
 
-        ValueTypeCategory::Boolean | ValueTypeCategory::Duration | ValueTypeCategory::Struct => err(),
+        ValueTypeCategory::Boolean | ValueTypeCategory::Duration | ValueTypeCategory::Struct => match reducer {
+            Reducer::List(var) => Ok(ReduceInstruction::List(var, value_type.clone())),
+            _ => err(),
+        },
     }
 }
 
@@ -840,8 +889,12 @@ fn collect_value_types_of_function_call_assignments(
                             }),
                         });
                     }
-                    value_type_annotations
-                        .insert(var.as_variable().unwrap(), ExpressionValueType::Single(value_type.clone()));
+                    let variable = var.as_variable().unwrap();
+                    let expression_value_type = match variable_registry.get_variable_category(variable) {
+                        Some(VariableCategory::ValueList) => ExpressionValueType::List(value_type.clone()),
+                        _ => ExpressionValueType::Single(value_type.clone()),
+                    };
+                    value_type_annotations.insert(variable, expression_value_type);
                     Ok(())
                 }
                 FunctionParameterAnnotation::AnyConcept | FunctionParameterAnnotation::Concept(_) => Ok(()),
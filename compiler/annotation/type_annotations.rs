@@ -5,12 +5,18 @@
  */
 
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     sync::Arc,
 };
 
 use answer::{variable::Variable, Type};
-use ir::pattern::{conjunction::Conjunction, constraint::Constraint, Scope, ScopeId, Vertex};
+use encoding::value::value_type::ValueTypeCategory;
+use ir::pattern::{
+    conjunction::Conjunction,
+    constraint::{Comparator, Comparison, Constraint},
+    Scope, ScopeId, Vertex,
+};
+use itertools::Itertools;
 
 use crate::annotation::expression::compiled_expression::ExpressionValueType;
 
@@ -53,6 +59,14 @@ impl BlockAnnotations {
     pub(crate) fn referenced_types(&self) -> BTreeSet<Type> {
         self.scope_annotations.values().flat_map(|ta| ta.vertex.values().map(|v| &**v)).flatten().copied().collect()
     }
+
+    /// The annotations of a block which only differs from the annotated one in the names of its variables and scopes.
+    /// Variables outside the mapping pass through the block from earlier stages, so their annotations are left out.
+    pub(crate) fn map(&self, variables: &HashMap<Variable, Variable>, scopes: &HashMap<ScopeId, ScopeId>) -> Self {
+        let scope_annotations =
+            self.scope_annotations.iter().map(|(scope, annotations)| (scopes[scope], annotations.map(variables)));
+        Self { scope_annotations: scope_annotations.collect() }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -60,6 +74,8 @@ pub struct TypeAnnotations {
     vertex: BTreeMap<Vertex<Variable>, Arc<BTreeSet<Type>>>,
     constraints: HashMap<Constraint<Variable>, ConstraintTypeAnnotations>,
     value_type_annotations: Option<BTreeMap<Vertex<Variable>, ExpressionValueType>>,
+    unsatisfiable_constraint: Option<UnsatisfiableConstraint>,
+    comparison_value_types: HashMap<Comparison<Variable>, ComparisonValueTypes>,
 }
 
 impl TypeAnnotations {
@@ -67,7 +83,13 @@ impl TypeAnnotations {
         variables: BTreeMap<Vertex<Variable>, Arc<BTreeSet<Type>>>,
         constraints: HashMap<Constraint<Variable>, ConstraintTypeAnnotations>,
     ) -> Self {
-        TypeAnnotations { vertex: variables, constraints, value_type_annotations: None }
+        TypeAnnotations {
+            vertex: variables,
+            constraints,
+            value_type_annotations: None,
+            unsatisfiable_constraint: None,
+            comparison_value_types: HashMap::new(),
+        }
     }
 
     pub fn vertex_annotations(&self) -> &BTreeMap<Vertex<Variable>, Arc<BTreeSet<Type>>> {
@@ -103,6 +125,172 @@ impl TypeAnnotations {
     pub fn constraint_annotations_of(&self, constraint: Constraint<Variable>) -> Option<&ConstraintTypeAnnotations> {
         self.constraints.get(&constraint)
     }
+
+    pub fn unsatisfiable_constraint(&self) -> Option<&UnsatisfiableConstraint> {
+        self.unsatisfiable_constraint.as_ref()
+    }
+
+    pub(super) fn set_unsatisfiable_constraint(&mut self, unsatisfiable_constraint: Option<UnsatisfiableConstraint>) {
+        self.unsatisfiable_constraint = unsatisfiable_constraint;
+    }
+
+    pub fn comparison_value_types(&self) -> &HashMap<Comparison<Variable>, ComparisonValueTypes> {
+        &self.comparison_value_types
+    }
+
+    pub fn comparison_value_types_of(&self, comparison: &Comparison<Variable>) -> Option<&ComparisonValueTypes> {
+        self.comparison_value_types.get(comparison)
+    }
+
+    pub(super) fn set_comparison_value_types(
+        &mut self,
+        comparison_value_types: HashMap<Comparison<Variable>, ComparisonValueTypes>,
+    ) {
+        self.comparison_value_types = comparison_value_types;
+    }
+
+    fn map(&self, variables: &HashMap<Variable, Variable>) -> Self {
+        let map_vertex = |vertex: &Vertex<Variable>| match vertex {
+            Vertex::Variable(variable) => variables.get(variable).map(|&variable| Vertex::Variable(variable)),
+            Vertex::Label(_) | Vertex::Parameter(_) => Some(vertex.clone()),
+        };
+        TypeAnnotations {
+            vertex: self
+                .vertex
+                .iter()
+                .filter_map(|(vertex, types)| Some((map_vertex(vertex)?, types.clone())))
+                .collect(),
+            constraints: self
+                .constraints
+                .iter()
+                .map(|(constraint, annotations)| (constraint.clone().map(variables), annotations.clone()))
+                .collect(),
+            value_type_annotations: self.value_type_annotations.as_ref().map(|annotations| {
+                annotations
+                    .iter()
+                    .filter_map(|(vertex, value_type)| Some((map_vertex(vertex)?, value_type.clone())))
+                    .collect()
+            }),
+            unsatisfiable_constraint: self.unsatisfiable_constraint.as_ref().map(|unsatisfiable| {
+                UnsatisfiableConstraint {
+                    constraint: unsatisfiable.constraint.clone().map(variables),
+                    left: unsatisfiable.left.clone().map(variables),
+                    right: unsatisfiable.right.clone().map(variables),
+                    left_types: unsatisfiable.left_types.clone(),
+                    right_types: unsatisfiable.right_types.clone(),
+                }
+            }),
+            comparison_value_types: self
+                .comparison_value_types
+                .iter()
+                .map(|(comparison, value_types)| (comparison.clone().map(variables), value_types.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// The value types a comparison can be evaluated in: for each pair of comparable operand value types,
+/// the value type both operands are implicitly cast to before being compared.
+/// The check instruction of the comparison casts its operands accordingly, and pairs without a cast never match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComparisonValueTypes {
+    operand_value_types: (HashSet<ValueTypeCategory>, HashSet<ValueTypeCategory>),
+    casts: HashMap<(ValueTypeCategory, ValueTypeCategory), ValueTypeCategory>,
+}
+
+impl ComparisonValueTypes {
+    pub(crate) fn new(
+        comparator: Comparator,
+        lhs_value_types: &HashSet<ValueTypeCategory>,
+        rhs_value_types: &HashSet<ValueTypeCategory>,
+    ) -> Self {
+        let casts = lhs_value_types
+            .iter()
+            .cartesian_product(rhs_value_types.iter())
+            .filter_map(|(&lhs, &rhs)| {
+                let category = ValueTypeCategory::comparison_category(lhs, rhs)?;
+                match comparator {
+                    Comparator::Like | Comparator::Contains => {
+                        (category == ValueTypeCategory::String).then_some(((lhs, rhs), category))
+                    }
+                    _ => Some(((lhs, rhs), category)),
+                }
+            })
+            .collect();
+        Self { operand_value_types: (lhs_value_types.clone(), rhs_value_types.clone()), casts }
+    }
+
+    pub fn is_comparable(&self) -> bool {
+        !self.casts.is_empty()
+    }
+
+    /// All value types the operands may take, including those which cannot be compared.
+    pub fn operand_value_types(&self) -> (&HashSet<ValueTypeCategory>, &HashSet<ValueTypeCategory>) {
+        (&self.operand_value_types.0, &self.operand_value_types.1)
+    }
+
+    pub fn lhs_value_types(&self) -> HashSet<ValueTypeCategory> {
+        self.casts.keys().map(|&(lhs, _)| lhs).collect()
+    }
+
+    pub fn rhs_value_types(&self) -> HashSet<ValueTypeCategory> {
+        self.casts.keys().map(|&(_, rhs)| rhs).collect()
+    }
+
+    pub fn cast_of(&self, lhs: ValueTypeCategory, rhs: ValueTypeCategory) -> Option<ValueTypeCategory> {
+        self.casts.get(&(lhs, rhs)).copied()
+    }
+
+    pub fn has_implicit_casts(&self) -> bool {
+        self.casts.keys().any(|(lhs, rhs)| lhs != rhs)
+    }
+
+    pub fn has_lossy_implicit_casts(&self) -> bool {
+        self.casts.iter().any(|(&(lhs, rhs), &category)| lhs != rhs && category == ValueTypeCategory::Double)
+    }
+}
+
+/// The constraint which type inference found no types for, making its conjunction unsatisfiable,
+/// along with the types each side could take when the conflict was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsatisfiableConstraint {
+    constraint: Constraint<Variable>,
+    left: Vertex<Variable>,
+    right: Vertex<Variable>,
+    left_types: BTreeSet<Type>,
+    right_types: BTreeSet<Type>,
+}
+
+impl UnsatisfiableConstraint {
+    pub(super) fn new(
+        constraint: Constraint<Variable>,
+        left: Vertex<Variable>,
+        right: Vertex<Variable>,
+        left_types: BTreeSet<Type>,
+        right_types: BTreeSet<Type>,
+    ) -> Self {
+        Self { constraint, left, right, left_types, right_types }
+    }
+
+    pub fn constraint(&self) -> &Constraint<Variable> {
+        &self.constraint
+    }
+
+    pub fn left(&self) -> &Vertex<Variable> {
+        &self.left
+    }
+
+    pub fn right(&self) -> &Vertex<Variable> {
+        &self.right
+    }
+
+    pub fn left_types(&self) -> &BTreeSet<Type> {
+        &self.left_types
+    }
+
+    pub fn right_types(&self) -> &BTreeSet<Type> {
+        &self.right_types
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
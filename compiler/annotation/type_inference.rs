@@ -80,6 +80,7 @@ pub mod tests {
             annotate_named_function, AnnotatedFunctionSignature, AnnotatedFunctionSignaturesImpl,
             EmptyAnnotatedFunctionSignatures,
         },
+        limits::CompileLimits,
         match_inference::{
             compute_type_inference_graph, infer_types, prune_types, NestedTypeInferenceGraphDisjunction,
             TypeInferenceEdge, TypeInferenceGraph, VertexAnnotations,
@@ -199,9 +200,14 @@ pub mod tests {
             // with fun fn_test() -> animal: match $called_animal isa cat, has $called_name; return { $called_animal };
             let (entry, entry_context, mut f_ir) = with_local_cache;
 
-            let f_annotations =
-                annotate_named_function(&mut f_ir, &snapshot, &type_manager, &EmptyAnnotatedFunctionSignatures)
-                    .unwrap();
+            let f_annotations = annotate_named_function(
+                &mut f_ir,
+                &snapshot,
+                &type_manager,
+                &EmptyAnnotatedFunctionSignatures,
+                &CompileLimits::default(),
+            )
+            .unwrap();
             let f_var_animal =
                 var_from_registry(&f_ir.translation_context().variable_registry, "called_animal").unwrap();
             let f_var_animal_type =
@@ -316,7 +322,7 @@ pub mod tests {
             right_to_left.entry(r).or_insert_with(BTreeSet::new);
             right_to_left.get_mut(&r).unwrap().insert(l);
         }
-        TypeInferenceEdge { constraint, left, right, left_to_right, right_to_left }
+        TypeInferenceEdge { constraint, left, right, left_to_right, right_to_left, conflicting_types: None }
     }
 
     #[test]
@@ -1460,6 +1466,33 @@ pub mod tests {
             assert_eq!(expected_graph, graph)
         }
     }
+
+    #[test]
+    fn comparison_value_types_widen_mixed_operands() {
+        use std::collections::HashSet;
+
+        use encoding::value::value_type::ValueTypeCategory::{Decimal, Double, Integer, String};
+        use ir::pattern::constraint::Comparator;
+
+        use crate::annotation::type_annotations::ComparisonValueTypes;
+
+        let attribute_subtypes = HashSet::from([Integer, Double, String]);
+        let value_types =
+            ComparisonValueTypes::new(Comparator::Greater, &attribute_subtypes, &HashSet::from([Decimal]));
+        assert!(value_types.is_comparable() && value_types.has_implicit_casts());
+        assert_eq!(value_types.lhs_value_types(), HashSet::from([Integer, Double]));
+        assert_eq!(value_types.cast_of(Integer, Decimal), Some(Decimal));
+        assert_eq!(value_types.cast_of(Double, Decimal), Some(Double));
+        assert_eq!(value_types.cast_of(String, Decimal), None);
+
+        let value_types = ComparisonValueTypes::new(Comparator::Like, &attribute_subtypes, &HashSet::from([String]));
+        assert_eq!(value_types.lhs_value_types(), HashSet::from([String]));
+        assert!(!value_types.has_implicit_casts());
+
+        let value_types =
+            ComparisonValueTypes::new(Comparator::Like, &HashSet::from([Integer]), &HashSet::from([Integer]));
+        assert!(!value_types.is_comparable());
+    }
 }
 
 pub fn get_type_annotation_from_label<Snapshot: ReadableSnapshot>(
@@ -0,0 +1,508 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use answer::{variable::Variable, Type};
+use concept::type_::type_manager::TypeManager;
+use ir::{
+    pattern::{
+        conjunction::Conjunction, constraint::Constraint, nested_pattern::NestedPattern,
+        variable_category::VariableCategory, Scope, ScopeId, Vertex,
+    },
+    pipeline::{block::Block, fingerprint::CanonicalForm, VariableRegistry},
+};
+use moka::sync::Cache;
+use resource::constants::compiler::TYPE_INFERENCE_CACHE_SIZE;
+use storage::sequence_number::SequenceNumber;
+
+use crate::annotation::{type_annotations::BlockAnnotations, TypeInferenceError};
+
+/// Shares the annotations inferred for a block between queries with the same block against the same schema,
+/// so that repeated query shapes skip seeding and pruning the type inference graph.
+///
+/// Blocks are compared in their canonical form, so a block matches regardless of how its variables are named or
+/// numbered, or the order its constraints and nested patterns are written in. Annotations are cached in the
+/// canonical form and renamed into each block they are handed out to.
+///
+/// Blocks are only cached when the schema is read from the type cache, which identifies the schema version.
+/// Blocks calling functions are never cached, since their annotations depend on the query's preamble.
+#[derive(Debug)]
+pub struct TypeInferenceCache {
+    cache: Cache<InferenceKey, CachedAnnotations>,
+}
+
+impl TypeInferenceCache {
+    pub fn new() -> Self {
+        Self { cache: Cache::new(TYPE_INFERENCE_CACHE_SIZE) }
+    }
+
+    pub(crate) fn get_or_infer(
+        &self,
+        type_manager: &TypeManager,
+        block: &Block,
+        variable_registry: &VariableRegistry,
+        input_annotations: &BTreeMap<Variable, Arc<BTreeSet<Type>>>,
+        is_write_stage: bool,
+        infer: impl FnOnce() -> Result<BlockAnnotations, TypeInferenceError>,
+    ) -> Result<BlockAnnotations, TypeInferenceError> {
+        let Some(schema_version) = type_manager.schema_version() else {
+            return infer();
+        };
+        if calls_functions(block.conjunction()) {
+            return infer();
+        }
+        let canonical_form = CanonicalForm::of_conjunction(block.conjunction());
+        let canonical_variables = canonical_form.variables();
+        let mut conjunctions = Vec::new();
+        let mut scopes = Vec::new();
+        canonical_conjunctions(
+            &canonical_form,
+            block.conjunction(),
+            &canonical_variables,
+            &mut conjunctions,
+            &mut scopes,
+        );
+        let key = InferenceKey {
+            schema_version,
+            conjunctions,
+            variable_categories: block
+                .variables()
+                .filter_map(|variable| {
+                    Some((*canonical_variables.get(&variable)?, variable_registry.get_variable_category(variable)))
+                })
+                .collect(),
+            // annotations of variables the block does not refer to pass through it unchanged
+            input_annotations: input_annotations
+                .iter()
+                .filter_map(|(variable, types)| Some((*canonical_variables.get(variable)?, types.clone())))
+                .collect(),
+            is_write_stage,
+        };
+
+        if let Some(cached) = self.cache.get(&key) {
+            let variables = canonical_variables.iter().map(|(&variable, &canonical)| (canonical, variable)).collect();
+            let scopes = cached.scopes.iter().copied().zip(scopes).collect();
+            let mut annotations = cached.annotations.map(&variables, &scopes);
+            let root_annotations = annotations.type_annotations_mut_of(block.conjunction()).unwrap();
+            for (&variable, types) in input_annotations {
+                root_annotations.vertex_annotations_mut().entry(Vertex::Variable(variable)).or_insert(types.clone());
+            }
+            return Ok(annotations);
+        }
+        let annotations = infer()?;
+        let own_scopes = scopes.iter().map(|&scope| (scope, scope)).collect();
+        let cached = CachedAnnotations { annotations: annotations.map(&canonical_variables, &own_scopes), scopes };
+        self.cache.insert(key, cached);
+        Ok(annotations)
+    }
+
+    pub fn invalidate_all(&self) {
+        self.cache.invalidate_all();
+    }
+}
+
+impl Default for TypeInferenceCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn calls_functions(conjunction: &Conjunction) -> bool {
+    conjunction.constraints().iter().any(|constraint| matches!(constraint, Constraint::FunctionCallBinding(_)))
+        || conjunction.nested_patterns().iter().any(|nested| match nested {
+            NestedPattern::Disjunction(disjunction) => disjunction.conjunctions().iter().any(calls_functions),
+            NestedPattern::Negation(negation) => calls_functions(negation.conjunction()),
+            NestedPattern::Optional(optional) => calls_functions(optional.conjunction()),
+        })
+}
+
+/// Collects the conjunctions of a block in canonical order, with their constraints renamed to canonical variables,
+/// along with the scope of each conjunction in the same order.
+fn canonical_conjunctions(
+    canonical_form: &CanonicalForm,
+    conjunction: &Conjunction,
+    canonical_variables: &HashMap<Variable, Variable>,
+    conjunctions: &mut Vec<CanonicalConjunction>,
+    scopes: &mut Vec<ScopeId>,
+) {
+    let nested_patterns = canonical_form.nested_patterns(conjunction);
+    conjunctions.push(CanonicalConjunction {
+        constraints: canonical_form
+            .constraints(conjunction)
+            .into_iter()
+            .map(|constraint| constraint.clone().map(canonical_variables))
+            .collect(),
+        nested_patterns: nested_patterns
+            .iter()
+            .map(|nested| match nested {
+                NestedPattern::Disjunction(disjunction) => {
+                    NestedPatternKind::Disjunction(disjunction.conjunctions().len())
+                }
+                NestedPattern::Negation(_) => NestedPatternKind::Negation,
+                NestedPattern::Optional(_) => NestedPatternKind::Optional,
+            })
+            .collect(),
+    });
+    scopes.push(conjunction.scope_id());
+    for nested in nested_patterns {
+        match nested {
+            NestedPattern::Disjunction(disjunction) => {
+                for branch in canonical_form.branches(disjunction) {
+                    canonical_conjunctions(canonical_form, branch, canonical_variables, conjunctions, scopes);
+                }
+            }
+            NestedPattern::Negation(negation) => canonical_conjunctions(
+                canonical_form,
+                negation.conjunction(),
+                canonical_variables,
+                conjunctions,
+                scopes,
+            ),
+            NestedPattern::Optional(optional) => canonical_conjunctions(
+                canonical_form,
+                optional.conjunction(),
+                canonical_variables,
+                conjunctions,
+                scopes,
+            ),
+        }
+    }
+}
+
+/// The annotations of a block, in its canonical variables, along with the scopes of its conjunctions in canonical
+/// order.
+#[derive(Debug, Clone)]
+struct CachedAnnotations {
+    annotations: BlockAnnotations,
+    scopes: Vec<ScopeId>,
+}
+
+/// A block in canonical form. The categories of the block's variables are part of the key: the same block may follow
+/// stages which bind its input variables differently.
+#[derive(Debug)]
+struct InferenceKey {
+    schema_version: SequenceNumber,
+    conjunctions: Vec<CanonicalConjunction>,
+    variable_categories: BTreeMap<Variable, Option<VariableCategory>>,
+    input_annotations: BTreeMap<Variable, Arc<BTreeSet<Type>>>,
+    is_write_stage: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct CanonicalConjunction {
+    constraints: Vec<Constraint<Variable>>,
+    nested_patterns: Vec<NestedPatternKind>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum NestedPatternKind {
+    Disjunction(usize),
+    Negation,
+    Optional,
+}
+
+impl Hash for InferenceKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&self.schema_version, state);
+        Hash::hash(&self.conjunctions, state);
+        self.variable_categories.keys().for_each(|variable| Hash::hash(variable, state));
+        Hash::hash(&self.input_annotations, state);
+        Hash::hash(&self.is_write_stage, state);
+    }
+}
+
+impl PartialEq for InferenceKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.schema_version == other.schema_version
+            && self.conjunctions == other.conjunctions
+            && self.variable_categories == other.variable_categories
+            && self.input_annotations == other.input_annotations
+            && self.is_write_stage == other.is_write_stage
+    }
+}
+
+impl Eq for InferenceKey {}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{cell::Cell, collections::BTreeMap, sync::Arc};
+
+    use concept::type_::type_manager::{type_cache::TypeCache, TypeManager};
+    use encoding::{
+        graph::{
+            definition::definition_key_generator::DefinitionKeyGenerator, type_::vertex_generator::TypeVertexGenerator,
+        },
+        value::label::Label,
+    };
+    use ir::{
+        pattern::{
+            constraint::IsaKind,
+            variable_category::{VariableCategory, VariableOptionality},
+        },
+        pipeline::{
+            block::Block,
+            function_signature::{FunctionID, FunctionSignature},
+            ParameterRegistry, VariableRegistry,
+        },
+        translation::PipelineTranslationContext,
+    };
+    use resource::profile::CommitProfile;
+    use storage::{durability_client::WALClient, snapshot::CommittableSnapshot, MVCCStorage};
+
+    use crate::annotation::{
+        function::EmptyAnnotatedFunctionSignatures,
+        match_inference::infer_types,
+        tests::{
+            managers,
+            schema_consts::{setup_types, LABEL_CAT},
+            setup_storage,
+        },
+        type_annotations::BlockAnnotations,
+        type_inference_cache::TypeInferenceCache,
+        TypeInferenceError,
+    };
+
+    // match $animal isa <label>;
+    fn isa_block(label: &Label) -> (Block, VariableRegistry) {
+        let mut context = PipelineTranslationContext::new();
+        let mut value_parameters = ParameterRegistry::new();
+        let mut builder = Block::builder(context.new_block_builder_context(&mut value_parameters));
+        let mut conjunction = builder.conjunction_mut();
+        let var_animal = conjunction.constraints_mut().get_or_declare_variable("animal", None).unwrap();
+        let var_animal_type = conjunction.constraints_mut().get_or_declare_variable("animal_type", None).unwrap();
+        conjunction.constraints_mut().add_label(var_animal_type, label.clone()).unwrap();
+        conjunction.constraints_mut().add_isa(IsaKind::Subtype, var_animal, var_animal_type.into(), None).unwrap();
+        let block = builder.finish().unwrap();
+        (block, context.variable_registry)
+    }
+
+    // match $cat_type label <label>; $cat isa $cat_type; declaring the variables in the opposite order
+    fn reordered_isa_block(label: &Label) -> (Block, VariableRegistry) {
+        let mut context = PipelineTranslationContext::new();
+        let mut value_parameters = ParameterRegistry::new();
+        let mut builder = Block::builder(context.new_block_builder_context(&mut value_parameters));
+        let mut conjunction = builder.conjunction_mut();
+        let var_cat_type = conjunction.constraints_mut().get_or_declare_variable("cat_type", None).unwrap();
+        let var_cat = conjunction.constraints_mut().get_or_declare_variable("cat", None).unwrap();
+        conjunction.constraints_mut().add_isa(IsaKind::Subtype, var_cat, var_cat_type.into(), None).unwrap();
+        conjunction.constraints_mut().add_label(var_cat_type, label.clone()).unwrap();
+        let block = builder.finish().unwrap();
+        (block, context.variable_registry)
+    }
+
+    fn cached_type_manager(storage: &Arc<MVCCStorage<WALClient>>) -> TypeManager {
+        let type_cache = TypeCache::new(storage.clone(), storage.snapshot_watermark()).unwrap();
+        TypeManager::new(
+            Arc::new(DefinitionKeyGenerator::new()),
+            Arc::new(TypeVertexGenerator::new()),
+            Some(Arc::new(type_cache)),
+        )
+    }
+
+    fn get_or_infer(
+        cache: &TypeInferenceCache,
+        type_manager: &TypeManager,
+        block: &Block,
+        variable_registry: &VariableRegistry,
+        is_write_stage: bool,
+        inferred: &Cell<usize>,
+        infer: impl FnOnce() -> Result<BlockAnnotations, TypeInferenceError>,
+    ) -> BlockAnnotations {
+        cache
+            .get_or_infer(type_manager, block, variable_registry, &BTreeMap::new(), is_write_stage, || {
+                inferred.set(inferred.get() + 1);
+                infer()
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn equal_blocks_share_annotations_within_schema_version() {
+        let (_tmp_dir, storage) = setup_storage();
+        let (type_manager, thing_manager) = managers();
+        setup_types(storage.clone().open_snapshot_write(), &type_manager, &thing_manager);
+        let type_manager = cached_type_manager(&storage);
+        let snapshot = storage.clone().open_snapshot_read();
+
+        let cache = TypeInferenceCache::new();
+        let inferred = Cell::new(0);
+        let mut annotations = Vec::new();
+        // each query translates its own block, with its own variables
+        for _ in 0..2 {
+            let (block, variable_registry) = isa_block(&LABEL_CAT);
+            let infer = || {
+                infer_types(
+                    &snapshot,
+                    &block,
+                    &variable_registry,
+                    &type_manager,
+                    &BTreeMap::new(),
+                    &EmptyAnnotatedFunctionSignatures,
+                    false,
+                )
+            };
+            let block_annotations =
+                get_or_infer(&cache, &type_manager, &block, &variable_registry, false, &inferred, infer);
+            annotations.push(block_annotations.type_annotations_of(block.conjunction()).unwrap().clone());
+        }
+        assert_eq!(inferred.get(), 1);
+        assert_eq!(annotations[0].vertex_annotations(), annotations[1].vertex_annotations());
+
+        // write stages are annotated separately
+        let (block, variable_registry) = isa_block(&LABEL_CAT);
+        let infer = || {
+            infer_types(
+                &snapshot,
+                &block,
+                &variable_registry,
+                &type_manager,
+                &BTreeMap::new(),
+                &EmptyAnnotatedFunctionSignatures,
+                true,
+            )
+        };
+        get_or_infer(&cache, &type_manager, &block, &variable_registry, true, &inferred, infer);
+        assert_eq!(inferred.get(), 2);
+    }
+
+    #[test]
+    fn blocks_differing_in_variables_and_order_share_renamed_annotations() {
+        let (_tmp_dir, storage) = setup_storage();
+        let (type_manager, thing_manager) = managers();
+        setup_types(storage.clone().open_snapshot_write(), &type_manager, &thing_manager);
+        let type_manager = cached_type_manager(&storage);
+        let snapshot = storage.clone().open_snapshot_read();
+        let infer = |block: &Block, variable_registry: &VariableRegistry| {
+            infer_types(
+                &snapshot,
+                block,
+                variable_registry,
+                &type_manager,
+                &BTreeMap::new(),
+                &EmptyAnnotatedFunctionSignatures,
+                false,
+            )
+        };
+
+        let cache = TypeInferenceCache::new();
+        let inferred = Cell::new(0);
+        let (block, variable_registry) = isa_block(&LABEL_CAT);
+        get_or_infer(&cache, &type_manager, &block, &variable_registry, false, &inferred, || {
+            infer(&block, &variable_registry)
+        });
+
+        let (reordered_block, reordered_variable_registry) = reordered_isa_block(&LABEL_CAT);
+        let cached = get_or_infer(
+            &cache,
+            &type_manager,
+            &reordered_block,
+            &reordered_variable_registry,
+            false,
+            &inferred,
+            || infer(&reordered_block, &reordered_variable_registry),
+        );
+        assert_eq!(inferred.get(), 1);
+
+        let expected = infer(&reordered_block, &reordered_variable_registry).unwrap();
+        let conjunction = reordered_block.conjunction();
+        assert_eq!(cached.type_annotations_of(conjunction), expected.type_annotations_of(conjunction));
+    }
+
+    #[test]
+    fn schema_changes_and_uncached_schemas_are_inferred_again() {
+        let (_tmp_dir, storage) = setup_storage();
+        let (uncached_type_manager, thing_manager) = managers();
+        setup_types(storage.clone().open_snapshot_write(), &uncached_type_manager, &thing_manager);
+        let (block, variable_registry) = isa_block(&LABEL_CAT);
+
+        let cache = TypeInferenceCache::new();
+        let inferred = Cell::new(0);
+        let infer_with = |type_manager: &TypeManager| {
+            let snapshot = storage.clone().open_snapshot_read();
+            let infer = || {
+                infer_types(
+                    &snapshot,
+                    &block,
+                    &variable_registry,
+                    type_manager,
+                    &BTreeMap::new(),
+                    &EmptyAnnotatedFunctionSignatures,
+                    false,
+                )
+            };
+            get_or_infer(&cache, type_manager, &block, &variable_registry, false, &inferred, infer);
+        };
+
+        // a schema read from the snapshot has no version, and may be modified by the snapshot
+        infer_with(&uncached_type_manager);
+        infer_with(&uncached_type_manager);
+        assert_eq!(inferred.get(), 2);
+
+        infer_with(&cached_type_manager(&storage));
+        infer_with(&cached_type_manager(&storage));
+        assert_eq!(inferred.get(), 3);
+
+        let mut snapshot = storage.clone().open_snapshot_schema();
+        uncached_type_manager.create_entity_type(&mut snapshot, &Label::build("bird", None)).unwrap();
+        snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+        infer_with(&cached_type_manager(&storage));
+        assert_eq!(inferred.get(), 4);
+    }
+
+    #[test]
+    fn blocks_calling_functions_are_not_cached() {
+        let (_tmp_dir, storage) = setup_storage();
+        let (type_manager, thing_manager) = managers();
+        setup_types(storage.clone().open_snapshot_write(), &type_manager, &thing_manager);
+        let type_manager = cached_type_manager(&storage);
+        let snapshot = storage.clone().open_snapshot_read();
+
+        // match $animal isa cat; annotations to return in place of inferring a call
+        let (block, variable_registry) = isa_block(&LABEL_CAT);
+        let annotations = infer_types(
+            &snapshot,
+            &block,
+            &variable_registry,
+            &type_manager,
+            &BTreeMap::new(),
+            &EmptyAnnotatedFunctionSignatures,
+            false,
+        )
+        .unwrap();
+
+        // match $animal = test_fn();
+        let mut context = PipelineTranslationContext::new();
+        let mut value_parameters = ParameterRegistry::new();
+        let mut builder = Block::builder(context.new_block_builder_context(&mut value_parameters));
+        let mut conjunction = builder.conjunction_mut();
+        let var_animal = conjunction.constraints_mut().get_or_declare_variable("animal", None).unwrap();
+        let signature = FunctionSignature::new(
+            FunctionID::Preamble(0),
+            vec![],
+            vec![(VariableCategory::Object, VariableOptionality::Required)],
+            true,
+        );
+        conjunction
+            .constraints_mut()
+            .add_function_binding(vec![var_animal], &signature, vec![], "test_fn", None)
+            .unwrap();
+        let block = builder.finish().unwrap();
+
+        let cache = TypeInferenceCache::new();
+        let inferred = Cell::new(0);
+        for _ in 0..2 {
+            get_or_infer(&cache, &type_manager, &block, &context.variable_registry, false, &inferred, || {
+                Ok(annotations.clone())
+            });
+        }
+        assert_eq!(inferred.get(), 2);
+    }
+}
@@ -8,12 +8,17 @@ use std::{
     borrow::Cow,
     collections::{BTreeMap, BTreeSet, HashSet},
     iter::zip,
+    sync::Arc,
 };
 
 use answer::{variable::Variable, Type as TypeAnnotation, Type};
 use concept::{
     error::ConceptReadError,
-    type_::{object_type::ObjectType, type_manager::TypeManager, OwnerAPI, PlayerAPI, TypeAPI},
+    type_::{
+        object_type::ObjectType,
+        type_manager::{hierarchy_closure::TypeHierarchyClosure, TypeManager},
+        OwnerAPI, PlayerAPI, TypeAPI,
+    },
 };
 use encoding::value::value_type::{ValueType, ValueTypeCategory};
 use ir::{
@@ -46,6 +51,8 @@ pub struct TypeGraphSeedingContext<'this, Snapshot: ReadableSnapshot> {
     function_annotations: &'this dyn AnnotatedFunctionSignatures,
     variable_registry: &'this VariableRegistry,
     is_write_stage: bool,
+    // only available from the type cache, since building it without one would read the whole hierarchy
+    hierarchy_closure: Option<Arc<TypeHierarchyClosure>>,
 }
 
 impl<'this, Snapshot: ReadableSnapshot> TypeGraphSeedingContext<'this, Snapshot> {
@@ -56,7 +63,14 @@ impl<'this, Snapshot: ReadableSnapshot> TypeGraphSeedingContext<'this, Snapshot>
         variable_registry: &'this VariableRegistry,
         is_write_stage: bool,
     ) -> Self {
-        TypeGraphSeedingContext { snapshot, type_manager, function_annotations, variable_registry, is_write_stage }
+        TypeGraphSeedingContext {
+            snapshot,
+            type_manager,
+            function_annotations,
+            variable_registry,
+            is_write_stage,
+            hierarchy_closure: type_manager.get_cached_hierarchy_closure(),
+        }
     }
 
     pub(crate) fn create_graph<'graph>(
@@ -574,6 +588,28 @@ impl<'this, Snapshot: ReadableSnapshot> TypeGraphSeedingContext<'this, Snapshot>
     }
 }
 
+fn is_subtype_transitive_of_or_same(
+    hierarchy_closure: &TypeHierarchyClosure,
+    subtype: &TypeAnnotation,
+    supertype: &TypeAnnotation,
+) -> bool {
+    match (subtype, supertype) {
+        (TypeAnnotation::Entity(subtype), TypeAnnotation::Entity(supertype)) => {
+            hierarchy_closure.is_subtype_transitive_of_or_same(*subtype, *supertype)
+        }
+        (TypeAnnotation::Relation(subtype), TypeAnnotation::Relation(supertype)) => {
+            hierarchy_closure.is_subtype_transitive_of_or_same(*subtype, *supertype)
+        }
+        (TypeAnnotation::Attribute(subtype), TypeAnnotation::Attribute(supertype)) => {
+            hierarchy_closure.is_subtype_transitive_of_or_same(*subtype, *supertype)
+        }
+        (TypeAnnotation::RoleType(subtype), TypeAnnotation::RoleType(supertype)) => {
+            hierarchy_closure.is_subtype_transitive_of_or_same(*subtype, *supertype)
+        }
+        _ => false,
+    }
+}
+
 trait UnaryConstraint {
     fn apply<Snapshot: ReadableSnapshot>(
         &self,
@@ -811,10 +847,19 @@ trait BinaryConstraint {
             debug_assert!(!left_is_thing || left_types.iter().all(|t| context.is_not_abstract(t).unwrap()));
             debug_assert!(!right_is_thing || allowed_right_types.iter().all(|t| context.is_not_abstract(t).unwrap()));
         }
+        let hierarchy_closure = context.hierarchy_closure.as_deref().filter(|_| self.is_transitive_subtyping(context));
         for left_type in left_types {
             let mut right_annotations = BTreeSet::new();
-            self.annotate_left_to_right_for_type(context, left_type, &mut right_annotations)?;
-            right_annotations.retain(|type_| allowed_right_types.contains(type_));
+            if let Some(hierarchy_closure) = hierarchy_closure {
+                for right_type in allowed_right_types {
+                    if is_subtype_transitive_of_or_same(hierarchy_closure, left_type, right_type) {
+                        right_annotations.insert(*right_type);
+                    }
+                }
+            } else {
+                self.annotate_left_to_right_for_type(context, left_type, &mut right_annotations)?;
+                right_annotations.retain(|type_| allowed_right_types.contains(type_));
+            }
             debug_assert!(
                 !self.check_for_thing_vars(context).1
                     || right_annotations.iter().all(|t| context.is_not_abstract(t).unwrap())
@@ -839,10 +884,19 @@ trait BinaryConstraint {
             debug_assert!(!right_is_thing || right_types.iter().all(|t| context.is_not_abstract(t).unwrap()));
             debug_assert!(!left_is_thing || allowed_left_types.iter().all(|t| context.is_not_abstract(t).unwrap()));
         }
+        let hierarchy_closure = context.hierarchy_closure.as_deref().filter(|_| self.is_transitive_subtyping(context));
         for right_type in right_types {
             let mut left_annotations = BTreeSet::new();
-            self.annotate_right_to_left_for_type(context, right_type, &mut left_annotations)?;
-            left_annotations.retain(|type_| allowed_left_types.contains(type_));
+            if let Some(hierarchy_closure) = hierarchy_closure {
+                for left_type in allowed_left_types {
+                    if is_subtype_transitive_of_or_same(hierarchy_closure, left_type, right_type) {
+                        left_annotations.insert(*left_type);
+                    }
+                }
+            } else {
+                self.annotate_right_to_left_for_type(context, right_type, &mut left_annotations)?;
+                left_annotations.retain(|type_| allowed_left_types.contains(type_));
+            }
             debug_assert!(
                 !self.check_for_thing_vars(context).0
                     || left_annotations.iter().all(|t| context.is_not_abstract(t).unwrap())
@@ -854,6 +908,12 @@ trait BinaryConstraint {
         Ok(right_to_left)
     }
 
+    /// Whether the constraint relates each left type to exactly its supertypes, or itself, on the right.
+    /// Such constraints are propagated by constant-time checks against the type hierarchy closure.
+    fn is_transitive_subtyping(&self, _context: &TypeGraphSeedingContext<'_, impl ReadableSnapshot>) -> bool {
+        false
+    }
+
     fn annotate_left_to_right_for_type(
         &self,
         context: &TypeGraphSeedingContext<'_, impl ReadableSnapshot>,
@@ -981,6 +1041,10 @@ impl BinaryConstraint for Isa<Variable> {
         self.type_()
     }
 
+    fn is_transitive_subtyping(&self, context: &TypeGraphSeedingContext<'_, impl ReadableSnapshot>) -> bool {
+        !context.is_write_stage && self.isa_kind() == IsaKind::Subtype
+    }
+
     fn annotate_left_to_right_for_type(
         &self,
         context: &TypeGraphSeedingContext<'_, impl ReadableSnapshot>,
@@ -1079,6 +1143,10 @@ impl BinaryConstraint for Sub<Variable> {
         self.supertype()
     }
 
+    fn is_transitive_subtyping(&self, _context: &TypeGraphSeedingContext<'_, impl ReadableSnapshot>) -> bool {
+        self.sub_kind() == SubKind::Subtype
+    }
+
     fn annotate_left_to_right_for_type(
         &self,
         context: &TypeGraphSeedingContext<'_, impl ReadableSnapshot>,
@@ -36,6 +36,51 @@ pub fn check_type_combinations_for_write(
     input_annotations_variables: &BTreeMap<Variable, Arc<BTreeSet<answer::Type>>>,
     input_annotations_constraints: &HashMap<Constraint<Variable>, ConstraintTypeAnnotations>,
     insert_annotations: &BlockAnnotations,
+) -> Result<(), TypeInferenceError> {
+    check_type_combinations_for_block(
+        snapshot,
+        type_manager,
+        variable_registry,
+        block,
+        input_annotations_variables,
+        input_annotations_constraints,
+        insert_annotations,
+        true,
+    )
+}
+
+/// Deletes may use variables whose types only sometimes form a valid edge, such as `delete has $a of $x;` for any
+/// attribute `$a`: the edges are resolved per row, so only combinations which are never valid are rejected.
+pub fn check_type_combinations_for_delete(
+    snapshot: &impl ReadableSnapshot,
+    type_manager: &TypeManager,
+    variable_registry: &VariableRegistry,
+    block: &Block,
+    input_annotations_variables: &BTreeMap<Variable, Arc<BTreeSet<answer::Type>>>,
+    input_annotations_constraints: &HashMap<Constraint<Variable>, ConstraintTypeAnnotations>,
+    delete_annotations: &BlockAnnotations,
+) -> Result<(), TypeInferenceError> {
+    check_type_combinations_for_block(
+        snapshot,
+        type_manager,
+        variable_registry,
+        block,
+        input_annotations_variables,
+        input_annotations_constraints,
+        delete_annotations,
+        false,
+    )
+}
+
+fn check_type_combinations_for_block(
+    snapshot: &impl ReadableSnapshot,
+    type_manager: &TypeManager,
+    variable_registry: &VariableRegistry,
+    block: &Block,
+    input_annotations_variables: &BTreeMap<Variable, Arc<BTreeSet<answer::Type>>>,
+    input_annotations_constraints: &HashMap<Constraint<Variable>, ConstraintTypeAnnotations>,
+    insert_annotations: &BlockAnnotations,
+    require_all_valid: bool,
 ) -> Result<(), TypeInferenceError> {
     let conjunction = block.conjunction();
     check_type_combinations_for_write_conjunction(
@@ -46,6 +91,7 @@ pub fn check_type_combinations_for_write(
         input_annotations_variables,
         input_annotations_constraints,
         insert_annotations,
+        require_all_valid,
     )?;
     for nested_pattern in conjunction.nested_patterns() {
         match nested_pattern {
@@ -63,6 +109,7 @@ pub fn check_type_combinations_for_write(
                 input_annotations_variables,
                 input_annotations_constraints,
                 insert_annotations,
+                require_all_valid,
             )?,
         }
     }
@@ -77,6 +124,7 @@ fn check_type_combinations_for_write_conjunction(
     input_annotations_variables: &BTreeMap<Variable, Arc<BTreeSet<Type>>>,
     input_annotations_constraints: &HashMap<Constraint<Variable>, ConstraintTypeAnnotations>,
     insert_annotations: &BlockAnnotations,
+    require_all_valid: bool,
 ) -> Result<(), TypeInferenceError> {
     for constraint in conjunction.constraints() {
         match constraint {
@@ -94,6 +142,7 @@ fn check_type_combinations_for_write_conjunction(
                         .constraint_annotations_of(constraint.clone())
                         .unwrap()
                         .as_left_right(),
+                    require_all_valid,
                 )?;
             }
             Constraint::Links(links) => {
@@ -110,6 +159,7 @@ fn check_type_combinations_for_write_conjunction(
                         .constraint_annotations_of(constraint.clone())
                         .unwrap()
                         .as_links(),
+                    require_all_valid,
                 )?;
             }
 
@@ -145,6 +195,7 @@ pub(crate) fn validate_has_type_combinations_for_write(
     input_annotations_variables: &BTreeMap<Variable, Arc<BTreeSet<answer::Type>>>,
     input_annotations_constraints: &HashMap<Constraint<Variable>, ConstraintTypeAnnotations>, // Future use
     valid_insert_types: &LeftRightAnnotations,
+    require_all_valid: bool,
 ) -> Result<(), TypeInferenceError> {
     // TODO: Improve. This is still coarse and likely to rule out some valid combinations
     // Esp when doing queries using type variables. See
@@ -176,6 +227,7 @@ pub(crate) fn validate_has_type_combinations_for_write(
         snapshot,
         type_manager,
         insert_has.source_span(),
+        require_all_valid,
     )
 }
 
@@ -187,6 +239,7 @@ pub(crate) fn validate_links_type_combinations_for_write(
     input_annotations_variables: &BTreeMap<Variable, Arc<BTreeSet<answer::Type>>>,
     input_annotations_constraints: &HashMap<Constraint<Variable>, ConstraintTypeAnnotations>, // Future use
     valid_insert_types: &LinksAnnotations,
+    require_all_valid: bool,
 ) -> Result<(), TypeInferenceError> {
     // TODO: Should we check uniqueness of inferred role-types here instead of at compilation?
     // TODO: Improve. This is extremely coarse and likely to rule out many valid combinations
@@ -226,6 +279,7 @@ pub(crate) fn validate_links_type_combinations_for_write(
             snapshot,
             type_manager,
             insert_links.source_span(),
+            require_all_valid,
         )?;
     }
 
@@ -263,6 +317,7 @@ pub(crate) fn validate_links_type_combinations_for_write(
             snapshot,
             type_manager,
             insert_links.source_span(),
+            require_all_valid,
         )?;
     }
     Ok(())
@@ -316,13 +371,18 @@ fn check_insert_types_against_match_types<T: ContainsAndIterOnType>(
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
     source_span: Option<Span>,
+    require_all_valid: bool,
 ) -> Result<(), TypeInferenceError> {
-    let mut invalid_iter = match_pairs.iter().filter(|(left_type, right_type)| {
-        !valid_insert_pairs
+    let is_valid = |(left_type, right_type): &&(Type, Type)| {
+        valid_insert_pairs
             .get(left_type)
             .map(|valid_right_types| valid_right_types._contains(right_type))
             .unwrap_or(false)
-    });
+    };
+    if !require_all_valid && match_pairs.iter().any(|pair| is_valid(&pair)) {
+        return Ok(());
+    }
+    let mut invalid_iter = match_pairs.iter().filter(|pair| !is_valid(pair));
     if let Some((left_type, right_type)) = invalid_iter.next() {
         Err(TypeInferenceError::IllegalTypeCombinationForWrite {
             constraint_name: Into::<Constraint<Variable>>::into(constraint.clone()).name().to_string(),
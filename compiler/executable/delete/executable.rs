@@ -12,16 +12,14 @@ use ir::{
     pattern::{constraint::Constraint, nested_pattern::NestedPattern, Vertex},
     pipeline::{block::Block, VariableRegistry},
 };
+use itertools::Itertools;
 use typeql::common::Span;
 
 use crate::{
     annotation::type_annotations::BlockAnnotations,
     executable::{
-        delete::instructions::{ConnectionInstruction, Has, Links, ThingInstruction},
-        insert::{
-            executable::{get_thing_position, resolve_links_roles},
-            ThingPosition,
-        },
+        delete::instructions::{ConnectionInstruction, Has, Links, LinksAnyRole, ThingInstruction},
+        insert::{executable::get_thing_position, ThingPosition, TypeSource},
         next_executable_id, WriteCompilationError,
     },
     VariablePosition,
@@ -159,12 +157,8 @@ fn add_connection_deletes(
     variable_registry: &VariableRegistry,
     connection_deletes: &mut Vec<ConnectionInstruction>,
 ) -> Result<(), Box<WriteCompilationError>> {
-    let resolved_roles = resolve_links_roles(
-        conjunction.constraints(),
-        block_annotations.type_annotations_of(conjunction).expect("delete conjunction must have type annotations"),
-        input_variables,
-        variable_registry,
-    )?;
+    let type_annotations =
+        block_annotations.type_annotations_of(conjunction).expect("delete conjunction must have type annotations");
     for constraint in conjunction.constraints() {
         match constraint {
             Constraint::Has(has) => {
@@ -196,8 +190,29 @@ fn add_connection_deletes(
                     variable_registry,
                     links.source_span(),
                 )?;
-                let role = resolved_roles.get(&links.role_type().as_variable().unwrap()).unwrap().clone();
-                connection_deletes.push(ConnectionInstruction::Links(Links { relation, player, role }));
+                let role_type = links.role_type().as_variable().expect("links.role_type is always a variable");
+                let instruction = if let Some(&input_position) = input_variables.get(&role_type) {
+                    ConnectionInstruction::Links(Links {
+                        relation,
+                        player,
+                        role: TypeSource::InputVariable(input_position),
+                    })
+                } else {
+                    let role_types = type_annotations.vertex_annotations_of(links.role_type()).unwrap();
+                    match role_types.iter().exactly_one() {
+                        Ok(&role_type) => ConnectionInstruction::Links(Links {
+                            relation,
+                            player,
+                            role: TypeSource::Constant(role_type),
+                        }),
+                        Err(_) => ConnectionInstruction::LinksAnyRole(LinksAnyRole {
+                            relation,
+                            player,
+                            role_types: role_types.iter().copied().collect(),
+                        }),
+                    }
+                };
+                connection_deletes.push(instruction);
             }
             Constraint::LinksDeduplication(_) | Constraint::RoleName(_) => (), // Ignore. It will have done its job during type-inference
             Constraint::Iid(_)
@@ -10,8 +10,9 @@ use crate::executable::insert::{ThingPosition, TypeSource};
 
 #[derive(Debug)]
 pub enum ConnectionInstruction {
-    Has(Has),     // TODO: Ordering
-    Links(Links), // TODO: Ordering
+    Has(Has),                   // TODO: Ordering
+    Links(Links),               // TODO: Ordering
+    LinksAnyRole(LinksAnyRole), // TODO: Ordering
 }
 
 impl fmt::Display for ConnectionInstruction {
@@ -19,6 +20,7 @@ impl fmt::Display for ConnectionInstruction {
         match self {
             ConnectionInstruction::Has(_) => write!(f, "has"),
             ConnectionInstruction::Links(_) => write!(f, "links"),
+            ConnectionInstruction::LinksAnyRole(_) => write!(f, "links any role"),
         }
     }
 }
@@ -46,3 +48,12 @@ pub struct Links {
     pub player: ThingPosition,
     pub role: TypeSource,
 }
+
+/// Deletes the player from the relation in each of the candidate roles it plays,
+/// for links whose role type is neither an input nor inferred to a single type.
+#[derive(Debug)]
+pub struct LinksAnyRole {
+    pub relation: ThingPosition,
+    pub player: ThingPosition,
+    pub role_types: Vec<answer::Type>,
+}
@@ -47,6 +47,8 @@ pub(super) fn determine_compilation_order_and_tabling_types<FIDType: FunctionIDA
         assign_scc(&reverse_dependencies, &mut scc_mapping, root, root);
     });
 
+    validate_stream_modifiers_precede_recursive_calls(to_compile, &scc_mapping)?;
+
     // Convert SCCID to TablingTypes
     debug_assert!(scc_mapping.len() == to_compile.len() && to_compile.keys().all(|k| scc_mapping.contains_key(k)));
     let tabling_types = scc_mapping
@@ -60,6 +62,44 @@ pub(super) fn determine_compilation_order_and_tabling_types<FIDType: FunctionIDA
     Ok((post_order, tabling_types))
 }
 
+/// Sorting, offsetting and limiting are only defined over complete streams of answers, but a tabled function's answers
+/// to a recursive call are incomplete until the fixed point is reached. Such stages may only consume answers that
+/// do not depend on the function's own strongly connected component.
+fn validate_stream_modifiers_precede_recursive_calls<FIDType: FunctionIDAPI>(
+    to_compile: &HashMap<FIDType, AnnotatedFunction>,
+    scc_mapping: &HashMap<FIDType, StronglyConnectedComponentID>,
+) -> Result<(), ExecutableCompilationError> {
+    for (fid, function) in to_compile {
+        let scc = scc_mapping.get(fid).unwrap();
+        let mut has_recursive_call = false;
+        for stage in &function.stages {
+            let stage_name = match stage {
+                AnnotatedStage::Match { block, .. } => {
+                    let mut calls = HashSet::new();
+                    all_calls_in_conjunction(block.conjunction(), &mut calls);
+                    has_recursive_call |= calls
+                        .into_iter()
+                        .filter_map(|id| FIDType::try_from(id).ok())
+                        .any(|id| scc_mapping.get(&id) == Some(scc));
+                    continue;
+                }
+                AnnotatedStage::Sort(_) => "sort",
+                AnnotatedStage::Offset(_) => "offset",
+                AnnotatedStage::Limit(_) => "limit",
+                _ => continue,
+            };
+            if has_recursive_call {
+                let function_id: FunctionID = fid.clone().into();
+                return Err(ExecutableCompilationError::StreamModifierAfterRecursiveCall {
+                    function: function_id.to_string(),
+                    stage: stage_name.to_owned(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
 fn collect_dependencies<FIDType: FunctionIDAPI>(
     to_compile: &HashMap<FIDType, AnnotatedFunction>,
     forward_dependencies: &mut HashMap<FIDType, HashSet<FIDType>>,
@@ -13,6 +13,7 @@ use ir::{
         constraint::{Comparator, Constraint},
         expression::Expression,
         nested_pattern::NestedPattern,
+        variable_category::VariableOptionality,
         ParameterID, Vertex,
     },
     pipeline::{block::Block, VariableRegistry},
@@ -38,6 +39,7 @@ pub struct InsertExecutable {
     pub concept_instructions: Vec<ConceptInstruction>,
     pub connection_instructions: Vec<ConnectionInstruction>,
     pub optional_inserts: Vec<OptionalInsert>,
+    pub conditional_positions: HashSet<VariablePosition>,
     pub output_row_schema: Vec<Option<(Variable, VariableSource)>>,
 }
 
@@ -84,6 +86,9 @@ pub fn compile(
         input_variables,
     )?;
 
+    let conditional_positions =
+        collect_conditional_positions(block.conjunction(), &concept_instructions, variable_registry, input_variables);
+
     let mut optional_inserts = Vec::with_capacity(block.conjunction().nested_patterns().len());
     for nested_pattern in block.conjunction().nested_patterns() {
         let NestedPattern::Optional(optional) = nested_pattern else {
@@ -104,10 +109,35 @@ pub fn compile(
         concept_instructions,
         connection_instructions,
         optional_inserts,
+        conditional_positions,
         output_row_schema: prepare_output_row_schema(input_variables, &variable_positions),
     })
 }
 
+/// Input variables bound by an optional pattern of an earlier stage may be empty, as may the concepts inserted from
+/// them. Instructions referencing these positions are skipped for rows in which they are empty.
+fn collect_conditional_positions(
+    conjunction: &ir::pattern::conjunction::Conjunction,
+    concept_instructions: &[ConceptInstruction],
+    variable_registry: &VariableRegistry,
+    input_variables: &HashMap<Variable, VariablePosition>,
+) -> HashSet<VariablePosition> {
+    let mut conditional_positions: HashSet<VariablePosition> = conjunction
+        .constraints()
+        .iter()
+        .flat_map(|constraint| constraint.ids())
+        .filter(|&variable| variable_registry.get_variable_optionality(variable) == Some(VariableOptionality::Optional))
+        .filter_map(|variable| input_variables.get(&variable).copied())
+        .collect();
+    let conditionally_inserted = concept_instructions
+        .iter()
+        .filter(|instruction| instruction.input_positions().any(|position| conditional_positions.contains(&position)))
+        .map(|instruction| instruction.inserted_position().0)
+        .collect_vec();
+    conditional_positions.extend(conditionally_inserted);
+    conditional_positions
+}
+
 #[derive(Debug)]
 pub struct OptionalInsert {
     pub concept_instructions: Vec<ConceptInstruction>,
@@ -6,7 +6,10 @@
 
 use std::fmt;
 
-use crate::executable::insert::{ThingPosition, TypeSource, ValueSource};
+use crate::{
+    executable::insert::{ThingPosition, TypeSource, ValueSource},
+    VariablePosition,
+};
 
 #[derive(Debug)]
 pub enum InsertInstruction {
@@ -43,6 +46,18 @@ impl ConceptInstruction {
             ConceptInstruction::PutAttribute(inner) => inner.write_to,
         }
     }
+
+    pub fn input_positions(&self) -> impl Iterator<Item = VariablePosition> {
+        let (type_, value) = match self {
+            ConceptInstruction::PutObject(inner) => (&inner.type_, None),
+            ConceptInstruction::PutAttribute(inner) => (&inner.type_, Some(&inner.value)),
+        };
+        let value_position = match value {
+            Some(&ValueSource::Variable(position)) => Some(position),
+            Some(ValueSource::Parameter(_)) | None => None,
+        };
+        [type_.input_position(), value_position].into_iter().flatten()
+    }
 }
 
 impl fmt::Display for ConceptInstruction {
@@ -60,6 +75,16 @@ pub enum ConnectionInstruction {
     Links(Links), // TODO: Ordering
 }
 
+impl ConnectionInstruction {
+    pub fn input_positions(&self) -> impl Iterator<Item = VariablePosition> {
+        let positions = match self {
+            Self::Has(has) => [Some(has.owner.0), Some(has.attribute.0), None],
+            Self::Links(links) => [Some(links.relation.0), Some(links.player.0), links.role.input_position()],
+        };
+        positions.into_iter().flatten()
+    }
+}
+
 impl fmt::Display for ConnectionInstruction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
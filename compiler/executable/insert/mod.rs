@@ -23,6 +23,15 @@ pub enum TypeSource {
     Constant(answer::Type),
 }
 
+impl TypeSource {
+    pub fn input_position(&self) -> Option<VariablePosition> {
+        match self {
+            &TypeSource::InputVariable(position) => Some(position),
+            TypeSource::Constant(_) => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub enum ValueSource {
     Variable(VariablePosition),
@@ -20,7 +20,10 @@ use ir::pattern::{
 };
 use itertools::Itertools;
 
-use crate::{annotation::type_annotations::TypeAnnotations, ExecutorVariable, VariablePosition};
+use crate::{
+    annotation::type_annotations::{ComparisonValueTypes, TypeAnnotations},
+    ExecutorVariable, VariablePosition,
+};
 
 pub mod thing;
 pub mod type_;
@@ -627,6 +630,8 @@ pub enum CheckInstruction<ID> {
         lhs: CheckVertex<ID>,
         rhs: CheckVertex<ID>,
         comparator: Comparator,
+        // None when the operands' value types are only known at runtime
+        value_types: Option<ComparisonValueTypes>,
     },
     Unsatisfiable,
 }
@@ -683,8 +688,8 @@ impl<ID: IrID> CheckInstruction<ID> {
             Self::NotNone { variables } => {
                 CheckInstruction::NotNone { variables: variables.iter().map(|v| mapping[&v]).collect() }
             }
-            Self::Comparison { lhs, rhs, comparator } => {
-                CheckInstruction::Comparison { lhs: lhs.map(mapping), rhs: rhs.map(mapping), comparator }
+            Self::Comparison { lhs, rhs, comparator, value_types } => {
+                CheckInstruction::Comparison { lhs: lhs.map(mapping), rhs: rhs.map(mapping), comparator, value_types }
             }
             Self::Unsatisfiable => CheckInstruction::Unsatisfiable,
         }
@@ -799,7 +804,7 @@ impl<ID: IrID> fmt::Display for CheckInstruction<ID> {
             Self::LinksDeduplication { role1, player1, role2, player2 } => {
                 write!(f, "({role1},{player1}) __links_deduplication__ ({role2},{player2})")?;
             }
-            Self::Comparison { lhs, rhs, comparator } => {
+            Self::Comparison { lhs, rhs, comparator, .. } => {
                 write!(f, "{lhs} {comparator} {rhs}")?;
             }
             Self::Unsatisfiable => {
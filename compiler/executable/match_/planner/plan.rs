@@ -1597,6 +1597,7 @@ impl ConjunctionPlan<'_> {
                     lhs: CheckVertex::resolve(lhs_pos, self.local_annotations),
                     rhs: CheckVertex::resolve(rhs_pos, self.local_annotations),
                     comparator,
+                    value_types: self.local_annotations.comparison_value_types_of(comparison).cloned(),
                 };
 
                 conjunction_builder.push_check(check)
@@ -40,6 +40,12 @@ typedb_error! {
         MatchCompilation(5, "Error compiling match stage into executable.", typedb_source: ConjunctionCompilationError),
         PutMatchCompilation(6, "Error compiling put stage into a match executable.", typedb_source: ConjunctionCompilationError),
         PutInsertCompilation(7, "Error compiling put stage into an insert executable.", typedb_source: Box<WriteCompilationError>),
+        StreamModifierAfterRecursiveCall(
+            8,
+            "Function '{function}' applies '{stage}' to the answers of a recursive call. In recursive functions, 'sort', 'offset' and 'limit' stages must come before any recursive call.",
+            function: String,
+            stage: String,
+        ),
     }
 }
 
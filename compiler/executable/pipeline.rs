@@ -38,6 +38,7 @@ use crate::{
         ExecutableCompilationError,
     },
     query_structure::ParametrisedPipelineStructure,
+    warning::QueryWarning,
     VariablePosition,
 };
 
@@ -80,6 +81,7 @@ pub struct ExecutablePipeline {
     pub executable_fetch: Option<Arc<ExecutableFetch>>,
     pub pipeline_structure: Arc<ParametrisedPipelineStructure>,
     pub type_populations: TypePopulations,
+    pub warnings: Vec<QueryWarning>,
 }
 
 #[derive(Debug, Clone)]
@@ -191,6 +193,7 @@ pub fn compile_pipeline_and_functions(
         executable_stages,
         executable_fetch,
         type_populations,
+        warnings: Vec::new(),
     })
 }
 
@@ -240,8 +243,8 @@ pub(crate) fn compile_pipeline_stages(
         input_variables.enumerate().map(|(i, var)| (var, VariablePosition::new(i as u32))).collect();
     let mut last_match_annotations = None;
     let mut type_populations = TypePopulations::default();
-    for stage in annotated_stages {
-        // TODO: We can filter out the variables that are no longer needed in the future stages, but are carried as selected variables from the previous one
+    let live_variables = live_variables_after_stages(annotated_stages, function_return);
+    for (index, stage) in annotated_stages.iter().enumerate() {
         let (executable_stage, referenced_types) =
             match executable_stages.last().map(|stage| stage.output_row_mapping()) {
                 Some(row_mapping) => compile_stage(
@@ -268,11 +271,89 @@ pub(crate) fn compile_pipeline_stages(
                 Some(block_annotations.type_annotations_of(block.conjunction()).unwrap().vertex_annotations())
         }
         type_populations.update(&referenced_types, statistics);
+        let row_mapping = executable_stage.output_row_mapping();
         executable_stages.push(executable_stage);
+
+        // drop the variables no later stage uses, unless the next stage already replaces the rows
+        let next_stage_replaces_rows = matches!(
+            annotated_stages.get(index + 1),
+            None | Some(AnnotatedStage::Select(_) | AnnotatedStage::Reduce(..))
+        );
+        if let Some(live_variables) = &live_variables[index] {
+            if !next_stage_replaces_rows && row_mapping.keys().any(|variable| !live_variables.contains(variable)) {
+                let select = compile_select(&row_mapping, live_variables);
+                executable_stages.push(ExecutableStage::Select(Arc::new(select)));
+            }
+        }
     }
     Ok((input_variable_positions, executable_stages, type_populations))
 }
 
+/// Computes the variables used after each stage, where `None` means that all variables remain in use,
+/// as they do at the end of a pipeline or before a `distinct`.
+fn live_variables_after_stages(
+    annotated_stages: &[AnnotatedStage],
+    function_return: Option<&[Variable]>,
+) -> Vec<Option<HashSet<Variable>>> {
+    let mut live: Option<HashSet<Variable>> = function_return.map(|returned| returned.iter().copied().collect());
+    let mut live_after_stages = vec![None; annotated_stages.len()];
+    for (index, stage) in annotated_stages.iter().enumerate().rev() {
+        live_after_stages[index] = live.clone();
+        live = match stage {
+            AnnotatedStage::Match { block, .. }
+            | AnnotatedStage::Insert { block, .. }
+            | AnnotatedStage::Update { block, .. }
+            | AnnotatedStage::Put { block, .. }
+            | AnnotatedStage::Delete { block, .. } => live.map(|mut live| {
+                live.extend(block.variables());
+                live
+            }),
+            AnnotatedStage::Select(select) => Some(match live {
+                None => select.variables.clone(),
+                Some(live) => live.intersection(&select.variables).copied().collect(),
+            }),
+            AnnotatedStage::Sort(sort) => live.map(|mut live| {
+                live.extend(sort.variables.iter().map(|sort_variable| sort_variable.variable()));
+                live
+            }),
+            AnnotatedStage::Offset(_) | AnnotatedStage::Limit(_) => live,
+            AnnotatedStage::Require(require) => live.map(|mut live| {
+                live.extend(require.variables.iter().copied());
+                live
+            }),
+            AnnotatedStage::Distinct(_) => None,
+            AnnotatedStage::Reduce(reduce, _) => Some(
+                reduce
+                    .groupby
+                    .iter()
+                    .copied()
+                    .chain(reduce.assigned_reductions.iter().filter_map(|assigned| assigned.reduction.variable()))
+                    .collect(),
+            ),
+        };
+    }
+    live_after_stages
+}
+
+fn compile_select(
+    stage_input_positions: &HashMap<Variable, VariablePosition>,
+    selected_variables: &HashSet<Variable>,
+) -> SelectExecutable {
+    let mut retained_positions = HashSet::with_capacity(selected_variables.len());
+    let mut removed_positions =
+        HashSet::with_capacity(stage_input_positions.len().saturating_sub(selected_variables.len()));
+    let mut output_row_mapping = HashMap::with_capacity(selected_variables.len());
+    for (&variable, &pos) in stage_input_positions.iter() {
+        if selected_variables.contains(&variable) {
+            retained_positions.insert(pos);
+            output_row_mapping.insert(variable, pos);
+        } else {
+            removed_positions.insert(pos);
+        }
+    }
+    SelectExecutable::new(retained_positions, output_row_mapping, removed_positions)
+}
+
 fn compile_stage(
     statistics: &Statistics,
     variable_registry: &VariableRegistry,
@@ -367,28 +448,10 @@ fn compile_stage(
             .map_err(|typedb_source| ExecutableCompilationError::DeleteExecutableCompilation { typedb_source })?;
             Ok((ExecutableStage::Delete(Arc::new(plan)), BTreeSet::new()))
         }
-        AnnotatedStage::Select(select) => {
-            let mut retained_positions = HashSet::with_capacity(select.variables.len());
-            let mut removed_positions =
-                HashSet::with_capacity(stage_input_positions.len().saturating_sub(select.variables.len()));
-            let mut output_row_mapping = HashMap::with_capacity(select.variables.len());
-            for (&variable, &pos) in stage_input_positions.iter() {
-                if select.variables.contains(&variable) {
-                    retained_positions.insert(pos);
-                    output_row_mapping.insert(variable, pos);
-                } else {
-                    removed_positions.insert(pos);
-                }
-            }
-            Ok((
-                ExecutableStage::Select(Arc::new(SelectExecutable::new(
-                    retained_positions,
-                    output_row_mapping,
-                    removed_positions,
-                ))),
-                BTreeSet::new(),
-            ))
-        }
+        AnnotatedStage::Select(select) => Ok((
+            ExecutableStage::Select(Arc::new(compile_select(stage_input_positions, &select.variables))),
+            BTreeSet::new(),
+        )),
         AnnotatedStage::Sort(sort) => Ok((
             ExecutableStage::Sort(Arc::new(SortExecutable::new(sort.variables.clone(), stage_input_positions.clone()))),
             BTreeSet::new(),
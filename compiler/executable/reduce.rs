@@ -10,7 +10,9 @@ use answer::variable::Variable;
 use encoding::value::value_type::ValueType;
 use ir::{pattern::IrID, pipeline::reduce::Reducer};
 
-use crate::{executable::next_executable_id, VariablePosition};
+use crate::{
+    annotation::expression::compiled_expression::ExpressionValueType, executable::next_executable_id, VariablePosition,
+};
 
 #[derive(Debug, Clone)]
 pub struct ReduceExecutable {
@@ -49,6 +51,7 @@ pub enum ReduceInstruction<ID: IrID> {
     MaxDate(ID), MinDate(ID),
     MaxDateTime(ID), MinDateTime(ID),
     MaxDateTimeTZ(ID), MinDateTimeTZ(ID),
+    List(ID, ValueType),
 }
 
 impl<ID: IrID> ReduceInstruction<ID> {
@@ -82,7 +85,8 @@ impl<ID: IrID> ReduceInstruction<ID> {
             | ReduceInstruction::MaxDateTime(id)
             | ReduceInstruction::MinDateTime(id)
             | ReduceInstruction::MaxDateTimeTZ(id)
-            | ReduceInstruction::MinDateTimeTZ(id) => Some(id),
+            | ReduceInstruction::MinDateTimeTZ(id)
+            | ReduceInstruction::List(id, _) => Some(id),
         }
     }
 
@@ -123,6 +127,16 @@ impl<ID: IrID> ReduceInstruction<ID> {
 
             Self::MaxDateTimeTZ(_) => ValueType::DateTimeTZ,
             Self::MinDateTimeTZ(_) => ValueType::DateTimeTZ,
+
+            // The type of the list's elements
+            Self::List(_, value_type) => value_type.clone(),
+        }
+    }
+
+    pub fn output_expression_value_type(&self) -> ExpressionValueType {
+        match self {
+            Self::List(_, value_type) => ExpressionValueType::List(value_type.clone()),
+            _ => ExpressionValueType::Single(self.output_type()),
         }
     }
 
@@ -156,6 +170,7 @@ impl<ID: IrID> ReduceInstruction<ID> {
             ReduceInstruction::MinDateTime(id) => ReduceInstruction::MinDateTime(mapping[&id]),
             ReduceInstruction::MaxDateTimeTZ(id) => ReduceInstruction::MaxDateTimeTZ(mapping[&id]),
             ReduceInstruction::MinDateTimeTZ(id) => ReduceInstruction::MinDateTimeTZ(mapping[&id]),
+            ReduceInstruction::List(id, value_type) => ReduceInstruction::List(mapping[&id], value_type),
         }
     }
 }
@@ -183,6 +198,7 @@ impl ReduceInstruction<Variable> {
             Self::MeanInteger(id) | Self::MeanDouble(id) | Self::MeanDecimal(id) => Reducer::Mean(id),
             Self::MedianInteger(id) | Self::MedianDouble(id) | Self::MedianDecimal(id) => Reducer::Median(id),
             Self::StdInteger(id) | Self::StdDouble(id) | Self::StdDecimal(id) => Reducer::Std(id),
+            Self::List(id, _) => Reducer::List(id),
         }
     }
 }
@@ -109,6 +109,7 @@ fn validate_has_updatable(
         input_annotations_variables,
         input_annotations_constraints,
         left_right,
+        true,
     )?;
 
     let input_owner_types = input_annotations_variables.get(&has.owner().as_variable().unwrap()).ok_or(
@@ -149,6 +150,7 @@ fn validate_links_updatable(
         input_annotations_variables,
         input_annotations_constraints,
         left_right_filtered,
+        true,
     )?;
 
     let input_relation_types = input_annotations_variables.get(&links.relation().as_variable().unwrap()).ok_or(
@@ -15,6 +15,7 @@ pub mod annotation;
 pub mod executable;
 pub mod query_structure;
 pub mod transformation;
+pub mod warning;
 
 macro_rules! filter_variants {
     ($variant:path : $iterable:expr) => {
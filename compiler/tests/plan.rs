@@ -0,0 +1,46 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use test_utils_compiler::{compile_match_plan, StepDescription};
+
+const SCHEMA: &str = "define
+    attribute age value integer;
+    attribute name value string;
+    entity person owns age @card(0..), owns name @card(0..);
+";
+
+#[test]
+fn test_plan_is_deterministic() {
+    let query = "match $person isa person, has name $name, has age $age;";
+    let first = compile_match_plan(SCHEMA, query);
+    let second = compile_match_plan(SCHEMA, query);
+    assert_eq!(first, second);
+    assert_eq!(
+        serde_json::to_string(&first).unwrap(),
+        serde_json::to_string(&second).unwrap(),
+        "serialised plans must be identical"
+    );
+}
+
+#[test]
+fn test_plan_describes_intersections() {
+    let plan = compile_match_plan(SCHEMA, "match $person isa person, has name $name;");
+    assert!(!plan.steps.is_empty());
+    assert!(plan.variables.values().any(|name| name == "$person"));
+    for step in &plan.steps {
+        let StepDescription::Intersection { sort_variable, instructions } = step else {
+            panic!("Expected only intersections for a simple conjunction, found {}", step.kind());
+        };
+        assert!(!instructions.is_empty());
+        assert!(["$person", "$name"].contains(&sort_variable.as_str()), "unexpected sort variable {sort_variable}");
+    }
+}
+
+#[test]
+fn test_plan_describes_negation() {
+    let plan = compile_match_plan(SCHEMA, "match $person isa person; not { $person has name $name; };");
+    assert!(plan.step_kinds().contains(&"negation"), "plan steps: {:?}", plan.step_kinds());
+}
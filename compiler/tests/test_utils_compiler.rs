@@ -0,0 +1,215 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use answer::variable::Variable;
+use compiler::{
+    annotation::{
+        expression::block_compiler::compile_expressions, function::EmptyAnnotatedFunctionSignatures,
+        match_inference::infer_types,
+    },
+    executable::{
+        function::ExecutableFunctionRegistry,
+        match_::planner::conjunction_executable::{ConjunctionExecutable, ExecutionStep},
+    },
+    ExecutorVariable,
+};
+use concept::thing::statistics::Statistics;
+use encoding::graph::definition::definition_key_generator::DefinitionKeyGenerator;
+use function::function_manager::FunctionManager;
+use ir::{
+    pipeline::{function_signature::HashMapFunctionSignatureIndex, ParameterRegistry, VariableRegistry},
+    translation::{match_::translate_match, PipelineTranslationContext},
+};
+use query::query_manager::QueryManager;
+use resource::profile::CommitProfile;
+use serde::Serialize;
+use storage::{sequence_number::SequenceNumber, snapshot::CommittableSnapshot};
+use test_utils_concept::{load_managers, setup_concept_storage};
+use test_utils_encoding::create_core_storage;
+
+/// A stable, serialisable description of a compiled match plan, intended for asserting planner output in tests.
+/// Instructions are rendered with IR variables, whose user-facing names are listed in `variables`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlanDescription {
+    pub variables: BTreeMap<String, String>,
+    pub steps: Vec<StepDescription>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StepDescription {
+    Intersection { sort_variable: String, instructions: Vec<String> },
+    UnsortedJoin { iterate: String, checks: Vec<String> },
+    Assignment { assigned: String },
+    Check { checks: Vec<String> },
+    Disjunction { branches: Vec<PlanDescription> },
+    Negation { negation: PlanDescription },
+    Optional { optional: PlanDescription },
+    FunctionCall { function_id: String },
+}
+
+impl PlanDescription {
+    pub fn step_kinds(&self) -> Vec<&'static str> {
+        self.steps.iter().map(StepDescription::kind).collect()
+    }
+}
+
+impl StepDescription {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            StepDescription::Intersection { .. } => "intersection",
+            StepDescription::UnsortedJoin { .. } => "unsorted_join",
+            StepDescription::Assignment { .. } => "assignment",
+            StepDescription::Check { .. } => "check",
+            StepDescription::Disjunction { .. } => "disjunction",
+            StepDescription::Negation { .. } => "negation",
+            StepDescription::Optional { .. } => "optional",
+            StepDescription::FunctionCall { .. } => "function_call",
+        }
+    }
+}
+
+/// Defines `schema` in a fresh database, then translates, annotates and plans the match stage of `query`.
+/// The database holds no data, so the plan only depends on the schema and the query.
+pub fn compile_match_plan(schema: &str, query: &str) -> PlanDescription {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+
+    let query_manager = QueryManager::new(None);
+    let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let define = typeql::parse_query(schema).unwrap().into_structure().into_schema();
+    query_manager
+        .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, schema)
+        .unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let mut statistics = Statistics::new(SequenceNumber::new(0));
+    statistics.may_synchronise(&storage).unwrap();
+
+    let match_ = typeql::parse_query(query).unwrap().into_structure().into_pipeline().stages.remove(0).into_match();
+    let mut translation_context = PipelineTranslationContext::new();
+    let mut value_parameters = ParameterRegistry::new();
+    let builder = translate_match(
+        &mut translation_context,
+        &mut value_parameters,
+        &HashMapFunctionSignatureIndex::empty(),
+        &match_,
+    )
+    .unwrap();
+    let block = builder.finish().unwrap();
+
+    let snapshot = storage.clone().open_snapshot_read();
+    let (type_manager, _thing_manager) = load_managers(storage.clone(), None);
+    let block_annotations = infer_types(
+        &snapshot,
+        &block,
+        &translation_context.variable_registry,
+        &type_manager,
+        &BTreeMap::new(),
+        &EmptyAnnotatedFunctionSignatures,
+        false,
+    )
+    .unwrap();
+    let compiled_expressions = compile_expressions(
+        &snapshot,
+        &type_manager,
+        &block,
+        &mut translation_context.variable_registry,
+        &value_parameters,
+        &block_annotations,
+        &mut BTreeMap::new(),
+    )
+    .unwrap();
+
+    let conjunction_executable = compiler::executable::match_::planner::compile(
+        &block,
+        &BTreeMap::new(),
+        &HashMap::new(),
+        block.conjunction().named_visible_binding_variables(block.block_context()).collect(),
+        &block_annotations,
+        &translation_context.variable_registry,
+        &compiled_expressions,
+        &statistics,
+        &ExecutableFunctionRegistry::empty(),
+    )
+    .unwrap();
+    describe_conjunction(&conjunction_executable, &translation_context.variable_registry)
+}
+
+pub fn describe_conjunction(
+    executable: &ConjunctionExecutable,
+    variable_registry: &VariableRegistry,
+) -> PlanDescription {
+    let map = executable.variable_reverse_map();
+    let variables =
+        map.values().map(|&variable| (variable.to_string(), describe_variable(variable, variable_registry))).collect();
+    let steps = executable.steps().iter().map(|step| describe_step(step, map, variable_registry)).collect();
+    PlanDescription { variables, steps }
+}
+
+fn describe_step(
+    step: &ExecutionStep,
+    map: &HashMap<ExecutorVariable, Variable>,
+    variable_registry: &VariableRegistry,
+) -> StepDescription {
+    match step {
+        ExecutionStep::Intersection(step) => StepDescription::Intersection {
+            sort_variable: describe_executor_variable(step.sort_variable, map, variable_registry),
+            instructions: step
+                .instructions
+                .iter()
+                .map(|(instruction, _)| instruction.clone().map(map).to_string())
+                .collect(),
+        },
+        ExecutionStep::UnsortedJoin(step) => StepDescription::UnsortedJoin {
+            iterate: step.iterate_instruction.clone().map(map).to_string(),
+            checks: step.check_instructions.iter().map(|check| check.clone().map(map).to_string()).collect(),
+        },
+        ExecutionStep::Assignment(step) => {
+            StepDescription::Assignment { assigned: describe_executor_variable(step.unbound, map, variable_registry) }
+        }
+        ExecutionStep::Check(step) => StepDescription::Check {
+            checks: step.check_instructions.iter().map(|check| check.clone().map(map).to_string()).collect(),
+        },
+        ExecutionStep::Disjunction(step) => StepDescription::Disjunction {
+            branches: step.branches.iter().map(|branch| describe_conjunction(branch, variable_registry)).collect(),
+        },
+        ExecutionStep::Negation(step) => {
+            StepDescription::Negation { negation: describe_conjunction(&step.negation, variable_registry) }
+        }
+        ExecutionStep::Optional(step) => {
+            StepDescription::Optional { optional: describe_conjunction(&step.optional, variable_registry) }
+        }
+        ExecutionStep::FunctionCall(step) => {
+            StepDescription::FunctionCall { function_id: step.function_id.to_string() }
+        }
+    }
+}
+
+fn describe_executor_variable(
+    variable: ExecutorVariable,
+    map: &HashMap<ExecutorVariable, Variable>,
+    variable_registry: &VariableRegistry,
+) -> String {
+    match map.get(&variable) {
+        Some(&variable) => describe_variable(variable, variable_registry),
+        None => variable.to_string(),
+    }
+}
+
+fn describe_variable(variable: Variable, variable_registry: &VariableRegistry) -> String {
+    match variable_registry.get_variable_name(variable) {
+        Some(name) => format!("${name}"),
+        None => variable.to_string(),
+    }
+}
@@ -505,6 +505,9 @@ fn run_test_optimise_away(snapshot: impl ReadableSnapshot, type_manager: &TypeMa
     {
         let query = "match $p sub person, plays dog-ownership:dog;";
         let (mut conjunction, type_annotations) = translate_and_annotate(&snapshot, type_manager, query);
+        let unsatisfiable = type_annotations.type_annotations_of(&conjunction).unwrap().unsatisfiable_constraint();
+        assert!(matches!(unsatisfiable.unwrap().constraint(), Constraint::Plays(_)));
+        assert!(!unsatisfiable.unwrap().left_types().is_empty() && !unsatisfiable.unwrap().right_types().is_empty());
         optimize_away_statically_unsatisfiable_conjunctions(&mut conjunction, &type_annotations);
         assert!(matches!(conjunction.constraints().iter().exactly_one().unwrap(), Constraint::Unsatisfiable(_)));
     }
@@ -512,6 +515,13 @@ fn run_test_optimise_away(snapshot: impl ReadableSnapshot, type_manager: &TypeMa
     {
         let query = "match $p sub person; { $p plays dog-ownership:dog; } or { $p plays dog-ownership:owner; };";
         let (mut conjunction, type_annotations) = translate_and_annotate(&snapshot, type_manager, query);
+        assert!(type_annotations.type_annotations_of(&conjunction).unwrap().unsatisfiable_constraint().is_none());
+        let [dog_branch, owner_branch] = conjunction.nested_patterns()[0].as_disjunction().unwrap().conjunctions()
+        else {
+            unreachable!()
+        };
+        assert!(type_annotations.type_annotations_of(dog_branch).unwrap().unsatisfiable_constraint().is_some());
+        assert!(type_annotations.type_annotations_of(owner_branch).unwrap().unsatisfiable_constraint().is_none());
         optimize_away_statically_unsatisfiable_conjunctions(&mut conjunction, &type_annotations);
         assert!(matches!(conjunction.constraints().iter().exactly_one().unwrap(), Constraint::Sub(_)));
         let must_be_plays = conjunction
@@ -4,6 +4,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 use concept::type_::type_manager::TypeManager;
+use ir::pipeline::VariableRegistry;
 use storage::snapshot::ReadableSnapshot;
 
 use crate::{
@@ -15,21 +16,40 @@ use crate::{
         relation_index::relation_index_transformation,
         StaticOptimiserError,
     },
+    warning::{count_disjunction_branches, unsatisfiable_constraint_warnings, QueryWarning},
 };
 
 pub fn apply_transformations(
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
+    variable_registry: &VariableRegistry,
     pipeline: &mut AnnotatedPipeline,
-) -> Result<(), StaticOptimiserError> {
+) -> Result<Vec<QueryWarning>, StaticOptimiserError> {
+    let mut pruned_branches = 0;
+    let mut unsatisfiable_constraints = Vec::new();
     for stage in &mut pipeline.annotated_stages {
         if let AnnotatedStage::Match { block, block_annotations, .. } = stage {
+            // The pruned branches are gone afterwards, so their causes have to be collected first
+            unsatisfiable_constraints.extend(unsatisfiable_constraint_warnings(
+                snapshot,
+                type_manager,
+                variable_registry,
+                block.conjunction(),
+                block_annotations,
+            ));
+            let branches = count_disjunction_branches(block.conjunction());
             optimize_away_statically_unsatisfiable_conjunctions(block.conjunction_mut(), block_annotations);
+            pruned_branches += branches - count_disjunction_branches(block.conjunction());
             prune_redundant_roleplayer_deduplication(block.conjunction_mut(), block_annotations);
             relation_index_transformation(block.conjunction_mut(), block_annotations, type_manager, snapshot)?;
         }
     }
-    Ok(())
+    let warnings = (pruned_branches > 0)
+        .then_some(QueryWarning::UnsatisfiableBranchesPruned { count: pruned_branches })
+        .into_iter()
+        .chain(unsatisfiable_constraints)
+        .collect();
+    Ok(warnings)
 
     // Ideas:
     // - we should move subtrees/graphs of a query that have no returned variables into a new pattern: "Check", which are only checked for a single answer
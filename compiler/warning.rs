@@ -0,0 +1,375 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{collections::HashSet, fmt};
+
+use answer::variable::Variable;
+use concept::type_::type_manager::TypeManager;
+use ir::{
+    pattern::{
+        conjunction::Conjunction,
+        constraint::{Comparator, Constraint},
+        nested_pattern::NestedPattern,
+        Vertex,
+    },
+    pipeline::{ParameterRegistry, VariableRegistry},
+};
+use itertools::Itertools;
+use storage::snapshot::ReadableSnapshot;
+
+use crate::annotation::{
+    expression::instructions::op_codes::ExpressionOpCode,
+    pipeline::AnnotatedStage,
+    type_annotations::{BlockAnnotations, UnsatisfiableConstraint},
+};
+
+/// Hints about a query found while compiling or executing it, which do not stop it from running.
+/// Warnings are returned alongside the answers, so that drivers can surface them to users.
+/// Unused variables and unbounded scans are only reported when linting a query, as they are often intended.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum QueryWarning {
+    UnsatisfiableBranchesPruned {
+        count: usize,
+    },
+    ImplicitCastsApplied {
+        count: usize,
+        lossy_count: usize,
+    },
+    UnsatisfiableConstraint {
+        constraint_type: String,
+        left_variable: String,
+        right_variable: String,
+        left_types: String,
+        right_types: String,
+    },
+    UnusedVariable {
+        variable: String,
+    },
+    UnboundedScan,
+    WritesSkipped {
+        count: usize,
+    },
+    IncomparableComparison {
+        comparison: String,
+        lhs_value_types: String,
+        rhs_value_types: String,
+    },
+}
+
+impl QueryWarning {
+    const PREFIX: &'static str = "QWN";
+
+    pub fn code(&self) -> String {
+        let number = match self {
+            QueryWarning::UnsatisfiableBranchesPruned { .. } => 1,
+            QueryWarning::ImplicitCastsApplied { .. } => 2,
+            QueryWarning::UnsatisfiableConstraint { .. } => 3,
+            QueryWarning::UnusedVariable { .. } => 4,
+            QueryWarning::UnboundedScan => 5,
+            QueryWarning::WritesSkipped { .. } => 6,
+            QueryWarning::IncomparableComparison { .. } => 7,
+        };
+        format!("{}{}", Self::PREFIX, number)
+    }
+}
+
+impl fmt::Display for QueryWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryWarning::UnsatisfiableBranchesPruned { count } => write!(
+                f,
+                "{count} disjunction branch(es) can never match given the schema, and were removed from the query."
+            ),
+            QueryWarning::ImplicitCastsApplied { count, lossy_count: 0 } => write!(
+                f,
+                "{count} expression(s) or comparison(s) implicitly cast their operands to a wider value type."
+            ),
+            QueryWarning::ImplicitCastsApplied { count, lossy_count } => write!(
+                f,
+                "{count} expression(s) or comparison(s) implicitly cast their operands to a wider value type. {lossy_count} of them cast to 'double', which may lose precision."
+            ),
+            QueryWarning::UnsatisfiableConstraint {
+                constraint_type,
+                left_variable,
+                right_variable,
+                left_types,
+                right_types,
+            } => write!(
+                f,
+                "No compatible types were found for '{left_variable}' & '{right_variable}' across a '{constraint_type}' constraint, so the pattern containing it can never match. Types were:\n- {left_variable}: [{left_types}]\n- {right_variable}: [{right_types}]"
+            ),
+            QueryWarning::UnusedVariable { variable } => write!(
+                f,
+                "Variable '{variable}' is only used once and is not returned, so it can be replaced by an anonymous variable."
+            ),
+            QueryWarning::UnboundedScan => write!(
+                f,
+                "The first match stage reads instances without an 'iid' or an equality against a value, and is not followed by a limit, so it scans every instance of its types."
+            ),
+            QueryWarning::WritesSkipped { count } => write!(
+                f,
+                "{count} write instruction(s) were skipped because they use variables that optional patterns left empty."
+            ),
+            QueryWarning::IncomparableComparison { comparison, lhs_value_types, rhs_value_types } => write!(
+                f,
+                "The comparison '{comparison}' can never be satisfied, since none of the value types of its operands can be compared, so the pattern containing it can never match. Value types were:\n- left: [{lhs_value_types}]\n- right: [{rhs_value_types}]"
+            ),
+        }
+    }
+}
+
+pub(crate) fn count_disjunction_branches(conjunction: &Conjunction) -> usize {
+    conjunction
+        .nested_patterns()
+        .iter()
+        .map(|nested| match nested {
+            NestedPattern::Disjunction(disjunction) => {
+                disjunction.conjunctions().iter().map(|branch| 1 + count_disjunction_branches(branch)).sum()
+            }
+            NestedPattern::Negation(negation) => count_disjunction_branches(negation.conjunction()),
+            NestedPattern::Optional(optional) => count_disjunction_branches(optional.conjunction()),
+        })
+        .sum()
+}
+
+pub(crate) fn unsatisfiable_constraint_warnings(
+    snapshot: &impl ReadableSnapshot,
+    type_manager: &TypeManager,
+    variable_registry: &VariableRegistry,
+    conjunction: &Conjunction,
+    block_annotations: &BlockAnnotations,
+) -> Vec<QueryWarning> {
+    let resolve_vertex = |vertex: &Vertex<Variable>| match vertex {
+        Vertex::Variable(v) => variable_registry.get_variable_name_or_unnamed(*v).to_owned(),
+        Vertex::Label(label) => label.scoped_name().as_str().to_string(),
+        Vertex::Parameter(_) => unreachable!("Parameters can't be involved in TypeInferenceEdges"),
+    };
+    let resolve_type_label = |type_: &answer::Type| {
+        type_
+            .get_label(snapshot, type_manager)
+            .map(|label| label.scoped_name().to_string())
+            .unwrap_or("(Error while resolving label)".to_owned())
+    };
+    let mut warnings = Vec::new();
+    collect_unsatisfiable_constraints(conjunction, block_annotations, &mut |unsatisfiable| {
+        warnings.push(QueryWarning::UnsatisfiableConstraint {
+            constraint_type: unsatisfiable.constraint().name().to_owned(),
+            left_variable: resolve_vertex(unsatisfiable.left()),
+            right_variable: resolve_vertex(unsatisfiable.right()),
+            left_types: unsatisfiable.left_types().iter().map(resolve_type_label).join(", "),
+            right_types: unsatisfiable.right_types().iter().map(resolve_type_label).join(", "),
+        })
+    });
+    warnings
+}
+
+fn collect_unsatisfiable_constraints(
+    conjunction: &Conjunction,
+    block_annotations: &BlockAnnotations,
+    on_unsatisfiable: &mut impl FnMut(&UnsatisfiableConstraint),
+) {
+    if let Some(unsatisfiable) = block_annotations
+        .type_annotations_of(conjunction)
+        .and_then(|annotations| annotations.unsatisfiable_constraint())
+    {
+        on_unsatisfiable(unsatisfiable);
+    }
+    conjunction.nested_patterns().iter().for_each(|nested| match nested {
+        NestedPattern::Disjunction(disjunction) => disjunction
+            .conjunctions()
+            .iter()
+            .for_each(|branch| collect_unsatisfiable_constraints(branch, block_annotations, on_unsatisfiable)),
+        NestedPattern::Negation(negation) => {
+            collect_unsatisfiable_constraints(negation.conjunction(), block_annotations, on_unsatisfiable)
+        }
+        NestedPattern::Optional(optional) => {
+            collect_unsatisfiable_constraints(optional.conjunction(), block_annotations, on_unsatisfiable)
+        }
+    })
+}
+
+pub fn implicit_cast_warning(stages: &[AnnotatedStage]) -> Option<QueryWarning> {
+    let (count, lossy_count) = stages
+        .iter()
+        .filter_map(|stage| match stage {
+            AnnotatedStage::Match { executable_expressions, block_annotations, .. } => {
+                Some((executable_expressions, block_annotations))
+            }
+            _ => None,
+        })
+        .fold((0, 0), |(count, lossy_count), (expressions, block_annotations)| {
+            let comparisons = block_annotations
+                .type_annotations()
+                .values()
+                .flat_map(|annotations| annotations.comparison_value_types().values());
+            let expression_instructions = expressions.values().map(|expression| expression.instructions());
+            let casting_expressions = expression_instructions.clone().filter(|ops| ops.iter().any(is_cast)).count();
+            let lossy_expressions = expression_instructions.filter(|ops| ops.iter().any(is_lossy_cast)).count();
+            let casting_comparisons =
+                comparisons.clone().filter(|value_types| value_types.has_implicit_casts()).count();
+            let lossy_comparisons = comparisons.filter(|value_types| value_types.has_lossy_implicit_casts()).count();
+            (count + casting_expressions + casting_comparisons, lossy_count + lossy_expressions + lossy_comparisons)
+        });
+    (count > 0).then_some(QueryWarning::ImplicitCastsApplied { count, lossy_count })
+}
+
+pub fn incomparable_comparison_warnings(
+    variable_registry: &VariableRegistry,
+    parameters: &ParameterRegistry,
+    stages: &[AnnotatedStage],
+) -> Vec<QueryWarning> {
+    let operand_name = |operand: &Vertex<Variable>| match operand {
+        Vertex::Variable(variable) => variable_registry.get_variable_name_or_unnamed(*variable).to_owned(),
+        Vertex::Parameter(parameter) => {
+            parameters.value(parameter).map(|value| value.to_string()).unwrap_or_else(|| parameter.to_string())
+        }
+        Vertex::Label(label) => label.scoped_name().as_str().to_owned(),
+    };
+    let mut warnings = Vec::new();
+    for stage in stages {
+        let AnnotatedStage::Match { block_annotations, .. } = stage else { continue };
+        for annotations in block_annotations.type_annotations().values() {
+            let incomparable = annotations.comparison_value_types().iter().filter(|(_, types)| !types.is_comparable());
+            for (comparison, value_types) in incomparable {
+                let (lhs_value_types, rhs_value_types) = value_types.operand_value_types();
+                warnings.push(QueryWarning::IncomparableComparison {
+                    comparison: format!(
+                        "{} {} {}",
+                        operand_name(comparison.lhs()),
+                        comparison.comparator().name(),
+                        operand_name(comparison.rhs())
+                    ),
+                    lhs_value_types: lhs_value_types.iter().map(|value_type| value_type.name()).sorted().join(", "),
+                    rhs_value_types: rhs_value_types.iter().map(|value_type| value_type.name()).sorted().join(", "),
+                });
+            }
+        }
+    }
+    warnings
+}
+
+pub fn lint_warnings(
+    variable_registry: &VariableRegistry,
+    stages: &[AnnotatedStage],
+    fetch_variables: Option<HashSet<Variable>>,
+) -> Vec<QueryWarning> {
+    let mut warnings = unused_variable_warnings(variable_registry, stages, fetch_variables);
+    warnings.extend(unbounded_scan_warning(stages));
+    warnings
+}
+
+fn unused_variable_warnings(
+    variable_registry: &VariableRegistry,
+    stages: &[AnnotatedStage],
+    fetch_variables: Option<HashSet<Variable>>,
+) -> Vec<QueryWarning> {
+    let mut references = Vec::new();
+    let mut outputs = HashSet::new();
+    for stage in stages {
+        match stage {
+            AnnotatedStage::Match { block, .. }
+            | AnnotatedStage::Insert { block, .. }
+            | AnnotatedStage::Update { block, .. }
+            | AnnotatedStage::Put { block, .. } => {
+                collect_constraint_references(block.conjunction(), &mut references);
+                outputs.extend(block.variables());
+            }
+            AnnotatedStage::Delete { block, deleted_variables, .. } => {
+                collect_constraint_references(block.conjunction(), &mut references);
+                references.extend(deleted_variables.iter().copied());
+                deleted_variables.iter().for_each(|variable| {
+                    outputs.remove(variable);
+                });
+            }
+            AnnotatedStage::Select(select) => {
+                references.extend(select.variables.iter().copied());
+                outputs.retain(|variable| select.variables.contains(variable));
+            }
+            AnnotatedStage::Sort(sort) => {
+                references.extend(sort.variables.iter().map(|sort_variable| sort_variable.variable()))
+            }
+            AnnotatedStage::Require(require) => references.extend(require.variables.iter().copied()),
+            AnnotatedStage::Reduce(reduce, _) => {
+                references.extend(reduce.variables());
+                references
+                    .extend(reduce.assigned_reductions.iter().filter_map(|assigned| assigned.reduction.variable()));
+                outputs = reduce.variables().collect();
+            }
+            AnnotatedStage::Offset(_) | AnnotatedStage::Limit(_) | AnnotatedStage::Distinct(_) => {}
+        }
+    }
+    // Returned variables are used by the caller
+    references.extend(fetch_variables.unwrap_or(outputs));
+    references
+        .into_iter()
+        .counts()
+        .into_iter()
+        .filter(|(_, count)| *count == 1)
+        .filter_map(|(variable, _)| variable_registry.get_variable_name(variable))
+        .sorted()
+        .map(|variable| QueryWarning::UnusedVariable { variable: variable.clone() })
+        .collect()
+}
+
+fn collect_constraint_references(conjunction: &Conjunction, references: &mut Vec<Variable>) {
+    conjunction.constraints().iter().for_each(|constraint| references.extend(constraint.ids().unique()));
+    conjunction.nested_patterns().iter().for_each(|nested| match nested {
+        NestedPattern::Disjunction(disjunction) => {
+            disjunction.conjunctions().iter().for_each(|branch| collect_constraint_references(branch, references))
+        }
+        NestedPattern::Negation(negation) => collect_constraint_references(negation.conjunction(), references),
+        NestedPattern::Optional(optional) => collect_constraint_references(optional.conjunction(), references),
+    })
+}
+
+fn unbounded_scan_warning(stages: &[AnnotatedStage]) -> Option<QueryWarning> {
+    // Later match stages are bounded by the rows flowing into them
+    let Some(AnnotatedStage::Match { block, .. }) = stages.first() else { return None };
+    let constraints = block.conjunction().constraints();
+    let reads_instances = constraints.iter().any(|constraint| {
+        matches!(
+            constraint,
+            Constraint::Isa(_) | Constraint::Has(_) | Constraint::Links(_) | Constraint::IndexedRelation(_)
+        )
+    });
+    let is_anchored = constraints.iter().any(|constraint| match constraint {
+        Constraint::Iid(_) => true,
+        Constraint::Comparison(comparison) => {
+            comparison.comparator() == Comparator::Equal
+                && (matches!(comparison.lhs(), Vertex::Parameter(_))
+                    || matches!(comparison.rhs(), Vertex::Parameter(_)))
+        }
+        _ => false,
+    });
+    let is_limited = stages.iter().any(|stage| matches!(stage, AnnotatedStage::Limit(_)));
+    (reads_instances && !is_anchored && !is_limited).then_some(QueryWarning::UnboundedScan)
+}
+
+fn is_cast(op_code: &ExpressionOpCode) -> bool {
+    matches!(
+        op_code,
+        ExpressionOpCode::CastUnaryIntegerToDouble
+            | ExpressionOpCode::CastLeftIntegerToDouble
+            | ExpressionOpCode::CastRightIntegerToDouble
+            | ExpressionOpCode::CastUnaryIntegerToDecimal
+            | ExpressionOpCode::CastLeftIntegerToDecimal
+            | ExpressionOpCode::CastRightIntegerToDecimal
+            | ExpressionOpCode::CastUnaryDecimalToDouble
+            | ExpressionOpCode::CastLeftDecimalToDouble
+            | ExpressionOpCode::CastRightDecimalToDouble
+    )
+}
+
+/// Casts to double may round integers beyond 2^53 and most decimals, while casts from integer to decimal are exact.
+fn is_lossy_cast(op_code: &ExpressionOpCode) -> bool {
+    is_cast(op_code)
+        && !matches!(
+            op_code,
+            ExpressionOpCode::CastUnaryIntegerToDecimal
+                | ExpressionOpCode::CastLeftIntegerToDecimal
+                | ExpressionOpCode::CastRightIntegerToDecimal
+        )
+}
@@ -37,6 +37,8 @@ typedb_error!(
         UnsetHasOrderedOwnsUnordered(10, "Concept write failed, cannot unset an ordered owns when the ownership is unordered."),
         UnsetHasUnorderedOwnsOrdered(11, "Concept write failed, cannot unset an unordered owns when the ownership is ordered"),
         SetPlayersOrderedRoleUnordered(12, "Concept write failed, cannot set relation's ordered role players as unordered."),
+        ProvidedIIDTypeMismatch(13, "Concept write failed, cannot create an instance of type '{type_label}' with the IID of an instance of another type.", type_label: Label),
+        ProvidedIIDInUse(14, "Concept write failed, cannot create an instance of type '{type_label}' with an IID that is already in use.", type_label: Label),
     }
 );
 
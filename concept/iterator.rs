@@ -97,6 +97,37 @@ where
     }
 }
 
+/// Iterates the instances in descending order, the reverse of `InstanceIterator`
+pub struct ReverseInstanceIterator<T> {
+    snapshot_iterator: Option<storage::snapshot::iterator::SnapshotReverseRangeIterator>,
+    _ph: PhantomData<T>,
+}
+
+impl<T> ReverseInstanceIterator<T> {
+    pub(crate) fn new(snapshot_iterator: storage::snapshot::iterator::SnapshotReverseRangeIterator) -> Self {
+        Self { snapshot_iterator: Some(snapshot_iterator), _ph: PhantomData }
+    }
+
+    pub(crate) fn empty() -> Self {
+        Self { snapshot_iterator: None, _ph: PhantomData }
+    }
+}
+
+impl<T> Iterator for ReverseInstanceIterator<T>
+where
+    T: ThingAPI + 'static,
+{
+    type Item = Result<T, Box<ConceptReadError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = match self.snapshot_iterator.as_mut()?.next()? {
+            Ok((storage_key, _)) => Ok(T::new(T::Vertex::decode(storage_key.bytes()))),
+            Err(err) => Err(Box::new(SnapshotIterate { source: err })),
+        };
+        Some(item)
+    }
+}
+
 #[macro_export]
 macro_rules! concept_iterator {
     ($name:ident, $concept_type:ident, $map_fn: expr) => {
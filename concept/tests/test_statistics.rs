@@ -9,7 +9,10 @@
 use std::{collections::BTreeMap, sync::Arc};
 
 use concept::{
-    thing::{object::ObjectAPI, statistics::Statistics, thing_manager::ThingManager, ThingAPI},
+    thing::{
+        object::ObjectAPI, statistics::Statistics, statistics_sampler::StatisticsSampler, thing_manager::ThingManager,
+        ThingAPI,
+    },
     type_::{
         annotation::{AnnotationCardinality, AnnotationIndependent},
         attribute_type::AttributeTypeAnnotation,
@@ -362,3 +365,55 @@ fn put_plays() {
 
     assert_statistics_eq!(synchronised, read_statistics(storage, &thing_manager));
 }
+
+#[test]
+fn sample_corrects_drifted_counts() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+
+    let person_label = Label::build("person", None);
+    let name_label = Label::build("name", None);
+
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let person_type = type_manager.create_entity_type(&mut snapshot, &person_label).unwrap();
+    let name_type = type_manager.create_attribute_type(&mut snapshot, &name_label).unwrap();
+    name_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::String).unwrap();
+    person_type
+        .set_owns(
+            &mut snapshot,
+            &type_manager,
+            &thing_manager,
+            name_type,
+            Ordering::Unordered,
+            StorageCounters::DISABLED,
+        )
+        .unwrap();
+    let person = thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+    let name = thing_manager.create_attribute(&mut snapshot, name_type, Value::String("alice".into())).unwrap();
+    person.set_has_unordered(&mut snapshot, &thing_manager, &name, StorageCounters::DISABLED).unwrap();
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap().unwrap();
+
+    let mut synchronised = Statistics::new(SequenceNumber::MIN);
+    synchronised.may_synchronise(&storage).unwrap();
+
+    let mut drifted = synchronised.clone();
+    *drifted.entity_counts.get_mut(&person_type).unwrap() += 3;
+    drifted.total_entity_count += 3;
+    drifted.total_thing_count += 3;
+    *drifted.has_attribute_counts.get_mut(&person_type.into_object_type()).unwrap().get_mut(&name_type).unwrap() += 2;
+    *drifted.attribute_owner_counts.get_mut(&name_type).unwrap().get_mut(&person_type.into_object_type()).unwrap() += 2;
+    drifted.total_has_count += 2;
+
+    let snapshot = storage.clone().open_snapshot_read_at(drifted.sequence_number);
+    let mut sampler = StatisticsSampler::new();
+    let sample = sampler.sample(&mut drifted, &snapshot, &thing_manager, 8).unwrap();
+    assert!(sample.corrected());
+    assert_eq!(sample.types.len(), 2);
+
+    let sample = sampler.sample(&mut drifted, &snapshot, &thing_manager, 8).unwrap();
+    assert!(!sample.corrected());
+
+    assert_statistics_eq!(drifted, read_statistics(storage, &thing_manager));
+}
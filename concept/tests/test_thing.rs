@@ -6,10 +6,16 @@
 
 #![deny(unused_must_use)]
 
-use std::{borrow::Cow, collections::HashMap, ops::Bound};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    ops::Bound,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
+use chrono::NaiveDate;
 use concept::{
-    error::ConceptReadError,
+    error::{ConceptReadError, ConceptWriteError},
     thing::{
         attribute::Attribute,
         entity::Entity,
@@ -18,19 +24,30 @@ use concept::{
         ThingAPI,
     },
     type_::{
-        annotation::{AnnotationCardinality, AnnotationDistinct, AnnotationIndependent, AnnotationUnique},
+        annotation::{
+            AnnotationCardinality, AnnotationDistinct, AnnotationIndependent, AnnotationRange, AnnotationUnique,
+            AnnotationValues,
+        },
         attribute_type::AttributeTypeAnnotation,
         object_type::ObjectType,
         owns::OwnsAnnotation,
         relates::RelatesAnnotation,
         type_manager::TypeManager,
-        Ordering, OwnerAPI, PlayerAPI,
+        ObjectTypeAPI, Ordering, OwnerAPI, PlayerAPI, RelationIndexThreshold, TimeToLive, TypeAPI,
     },
 };
 use encoding::{
     error::EncodingError,
-    graph::definition::definition_key::DefinitionKey,
+    graph::{
+        definition::definition_key::DefinitionKey,
+        thing::{
+            edge::ThingEdgeIndexedRelation,
+            vertex_object::{ObjectID, ObjectVertex},
+        },
+        Typed,
+    },
     value::{
+        decimal_value::Decimal,
         label::Label,
         value::Value,
         value_struct::StructValue,
@@ -38,9 +55,13 @@ use encoding::{
     },
 };
 use itertools::Itertools;
-use resource::profile::{CommitProfile, StorageCounters};
+use resource::{
+    constants::concept::COMMIT_VALIDATION_SHARD_SIZE_MIN,
+    profile::{CommitProfile, StorageCounters},
+};
 use storage::{
     durability_client::WALClient,
+    key_range::KeyRange,
     snapshot::{CommittableSnapshot, ReadSnapshot, ReadableSnapshot, SchemaSnapshot, WritableSnapshot, WriteSnapshot},
 };
 use test_utils_concept::{load_managers, setup_concept_storage};
@@ -76,6 +97,299 @@ fn thing_create_iterate() {
     }
 }
 
+#[test]
+fn thing_iterators_resume_after_vertex() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let person_type = type_manager.create_entity_type(&mut snapshot, &Label::build("person", None)).unwrap();
+    let name_type = type_manager.create_attribute_type(&mut snapshot, &Label::build("name", None)).unwrap();
+    name_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::String).unwrap();
+    person_type
+        .set_owns(
+            &mut snapshot,
+            &type_manager,
+            &thing_manager,
+            name_type,
+            Ordering::Unordered,
+            StorageCounters::DISABLED,
+        )
+        .unwrap();
+    let person = thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+    for _ in 0..2 {
+        thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+    }
+    for name in ["alice", "bob", "charlie"] {
+        let name = thing_manager
+            .create_attribute(&mut snapshot, name_type, Value::String(Cow::Owned(name.to_owned())))
+            .unwrap();
+        person.set_has_unordered(&mut snapshot, &thing_manager, &name, StorageCounters::DISABLED).unwrap();
+    }
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let snapshot: ReadSnapshot<WALClient> = storage.clone().open_snapshot_read();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    let person_type = person_type.into_object_type();
+
+    let objects: Vec<Object> =
+        thing_manager.get_objects_in(&snapshot, person_type, StorageCounters::DISABLED).try_collect().unwrap();
+    assert_eq!(objects.len(), 3);
+    let objects_after: Vec<Object> = thing_manager
+        .get_objects_in_after(&snapshot, person_type, objects[0], StorageCounters::DISABLED)
+        .try_collect()
+        .unwrap();
+    assert_eq!(objects_after, objects[1..]);
+
+    let attributes: Vec<Attribute> = thing_manager
+        .get_attributes_in(&snapshot, name_type, StorageCounters::DISABLED)
+        .unwrap()
+        .try_collect()
+        .unwrap();
+    assert_eq!(attributes.len(), 3);
+    let attributes_after: Vec<Attribute> = thing_manager
+        .get_attributes_in_after(&snapshot, name_type, &attributes[1], StorageCounters::DISABLED)
+        .unwrap()
+        .try_collect()
+        .unwrap();
+    assert_eq!(attributes_after, attributes[2..]);
+
+    let owned: Vec<Attribute> = person
+        .get_has_unordered(&snapshot, &thing_manager, StorageCounters::DISABLED)
+        .unwrap()
+        .map_ok(|(has, _)| has.attribute())
+        .try_collect()
+        .unwrap();
+    assert_eq!(owned, attributes);
+    let owned_after: Vec<Attribute> = thing_manager
+        .get_has_from_thing_unordered_after(&snapshot, person, &owned[0], StorageCounters::DISABLED)
+        .map_ok(|(has, _)| has.attribute())
+        .try_collect()
+        .unwrap();
+    assert_eq!(owned_after, owned[1..]);
+}
+
+#[test]
+fn objects_iterate_in_reverse_including_buffered_writes() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let person_type = type_manager.create_entity_type(&mut snapshot, &Label::build("person", None)).unwrap();
+    for _ in 0..3 {
+        thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+    }
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let mut snapshot: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+    let person_type = person_type.into_object_type();
+
+    let objects: Vec<Object> =
+        thing_manager.get_objects_in(&snapshot, person_type, StorageCounters::DISABLED).try_collect().unwrap();
+    assert_eq!(objects.len(), 4);
+    let objects_reversed: Vec<Object> =
+        thing_manager.get_objects_in_reverse(&snapshot, person_type, StorageCounters::DISABLED).try_collect().unwrap();
+    assert_eq!(objects_reversed, objects.into_iter().rev().collect_vec());
+}
+
+#[test]
+fn object_create_with_iid() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let person_type = type_manager.create_entity_type(&mut snapshot, &Label::build("person", None)).unwrap();
+    let company_type = type_manager.create_entity_type(&mut snapshot, &Label::build("company", None)).unwrap();
+
+    let vertex = ObjectVertex::build_entity(person_type.vertex().type_id_(), ObjectID::new(10));
+    let person = thing_manager.create_entity_with_iid(&mut snapshot, person_type, vertex).unwrap();
+    assert_eq!(person.vertex(), vertex);
+    let generated = thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+    assert_eq!(generated.vertex().object_id(), ObjectID::new(11));
+
+    let in_use = thing_manager.create_entity_with_iid(&mut snapshot, person_type, vertex).unwrap_err();
+    assert!(matches!(*in_use, ConceptWriteError::ProvidedIIDInUse { .. }), "{in_use:?}");
+    let other_type = ObjectVertex::build_entity(person_type.vertex().type_id_(), ObjectID::new(20));
+    let mismatch = thing_manager.create_entity_with_iid(&mut snapshot, company_type, other_type).unwrap_err();
+    assert!(matches!(*mismatch, ConceptWriteError::ProvidedIIDTypeMismatch { .. }), "{mismatch:?}");
+
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let snapshot: ReadSnapshot<WALClient> = storage.clone().open_snapshot_read();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    let entities: Vec<Entity> = thing_manager.get_entities(&snapshot, StorageCounters::DISABLED).try_collect().unwrap();
+    assert_eq!(entities, vec![person, generated]);
+    drop(snapshot);
+
+    // a provided IID that a concurrent transaction has just generated conflicts with it
+    let mut generating_snapshot: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+    let mut providing_snapshot: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+    let concurrent = thing_manager.create_entity(&mut generating_snapshot, person_type).unwrap();
+    thing_manager.create_entity_with_iid(&mut providing_snapshot, person_type, concurrent.vertex()).unwrap();
+    thing_manager.finalise(&mut generating_snapshot, StorageCounters::DISABLED).unwrap();
+    generating_snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+    thing_manager.finalise(&mut providing_snapshot, StorageCounters::DISABLED).unwrap();
+    assert!(providing_snapshot.commit(&mut CommitProfile::DISABLED).is_err());
+}
+
+#[test]
+fn objects_inserted_by_snapshot() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let person_type = type_manager.create_entity_type(&mut snapshot, &Label::build("person", None)).unwrap();
+    thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let mut snapshot: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    let person_object_type = person_type.into_object_type();
+    assert!(thing_manager.get_objects_inserted(&snapshot, person_object_type).is_empty());
+    let inserted: Vec<Object> = (0..2)
+        .map(|_| Object::Entity(thing_manager.create_entity(&mut snapshot, person_type).unwrap()))
+        .sorted()
+        .collect();
+    let found = thing_manager.get_objects_inserted(&snapshot, person_object_type);
+    assert_eq!(found.into_iter().sorted().collect_vec(), inserted);
+}
+
+#[test]
+fn expired_objects_are_deleted() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let session_type = type_manager.create_entity_type(&mut snapshot, &Label::build("session", None)).unwrap();
+    let guest_session_type =
+        type_manager.create_entity_type(&mut snapshot, &Label::build("guest-session", None)).unwrap();
+    guest_session_type.set_supertype(&mut snapshot, &type_manager, &thing_manager, session_type).unwrap();
+    let person_type = type_manager.create_entity_type(&mut snapshot, &Label::build("person", None)).unwrap();
+    type_manager.set_object_type_time_to_live(&mut snapshot, session_type.into_object_type(), TimeToLive(0)).unwrap();
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let mut snapshot: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    assert_eq!(
+        type_manager.get_object_type_time_to_live(&snapshot, guest_session_type.into_object_type()).unwrap(),
+        Some(TimeToLive(0))
+    );
+    assert_eq!(type_manager.get_object_type_time_to_live(&snapshot, person_type.into_object_type()).unwrap(), None);
+    let session = thing_manager.create_entity(&mut snapshot, session_type).unwrap();
+    let guest_session = thing_manager.create_entity(&mut snapshot, guest_session_type).unwrap();
+    let person = thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+    assert!(thing_manager.get_creation_time(&snapshot, guest_session, StorageCounters::DISABLED).unwrap().is_some());
+    assert!(thing_manager.get_creation_time(&snapshot, person, StorageCounters::DISABLED).unwrap().is_none());
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let mut snapshot: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    let mut retained = Vec::new();
+    let deleted = thing_manager
+        .delete_expired_objects(&mut snapshot, UNIX_EPOCH, |_, _| (), &mut retained, StorageCounters::DISABLED)
+        .unwrap();
+    assert_eq!(deleted, 0);
+    let deleted = thing_manager
+        .delete_expired_objects(&mut snapshot, SystemTime::now(), |_, _| (), &mut retained, StorageCounters::DISABLED)
+        .unwrap();
+    assert_eq!(deleted, 2);
+    assert!(retained.is_empty());
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let snapshot: ReadSnapshot<WALClient> = storage.clone().open_snapshot_read();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    let remaining: Vec<Entity> =
+        thing_manager.get_entities(&snapshot, StorageCounters::DISABLED).try_collect().unwrap();
+    assert_eq!(remaining, vec![person]);
+    assert!(!remaining.contains(&session));
+    assert!(thing_manager.get_creation_time(&snapshot, session, StorageCounters::DISABLED).unwrap().is_none());
+}
+
+#[test]
+fn expired_objects_violating_cardinality_are_kept() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let session_type = type_manager.create_entity_type(&mut snapshot, &Label::build("session", None)).unwrap();
+    type_manager.set_object_type_time_to_live(&mut snapshot, session_type.into_object_type(), TimeToLive(0)).unwrap();
+    let login_type = type_manager.create_relation_type(&mut snapshot, &Label::build("login", None)).unwrap();
+    let session_relates = login_type
+        .create_relates(
+            &mut snapshot,
+            &type_manager,
+            &thing_manager,
+            "session",
+            Ordering::Unordered,
+            StorageCounters::DISABLED,
+        )
+        .unwrap();
+    session_relates
+        .set_annotation(
+            &mut snapshot,
+            &type_manager,
+            &thing_manager,
+            RelatesAnnotation::Cardinality(AnnotationCardinality::new(1, Some(1))),
+        )
+        .unwrap();
+    let session_role = session_relates.role();
+    session_type
+        .set_plays(&mut snapshot, &type_manager, &thing_manager, session_role, StorageCounters::DISABLED)
+        .unwrap();
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let mut snapshot: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    let logged_in_session = thing_manager.create_entity(&mut snapshot, session_type).unwrap();
+    let abandoned_session = thing_manager.create_entity(&mut snapshot, session_type).unwrap();
+    let login = thing_manager.create_relation(&mut snapshot, login_type).unwrap();
+    login
+        .add_player(
+            &mut snapshot,
+            &thing_manager,
+            session_role,
+            logged_in_session.into_object(),
+            StorageCounters::DISABLED,
+        )
+        .unwrap();
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let mut snapshot: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    let mut retained = Vec::new();
+    let deleted = thing_manager
+        .delete_expired_objects(&mut snapshot, SystemTime::now(), |_, _| (), &mut retained, StorageCounters::DISABLED)
+        .unwrap();
+    assert_eq!(deleted, 1);
+    assert_eq!(retained.len(), 1);
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let snapshot: ReadSnapshot<WALClient> = storage.clone().open_snapshot_read();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    let remaining: Vec<Entity> =
+        thing_manager.get_entities(&snapshot, StorageCounters::DISABLED).try_collect().unwrap();
+    assert_eq!(remaining, vec![logged_in_session]);
+    assert!(!remaining.contains(&abandoned_session));
+}
+
 #[test]
 fn attribute_create() {
     let (_tmp_dir, mut storage) = create_core_storage();
@@ -242,7 +556,256 @@ fn has() {
         let retrieved_attributes_count =
             person_1.get_has_unordered(&snapshot, &thing_manager, StorageCounters::DISABLED).unwrap().count();
         assert_eq!(retrieved_attributes_count, 2);
+
+        let integer_attributes: Vec<_> = person_1
+            .get_has_of_value_type(&snapshot, &thing_manager, ValueTypeCategory::Integer, StorageCounters::DISABLED)
+            .unwrap()
+            .map_ok(|(attribute, _)| {
+                attribute.get_value(&snapshot, &thing_manager, StorageCounters::DISABLED).unwrap().into_owned()
+            })
+            .try_collect()
+            .unwrap();
+        assert_eq!(integer_attributes, vec![Value::Integer(age_value)]);
+        let double_attributes_count = person_1
+            .get_has_of_value_type(&snapshot, &thing_manager, ValueTypeCategory::Double, StorageCounters::DISABLED)
+            .unwrap()
+            .count();
+        assert_eq!(double_attributes_count, 0);
+    }
+}
+
+#[test]
+fn get_has_from_owners() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let age_type = type_manager.create_attribute_type(&mut snapshot, &Label::build("age", None)).unwrap();
+    age_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::Integer).unwrap();
+    let person_type = type_manager.create_entity_type(&mut snapshot, &Label::build("person", None)).unwrap();
+    person_type
+        .set_owns(
+            &mut snapshot,
+            &type_manager,
+            &thing_manager,
+            age_type,
+            Ordering::Unordered,
+            StorageCounters::DISABLED,
+        )
+        .unwrap();
+    let mut people = Vec::new();
+    for age in 0..4 {
+        let person = thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+        let age = thing_manager.create_attribute(&mut snapshot, age_type, Value::Integer(age)).unwrap();
+        person.set_has_unordered(&mut snapshot, &thing_manager, &age, StorageCounters::DISABLED).unwrap();
+        people.push(Object::Entity(person));
+    }
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let snapshot: ReadSnapshot<WALClient> = storage.clone().open_snapshot_read();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    people.sort();
+    let batch = vec![people[0], people[2], people[3]];
+    let owners: Vec<Object> = thing_manager
+        .get_has_from_owners_unordered(&snapshot, batch.clone(), StorageCounters::DISABLED)
+        .map_ok(|(has, _)| has.owner())
+        .try_collect()
+        .unwrap();
+    assert_eq!(owners, batch);
+    let empty = thing_manager.get_has_from_owners_unordered(&snapshot, Vec::new(), StorageCounters::DISABLED);
+    assert_eq!(empty.count(), 0);
+}
+
+#[test]
+fn get_owners_by_types() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let name_type = type_manager.create_attribute_type(&mut snapshot, &Label::build("name", None)).unwrap();
+    name_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::String).unwrap();
+    let name = thing_manager.create_attribute(&mut snapshot, name_type, Value::String(Cow::Borrowed("Alex"))).unwrap();
+    let mut owners_by_type = Vec::new();
+    for label in ["person", "company", "dog"] {
+        let owner_type = type_manager.create_entity_type(&mut snapshot, &Label::build(label, None)).unwrap();
+        owner_type
+            .set_owns(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                name_type,
+                Ordering::Unordered,
+                StorageCounters::DISABLED,
+            )
+            .unwrap();
+        let owner = thing_manager.create_entity(&mut snapshot, owner_type).unwrap();
+        owner.set_has_unordered(&mut snapshot, &thing_manager, &name, StorageCounters::DISABLED).unwrap();
+        owners_by_type.push((owner_type.into_object_type(), Object::Entity(owner)));
+    }
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let snapshot: ReadSnapshot<WALClient> = storage.clone().open_snapshot_read();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    let [(person_type, person), _, (dog_type, dog)] = owners_by_type.try_into().unwrap();
+    let owners: Vec<Object> = name
+        .get_owners_by_types(&snapshot, &thing_manager, [dog_type, person_type], StorageCounters::DISABLED)
+        .map_ok(|(owner, _)| owner)
+        .try_collect()
+        .unwrap();
+    assert_eq!(owners, vec![person, dog]);
+    let none = name.get_owners_by_types(&snapshot, &thing_manager, [], StorageCounters::DISABLED);
+    assert_eq!(none.count(), 0);
+}
+
+#[test]
+fn values_constraints() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let age_label = Label::build("age", None);
+    let name_label = Label::build("name", None);
+    let person_label = Label::build("person", None);
+
+    let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+    {
+        let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+
+        let age_type = type_manager.create_attribute_type(&mut snapshot, &age_label).unwrap();
+        age_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::Integer).unwrap();
+        age_type
+            .set_annotation(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                AttributeTypeAnnotation::Values(AnnotationValues::new(vec![Value::Integer(0), Value::Integer(18)])),
+                StorageCounters::DISABLED,
+            )
+            .unwrap();
+        let name_type = type_manager.create_attribute_type(&mut snapshot, &name_label).unwrap();
+        name_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::String).unwrap();
+
+        let person_type = type_manager.create_entity_type(&mut snapshot, &person_label).unwrap();
+        let name_owns = person_type
+            .set_owns(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                name_type,
+                Ordering::Unordered,
+                StorageCounters::DISABLED,
+            )
+            .unwrap();
+        name_owns
+            .set_annotation(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                OwnsAnnotation::Values(AnnotationValues::new(vec![
+                    Value::String(Cow::Borrowed("Alice")),
+                    Value::String(Cow::Borrowed("Bob")),
+                ])),
+            )
+            .unwrap();
+
+        assert_eq!(age_type.get_constraints_values(&snapshot, &type_manager).unwrap().len(), 1);
+        assert_eq!(
+            person_type.get_owned_attribute_type_constraints_values(&snapshot, &type_manager, name_type).unwrap().len(),
+            1
+        );
+
+        // Attribute type constraints apply to every attribute, regardless of its owner
+        assert!(thing_manager.create_attribute(&mut snapshot, age_type, Value::Integer(5)).is_err());
+        thing_manager.create_attribute(&mut snapshot, age_type, Value::Integer(18)).unwrap();
+
+        // Ownership constraints only apply to the attributes owned through that ownership
+        let person = thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+        let charlie =
+            thing_manager.create_attribute(&mut snapshot, name_type, Value::String(Cow::Borrowed("Charlie"))).unwrap();
+        assert!(person.set_has_unordered(&mut snapshot, &thing_manager, &charlie, StorageCounters::DISABLED).is_err());
+        let alice =
+            thing_manager.create_attribute(&mut snapshot, name_type, Value::String(Cow::Borrowed("Alice"))).unwrap();
+        person.set_has_unordered(&mut snapshot, &thing_manager, &alice, StorageCounters::DISABLED).unwrap();
+
+        let finalise_result = thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED);
+        assert!(finalise_result.is_ok());
     }
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+}
+
+#[test]
+fn range_constraints() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let born_label = Label::build("born", None);
+    let balance_label = Label::build("balance", None);
+    let person_label = Label::build("person", None);
+
+    let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+    {
+        let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+
+        // Bounds may be written with a value type castable to the attribute's value type
+        let born_type = type_manager.create_attribute_type(&mut snapshot, &born_label).unwrap();
+        born_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::DateTime).unwrap();
+        born_type
+            .set_annotation(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                AttributeTypeAnnotation::Range(AnnotationRange::new(
+                    Some(Value::Date(NaiveDate::from_ymd_opt(1900, 1, 1).unwrap())),
+                    None,
+                )),
+                StorageCounters::DISABLED,
+            )
+            .unwrap();
+        let balance_type = type_manager.create_attribute_type(&mut snapshot, &balance_label).unwrap();
+        balance_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::Decimal).unwrap();
+
+        let person_type = type_manager.create_entity_type(&mut snapshot, &person_label).unwrap();
+        let balance_owns = person_type
+            .set_owns(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                balance_type,
+                Ordering::Unordered,
+                StorageCounters::DISABLED,
+            )
+            .unwrap();
+        balance_owns
+            .set_annotation(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                OwnsAnnotation::Range(AnnotationRange::new(Some(Value::Integer(0)), Some(Value::Integer(100)))),
+            )
+            .unwrap();
+
+        let before = NaiveDate::from_ymd_opt(1899, 12, 31).unwrap().and_hms_opt(23, 59, 59).unwrap();
+        assert!(thing_manager.create_attribute(&mut snapshot, born_type, Value::DateTime(before)).is_err());
+        let after = NaiveDate::from_ymd_opt(1900, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+        thing_manager.create_attribute(&mut snapshot, born_type, Value::DateTime(after)).unwrap();
+
+        let person = thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+        let overdrawn =
+            thing_manager.create_attribute(&mut snapshot, balance_type, Value::Decimal(Decimal::new(-1, 0))).unwrap();
+        assert!(person
+            .set_has_unordered(&mut snapshot, &thing_manager, &overdrawn, StorageCounters::DISABLED)
+            .is_err());
+        let in_range =
+            thing_manager.create_attribute(&mut snapshot, balance_type, Value::Decimal(Decimal::new(99, 0))).unwrap();
+        person.set_has_unordered(&mut snapshot, &thing_manager, &in_range, StorageCounters::DISABLED).unwrap();
+
+        let finalise_result = thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED);
+        assert!(finalise_result.is_ok());
+    }
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
 }
 
 #[test]
@@ -780,6 +1343,22 @@ fn role_player_distinct() {
         assert_eq!(employment_1.get_players(&snapshot, &thing_manager, StorageCounters::DISABLED).count(), 2);
         assert_eq!(employment_2.get_players(&snapshot, &thing_manager, StorageCounters::DISABLED).count(), 3);
 
+        let count_players = |relation: Relation, role_type| {
+            relation.count_players(&snapshot, &thing_manager, role_type, StorageCounters::DISABLED).unwrap()
+        };
+        assert_eq!(count_players(employment_2, employee_type), 1);
+        assert_eq!(count_players(employment_2, employer_type), 2);
+
+        let players: Vec<Object> = employment_2
+            .get_players(&snapshot, &thing_manager, StorageCounters::DISABLED)
+            .map(|result| result.unwrap().0.player())
+            .collect();
+        let players_from: Vec<Object> = employment_2
+            .get_players_from(&snapshot, &thing_manager, players[1], StorageCounters::DISABLED)
+            .map(|result| result.unwrap().0.player())
+            .collect();
+        assert_eq!(players_from, players[1..]);
+
         assert_eq!(person_1.get_relations_roles(&snapshot, &thing_manager, StorageCounters::DISABLED).count(), 2);
         assert_eq!(company_1.get_relations_roles(&snapshot, &thing_manager, StorageCounters::DISABLED).count(), 1);
         assert_eq!(company_2.get_relations_roles(&snapshot, &thing_manager, StorageCounters::DISABLED).count(), 1);
@@ -1928,6 +2507,319 @@ fn schema_transactions_generate_role_player_indices() {
     }
 }
 
+#[test]
+fn relation_index_threshold_changes_rebuild_role_player_indices() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let membership_label = Label::build("membership", None);
+    let group_label = Label::build("group", None);
+    let person_label = Label::build("person", None);
+
+    let (membership_type, group_type, person_type, group_role, member_role) = {
+        let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+        let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+        let membership_type = type_manager.create_relation_type(&mut snapshot, &membership_label).unwrap();
+        let mut roles = Vec::new();
+        for role_label in ["group", "member"] {
+            let relates = membership_type
+                .create_relates(
+                    &mut snapshot,
+                    &type_manager,
+                    &thing_manager,
+                    role_label,
+                    Ordering::Unordered,
+                    StorageCounters::DISABLED,
+                )
+                .unwrap();
+            relates
+                .set_annotation(
+                    &mut snapshot,
+                    &type_manager,
+                    &thing_manager,
+                    RelatesAnnotation::Cardinality(AnnotationCardinality::new(0, Some(2))),
+                )
+                .unwrap();
+            roles.push(relates.role());
+        }
+        let group_type = type_manager.create_entity_type(&mut snapshot, &group_label).unwrap();
+        let person_type = type_manager.create_entity_type(&mut snapshot, &person_label).unwrap();
+        group_type
+            .set_plays(&mut snapshot, &type_manager, &thing_manager, roles[0], StorageCounters::DISABLED)
+            .unwrap();
+        person_type
+            .set_plays(&mut snapshot, &type_manager, &thing_manager, roles[1], StorageCounters::DISABLED)
+            .unwrap();
+        snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+        (membership_type, group_type, person_type, roles[0], roles[1])
+    };
+
+    let (group, person) = {
+        let mut snapshot: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+        let (_type_manager, thing_manager) = load_managers(storage.clone(), None);
+        let group = thing_manager.create_entity(&mut snapshot, group_type).unwrap();
+        let person = thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+        let membership = thing_manager.create_relation(&mut snapshot, membership_type).unwrap();
+        membership
+            .add_player(&mut snapshot, &thing_manager, group_role, Object::Entity(group), StorageCounters::DISABLED)
+            .unwrap();
+        membership
+            .add_player(&mut snapshot, &thing_manager, member_role, Object::Entity(person), StorageCounters::DISABLED)
+            .unwrap();
+        thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+        snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+        (Object::Entity(group), Object::Entity(person))
+    };
+
+    let indexed_count = |object: Object| -> Result<u64, Box<ConceptReadError>> {
+        let snapshot: ReadSnapshot<WALClient> = storage.clone().open_snapshot_read();
+        let (_type_manager, thing_manager) = load_managers(storage.clone(), None);
+        let indexed =
+            object.get_indexed_relations(&snapshot, &thing_manager, membership_type, StorageCounters::DISABLED)?;
+        Ok(indexed.map(|res| res.unwrap().1).sum())
+    };
+    assert_eq!(indexed_count(group).unwrap(), 1);
+    assert_eq!(indexed_count(person).unwrap(), 1);
+
+    let set_threshold = |threshold: u64| {
+        let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+        let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+        type_manager
+            .set_relation_index_threshold(
+                &mut snapshot,
+                &thing_manager,
+                RelationIndexThreshold(threshold),
+                StorageCounters::DISABLED,
+            )
+            .unwrap();
+        assert_eq!(type_manager.get_relation_index_threshold(&snapshot).unwrap(), RelationIndexThreshold(threshold));
+        thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+        snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+    };
+
+    // Total role cardinality of 4 exceeds the threshold
+    set_threshold(3);
+    assert!(indexed_count(group).is_err(), "Expected no indices below the relation index threshold");
+    let snapshot: ReadSnapshot<WALClient> = storage.clone().open_snapshot_read();
+    let index_prefix = ThingEdgeIndexedRelation::prefix_start(membership_type.vertex().type_id_(), group.vertex());
+    assert!(
+        !snapshot
+            .any_in_range(&KeyRange::new_within(index_prefix, ThingEdgeIndexedRelation::FIXED_WIDTH_ENCODING), false),
+        "Expected indices to be removed when the relation index threshold is lowered"
+    );
+    drop(snapshot);
+
+    set_threshold(4);
+    assert_eq!(indexed_count(group).unwrap(), 1, "Expected indices to be rebuilt");
+    assert_eq!(indexed_count(person).unwrap(), 1, "Expected indices to be rebuilt");
+}
+
+#[test]
+fn rebuild_relation_index_after_cardinality_change() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let membership_label = Label::build("membership", None);
+
+    let (membership_type, group_type, person_type, group_role, member_role) = {
+        let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+        let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+        let membership_type = type_manager.create_relation_type(&mut snapshot, &membership_label).unwrap();
+        let mut roles = Vec::new();
+        for role_label in ["group", "member"] {
+            let relates = membership_type
+                .create_relates(
+                    &mut snapshot,
+                    &type_manager,
+                    &thing_manager,
+                    role_label,
+                    Ordering::Unordered,
+                    StorageCounters::DISABLED,
+                )
+                .unwrap();
+            relates
+                .set_annotation(
+                    &mut snapshot,
+                    &type_manager,
+                    &thing_manager,
+                    RelatesAnnotation::Cardinality(AnnotationCardinality::new(0, None)), // too large to be indexed
+                )
+                .unwrap();
+            roles.push(relates.role());
+        }
+        let group_type = type_manager.create_entity_type(&mut snapshot, &Label::build("group", None)).unwrap();
+        let person_type = type_manager.create_entity_type(&mut snapshot, &Label::build("person", None)).unwrap();
+        group_type
+            .set_plays(&mut snapshot, &type_manager, &thing_manager, roles[0], StorageCounters::DISABLED)
+            .unwrap();
+        person_type
+            .set_plays(&mut snapshot, &type_manager, &thing_manager, roles[1], StorageCounters::DISABLED)
+            .unwrap();
+        snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+        (membership_type, group_type, person_type, roles[0], roles[1])
+    };
+
+    let group = {
+        let mut snapshot: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+        let (_type_manager, thing_manager) = load_managers(storage.clone(), None);
+        let group = thing_manager.create_entity(&mut snapshot, group_type).unwrap();
+        let person = thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+        let membership = thing_manager.create_relation(&mut snapshot, membership_type).unwrap();
+        membership
+            .add_player(&mut snapshot, &thing_manager, group_role, Object::Entity(group), StorageCounters::DISABLED)
+            .unwrap();
+        membership
+            .add_player(&mut snapshot, &thing_manager, member_role, Object::Entity(person), StorageCounters::DISABLED)
+            .unwrap();
+        thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+        snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+        Object::Entity(group)
+    };
+
+    {
+        let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+        let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+        for relates in membership_type.get_relates(&snapshot, &type_manager).unwrap().iter().copied().collect_vec() {
+            relates
+                .set_annotation(
+                    &mut snapshot,
+                    &type_manager,
+                    &thing_manager,
+                    RelatesAnnotation::Cardinality(AnnotationCardinality::new(0, Some(2))),
+                )
+                .unwrap();
+        }
+        let mut progress = Vec::new();
+        thing_manager
+            .rebuild_relation_index(
+                &mut snapshot,
+                membership_type,
+                |completed, total| progress.push((completed, total)),
+                StorageCounters::DISABLED,
+            )
+            .unwrap();
+        assert_eq!(progress, vec![(1, 1)]);
+        thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+        snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+    }
+
+    let snapshot: ReadSnapshot<WALClient> = storage.clone().open_snapshot_read();
+    let (_type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let indexed_count: u64 = group
+        .get_indexed_relations(&snapshot, &thing_manager, membership_type, StorageCounters::DISABLED)
+        .unwrap()
+        .map(|res| res.unwrap().1)
+        .sum();
+    assert_eq!(indexed_count, 1, "Expected the rebuilt index to contain existing relations");
+}
+
+#[test]
+fn delete_orphaned_attributes() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let person_type = type_manager.create_entity_type(&mut snapshot, &Label::build("person", None)).unwrap();
+    let name_type = type_manager.create_attribute_type(&mut snapshot, &Label::build("name", None)).unwrap();
+    name_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::String).unwrap();
+    person_type
+        .set_owns(
+            &mut snapshot,
+            &type_manager,
+            &thing_manager,
+            name_type,
+            Ordering::Unordered,
+            StorageCounters::DISABLED,
+        )
+        .unwrap();
+    let person = thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+    let owned =
+        thing_manager.create_attribute(&mut snapshot, name_type, Value::String(Cow::Borrowed("alice"))).unwrap();
+    person.set_has_unordered(&mut snapshot, &thing_manager, &owned, StorageCounters::DISABLED).unwrap();
+    thing_manager.create_attribute(&mut snapshot, name_type, Value::String(Cow::Borrowed("bob"))).unwrap();
+    // committing without finalising leaves the unowned attribute behind
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let mut snapshot: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    let mut progress = Vec::new();
+    let deleted = thing_manager
+        .delete_orphaned_attributes(
+            &mut snapshot,
+            |completed, total| progress.push((completed, total)),
+            StorageCounters::DISABLED,
+        )
+        .unwrap();
+    assert_eq!(deleted, 1);
+    assert!(progress.last().is_some_and(|(completed, total)| completed == total));
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let mut snapshot: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    let deleted =
+        thing_manager.delete_orphaned_attributes(&mut snapshot, |_, _| (), StorageCounters::DISABLED).unwrap();
+    assert_eq!(deleted, 0);
+    let names: Vec<Attribute> = thing_manager
+        .get_attributes_in(&snapshot, name_type, StorageCounters::DISABLED)
+        .unwrap()
+        .try_collect()
+        .unwrap();
+    assert_eq!(names, vec![owned]);
+}
+
+#[test]
+fn commit_validation_reports_every_shard() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let (person_type, name_type) = {
+        let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+        let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+        let person_type = type_manager.create_entity_type(&mut snapshot, &Label::build("person", None)).unwrap();
+        let name_type = type_manager.create_attribute_type(&mut snapshot, &Label::build("name", None)).unwrap();
+        name_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::String).unwrap();
+        person_type
+            .set_owns(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                name_type,
+                Ordering::Unordered,
+                StorageCounters::DISABLED,
+            )
+            .unwrap()
+            .set_annotation(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                OwnsAnnotation::Cardinality(AnnotationCardinality::new(1, Some(1))),
+            )
+            .unwrap();
+        thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+        snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+        (person_type, name_type)
+    };
+
+    // enough modified objects to be validated in several shards
+    let person_count = 3 * COMMIT_VALIDATION_SHARD_SIZE_MIN;
+    let mut snapshot: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+    let (_, thing_manager) = load_managers(storage.clone(), None);
+    for index in 0..person_count {
+        let person = thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+        // every other person is missing its name
+        if index % 2 == 0 {
+            let name = thing_manager
+                .create_attribute(&mut snapshot, name_type, Value::String(Cow::Owned(format!("name-{index}"))))
+                .unwrap();
+            person.set_has_unordered(&mut snapshot, &thing_manager, &name, StorageCounters::DISABLED).unwrap();
+        }
+    }
+    let errors = thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap_err();
+    assert_eq!(errors.len(), person_count / 2);
+}
+
 #[test]
 fn attribute_string_write_read_delete() {
     let (_tmp_dir, mut storage) = create_core_storage();
@@ -2432,3 +3324,60 @@ pub fn define_struct(
     }
     struct_key
 }
+
+#[test]
+fn unique_value_concurrent_commits() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+
+    let person_label = Label::build("person", None);
+    let email_label = Label::build("email", None);
+    let (person_type, email_type) = {
+        let mut snapshot: SchemaSnapshot<WALClient> = storage.clone().open_snapshot_schema();
+        let person_type = type_manager.create_entity_type(&mut snapshot, &person_label).unwrap();
+        let email_type = type_manager.create_attribute_type(&mut snapshot, &email_label).unwrap();
+        email_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::String).unwrap();
+        let owns = person_type
+            .set_owns(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                email_type,
+                Ordering::Unordered,
+                StorageCounters::DISABLED,
+            )
+            .unwrap();
+        owns.set_annotation(&mut snapshot, &type_manager, &thing_manager, OwnsAnnotation::Unique(AnnotationUnique))
+            .unwrap();
+
+        thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+        snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+        (person_type, email_type)
+    };
+
+    // Both transactions see no existing owner of the value, so only the commit-time lock can reject one of them
+    let mut snapshot_1: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+    let mut snapshot_2: WriteSnapshot<WALClient> = storage.clone().open_snapshot_write();
+    for snapshot in [&mut snapshot_1, &mut snapshot_2] {
+        let person = thing_manager.create_entity(snapshot, person_type).unwrap();
+        let email =
+            thing_manager.create_attribute(snapshot, email_type, Value::String(Cow::Borrowed("alice@typedb"))).unwrap();
+        person.set_has_unordered(snapshot, &thing_manager, &email, StorageCounters::DISABLED).unwrap();
+        thing_manager.finalise(snapshot, StorageCounters::DISABLED).unwrap();
+    }
+    snapshot_1.commit(&mut CommitProfile::DISABLED).unwrap();
+    assert!(snapshot_2.commit(&mut CommitProfile::DISABLED).is_err());
+
+    let snapshot: ReadSnapshot<WALClient> = storage.clone().open_snapshot_read();
+    let email = thing_manager
+        .get_attribute_with_value(
+            &snapshot,
+            email_type,
+            Value::String(Cow::Borrowed("alice@typedb")),
+            StorageCounters::DISABLED,
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(email.get_owners(&snapshot, &thing_manager, StorageCounters::DISABLED).count(), 1);
+}
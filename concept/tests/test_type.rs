@@ -16,7 +16,9 @@ use chrono_tz::Tz;
 use concept::{
     thing::{statistics::Statistics, thing_manager::ThingManager},
     type_::{
-        annotation::{AnnotationAbstract, AnnotationRange, AnnotationValues},
+        annotation::{
+            AnnotationAbstract, AnnotationCategory, AnnotationKey, AnnotationRange, AnnotationRegex, AnnotationValues,
+        },
         attribute_type::AttributeTypeAnnotation,
         entity_type::EntityTypeAnnotation,
         object_type::ObjectType,
@@ -32,7 +34,7 @@ use encoding::{
             definition_key::DefinitionKey, definition_key_generator::DefinitionKeyGenerator, r#struct::StructDefinition,
         },
         thing::vertex_generator::ThingVertexGenerator,
-        type_::vertex_generator::TypeVertexGenerator,
+        type_::{vertex::TypeVertexEncoding, vertex_generator::TypeVertexGenerator},
     },
     value::{decimal_value::Decimal, label::Label, timezone::TimeZone, value::Value, value_type::ValueType},
 };
@@ -149,6 +151,14 @@ fn entity_usage() {
         assert_eq!(child_type.get_owns_attribute(&snapshot, &type_manager, age_type).unwrap(), Some(owns));
         assert!(child_type.has_owns_attribute(&snapshot, &type_manager, age_type).unwrap());
 
+        owns.set_annotation(&mut snapshot, &type_manager, &thing_manager, OwnsAnnotation::Key(AnnotationKey)).unwrap();
+        let key_owns = type_manager.get_owns_with_annotation_category(&snapshot, AnnotationCategory::Key).unwrap();
+        assert_eq!(key_owns, [owns].into());
+        assert!(type_manager
+            .get_owns_with_annotation_category(&snapshot, AnnotationCategory::Unique)
+            .unwrap()
+            .is_empty());
+
         // --- adult sub person ---
         let adult_type = type_manager.create_entity_type(&mut snapshot, &Label::build("adult", None)).unwrap();
         adult_type.set_supertype(&mut snapshot, &type_manager, &thing_manager, person_type).unwrap();
@@ -176,6 +186,22 @@ fn entity_usage() {
                 assert_eq!(ObjectType::Entity(person_type), child_owns_height.owner());
             }
         }
+
+        // --- kind-scoped iteration ---
+        let entity_types = type_manager.iterate_entity_types(&snapshot).unwrap().collect::<Vec<_>>();
+        assert_eq!(entity_types, [person_type, child_type, adult_type]);
+        let attribute_types = type_manager.iterate_attribute_types(&snapshot).unwrap().collect::<Vec<_>>();
+        assert_eq!(attribute_types, [age_type, height_type]);
+        assert_eq!(type_manager.iterate_relation_types(&snapshot).unwrap().count(), 0);
+
+        // --- hierarchy closure ---
+        let hierarchy_closure = type_manager.get_hierarchy_closure(&snapshot).unwrap();
+        assert!(hierarchy_closure.is_subtype_transitive_of(child_type, person_type));
+        assert!(hierarchy_closure.is_subtype_transitive_of_or_same(person_type, person_type));
+        assert!(!hierarchy_closure.is_subtype_transitive_of(person_type, person_type));
+        assert!(!hierarchy_closure.is_subtype_transitive_of_or_same(person_type, child_type));
+        assert!(!hierarchy_closure.is_subtype_transitive_of_or_same(child_type, adult_type));
+        assert!(!hierarchy_closure.is_subtype_transitive_of_or_same(age_type, height_type));
     }
     snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
 
@@ -222,6 +248,12 @@ fn entity_usage() {
         assert!(all_owns.contains(&expected_owns));
         assert_eq!(child_type.get_owns_attribute(&snapshot, &type_manager, age_type).unwrap(), Some(expected_owns));
         assert!(child_type.has_owns_attribute(&snapshot, &type_manager, age_type).unwrap());
+        let key_owns = type_manager.get_owns_with_annotation_category(&snapshot, AnnotationCategory::Key).unwrap();
+        assert_eq!(key_owns, [expected_owns].into());
+        assert!(type_manager
+            .get_plays_with_annotation_category(&snapshot, AnnotationCategory::Cardinality)
+            .unwrap()
+            .is_empty());
 
         // --- adult sub person ---
         assert_eq!(person_type.get_subtypes(&snapshot, &type_manager).unwrap().len(), 2);
@@ -236,7 +268,336 @@ fn entity_usage() {
                 assert_eq!(ObjectType::Entity(person_type), child_owns_height.owner());
             }
         }
+
+        // --- kind-scoped iteration ---
+        let adult_type = type_manager.get_entity_type(&snapshot, &Label::build("adult", None)).unwrap().unwrap();
+        let entity_types = type_manager.iterate_entity_types(&snapshot).unwrap().collect::<Vec<_>>();
+        assert_eq!(entity_types, [person_type, child_type, adult_type]);
+        let attribute_types = type_manager.iterate_attribute_types(&snapshot).unwrap().collect::<Vec<_>>();
+        assert_eq!(attribute_types, [age_type, height_type]);
+        assert_eq!(type_manager.iterate_relation_types(&snapshot).unwrap().count(), 0);
+
+        // --- hierarchy closure ---
+        let hierarchy_closure = type_manager.get_hierarchy_closure(&snapshot).unwrap();
+        assert!(hierarchy_closure.is_subtype_transitive_of(child_type, person_type));
+        assert!(hierarchy_closure.is_subtype_transitive_of_or_same(person_type, person_type));
+        assert!(!hierarchy_closure.is_subtype_transitive_of(person_type, person_type));
+        assert!(!hierarchy_closure.is_subtype_transitive_of_or_same(person_type, child_type));
+        assert!(!hierarchy_closure.is_subtype_transitive_of_or_same(child_type, adult_type));
+        assert!(!hierarchy_closure.is_subtype_transitive_of_or_same(age_type, height_type));
+    }
+}
+
+#[test]
+fn type_cache_incremental() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let person_label = Label::build("person", None);
+    let name_label = Label::build("name", None);
+
+    let mut snapshot: WriteSnapshot<_> = storage.clone().open_snapshot_write();
+    {
+        let type_manager = type_manager_no_cache();
+        let thing_manager = thing_manager(type_manager.clone());
+        let person_type = type_manager.create_entity_type(&mut snapshot, &person_label).unwrap();
+        let name_type = type_manager.create_attribute_type(&mut snapshot, &name_label).unwrap();
+        name_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::String).unwrap();
+        person_type
+            .set_owns(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                name_type,
+                Ordering::Unordered,
+                StorageCounters::DISABLED,
+            )
+            .unwrap();
+    }
+    let sequence_number = snapshot.commit(&mut CommitProfile::DISABLED).unwrap().unwrap();
+    let previous_cache = TypeCache::new(storage.clone(), sequence_number).unwrap();
+    assert!(!previous_cache.is_incremental());
+
+    let mut snapshot: WriteSnapshot<_> = storage.clone().open_snapshot_write();
+    {
+        let type_manager = type_manager_no_cache();
+        let thing_manager = thing_manager(type_manager.clone());
+        let person_type = type_manager.get_entity_type(&snapshot, &person_label).unwrap().unwrap();
+        let child_type = type_manager.create_entity_type(&mut snapshot, &Label::build("child", None)).unwrap();
+        child_type.set_supertype(&mut snapshot, &type_manager, &thing_manager, person_type).unwrap();
+    }
+    let modified_types = TypeCache::modified_types(&snapshot);
+    let sequence_number = snapshot.commit(&mut CommitProfile::DISABLED).unwrap().unwrap();
+    let cache = TypeCache::new_incremental(&previous_cache, storage.clone(), sequence_number, &modified_types).unwrap();
+    assert!(cache.is_incremental());
+
+    {
+        let snapshot: ReadSnapshot<_> = storage.clone().open_snapshot_read_at(sequence_number);
+        let definition_key_generator = Arc::new(DefinitionKeyGenerator::new());
+        let type_vertex_generator = Arc::new(TypeVertexGenerator::new());
+        let type_manager = TypeManager::new(definition_key_generator, type_vertex_generator, Some(Arc::new(cache)));
+
+        let person_type = type_manager.get_entity_type(&snapshot, &person_label).unwrap().unwrap();
+        let child_type = type_manager.get_entity_type(&snapshot, &Label::build("child", None)).unwrap().unwrap();
+        let name_type = type_manager.get_attribute_type(&snapshot, &name_label).unwrap().unwrap();
+        assert!(modified_types.contains(&child_type.vertex()) && modified_types.contains(&person_type.vertex()));
+        assert!(!modified_types.contains(&name_type.vertex()));
+
+        // The new subtype is visible from its supertype, and from the attribute type it inherits the ownership of
+        assert_eq!(child_type.get_supertype(&snapshot, &type_manager).unwrap(), Some(person_type));
+        assert!(person_type.get_subtypes(&snapshot, &type_manager).unwrap().contains(&child_type));
+        assert!(child_type.get_owns_attribute(&snapshot, &type_manager, name_type).unwrap().is_some());
+        assert!(name_type
+            .get_owner_types(&snapshot, &type_manager)
+            .unwrap()
+            .contains_key(&ObjectType::Entity(child_type)));
+        assert_eq!(name_type.get_value_type_without_source(&snapshot, &type_manager).unwrap(), Some(ValueType::String));
+    }
+}
+
+fn sorted_debug<T: std::fmt::Debug>(items: impl IntoIterator<Item = T>) -> Vec<String> {
+    let mut items = items.into_iter().map(|item| format!("{item:?}")).collect::<Vec<_>>();
+    items.sort();
+    items
+}
+
+/// Everything read from the type cache that the schema can change, in an order independent of the cache's maps
+fn type_cache_summary(snapshot: &impl ReadableSnapshot, type_cache: Arc<TypeCache>) -> Vec<String> {
+    let definition_key_generator = Arc::new(DefinitionKeyGenerator::new());
+    let type_vertex_generator = Arc::new(TypeVertexGenerator::new());
+    let type_manager = TypeManager::new(definition_key_generator, type_vertex_generator, Some(type_cache));
+
+    let mut summary = vec![type_manager.get_types_syntax(snapshot).unwrap()];
+    for object_type in type_manager.get_object_types(snapshot).unwrap() {
+        let subtypes = object_type.get_subtypes_transitive(snapshot, &type_manager).unwrap();
+        summary.push(format!("{object_type:?} subtypes {:?}", sorted_debug(subtypes.iter())));
+        for owns in object_type.get_owns(snapshot, &type_manager).unwrap().iter() {
+            let constraints = owns.get_constraints(snapshot, &type_manager).unwrap();
+            summary.push(format!("{owns:?} constraints {:?}", sorted_debug(constraints.iter())));
+        }
+        for plays in object_type.get_plays(snapshot, &type_manager).unwrap().iter() {
+            let constraints = plays.get_constraints(snapshot, &type_manager).unwrap();
+            summary.push(format!("{plays:?} constraints {:?}", sorted_debug(constraints.iter())));
+        }
+    }
+    for entity_type in type_manager.get_entity_types(snapshot).unwrap() {
+        let constraints = entity_type.get_constraints(snapshot, &type_manager).unwrap();
+        summary.push(format!("{entity_type:?} constraints {:?}", sorted_debug(constraints.iter())));
+    }
+    for relation_type in type_manager.get_relation_types(snapshot).unwrap() {
+        let constraints = relation_type.get_constraints(snapshot, &type_manager).unwrap();
+        summary.push(format!("{relation_type:?} constraints {:?}", sorted_debug(constraints.iter())));
+        for relates in relation_type.get_relates(snapshot, &type_manager).unwrap().iter() {
+            let constraints = relates.get_constraints(snapshot, &type_manager).unwrap();
+            summary.push(format!("{relates:?} constraints {:?}", sorted_debug(constraints.iter())));
+        }
+    }
+    for attribute_type in type_manager.get_attribute_types(snapshot).unwrap() {
+        let subtypes = attribute_type.get_subtypes_transitive(snapshot, &type_manager).unwrap();
+        let owner_types = attribute_type.get_owner_types(snapshot, &type_manager).unwrap();
+        let constraints = attribute_type.get_constraints(snapshot, &type_manager).unwrap();
+        summary.push(format!(
+            "{attribute_type:?} subtypes {:?} owners {:?} constraints {:?}",
+            sorted_debug(subtypes.iter()),
+            sorted_debug(owner_types.keys()),
+            sorted_debug(constraints.iter())
+        ));
+    }
+    for role_type in type_manager.get_role_types(snapshot).unwrap() {
+        let player_types = role_type.get_player_types(snapshot, &type_manager).unwrap();
+        let relation_types = role_type.get_relation_types(snapshot, &type_manager).unwrap();
+        let constraints = role_type.get_constraints(snapshot, &type_manager).unwrap();
+        summary.push(format!(
+            "{role_type:?} players {:?} relations {:?} constraints {:?}",
+            sorted_debug(player_types.keys()),
+            sorted_debug(relation_types.keys()),
+            sorted_debug(constraints.iter())
+        ));
+    }
+    summary.sort();
+    summary
+}
+
+/// Commits the snapshot, and checks that updating the previous cache incrementally reads the same schema
+/// as rebuilding the cache in full
+fn commit_with_incremental_cache(
+    storage: &Arc<MVCCStorage<WALClient>>,
+    previous_cache: &TypeCache,
+    snapshot: WriteSnapshot<WALClient>,
+) -> Arc<TypeCache> {
+    let modified_types = TypeCache::modified_types(&snapshot);
+    let sequence_number = snapshot.commit(&mut CommitProfile::DISABLED).unwrap().unwrap();
+    let cache = Arc::new(
+        TypeCache::new_incremental(previous_cache, storage.clone(), sequence_number, &modified_types).unwrap(),
+    );
+    let rebuilt_cache = Arc::new(TypeCache::new(storage.clone(), sequence_number).unwrap());
+    assert!(cache.is_incremental());
+
+    let snapshot: ReadSnapshot<_> = storage.clone().open_snapshot_read_at(sequence_number);
+    assert_eq!(type_cache_summary(&snapshot, cache.clone()), type_cache_summary(&snapshot, rebuilt_cache));
+    cache
+}
+
+#[test]
+fn type_cache_incremental_matches_rebuilt() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let person_label = Label::build("person", None);
+    let child_label = Label::build("child", None);
+    let name_label = Label::build("name", None);
+    let age_label = Label::build("age", None);
+    let friendship_label = Label::build("friendship", None);
+    let friend_label = Label::build_scoped("friend", "friendship", None);
+    let mentor_label = Label::build_scoped("mentor", "friendship", None);
+
+    let mut snapshot: WriteSnapshot<_> = storage.clone().open_snapshot_write();
+    {
+        let type_manager = type_manager_no_cache();
+        let thing_manager = thing_manager(type_manager.clone());
+        let person_type = type_manager.create_entity_type(&mut snapshot, &person_label).unwrap();
+        let name_type = type_manager.create_attribute_type(&mut snapshot, &name_label).unwrap();
+        name_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::String).unwrap();
+        person_type
+            .set_owns(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                name_type,
+                Ordering::Unordered,
+                StorageCounters::DISABLED,
+            )
+            .unwrap();
+        let friendship_type = type_manager.create_relation_type(&mut snapshot, &friendship_label).unwrap();
+        let friend_type = friendship_type
+            .create_relates(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                "friend",
+                Ordering::Unordered,
+                StorageCounters::DISABLED,
+            )
+            .unwrap()
+            .role();
+        person_type
+            .set_plays(&mut snapshot, &type_manager, &thing_manager, friend_type, StorageCounters::DISABLED)
+            .unwrap();
+    }
+    let sequence_number = snapshot.commit(&mut CommitProfile::DISABLED).unwrap().unwrap();
+    let cache = TypeCache::new(storage.clone(), sequence_number).unwrap();
+
+    // --- new subtype and new owns ---
+    let mut snapshot: WriteSnapshot<_> = storage.clone().open_snapshot_write();
+    {
+        let type_manager = type_manager_no_cache();
+        let thing_manager = thing_manager(type_manager.clone());
+        let person_type = type_manager.get_entity_type(&snapshot, &person_label).unwrap().unwrap();
+        let child_type = type_manager.create_entity_type(&mut snapshot, &child_label).unwrap();
+        child_type.set_supertype(&mut snapshot, &type_manager, &thing_manager, person_type).unwrap();
+        let age_type = type_manager.create_attribute_type(&mut snapshot, &age_label).unwrap();
+        age_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::Integer).unwrap();
+        person_type
+            .set_owns(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                age_type,
+                Ordering::Unordered,
+                StorageCounters::DISABLED,
+            )
+            .unwrap();
+    }
+    let cache = commit_with_incremental_cache(&storage, &cache, snapshot);
+
+    // --- annotation changes ---
+    let mut snapshot: WriteSnapshot<_> = storage.clone().open_snapshot_write();
+    {
+        let type_manager = type_manager_no_cache();
+        let thing_manager = thing_manager(type_manager.clone());
+        let person_type = type_manager.get_entity_type(&snapshot, &person_label).unwrap().unwrap();
+        let name_type = type_manager.get_attribute_type(&snapshot, &name_label).unwrap().unwrap();
+        person_type
+            .set_annotation(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                EntityTypeAnnotation::Abstract(AnnotationAbstract),
+                StorageCounters::DISABLED,
+            )
+            .unwrap();
+        let owns = person_type.get_owns_attribute(&snapshot, &type_manager, name_type).unwrap().unwrap();
+        owns.set_annotation(&mut snapshot, &type_manager, &thing_manager, OwnsAnnotation::Key(AnnotationKey)).unwrap();
+    }
+    let cache = commit_with_incremental_cache(&storage, &cache, snapshot);
+
+    // --- new relates and plays ---
+    let mut snapshot: WriteSnapshot<_> = storage.clone().open_snapshot_write();
+    {
+        let type_manager = type_manager_no_cache();
+        let thing_manager = thing_manager(type_manager.clone());
+        let child_type = type_manager.get_entity_type(&snapshot, &child_label).unwrap().unwrap();
+        let friendship_type = type_manager.get_relation_type(&snapshot, &friendship_label).unwrap().unwrap();
+        let mentor_type = friendship_type
+            .create_relates(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                "mentor",
+                Ordering::Unordered,
+                StorageCounters::DISABLED,
+            )
+            .unwrap()
+            .role();
+        child_type
+            .set_plays(&mut snapshot, &type_manager, &thing_manager, mentor_type, StorageCounters::DISABLED)
+            .unwrap();
+    }
+    let cache = commit_with_incremental_cache(&storage, &cache, snapshot);
+
+    // --- removed owns, plays and relates, and an unset annotation ---
+    let mut snapshot: WriteSnapshot<_> = storage.clone().open_snapshot_write();
+    {
+        let type_manager = type_manager_no_cache();
+        let thing_manager = thing_manager(type_manager.clone());
+        let person_type = type_manager.get_entity_type(&snapshot, &person_label).unwrap().unwrap();
+        let child_type = type_manager.get_entity_type(&snapshot, &child_label).unwrap().unwrap();
+        let name_type = type_manager.get_attribute_type(&snapshot, &name_label).unwrap().unwrap();
+        let age_type = type_manager.get_attribute_type(&snapshot, &age_label).unwrap().unwrap();
+        let friend_type = type_manager.get_role_type(&snapshot, &friend_label).unwrap().unwrap();
+        let mentor_type = type_manager.get_role_type(&snapshot, &mentor_label).unwrap().unwrap();
+        person_type.unset_owns(&mut snapshot, &type_manager, &thing_manager, age_type).unwrap();
+        person_type.unset_plays(&mut snapshot, &type_manager, &thing_manager, friend_type).unwrap();
+        child_type.unset_plays(&mut snapshot, &type_manager, &thing_manager, mentor_type).unwrap();
+        mentor_type.delete(&mut snapshot, &type_manager, &thing_manager).unwrap();
+        let owns = person_type.get_owns_attribute(&snapshot, &type_manager, name_type).unwrap().unwrap();
+        owns.unset_annotation(&mut snapshot, &type_manager, &thing_manager, AnnotationCategory::Key).unwrap();
+    }
+    let cache = commit_with_incremental_cache(&storage, &cache, snapshot);
+
+    // --- undefined types ---
+    let mut snapshot: WriteSnapshot<_> = storage.clone().open_snapshot_write();
+    {
+        let type_manager = type_manager_no_cache();
+        let thing_manager = thing_manager(type_manager.clone());
+        let child_type = type_manager.get_entity_type(&snapshot, &child_label).unwrap().unwrap();
+        let age_type = type_manager.get_attribute_type(&snapshot, &age_label).unwrap().unwrap();
+        child_type.delete(&mut snapshot, &type_manager, &thing_manager).unwrap();
+        age_type.delete(&mut snapshot, &type_manager, &thing_manager).unwrap();
     }
+    let cache = commit_with_incremental_cache(&storage, &cache, snapshot);
+
+    let snapshot: ReadSnapshot<_> = storage.clone().open_snapshot_read_at(cache.open_sequence_number());
+    let type_manager = TypeManager::new(
+        Arc::new(DefinitionKeyGenerator::new()),
+        Arc::new(TypeVertexGenerator::new()),
+        Some(cache.clone()),
+    );
+    assert!(type_manager.get_entity_type(&snapshot, &child_label).unwrap().is_none());
+    assert!(type_manager.get_role_type(&snapshot, &mentor_label).unwrap().is_none());
+    let person_type = type_manager.get_entity_type(&snapshot, &person_label).unwrap().unwrap();
+    assert!(person_type.get_subtypes(&snapshot, &type_manager).unwrap().is_empty());
+    assert!(person_type.get_plays(&snapshot, &type_manager).unwrap().is_empty());
 }
 
 #[test]
@@ -310,6 +671,76 @@ fn role_usage() {
     }
 }
 
+#[test]
+fn type_rename() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let person_label = Label::build("person", None);
+    let name_label = Label::build("name", None);
+    let friendship_label = Label::build("friendship", None);
+
+    let mut snapshot: WriteSnapshot<_> = storage.clone().open_snapshot_write();
+    {
+        let type_manager = type_manager_no_cache();
+        let thing_manager = thing_manager(type_manager.clone());
+
+        let person_type = type_manager.create_entity_type(&mut snapshot, &person_label).unwrap();
+        type_manager.create_attribute_type(&mut snapshot, &name_label).unwrap();
+        let friendship_type = type_manager.create_relation_type(&mut snapshot, &friendship_label).unwrap();
+        let role_type = friendship_type
+            .create_relates(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                "friend",
+                Ordering::Unordered,
+                StorageCounters::DISABLED,
+            )
+            .unwrap()
+            .role();
+        person_type
+            .set_plays(&mut snapshot, &type_manager, &thing_manager, role_type, StorageCounters::DISABLED)
+            .unwrap();
+    }
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let mut snapshot: WriteSnapshot<_> = storage.clone().open_snapshot_write();
+    {
+        let type_manager = type_manager_no_cache();
+
+        let person_type = type_manager.get_entity_type(&snapshot, &person_label).unwrap().unwrap();
+        let friendship_type = type_manager.get_relation_type(&snapshot, &friendship_label).unwrap().unwrap();
+        let role_type =
+            friendship_type.get_relates_role_name(&snapshot, &type_manager, "friend").unwrap().unwrap().role();
+
+        // Collisions with existing labels are rejected, renaming to the current label is a no-op
+        assert!(person_type.rename(&mut snapshot, &type_manager, &name_label).is_err());
+        person_type.rename(&mut snapshot, &type_manager, &person_label).unwrap();
+        assert!(role_type.rename(&mut snapshot, &type_manager, &Label::build_scoped("pal", "marriage", None)).is_err());
+
+        person_type.rename(&mut snapshot, &type_manager, &Label::build("human", None)).unwrap();
+        friendship_type.rename(&mut snapshot, &type_manager, &Label::build("bond", None)).unwrap();
+        role_type.rename(&mut snapshot, &type_manager, &Label::build_scoped("pal", "bond", None)).unwrap();
+    }
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    {
+        let snapshot: ReadSnapshot<_> = storage.clone().open_snapshot_read();
+        let type_manager = type_manager_at_snapshot(storage.clone(), &snapshot);
+
+        assert!(type_manager.get_entity_type(&snapshot, &person_label).unwrap().is_none());
+        assert!(type_manager.get_relation_type(&snapshot, &friendship_label).unwrap().is_none());
+
+        let human_type = type_manager.get_entity_type(&snapshot, &Label::build("human", None)).unwrap().unwrap();
+        let bond_type = type_manager.get_relation_type(&snapshot, &Label::build("bond", None)).unwrap().unwrap();
+        let role_type = bond_type.get_relates_role_name(&snapshot, &type_manager, "pal").unwrap().unwrap().role();
+        assert_eq!(role_type.get_label(&snapshot, &type_manager).unwrap().scoped_name().as_str(), "bond:pal");
+        let plays = human_type.get_plays_role(&snapshot, &type_manager, role_type).unwrap().unwrap();
+        assert_eq!(plays.player(), ObjectType::Entity(human_type));
+    }
+}
+
 #[test]
 fn annotations_with_range_arguments() {
     let (_tmp_dir, mut storage) = create_core_storage();
@@ -1198,6 +1629,79 @@ fn annotations_with_value_arguments() {
     }
 }
 
+#[test]
+fn regex_conflicts() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let mut snapshot: WriteSnapshot<_> = storage.clone().open_snapshot_write();
+    let type_manager = type_manager_no_cache();
+    let thing_manager = thing_manager(type_manager.clone());
+
+    let name_type = type_manager.create_attribute_type(&mut snapshot, &Label::build("name", None)).unwrap();
+    name_type.set_value_type(&mut snapshot, &type_manager, &thing_manager, ValueType::String).unwrap();
+    name_type
+        .set_annotation(
+            &mut snapshot,
+            &type_manager,
+            &thing_manager,
+            AttributeTypeAnnotation::Regex(AnnotationRegex::new("^[a-z]+$".to_owned())),
+            StorageCounters::DISABLED,
+        )
+        .unwrap();
+    let nickname_type = type_manager.create_attribute_type(&mut snapshot, &Label::build("nickname", None)).unwrap();
+    nickname_type.set_supertype(&mut snapshot, &type_manager, &thing_manager, name_type).unwrap();
+
+    // No value can match both regexes
+    assert!(nickname_type
+        .set_annotation(
+            &mut snapshot,
+            &type_manager,
+            &thing_manager,
+            AttributeTypeAnnotation::Regex(AnnotationRegex::new("^[0-9]+$".to_owned())),
+            StorageCounters::DISABLED,
+        )
+        .is_err());
+    // Values such as "bob" match both regexes
+    nickname_type
+        .set_annotation(
+            &mut snapshot,
+            &type_manager,
+            &thing_manager,
+            AttributeTypeAnnotation::Regex(AnnotationRegex::new("b".to_owned())),
+            StorageCounters::DISABLED,
+        )
+        .unwrap();
+
+    let person_type = type_manager.create_entity_type(&mut snapshot, &Label::build("person", None)).unwrap();
+    let name_owns = person_type
+        .set_owns(
+            &mut snapshot,
+            &type_manager,
+            &thing_manager,
+            name_type,
+            Ordering::Unordered,
+            StorageCounters::DISABLED,
+        )
+        .unwrap();
+    assert!(name_owns
+        .set_annotation(
+            &mut snapshot,
+            &type_manager,
+            &thing_manager,
+            OwnsAnnotation::Regex(AnnotationRegex::new("^[A-Z]".to_owned())),
+        )
+        .is_err());
+    name_owns
+        .set_annotation(
+            &mut snapshot,
+            &type_manager,
+            &thing_manager,
+            OwnsAnnotation::Regex(AnnotationRegex::new("^[a-c]".to_owned())),
+        )
+        .unwrap();
+}
+
 #[test]
 fn test_struct_definition() {
     let (_tmp_dir, mut storage) = create_core_storage();
@@ -41,7 +41,7 @@ use crate::{
         thing_manager::ThingManager,
         HKInstance, ThingAPI,
     },
-    type_::{attribute_type::AttributeType, ObjectTypeAPI},
+    type_::{attribute_type::AttributeType, object_type::ObjectType, ObjectTypeAPI},
     ConceptAPI, ConceptStatus,
 };
 
@@ -111,6 +111,16 @@ impl Attribute {
         thing_manager.get_owners_by_type(snapshot, self, owner_type, storage_counters)
     }
 
+    pub fn get_owners_by_types(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        owner_types: impl IntoIterator<Item = ObjectType>,
+        storage_counters: StorageCounters,
+    ) -> impl Iterator<Item = Result<(Object, u64), Box<ConceptReadError>>> {
+        thing_manager.get_owners_by_types(snapshot, self, owner_types, storage_counters)
+    }
+
     pub fn next_possible(&self) -> Attribute {
         let mut bytes = self.vertex.to_bytes().into_array();
         bytes.increment().unwrap();
@@ -167,7 +177,7 @@ impl ThingAPI for Attribute {
         thing_manager: &ThingManager,
         storage_counters: StorageCounters,
     ) -> Result<(), Box<ConceptWriteError>> {
-        for object in self.get_owners(snapshot, thing_manager, StorageCounters::DISABLED).map_ok(|(key, _)| key) {
+        for object in self.get_owners(snapshot, thing_manager, storage_counters.clone()).map_ok(|(key, _)| key) {
             thing_manager.unset_has(snapshot, object?, &self, storage_counters.clone())?;
         }
         thing_manager.delete_attribute(snapshot, self, storage_counters)?;
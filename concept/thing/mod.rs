@@ -30,6 +30,7 @@ pub mod has;
 pub mod object;
 pub mod relation;
 pub mod statistics;
+pub mod statistics_sampler;
 mod r#struct;
 pub mod thing_manager;
 
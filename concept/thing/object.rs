@@ -28,7 +28,7 @@ use resource::{
 };
 use storage::{
     key_value::StorageKey,
-    snapshot::{ReadableSnapshot, WritableSnapshot},
+    snapshot::{iterator::SnapshotRangeIterator, ReadableSnapshot, WritableSnapshot},
 };
 
 use crate::{
@@ -246,6 +246,16 @@ pub trait ObjectAPI: ThingAPI<Vertex = ObjectVertex> + Copy + fmt::Debug {
         )
     }
 
+    fn get_has_of_value_type(
+        self,
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        value_type_category: ValueTypeCategory,
+        storage_counters: StorageCounters,
+    ) -> Result<impl Iterator<Item = Result<(Attribute, u64), Box<ConceptReadError>>>, Box<ConceptReadError>> {
+        thing_manager.owner_get_has_unordered_of_value_type(snapshot, self, value_type_category, storage_counters)
+    }
+
     fn set_has_unordered(
         self,
         snapshot: &mut impl WritableSnapshot,
@@ -284,8 +294,10 @@ pub trait ObjectAPI: ThingAPI<Vertex = ObjectVertex> + Copy + fmt::Debug {
             Ordering::Ordered => return Err(Box::new(ConceptWriteError::SetHasUnorderedOwnsOrdered {})),
         }
 
-        OperationTimeValidation::validate_owns_is_not_abstract(snapshot, thing_manager, self, attribute.type_())
-            .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        if !thing_manager.defers_validation() {
+            OperationTimeValidation::validate_owns_is_not_abstract(snapshot, thing_manager, self, attribute.type_())
+                .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        }
 
         thing_manager.set_has_unordered(snapshot, self, attribute, storage_counters)
     }
@@ -353,8 +365,10 @@ pub trait ObjectAPI: ThingAPI<Vertex = ObjectVertex> + Copy + fmt::Debug {
             Ordering::Ordered => (),
         }
 
-        OperationTimeValidation::validate_owns_is_not_abstract(snapshot, thing_manager, self, attribute_type)
-            .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        if !thing_manager.defers_validation() {
+            OperationTimeValidation::validate_owns_is_not_abstract(snapshot, thing_manager, self, attribute_type)
+                .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        }
 
         let mut new_counts = BTreeMap::<_, u64>::new();
         for attribute in &new_attributes {
@@ -650,3 +664,98 @@ edge_iterator!(
     storage_key_has_reverse_edge_to_has,
     has_to_reverse_edge_storage_key
 );
+
+/// Iterates the ownerships of a sorted batch of owners in a single range scan,
+/// seeking over the owners in between instead of opening one scan per owner.
+pub struct HasOwnersIterator {
+    snapshot_iterator: Option<SnapshotRangeIterator>,
+    owners: Vec<Object>,
+    owner_index: usize,
+}
+
+impl HasOwnersIterator {
+    pub(crate) fn new(snapshot_iterator: SnapshotRangeIterator, owners: Vec<Object>) -> Self {
+        debug_assert!(owners.windows(2).all(|pair| pair[0] < pair[1]));
+        Self { snapshot_iterator: Some(snapshot_iterator), owners, owner_index: 0 }
+    }
+
+    pub fn new_empty() -> Self {
+        Self { snapshot_iterator: None, owners: Vec::new(), owner_index: 0 }
+    }
+
+    /// Positions the underlying iterator on an ownership of a batch owner, returning false once none remain.
+    fn find_batch_owner(&mut self) -> Result<bool, Box<ConceptReadError>> {
+        let Some(iterator) = self.snapshot_iterator.as_mut() else {
+            return Ok(false);
+        };
+        loop {
+            let owner = match iterator.peek() {
+                None => return Ok(false),
+                Some(Err(error)) => {
+                    return Err(Box::new(ConceptReadError::SnapshotIterate { source: error.clone() }));
+                }
+                Some(Ok((storage_key, _))) => {
+                    Object::new(ThingEdgeHas::decode(Bytes::Reference(storage_key.bytes())).from())
+                }
+            };
+            while self.owners.get(self.owner_index).is_some_and(|batch_owner| *batch_owner < owner) {
+                self.owner_index += 1;
+            }
+            match self.owners.get(self.owner_index) {
+                None => {
+                    self.snapshot_iterator = None;
+                    return Ok(false);
+                }
+                Some(batch_owner) if *batch_owner == owner => return Ok(true),
+                Some(batch_owner) => {
+                    iterator.seek(ThingEdgeHas::prefix_from_object(batch_owner.vertex()).as_reference())
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for HasOwnersIterator {
+    type Item = Result<(Has, u64), Box<ConceptReadError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use lending_iterator::LendingIterator;
+        match self.find_batch_owner() {
+            Ok(false) => None,
+            Err(error) => Some(Err(error)),
+            Ok(true) => self.snapshot_iterator.as_mut()?.next().map(|result| {
+                result
+                    .map(|(storage_key, value_bytes)| storage_key_has_edge_to_has(storage_key, value_bytes))
+                    .map_err(|error| Box::new(ConceptReadError::SnapshotIterate { source: error }))
+            }),
+        }
+    }
+}
+
+impl lending_iterator::LendingIterator for HasOwnersIterator {
+    type Item<'a> = Result<(Has, u64), Box<ConceptReadError>>;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        Iterator::next(self)
+    }
+}
+
+impl lending_iterator::Seekable<Result<(Has, u64), Box<ConceptReadError>>> for HasOwnersIterator {
+    fn seek(&mut self, key: &Result<(Has, u64), Box<ConceptReadError>>) {
+        if let (Ok(key), Some(iterator)) = (key, self.snapshot_iterator.as_mut()) {
+            iterator.seek(has_to_edge_storage_key(key).as_reference());
+        }
+    }
+
+    fn compare_key(
+        &self,
+        item: &Self::Item<'_>,
+        other_item: &Result<(Has, u64), Box<ConceptReadError>>,
+    ) -> std::cmp::Ordering {
+        match (item, other_item) {
+            (Ok(item), Ok(other_item)) => has_to_edge_storage_key(item).cmp(&has_to_edge_storage_key(other_item)),
+            // arbitrary
+            _ => std::cmp::Ordering::Equal,
+        }
+    }
+}
@@ -92,8 +92,17 @@ impl Relation {
         thing_manager.get_role_players(snapshot, self, storage_counters)
     }
 
-    // TODO: It is basically the same as `get_players_role_type`, but with counts. Do we need to return counts?
-    // `has`-related Object's methods return counts. Please refactor when working on lists.
+    /// Iterates the players from `player_start` onwards (inclusive), so that callers can page through large relations.
+    pub fn get_players_from(
+        self,
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        player_start: Object,
+        storage_counters: StorageCounters,
+    ) -> impl Iterator<Item = Result<(RolePlayer, u64), Box<ConceptReadError>>> {
+        thing_manager.get_role_players_from(snapshot, self, player_start, storage_counters)
+    }
+
     pub fn get_players_by_role(
         self,
         snapshot: &impl ReadableSnapshot,
@@ -121,12 +130,19 @@ impl Relation {
         role_type: RoleType,
         storage_counters: StorageCounters,
     ) -> impl Iterator<Item = Result<Object, Box<ConceptReadError>>> {
-        self.get_players(snapshot, thing_manager, storage_counters).filter_map::<Result<Object, _>, _>(move |res| {
-            match res {
-                Ok((roleplayer, _count)) => (roleplayer.role_type() == role_type).then_some(Ok(roleplayer.player)),
-                Err(error) => Some(Err(error)),
-            }
-        })
+        self.get_players_by_role(snapshot, thing_manager, role_type, storage_counters)
+            .map(|res| res.map(|(role_player, _count)| role_player.player))
+    }
+
+    /// Counts the players of a role, including repeated players, without iterating players of other roles.
+    pub fn count_players(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        role_type: RoleType,
+        storage_counters: StorageCounters,
+    ) -> Result<u64, Box<ConceptReadError>> {
+        thing_manager.count_role_players(snapshot, *self, role_type, storage_counters)
     }
 
     pub fn get_player_counts(
@@ -191,11 +207,13 @@ impl Relation {
         )
         .map_err(|error| Box::new(ConceptWriteError::DataValidation { typedb_source: error }))?;
 
-        OperationTimeValidation::validate_relates_is_not_abstract(snapshot, thing_manager, self, role_type)
-            .map_err(|error| Box::new(ConceptWriteError::DataValidation { typedb_source: error }))?;
+        if !thing_manager.defers_validation() {
+            OperationTimeValidation::validate_relates_is_not_abstract(snapshot, thing_manager, self, role_type)
+                .map_err(|error| Box::new(ConceptWriteError::DataValidation { typedb_source: error }))?;
 
-        OperationTimeValidation::validate_plays_is_not_abstract(snapshot, thing_manager, player, role_type)
-            .map_err(|error| Box::new(ConceptWriteError::DataValidation { typedb_source: error }))?;
+            OperationTimeValidation::validate_plays_is_not_abstract(snapshot, thing_manager, player, role_type)
+                .map_err(|error| Box::new(ConceptWriteError::DataValidation { typedb_source: error }))?;
+        }
 
         let distinct = self.type_().is_related_role_type_distinct(snapshot, thing_manager.type_manager(), role_type)?;
         if distinct {
@@ -234,8 +252,10 @@ impl Relation {
         )
         .map_err(|error| Box::new(ConceptWriteError::DataValidation { typedb_source: error }))?;
 
-        OperationTimeValidation::validate_relates_is_not_abstract(snapshot, thing_manager, self, role_type)
-            .map_err(|error| Box::new(ConceptWriteError::DataValidation { typedb_source: error }))?;
+        if !thing_manager.defers_validation() {
+            OperationTimeValidation::validate_relates_is_not_abstract(snapshot, thing_manager, self, role_type)
+                .map_err(|error| Box::new(ConceptWriteError::DataValidation { typedb_source: error }))?;
+        }
 
         let mut new_counts = HashMap::<_, u64>::new();
         for &player in &new_players {
@@ -247,8 +267,10 @@ impl Relation {
             )
             .map_err(|error| Box::new(ConceptWriteError::DataValidation { typedb_source: error }))?;
 
-            OperationTimeValidation::validate_plays_is_not_abstract(snapshot, thing_manager, player, role_type)
-                .map_err(|error| Box::new(ConceptWriteError::DataValidation { typedb_source: error }))?;
+            if !thing_manager.defers_validation() {
+                OperationTimeValidation::validate_plays_is_not_abstract(snapshot, thing_manager, player, role_type)
+                    .map_err(|error| Box::new(ConceptWriteError::DataValidation { typedb_source: error }))?;
+            }
 
             OperationTimeValidation::validate_role_player_exists_to_add_player(
                 snapshot,
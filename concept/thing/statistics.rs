@@ -125,6 +125,12 @@ impl Statistics {
         }
     }
 
+    /// The sequence number from which the WAL is read to bring these statistics up to date.
+    pub fn synchronisation_start(&self) -> SequenceNumber {
+        // make it a little more likely that we capture concurrent commits
+        DurabilitySequenceNumber::new(self.sequence_number.number().saturating_sub(Self::COMMIT_CONTEXT_SIZE).max(1))
+    }
+
     pub fn may_synchronise(&mut self, storage: &MVCCStorage<impl DurabilityClient>) -> Result<(), StatisticsError> {
         use StatisticsError::{DataRead, ReloadCommitData};
 
@@ -136,10 +142,7 @@ impl Statistics {
 
         let start = Instant::now();
 
-        // make it a little more likely that we capture concurrent commits
-        let load_start = DurabilitySequenceNumber::new(
-            self.sequence_number.number().saturating_sub(Self::COMMIT_CONTEXT_SIZE).max(1),
-        );
+        let load_start = self.synchronisation_start();
 
         let mut data_commits = BTreeMap::new();
         for (seq, status) in load_commit_data_from(load_start, storage.durability(), usize::MAX)
@@ -325,28 +328,28 @@ impl Statistics {
         self.links_index_counts.retain(|_, map| !map.is_empty());
     }
 
-    fn update_entities(&mut self, entity_type: EntityType, delta: i64) {
+    pub(super) fn update_entities(&mut self, entity_type: EntityType, delta: i64) {
         let count = self.entity_counts.entry(entity_type).or_default();
         *count = count.checked_add_signed(delta).unwrap();
         self.total_entity_count = self.total_entity_count.checked_add_signed(delta).unwrap();
         self.total_thing_count = self.total_thing_count.checked_add_signed(delta).unwrap();
     }
 
-    fn update_relations(&mut self, relation_type: RelationType, delta: i64) {
+    pub(super) fn update_relations(&mut self, relation_type: RelationType, delta: i64) {
         let count = self.relation_counts.entry(relation_type).or_default();
         *count = count.checked_add_signed(delta).unwrap();
         self.total_relation_count = self.total_relation_count.checked_add_signed(delta).unwrap();
         self.total_thing_count = self.total_thing_count.checked_add_signed(delta).unwrap();
     }
 
-    fn update_attributes(&mut self, attribute_type: AttributeType, delta: i64) {
+    pub(super) fn update_attributes(&mut self, attribute_type: AttributeType, delta: i64) {
         let count = self.attribute_counts.entry(attribute_type).or_default();
         *count = count.checked_add_signed(delta).unwrap();
         self.total_attribute_count = self.total_attribute_count.checked_add_signed(delta).unwrap();
         self.total_thing_count = self.total_thing_count.checked_add_signed(delta).unwrap();
     }
 
-    fn update_has(&mut self, owner_type: ObjectType, attribute_type: AttributeType, delta: i64) {
+    pub(super) fn update_has(&mut self, owner_type: ObjectType, attribute_type: AttributeType, delta: i64) {
         let attribute_count =
             self.has_attribute_counts.entry(owner_type).or_default().entry(attribute_type).or_default();
         *attribute_count = attribute_count.checked_add_signed(delta).unwrap();
@@ -355,7 +358,7 @@ impl Statistics {
         self.total_has_count = self.total_has_count.checked_add_signed(delta).unwrap();
     }
 
-    fn update_role_player(
+    pub(super) fn update_role_player(
         &mut self,
         player_type: ObjectType,
         role_type: RoleType,
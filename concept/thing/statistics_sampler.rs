@@ -0,0 +1,265 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{collections::HashMap, hash::Hash};
+
+use resource::profile::StorageCounters;
+use storage::{sequence_number::SequenceNumber, snapshot::ReadableSnapshot};
+
+use crate::{
+    error::ConceptReadError,
+    thing::{statistics::Statistics, thing_manager::ThingManager},
+    type_::{
+        attribute_type::AttributeType, entity_type::EntityType, object_type::ObjectType, relation_type::RelationType,
+        role_type::RoleType,
+    },
+};
+
+/// Recounts the instances, ownerships and role players of a few types at a time, and corrects the statistics where
+/// the counts maintained from commits have drifted from the data. Successive samples cycle through every type.
+///
+/// The snapshot must be opened at the sequence number of the statistics being corrected, so that the recounts and
+/// the commit deltas applied afterwards line up.
+#[derive(Debug, Default)]
+pub struct StatisticsSampler {
+    next_type_index: usize,
+    last_sample: Option<StatisticsSample>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SampledType {
+    Entity(EntityType),
+    Relation(RelationType),
+    Attribute(AttributeType),
+}
+
+#[derive(Debug, Clone)]
+pub struct StatisticsSample {
+    pub sequence_number: SequenceNumber,
+    pub types: Vec<TypeSample>,
+    corrections: Vec<StatisticsCorrection>,
+}
+
+impl StatisticsSample {
+    pub fn corrected(&self) -> bool {
+        !self.corrections.is_empty()
+    }
+
+    /// Applies the corrections found by this sample to statistics at the same or a later sequence number.
+    /// Commit deltas never undo a drift, so it carries over to later statistics unchanged.
+    pub fn apply_corrections(&self, statistics: &mut Statistics) {
+        debug_assert!(statistics.sequence_number >= self.sequence_number);
+        for correction in &self.corrections {
+            correction.apply(statistics);
+        }
+    }
+}
+
+/// The change needed to bring a drifted count in line with the recount.
+#[derive(Debug, Clone, Copy)]
+enum StatisticsCorrection {
+    Entities(EntityType, i64),
+    Relations(RelationType, i64),
+    Attributes(AttributeType, i64),
+    Has(ObjectType, AttributeType, i64),
+    RolePlayer(ObjectType, RoleType, RelationType, i64),
+}
+
+impl StatisticsCorrection {
+    fn apply(self, statistics: &mut Statistics) {
+        match self {
+            Self::Entities(entity_type, delta) => statistics.update_entities(entity_type, delta),
+            Self::Relations(relation_type, delta) => statistics.update_relations(relation_type, delta),
+            Self::Attributes(attribute_type, delta) => statistics.update_attributes(attribute_type, delta),
+            Self::Has(owner_type, attribute_type, delta) => statistics.update_has(owner_type, attribute_type, delta),
+            Self::RolePlayer(player_type, role_type, relation_type, delta) => {
+                statistics.update_role_player(player_type, role_type, relation_type, delta)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TypeSample {
+    pub type_: SampledType,
+    pub recorded_instances: u64,
+    pub counted_instances: u64,
+    /// The number of ownership and role player counts of the type that were found to have drifted.
+    pub corrected_edge_counts: usize,
+}
+
+impl TypeSample {
+    pub fn corrected(&self) -> bool {
+        self.recorded_instances != self.counted_instances || self.corrected_edge_counts != 0
+    }
+}
+
+impl StatisticsSampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn last_sample(&self) -> Option<&StatisticsSample> {
+        self.last_sample.as_ref()
+    }
+
+    pub fn sample(
+        &mut self,
+        statistics: &mut Statistics,
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        types_per_sample: usize,
+    ) -> Result<&StatisticsSample, Box<ConceptReadError>> {
+        debug_assert_eq!(snapshot.open_sequence_number(), statistics.sequence_number);
+        let type_manager = thing_manager.type_manager();
+        let mut all_types = Vec::new();
+        all_types.extend(type_manager.get_entity_types(snapshot)?.into_iter().map(SampledType::Entity));
+        all_types.extend(type_manager.get_relation_types(snapshot)?.into_iter().map(SampledType::Relation));
+        all_types.extend(type_manager.get_attribute_types(snapshot)?.into_iter().map(SampledType::Attribute));
+
+        let mut types = Vec::new();
+        let mut corrections = Vec::new();
+        if !all_types.is_empty() {
+            let start = self.next_type_index % all_types.len();
+            for &type_ in all_types.iter().cycle().skip(start).take(types_per_sample.min(all_types.len())) {
+                types.push(Self::sample_type(statistics, snapshot, thing_manager, type_, &mut corrections)?);
+            }
+            self.next_type_index = start + types.len();
+        }
+        let sample = StatisticsSample { sequence_number: statistics.sequence_number, types, corrections };
+        sample.apply_corrections(statistics);
+        Ok(self.last_sample.insert(sample))
+    }
+
+    fn sample_type(
+        statistics: &Statistics,
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        type_: SampledType,
+        corrections: &mut Vec<StatisticsCorrection>,
+    ) -> Result<TypeSample, Box<ConceptReadError>> {
+        let storage_counters = StorageCounters::DISABLED;
+        let (recorded_instances, counted_instances, corrected_edge_counts) = match type_ {
+            SampledType::Entity(entity_type) => {
+                let recorded = statistics.entity_counts.get(&entity_type).copied().unwrap_or(0);
+                let counted = count(thing_manager.get_entities_in(snapshot, entity_type, storage_counters))?;
+                if recorded != counted {
+                    corrections.push(StatisticsCorrection::Entities(entity_type, delta(recorded, counted)));
+                }
+                let corrected_has = Self::sample_has(
+                    statistics,
+                    snapshot,
+                    thing_manager,
+                    ObjectType::Entity(entity_type),
+                    corrections,
+                )?;
+                (recorded, counted, corrected_has)
+            }
+            SampledType::Relation(relation_type) => {
+                let recorded = statistics.relation_counts.get(&relation_type).copied().unwrap_or(0);
+                let counted = count(thing_manager.get_relations_in(snapshot, relation_type, storage_counters))?;
+                if recorded != counted {
+                    corrections.push(StatisticsCorrection::Relations(relation_type, delta(recorded, counted)));
+                }
+                let owner_type = ObjectType::Relation(relation_type);
+                let corrected_has = Self::sample_has(statistics, snapshot, thing_manager, owner_type, corrections)?;
+                let corrected_links =
+                    Self::sample_links(statistics, snapshot, thing_manager, relation_type, corrections)?;
+                (recorded, counted, corrected_has + corrected_links)
+            }
+            SampledType::Attribute(attribute_type) => {
+                let recorded = statistics.attribute_counts.get(&attribute_type).copied().unwrap_or(0);
+                let counted = count(thing_manager.get_attributes_in(snapshot, attribute_type, storage_counters)?)?;
+                if recorded != counted {
+                    corrections.push(StatisticsCorrection::Attributes(attribute_type, delta(recorded, counted)));
+                }
+                (recorded, counted, 0)
+            }
+        };
+        Ok(TypeSample { type_, recorded_instances, counted_instances, corrected_edge_counts })
+    }
+
+    fn sample_has(
+        statistics: &Statistics,
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        owner_type: ObjectType,
+        corrections: &mut Vec<StatisticsCorrection>,
+    ) -> Result<usize, Box<ConceptReadError>> {
+        let mut counted: HashMap<AttributeType, u64> = HashMap::new();
+        let iterator = thing_manager.get_has_from_owner_type_range_unordered(
+            snapshot,
+            &(owner_type..=owner_type),
+            StorageCounters::DISABLED,
+        );
+        for result in iterator {
+            let (has, _) = result?;
+            *counted.entry(has.attribute().type_()).or_default() += 1;
+        }
+        let recorded = statistics.has_attribute_counts.get(&owner_type).cloned().unwrap_or_default();
+        let drifted = drifted_counts(&recorded, &counted);
+        corrections.extend(
+            drifted.iter().map(|&(attribute_type, delta)| StatisticsCorrection::Has(owner_type, attribute_type, delta)),
+        );
+        Ok(drifted.len())
+    }
+
+    fn sample_links(
+        statistics: &Statistics,
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        relation_type: RelationType,
+        corrections: &mut Vec<StatisticsCorrection>,
+    ) -> Result<usize, Box<ConceptReadError>> {
+        let mut counted: HashMap<(RoleType, ObjectType), u64> = HashMap::new();
+        let iterator = thing_manager.get_links_by_relation_type_range(
+            snapshot,
+            &(relation_type..=relation_type),
+            StorageCounters::DISABLED,
+        );
+        for result in iterator {
+            let (links, _) = result?;
+            *counted.entry((links.role_type(), links.player().type_())).or_default() += 1;
+        }
+        let recorded: HashMap<(RoleType, ObjectType), u64> = statistics
+            .relation_role_player_counts
+            .get(&relation_type)
+            .into_iter()
+            .flat_map(|role_players| {
+                role_players.iter().flat_map(|(&role_type, players)| {
+                    players.iter().map(move |(&player_type, &count)| ((role_type, player_type), count))
+                })
+            })
+            .collect();
+        let drifted = drifted_counts(&recorded, &counted);
+        corrections.extend(drifted.iter().map(|&((role_type, player_type), delta)| {
+            StatisticsCorrection::RolePlayer(player_type, role_type, relation_type, delta)
+        }));
+        Ok(drifted.len())
+    }
+}
+
+fn count<T>(iterator: impl Iterator<Item = Result<T, Box<ConceptReadError>>>) -> Result<u64, Box<ConceptReadError>> {
+    iterator.try_fold(0, |count, result| result.map(|_| count + 1))
+}
+
+fn delta(recorded: u64, counted: u64) -> i64 {
+    counted as i64 - recorded as i64
+}
+
+fn drifted_counts<K: Copy + Hash + Eq>(recorded: &HashMap<K, u64>, counted: &HashMap<K, u64>) -> Vec<(K, i64)> {
+    let recorded_keys = recorded.keys().filter(|key| !counted.contains_key(key));
+    counted
+        .keys()
+        .chain(recorded_keys)
+        .map(|key| {
+            let recorded = recorded.get(key).copied().unwrap_or(0);
+            let counted = counted.get(key).copied().unwrap_or(0);
+            (*key, delta(recorded, counted))
+        })
+        .filter(|&(_, delta)| delta != 0)
+        .collect()
+}
@@ -6,10 +6,13 @@
 
 use std::{
     borrow::Cow,
-    collections::{Bound, HashMap, HashSet},
+    collections::{BTreeSet, Bound, HashMap, HashSet},
     iter::{once, Map},
+    num::NonZeroUsize,
     ops::RangeBounds,
     sync::Arc,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use bytes::{byte_array::ByteArray, util::increment, Bytes};
@@ -17,7 +20,10 @@ use encoding::{
     graph::{
         thing::{
             edge::{ThingEdgeHas, ThingEdgeHasReverse, ThingEdgeIndexedRelation, ThingEdgeLinks},
-            property::{build_object_vertex_property_has_order, build_object_vertex_property_links_order},
+            property::{
+                build_object_vertex_property_creation_time, build_object_vertex_property_has_order,
+                build_object_vertex_property_links_order,
+            },
             vertex_attribute::{AttributeID, AttributeVertex},
             vertex_generator::ThingVertexGenerator,
             vertex_object::ObjectVertex,
@@ -54,6 +60,7 @@ use lending_iterator::{LendingIterator, Peekable};
 use primitive::either::Either;
 use resource::{
     constants::{
+        concept::COMMIT_VALIDATION_SHARD_SIZE_MIN,
         encoding::StructFieldIDUInt,
         snapshot::{BUFFER_KEY_INLINE, BUFFER_VALUE_INLINE},
     },
@@ -67,18 +74,19 @@ use storage::{
 
 use crate::{
     error::{ConceptReadError, ConceptWriteError},
-    iterator::InstanceIterator,
+    iterator::{InstanceIterator, ReverseInstanceIterator},
     thing::{
         attribute::{Attribute, AttributeIterator},
         decode_attribute_ids, decode_role_players, encode_attribute_ids, encode_role_players,
         entity::Entity,
         has::Has,
-        object::{HasIterator, HasReverseIterator, Object, ObjectAPI},
+        object::{HasIterator, HasOwnersIterator, HasReverseIterator, Object, ObjectAPI},
         r#struct::StructIndexForAttributeTypeIterator,
         relation::{IndexedRelationsIterator, LinksIterator, LinksReverseIterator, Relation, RolePlayer},
         statistics::Statistics,
         thing_manager::validation::{
             cardinality_validation::{collect_errors, CardinalityChangeTracker, CardinalityValidation},
+            deferred_validation::DeferredModifications,
             operation_time_validation::OperationTimeValidation,
             DataValidationError,
         },
@@ -93,7 +101,7 @@ use crate::{
         relation_type::RelationType,
         role_type::RoleType,
         type_manager::TypeManager,
-        Capability, ObjectTypeAPI, OwnerAPI, PlayerAPI, TypeAPI,
+        Capability, ObjectTypeAPI, OwnerAPI, PlayerAPI, TimeToLive, TypeAPI,
     },
     ConceptStatus,
 };
@@ -105,6 +113,7 @@ pub struct ThingManager {
     vertex_generator: Arc<ThingVertexGenerator>,
     type_manager: Arc<TypeManager>,
     statistics: Arc<Statistics>,
+    defers_validation: bool,
 }
 
 impl ThingManager {
@@ -113,7 +122,18 @@ impl ThingManager {
         type_manager: Arc<TypeManager>,
         statistics: Arc<Statistics>,
     ) -> Self {
-        ThingManager { vertex_generator, type_manager, statistics }
+        ThingManager { vertex_generator, type_manager, statistics, defers_validation: false }
+    }
+
+    /// Defers the abstractness validation of written instances and capabilities, and the value and uniqueness
+    /// constraints of written attributes and has edges, to commit time, so that bulk loads skip the per-write lookups.
+    /// Cardinalities are always validated at commit time.
+    pub fn with_deferred_validation(self) -> Self {
+        Self { defers_validation: true, ..self }
+    }
+
+    pub(crate) fn defers_validation(&self) -> bool {
+        self.defers_validation
     }
 
     pub fn statistics(&self) -> &Statistics {
@@ -145,6 +165,52 @@ impl ThingManager {
         InstanceIterator::new(snapshot_iterator)
     }
 
+    /// Like `get_instances_in`, but resuming strictly after `after`, which must be an instance of `thing_type`.
+    fn get_instances_in_after<T: ThingAPI>(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        thing_type: T::TypeAPI,
+        after: &T,
+        keyspace: EncodingKeyspace,
+        storage_counters: StorageCounters,
+    ) -> InstanceIterator<T> {
+        if thing_type.is_abstract(snapshot, self.type_manager()).unwrap() {
+            return InstanceIterator::empty();
+        }
+
+        let prefix = <T as ThingAPI>::prefix_for_type(thing_type);
+        let storage_key_prefix =
+            <T as ThingAPI>::Vertex::build_prefix_type(prefix, thing_type.vertex().type_id_(), keyspace);
+        let key_range = KeyRange::new(
+            RangeStart::ExcludeFirstWithPrefix(after.vertex().into_storage_key().resize_to::<BUFFER_KEY_INLINE>()),
+            RangeEnd::EndPrefixInclusive(storage_key_prefix.resize_to::<BUFFER_KEY_INLINE>()),
+            prefix.fixed_width_keys(),
+        );
+        InstanceIterator::new(snapshot.iterate_range(&key_range, storage_counters))
+    }
+
+    /// Like `get_instances_in`, but in descending order.
+    fn get_instances_in_reverse<T: ThingAPI>(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        thing_type: T::TypeAPI,
+        keyspace: EncodingKeyspace,
+        storage_counters: StorageCounters,
+    ) -> ReverseInstanceIterator<T> {
+        if thing_type.is_abstract(snapshot, self.type_manager()).unwrap() {
+            return ReverseInstanceIterator::empty();
+        }
+
+        let prefix = <T as ThingAPI>::prefix_for_type(thing_type);
+        let storage_key_prefix =
+            <T as ThingAPI>::Vertex::build_prefix_type(prefix, thing_type.vertex().type_id_(), keyspace);
+        let snapshot_iterator = snapshot.iterate_range_reverse(
+            &KeyRange::new_within(storage_key_prefix, prefix.fixed_width_keys()),
+            storage_counters,
+        );
+        ReverseInstanceIterator::new(snapshot_iterator)
+    }
+
     fn get_instances<T: ThingAPI>(
         &self,
         keyspace: EncodingKeyspace,
@@ -216,6 +282,46 @@ impl ThingManager {
         self.get_instances_in(snapshot, object_type, <Object as ThingAPI>::Vertex::KEYSPACE, storage_counters)
     }
 
+    /// Returns the instances of exactly `object_type` inserted by this snapshot.
+    pub fn get_objects_inserted(&self, snapshot: &impl WritableSnapshot, object_type: ObjectType) -> Vec<Object> {
+        let prefix = <Object as ThingAPI>::prefix_for_type(object_type);
+        let storage_key_prefix =
+            ObjectVertex::build_prefix_type(prefix, object_type.vertex().type_id_(), ObjectVertex::KEYSPACE);
+        snapshot
+            .iterate_writes_range(&KeyRange::new_within(storage_key_prefix, prefix.fixed_width_keys()))
+            .filter(|(_, write)| matches!(write, Write::Insert { .. }))
+            .map(|(key, _)| Object::new(ObjectVertex::decode(key.bytes())))
+            .collect()
+    }
+
+    /// Resumes `get_objects_in` strictly after `after`, so paginated scans need not skip over earlier pages.
+    pub fn get_objects_in_after(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        object_type: ObjectType,
+        after: Object,
+        storage_counters: StorageCounters,
+    ) -> InstanceIterator<Object> {
+        debug_assert_eq!(after.type_(), object_type);
+        self.get_instances_in_after(
+            snapshot,
+            object_type,
+            &after,
+            <Object as ThingAPI>::Vertex::KEYSPACE,
+            storage_counters,
+        )
+    }
+
+    /// Returns the instances of `object_type` from the last to the first, including those written by the snapshot.
+    pub fn get_objects_in_reverse(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        object_type: ObjectType,
+        storage_counters: StorageCounters,
+    ) -> ReverseInstanceIterator<Object> {
+        self.get_instances_in_reverse(snapshot, object_type, <Object as ThingAPI>::Vertex::KEYSPACE, storage_counters)
+    }
+
     pub fn get_objects_in_range(
         &self,
         snapshot: &impl ReadableSnapshot,
@@ -369,6 +475,44 @@ impl ThingManager {
         ))
     }
 
+    /// Resumes `get_attributes_in` strictly after `after`, which must be an instance of `attribute_type`.
+    pub fn get_attributes_in_after(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        attribute_type: AttributeType,
+        after: &Attribute,
+        storage_counters: StorageCounters,
+    ) -> Result<AttributeIterator<InstanceIterator<Attribute>>, Box<ConceptReadError>> {
+        debug_assert_eq!(after.type_(), attribute_type);
+        let attribute_value_type =
+            attribute_type.get_value_type_without_source(snapshot, self.type_manager.as_ref())?;
+        let Some(value_type) = attribute_value_type.as_ref() else {
+            return Ok(AttributeIterator::new_empty());
+        };
+
+        // Owners of earlier attributes are irrelevant, so the has reverse edges also resume after `after`
+        let has_reverse_start = ThingEdgeHasReverse::prefix_from_attribute(after.vertex());
+        let has_reverse_end =
+            ThingEdgeHasReverse::prefix_from_attribute_type(value_type.category(), attribute_type.vertex().type_id_());
+        let range = KeyRange::new(
+            RangeStart::ExcludePrefix(has_reverse_start.resize_to::<BUFFER_KEY_INLINE>()),
+            RangeEnd::EndPrefixInclusive(has_reverse_end.resize_to::<BUFFER_KEY_INLINE>()),
+            ThingEdgeHasReverse::FIXED_WIDTH_ENCODING,
+        );
+        let has_reverse_iterator = HasReverseIterator::new(snapshot.iterate_range(&range, storage_counters.clone()));
+        Ok(AttributeIterator::new(
+            self.get_instances_in_after(
+                snapshot,
+                attribute_type,
+                after,
+                AttributeVertex::keyspace_for_category(value_type.category()),
+                storage_counters,
+            ),
+            has_reverse_iterator,
+            self.type_manager().get_independent_attribute_types(snapshot)?,
+        ))
+    }
+
     pub fn get_attribute(
         &self,
         snapshot: &impl ReadableSnapshot,
@@ -771,6 +915,25 @@ impl ThingManager {
         HasIterator::new(snapshot.iterate_range(&key_range, storage_counters))
     }
 
+    /// Returns the ownerships of a sorted, deduplicated batch of owners in a single range scan,
+    /// ordered by owner then attribute.
+    pub fn get_has_from_owners_unordered(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        owners: Vec<Object>,
+        storage_counters: StorageCounters,
+    ) -> HasOwnersIterator {
+        let (Some(first), Some(last)) = (owners.first(), owners.last()) else {
+            return HasOwnersIterator::new_empty();
+        };
+        let key_range = KeyRange::new(
+            RangeStart::Inclusive(ThingEdgeHas::prefix_from_object(first.vertex())),
+            RangeEnd::EndPrefixInclusive(ThingEdgeHas::prefix_from_object(last.vertex())),
+            ThingEdgeHas::FIXED_WIDTH_ENCODING,
+        );
+        HasOwnersIterator::new(snapshot.iterate_range(&key_range, storage_counters), owners)
+    }
+
     pub fn get_has_reverse(
         &self,
         snapshot: &impl ReadableSnapshot,
@@ -1028,6 +1191,24 @@ impl ThingManager {
         Ok(HasIterator::new(snapshot.iterate_range(&key_range, storage_counters)))
     }
 
+    /// Resumes iterating the unordered attributes of `owner` strictly after the ownership of `after`.
+    pub fn get_has_from_thing_unordered_after(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        owner: impl ObjectAPI,
+        after: &Attribute,
+        storage_counters: StorageCounters,
+    ) -> HasIterator {
+        let start = ThingEdgeHas::new(owner.vertex(), after.vertex()).into_storage_key();
+        let end = ThingEdgeHas::prefix_from_object(owner.vertex());
+        let key_range = KeyRange::new(
+            RangeStart::ExcludeFirstWithPrefix(start.resize_to::<BUFFER_KEY_INLINE>()),
+            RangeEnd::EndPrefixInclusive(end.resize_to::<BUFFER_KEY_INLINE>()),
+            ThingEdgeHas::FIXED_WIDTH_ENCODING,
+        );
+        HasIterator::new(snapshot.iterate_range(&key_range, storage_counters))
+    }
+
     pub(crate) fn owner_get_has_unordered_in_value_type<'a>(
         &self,
         snapshot: &impl ReadableSnapshot,
@@ -1070,6 +1251,43 @@ impl ThingManager {
         Ok(HasIterator::new(snapshot.iterate_range(&key_range, storage_counters)))
     }
 
+    /// Seeks directly to the attributes of each attribute type the owner may own that has the value type category,
+    /// so attributes of other value types are never read.
+    pub(crate) fn owner_get_has_unordered_of_value_type(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        owner: impl ObjectAPI,
+        value_type_category: ValueTypeCategory,
+        storage_counters: StorageCounters,
+    ) -> Result<impl Iterator<Item = Result<(Attribute, u64), Box<ConceptReadError>>>, Box<ConceptReadError>> {
+        let mut attribute_types = BTreeSet::new();
+        for attribute_type in owner.type_().get_owned_attribute_types(snapshot, self.type_manager())? {
+            attribute_types.insert(attribute_type);
+            attribute_types.extend(attribute_type.get_subtypes_transitive(snapshot, self.type_manager())?.iter());
+        }
+        let mut iterators = Vec::new();
+        for attribute_type in attribute_types {
+            let value_type = attribute_type.get_value_type_without_source(snapshot, self.type_manager())?;
+            if !value_type.is_some_and(|value_type| value_type.category() == value_type_category) {
+                continue;
+            }
+            let attribute_prefix = AttributeVertex::build_prefix_type(
+                AttributeVertex::PREFIX,
+                attribute_type.vertex().type_id_(),
+                AttributeVertex::keyspace_for_category(value_type_category),
+            );
+            let has_prefix = ThingEdgeHas::prefix_from_object_to_type_with_attribute_prefix(
+                owner.vertex(),
+                attribute_prefix.bytes(),
+            );
+            let range = KeyRange::new_within(has_prefix, ThingEdgeHas::FIXED_WIDTH_ENCODING);
+            iterators.push(HasIterator::new(snapshot.iterate_range(&range, storage_counters.clone())));
+        }
+        Ok(iterators.into_iter().flatten().map(|result: Result<(Has, u64), Box<ConceptReadError>>| {
+            result.map(|(has, count)| (has.attribute(), count))
+        }))
+    }
+
     pub(crate) fn get_has_from_thing_to_type_unordered<'a>(
         &self,
         snapshot: &impl ReadableSnapshot,
@@ -1217,6 +1435,30 @@ impl ThingManager {
         )
     }
 
+    /// Seeks directly to the owners of each owner type, so owners of other types are never read.
+    /// The owner types are visited in order, so owners are returned in storage order.
+    pub(crate) fn get_owners_by_types(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        attribute: &Attribute,
+        owner_types: impl IntoIterator<Item = ObjectType>,
+        storage_counters: StorageCounters,
+    ) -> impl Iterator<Item = Result<(Object, u64), Box<ConceptReadError>>> {
+        let owner_types: BTreeSet<ObjectType> = owner_types.into_iter().collect();
+        let iterators: Vec<_> = owner_types
+            .into_iter()
+            .map(|owner_type| {
+                let prefix =
+                    ThingEdgeHasReverse::prefix_from_attribute_to_type(attribute.vertex(), owner_type.vertex());
+                HasReverseIterator::new(snapshot.iterate_range(
+                    &KeyRange::new_within(prefix, ThingEdgeHasReverse::FIXED_WIDTH_ENCODING),
+                    storage_counters.clone(),
+                ))
+            })
+            .collect();
+        iterators.into_iter().flatten().map(|result| result.map(|(has, count)| (has.owner(), count)))
+    }
+
     pub fn get_has_reverse_by_attribute_and_owner_type_range(
         &self,
         snapshot: &impl ReadableSnapshot,
@@ -1432,6 +1674,52 @@ impl ThingManager {
         )
     }
 
+    /// Like `get_role_players`, but resuming from `player_start` (inclusive), in the order of the players' vertices.
+    pub(crate) fn get_role_players_from(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        relation: Relation,
+        player_start: Object,
+        storage_counters: StorageCounters,
+    ) -> impl Iterator<Item = Result<(RolePlayer, u64), Box<ConceptReadError>>> {
+        let start = ThingEdgeLinks::prefix_from_relation_player(relation.vertex(), player_start.vertex());
+        let end = ThingEdgeLinks::prefix_from_relation(relation.vertex());
+        let key_range = KeyRange::new(
+            RangeStart::Inclusive(start.resize_to::<BUFFER_KEY_INLINE>()),
+            RangeEnd::EndPrefixInclusive(end.resize_to::<BUFFER_KEY_INLINE>()),
+            ThingEdgeLinks::FIXED_WIDTH_ENCODING,
+        );
+        Iterator::map(LinksIterator::new(snapshot.iterate_range(&key_range, storage_counters)), |result| {
+            result.map(|(links, count)| (links.into_role_player(), count))
+        })
+    }
+
+    /// Counts the players of a role by seeking to the player types which can play it,
+    /// skipping the relation's players of other types.
+    pub(crate) fn count_role_players(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        relation: Relation,
+        role_type: RoleType,
+        storage_counters: StorageCounters,
+    ) -> Result<u64, Box<ConceptReadError>> {
+        let player_types = role_type.get_player_types(snapshot, self.type_manager())?;
+        let mut count = 0;
+        for player_type in player_types.keys() {
+            let prefix = ThingEdgeLinks::prefix_from_relation_player_type(relation.vertex(), player_type.vertex());
+            let mut links_iterator = LinksIterator::new(snapshot.iterate_range(
+                &KeyRange::new_within(prefix, ThingEdgeLinks::FIXED_WIDTH_ENCODING),
+                storage_counters.clone(),
+            ));
+            while let Some((links, links_count)) = links_iterator.next().transpose()? {
+                if links.role_type() == role_type {
+                    count += links_count;
+                }
+            }
+        }
+        Ok(count)
+    }
+
     pub(crate) fn get_role_players_ordered(
         &self,
         snapshot: &impl ReadableSnapshot,
@@ -1783,7 +2071,7 @@ impl ThingManager {
         snapshot.unmodifiable_lock_add(attribute.vertex().into_storage_key().into_owned_array())
     }
 
-    pub fn finalise<Snapshot: WritableSnapshot>(
+    pub fn finalise<Snapshot: WritableSnapshot + Sync>(
         &self,
         snapshot: &mut Snapshot,
         storage_counters: StorageCounters,
@@ -1793,6 +2081,9 @@ impl ThingManager {
                 .map_err(|typedb_source| vec![ConceptWriteError::ConceptRead { typedb_source }])?;
 
         self.validate_cardinalities(snapshot, &cardinality_change_tracker, storage_counters.clone())?;
+        if self.defers_validation() {
+            self.validate_deferred(snapshot, storage_counters.clone())?;
+        }
 
         // For immutable schema, the indices are updated at operation time
         if !Snapshot::IMMUTABLE_SCHEMA {
@@ -1872,7 +2163,8 @@ impl ThingManager {
                     &*unique_constraint.source().attribute().vertex().to_bytes(),
                     attribute_key.attribute_id().bytes(),
                     &attribute_value,
-                    &*owner.vertex().to_bytes(),
+                    // The owner instance must not be part of the key: concurrent transactions giving the same value
+                    // to different owners have to take the same lock for one of them to fail at commit.
                     &*unique_constraint.source().owner().vertex().to_bytes(),
                 ]
                 .into_iter(),
@@ -2161,47 +2453,143 @@ impl ThingManager {
 
     fn validate_cardinalities(
         &self,
-        snapshot: &mut impl WritableSnapshot,
+        snapshot: &mut (impl WritableSnapshot + Sync),
         change_tracker: &CardinalityChangeTracker,
         storage_counters: StorageCounters,
     ) -> Result<(), Vec<ConceptWriteError>> {
+        let snapshot = &*snapshot;
         let mut errors = Vec::new();
 
-        for (object, modified_owns) in change_tracker.modified_objects_attribute_types() {
-            let res = CardinalityValidation::validate_object_has(
-                snapshot,
-                self,
-                *object,
-                &modified_owns,
-                &mut errors,
-                storage_counters.clone(),
-            );
-            collect_errors!(errors, res, |typedb_source| DataValidationError::ConceptRead { typedb_source });
-        }
+        Self::validate_in_shards(
+            change_tracker.modified_objects_attribute_types(),
+            &mut errors,
+            |object, modified_owns, errors| {
+                CardinalityValidation::validate_object_has(
+                    snapshot,
+                    self,
+                    *object,
+                    modified_owns,
+                    errors,
+                    storage_counters.clone(),
+                )
+            },
+        );
 
-        for (object, modified_plays) in change_tracker.modified_objects_role_types() {
-            let res = CardinalityValidation::validate_object_links(
-                snapshot,
-                self,
-                *object,
-                &modified_plays,
-                &mut errors,
-                storage_counters.clone(),
-            );
-            collect_errors!(errors, res, |typedb_source| DataValidationError::ConceptRead { typedb_source });
-        }
+        Self::validate_in_shards(
+            change_tracker.modified_objects_role_types(),
+            &mut errors,
+            |object, modified_plays, errors| {
+                CardinalityValidation::validate_object_links(
+                    snapshot,
+                    self,
+                    *object,
+                    modified_plays,
+                    errors,
+                    storage_counters.clone(),
+                )
+            },
+        );
 
-        for (relation, modified_relates) in change_tracker.modified_relations_role_types() {
-            let res = CardinalityValidation::validate_relation_links(
-                snapshot,
-                self,
-                *relation,
-                &modified_relates,
-                &mut errors,
-                storage_counters.clone(),
-            );
-            collect_errors!(errors, res, |typedb_source| DataValidationError::ConceptRead { typedb_source });
+        Self::validate_in_shards(
+            change_tracker.modified_relations_role_types(),
+            &mut errors,
+            |relation, modified_relates, errors| {
+                CardinalityValidation::validate_relation_links(
+                    snapshot,
+                    self,
+                    *relation,
+                    modified_relates,
+                    errors,
+                    storage_counters.clone(),
+                )
+            },
+        );
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors
+                .into_iter()
+                .map(|typedb_source| ConceptWriteError::DataValidation { typedb_source: Box::new(typedb_source) })
+                .collect())
         }
+    }
+
+    fn validate_deferred(
+        &self,
+        snapshot: &mut (impl WritableSnapshot + Sync),
+        storage_counters: StorageCounters,
+    ) -> Result<(), Vec<ConceptWriteError>> {
+        let snapshot = &*snapshot;
+        let modifications = DeferredModifications::collect(snapshot, self, storage_counters.clone())
+            .map_err(|typedb_source| vec![ConceptWriteError::ConceptRead { typedb_source }])?;
+        let mut errors = Vec::new();
+
+        modifications.validate_created_instances(snapshot, self, storage_counters.clone(), &mut errors);
+
+        Self::validate_in_shards(modifications.objects_attributes(), &mut errors, |owner, attributes, errors| {
+            for attribute_type in attributes.iter().map(Attribute::type_).collect::<HashSet<_>>() {
+                let result =
+                    OperationTimeValidation::validate_owns_is_not_abstract(snapshot, self, *owner, attribute_type);
+                collect_errors!(errors, result, |error: Box<DataValidationError>| *error);
+            }
+            for attribute in attributes {
+                let attribute_type = attribute.type_();
+                let value = attribute.get_value(snapshot, self, storage_counters.clone())?;
+                let result = OperationTimeValidation::validate_has_unique_constraint(
+                    snapshot,
+                    self,
+                    *owner,
+                    attribute_type,
+                    value.as_reference(),
+                    storage_counters.clone(),
+                );
+                collect_errors!(errors, result, |error: Box<DataValidationError>| *error);
+                let result = OperationTimeValidation::validate_has_regex_constraints(
+                    snapshot,
+                    self,
+                    *owner,
+                    attribute_type,
+                    value.as_reference(),
+                );
+                collect_errors!(errors, result, |error: Box<DataValidationError>| *error);
+                let result = OperationTimeValidation::validate_has_range_constraints(
+                    snapshot,
+                    self,
+                    *owner,
+                    attribute_type,
+                    value.as_reference(),
+                );
+                collect_errors!(errors, result, |error: Box<DataValidationError>| *error);
+                let result = OperationTimeValidation::validate_has_values_constraints(
+                    snapshot,
+                    self,
+                    *owner,
+                    attribute_type,
+                    value.as_reference(),
+                );
+                collect_errors!(errors, result, |error: Box<DataValidationError>| *error);
+            }
+            Ok(())
+        });
+
+        Self::validate_in_shards(modifications.objects_role_types(), &mut errors, |player, role_types, errors| {
+            for &role_type in role_types {
+                let result =
+                    OperationTimeValidation::validate_plays_is_not_abstract(snapshot, self, *player, role_type);
+                collect_errors!(errors, result, |error: Box<DataValidationError>| *error);
+            }
+            Ok(())
+        });
+
+        Self::validate_in_shards(modifications.relations_role_types(), &mut errors, |relation, role_types, errors| {
+            for &role_type in role_types {
+                let result =
+                    OperationTimeValidation::validate_relates_is_not_abstract(snapshot, self, *relation, role_type);
+                collect_errors!(errors, result, |error: Box<DataValidationError>| *error);
+            }
+            Ok(())
+        });
 
         if errors.is_empty() {
             Ok(())
@@ -2213,6 +2601,39 @@ impl ThingManager {
         }
     }
 
+    /// Validates the modified concepts in key order. Large modifications are split into contiguous shards
+    /// validated on separate threads, whose errors are concatenated in shard order,
+    /// so the errors reported do not depend on the number of threads.
+    fn validate_in_shards<K: Ord + Sync, V: Sync>(
+        modified: &HashMap<K, V>,
+        out_errors: &mut Vec<DataValidationError>,
+        validate: impl Fn(&K, &V, &mut Vec<DataValidationError>) -> Result<(), Box<ConceptReadError>> + Sync,
+    ) {
+        let modified = modified.iter().sorted_by_key(|(key, _)| *key).collect_vec();
+        let validate_shard = &|shard: &[(&K, &V)]| {
+            let mut errors = Vec::new();
+            for (key, value) in shard {
+                let res = validate(key, value, &mut errors);
+                collect_errors!(errors, res, |typedb_source| DataValidationError::ConceptRead { typedb_source });
+            }
+            errors
+        };
+
+        let parallelism = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        if parallelism == 1 || modified.len() < 2 * COMMIT_VALIDATION_SHARD_SIZE_MIN {
+            out_errors.extend(validate_shard(&modified));
+            return;
+        }
+        let shard_size = modified.len().div_ceil(parallelism).max(COMMIT_VALIDATION_SHARD_SIZE_MIN);
+        thread::scope(|scope| {
+            let shards =
+                modified.chunks(shard_size).map(|shard| scope.spawn(move || validate_shard(shard))).collect_vec();
+            for shard in shards {
+                out_errors.extend(shard.join().expect("Commit validation thread panicked"));
+            }
+        });
+    }
+
     fn update_relation_indices_on_schema_commit(
         &self,
         snapshot: &mut impl WritableSnapshot,
@@ -2259,6 +2680,207 @@ impl ThingManager {
         Ok(())
     }
 
+    /// Deletes every attribute of a non-independent type that has no owners in the snapshot,
+    /// returning the number of attributes deleted. `on_progress` is called with the number of
+    /// attribute types processed and the total.
+    pub fn delete_orphaned_attributes(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        mut on_progress: impl FnMut(u64, u64),
+        storage_counters: StorageCounters,
+    ) -> Result<u64, Box<ConceptWriteError>> {
+        let attribute_types = self
+            .type_manager()
+            .get_attribute_types(snapshot)
+            .map_err(|typedb_source| Box::new(ConceptWriteError::ConceptRead { typedb_source }))?;
+        let total = attribute_types.len() as u64;
+        let mut deleted = 0;
+        for (completed, attribute_type) in attribute_types.into_iter().enumerate() {
+            let is_independent = attribute_type.is_independent(snapshot, self.type_manager())?;
+            if let Some(value_type) = attribute_type.get_value_type_without_source(snapshot, self.type_manager())? {
+                if !is_independent {
+                    // attribute iterators hide unowned attributes, so the vertices are scanned directly
+                    let orphans: Vec<Attribute> = self
+                        .get_instances_in(
+                            snapshot,
+                            attribute_type,
+                            AttributeVertex::keyspace_for_category(value_type.category()),
+                            storage_counters.clone(),
+                        )
+                        .filter_ok(|attribute: &Attribute| !self.has_owners(snapshot, attribute, false))
+                        .try_collect()
+                        .map_err(|typedb_source| Box::new(ConceptWriteError::ConceptRead { typedb_source }))?;
+                    deleted += orphans.len() as u64;
+                    for attribute in orphans {
+                        self.delete_attribute(snapshot, attribute, storage_counters.clone())?;
+                    }
+                }
+            }
+            on_progress(completed as u64 + 1, total);
+        }
+        Ok(deleted)
+    }
+
+    /// Deletes the entities and relations which were created longer ago than the time to live of their type,
+    /// returning the number of objects deleted. `on_progress` is called with the number of types processed and the total.
+    /// Expired objects whose deletion would violate a cardinality constraint are kept, and the violations are
+    /// collected in `out_retained`.
+    pub fn delete_expired_objects(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        now: SystemTime,
+        mut on_progress: impl FnMut(u64, u64),
+        out_retained: &mut Vec<DataValidationError>,
+        storage_counters: StorageCounters,
+    ) -> Result<u64, Box<ConceptWriteError>> {
+        let entity_types = self.type_manager().get_entity_types(snapshot)?;
+        let relation_types = self.type_manager().get_relation_types(snapshot)?;
+        let object_types: Vec<ObjectType> = entity_types
+            .into_iter()
+            .map(EntityType::into_object_type)
+            .chain(relation_types.into_iter().map(RelationType::into_object_type))
+            .collect();
+        let total = object_types.len() as u64;
+        let now_millis = millis_since_epoch(now);
+        let mut deleted = 0;
+        for (completed, object_type) in object_types.into_iter().enumerate() {
+            if let Some(TimeToLive(seconds)) =
+                self.type_manager().get_object_type_time_to_live(snapshot, object_type)?
+            {
+                let expiry_millis = seconds.saturating_mul(1000);
+                let mut expired = Vec::new();
+                for object in self.get_objects_in(snapshot, object_type, storage_counters.clone()) {
+                    let object = object?;
+                    let created = self.get_creation_time(snapshot, object, storage_counters.clone())?;
+                    if created
+                        .is_some_and(|created| millis_since_epoch(created).saturating_add(expiry_millis) <= now_millis)
+                    {
+                        expired.push(object);
+                    }
+                }
+                for object in expired {
+                    // deletions so far are visible in the snapshot, so each object is checked against the rest
+                    match self.validate_object_deletion(snapshot, object, storage_counters.clone()) {
+                        Ok(()) => {
+                            object.delete(snapshot, self, storage_counters.clone())?;
+                            deleted += 1;
+                        }
+                        Err(error) => match *error {
+                            DataValidationError::ConceptRead { typedb_source } => {
+                                return Err(Box::new(ConceptWriteError::ConceptRead { typedb_source }))
+                            }
+                            violation => out_retained.push(violation),
+                        },
+                    }
+                }
+            }
+            on_progress(completed as u64 + 1, total);
+        }
+        Ok(deleted)
+    }
+
+    /// Checks that deleting `object` leaves the relations it plays in, and the players of `object` if it is
+    /// a relation, within their cardinality constraints.
+    fn validate_object_deletion(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        object: Object,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<DataValidationError>> {
+        let mut removed_from_relations: HashMap<Relation, HashMap<RoleType, u64>> = HashMap::new();
+        for relation_role in object.get_relations_roles(snapshot, self, storage_counters.clone()) {
+            let (relation, role_type, count) =
+                relation_role.map_err(|typedb_source| Box::new(DataValidationError::ConceptRead { typedb_source }))?;
+            *removed_from_relations.entry(relation).or_default().entry(role_type).or_default() += count;
+        }
+        for (relation, removed_role_counts) in removed_from_relations {
+            CardinalityValidation::validate_relation_links_after_removal(
+                snapshot,
+                self,
+                relation,
+                &removed_role_counts,
+                storage_counters.clone(),
+            )?;
+        }
+
+        if let Object::Relation(relation) = object {
+            let mut removed_from_players: HashMap<Object, HashMap<RoleType, u64>> = HashMap::new();
+            for role_player in relation.get_players(snapshot, self, storage_counters.clone()) {
+                let (role_player, count) = role_player
+                    .map_err(|typedb_source| Box::new(DataValidationError::ConceptRead { typedb_source }))?;
+                *removed_from_players
+                    .entry(role_player.player())
+                    .or_default()
+                    .entry(role_player.role_type())
+                    .or_default() += count;
+            }
+            for (player, removed_role_counts) in removed_from_players {
+                CardinalityValidation::validate_object_links_after_removal(
+                    snapshot,
+                    self,
+                    player,
+                    &removed_role_counts,
+                    storage_counters.clone(),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the role player index of every relation of `relation_type` if the type qualifies for it,
+    /// and removes it otherwise. `on_progress` is called with the number of relations processed and the total.
+    pub fn rebuild_relation_index(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        relation_type: RelationType,
+        on_progress: impl FnMut(u64, u64),
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        let qualifies_for_relation_index = relation_type
+            .schema_qualifies_for_relation_index(snapshot, self.type_manager())
+            .map_err(|typedb_source| Box::new(ConceptWriteError::ConceptRead { typedb_source }))?;
+        self.update_relation_type_index(
+            snapshot,
+            relation_type,
+            qualifies_for_relation_index,
+            on_progress,
+            storage_counters,
+        )
+    }
+
+    /// Builds or removes the role player index of every relation of `relation_type`.
+    pub(crate) fn update_relation_type_index(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        relation_type: RelationType,
+        qualifies_for_relation_index: bool,
+        mut on_progress: impl FnMut(u64, u64),
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        let role_types: HashSet<RoleType> = relation_type
+            .get_relates(snapshot, self.type_manager())
+            .map_err(|typedb_source| Box::new(ConceptWriteError::ConceptRead { typedb_source }))?
+            .iter()
+            .map(|relates| relates.role())
+            .collect();
+        let relations: Vec<Relation> = self
+            .get_relations_in(snapshot, relation_type, storage_counters.clone())
+            .try_collect()
+            .map_err(|typedb_source| Box::new(ConceptWriteError::ConceptRead { typedb_source }))?;
+        let total = relations.len() as u64;
+        for (completed, relation) in relations.into_iter().enumerate() {
+            self.update_relation_index_on_schema_commit(
+                snapshot,
+                relation,
+                &role_types,
+                qualifies_for_relation_index,
+                storage_counters.clone(),
+            )?;
+            on_progress(completed as u64 + 1, total);
+        }
+        Ok(())
+    }
+
     fn update_relation_index_on_schema_commit(
         &self,
         snapshot: &mut impl WritableSnapshot,
@@ -2300,10 +2922,14 @@ impl ThingManager {
         snapshot: &mut impl WritableSnapshot,
         entity_type: EntityType,
     ) -> Result<Entity, Box<ConceptWriteError>> {
-        OperationTimeValidation::validate_entity_type_is_not_abstract(snapshot, self, entity_type)
-            .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        if !self.defers_validation() {
+            OperationTimeValidation::validate_entity_type_is_not_abstract(snapshot, self, entity_type)
+                .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        }
 
-        Ok(Entity::new(self.vertex_generator.create_entity(entity_type.vertex().type_id_(), snapshot)))
+        let entity = Entity::new(self.vertex_generator.create_entity(entity_type.vertex().type_id_(), snapshot));
+        self.may_put_creation_time(snapshot, entity_type.into_object_type(), entity.vertex())?;
+        Ok(entity)
     }
 
     pub fn create_relation(
@@ -2311,10 +2937,113 @@ impl ThingManager {
         snapshot: &mut impl WritableSnapshot,
         relation_type: RelationType,
     ) -> Result<Relation, Box<ConceptWriteError>> {
-        OperationTimeValidation::validate_relation_type_is_not_abstract(snapshot, self, relation_type)
-            .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        if !self.defers_validation() {
+            OperationTimeValidation::validate_relation_type_is_not_abstract(snapshot, self, relation_type)
+                .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        }
+
+        let relation =
+            Relation::new(self.vertex_generator.create_relation(relation_type.vertex().type_id_(), snapshot));
+        self.may_put_creation_time(snapshot, relation_type.into_object_type(), relation.vertex())?;
+        Ok(relation)
+    }
+
+    /// Creates an entity with the provided IID rather than a generated one, so that imported data keeps the
+    /// identities it was exported with. The IID must be an unused entity IID of the entity type.
+    pub fn create_entity_with_iid(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        entity_type: EntityType,
+        vertex: ObjectVertex,
+    ) -> Result<Entity, Box<ConceptWriteError>> {
+        self.validate_provided_object_vertex(snapshot, entity_type.into_object_type(), vertex)?;
+        if !self.defers_validation() {
+            OperationTimeValidation::validate_entity_type_is_not_abstract(snapshot, self, entity_type)
+                .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        }
 
-        Ok(Relation::new(self.vertex_generator.create_relation(relation_type.vertex().type_id_(), snapshot)))
+        self.vertex_generator.create_object_with_vertex(vertex, snapshot);
+        self.may_put_creation_time(snapshot, entity_type.into_object_type(), vertex)?;
+        Ok(Entity::new(vertex))
+    }
+
+    /// Creates a relation with the provided IID rather than a generated one, so that imported data keeps the
+    /// identities it was exported with. The IID must be an unused relation IID of the relation type.
+    pub fn create_relation_with_iid(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        relation_type: RelationType,
+        vertex: ObjectVertex,
+    ) -> Result<Relation, Box<ConceptWriteError>> {
+        self.validate_provided_object_vertex(snapshot, relation_type.into_object_type(), vertex)?;
+        if !self.defers_validation() {
+            OperationTimeValidation::validate_relation_type_is_not_abstract(snapshot, self, relation_type)
+                .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        }
+
+        self.vertex_generator.create_object_with_vertex(vertex, snapshot);
+        self.may_put_creation_time(snapshot, relation_type.into_object_type(), vertex)?;
+        Ok(Relation::new(vertex))
+    }
+
+    fn validate_provided_object_vertex(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        object_type: ObjectType,
+        vertex: ObjectVertex,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        let expected_prefix = match object_type {
+            ObjectType::Entity(_) => Prefix::VertexEntity,
+            ObjectType::Relation(_) => Prefix::VertexRelation,
+        };
+        if vertex.prefix() != expected_prefix || vertex.type_id_() != object_type.vertex().type_id_() {
+            let type_label = object_type.get_label(snapshot, self.type_manager())?.clone();
+            return Err(Box::new(ConceptWriteError::ProvidedIIDTypeMismatch { type_label }));
+        }
+        let storage_key = vertex.into_storage_key();
+        let is_in_use = snapshot
+            .contains(storage_key.as_reference(), StorageCounters::DISABLED)
+            .map_err(|source| Box::new(ConceptWriteError::SnapshotGet { source }))?;
+        if is_in_use {
+            let type_label = object_type.get_label(snapshot, self.type_manager())?.clone();
+            return Err(Box::new(ConceptWriteError::ProvidedIIDInUse { type_label }));
+        }
+        Ok(())
+    }
+
+    /// Records the creation time of objects whose type has a time to live, so they can be expired later.
+    /// Objects created before their type was given a time to live have no creation time, and never expire.
+    fn may_put_creation_time(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        object_type: ObjectType,
+        object_vertex: ObjectVertex,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        if self.type_manager().get_object_type_time_to_live(snapshot, object_type)?.is_some() {
+            let key = build_object_vertex_property_creation_time(object_vertex);
+            snapshot.put_val(
+                key.into_storage_key().into_owned_array(),
+                ByteArray::copy(&encode_u64(millis_since_epoch(SystemTime::now()))),
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the creation time of an object, if its type had a time to live when the object was created.
+    pub fn get_creation_time(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        object: impl ObjectAPI,
+        storage_counters: StorageCounters,
+    ) -> Result<Option<SystemTime>, Box<ConceptReadError>> {
+        let key = build_object_vertex_property_creation_time(object.vertex());
+        snapshot
+            .get_mapped(
+                key.into_storage_key().as_reference(),
+                |bytes| UNIX_EPOCH + Duration::from_millis(decode_u64(bytes.try_into().unwrap())),
+                storage_counters,
+            )
+            .map_err(|err| Box::new(ConceptReadError::SnapshotGet { source: err }))
     }
 
     pub fn create_attribute(
@@ -2323,32 +3052,34 @@ impl ThingManager {
         attribute_type: AttributeType,
         value: Value<'_>,
     ) -> Result<Attribute, Box<ConceptWriteError>> {
-        OperationTimeValidation::validate_attribute_type_is_not_abstract(snapshot, self, attribute_type)
-            .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        if !self.defers_validation() {
+            OperationTimeValidation::validate_attribute_type_is_not_abstract(snapshot, self, attribute_type)
+                .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
 
-        OperationTimeValidation::validate_attribute_regex_constraints(
-            snapshot,
-            self,
-            attribute_type,
-            value.as_reference(),
-        )
-        .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+            OperationTimeValidation::validate_attribute_regex_constraints(
+                snapshot,
+                self,
+                attribute_type,
+                value.as_reference(),
+            )
+            .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
 
-        OperationTimeValidation::validate_attribute_range_constraints(
-            snapshot,
-            self,
-            attribute_type,
-            value.as_reference(),
-        )
-        .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+            OperationTimeValidation::validate_attribute_range_constraints(
+                snapshot,
+                self,
+                attribute_type,
+                value.as_reference(),
+            )
+            .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
 
-        OperationTimeValidation::validate_attribute_values_constraints(
-            snapshot,
-            self,
-            attribute_type,
-            value.as_reference(),
-        )
-        .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+            OperationTimeValidation::validate_attribute_values_constraints(
+                snapshot,
+                self,
+                attribute_type,
+                value.as_reference(),
+            )
+            .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        }
 
         self.put_attribute(snapshot, attribute_type, value)
     }
@@ -2475,6 +3206,7 @@ impl ThingManager {
         entity: Entity,
         storage_counters: StorageCounters,
     ) -> Result<(), Box<ConceptWriteError>> {
+        self.unset_creation_time(snapshot, entity.vertex());
         self.delete_object(snapshot, entity.vertex().into_storage_key(), storage_counters)
     }
 
@@ -2484,9 +3216,15 @@ impl ThingManager {
         relation: Relation,
         storage_counters: StorageCounters,
     ) -> Result<(), Box<ConceptWriteError>> {
+        self.unset_creation_time(snapshot, relation.vertex());
         self.delete_object(snapshot, relation.vertex().into_storage_key(), storage_counters)
     }
 
+    fn unset_creation_time(&self, snapshot: &mut impl WritableSnapshot, object_vertex: ObjectVertex) {
+        let key = build_object_vertex_property_creation_time(object_vertex);
+        snapshot.delete(key.into_storage_key().into_owned_array())
+    }
+
     fn delete_object(
         &self,
         snapshot: &mut impl WritableSnapshot,
@@ -2570,42 +3308,45 @@ impl ThingManager {
             value.as_reference(),
         )?;
 
-        OperationTimeValidation::validate_has_unique_constraint(
-            snapshot,
-            self,
-            owner,
-            attribute.type_(),
-            value.as_reference(),
-            storage_counters.clone(),
-        )
-        .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        // Deferred validation checks the constraints on the has edges written, once the transaction commits
+        if !self.defers_validation() {
+            OperationTimeValidation::validate_has_unique_constraint(
+                snapshot,
+                self,
+                owner,
+                attribute.type_(),
+                value.as_reference(),
+                storage_counters.clone(),
+            )
+            .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
 
-        OperationTimeValidation::validate_has_regex_constraints(
-            snapshot,
-            self,
-            owner,
-            attribute_type,
-            value.as_reference(),
-        )
-        .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+            OperationTimeValidation::validate_has_regex_constraints(
+                snapshot,
+                self,
+                owner,
+                attribute_type,
+                value.as_reference(),
+            )
+            .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
 
-        OperationTimeValidation::validate_has_range_constraints(
-            snapshot,
-            self,
-            owner,
-            attribute_type,
-            value.as_reference(),
-        )
-        .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+            OperationTimeValidation::validate_has_range_constraints(
+                snapshot,
+                self,
+                owner,
+                attribute_type,
+                value.as_reference(),
+            )
+            .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
 
-        OperationTimeValidation::validate_has_values_constraints(
-            snapshot,
-            self,
-            owner,
-            attribute_type,
-            value.as_reference(),
-        )
-        .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+            OperationTimeValidation::validate_has_values_constraints(
+                snapshot,
+                self,
+                owner,
+                attribute_type,
+                value.as_reference(),
+            )
+            .map_err(|typedb_source| ConceptWriteError::DataValidation { typedb_source })?;
+        }
 
         if count == 0 {
             self.unset_has(snapshot, owner, attribute, storage_counters.clone())
@@ -3010,3 +3751,7 @@ impl ThingManager {
         }
     }
 }
+
+fn millis_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_millis() as u64)
+}
@@ -77,6 +77,7 @@ macro_rules! validate_capability_cardinality_constraint {
             thing_manager: &ThingManager,
             object: $object_instance,
             interface_types_to_check: &HashSet<<$capability_type as Capability>::InterfaceType>,
+            removed_counts: &HashMap<<$capability_type as Capability>::InterfaceType, u64>,
             storage_counters: StorageCounters,
         ) -> Result<(), Box<DataValidationError>> {
             let mut cardinality_constraints: HashSet<CapabilityConstraint<$capability_type>> = HashSet::new();
@@ -88,13 +89,26 @@ macro_rules! validate_capability_cardinality_constraint {
             });
 
             for interface_type in interface_types_to_check {
-                for constraint in object
-                    .type_()
-                    .$get_cardinality_constraints_func(snapshot, thing_manager.type_manager(), interface_type.clone())
-                    .map_err(|source| Box::new(DataValidationError::ConceptRead { typedb_source: source }))?
-                    .into_iter()
+                // A specialised interface type is hidden from the specialising object types, so its constraints only
+                // reach their instances through the specialising (sub) interface types
+                let sub_interface_types = interface_type
+                    .get_subtypes_transitive(snapshot, thing_manager.type_manager())
+                    .map_err(|source| Box::new(DataValidationError::ConceptRead { typedb_source: source }))?;
+                for checked_interface_type in
+                    TypeAPI::chain_types(interface_type.clone(), sub_interface_types.into_iter().cloned())
                 {
-                    cardinality_constraints.insert(constraint);
+                    for constraint in object
+                        .type_()
+                        .$get_cardinality_constraints_func(
+                            snapshot,
+                            thing_manager.type_manager(),
+                            checked_interface_type,
+                        )
+                        .map_err(|source| Box::new(DataValidationError::ConceptRead { typedb_source: source }))?
+                        .into_iter()
+                    {
+                        cardinality_constraints.insert(constraint);
+                    }
                 }
             }
 
@@ -116,7 +130,10 @@ macro_rules! validate_capability_cardinality_constraint {
                     .map_err(|source| Box::new(DataValidationError::ConceptRead { typedb_source: source }))?;
                 let count =
                     TypeAPI::chain_types(source_interface_type.clone(), sub_interface_types.into_iter().cloned())
-                        .filter_map(|interface_type| counts.get(&interface_type))
+                        .filter_map(|interface_type| {
+                            let removed = removed_counts.get(&interface_type).copied().unwrap_or(0);
+                            counts.get(&interface_type).map(|count| count.saturating_sub(removed))
+                        })
                         .sum();
                 $check_func(snapshot, thing_manager.type_manager(), &constraint, object, source_interface_type, count)?;
             }
@@ -718,6 +735,7 @@ impl CardinalityValidation {
             thing_manager,
             object,
             modified_attribute_types,
+            &HashMap::new(),
             storage_counters,
         );
         collect_errors!(out_errors, cardinality_check, |e: Box<_>| *e);
@@ -737,6 +755,7 @@ impl CardinalityValidation {
             thing_manager,
             object,
             modified_role_types,
+            &HashMap::new(),
             storage_counters,
         );
         collect_errors!(out_errors, cardinality_check, |e: Box<_>| *e);
@@ -756,12 +775,51 @@ impl CardinalityValidation {
             thing_manager,
             relation,
             modified_role_types,
+            &HashMap::new(),
             storage_counters,
         );
         collect_errors!(out_errors, cardinality_check, |e: Box<_>| *e);
         Ok(())
     }
 
+    /// Validates the plays cardinalities of `object` as if it played each role type `removed_role_counts` fewer times.
+    pub(crate) fn validate_object_links_after_removal(
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        object: Object,
+        removed_role_counts: &HashMap<RoleType, u64>,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<DataValidationError>> {
+        let role_types = removed_role_counts.keys().cloned().collect();
+        Self::validate_plays_cardinality_constraint(
+            snapshot,
+            thing_manager,
+            object,
+            &role_types,
+            removed_role_counts,
+            storage_counters,
+        )
+    }
+
+    /// Validates the relates cardinalities of `relation` as if each role type had `removed_role_counts` fewer players.
+    pub(crate) fn validate_relation_links_after_removal(
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        relation: Relation,
+        removed_role_counts: &HashMap<RoleType, u64>,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<DataValidationError>> {
+        let role_types = removed_role_counts.keys().cloned().collect();
+        Self::validate_relates_cardinality_constraint(
+            snapshot,
+            thing_manager,
+            relation,
+            &role_types,
+            removed_role_counts,
+            storage_counters,
+        )
+    }
+
     validate_capability_cardinality_constraint!(
         validate_owns_cardinality_constraint,
         Owns,
@@ -0,0 +1,201 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::{HashMap, HashSet};
+
+use bytes::Bytes;
+use encoding::{
+    graph::thing::{
+        edge::{ThingEdgeHas, ThingEdgeLinks},
+        vertex_attribute::AttributeVertex,
+        vertex_object::ObjectVertex,
+        ThingVertex,
+    },
+    layout::prefix::Prefix,
+    Prefixed,
+};
+use resource::profile::StorageCounters;
+use storage::{
+    key_range::{KeyRange, RangeEnd, RangeStart},
+    key_value::StorageKey,
+    snapshot::{write::Write, ReadableSnapshot},
+};
+
+use crate::{
+    error::ConceptReadError,
+    thing::{
+        attribute::Attribute,
+        object::{Object, ObjectAPI},
+        relation::Relation,
+        thing_manager::{
+            validation::{
+                cardinality_validation::collect_errors, operation_time_validation::OperationTimeValidation,
+                DataValidationError,
+            },
+            ThingManager,
+        },
+    },
+    type_::{object_type::ObjectType, role_type::RoleType},
+};
+
+/// The writes of a transaction whose validation is deferred from operation time to commit time.
+/// They are collected from the writes of the transaction's own snapshot when it commits,
+/// so nothing is recorded while writing and no state is shared between transactions.
+#[derive(Debug, Default)]
+pub(crate) struct DeferredModifications {
+    created_object_types: HashSet<ObjectType>,
+    put_attributes: Vec<Attribute>,
+    objects_attributes: HashMap<Object, Vec<Attribute>>,
+    objects_role_types: HashMap<Object, HashSet<RoleType>>,
+    relations_role_types: HashMap<Relation, HashSet<RoleType>>,
+}
+
+impl DeferredModifications {
+    pub(crate) fn collect(
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        storage_counters: StorageCounters,
+    ) -> Result<Self, Box<ConceptReadError>> {
+        let mut modifications = Self::default();
+
+        let created_objects = snapshot
+            .iterate_writes_range(&KeyRange::new_variable_width(
+                RangeStart::Inclusive(StorageKey::new(
+                    ObjectVertex::KEYSPACE,
+                    Bytes::<0>::reference(
+                        ObjectVertex::build_prefix_prefix(Prefix::VertexEntity, ObjectVertex::KEYSPACE).bytes(),
+                    ),
+                )),
+                RangeEnd::EndPrefixInclusive(StorageKey::new(
+                    ObjectVertex::KEYSPACE,
+                    Bytes::<0>::reference(
+                        ObjectVertex::build_prefix_prefix(Prefix::VertexRelation, ObjectVertex::KEYSPACE).bytes(),
+                    ),
+                )),
+            ))
+            .filter(|(_, write)| matches!(write, Write::Insert { .. }));
+        for (key, _) in created_objects {
+            modifications.created_object_types.insert(Object::new(ObjectVertex::decode(key.bytes())).type_());
+        }
+
+        for is_short in [true, false] {
+            let put_attributes = snapshot
+                .iterate_writes_range(&KeyRange::new_within(
+                    StorageKey::new(
+                        AttributeVertex::keyspace_for_is_short(is_short),
+                        Bytes::inline(Prefix::VertexAttribute.prefix_id().to_bytes(), 1),
+                    ),
+                    Prefix::VertexAttribute.fixed_width_keys(),
+                ))
+                .filter(|(_, write)| !matches!(write, Write::Delete));
+            for (key, _) in put_attributes {
+                modifications.put_attributes.push(Attribute::new(AttributeVertex::decode(key.bytes())));
+            }
+        }
+
+        let put_has = snapshot
+            .iterate_writes_range(&KeyRange::new_within(ThingEdgeHas::prefix(), ThingEdgeHas::FIXED_WIDTH_ENCODING))
+            .filter(|(_, write)| !matches!(write, Write::Delete));
+        for (key, _) in put_has {
+            let edge = ThingEdgeHas::decode(Bytes::Reference(key.byte_array()));
+            let owner = Object::new(edge.from());
+            if thing_manager.instance_exists(snapshot, &owner, storage_counters.clone())? {
+                modifications.objects_attributes.entry(owner).or_default().push(Attribute::new(edge.to()));
+            }
+        }
+
+        let put_links = snapshot
+            .iterate_writes_range(&KeyRange::new_within(ThingEdgeLinks::prefix(), ThingEdgeLinks::FIXED_WIDTH_ENCODING))
+            .filter(|(_, write)| !matches!(write, Write::Delete));
+        for (key, _) in put_links {
+            let edge = ThingEdgeLinks::decode(Bytes::reference(key.bytes()));
+            let relation = Relation::new(edge.relation());
+            let player = Object::new(edge.player());
+            let role_type = RoleType::build_from_type_id(edge.role_id());
+            if thing_manager.instance_exists(snapshot, &relation, storage_counters.clone())? {
+                modifications.relations_role_types.entry(relation).or_default().insert(role_type);
+            }
+            if thing_manager.instance_exists(snapshot, &player, storage_counters.clone())? {
+                modifications.objects_role_types.entry(player).or_default().insert(role_type);
+            }
+        }
+
+        Ok(modifications)
+    }
+
+    pub(crate) fn objects_attributes(&self) -> &HashMap<Object, Vec<Attribute>> {
+        &self.objects_attributes
+    }
+
+    pub(crate) fn objects_role_types(&self) -> &HashMap<Object, HashSet<RoleType>> {
+        &self.objects_role_types
+    }
+
+    pub(crate) fn relations_role_types(&self) -> &HashMap<Relation, HashSet<RoleType>> {
+        &self.relations_role_types
+    }
+
+    pub(crate) fn validate_created_instances(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        storage_counters: StorageCounters,
+        errors: &mut Vec<DataValidationError>,
+    ) {
+        for &object_type in &self.created_object_types {
+            let result = match object_type {
+                ObjectType::Entity(entity_type) => {
+                    OperationTimeValidation::validate_entity_type_is_not_abstract(snapshot, thing_manager, entity_type)
+                }
+                ObjectType::Relation(relation_type) => OperationTimeValidation::validate_relation_type_is_not_abstract(
+                    snapshot,
+                    thing_manager,
+                    relation_type,
+                ),
+            };
+            collect_errors!(errors, result, |error: Box<DataValidationError>| *error);
+        }
+        for attribute_type in self.put_attributes.iter().map(Attribute::type_).collect::<HashSet<_>>() {
+            let result = OperationTimeValidation::validate_attribute_type_is_not_abstract(
+                snapshot,
+                thing_manager,
+                attribute_type,
+            );
+            collect_errors!(errors, result, |error: Box<DataValidationError>| *error);
+        }
+        for attribute in &self.put_attributes {
+            let value = match attribute.get_value(snapshot, thing_manager, storage_counters.clone()) {
+                Ok(value) => value,
+                Err(typedb_source) => {
+                    errors.push(DataValidationError::ConceptRead { typedb_source });
+                    continue;
+                }
+            };
+            let attribute_type = attribute.type_();
+            let result = OperationTimeValidation::validate_attribute_regex_constraints(
+                snapshot,
+                thing_manager,
+                attribute_type,
+                value.as_reference(),
+            );
+            collect_errors!(errors, result, |error: Box<DataValidationError>| *error);
+            let result = OperationTimeValidation::validate_attribute_range_constraints(
+                snapshot,
+                thing_manager,
+                attribute_type,
+                value.as_reference(),
+            );
+            collect_errors!(errors, result, |error: Box<DataValidationError>| *error);
+            let result = OperationTimeValidation::validate_attribute_values_constraints(
+                snapshot,
+                thing_manager,
+                attribute_type,
+                value.as_reference(),
+            );
+            collect_errors!(errors, result, |error: Box<DataValidationError>| *error);
+        }
+    }
+}
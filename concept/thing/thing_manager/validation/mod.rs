@@ -14,6 +14,7 @@ use crate::{
 };
 
 pub(crate) mod cardinality_validation;
+pub(crate) mod deferred_validation;
 pub(crate) mod operation_time_validation;
 pub(crate) mod validation;
 
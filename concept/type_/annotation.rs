@@ -7,9 +7,10 @@
 use std::{
     borrow::Cow,
     cmp::{max, min, Ordering},
-    collections::HashSet,
+    collections::{HashSet, VecDeque},
     fmt,
     hash::Hash,
+    iter,
     iter::Sum,
     ops::Add,
 };
@@ -22,6 +23,13 @@ use encoding::{
 };
 use error::typedb_error;
 use regex::Regex;
+use regex_automata::{
+    hybrid::{
+        dfa::{Cache, DFA},
+        LazyStateID,
+    },
+    Input,
+};
 use resource::constants::snapshot::BUFFER_VALUE_INLINE;
 use serde::{Deserialize, Serialize};
 
@@ -225,6 +233,9 @@ impl fmt::Display for AnnotationCardinality {
     }
 }
 
+const REGEX_CONFLICT_CACHE_CAPACITY: usize = 4 * 1024 * 1024;
+const REGEX_CONFLICT_MAX_STATES: usize = 4096;
+
 #[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
 pub struct AnnotationRegex {
     regex: Cow<'static, str>,
@@ -262,9 +273,72 @@ impl AnnotationRegex {
         matches!(value_type, Some(ValueType::String))
     }
 
-    // TODO: Can try to implement the check, but allow everything now!
-    pub fn narrowed_correctly_by(&self, _other: &Self) -> bool {
-        true
+    // Regexes of supertypes and interfaces stay in force on top of narrower ones, so a narrowing regex is only
+    // rejected when it provably conflicts: no value could ever match both. Undecided cases are allowed.
+    pub fn narrowed_correctly_by(&self, other: &Self) -> bool {
+        self.regex == other.regex || Self::can_match_together(&self.regex, &other.regex) != Some(false)
+    }
+
+    // Explores the product of both lazy DFAs in unanchored `is_match` mode, looking for a byte string matched by
+    // both regexes. Returns `None` if the automata cannot be built or the exploration exceeds its limits.
+    fn can_match_together(first: &str, second: &str) -> Option<bool> {
+        #[derive(Clone, Copy, Eq, PartialEq, Hash)]
+        enum Progress {
+            Searching(LazyStateID),
+            Matched,
+        }
+
+        fn advance(dfa: &DFA, cache: &mut Cache, progress: Progress, byte: Option<u8>) -> Option<Option<Progress>> {
+            let Progress::Searching(state) = progress else {
+                return Some(Some(Progress::Matched));
+            };
+            let next = match byte {
+                Some(byte) => dfa.next_state(cache, state, byte).ok()?,
+                None => dfa.next_eoi_state(cache, state).ok()?,
+            };
+            if next.is_match() {
+                Some(Some(Progress::Matched))
+            } else if next.is_quit() || next.is_unknown() {
+                None
+            } else if next.is_dead() || byte.is_none() {
+                Some(None)
+            } else {
+                Some(Some(Progress::Searching(next)))
+            }
+        }
+
+        let build = |pattern: &str| {
+            let config = DFA::config().cache_capacity(REGEX_CONFLICT_CACHE_CAPACITY).minimum_cache_clear_count(Some(0));
+            let dfa = DFA::builder().configure(config).build(pattern).ok()?;
+            let mut cache = dfa.create_cache();
+            let start = dfa.start_state_forward(&mut cache, &Input::new("")).ok()?;
+            Some((dfa, cache, Progress::Searching(start)))
+        };
+        let (first_dfa, mut first_cache, first_start) = build(first)?;
+        let (second_dfa, mut second_cache, second_start) = build(second)?;
+
+        let mut visited = HashSet::from([(first_start, second_start)]);
+        let mut queue = VecDeque::from([(first_start, second_start)]);
+        while let Some((first_progress, second_progress)) = queue.pop_front() {
+            for byte in (0..=u8::MAX).map(Some).chain(iter::once(None)) {
+                let Some(first_next) = advance(&first_dfa, &mut first_cache, first_progress, byte)? else {
+                    continue;
+                };
+                let Some(second_next) = advance(&second_dfa, &mut second_cache, second_progress, byte)? else {
+                    continue;
+                };
+                if first_next == Progress::Matched && second_next == Progress::Matched {
+                    return Some(true);
+                }
+                if byte.is_some() && visited.insert((first_next, second_next)) {
+                    if visited.len() > REGEX_CONFLICT_MAX_STATES {
+                        return None;
+                    }
+                    queue.push_back((first_next, second_next));
+                }
+            }
+        }
+        Some(false)
     }
 }
 
@@ -380,7 +454,7 @@ impl AnnotationRange {
             None => true,
             Some(start) => match &value {
                 None => false,
-                Some(value) => start.partial_cmp(value).is_some_and(|ord| ord.is_le()),
+                Some(value) => Self::compare_bound(start, value).is_some_and(|ord| ord.is_le()),
             },
         }
     }
@@ -390,10 +464,19 @@ impl AnnotationRange {
             None => true,
             Some(end) => match &value {
                 None => false,
-                Some(value) => end.partial_cmp(value).is_some_and(|ord| ord.is_ge()),
+                Some(value) => Self::compare_bound(end, value).is_some_and(|ord| ord.is_ge()),
             },
         }
     }
+
+    // Bounds may be written with a value type trivially castable to the attribute's one (e.g. a date bound on a
+    // datetime attribute), which `Value::partial_cmp` does not always order, so the bound is widened when needed.
+    fn compare_bound(bound: Value<'static>, value: &Value<'_>) -> Option<Ordering> {
+        bound.partial_cmp(value).or_else(|| {
+            let value_type_category = value.value_type().category();
+            bound.cast(value_type_category).and_then(|bound| bound.partial_cmp(value))
+        })
+    }
 }
 
 impl fmt::Display for AnnotationRange {
@@ -128,6 +128,25 @@ impl TypeAPI for AttributeType {
         type_manager.delete_attribute_type(snapshot, thing_manager, self)
     }
 
+    fn delete_instances(
+        self,
+        snapshot: &mut impl WritableSnapshot,
+        type_manager: &TypeManager,
+        thing_manager: &ThingManager,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        type_manager.delete_attribute_type_instances(snapshot, thing_manager, self, storage_counters)
+    }
+
+    fn rename(
+        self,
+        snapshot: &mut impl WritableSnapshot,
+        type_manager: &TypeManager,
+        new_label: &Label,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        type_manager.rename_type(snapshot, self, new_label)
+    }
+
     fn get_label<'m>(
         &self,
         snapshot: &impl ReadableSnapshot,
@@ -12,7 +12,7 @@ use encoding::{
         definition::r#struct::StructDefinition,
         type_::{
             edge::TypeEdgeEncoding,
-            property::{TypeEdgePropertyEncoding, TypeVertexPropertyEncoding},
+            property::{DatabasePropertyEncoding, TypeEdgePropertyEncoding, TypeVertexPropertyEncoding},
             vertex::{TypeVertex, TypeVertexEncoding},
             CapabilityKind, Kind,
         },
@@ -23,7 +23,11 @@ use encoding::{
 use itertools::Itertools;
 use primitive::maybe_owns::MaybeOwns;
 use resource::{
-    constants::snapshot::{BUFFER_KEY_INLINE, BUFFER_VALUE_INLINE},
+    constants::{
+        concept::{DEFAULT_HISTORY_RETENTION, DEFAULT_RELATION_INDEX_THRESHOLD},
+        server::DEFAULT_STATISTICS_UPDATES,
+        snapshot::{BUFFER_KEY_INLINE, BUFFER_VALUE_INLINE},
+    },
     profile::StorageCounters,
 };
 use serde::{Deserialize, Serialize};
@@ -81,6 +85,42 @@ pub trait TypeAPI: ConceptAPI + TypeVertexEncoding + Copy + Sized + Hash + Eq {
         thing_manager: &ThingManager,
     ) -> Result<(), Box<ConceptWriteError>>;
 
+    /// Deletes every instance of this type, including the ownerships and role players that depend on them.
+    /// Used to cascade a type deletion, after which the type itself can be deleted.
+    fn delete_instances(
+        self,
+        snapshot: &mut impl WritableSnapshot,
+        type_manager: &TypeManager,
+        thing_manager: &ThingManager,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptWriteError>>;
+
+    fn delete_with_policy(
+        self,
+        snapshot: &mut impl WritableSnapshot,
+        type_manager: &TypeManager,
+        thing_manager: &ThingManager,
+        policy: TypeDeletePolicy,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        match policy {
+            TypeDeletePolicy::Restrict => {}
+            TypeDeletePolicy::Cascade => {
+                self.delete_instances(snapshot, type_manager, thing_manager, storage_counters)?
+            }
+        }
+        self.delete(snapshot, type_manager, thing_manager)
+    }
+
+    /// Changes the label of this type, keeping its instances and schema intact.
+    /// Fails if the new label is already in use; renaming a type to its current label is a no-op.
+    fn rename(
+        self,
+        snapshot: &mut impl WritableSnapshot,
+        type_manager: &TypeManager,
+        new_label: &Label,
+    ) -> Result<(), Box<ConceptWriteError>>;
+
     fn get_label<'m>(
         &self,
         snapshot: &impl ReadableSnapshot,
@@ -732,6 +772,16 @@ pub trait PlayerAPI: TypeAPI {
     }
 }
 
+/// What to do with existing instances when their type is deleted.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum TypeDeletePolicy {
+    /// Fail the deletion if the type has any instances.
+    #[default]
+    Restrict,
+    /// Delete the instances, and everything that depends on them, together with the type.
+    Cascade,
+}
+
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
 pub enum Ordering {
     // ##########################################################################
@@ -796,6 +846,187 @@ impl TypeVertexPropertyEncoding for Independent {
     }
 }
 
+/// The number of seconds after which instances of an entity or relation type, and its subtypes, expire.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TimeToLive(pub u64);
+
+impl fmt::Display for TimeToLive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}s", self.0)
+    }
+}
+
+impl TypeVertexPropertyEncoding for TimeToLive {
+    const INFIX: Infix = Infix::PropertyTimeToLive;
+
+    fn from_value_bytes(value: &[u8]) -> TimeToLive {
+        bincode::deserialize(value).unwrap()
+    }
+
+    fn to_value_bytes(&self) -> Option<Bytes<'static, BUFFER_VALUE_INLINE>> {
+        Some(Bytes::copy(bincode::serialize(self).unwrap().as_slice()))
+    }
+}
+
+/// The total role cardinality up to which relations of a type are indexed by their role player pairs.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RelationIndexThreshold(pub u64);
+
+impl Default for RelationIndexThreshold {
+    fn default() -> Self {
+        Self(DEFAULT_RELATION_INDEX_THRESHOLD)
+    }
+}
+
+impl fmt::Display for RelationIndexThreshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Schema functions called in a follow-up transaction after commits which insert instances of particular types.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, Eq, PartialEq)]
+pub struct CommitTriggers(pub Vec<CommitTrigger>);
+
+/// The schema function named `function`, called after each commit inserting instances of `type_label` or its
+/// subtypes, with each inserted instance in turn as its only argument.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct CommitTrigger {
+    pub name: String,
+    pub type_label: String,
+    pub function: String,
+    pub failure_policy: TriggerFailurePolicy,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TriggerFailurePolicy {
+    /// The failure is logged, and the trigger's transaction is discarded.
+    Log,
+    /// The trigger is rerun in a new transaction up to `attempts` more times, with exponential backoff,
+    /// before its failure is logged.
+    Retry { attempts: u32 },
+}
+
+impl DatabasePropertyEncoding for CommitTriggers {
+    const INFIX: Infix = Infix::PropertyCommitTriggers;
+
+    fn from_value_bytes(value: &[u8]) -> CommitTriggers {
+        bincode::deserialize(value).unwrap()
+    }
+
+    fn to_value_bytes(&self) -> Bytes<'static, BUFFER_VALUE_INLINE> {
+        Bytes::copy(bincode::serialize(self).unwrap().as_slice())
+    }
+}
+
+/// The number of commits behind the latest at which read transactions may still be opened.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct HistoryRetention(pub u64);
+
+impl Default for HistoryRetention {
+    fn default() -> Self {
+        Self(DEFAULT_HISTORY_RETENTION)
+    }
+}
+
+impl fmt::Display for HistoryRetention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl DatabasePropertyEncoding for HistoryRetention {
+    const INFIX: Infix = Infix::PropertyHistoryRetention;
+
+    fn from_value_bytes(value: &[u8]) -> HistoryRetention {
+        bincode::deserialize(value).unwrap()
+    }
+
+    fn to_value_bytes(&self) -> Bytes<'static, BUFFER_VALUE_INLINE> {
+        Bytes::copy(bincode::serialize(self).unwrap().as_slice())
+    }
+}
+
+/// The number of bytes of storage a database may use before write transactions are rejected.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct StorageQuota(pub u64);
+
+impl fmt::Display for StorageQuota {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes", self.0)
+    }
+}
+
+impl DatabasePropertyEncoding for StorageQuota {
+    const INFIX: Infix = Infix::PropertyStorageQuota;
+
+    fn from_value_bytes(value: &[u8]) -> StorageQuota {
+        bincode::deserialize(value).unwrap()
+    }
+
+    fn to_value_bytes(&self) -> Bytes<'static, BUFFER_VALUE_INLINE> {
+        Bytes::copy(bincode::serialize(self).unwrap().as_slice())
+    }
+}
+
+/// The version of the schema of a server-managed internal database, recorded so that the server can migrate the
+/// database when it is opened by a newer server version.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct InternalSchemaVersion(pub u64);
+
+impl fmt::Display for InternalSchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl DatabasePropertyEncoding for InternalSchemaVersion {
+    const INFIX: Infix = Infix::PropertyInternalSchemaVersion;
+
+    fn from_value_bytes(value: &[u8]) -> InternalSchemaVersion {
+        bincode::deserialize(value).unwrap()
+    }
+
+    fn to_value_bytes(&self) -> Bytes<'static, BUFFER_VALUE_INLINE> {
+        Bytes::copy(bincode::serialize(self).unwrap().as_slice())
+    }
+}
+
+/// Settings of a database, stored with its schema. Transactions may still set their own timeouts,
+/// and fall back on the server defaults where the database sets none.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct DatabaseSettings {
+    pub relation_index_threshold: RelationIndexThreshold,
+    pub transaction_timeout_millis: Option<u64>,
+    pub schema_lock_acquire_timeout_millis: Option<u64>,
+    /// Whether statistics are kept up to date as data is written. Bulk loads may disable it, in which case queries
+    /// are planned with the statistics from before, until it is enabled again
+    pub statistics_updates: bool,
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            relation_index_threshold: RelationIndexThreshold::default(),
+            transaction_timeout_millis: None,
+            schema_lock_acquire_timeout_millis: None,
+            statistics_updates: DEFAULT_STATISTICS_UPDATES,
+        }
+    }
+}
+
+impl DatabasePropertyEncoding for DatabaseSettings {
+    const INFIX: Infix = Infix::PropertyDatabaseSettings;
+
+    fn from_value_bytes(value: &[u8]) -> DatabaseSettings {
+        bincode::deserialize(value).unwrap()
+    }
+
+    fn to_value_bytes(&self) -> Bytes<'static, BUFFER_VALUE_INLINE> {
+        Bytes::copy(bincode::serialize(self).unwrap().as_slice())
+    }
+}
+
 pub trait Capability:
     TypeEdgeEncoding<From = Self::ObjectType, To = Self::InterfaceType> + Sized + Copy + Hash + Eq + 'static
 {
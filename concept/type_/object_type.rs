@@ -250,6 +250,25 @@ impl TypeAPI for ObjectType {
         with_object_type!(self, |object| { object.delete(snapshot, type_manager, thing_manager) })
     }
 
+    fn delete_instances(
+        self,
+        snapshot: &mut impl WritableSnapshot,
+        type_manager: &TypeManager,
+        thing_manager: &ThingManager,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        type_manager.delete_object_type_instances(snapshot, thing_manager, self, storage_counters)
+    }
+
+    fn rename(
+        self,
+        snapshot: &mut impl WritableSnapshot,
+        type_manager: &TypeManager,
+        new_label: &Label,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        with_object_type!(self, |object| { object.rename(snapshot, type_manager, new_label) })
+    }
+
     fn get_label<'m>(
         &self,
         snapshot: &impl ReadableSnapshot,
@@ -26,7 +26,7 @@ use encoding::{
 };
 use lending_iterator::higher_order::Hkt;
 use primitive::maybe_owns::MaybeOwns;
-use resource::constants::snapshot::BUFFER_KEY_INLINE;
+use resource::{constants::snapshot::BUFFER_KEY_INLINE, profile::StorageCounters};
 use storage::{
     key_value::StorageKey,
     snapshot::{ReadableSnapshot, WritableSnapshot},
@@ -120,6 +120,25 @@ impl TypeAPI for RoleType {
         type_manager.delete_role_type(snapshot, thing_manager, self)
     }
 
+    fn delete_instances(
+        self,
+        snapshot: &mut impl WritableSnapshot,
+        type_manager: &TypeManager,
+        thing_manager: &ThingManager,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        type_manager.delete_role_type_instances(snapshot, thing_manager, self, storage_counters)
+    }
+
+    fn rename(
+        self,
+        snapshot: &mut impl WritableSnapshot,
+        type_manager: &TypeManager,
+        new_label: &Label,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        type_manager.rename_type(snapshot, self, new_label)
+    }
+
     fn get_label<'m>(
         &self,
         snapshot: &impl ReadableSnapshot,
@@ -7,6 +7,7 @@
 use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
+    iter,
     sync::Arc,
 };
 
@@ -20,20 +21,23 @@ use encoding::{
     },
     value::{label::Label, value_type::ValueType},
 };
-use itertools::Itertools;
+use itertools::{Either, Itertools};
 use primitive::maybe_owns::MaybeOwns;
-use resource::{
-    constants::{concept::RELATION_INDEX_THRESHOLD, encoding::StructFieldIDUInt},
-    profile::StorageCounters,
+use resource::{constants::encoding::StructFieldIDUInt, profile::StorageCounters};
+use storage::{
+    sequence_number::SequenceNumber,
+    snapshot::{ReadableSnapshot, WritableSnapshot},
 };
-use storage::snapshot::{ReadableSnapshot, WritableSnapshot};
 use type_cache::TypeCache;
 use type_writer::TypeWriter;
-use validation::{commit_time_validation::CommitTimeValidation, operation_time_validation::OperationTimeValidation};
+use validation::{
+    commit_time_validation::CommitTimeValidation, operation_time_validation::OperationTimeValidation,
+    SchemaValidationError,
+};
 
 use crate::{
     error::{ConceptReadError, ConceptWriteError},
-    thing::thing_manager::ThingManager,
+    thing::{attribute::Attribute, object::Object, relation::Relation, thing_manager::ThingManager, ThingAPI},
     type_::{
         annotation::{
             Annotation, AnnotationAbstract, AnnotationCardinality, AnnotationCascade, AnnotationCategory,
@@ -54,11 +58,15 @@ use crate::{
         relates::{Relates, RelatesAnnotation},
         relation_type::{RelationType, RelationTypeAnnotation},
         role_type::{RoleType, RoleTypeAnnotation},
-        type_manager::type_reader::TypeReader,
-        Capability, Independent, KindAPI, ObjectTypeAPI, Ordering, OwnerAPI, PlayerAPI, TypeAPI, TypeQLSyntax,
+        type_manager::{hierarchy_closure::TypeHierarchyClosure, schema_diff::SchemaDiff, type_reader::TypeReader},
+        Capability, CommitTriggers, DatabaseSettings, HistoryRetention, Independent, InternalSchemaVersion, KindAPI,
+        ObjectTypeAPI, Ordering, OwnerAPI, PlayerAPI, RelationIndexThreshold, StorageQuota, TimeToLive, TypeAPI,
+        TypeQLSyntax,
     },
 };
 
+pub mod hierarchy_closure;
+pub mod schema_diff;
 pub mod type_cache;
 pub mod type_reader;
 mod type_writer;
@@ -105,6 +113,56 @@ macro_rules! get_types_methods {
     }
 }
 
+macro_rules! iterate_types_methods {
+    ($(
+        fn $method_name:ident() -> $type_:ident = $reader_method:ident | $cache_method:ident;
+    )*) => {
+        $(
+            /// Iterates all the types of this kind in type ID order, borrowing them from the type cache
+            /// rather than collecting them when the cache is available.
+            pub fn $method_name<'a>(
+                &'a self, snapshot: &impl ReadableSnapshot
+            ) -> Result<impl Iterator<Item = $type_> + 'a, Box<ConceptReadError>> {
+                if let Some(cache) = &self.type_cache {
+                    Ok(Either::Left(cache.$cache_method()))
+                } else {
+                    Ok(Either::Right(TypeReader::$reader_method(snapshot)?.into_iter()))
+                }
+            }
+        )*
+    }
+}
+
+macro_rules! get_capabilities_with_annotation_category_methods {
+    ($(
+        fn $method_name:ident() -> $capability:ident = $cache_method:ident;
+    )*) => {
+        $(
+            /// Finds every capability of this kind that declares an annotation of the given category, such as all
+            /// `@key` owns, in a single pass over the capabilities rather than by visiting each type.
+            pub fn $method_name(
+                &self, snapshot: &impl ReadableSnapshot, annotation_category: AnnotationCategory
+            ) -> Result<HashSet<$capability>, Box<ConceptReadError>> {
+                if let Some(cache) = &self.type_cache {
+                    Ok(cache.$cache_method(annotation_category))
+                } else {
+                    let mut capabilities = HashSet::new();
+                    for capability in TypeReader::get_all_capabilities::<$capability>(snapshot)? {
+                        let annotations = TypeReader::get_capability_annotations_declared(snapshot, capability)?;
+                        if annotations
+                            .into_iter()
+                            .any(|annotation| Annotation::from(annotation).category() == annotation_category)
+                        {
+                            capabilities.insert(capability);
+                        }
+                    }
+                    Ok(capabilities)
+                }
+            }
+        )*
+    }
+}
+
 macro_rules! get_supertype_methods {
     ($(
         fn $method_name:ident() -> $type_:ident = $cache_method:ident;
@@ -382,6 +440,12 @@ impl TypeManager {
         TypeManager { definition_key_generator, vertex_generator, type_cache: schema_cache }
     }
 
+    /// The sequence number of the schema read through the type cache, or `None` if the schema is read from the
+    /// snapshot and so may be modified by it.
+    pub fn schema_version(&self) -> Option<SequenceNumber> {
+        self.type_cache.as_ref().map(|cache| cache.open_sequence_number())
+    }
+
     pub fn definition_key_generator(&self) -> Arc<DefinitionKeyGenerator> {
         self.definition_key_generator.clone()
     }
@@ -487,6 +551,18 @@ impl TypeManager {
         fn get_attribute_types() -> AttributeType = get_attribute_types | get_attribute_types;
     }
 
+    iterate_types_methods! {
+        fn iterate_entity_types() -> EntityType = get_entity_types | iterate_entity_types;
+        fn iterate_relation_types() -> RelationType = get_relation_types | iterate_relation_types;
+        fn iterate_attribute_types() -> AttributeType = get_attribute_types | iterate_attribute_types;
+    }
+
+    get_capabilities_with_annotation_category_methods! {
+        fn get_owns_with_annotation_category() -> Owns = get_owns_with_annotation_category;
+        fn get_plays_with_annotation_category() -> Plays = get_plays_with_annotation_category;
+        fn get_relates_with_annotation_category() -> Relates = get_relates_with_annotation_category;
+    }
+
     get_supertype_methods! {
         fn get_entity_type_supertype() -> EntityType = get_supertype;
         fn get_relation_type_supertype() -> RelationType = get_supertype;
@@ -529,6 +605,24 @@ impl TypeManager {
         fn get_attribute_type_label_arc() -> AttributeType = get_label_owned;
     }
 
+    /// The transitive closure of the type hierarchy kept by the type cache, if there is one.
+    pub fn get_cached_hierarchy_closure(&self) -> Option<Arc<TypeHierarchyClosure>> {
+        self.type_cache.as_ref().map(|cache| cache.get_hierarchy_closure())
+    }
+
+    /// The transitive closure of the type hierarchy, for constant-time subtype checks.
+    /// Without a type cache, it is read from the snapshot in full.
+    pub fn get_hierarchy_closure(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+    ) -> Result<Arc<TypeHierarchyClosure>, Box<ConceptReadError>> {
+        if let Some(cache) = &self.type_cache {
+            Ok(cache.get_hierarchy_closure())
+        } else {
+            Ok(Arc::new(TypeHierarchyClosure::read(snapshot)?))
+        }
+    }
+
     pub fn get_roles_by_name(
         &self,
         snapshot: &impl ReadableSnapshot,
@@ -794,7 +888,7 @@ impl TypeManager {
                 Some(end) => max_card += end,
             }
         }
-        Ok(max_card <= RELATION_INDEX_THRESHOLD)
+        Ok(max_card <= self.get_relation_index_threshold(snapshot)?.0)
     }
 
     pub(crate) fn get_entity_type_plays_declared<'this>(
@@ -1148,24 +1242,75 @@ impl TypeManager {
         }
     }
 
+    /// Formats the schema types with structs first, then attribute, entity and relation types,
+    /// so that the schema can be re-applied as written.
     pub fn get_types_syntax(&self, snapshot: &impl ReadableSnapshot) -> Result<String, Box<ConceptReadError>> {
         let mut syntax = String::new();
-        for attribute_type in self.get_attribute_types(snapshot)?.iter() {
+        let struct_definitions = self.get_struct_definitions(snapshot)?;
+        let mut formatted_structs = HashSet::new();
+        for struct_key in struct_definitions.keys().sorted_by_key(|key| &struct_definitions[*key].name) {
+            self.format_struct_syntax_after_dependencies(
+                &mut syntax,
+                snapshot,
+                &struct_definitions,
+                struct_key,
+                &mut formatted_structs,
+            )?;
+        }
+        for attribute_type in self.iterate_attribute_types(snapshot)? {
             attribute_type.format_syntax(&mut syntax, snapshot, self)?;
         }
-        for entity_type in self.get_entity_types(snapshot)?.iter() {
+        for entity_type in self.iterate_entity_types(snapshot)? {
             entity_type.format_syntax(&mut syntax, snapshot, self)?;
         }
-        for relation_type in self.get_relation_types(snapshot)?.iter() {
+        for relation_type in self.iterate_relation_types(snapshot)? {
             relation_type.format_syntax(&mut syntax, snapshot, self)?;
         }
-        for (_struct_key, struct_definition) in
-            self.get_struct_definitions(snapshot)?.into_iter().sorted_by_key(|(_, definition)| definition.name.clone())
-        {
-            struct_definition.format_syntax(&mut syntax, snapshot, self)?;
-        }
         Ok(syntax)
     }
+
+    /// Formats a struct after the structs used by its fields, ordering independent structs by name.
+    fn format_struct_syntax_after_dependencies(
+        &self,
+        syntax: &mut String,
+        snapshot: &impl ReadableSnapshot,
+        struct_definitions: &HashMap<DefinitionKey, StructDefinition>,
+        struct_key: &DefinitionKey,
+        formatted_structs: &mut HashSet<DefinitionKey>,
+    ) -> Result<(), Box<ConceptReadError>> {
+        if !formatted_structs.insert(struct_key.clone()) {
+            return Ok(());
+        }
+        let struct_definition = &struct_definitions[struct_key];
+        let field_struct_keys = struct_definition
+            .fields
+            .values()
+            .filter_map(|field| match &field.value_type {
+                ValueType::Struct(field_struct_key) => Some(field_struct_key),
+                _ => None,
+            })
+            .sorted_by_key(|field_struct_key| &struct_definitions[*field_struct_key].name);
+        for field_struct_key in field_struct_keys {
+            self.format_struct_syntax_after_dependencies(
+                syntax,
+                snapshot,
+                struct_definitions,
+                field_struct_key,
+                formatted_structs,
+            )?;
+        }
+        struct_definition.format_syntax(syntax, snapshot, self)
+    }
+
+    /// The define, redefine and undefine statements converging this schema onto the schema read by `target_type_manager`.
+    pub fn get_schema_diff(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        target_type_manager: &TypeManager,
+        target_snapshot: &impl ReadableSnapshot,
+    ) -> Result<SchemaDiff, Box<ConceptReadError>> {
+        SchemaDiff::build(snapshot, self, target_snapshot, target_type_manager)
+    }
 }
 
 impl TypeManager {
@@ -1418,6 +1563,7 @@ impl TypeManager {
         self.validate_delete_type(snapshot, thing_manager, entity_type)?;
 
         self.delete_object_type_capabilities_unchecked(snapshot, entity_type.into_object_type())?;
+        TypeWriter::storage_delete_type_vertex_property::<TimeToLive>(snapshot, entity_type);
         self.delete_type(snapshot, entity_type)
     }
 
@@ -1434,6 +1580,7 @@ impl TypeManager {
         }
 
         self.delete_object_type_capabilities_unchecked(snapshot, relation_type.into_object_type())?;
+        TypeWriter::storage_delete_type_vertex_property::<TimeToLive>(snapshot, relation_type);
         self.delete_type(snapshot, relation_type)
     }
 
@@ -1495,6 +1642,80 @@ impl TypeManager {
         Ok(())
     }
 
+    pub(crate) fn delete_object_type_instances(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        thing_manager: &ThingManager,
+        object_type: ObjectType,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        let objects: Vec<Object> =
+            thing_manager.get_objects_in(snapshot, object_type, storage_counters.clone()).try_collect()?;
+        for object in objects {
+            object.delete(snapshot, thing_manager, storage_counters.clone())?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn delete_attribute_type_instances(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        thing_manager: &ThingManager,
+        attribute_type: AttributeType,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        let attributes: Vec<Attribute> =
+            thing_manager.get_attributes_in(snapshot, attribute_type, storage_counters.clone())?.try_collect()?;
+        for attribute in attributes {
+            attribute.delete(snapshot, thing_manager, storage_counters.clone())?;
+        }
+
+        // Ordered ownerships keep a list per owner, which outlives the individual attributes
+        let owns = attribute_type.get_owns(snapshot, self)?.iter().copied().collect_vec();
+        for owns in owns {
+            if owns.get_ordering(snapshot, self)? != Ordering::Ordered {
+                continue;
+            }
+            let owner_types = iter::once(owns.owner())
+                .chain(owns.owner().get_subtypes_transitive(snapshot, self)?.iter().copied())
+                .collect_vec();
+            for owner_type in owner_types {
+                let owners: Vec<Object> =
+                    thing_manager.get_objects_in(snapshot, owner_type, storage_counters.clone()).try_collect()?;
+                for owner in owners {
+                    thing_manager.unset_has_ordered(snapshot, owner, attribute_type, storage_counters.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn delete_role_type_instances(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        thing_manager: &ThingManager,
+        role_type: RoleType,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        let relation_type = role_type.get_relates_explicit(snapshot, self)?.relation();
+        let relation_types = iter::once(relation_type)
+            .chain(relation_type.get_subtypes_transitive(snapshot, self)?.iter().copied())
+            .collect_vec();
+        for relation_type in relation_types {
+            let relations: Vec<Relation> =
+                thing_manager.get_relations_in(snapshot, relation_type, storage_counters.clone()).try_collect()?;
+            for relation in relations {
+                let players: Vec<Object> = relation
+                    .get_players_role_type(snapshot, thing_manager, role_type, storage_counters.clone())
+                    .try_collect()?;
+                for player in players {
+                    thing_manager.unset_links(snapshot, relation, player, role_type, storage_counters.clone())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn delete_type(
         &self,
         snapshot: &mut impl WritableSnapshot,
@@ -1546,6 +1767,47 @@ impl TypeManager {
         Ok(())
     }
 
+    pub(crate) fn rename_type<T: KindAPI>(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        type_: T,
+        new_label: &Label,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        debug_assert!(OperationTimeValidation::validate_type_exists(snapshot, type_).is_ok());
+
+        if TypeReader::get_label(snapshot, type_)?.as_ref() == Some(new_label) {
+            return Ok(());
+        }
+
+        // The type vertex is unchanged, so instances, capabilities and annotations are preserved by construction
+        match T::KIND {
+            Kind::Entity | Kind::Attribute => self.set_label(snapshot, type_, new_label),
+            Kind::Relation => self.set_relation_type_label(snapshot, RelationType::new(type_.into_vertex()), new_label),
+            Kind::Role => self.rename_role_type(snapshot, RoleType::new(type_.into_vertex()), new_label),
+        }
+    }
+
+    fn rename_role_type(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        role_type: RoleType,
+        new_label: &Label,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        let relation_type = role_type.get_relates_explicit(snapshot, self)?.relation();
+        let relation_label = TypeReader::get_label(snapshot, relation_type)?.unwrap();
+        if !new_label.scope().is_some_and(|scope| scope.as_str() == relation_label.name().as_str()) {
+            let role_label = TypeReader::get_label(snapshot, role_type)?.unwrap();
+            return Err(Box::new(ConceptWriteError::SchemaValidation {
+                typedb_source: Box::new(SchemaValidationError::RoleRenameCannotChangeScope {
+                    role: role_label,
+                    new_label: new_label.clone(),
+                    relation: relation_label,
+                }),
+            }));
+        }
+        self.set_role_type_name(snapshot, role_type, new_label.name().as_str())
+    }
+
     pub(crate) fn set_label<T: KindAPI>(
         &self,
         snapshot: &mut impl WritableSnapshot,
@@ -2680,6 +2942,122 @@ impl TypeManager {
         Ok(())
     }
 
+    pub fn get_relation_index_threshold(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+    ) -> Result<RelationIndexThreshold, Box<ConceptReadError>> {
+        Ok(self.get_database_settings(snapshot)?.relation_index_threshold)
+    }
+
+    pub fn get_commit_triggers(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+    ) -> Result<CommitTriggers, Box<ConceptReadError>> {
+        Ok(TypeReader::get_database_property::<CommitTriggers>(snapshot)?.unwrap_or_default())
+    }
+
+    pub fn set_commit_triggers(&self, snapshot: &mut impl WritableSnapshot, triggers: CommitTriggers) {
+        TypeWriter::storage_put_database_property(snapshot, triggers);
+    }
+
+    pub fn get_storage_quota(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+    ) -> Result<Option<StorageQuota>, Box<ConceptReadError>> {
+        if let Some(cache) = &self.type_cache {
+            Ok(cache.get_storage_quota())
+        } else {
+            TypeReader::get_database_property::<StorageQuota>(snapshot)
+        }
+    }
+
+    pub fn set_storage_quota(&self, snapshot: &mut impl WritableSnapshot, quota: StorageQuota) {
+        TypeWriter::storage_put_database_property(snapshot, quota);
+    }
+
+    pub fn unset_storage_quota(&self, snapshot: &mut impl WritableSnapshot) {
+        TypeWriter::storage_delete_database_property::<StorageQuota>(snapshot);
+    }
+
+    pub fn get_database_settings(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+    ) -> Result<DatabaseSettings, Box<ConceptReadError>> {
+        if let Some(cache) = &self.type_cache {
+            Ok(cache.get_database_settings())
+        } else {
+            Ok(TypeReader::get_database_property::<DatabaseSettings>(snapshot)?.unwrap_or_default())
+        }
+    }
+
+    pub fn get_internal_schema_version(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+    ) -> Result<Option<InternalSchemaVersion>, Box<ConceptReadError>> {
+        TypeReader::get_database_property::<InternalSchemaVersion>(snapshot)
+    }
+
+    pub fn set_internal_schema_version(&self, snapshot: &mut impl WritableSnapshot, version: InternalSchemaVersion) {
+        TypeWriter::storage_put_database_property(snapshot, version);
+    }
+
+    pub fn get_history_retention(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+    ) -> Result<HistoryRetention, Box<ConceptReadError>> {
+        Ok(TypeReader::get_database_property::<HistoryRetention>(snapshot)?.unwrap_or_default())
+    }
+
+    pub fn set_history_retention(&self, snapshot: &mut impl WritableSnapshot, retention: HistoryRetention) {
+        TypeWriter::storage_put_database_property(snapshot, retention);
+    }
+
+    pub fn set_relation_index_threshold(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        thing_manager: &ThingManager,
+        threshold: RelationIndexThreshold,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        let settings = self.get_database_settings(snapshot)?;
+        let settings = DatabaseSettings { relation_index_threshold: threshold, ..settings };
+        self.set_database_settings(snapshot, thing_manager, settings, storage_counters)
+    }
+
+    /// Replaces the settings of the database. If the relation index threshold changes, relation types crossing it
+    /// have the role player index of their instances built or removed.
+    pub fn set_database_settings(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        thing_manager: &ThingManager,
+        settings: DatabaseSettings,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        let relation_types = self.get_relation_types(snapshot)?.iter().copied().collect_vec();
+        let mut qualified_before = HashSet::new();
+        for &relation_type in &relation_types {
+            if self.type_qualifies_for_relation_index(snapshot, relation_type)? {
+                qualified_before.insert(relation_type);
+            }
+        }
+
+        TypeWriter::storage_put_database_property(snapshot, settings);
+
+        for relation_type in relation_types {
+            let qualifies = self.type_qualifies_for_relation_index(snapshot, relation_type)?;
+            if qualifies != qualified_before.contains(&relation_type) {
+                thing_manager.update_relation_type_index(
+                    snapshot,
+                    relation_type,
+                    qualifies,
+                    |_, _| (),
+                    storage_counters.clone(),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_is_relation_type_independent(
         &self,
         snapshot: &impl ReadableSnapshot,
@@ -2692,6 +3070,43 @@ impl TypeManager {
         }
     }
 
+    /// Returns the time to live of instances of `object_type`, declared on it or inherited from a supertype.
+    pub fn get_object_type_time_to_live(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        object_type: ObjectType,
+    ) -> Result<Option<TimeToLive>, Box<ConceptReadError>> {
+        if let Some(cache) = &self.type_cache {
+            match object_type {
+                ObjectType::Entity(entity_type) => Ok(cache.get_time_to_live(entity_type)),
+                ObjectType::Relation(relation_type) => Ok(cache.get_time_to_live(relation_type)),
+            }
+        } else {
+            TypeReader::get_time_to_live(snapshot, object_type)
+        }
+    }
+
+    /// Sets the time to live of instances of `object_type`. TypeQL has no `@ttl` annotation, so it is only set through
+    /// the database time to live endpoint of the HTTP API.
+    pub fn set_object_type_time_to_live(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        object_type: ObjectType,
+        time_to_live: TimeToLive,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        TypeWriter::storage_put_type_vertex_property(snapshot, object_type, Some(time_to_live));
+        Ok(())
+    }
+
+    pub fn unset_object_type_time_to_live(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        object_type: ObjectType,
+    ) -> Result<(), Box<ConceptWriteError>> {
+        TypeWriter::storage_delete_type_vertex_property::<TimeToLive>(snapshot, object_type);
+        Ok(())
+    }
+
     pub(crate) fn set_owns_annotation_distinct(
         &self,
         snapshot: &mut impl WritableSnapshot,
@@ -0,0 +1,123 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::iter;
+
+use encoding::graph::{
+    type_::{vertex::TypeVertexEncoding, Kind},
+    Typed,
+};
+use storage::snapshot::ReadableSnapshot;
+
+use crate::{
+    error::ConceptReadError,
+    type_::{type_manager::type_reader::TypeReader, KindAPI, TypeAPI},
+};
+
+/// The transitive closure of the type hierarchy, with the supertypes of each type kept as a bitset, so that subtype
+/// checks take constant time.
+#[derive(Debug, Default)]
+pub struct TypeHierarchyClosure {
+    entity_types: KindClosure,
+    relation_types: KindClosure,
+    attribute_types: KindClosure,
+    role_types: KindClosure,
+}
+
+impl TypeHierarchyClosure {
+    pub(crate) fn new(
+        entity_types: KindClosure,
+        relation_types: KindClosure,
+        attribute_types: KindClosure,
+        role_types: KindClosure,
+    ) -> Self {
+        Self { entity_types, relation_types, attribute_types, role_types }
+    }
+
+    pub(crate) fn read(snapshot: &impl ReadableSnapshot) -> Result<Self, Box<ConceptReadError>> {
+        Ok(Self {
+            entity_types: KindClosure::read(snapshot, TypeReader::get_entity_types(snapshot)?)?,
+            relation_types: KindClosure::read(snapshot, TypeReader::get_relation_types(snapshot)?)?,
+            attribute_types: KindClosure::read(snapshot, TypeReader::get_attribute_types(snapshot)?)?,
+            role_types: KindClosure::read(snapshot, TypeReader::get_role_types(snapshot)?)?,
+        })
+    }
+
+    pub fn is_subtype_transitive_of<T: KindAPI>(&self, subtype: T, supertype: T) -> bool {
+        subtype != supertype && self.is_subtype_transitive_of_or_same(subtype, supertype)
+    }
+
+    pub fn is_subtype_transitive_of_or_same<T: KindAPI>(&self, subtype: T, supertype: T) -> bool {
+        self.kind_closure(T::KIND).contains(subtype, supertype)
+    }
+
+    fn kind_closure(&self, kind: Kind) -> &KindClosure {
+        match kind {
+            Kind::Entity => &self.entity_types,
+            Kind::Relation => &self.relation_types,
+            Kind::Attribute => &self.attribute_types,
+            Kind::Role => &self.role_types,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct KindClosure {
+    // the dense index of each type, by type ID, so that sparse type IDs do not inflate the bitsets
+    indices: Box<[u32]>,
+    // words in the bitset of each type
+    stride: usize,
+    // the bitsets of the supertypes of each type, including the type itself, in dense index order
+    supertypes: Box<[u64]>,
+}
+
+impl KindClosure {
+    const NO_INDEX: u32 = u32::MAX;
+
+    pub(crate) fn new<'a, T: TypeAPI + 'a>(hierarchies: impl IntoIterator<Item = (T, &'a [T])>) -> Self {
+        let hierarchies = hierarchies.into_iter().collect::<Vec<_>>();
+        let id_count = hierarchies.iter().map(|(type_, _)| Self::type_id(*type_) + 1).max().unwrap_or(0);
+        let mut indices = vec![Self::NO_INDEX; id_count].into_boxed_slice();
+        for (index, (type_, _)) in hierarchies.iter().enumerate() {
+            indices[Self::type_id(*type_)] = index as u32;
+        }
+        let type_count = hierarchies.len();
+        let stride = type_count.div_ceil(u64::BITS as usize);
+        let mut supertypes = vec![0; type_count * stride].into_boxed_slice();
+        for (row, (type_, type_supertypes)) in hierarchies.into_iter().enumerate() {
+            for supertype in iter::once(type_).chain(type_supertypes.iter().copied()) {
+                let index = indices[Self::type_id(supertype)] as usize;
+                supertypes[row * stride + index / u64::BITS as usize] |= 1 << (index % u64::BITS as usize);
+            }
+        }
+        Self { indices, stride, supertypes }
+    }
+
+    fn read<T: TypeAPI>(snapshot: &impl ReadableSnapshot, types: Vec<T>) -> Result<Self, Box<ConceptReadError>> {
+        let hierarchies = types
+            .into_iter()
+            .map(|type_| Ok((type_, TypeReader::get_supertypes_transitive(snapshot, type_)?)))
+            .collect::<Result<Vec<_>, Box<ConceptReadError>>>()?;
+        Ok(Self::new(hierarchies.iter().map(|(type_, supertypes)| (*type_, supertypes.as_slice()))))
+    }
+
+    fn type_id(type_: impl TypeAPI) -> usize {
+        type_.vertex().type_id_().as_u16() as usize
+    }
+
+    fn index(&self, type_: impl TypeAPI) -> Option<usize> {
+        self.indices.get(Self::type_id(type_)).filter(|index| **index != Self::NO_INDEX).map(|index| *index as usize)
+    }
+
+    fn contains(&self, subtype: impl TypeAPI, supertype: impl TypeAPI) -> bool {
+        let (Some(subtype_index), Some(supertype_index)) = (self.index(subtype), self.index(supertype)) else {
+            return false;
+        };
+        self.supertypes[subtype_index * self.stride + supertype_index / u64::BITS as usize]
+            & (1 << (supertype_index % u64::BITS as usize))
+            != 0
+    }
+}
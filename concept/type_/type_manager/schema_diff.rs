@@ -0,0 +1,473 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+};
+
+use encoding::graph::type_::Kind;
+use itertools::Itertools;
+use storage::snapshot::ReadableSnapshot;
+
+use crate::{
+    error::ConceptReadError,
+    type_::{
+        annotation::Annotation, attribute_type::AttributeType, relation_type::RelationType, type_manager::TypeManager,
+        Capability, KindAPI, OwnerAPI, PlayerAPI, TypeAPI, TypeQLSyntax,
+    },
+};
+
+// TypeQL keyword separating the undefined part from the type it is undefined from
+const UNDEFINE_FROM: &str = "from";
+
+/// The statements converging one schema onto another, grouped by the clause that applies them.
+/// The clauses are applied in the order `undefine`, `define`, `redefine`, so that types changing kind are removed
+/// before they are defined again. Dependants are detached from a removed supertype in the same `undefine`.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct SchemaDiff {
+    pub define: Vec<String>,
+    pub redefine: Vec<String>,
+    pub undefine: Vec<String>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.define.is_empty() && self.redefine.is_empty() && self.undefine.is_empty()
+    }
+
+    /// One TypeQL query per non-empty clause, in the order they must be executed.
+    pub fn queries(&self) -> Vec<String> {
+        [
+            (typeql::token::Clause::Undefine, &self.undefine),
+            (typeql::token::Clause::Define, &self.define),
+            (typeql::token::Clause::Redefine, &self.redefine),
+        ]
+        .into_iter()
+        .filter(|(_, statements)| !statements.is_empty())
+        .map(|(clause, statements)| {
+            let mut query = clause.to_string();
+            for statement in statements {
+                query.push_str("\n  ");
+                query.push_str(statement);
+                query.push(';');
+            }
+            query
+        })
+        .collect()
+    }
+
+    pub(crate) fn build(
+        current_snapshot: &impl ReadableSnapshot,
+        current_type_manager: &TypeManager,
+        target_snapshot: &impl ReadableSnapshot,
+        target_type_manager: &TypeManager,
+    ) -> Result<Self, Box<ConceptReadError>> {
+        let current = SchemaSummary::build(current_snapshot, current_type_manager)?;
+        let target = SchemaSummary::build(target_snapshot, target_type_manager)?;
+
+        let mut diff = Self::default();
+        for (label, target_type) in &target.types {
+            match current.types.get(label) {
+                Some(current_type) if current_type.kind == target_type.kind => {
+                    diff.add_type_changes(label, current_type, target_type, &target.types)
+                }
+                Some(_) => {
+                    diff.undefine.push(label.clone());
+                    diff.define.push(target_type.syntax.clone());
+                }
+                None => diff.define.push(target_type.syntax.clone()),
+            }
+        }
+        for label in current.types.keys().filter(|label| !target.types.contains_key(*label)) {
+            diff.undefine.push(label.clone());
+        }
+
+        for (name, target_fields) in &target.structs {
+            let struct_ = format!("{} {name}:", typeql::token::Keyword::Struct);
+            let Some(current_fields) = current.structs.get(name) else {
+                diff.define.push(format!("{struct_} {}", target_fields.values().join(", ")));
+                continue;
+            };
+            for (field, target_field) in target_fields {
+                match current_fields.get(field) {
+                    None => diff.define.push(format!("{struct_} {target_field}")),
+                    Some(current_field) if current_field != target_field => {
+                        diff.redefine.push(format!("{struct_} {target_field}"))
+                    }
+                    Some(_) => {}
+                }
+            }
+            // TypeQL has no statement removing a single struct field, so fields only present in the current
+            // schema are left in place
+        }
+        for name in current.structs.keys().filter(|name| !target.structs.contains_key(*name)) {
+            diff.undefine.push(format!("{} {name}", typeql::token::Keyword::Struct));
+        }
+        Ok(diff)
+    }
+
+    /// The statements converging the functions named in `current` onto those in `target`, given by their definitions.
+    pub fn add_function_changes(&mut self, current: &BTreeMap<String, String>, target: &BTreeMap<String, String>) {
+        for (name, target_definition) in target {
+            match current.get(name) {
+                None => self.define.push(target_definition.clone()),
+                Some(current_definition) if current_definition != target_definition => {
+                    self.redefine.push(target_definition.clone())
+                }
+                Some(_) => {}
+            }
+        }
+        for name in current.keys().filter(|name| !target.contains_key(*name)) {
+            self.undefine.push(format!("{} {name}", typeql::token::Keyword::Fun));
+        }
+    }
+
+    fn add_type_changes(
+        &mut self,
+        label: &str,
+        current: &TypeSummary,
+        target: &TypeSummary,
+        target_types: &BTreeMap<String, TypeSummary>,
+    ) {
+        let kind = target.kind;
+        let type_ = format!("{kind} {label}");
+
+        self.add_annotation_changes(&type_, label, &current.annotations, &target.annotations);
+
+        match (&current.supertype, &target.supertype) {
+            (None, Some(supertype)) => self.define.push(format!("{type_} {} {supertype}", typeql::token::Keyword::Sub)),
+            (Some(current_supertype), Some(supertype)) if current_supertype != supertype => {
+                let is_supertype_kept =
+                    target_types.get(current_supertype).is_some_and(|supertype| supertype.kind == kind);
+                if is_supertype_kept {
+                    self.redefine.push(format!("{type_} {} {supertype}", typeql::token::Keyword::Sub))
+                } else {
+                    // the current supertype is undefined before anything is defined, so the type is detached first
+                    self.undefine
+                        .push(format!("{} {current_supertype} {UNDEFINE_FROM} {label}", typeql::token::Keyword::Sub));
+                    self.define.push(format!("{type_} {} {supertype}", typeql::token::Keyword::Sub))
+                }
+            }
+            (Some(supertype), None) => {
+                self.undefine.push(format!("{} {supertype} {UNDEFINE_FROM} {label}", typeql::token::Keyword::Sub))
+            }
+            _ => {}
+        }
+
+        match (&current.value_type, &target.value_type) {
+            (None, Some(value_type)) => {
+                self.define.push(format!("{type_} {} {value_type}", typeql::token::Keyword::Value))
+            }
+            (Some(current_value_type), Some(value_type)) if current_value_type != value_type => {
+                self.redefine.push(format!("{type_} {} {value_type}", typeql::token::Keyword::Value))
+            }
+            (Some(value_type), None) => {
+                self.undefine.push(format!("{} {value_type} {UNDEFINE_FROM} {label}", typeql::token::Keyword::Value))
+            }
+            _ => {}
+        }
+        if let Some(value_type) = &target.value_type {
+            let value = format!("{} {value_type}", typeql::token::Keyword::Value);
+            self.add_annotation_changes(
+                &format!("{type_} {value}"),
+                &format!("{label} {value}"),
+                &current.value_type_annotations,
+                &target.value_type_annotations,
+            );
+        }
+
+        for (key, target_capability) in &target.capabilities {
+            let declaration = format!("{type_} {}", target_capability.declaration());
+            match current.capabilities.get(key) {
+                None => self.define.push(format!("{declaration}{}", target_capability.annotations_syntax())),
+                Some(current_capability) => {
+                    if current_capability.ordering != target_capability.ordering
+                        || current_capability.specialises != target_capability.specialises
+                    {
+                        match &target_capability.specialises {
+                            None => self.undefine.push(format!(
+                                "{} {} {UNDEFINE_FROM} {label} {key}",
+                                typeql::token::Keyword::As,
+                                current_capability.specialises.as_ref().unwrap()
+                            )),
+                            Some(_) => self.redefine.push(declaration.clone()),
+                        }
+                    }
+                    let capability = format!("{label} {key}");
+                    self.add_annotation_changes(
+                        &declaration,
+                        &capability,
+                        &current_capability.annotations,
+                        &target_capability.annotations,
+                    );
+                }
+            }
+        }
+        for key in current.capabilities.keys().filter(|key| !target.capabilities.contains_key(*key)) {
+            self.undefine.push(format!("{key} {UNDEFINE_FROM} {label}"));
+        }
+    }
+
+    fn add_annotation_changes(
+        &mut self,
+        annotated: &str,
+        undefine_target: &str,
+        current: &BTreeMap<String, String>,
+        target: &BTreeMap<String, String>,
+    ) {
+        for (category, annotation) in target {
+            match current.get(category) {
+                None => self.define.push(format!("{annotated} {annotation}")),
+                Some(current_annotation) if current_annotation != annotation => {
+                    self.redefine.push(format!("{annotated} {annotation}"))
+                }
+                Some(_) => {}
+            }
+        }
+        for category in current.keys().filter(|category| !target.contains_key(*category)) {
+            self.undefine.push(format!("{category} {UNDEFINE_FROM} {undefine_target}"));
+        }
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.queries().join("\n\n"))
+    }
+}
+
+struct SchemaSummary {
+    types: BTreeMap<String, TypeSummary>,
+    structs: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl SchemaSummary {
+    fn build(snapshot: &impl ReadableSnapshot, type_manager: &TypeManager) -> Result<Self, Box<ConceptReadError>> {
+        let mut types = BTreeMap::new();
+        for attribute_type in type_manager.get_attribute_types(snapshot)?.iter() {
+            let (label, mut summary) = TypeSummary::build(*attribute_type, snapshot, type_manager)?;
+            summary.add_value_type(*attribute_type, snapshot, type_manager)?;
+            types.insert(label, summary);
+        }
+        for entity_type in type_manager.get_entity_types(snapshot)?.iter() {
+            let (label, mut summary) = TypeSummary::build(*entity_type, snapshot, type_manager)?;
+            summary.add_owns(*entity_type, snapshot, type_manager)?;
+            summary.add_plays(*entity_type, snapshot, type_manager)?;
+            types.insert(label, summary);
+        }
+        for relation_type in type_manager.get_relation_types(snapshot)?.iter() {
+            let (label, mut summary) = TypeSummary::build(*relation_type, snapshot, type_manager)?;
+            summary.add_relates(*relation_type, snapshot, type_manager)?;
+            summary.add_owns(*relation_type, snapshot, type_manager)?;
+            summary.add_plays(*relation_type, snapshot, type_manager)?;
+            types.insert(label, summary);
+        }
+
+        let mut structs = BTreeMap::new();
+        for (_, struct_definition) in type_manager.get_struct_definitions(snapshot)? {
+            let mut fields = BTreeMap::new();
+            for (field_name, field_id) in &struct_definition.field_names {
+                let field = struct_definition.fields.get(field_id).unwrap();
+                let mut syntax = format!("{field_name} {} ", typeql::token::Keyword::Value);
+                field.value_type.format_syntax(&mut syntax, snapshot, type_manager)?;
+                if field.optional {
+                    syntax.push_str(typeql::token::Char::Question.as_str());
+                }
+                fields.insert(field_name.clone(), syntax);
+            }
+            structs.insert(struct_definition.name.clone(), fields);
+        }
+        Ok(Self { types, structs })
+    }
+}
+
+struct TypeSummary {
+    kind: Kind,
+    syntax: String,
+    annotations: BTreeMap<String, String>,
+    supertype: Option<String>,
+    value_type: Option<String>,
+    value_type_annotations: BTreeMap<String, String>,
+    capabilities: BTreeMap<String, CapabilitySummary>,
+}
+
+impl TypeSummary {
+    fn build<T: KindAPI>(
+        type_: T,
+        snapshot: &impl ReadableSnapshot,
+        type_manager: &TypeManager,
+    ) -> Result<(String, Self), Box<ConceptReadError>> {
+        let label = type_.get_label(snapshot, type_manager)?.scoped_name().as_str().to_owned();
+        let mut syntax = String::new();
+        type_.format_syntax(&mut syntax, snapshot, type_manager)?;
+        let syntax = syntax.trim().trim_end_matches(';').to_owned();
+        let annotations = annotations_by_category(
+            type_.get_annotations_declared(snapshot, type_manager)?.iter().map(|annotation| annotation.clone().into()),
+        );
+        let supertype = match type_.get_supertype(snapshot, type_manager)? {
+            Some(supertype) => Some(supertype.get_label(snapshot, type_manager)?.name().as_str().to_owned()),
+            None => None,
+        };
+        let summary = Self {
+            kind: T::KIND,
+            syntax,
+            annotations,
+            supertype,
+            value_type: None,
+            value_type_annotations: BTreeMap::new(),
+            capabilities: BTreeMap::new(),
+        };
+        Ok((label, summary))
+    }
+
+    fn add_value_type(
+        &mut self,
+        attribute_type: AttributeType,
+        snapshot: &impl ReadableSnapshot,
+        type_manager: &TypeManager,
+    ) -> Result<(), Box<ConceptReadError>> {
+        if let Some(value_type) = attribute_type.get_value_type_declared(snapshot, type_manager)? {
+            let mut syntax = String::new();
+            value_type.format_syntax(&mut syntax, snapshot, type_manager)?;
+            self.value_type = Some(syntax);
+        }
+        self.annotations = annotations_by_category(
+            attribute_type
+                .get_annotations_declared(snapshot, type_manager)?
+                .iter()
+                .filter(|annotation| !annotation.is_value_type_annotation())
+                .map(|annotation| Annotation::from(annotation.clone())),
+        );
+        self.value_type_annotations = annotations_by_category(
+            attribute_type
+                .get_value_type_annotations_declared(snapshot, type_manager)?
+                .into_iter()
+                .map(Annotation::from),
+        );
+        Ok(())
+    }
+
+    fn add_owns(
+        &mut self,
+        owner: impl OwnerAPI,
+        snapshot: &impl ReadableSnapshot,
+        type_manager: &TypeManager,
+    ) -> Result<(), Box<ConceptReadError>> {
+        for owns in owner.get_owns_declared(snapshot, type_manager)?.iter() {
+            let attribute = owns.attribute().get_label(snapshot, type_manager)?.name().as_str().to_owned();
+            let capability = CapabilitySummary {
+                keyword: typeql::token::Keyword::Owns,
+                interface: attribute,
+                ordering: owns.get_ordering(snapshot, type_manager)?.to_string(),
+                specialises: None,
+                annotations: annotations_by_category(
+                    owns.get_annotations_declared(snapshot, type_manager)?.iter().map(|a| Annotation::from(a.clone())),
+                ),
+            };
+            self.capabilities.insert(capability.key(), capability);
+        }
+        Ok(())
+    }
+
+    fn add_plays(
+        &mut self,
+        player: impl PlayerAPI,
+        snapshot: &impl ReadableSnapshot,
+        type_manager: &TypeManager,
+    ) -> Result<(), Box<ConceptReadError>> {
+        for plays in player.get_plays_declared(snapshot, type_manager)?.iter() {
+            let role = plays.role().get_label(snapshot, type_manager)?.scoped_name().as_str().to_owned();
+            let capability = CapabilitySummary {
+                keyword: typeql::token::Keyword::Plays,
+                interface: role,
+                ordering: String::new(),
+                specialises: None,
+                annotations: annotations_by_category(
+                    plays.get_annotations_declared(snapshot, type_manager)?.iter().map(|a| Annotation::from(a.clone())),
+                ),
+            };
+            self.capabilities.insert(capability.key(), capability);
+        }
+        Ok(())
+    }
+
+    fn add_relates(
+        &mut self,
+        relation_type: RelationType,
+        snapshot: &impl ReadableSnapshot,
+        type_manager: &TypeManager,
+    ) -> Result<(), Box<ConceptReadError>> {
+        let declared_relates = relation_type.get_relates_declared(snapshot, type_manager)?;
+        let mut super_roles = HashSet::new();
+        for relates in declared_relates.iter() {
+            if let Some(role_supertype) = relates.role().get_supertype(snapshot, type_manager)? {
+                super_roles.insert(role_supertype);
+            }
+        }
+        // Relates of specialised roles are implied by the specialising relates, as in the exported syntax
+        for relates in declared_relates.iter().filter(|relates| !super_roles.contains(&relates.role())) {
+            let role = relates.role();
+            let specialises = match role.get_supertype(snapshot, type_manager)? {
+                Some(role_supertype) => {
+                    Some(role_supertype.get_label(snapshot, type_manager)?.name().as_str().to_owned())
+                }
+                None => None,
+            };
+            let capability = CapabilitySummary {
+                keyword: typeql::token::Keyword::Relates,
+                interface: role.get_label(snapshot, type_manager)?.name().as_str().to_owned(),
+                ordering: role.get_ordering(snapshot, type_manager)?.to_string(),
+                specialises,
+                annotations: annotations_by_category(
+                    relates
+                        .get_annotations_declared(snapshot, type_manager)?
+                        .iter()
+                        .map(|a| Annotation::from(a.clone())),
+                ),
+            };
+            self.capabilities.insert(capability.key(), capability);
+        }
+        Ok(())
+    }
+}
+
+struct CapabilitySummary {
+    keyword: typeql::token::Keyword,
+    interface: String,
+    ordering: String,
+    specialises: Option<String>,
+    annotations: BTreeMap<String, String>,
+}
+
+impl CapabilitySummary {
+    fn key(&self) -> String {
+        format!("{} {}", self.keyword, self.interface)
+    }
+
+    fn declaration(&self) -> String {
+        match &self.specialises {
+            None => format!("{} {}{}", self.keyword, self.interface, self.ordering),
+            Some(specialised) => format!(
+                "{} {}{} {} {}{}",
+                self.keyword,
+                self.interface,
+                self.ordering,
+                typeql::token::Keyword::As,
+                specialised,
+                self.ordering
+            ),
+        }
+    }
+
+    fn annotations_syntax(&self) -> String {
+        self.annotations.values().map(|annotation| format!(" {annotation}")).join("")
+    }
+}
+
+fn annotations_by_category(annotations: impl Iterator<Item = Annotation>) -> BTreeMap<String, String> {
+    annotations.map(|annotation| (annotation.category().to_string(), annotation.to_string())).collect()
+}
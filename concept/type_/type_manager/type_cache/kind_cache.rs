@@ -36,16 +36,16 @@ use crate::type_::{
     relation_type::RelationType,
     role_type::RoleType,
     type_manager::type_reader::TypeReader,
-    Capability, Independent, KindAPI, ObjectTypeAPI, Ordering, PlayerAPI, TypeAPI,
+    Capability, Independent, KindAPI, ObjectTypeAPI, Ordering, PlayerAPI, TimeToLive, TypeAPI,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct EntityTypeCache {
     pub(super) common_type_cache: CommonTypeCache<EntityType>,
     pub(super) object_cache: ObjectCache,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct RelationTypeCache {
     pub(super) common_type_cache: CommonTypeCache<RelationType>,
     pub(super) relates_root: HashSet<Relates>,
@@ -57,7 +57,7 @@ pub(crate) struct RelationTypeCache {
     pub(super) object_cache: ObjectCache,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct RoleTypeCache {
     pub(super) common_type_cache: CommonTypeCache<RoleType>,
     pub(super) ordering: Ordering,
@@ -68,7 +68,7 @@ pub(crate) struct RoleTypeCache {
     pub(super) player_types: HashMap<ObjectType, Plays>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct AttributeTypeCache {
     pub(super) common_type_cache: CommonTypeCache<AttributeType>,
     pub(super) value_type_declared: Option<ValueType>,
@@ -77,24 +77,24 @@ pub(crate) struct AttributeTypeCache {
     pub(super) owner_types: HashMap<ObjectType, Owns>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct OwnsCache {
     pub(super) ordering: Ordering,
     pub(super) common_capability_cache: CommonCapabilityCache<Owns>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct PlaysCache {
     pub(super) common_capability_cache: CommonCapabilityCache<Plays>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct RelatesCache {
     pub(super) is_implicit: bool,
     pub(super) common_capability_cache: CommonCapabilityCache<Relates>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct CommonTypeCache<T: KindAPI> {
     pub(super) type_: T,
     pub(super) label: Arc<Label>,
@@ -107,14 +107,14 @@ pub(crate) struct CommonTypeCache<T: KindAPI> {
     pub(super) subtypes_transitive: Vec<T>, // TODO: benchmark smallvec
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct CommonCapabilityCache<CAP: Capability> {
     pub(super) capability: CAP,
     pub(super) annotations_declared: HashSet<CAP::AnnotationType>,
     pub(super) constraints: HashSet<CapabilityConstraint<CAP>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ObjectCache {
     pub(super) owns_declared: HashSet<Owns>,
     pub(super) owns: HashSet<Owns>,
@@ -124,10 +124,43 @@ pub struct ObjectCache {
     pub(super) plays: HashSet<Plays>,
     pub(super) plays_with_specialised: HashSet<Plays>,
     pub(super) played_role_type_constraints: HashMap<RoleType, HashSet<CapabilityConstraint<Plays>>>,
+    pub(super) time_to_live: Option<TimeToLive>,
+}
+
+/// The caches of a previous `TypeCache`, which may be carried over into a new one
+/// for the types and capabilities that are not affected by the schema changes in between.
+#[derive(Debug)]
+pub(super) struct CacheReuse<'a, C: ?Sized> {
+    pub(super) previous: &'a C,
+    pub(super) affected_types: &'a HashSet<TypeVertex>,
+}
+
+impl<Cache: Clone> CacheReuse<'_, [Option<Cache>]> {
+    fn get_type(&self, type_: impl TypeVertexEncoding) -> Option<Cache> {
+        let vertex = type_.vertex();
+        if self.affected_types.contains(&vertex) {
+            return None;
+        }
+        self.previous.get(vertex.type_id_().as_u16() as usize)?.clone()
+    }
+}
+
+impl<CAP: Capability, Cache: Clone> CacheReuse<'_, HashMap<CAP, Cache>> {
+    fn get_capability(&self, capability: CAP) -> Option<Cache> {
+        if self.affected_types.contains(&capability.object().vertex())
+            || self.affected_types.contains(&capability.interface().vertex())
+        {
+            return None;
+        }
+        self.previous.get(&capability).cloned()
+    }
 }
 
 impl EntityTypeCache {
-    pub(super) fn create(snapshot: &impl ReadableSnapshot) -> Box<[Option<EntityTypeCache>]> {
+    pub(super) fn create(
+        snapshot: &impl ReadableSnapshot,
+        reuse: Option<&CacheReuse<'_, [Option<EntityTypeCache>]>>,
+    ) -> Box<[Option<EntityTypeCache>]> {
         let entities = snapshot
             .iterate_range(
                 &KeyRange::new_within(EntityType::prefix_for_kind(), EntityType::PREFIX.fixed_width_keys()),
@@ -139,18 +172,27 @@ impl EntityTypeCache {
         let mut caches = (0..=max_entity_id).map(|_| None).collect::<Box<[_]>>();
 
         for entity_type in entities.into_iter() {
-            let cache = EntityTypeCache {
-                common_type_cache: CommonTypeCache::create(snapshot, entity_type),
-                object_cache: ObjectCache::create(snapshot, entity_type),
-            };
+            let cache = reuse
+                .and_then(|reuse| reuse.get_type(entity_type))
+                .unwrap_or_else(|| Self::create_for(snapshot, entity_type));
             caches[entity_type.vertex().type_id_().as_u16() as usize] = Some(cache);
         }
         caches
     }
+
+    fn create_for(snapshot: &impl ReadableSnapshot, entity_type: EntityType) -> EntityTypeCache {
+        EntityTypeCache {
+            common_type_cache: CommonTypeCache::create(snapshot, entity_type),
+            object_cache: ObjectCache::create(snapshot, entity_type),
+        }
+    }
 }
 
 impl RelationTypeCache {
-    pub(super) fn create(snapshot: &impl ReadableSnapshot) -> Box<[Option<RelationTypeCache>]> {
+    pub(super) fn create(
+        snapshot: &impl ReadableSnapshot,
+        reuse: Option<&CacheReuse<'_, [Option<RelationTypeCache>]>>,
+    ) -> Box<[Option<RelationTypeCache>]> {
         let relations = snapshot
             .iterate_range(
                 &KeyRange::new_within(RelationType::prefix_for_kind(), Prefix::VertexRelationType.fixed_width_keys()),
@@ -161,34 +203,42 @@ impl RelationTypeCache {
         let max_relation_id = relations.iter().map(|r| r.vertex().type_id_().as_u16()).max().unwrap_or(0);
         let mut caches = (0..=max_relation_id).map(|_| None).collect::<Box<[_]>>();
         for relation_type in relations.into_iter() {
-            let common_type_cache = CommonTypeCache::create(snapshot, relation_type);
-            let object_cache = ObjectCache::create(snapshot, relation_type);
-            let relates_root = TypeReader::get_relation_type_relates_root(snapshot, relation_type).unwrap();
-            let relates_declared = TypeReader::get_capabilities_declared::<Relates>(snapshot, relation_type).unwrap();
-            let relates = TypeReader::get_capabilities::<Relates>(snapshot, relation_type, false).unwrap();
-            let relates_with_specialised =
-                TypeReader::get_capabilities::<Relates>(snapshot, relation_type, true).unwrap();
-            let related_role_type_constraints =
-                TypeReader::get_type_capabilities_constraints::<Relates>(snapshot, relation_type).unwrap();
-            let independency = TypeReader::get_relation_type_independence(snapshot, relation_type).unwrap();
-            let cache = RelationTypeCache {
-                common_type_cache,
-                relates_root,
-                relates_declared,
-                relates,
-                relates_with_specialised,
-                related_role_type_constraints,
-                independence: independency,
-                object_cache,
-            };
+            let cache = reuse
+                .and_then(|reuse| reuse.get_type(relation_type))
+                .unwrap_or_else(|| Self::create_for(snapshot, relation_type));
             caches[relation_type.vertex().type_id_().as_u16() as usize] = Some(cache);
         }
         caches
     }
+
+    fn create_for(snapshot: &impl ReadableSnapshot, relation_type: RelationType) -> RelationTypeCache {
+        let common_type_cache = CommonTypeCache::create(snapshot, relation_type);
+        let object_cache = ObjectCache::create(snapshot, relation_type);
+        let relates_root = TypeReader::get_relation_type_relates_root(snapshot, relation_type).unwrap();
+        let relates_declared = TypeReader::get_capabilities_declared::<Relates>(snapshot, relation_type).unwrap();
+        let relates = TypeReader::get_capabilities::<Relates>(snapshot, relation_type, false).unwrap();
+        let relates_with_specialised = TypeReader::get_capabilities::<Relates>(snapshot, relation_type, true).unwrap();
+        let related_role_type_constraints =
+            TypeReader::get_type_capabilities_constraints::<Relates>(snapshot, relation_type).unwrap();
+        let independency = TypeReader::get_relation_type_independence(snapshot, relation_type).unwrap();
+        RelationTypeCache {
+            common_type_cache,
+            relates_root,
+            relates_declared,
+            relates,
+            relates_with_specialised,
+            related_role_type_constraints,
+            independence: independency,
+            object_cache,
+        }
+    }
 }
 
 impl AttributeTypeCache {
-    pub(super) fn create(snapshot: &impl ReadableSnapshot) -> Box<[Option<AttributeTypeCache>]> {
+    pub(super) fn create(
+        snapshot: &impl ReadableSnapshot,
+        reuse: Option<&CacheReuse<'_, [Option<AttributeTypeCache>]>>,
+    ) -> Box<[Option<AttributeTypeCache>]> {
         let attributes = snapshot
             .iterate_range(
                 &KeyRange::new_within(AttributeType::prefix_for_kind(), TypeVertex::FIXED_WIDTH_ENCODING),
@@ -199,25 +249,31 @@ impl AttributeTypeCache {
         let max_attribute_id = attributes.iter().map(|a| a.vertex().type_id_().as_u16()).max().unwrap_or(0);
         let mut caches = (0..=max_attribute_id).map(|_| None).collect::<Box<[_]>>();
         for attribute_type in attributes {
-            let cache = AttributeTypeCache {
-                common_type_cache: CommonTypeCache::create(snapshot, attribute_type),
-                value_type_declared: TypeReader::get_value_type_declared(snapshot, attribute_type).unwrap(),
-                value_type: TypeReader::get_value_type(snapshot, attribute_type).unwrap(),
-                owns: TypeReader::get_capabilities_for_interface::<Owns>(snapshot, attribute_type).unwrap(),
-                owner_types: TypeReader::get_object_types_with_capabilities_for_interface::<Owns>(
-                    snapshot,
-                    attribute_type,
-                )
-                .unwrap(),
-            };
+            let cache = reuse
+                .and_then(|reuse| reuse.get_type(attribute_type))
+                .unwrap_or_else(|| Self::create_for(snapshot, attribute_type));
             caches[attribute_type.vertex().type_id_().as_u16() as usize] = Some(cache);
         }
         caches
     }
+
+    fn create_for(snapshot: &impl ReadableSnapshot, attribute_type: AttributeType) -> AttributeTypeCache {
+        AttributeTypeCache {
+            common_type_cache: CommonTypeCache::create(snapshot, attribute_type),
+            value_type_declared: TypeReader::get_value_type_declared(snapshot, attribute_type).unwrap(),
+            value_type: TypeReader::get_value_type(snapshot, attribute_type).unwrap(),
+            owns: TypeReader::get_capabilities_for_interface::<Owns>(snapshot, attribute_type).unwrap(),
+            owner_types: TypeReader::get_object_types_with_capabilities_for_interface::<Owns>(snapshot, attribute_type)
+                .unwrap(),
+        }
+    }
 }
 
 impl RoleTypeCache {
-    pub(super) fn create(snapshot: &impl ReadableSnapshot) -> Box<[Option<RoleTypeCache>]> {
+    pub(super) fn create(
+        snapshot: &impl ReadableSnapshot,
+        reuse: Option<&CacheReuse<'_, [Option<RoleTypeCache>]>>,
+    ) -> Box<[Option<RoleTypeCache>]> {
         let roles = snapshot
             .iterate_range(
                 &KeyRange::new_within(RoleType::prefix_for_kind(), TypeVertex::FIXED_WIDTH_ENCODING),
@@ -228,30 +284,37 @@ impl RoleTypeCache {
         let max_role_id = roles.iter().map(|r| r.vertex().type_id_().as_u16()).max().unwrap_or(0);
         let mut caches = (0..=max_role_id).map(|_| None).collect::<Box<[_]>>();
         for role_type in roles.into_iter() {
-            let ordering = TypeReader::get_type_ordering(snapshot, role_type).unwrap();
-            let cache = RoleTypeCache {
-                common_type_cache: CommonTypeCache::create(snapshot, role_type),
-                ordering,
-                relates_explicit: TypeReader::get_role_type_relates_explicit(snapshot, role_type).unwrap(),
-                relates: TypeReader::get_capabilities_for_interface::<Relates>(snapshot, role_type).unwrap(),
-                relation_types: TypeReader::get_object_types_with_capabilities_for_interface::<Relates>(
-                    snapshot, role_type,
-                )
-                .unwrap(),
-                plays: TypeReader::get_capabilities_for_interface::<Plays>(snapshot, role_type).unwrap(),
-                player_types: TypeReader::get_object_types_with_capabilities_for_interface::<Plays>(
-                    snapshot, role_type,
-                )
-                .unwrap(),
-            };
+            let cache = reuse
+                .and_then(|reuse| reuse.get_type(role_type))
+                .unwrap_or_else(|| Self::create_for(snapshot, role_type));
             caches[role_type.vertex().type_id_().as_u16() as usize] = Some(cache);
         }
         caches
     }
+
+    fn create_for(snapshot: &impl ReadableSnapshot, role_type: RoleType) -> RoleTypeCache {
+        let ordering = TypeReader::get_type_ordering(snapshot, role_type).unwrap();
+        RoleTypeCache {
+            common_type_cache: CommonTypeCache::create(snapshot, role_type),
+            ordering,
+            relates_explicit: TypeReader::get_role_type_relates_explicit(snapshot, role_type).unwrap(),
+            relates: TypeReader::get_capabilities_for_interface::<Relates>(snapshot, role_type).unwrap(),
+            relation_types: TypeReader::get_object_types_with_capabilities_for_interface::<Relates>(
+                snapshot, role_type,
+            )
+            .unwrap(),
+            plays: TypeReader::get_capabilities_for_interface::<Plays>(snapshot, role_type).unwrap(),
+            player_types: TypeReader::get_object_types_with_capabilities_for_interface::<Plays>(snapshot, role_type)
+                .unwrap(),
+        }
+    }
 }
 
 impl OwnsCache {
-    pub(super) fn create(snapshot: &impl ReadableSnapshot) -> HashMap<Owns, OwnsCache> {
+    pub(super) fn create(
+        snapshot: &impl ReadableSnapshot,
+        reuse: Option<&CacheReuse<'_, HashMap<Owns, OwnsCache>>>,
+    ) -> HashMap<Owns, OwnsCache> {
         let mut map = HashMap::new();
         let mut it = snapshot.iterate_range(
             &KeyRange::new_within(TypeEdge::build_prefix(Prefix::EdgeOwnsReverse), TypeEdge::FIXED_WIDTH_ENCODING),
@@ -262,10 +325,10 @@ impl OwnsCache {
             let attribute = AttributeType::new(edge.from());
             let owner = ObjectType::new(edge.to());
             let owns = Owns::new(owner, attribute);
-            let cache = OwnsCache {
+            let cache = reuse.and_then(|reuse| reuse.get_capability(owns)).unwrap_or_else(|| OwnsCache {
                 ordering: TypeReader::get_capability_ordering(snapshot, owns).unwrap(),
                 common_capability_cache: CommonCapabilityCache::create(snapshot, owns),
-            };
+            });
             map.insert(owns, cache);
         }
         map
@@ -273,7 +336,10 @@ impl OwnsCache {
 }
 
 impl PlaysCache {
-    pub(super) fn create(snapshot: &impl ReadableSnapshot) -> HashMap<Plays, PlaysCache> {
+    pub(super) fn create(
+        snapshot: &impl ReadableSnapshot,
+        reuse: Option<&CacheReuse<'_, HashMap<Plays, PlaysCache>>>,
+    ) -> HashMap<Plays, PlaysCache> {
         let mut map = HashMap::new();
         let mut it = snapshot.iterate_range(
             &KeyRange::new_within(TypeEdge::build_prefix(Prefix::EdgePlays), TypeEdge::FIXED_WIDTH_ENCODING),
@@ -285,7 +351,9 @@ impl PlaysCache {
             let player = ObjectType::new(edge.from());
             let role = RoleType::new(edge.to());
             let plays = Plays::new(player, role);
-            let cache = PlaysCache { common_capability_cache: CommonCapabilityCache::create(snapshot, plays) };
+            let cache = reuse.and_then(|reuse| reuse.get_capability(plays)).unwrap_or_else(|| PlaysCache {
+                common_capability_cache: CommonCapabilityCache::create(snapshot, plays),
+            });
             map.insert(plays, cache);
         }
         map
@@ -293,7 +361,10 @@ impl PlaysCache {
 }
 
 impl RelatesCache {
-    pub(super) fn create(snapshot: &impl ReadableSnapshot) -> HashMap<Relates, RelatesCache> {
+    pub(super) fn create(
+        snapshot: &impl ReadableSnapshot,
+        reuse: Option<&CacheReuse<'_, HashMap<Relates, RelatesCache>>>,
+    ) -> HashMap<Relates, RelatesCache> {
         let mut map = HashMap::new();
         let mut it = snapshot.iterate_range(
             &KeyRange::new_within(TypeEdge::build_prefix(Prefix::EdgeRelates), TypeEdge::FIXED_WIDTH_ENCODING),
@@ -305,9 +376,10 @@ impl RelatesCache {
             let relation = RelationType::new(edge.from());
             let role = RoleType::new(edge.to());
             let relates = Relates::new(relation, role);
-            let is_implicit = TypeReader::is_relates_implicit(snapshot, relates).unwrap();
-            let cache =
-                RelatesCache { is_implicit, common_capability_cache: CommonCapabilityCache::create(snapshot, relates) };
+            let cache = reuse.and_then(|reuse| reuse.get_capability(relates)).unwrap_or_else(|| RelatesCache {
+                is_implicit: TypeReader::is_relates_implicit(snapshot, relates).unwrap(),
+                common_capability_cache: CommonCapabilityCache::create(snapshot, relates),
+            });
             map.insert(relates, cache);
         }
         map
@@ -367,6 +439,7 @@ impl ObjectCache {
         let plays_with_specialised = TypeReader::get_capabilities::<Plays>(snapshot, object_type, true).unwrap();
         let played_role_type_constraints =
             TypeReader::get_type_capabilities_constraints::<Plays>(snapshot, object_type).unwrap();
+        let time_to_live = TypeReader::get_time_to_live(snapshot, object_type).unwrap();
 
         ObjectCache {
             owns_declared,
@@ -377,6 +450,7 @@ impl ObjectCache {
             plays,
             plays_with_specialised,
             played_role_type_constraints,
+            time_to_live,
         }
     }
 }
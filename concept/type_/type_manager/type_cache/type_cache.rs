@@ -9,39 +9,60 @@ use std::{
     sync::Arc,
 };
 
+use bytes::Bytes;
 use encoding::{
-    graph::definition::{definition_key::DefinitionKey, r#struct::StructDefinition},
+    graph::{
+        definition::{definition_key::DefinitionKey, r#struct::StructDefinition},
+        type_::{
+            edge::TypeEdge,
+            property::{TypeEdgeProperty, TypeVertexProperty},
+            vertex::{TypeVertex, TypeVertexEncoding},
+        },
+    },
+    layout::prefix::{Prefix, PrefixID},
     value::{label::Label, value_type::ValueType},
+    EncodingKeyspace, Prefixed,
 };
 use error::typedb_error;
-use storage::{sequence_number::SequenceNumber, MVCCStorage};
-
-use crate::type_::{
-    attribute_type::AttributeType,
-    constraint::{CapabilityConstraint, Constraint, ConstraintCategory, TypeConstraint},
-    entity_type::EntityType,
-    object_type::ObjectType,
-    owns::{Owns, OwnsAnnotation},
-    plays::{Plays, PlaysAnnotation},
-    relates::{Relates, RelatesAnnotation},
-    relation_type::RelationType,
-    role_type::RoleType,
-    type_manager::type_cache::{
-        kind_cache::{
-            AttributeTypeCache, CommonTypeCache, EntityTypeCache, ObjectCache, OwnsCache, PlaysCache, RelatesCache,
-            RelationTypeCache, RoleTypeCache,
+use storage::{keyspace::KeyspaceSet, sequence_number::SequenceNumber, snapshot::ReadableSnapshot, MVCCStorage};
+
+use crate::{
+    error::ConceptReadError,
+    type_::{
+        annotation::{Annotation, AnnotationCategory},
+        attribute_type::AttributeType,
+        constraint::{CapabilityConstraint, Constraint, ConstraintCategory, TypeConstraint},
+        entity_type::EntityType,
+        object_type::ObjectType,
+        owns::{Owns, OwnsAnnotation},
+        plays::{Plays, PlaysAnnotation},
+        relates::{Relates, RelatesAnnotation},
+        relation_type::RelationType,
+        role_type::RoleType,
+        type_manager::{
+            hierarchy_closure::{KindClosure, TypeHierarchyClosure},
+            type_cache::{
+                kind_cache::{
+                    AttributeTypeCache, CacheReuse, CommonCapabilityCache, CommonTypeCache, EntityTypeCache,
+                    ObjectCache, OwnsCache, PlaysCache, RelatesCache, RelationTypeCache, RoleTypeCache,
+                },
+                selection,
+                selection::{CacheGetter, HasCommonTypeCache, HasObjectCache},
+                struct_definition_cache::StructDefinitionCache,
+                type_reader::TypeReader,
+            },
         },
-        selection,
-        selection::{CacheGetter, HasCommonTypeCache, HasObjectCache},
-        struct_definition_cache::StructDefinitionCache,
+        Capability, DatabaseSettings, Independent, KindAPI, Ordering, OwnerAPI, PlayerAPI, StorageQuota, TimeToLive,
+        TypeAPI,
     },
-    Independent, KindAPI, Ordering, OwnerAPI, PlayerAPI,
 };
 
 // TODO: could/should we slab allocate the schema cache?
 #[derive(Debug)]
 pub struct TypeCache {
     open_sequence_number: SequenceNumber,
+    // Whether the cache carried over the caches of unaffected types from a previous cache
+    is_incremental: bool,
 
     // Types that are borrowable and returned from the cache
     entity_types: Box<[Option<EntityTypeCache>]>,
@@ -65,6 +86,9 @@ pub struct TypeCache {
     role_types_by_name: HashMap<String, Vec<RoleType>>,
     // specific caches to simplify architectures
     independent_attribute_types: Arc<HashSet<AttributeType>>,
+    hierarchy_closure: Arc<TypeHierarchyClosure>,
+    storage_quota: Option<StorageQuota>,
+    database_settings: DatabaseSettings,
 }
 
 selection::impl_cache_getter!(EntityTypeCache, EntityType, entity_types);
@@ -91,12 +115,174 @@ impl TypeCache {
         //       then go through it again to pull out the type information.
 
         let snapshot = storage.open_snapshot_read_at(open_sequence_number);
+        Ok(Self::create(&snapshot, open_sequence_number, None))
+    }
+
+    /// Creates the cache of the schema at `open_sequence_number` from the cache of a previous schema, where
+    /// `modified_types` are the types written in between (see `TypeCache::modified_types`).
+    /// Only the types whose cached state may depend on the modified types are read from storage,
+    /// while the caches of all other types and their capabilities are carried over.
+    pub fn new_incremental<D>(
+        previous: &TypeCache,
+        storage: Arc<MVCCStorage<D>>,
+        open_sequence_number: SequenceNumber,
+        modified_types: &HashSet<TypeVertex>,
+    ) -> Result<Self, TypeCacheCreateError> {
+        let snapshot = storage.open_snapshot_read_at(open_sequence_number);
+        let affected_types = Self::expand_affected_types(&snapshot, modified_types)
+            .map_err(|typedb_source| TypeCacheCreateError::ConceptRead { typedb_source })?;
+        Ok(Self::create(&snapshot, open_sequence_number, Some((previous, &affected_types))))
+    }
+
+    /// The types whose definitions are written in the snapshot: the types of written type vertices and
+    /// their properties, and both ends of written type edges and their properties.
+    pub fn modified_types(snapshot: &impl ReadableSnapshot) -> HashSet<TypeVertex> {
+        let mut modified_types = HashSet::new();
+        for (key, _) in snapshot.iterate_writes() {
+            if key.keyspace_id() != EncodingKeyspace::DefaultOptimisedPrefix11.id() {
+                continue;
+            }
+            let bytes = Bytes::reference(key.bytes());
+            match Prefix::from_prefix_id(PrefixID::new(key.bytes()[TypeVertex::INDEX_PREFIX])) {
+                Prefix::VertexEntityType
+                | Prefix::VertexRelationType
+                | Prefix::VertexAttributeType
+                | Prefix::VertexRoleType => {
+                    modified_types.insert(TypeVertex::decode(bytes));
+                }
+                Prefix::EdgeSub
+                | Prefix::EdgeSubReverse
+                | Prefix::EdgeOwns
+                | Prefix::EdgeOwnsReverse
+                | Prefix::EdgePlays
+                | Prefix::EdgePlaysReverse
+                | Prefix::EdgeRelates
+                | Prefix::EdgeRelatesReverse => {
+                    let edge = TypeEdge::decode(bytes);
+                    modified_types.extend([edge.from(), edge.to()]);
+                }
+                Prefix::PropertyTypeVertex => {
+                    modified_types.insert(TypeVertexProperty::decode(bytes).type_vertex());
+                }
+                Prefix::PropertyTypeEdge => {
+                    let edge = TypeEdgeProperty::decode(bytes).type_edge();
+                    modified_types.extend([edge.from(), edge.to()]);
+                }
+                _ => (),
+            }
+        }
+        modified_types
+    }
+
+    /// Expands the modified types to all the types with cached state that may depend on them: their supertypes and
+    /// subtypes, the types they are connected to by capabilities, and those types' supertypes and subtypes in turn.
+    fn expand_affected_types(
+        snapshot: &impl ReadableSnapshot,
+        modified_types: &HashSet<TypeVertex>,
+    ) -> Result<HashSet<TypeVertex>, Box<ConceptReadError>> {
+        let mut hierarchies = HashSet::new();
+        for &vertex in modified_types {
+            Self::extend_with_hierarchy(snapshot, vertex, &mut hierarchies)?;
+        }
+        let mut neighbours = HashSet::new();
+        for &vertex in &hierarchies {
+            Self::extend_with_capability_neighbours(snapshot, vertex, &mut neighbours)?;
+        }
+        let mut affected_types = hierarchies;
+        for vertex in neighbours {
+            Self::extend_with_hierarchy(snapshot, vertex, &mut affected_types)?;
+        }
+        Ok(affected_types)
+    }
 
-        let entity_type_caches = EntityTypeCache::create(&snapshot);
-        let relation_type_caches = RelationTypeCache::create(&snapshot);
-        let role_type_caches = RoleTypeCache::create(&snapshot);
-        let attribute_type_caches = AttributeTypeCache::create(&snapshot);
-        let struct_definition_caches = StructDefinitionCache::create(&snapshot);
+    fn extend_with_hierarchy(
+        snapshot: &impl ReadableSnapshot,
+        vertex: TypeVertex,
+        types: &mut HashSet<TypeVertex>,
+    ) -> Result<(), Box<ConceptReadError>> {
+        if !types.insert(vertex) {
+            return Ok(());
+        }
+        match vertex.prefix() {
+            Prefix::VertexEntityType => Self::extend_with_hierarchy_of(snapshot, EntityType::new(vertex), types),
+            Prefix::VertexRelationType => Self::extend_with_hierarchy_of(snapshot, RelationType::new(vertex), types),
+            Prefix::VertexAttributeType => Self::extend_with_hierarchy_of(snapshot, AttributeType::new(vertex), types),
+            Prefix::VertexRoleType => Self::extend_with_hierarchy_of(snapshot, RoleType::new(vertex), types),
+            _ => Ok(()),
+        }
+    }
+
+    fn extend_with_hierarchy_of<T: TypeAPI>(
+        snapshot: &impl ReadableSnapshot,
+        type_: T,
+        types: &mut HashSet<TypeVertex>,
+    ) -> Result<(), Box<ConceptReadError>> {
+        let supertypes = TypeReader::get_supertypes_transitive(snapshot, type_)?;
+        let subtypes = TypeReader::get_subtypes_transitive(snapshot, type_)?;
+        types.extend(supertypes.into_iter().chain(subtypes).map(|type_| type_.into_vertex()));
+        Ok(())
+    }
+
+    fn extend_with_capability_neighbours(
+        snapshot: &impl ReadableSnapshot,
+        vertex: TypeVertex,
+        types: &mut HashSet<TypeVertex>,
+    ) -> Result<(), Box<ConceptReadError>> {
+        match vertex.prefix() {
+            Prefix::VertexEntityType | Prefix::VertexRelationType => {
+                let object_type = ObjectType::new(vertex);
+                Self::extend_with_interfaces::<Owns>(snapshot, object_type, types)?;
+                Self::extend_with_interfaces::<Plays>(snapshot, object_type, types)?;
+                if vertex.prefix() == Prefix::VertexRelationType {
+                    Self::extend_with_interfaces::<Relates>(snapshot, RelationType::new(vertex), types)?;
+                }
+                Ok(())
+            }
+            Prefix::VertexAttributeType => {
+                Self::extend_with_object_types::<Owns>(snapshot, AttributeType::new(vertex), types)
+            }
+            Prefix::VertexRoleType => {
+                Self::extend_with_object_types::<Plays>(snapshot, RoleType::new(vertex), types)?;
+                Self::extend_with_object_types::<Relates>(snapshot, RoleType::new(vertex), types)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn extend_with_interfaces<CAP: Capability>(
+        snapshot: &impl ReadableSnapshot,
+        object_type: CAP::ObjectType,
+        types: &mut HashSet<TypeVertex>,
+    ) -> Result<(), Box<ConceptReadError>> {
+        let capabilities = TypeReader::get_capabilities::<CAP>(snapshot, object_type, true)?;
+        types.extend(capabilities.into_iter().map(|capability| capability.interface().into_vertex()));
+        Ok(())
+    }
+
+    fn extend_with_object_types<CAP: Capability>(
+        snapshot: &impl ReadableSnapshot,
+        interface_type: CAP::InterfaceType,
+        types: &mut HashSet<TypeVertex>,
+    ) -> Result<(), Box<ConceptReadError>> {
+        let object_types =
+            TypeReader::get_object_types_with_capabilities_for_interface::<CAP>(snapshot, interface_type)?;
+        types.extend(object_types.into_keys().map(|object_type| object_type.into_vertex()));
+        Ok(())
+    }
+
+    fn create(
+        snapshot: &impl ReadableSnapshot,
+        open_sequence_number: SequenceNumber,
+        reuse: Option<(&TypeCache, &HashSet<TypeVertex>)>,
+    ) -> Self {
+        let entity_type_caches =
+            EntityTypeCache::create(snapshot, Self::reuse(reuse, |cache| &*cache.entity_types).as_ref());
+        let relation_type_caches =
+            RelationTypeCache::create(snapshot, Self::reuse(reuse, |cache| &*cache.relation_types).as_ref());
+        let role_type_caches = RoleTypeCache::create(snapshot, Self::reuse(reuse, |cache| &*cache.role_types).as_ref());
+        let attribute_type_caches =
+            AttributeTypeCache::create(snapshot, Self::reuse(reuse, |cache| &*cache.attribute_types).as_ref());
+        let struct_definition_caches = StructDefinitionCache::create(snapshot);
 
         let entity_types_index_label = Self::build_label_to_type_index(&entity_type_caches);
         let relation_types_index_label = Self::build_label_to_type_index(&relation_type_caches);
@@ -122,6 +308,17 @@ impl TypeCache {
             })
             .collect();
 
+        let hierarchy_closure = TypeHierarchyClosure::new(
+            Self::build_kind_closure(&entity_type_caches),
+            Self::build_kind_closure(&relation_type_caches),
+            Self::build_kind_closure(&attribute_type_caches),
+            Self::build_kind_closure(&role_type_caches),
+        );
+
+        let storage_quota = TypeReader::get_database_property::<StorageQuota>(snapshot).unwrap();
+        let database_settings =
+            TypeReader::get_database_property::<DatabaseSettings>(snapshot).unwrap().unwrap_or_default();
+
         let mut role_types_by_name = HashMap::new();
         for (label, role_type) in &role_types_index_label {
             if !role_types_by_name.contains_key(label.name.as_str()) {
@@ -130,15 +327,16 @@ impl TypeCache {
             role_types_by_name.get_mut(label.name.as_str()).unwrap().push(*role_type);
         }
 
-        Ok(TypeCache {
+        TypeCache {
             open_sequence_number,
+            is_incremental: reuse.is_some(),
             entity_types: entity_type_caches,
             relation_types: relation_type_caches,
             role_types: role_type_caches,
             attribute_types: attribute_type_caches,
-            owns: OwnsCache::create(&snapshot),
-            plays: PlaysCache::create(&snapshot),
-            relates: RelatesCache::create(&snapshot),
+            owns: OwnsCache::create(snapshot, Self::reuse(reuse, |cache| &cache.owns).as_ref()),
+            plays: PlaysCache::create(snapshot, Self::reuse(reuse, |cache| &cache.plays).as_ref()),
+            relates: RelatesCache::create(snapshot, Self::reuse(reuse, |cache| &cache.relates).as_ref()),
             struct_definitions: struct_definition_caches,
 
             entity_types_index_label,
@@ -149,7 +347,17 @@ impl TypeCache {
             role_types_by_name,
 
             independent_attribute_types: Arc::new(independent_attribute_types),
-        })
+            hierarchy_closure: Arc::new(hierarchy_closure),
+            storage_quota,
+            database_settings,
+        }
+    }
+
+    fn reuse<'a, C: ?Sized>(
+        reuse: Option<(&'a TypeCache, &'a HashSet<TypeVertex>)>,
+        select: impl FnOnce(&'a TypeCache) -> &'a C,
+    ) -> Option<CacheReuse<'a, C>> {
+        reuse.map(|(previous, affected_types)| CacheReuse { previous: select(previous), affected_types })
     }
 
     fn build_label_to_type_index<T: KindAPI, Cache: HasCommonTypeCache<T>>(
@@ -162,6 +370,14 @@ impl TypeCache {
             .collect()
     }
 
+    fn build_kind_closure<T: KindAPI, Cache: HasCommonTypeCache<T>>(type_cache_array: &[Option<Cache>]) -> KindClosure {
+        KindClosure::new(
+            type_cache_array.iter().flatten().map(|cache| {
+                (cache.common_type_cache().type_, cache.common_type_cache().supertypes_transitive.as_slice())
+            }),
+        )
+    }
+
     fn build_name_to_struct_definition_index(
         struct_cache_array: &[Option<StructDefinitionCache>],
     ) -> HashMap<String, DefinitionKey> {
@@ -204,15 +420,27 @@ impl TypeCache {
     }
 
     pub(crate) fn get_entity_types(&self) -> Vec<EntityType> {
-        self.entity_types.iter().flatten().map(|cache| cache.common_type_cache().type_).collect()
+        self.iterate_entity_types().collect()
     }
 
     pub(crate) fn get_relation_types(&self) -> Vec<RelationType> {
-        self.relation_types.iter().flatten().map(|cache| cache.common_type_cache().type_).collect()
+        self.iterate_relation_types().collect()
     }
 
     pub(crate) fn get_attribute_types(&self) -> Vec<AttributeType> {
-        self.attribute_types.iter().flatten().map(|cache| cache.common_type_cache().type_).collect()
+        self.iterate_attribute_types().collect()
+    }
+
+    pub(crate) fn iterate_entity_types(&self) -> impl Iterator<Item = EntityType> + '_ {
+        self.entity_types.iter().flatten().map(|cache| cache.common_type_cache().type_)
+    }
+
+    pub(crate) fn iterate_relation_types(&self) -> impl Iterator<Item = RelationType> + '_ {
+        self.relation_types.iter().flatten().map(|cache| cache.common_type_cache().type_)
+    }
+
+    pub(crate) fn iterate_attribute_types(&self) -> impl Iterator<Item = AttributeType> + '_ {
+        self.attribute_types.iter().flatten().map(|cache| cache.common_type_cache().type_)
     }
 
     pub(crate) fn get_role_types(&self) -> Vec<RoleType> {
@@ -450,6 +678,14 @@ impl TypeCache {
         &T::get_cache(self, type_).object_cache().played_role_type_constraints
     }
 
+    pub(crate) fn get_time_to_live<'a, T, CACHE>(&'a self, type_: T) -> Option<TimeToLive>
+    where
+        T: OwnerAPI + PlayerAPI + CacheGetter<CacheType = CACHE>,
+        CACHE: HasObjectCache + 'a,
+    {
+        T::get_cache(self, type_).object_cache().time_to_live
+    }
+
     pub(crate) fn get_plays_annotations_declared(&self, plays: Plays) -> &HashSet<PlaysAnnotation> {
         &self.plays.get(&plays).unwrap().common_capability_cache.annotations_declared
     }
@@ -481,6 +717,42 @@ impl TypeCache {
         self.owns.get(&owns).unwrap().ordering
     }
 
+    pub(crate) fn get_owns_with_annotation_category(&self, category: AnnotationCategory) -> HashSet<Owns> {
+        Self::capabilities_with_annotation_category(
+            self.owns.values().map(|cache| &cache.common_capability_cache),
+            category,
+        )
+    }
+
+    pub(crate) fn get_plays_with_annotation_category(&self, category: AnnotationCategory) -> HashSet<Plays> {
+        Self::capabilities_with_annotation_category(
+            self.plays.values().map(|cache| &cache.common_capability_cache),
+            category,
+        )
+    }
+
+    pub(crate) fn get_relates_with_annotation_category(&self, category: AnnotationCategory) -> HashSet<Relates> {
+        Self::capabilities_with_annotation_category(
+            self.relates.values().map(|cache| &cache.common_capability_cache),
+            category,
+        )
+    }
+
+    fn capabilities_with_annotation_category<'a, CAP: Capability>(
+        caches: impl Iterator<Item = &'a CommonCapabilityCache<CAP>>,
+        category: AnnotationCategory,
+    ) -> HashSet<CAP> {
+        caches
+            .filter(|cache| {
+                cache
+                    .annotations_declared
+                    .iter()
+                    .any(|annotation| Into::<Annotation>::into(annotation.clone()).category() == category)
+            })
+            .map(|cache| cache.capability)
+            .collect()
+    }
+
     pub(crate) fn get_struct_definitions(&self) -> HashMap<DefinitionKey, StructDefinition> {
         self.struct_definitions
             .iter()
@@ -500,10 +772,31 @@ impl TypeCache {
     pub(crate) fn get_independent_attribute_types(&self) -> Arc<HashSet<AttributeType>> {
         self.independent_attribute_types.clone()
     }
+
+    pub fn open_sequence_number(&self) -> SequenceNumber {
+        self.open_sequence_number
+    }
+
+    pub fn is_incremental(&self) -> bool {
+        self.is_incremental
+    }
+
+    pub(crate) fn get_hierarchy_closure(&self) -> Arc<TypeHierarchyClosure> {
+        self.hierarchy_closure.clone()
+    }
+
+    pub(crate) fn get_storage_quota(&self) -> Option<StorageQuota> {
+        self.storage_quota
+    }
+
+    pub fn get_database_settings(&self) -> DatabaseSettings {
+        self.database_settings
+    }
 }
 
 typedb_error! {
     pub TypeCacheCreateError(component = "TypeCache create", prefix = "TCC") {
         Empty(1, ""),
+        ConceptRead(2, "Error reading the schema to update the type cache.", typedb_source: Box<ConceptReadError>),
     }
 }
@@ -14,7 +14,10 @@ use encoding::{
         type_::{
             edge::{TypeEdge, TypeEdgeEncoding},
             index::{IdentifierIndex, LabelToTypeVertexIndex, NameToStructDefinitionIndex},
-            property::{TypeEdgeProperty, TypeEdgePropertyEncoding, TypeVertexProperty, TypeVertexPropertyEncoding},
+            property::{
+                DatabasePropertyEncoding, TypeEdgeProperty, TypeEdgePropertyEncoding, TypeVertexProperty,
+                TypeVertexPropertyEncoding,
+            },
             vertex::{PrefixedTypeVertexEncoding, TypeVertex, TypeVertexEncoding},
             CapabilityKind,
         },
@@ -51,7 +54,7 @@ use crate::{
         relation_type::RelationType,
         role_type::RoleType,
         sub::Sub,
-        Capability, Independent, KindAPI, Ordering, TypeAPI,
+        Capability, Independent, KindAPI, Ordering, TimeToLive, TypeAPI,
     },
 };
 
@@ -322,6 +325,18 @@ impl TypeReader {
             .map_err(|error| Box::new(ConceptReadError::SnapshotIterate { source: error }))
     }
 
+    pub(crate) fn get_all_capabilities<CAP: Capability>(
+        snapshot: &impl ReadableSnapshot,
+    ) -> Result<HashSet<CAP>, Box<ConceptReadError>> {
+        snapshot
+            .iterate_range(
+                &KeyRange::new_within(TypeEdge::build_prefix(CAP::CANONICAL_PREFIX), TypeEdge::FIXED_WIDTH_ENCODING),
+                StorageCounters::DISABLED,
+            )
+            .collect_cloned_hashset(|key, _| CAP::decode_canonical_edge(Bytes::Reference(key.bytes()).into_owned()))
+            .map_err(|error| Box::new(ConceptReadError::SnapshotIterate { source: error }))
+    }
+
     pub(crate) fn get_capabilities<CAP: Capability>(
         snapshot: &impl ReadableSnapshot,
         object_type: CAP::ObjectType,
@@ -467,6 +482,21 @@ impl TypeReader {
         Self::get_type_property_declared::<Independent>(snapshot, type_)
     }
 
+    /// Returns the time to live declared on the type or, failing that, on its nearest supertype declaring one.
+    pub(crate) fn get_time_to_live<T: TypeAPI>(
+        snapshot: &impl ReadableSnapshot,
+        type_: T,
+    ) -> Result<Option<TimeToLive>, Box<ConceptReadError>> {
+        let mut type_opt = Some(type_);
+        while let Some(curr_type) = type_opt {
+            if let Some(time_to_live) = Self::get_type_property_declared::<TimeToLive>(snapshot, curr_type)? {
+                return Ok(Some(time_to_live));
+            }
+            type_opt = Self::get_supertype(snapshot, curr_type)?;
+        }
+        Ok(None)
+    }
+
     pub(crate) fn get_type_property_declared<PROPERTY>(
         snapshot: &impl ReadableSnapshot,
         type_: impl TypeVertexEncoding,
@@ -484,6 +514,21 @@ impl TypeReader {
         Ok(property)
     }
 
+    pub(crate) fn get_database_property<PROPERTY>(
+        snapshot: &impl ReadableSnapshot,
+    ) -> Result<Option<PROPERTY>, Box<ConceptReadError>>
+    where
+        PROPERTY: DatabasePropertyEncoding,
+    {
+        snapshot
+            .get_mapped(
+                PROPERTY::build_key().into_storage_key().as_reference(),
+                |value| PROPERTY::from_value_bytes(value),
+                StorageCounters::DISABLED,
+            )
+            .map_err(|err| Box::new(ConceptReadError::SnapshotGet { source: err }))
+    }
+
     pub(crate) fn get_type_property<PROPERTY, SOURCE>(
         snapshot: &impl ReadableSnapshot,
         type_: SOURCE,
@@ -581,8 +626,15 @@ impl TypeReader {
                     | Infix::PropertyValueType
                     | Infix::PropertyOrdering
                     | Infix::PropertyRelationTypeIndependent
+                    | Infix::PropertyTimeToLive
                     | Infix::PropertyHasOrder
-                    | Infix::PropertyLinksOrder => {
+                    | Infix::PropertyLinksOrder
+                    | Infix::PropertyCreationTime
+                    | Infix::PropertyCommitTriggers
+                    | Infix::PropertyHistoryRetention
+                    | Infix::PropertyStorageQuota
+                    | Infix::PropertyInternalSchemaVersion
+                    | Infix::PropertyDatabaseSettings => {
                         unreachable!("Retrieved unexpected infixes while reading annotations.")
                     }
                 };
@@ -673,8 +725,15 @@ impl TypeReader {
                     | Infix::PropertyValueType
                     | Infix::PropertyOrdering
                     | Infix::PropertyRelationTypeIndependent
+                    | Infix::PropertyTimeToLive
                     | Infix::PropertyHasOrder
-                    | Infix::PropertyLinksOrder => {
+                    | Infix::PropertyLinksOrder
+                    | Infix::PropertyCreationTime
+                    | Infix::PropertyCommitTriggers
+                    | Infix::PropertyHistoryRetention
+                    | Infix::PropertyStorageQuota
+                    | Infix::PropertyInternalSchemaVersion
+                    | Infix::PropertyDatabaseSettings => {
                         unreachable!("Retrieved unexpected infixes while reading annotations.")
                     }
                 };
@@ -13,7 +13,7 @@ use encoding::{
         type_::{
             edge::TypeEdgeEncoding,
             index::{LabelToTypeVertexIndex, NameToStructDefinitionIndex},
-            property::{TypeEdgePropertyEncoding, TypeVertexPropertyEncoding},
+            property::{DatabasePropertyEncoding, TypeEdgePropertyEncoding, TypeVertexPropertyEncoding},
             vertex::TypeVertexEncoding,
         },
     },
@@ -189,6 +189,21 @@ impl<Snapshot: WritableSnapshot> TypeWriter<Snapshot> {
         }
     }
 
+    pub(crate) fn storage_put_database_property<P>(snapshot: &mut Snapshot, property: P)
+    where
+        P: DatabasePropertyEncoding,
+    {
+        let key = P::build_key().into_storage_key();
+        snapshot.put_val(key.into_owned_array(), property.to_value_bytes().into_array())
+    }
+
+    pub(crate) fn storage_delete_database_property<P>(snapshot: &mut Snapshot)
+    where
+        P: DatabasePropertyEncoding,
+    {
+        snapshot.delete(P::build_key().into_storage_key().into_owned_array());
+    }
+
     pub(crate) fn storage_delete_type_vertex_property<P>(snapshot: &mut Snapshot, vertex: impl TypeVertexEncoding)
     where
         P: TypeVertexPropertyEncoding,
@@ -124,5 +124,6 @@ typedb_error!(
         ),
         CannotUnsetAbstractnessOfRelationTypeWithoutRoleTypes(60, "Cannot unset abstractness of relation type '{relation}' because it does not have any role types related.", relation: Label),
         CannotUnsetRelationSupertypeBecauseAllRoleTypesAreLost(61, "Cannot unset supertype of relation type '{relation}' because the relation type will not have any role types related.", relation: Label),
+        RoleRenameCannotChangeScope(62, "Role type '{role}' cannot be renamed to '{new_label}': role types must stay scoped by their relation type '{relation}'.", role: Label, new_label: Label, relation: Label),
     }
 );
@@ -0,0 +1,535 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    fmt,
+    fs::{self, File},
+    hash::{Hash, Hasher},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use error::{typedb_error, TypeDBError};
+use resource::constants::database::CLUSTER_STATE_FILE_NAME;
+use storage::{sequence_number::SequenceNumber, CommitGate};
+use tracing::{event, Level};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ClusterRole {
+    Follower,
+    Candidate,
+    Leader,
+}
+
+impl fmt::Display for ClusterRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClusterRole::Follower => write!(f, "follower"),
+            ClusterRole::Candidate => write!(f, "candidate"),
+            ClusterRole::Leader => write!(f, "leader"),
+        }
+    }
+}
+
+/// A member of the cluster, whose role is unknown unless this member has heard from it in the current term.
+#[derive(Debug, Clone)]
+pub struct ClusterMember {
+    pub address: String,
+    pub role: Option<ClusterRole>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ClusterReply {
+    pub term: u64,
+    pub accepted: bool,
+}
+
+/// Raft consensus on which member of a cluster sequences commits. Members are identified by their addresses.
+///
+/// The leader is the only member which commits; followers are replicas of the leader's databases,
+/// and report the commits they durably received when reading the leader's WAL. A commit is only applied on the
+/// leader once a quorum of members received it, and is otherwise aborted, so a newly elected leader, which must be
+/// at least as up to date as a quorum of members, has every applied commit.
+///
+/// The current term and vote are persisted, so that a restarted member never votes twice in one term.
+/// Whether the member has led is persisted too: a former leader's databases may hold commits it aborted which the
+/// next leader applied, so they are replicated again from scratch once it follows another leader.
+#[derive(Debug)]
+pub struct Cluster {
+    address: String,
+    peers: Vec<String>,
+    election_timeout: Duration,
+    state_file: PathBuf,
+    state: Mutex<ClusterState>,
+    progress_changed: Condvar,
+}
+
+#[derive(Debug)]
+struct ClusterState {
+    term: u64,
+    voted_for: Option<String>,
+    role: ClusterRole,
+    leader: Option<String>,
+    election_deadline: Instant,
+    votes: HashSet<String>,
+    has_led: bool,
+    // peers which accepted this leader's heartbeats in the current term
+    followers: HashSet<String>,
+    // database name -> follower address -> latest commit durably received by the follower
+    follower_progress: HashMap<String, HashMap<String, u64>>,
+}
+
+impl Cluster {
+    const HAS_LED: &'static str = "led";
+
+    pub fn open(
+        data_directory: &Path,
+        address: String,
+        peers: Vec<String>,
+        election_timeout: Duration,
+    ) -> Result<Arc<Self>, ClusterError> {
+        let state_file = data_directory.join(CLUSTER_STATE_FILE_NAME);
+        let (term, voted_for, has_led) = Self::read_persisted(&state_file)?;
+        let peers = peers.into_iter().filter(|peer| peer != &address).collect();
+        let cluster = Self {
+            address,
+            peers,
+            election_timeout,
+            state_file,
+            state: Mutex::new(ClusterState {
+                term,
+                voted_for,
+                role: ClusterRole::Follower,
+                leader: None,
+                election_deadline: Instant::now(),
+                votes: HashSet::new(),
+                has_led,
+                followers: HashSet::new(),
+                follower_progress: HashMap::new(),
+            }),
+            progress_changed: Condvar::new(),
+        };
+        cluster.reset_election_deadline(&mut cluster.state.lock().unwrap());
+        Ok(Arc::new(cluster))
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn peers(&self) -> &[String] {
+        &self.peers
+    }
+
+    fn quorum(&self) -> usize {
+        (self.peers.len() + 1) / 2 + 1
+    }
+
+    pub fn term(&self) -> u64 {
+        self.state.lock().unwrap().term
+    }
+
+    pub fn role(&self) -> ClusterRole {
+        self.state.lock().unwrap().role
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.role() == ClusterRole::Leader
+    }
+
+    pub fn leader(&self) -> Option<String> {
+        self.state.lock().unwrap().leader.clone()
+    }
+
+    /// Only the leader commits. Other members reject writes with the address of the leader to redirect to.
+    pub fn ensure_leader(&self) -> Result<(), ClusterError> {
+        let state = self.state.lock().unwrap();
+        match state.role {
+            ClusterRole::Leader => Ok(()),
+            _ => Err(ClusterError::NotLeader {
+                address: self.address.clone(),
+                leader: state.leader.clone().unwrap_or_else(|| "unknown".to_owned()),
+            }),
+        }
+    }
+
+    pub fn members(&self) -> Vec<ClusterMember> {
+        let state = self.state.lock().unwrap();
+        let this = ClusterMember { address: self.address.clone(), role: Some(state.role) };
+        let peers = self.peers.iter().map(|peer| ClusterMember {
+            address: peer.clone(),
+            role: if state.leader.as_ref() == Some(peer) {
+                Some(ClusterRole::Leader)
+            } else if state.followers.contains(peer) {
+                Some(ClusterRole::Follower)
+            } else {
+                None
+            },
+        });
+        [this].into_iter().chain(peers).collect()
+    }
+
+    /// Whether this member has led since its databases were last replicated from another leader.
+    pub fn has_led(&self) -> bool {
+        self.state.lock().unwrap().has_led
+    }
+
+    /// Records that the databases of this former leader were replicated again from the current leader.
+    pub fn clear_has_led(&self) -> Result<(), ClusterError> {
+        let mut state = self.state.lock().unwrap();
+        state.has_led = false;
+        self.persist(&state)
+    }
+
+    /// Starts an election once no leader was heard from before the election deadline,
+    /// returning the term in which to request votes from the peers.
+    pub fn may_start_election(&self) -> Result<Option<u64>, ClusterError> {
+        let mut state = self.state.lock().unwrap();
+        if state.role == ClusterRole::Leader || Instant::now() < state.election_deadline {
+            return Ok(None);
+        }
+        state.term += 1;
+        state.voted_for = Some(self.address.clone());
+        state.role = ClusterRole::Candidate;
+        state.leader = None;
+        state.votes = HashSet::from([self.address.clone()]);
+        self.reset_election_deadline(&mut state);
+        self.persist(&state)?;
+        let term = state.term;
+        event!(Level::INFO, "Cluster member '{}' started an election in term {}", self.address, term);
+        self.may_become_leader(&mut state);
+        Ok(Some(term))
+    }
+
+    /// Votes for a candidate at most once per term, and only if the candidate has received every commit this member has.
+    pub fn handle_vote_request(
+        &self,
+        term: u64,
+        candidate: &str,
+        is_candidate_up_to_date: bool,
+    ) -> Result<ClusterReply, ClusterError> {
+        let mut state = self.state.lock().unwrap();
+        self.observe_term_locked(&mut state, term)?;
+        let can_vote = state.voted_for.as_deref().map_or(true, |voted_for| voted_for == candidate);
+        let granted = term == state.term && can_vote && is_candidate_up_to_date;
+        if granted {
+            state.voted_for = Some(candidate.to_owned());
+            self.reset_election_deadline(&mut state);
+            self.persist(&state)?;
+        }
+        Ok(ClusterReply { term: state.term, accepted: granted })
+    }
+
+    /// Counts a vote received in `term`, returning whether this member became the leader.
+    pub fn handle_vote_reply(&self, term: u64, voter: &str, reply: ClusterReply) -> Result<bool, ClusterError> {
+        let mut state = self.state.lock().unwrap();
+        self.observe_term_locked(&mut state, reply.term)?;
+        if state.role != ClusterRole::Candidate || state.term != term || !reply.accepted {
+            return Ok(false);
+        }
+        state.votes.insert(voter.to_owned());
+        Ok(self.may_become_leader(&mut state))
+    }
+
+    /// Accepts the heartbeat of the leader of the current or a later term.
+    pub fn handle_heartbeat(&self, term: u64, leader: &str) -> Result<ClusterReply, ClusterError> {
+        let mut state = self.state.lock().unwrap();
+        self.observe_term_locked(&mut state, term)?;
+        if term < state.term {
+            return Ok(ClusterReply { term: state.term, accepted: false });
+        }
+        if state.leader.as_deref() != Some(leader) {
+            event!(Level::INFO, "Cluster member '{}' follows leader '{}' in term {}", self.address, leader, term);
+        }
+        state.role = ClusterRole::Follower;
+        state.leader = Some(leader.to_owned());
+        self.reset_election_deadline(&mut state);
+        Ok(ClusterReply { term: state.term, accepted: true })
+    }
+
+    /// Steps down when the peer replies from a later term, and otherwise records whether it follows this leader.
+    pub fn handle_heartbeat_reply(&self, term: u64, peer: &str, reply: ClusterReply) -> Result<(), ClusterError> {
+        let mut state = self.state.lock().unwrap();
+        self.observe_term_locked(&mut state, reply.term)?;
+        if state.role == ClusterRole::Leader && state.term == term && reply.accepted {
+            state.followers.insert(peer.to_owned());
+        }
+        Ok(())
+    }
+
+    pub fn record_follower_progress(&self, follower: &str, database_name: &str, received: u64) {
+        let mut state = self.state.lock().unwrap();
+        let progress = state.follower_progress.entry(database_name.to_owned()).or_default();
+        let follower_received = progress.entry(follower.to_owned()).or_default();
+        *follower_received = received.max(*follower_received);
+        self.progress_changed.notify_all();
+    }
+
+    /// Waits until a quorum of members, including this leader, durably received the commit in the database.
+    /// A leader which cannot reach a quorum in time steps down, so that it makes no further commits
+    /// after the ones it aborted, which a new leader may have received and apply.
+    pub fn await_commit_quorum(
+        &self,
+        database_name: &str,
+        sequence_number: u64,
+        timeout: Duration,
+    ) -> Result<(), ClusterError> {
+        let quorum = self.quorum();
+        let is_replicated = |state: &mut ClusterState| {
+            let followers = state
+                .follower_progress
+                .get(database_name)
+                .map_or(0, |progress| progress.values().filter(|received| **received >= sequence_number).count());
+            followers + 1 >= quorum
+        };
+        let state = self.state.lock().unwrap();
+        let (mut state, _) = self
+            .progress_changed
+            .wait_timeout_while(state, timeout, |state| state.role == ClusterRole::Leader && !is_replicated(state))
+            .unwrap();
+        if state.role != ClusterRole::Leader {
+            Err(ClusterError::LeadershipLost { sequence_number })
+        } else if !is_replicated(&mut state) {
+            event!(Level::WARN, "Cluster member '{}' steps down, as it cannot reach a quorum", self.address);
+            state.role = ClusterRole::Follower;
+            state.leader = None;
+            self.progress_changed.notify_all();
+            Err(ClusterError::CommitQuorumTimeout { sequence_number, quorum })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Members wait between one and two election timeouts before starting an election, so that they rarely
+    /// start competing elections. The wait is derived from the member's address and term, and varies across terms.
+    fn reset_election_deadline(&self, state: &mut ClusterState) {
+        let mut hasher = DefaultHasher::new();
+        (&self.address, state.term).hash(&mut hasher);
+        let jitter = self.election_timeout.mul_f64((hasher.finish() % 1000) as f64 / 1000.0);
+        state.election_deadline = Instant::now() + self.election_timeout + jitter;
+    }
+
+    fn may_become_leader(&self, state: &mut MutexGuard<'_, ClusterState>) -> bool {
+        if state.votes.len() < self.quorum() {
+            return false;
+        }
+        state.role = ClusterRole::Leader;
+        state.leader = Some(self.address.clone());
+        state.followers.clear();
+        state.follower_progress.clear();
+        if !state.has_led {
+            state.has_led = true;
+            // a leader which cannot persist this must not commit, and stays a candidate
+            if let Err(err) = self.persist(state) {
+                event!(Level::ERROR, "Cluster member '{}' could not become leader: {:?}", self.address, err);
+                state.role = ClusterRole::Candidate;
+                state.leader = None;
+                state.has_led = false;
+                return false;
+            }
+        }
+        event!(Level::INFO, "Cluster member '{}' was elected leader in term {}", self.address, state.term);
+        true
+    }
+
+    fn observe_term_locked(&self, state: &mut MutexGuard<'_, ClusterState>, term: u64) -> Result<(), ClusterError> {
+        if term > state.term {
+            state.term = term;
+            state.voted_for = None;
+            state.role = ClusterRole::Follower;
+            state.leader = None;
+            self.persist(state)?;
+            self.progress_changed.notify_all();
+        }
+        Ok(())
+    }
+
+    fn persist(&self, state: &ClusterState) -> Result<(), ClusterError> {
+        let contents = format!(
+            "{}\n{}\n{}\n",
+            state.term,
+            state.voted_for.as_deref().unwrap_or(""),
+            if state.has_led { Self::HAS_LED } else { "" }
+        );
+        let temporary_file = self.state_file.with_extension("tmp");
+        let write_durably = || -> io::Result<()> {
+            let mut file = File::create(&temporary_file)?;
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()?;
+            fs::rename(&temporary_file, &self.state_file)?;
+            // the rename itself is only durable once the directory holding the state file is synced
+            match self.state_file.parent() {
+                Some(directory) => File::open(directory)?.sync_all(),
+                None => Ok(()),
+            }
+        };
+        write_durably().map_err(|source| ClusterError::StatePersist { source: Arc::new(source) })
+    }
+
+    fn read_persisted(state_file: &Path) -> Result<(u64, Option<String>, bool), ClusterError> {
+        let contents = match fs::read_to_string(state_file) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok((0, None, false)),
+            Err(source) => return Err(ClusterError::StateRead { source: Arc::new(source) }),
+        };
+        let mut lines = contents.lines();
+        let term = lines.next().and_then(|term| term.parse().ok()).ok_or(ClusterError::StateCorrupted {})?;
+        let voted_for = lines.next().filter(|voted_for| !voted_for.is_empty()).map(str::to_owned);
+        let has_led = lines.next() == Some(Self::HAS_LED);
+        Ok((term, voted_for, has_led))
+    }
+}
+
+/// Applies a commit to a database on the cluster leader only once a quorum of members received it,
+/// re-checking at commit time that this member still leads.
+#[derive(Debug)]
+pub struct ClusterCommitGate {
+    cluster: Arc<Cluster>,
+    database_name: String,
+    timeout: Duration,
+}
+
+impl ClusterCommitGate {
+    pub fn new(cluster: Arc<Cluster>, database_name: String, timeout: Duration) -> Self {
+        Self { cluster, database_name, timeout }
+    }
+}
+
+impl CommitGate for ClusterCommitGate {
+    fn await_commit(&self, sequence_number: SequenceNumber) -> Result<(), String> {
+        self.cluster
+            .ensure_leader()
+            .and_then(|()| {
+                self.cluster.await_commit_quorum(&self.database_name, sequence_number.number(), self.timeout)
+            })
+            .map_err(|err| err.format_description())
+    }
+}
+
+typedb_error! {
+    pub ClusterError(component = "Cluster", prefix = "CLS") {
+        NotConfigured(1, "This server is not a member of a cluster."),
+        NotLeader(2, "Cluster member '{address}' is not the leader. Send writes to the leader '{leader}'.", address: String, leader: String),
+        CommitQuorumTimeout(3, "Commit {sequence_number} was not received by a quorum of {quorum} cluster members in time, so this leader aborted it and stepped down. A new leader which received it may still apply it.", sequence_number: u64, quorum: usize),
+        LeadershipLost(4, "Leadership was lost before commit {sequence_number} was received by a quorum of cluster members, so it was aborted here. A new leader which received it may still apply it.", sequence_number: u64),
+        StatePersist(5, "Error persisting the cluster term and vote.", source: Arc<io::Error>),
+        StateRead(6, "Error reading the persisted cluster term and vote.", source: Arc<io::Error>),
+        StateCorrupted(7, "The persisted cluster term and vote are corrupted."),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{path::Path, sync::Arc, time::Duration};
+
+    use test_utils::create_tmp_dir;
+
+    use super::{Cluster, ClusterError, ClusterReply, ClusterRole};
+
+    const ADDRESS: &str = "member-0";
+
+    fn open_cluster(data_directory: &Path, peer_count: usize) -> Arc<Cluster> {
+        let members = (0..=peer_count).map(|i| format!("member-{i}")).collect();
+        // a zero election timeout lets a member start an election straight away
+        Cluster::open(data_directory, ADDRESS.to_owned(), members, Duration::ZERO).unwrap()
+    }
+
+    fn elect(cluster: &Cluster) -> u64 {
+        let term = cluster.may_start_election().unwrap().unwrap();
+        let quorum = cluster.quorum();
+        for peer in cluster.peers().iter().take(quorum - 1) {
+            cluster.handle_vote_reply(term, peer, ClusterReply { term, accepted: true }).unwrap();
+        }
+        assert_eq!(cluster.role(), ClusterRole::Leader);
+        term
+    }
+
+    #[test]
+    fn quorum_is_a_majority_of_members() {
+        let directory = create_tmp_dir();
+        let quorums = [(0, 1), (1, 2), (2, 2), (3, 3), (4, 3)];
+        for (peer_count, quorum) in quorums {
+            assert_eq!(open_cluster(&directory, peer_count).quorum(), quorum, "with {peer_count} peers");
+        }
+    }
+
+    #[test]
+    fn candidate_becomes_leader_with_quorum_of_votes() {
+        let directory = create_tmp_dir();
+        let cluster = open_cluster(&directory, 4);
+
+        let term = cluster.may_start_election().unwrap().unwrap();
+        assert_eq!(cluster.role(), ClusterRole::Candidate);
+        assert!(!cluster.handle_vote_reply(term, "member-1", ClusterReply { term, accepted: true }).unwrap());
+        assert!(!cluster.handle_vote_reply(term, "member-2", ClusterReply { term, accepted: false }).unwrap());
+        // a vote from an earlier term does not count
+        assert!(!cluster.handle_vote_reply(term - 1, "member-3", ClusterReply { term, accepted: true }).unwrap());
+        assert!(cluster.handle_vote_reply(term, "member-3", ClusterReply { term, accepted: true }).unwrap());
+        assert_eq!(cluster.role(), ClusterRole::Leader);
+        assert_eq!(cluster.leader().as_deref(), Some(ADDRESS));
+        assert!(cluster.has_led());
+    }
+
+    #[test]
+    fn single_member_elects_itself() {
+        let directory = create_tmp_dir();
+        let cluster = open_cluster(&directory, 0);
+        cluster.may_start_election().unwrap();
+        assert!(cluster.is_leader());
+        assert_eq!(cluster.may_start_election().unwrap(), None);
+    }
+
+    #[test]
+    fn vote_is_granted_once_per_term_across_restarts() {
+        let directory = create_tmp_dir();
+        let cluster = open_cluster(&directory, 2);
+        assert!(cluster.handle_vote_request(1, "member-1", true).unwrap().accepted);
+        assert!(cluster.handle_vote_request(1, "member-1", true).unwrap().accepted);
+        assert!(!cluster.handle_vote_request(1, "member-2", true).unwrap().accepted);
+        drop(cluster);
+
+        let restarted = open_cluster(&directory, 2);
+        assert_eq!(restarted.term(), 1);
+        assert!(!restarted.handle_vote_request(1, "member-2", true).unwrap().accepted);
+        assert!(!restarted.handle_vote_request(2, "member-2", false).unwrap().accepted);
+        assert!(restarted.handle_vote_request(2, "member-2", true).unwrap().accepted);
+        assert_eq!(restarted.term(), 2);
+    }
+
+    #[test]
+    fn leader_steps_down_on_later_term() {
+        let directory = create_tmp_dir();
+        let cluster = open_cluster(&directory, 2);
+        let term = elect(&cluster);
+        cluster.handle_heartbeat_reply(term, "member-1", ClusterReply { term: term + 1, accepted: false }).unwrap();
+        assert_eq!(cluster.role(), ClusterRole::Follower);
+        assert_eq!(cluster.term(), term + 1);
+        assert!(matches!(cluster.ensure_leader(), Err(ClusterError::NotLeader { .. })));
+    }
+
+    #[test]
+    fn commit_waits_for_quorum_and_leader_steps_down_on_timeout() {
+        let directory = create_tmp_dir();
+        let cluster = open_cluster(&directory, 2);
+        elect(&cluster);
+
+        cluster.record_follower_progress("member-1", "db", 10);
+        cluster.await_commit_quorum("db", 10, Duration::from_millis(10)).unwrap();
+        assert!(cluster.is_leader());
+
+        let result = cluster.await_commit_quorum("db", 11, Duration::from_millis(10));
+        assert!(matches!(result, Err(ClusterError::CommitQuorumTimeout { sequence_number: 11, quorum: 2 })));
+        assert_eq!(cluster.role(), ClusterRole::Follower);
+        assert_eq!(cluster.leader(), None);
+        assert!(matches!(
+            cluster.await_commit_quorum("db", 10, Duration::from_millis(10)),
+            Err(ClusterError::LeadershipLost { .. })
+        ));
+    }
+}
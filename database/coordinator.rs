@@ -0,0 +1,241 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{
+    collections::HashSet,
+    fs,
+    fs::File,
+    io,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use durability::{encryption::KeyManagementService, wal::WAL, DurabilityServiceError};
+use error::typedb_error;
+use resource::profile::TransactionProfile;
+use storage::{
+    durability_client::{DurabilityClient, DurabilityClientError, WALClient},
+    recovery::commit_recovery::reject_unresolved_commits_from,
+    sequence_number::SequenceNumber,
+};
+use tracing::{event, Level};
+
+use crate::{
+    transaction::{DataCommitError, PreparedTransactionWrite, TransactionWrite},
+    Database,
+};
+
+/// Commits write transactions against distinct databases as a single logical transaction, using two-phase commit:
+/// every transaction is finalised, durably written and validated before any is applied.
+/// If any transaction fails to prepare, the ones already prepared are aborted and no database is modified.
+///
+/// Databases recover independently, so the participating databases are recorded in the commit log before any
+/// commit is prepared: after a crash between the two phases, recovery applies all of the commits or none.
+pub fn commit_atomically<D: DurabilityClient + Send + Sync>(
+    commit_log: &CommitLog,
+    transactions: Vec<TransactionWrite<D>>,
+) -> Result<(), MultiDatabaseCommitError> {
+    let mut names = HashSet::new();
+    if let Some(transaction) = transactions.iter().find(|transaction| !names.insert(transaction.database.name())) {
+        return Err(MultiDatabaseCommitError::DuplicateDatabase { name: transaction.database.name().to_owned() });
+    }
+
+    let databases = transactions.iter().map(|transaction| &**transaction.database.database()).collect::<Vec<_>>();
+    let mut record = match commit_log.begin(&databases) {
+        Ok(record) => record,
+        Err(typedb_source) => {
+            transactions.into_iter().for_each(TransactionWrite::close);
+            return Err(MultiDatabaseCommitError::CommitLog { typedb_source });
+        }
+    };
+    let mut prepared = Vec::with_capacity(transactions.len());
+    let mut transactions = transactions.into_iter();
+    while let Some(transaction) = transactions.next() {
+        let name = transaction.database.name().to_owned();
+        let (profile, result) = transaction.try_prepare_commit();
+        match result {
+            Ok(mut prepared_transaction) => {
+                prepared_transaction.await_durable();
+                prepared.push((profile, prepared_transaction));
+            }
+            Err(typedb_source) => {
+                transactions.for_each(TransactionWrite::close);
+                let error = MultiDatabaseCommitError::PrepareFailed { name, typedb_source };
+                return Err(abort_all(record, prepared).err().unwrap_or(error));
+            }
+        }
+    }
+    if let Err(typedb_source) = record.committed() {
+        return Err(abort_all(record, prepared).err().unwrap_or(MultiDatabaseCommitError::CommitLog { typedb_source }));
+    }
+
+    // Once every database has accepted its commit, each must be applied: later failures cannot be rolled back
+    let mut first_error = None;
+    for (mut profile, prepared_transaction) in prepared {
+        let name = prepared_transaction.database.name().to_owned();
+        if let Err(typedb_source) = prepared_transaction.apply(profile.commit_profile()) {
+            first_error.get_or_insert(MultiDatabaseCommitError::ApplyFailed { name, typedb_source });
+        }
+    }
+    record.finish();
+    first_error.map_or(Ok(()), Err)
+}
+
+fn abort_all<D: DurabilityClient>(
+    record: CommitLogRecord,
+    prepared: Vec<(TransactionProfile, PreparedTransactionWrite<D>)>,
+) -> Result<(), MultiDatabaseCommitError> {
+    let mut first_error = None;
+    for (_, prepared_transaction) in prepared {
+        let name = prepared_transaction.database.name().to_owned();
+        if let Err(typedb_source) = prepared_transaction.abort() {
+            first_error.get_or_insert(MultiDatabaseCommitError::AbortFailed { name, typedb_source });
+        }
+    }
+    // a commit that failed to abort is left to recovery, which rejects it since the record has no commit marker
+    if first_error.is_none() {
+        record.finish();
+    }
+    first_error.map_or(Ok(()), Err)
+}
+
+/// Records the multi-database commits in progress, one file per commit, so that a crash between the two phases
+/// is resolved on restart. Each record lists every participating database with the WAL position it had before
+/// any commit was prepared, followed by a marker once every database prepared its commit. On restart, the databases
+/// apply the commits of a marked record as they recover. For an unmarked record, every unresolved commit the listed
+/// databases wrote from their recorded position onwards is rejected before the databases are opened.
+#[derive(Debug)]
+pub struct CommitLog {
+    directory: PathBuf,
+    next_id: AtomicU64,
+}
+
+impl CommitLog {
+    const COMMITTED_MARKER: &'static str = "committed";
+    const DATABASE_PREFIX: &'static str = "database ";
+
+    /// Opens the commit log, resolving the records left by a crash in the databases of the data directory,
+    /// none of which may be open yet. Encrypted databases are read through the key management.
+    pub fn recover(
+        directory: PathBuf,
+        data_directory: &Path,
+        key_management: Option<&dyn KeyManagementService>,
+    ) -> Result<Self, CommitLogError> {
+        let io_error = |source: io::Error| CommitLogError::IO { path: directory.clone(), source: Arc::new(source) };
+        if directory.exists() {
+            for entry in fs::read_dir(&directory).map_err(io_error)? {
+                let path = entry.map_err(io_error)?.path();
+                Self::resolve(&path, data_directory, key_management)?;
+                fs::remove_file(&path).map_err(io_error)?;
+            }
+        } else {
+            fs::create_dir_all(&directory).map_err(io_error)?;
+        }
+        Ok(Self { directory, next_id: AtomicU64::new(0) })
+    }
+
+    fn resolve(
+        path: &Path,
+        data_directory: &Path,
+        key_management: Option<&dyn KeyManagementService>,
+    ) -> Result<(), CommitLogError> {
+        let contents = fs::read_to_string(path)
+            .map_err(|source| CommitLogError::IO { path: path.to_owned(), source: Arc::new(source) })?;
+        // a line is only complete once its newline is written
+        let lines = contents.split_inclusive('\n').filter_map(|line| line.strip_suffix('\n'));
+        if lines.clone().any(|line| line == Self::COMMITTED_MARKER) {
+            return Ok(());
+        }
+        let participants = lines
+            .filter_map(|line| line.strip_prefix(Self::DATABASE_PREFIX))
+            .filter_map(|line| line.split_once(' '))
+            .filter_map(|(start, name)| Some((start.parse::<u64>().ok()?, name)));
+        for (start, name) in participants {
+            let database_directory = data_directory.join(name);
+            if !database_directory.exists() {
+                continue;
+            }
+            let wal = WAL::load_with_key_management(&database_directory, key_management)
+                .map_err(|source| CommitLogError::WALOpen { name: name.to_owned(), source })?;
+            let rejected = reject_unresolved_commits_from(SequenceNumber::new(start), &WALClient::new(wal))
+                .map_err(|typedb_source| CommitLogError::CommitReject { name: name.to_owned(), typedb_source })?;
+            for sequence_number in rejected {
+                event!(
+                    Level::WARN,
+                    "Rejected commit {} in database '{}', since not every database of its transaction prepared it",
+                    sequence_number,
+                    name
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Starts the record of a commit across the databases, durably listing them before any of them prepares.
+    pub fn begin<D: DurabilityClient>(&self, databases: &[&Database<D>]) -> Result<CommitLogRecord, CommitLogError> {
+        let path = self.directory.join(self.next_id.fetch_add(1, Ordering::Relaxed).to_string());
+        let io_error = |source: io::Error| CommitLogError::IO { path: path.clone(), source: Arc::new(source) };
+        let file = File::create(&path).map_err(io_error)?;
+        let mut record = CommitLogRecord { path: path.clone(), file };
+        for database in databases {
+            let start = database.storage.durability().current();
+            writeln!(record.file, "{}{} {}", Self::DATABASE_PREFIX, start.number(), database.name())
+                .map_err(io_error)?;
+        }
+        record.file.sync_data().map_err(io_error)?;
+        // the record itself is only durable once the commit log directory is synced
+        File::open(&self.directory).and_then(|directory| directory.sync_all()).map_err(io_error)?;
+        Ok(record)
+    }
+}
+
+/// The record of one multi-database commit, removed once the commit is resolved in every database.
+#[derive(Debug)]
+pub struct CommitLogRecord {
+    path: PathBuf,
+    file: File,
+}
+
+impl CommitLogRecord {
+    pub fn committed(&mut self) -> Result<(), CommitLogError> {
+        self.write_line(CommitLog::COMMITTED_MARKER)
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<(), CommitLogError> {
+        writeln!(self.file, "{line}")
+            .and_then(|()| self.file.sync_data())
+            .map_err(|source| CommitLogError::IO { path: self.path.clone(), source: Arc::new(source) })
+    }
+
+    fn finish(self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            // the record is resolved again on restart, which finds every commit it lists already resolved
+            event!(Level::WARN, "Failed to remove multi-database commit record {:?}: {}", self.path, err);
+        }
+    }
+}
+
+typedb_error! {
+    pub MultiDatabaseCommitError(component = "Multi-database commit", prefix = "MDC") {
+        DuplicateDatabase(1, "Database '{name}' is written by more than one of the transactions.", name: String),
+        PrepareFailed(2, "Commit to database '{name}' failed, so no database was modified.", name: String, typedb_source: DataCommitError),
+        AbortFailed(3, "Failed to abort the prepared commit to database '{name}'.", name: String, typedb_source: DataCommitError),
+        ApplyFailed(4, "Commit to database '{name}' failed after every database accepted it.", name: String, typedb_source: DataCommitError),
+        CommitLog(5, "Failed to record the commit in the multi-database commit log, so no database was modified.", typedb_source: CommitLogError),
+    }
+}
+
+typedb_error! {
+    pub CommitLogError(component = "Multi-database commit log", prefix = "MCL") {
+        IO(1, "Error accessing the multi-database commit log at '{path:?}'.", path: PathBuf, source: Arc<io::Error>),
+        WALOpen(2, "Error opening the WAL of database '{name}'.", name: String, source: DurabilityServiceError),
+        CommitReject(3, "Error rejecting the unresolved commits in database '{name}'.", name: String, typedb_source: DurabilityClientError),
+    }
+}
@@ -10,22 +10,32 @@ use std::{
     fmt, fs, io,
     path::{Path, PathBuf},
     sync::{
+        atomic::{AtomicU64, Ordering},
         mpsc::{sync_channel, SyncSender},
-        Arc, Mutex, MutexGuard, RwLock, TryLockError,
+        Arc, Mutex, MutexGuard, RwLock, TryLockError, Weak,
     },
     time::{Duration, Instant},
 };
 
+use compiler::annotation::limits::CompileLimits;
 use concept::{
-    thing::statistics::{Statistics, StatisticsError},
-    type_::type_manager::{
-        type_cache::{TypeCache, TypeCacheCreateError},
-        TypeManager,
+    error::ConceptReadError,
+    thing::{
+        statistics::{Statistics, StatisticsError},
+        statistics_sampler::{StatisticsSample, StatisticsSampler},
+        thing_manager::ThingManager,
+    },
+    type_::{
+        type_manager::{
+            type_cache::{TypeCache, TypeCacheCreateError},
+            TypeManager,
+        },
+        DatabaseSettings, StorageQuota,
     },
 };
 use concurrency::IntervalRunner;
 use diagnostics::metrics::{DataLoadMetrics, DatabaseMetrics, SchemaLoadMetrics};
-use durability::{wal::WAL, DurabilityServiceError};
+use durability::{encryption::KeyManagementService, wal::WAL, DurabilityServiceError};
 use encoding::{
     error::EncodingError,
     graph::{
@@ -36,18 +46,32 @@ use encoding::{
 };
 use error::typedb_error;
 use function::{function_cache::FunctionCache, FunctionError};
+use options::TransactionOptions;
 use query::query_cache::QueryCache;
-use resource::constants::database::{CHECKPOINT_INTERVAL, STATISTICS_UPDATE_INTERVAL};
+use resource::{
+    constants::{
+        database::{
+            CHECKPOINT_INTERVAL, STATISTICS_SAMPLE_INTERVAL, STATISTICS_SAMPLE_TYPES, STATISTICS_UPDATE_INTERVAL,
+            STORAGE_QUOTA_THROTTLE_DELAY, STORAGE_QUOTA_THROTTLE_FRACTION,
+        },
+        memory::TYPE_CACHE_BYTES_PER_TYPE_ESTIMATE,
+    },
+    memory::{MemoryComponent, MemoryConsumer, MemoryPressure, MEMORY_MANAGER},
+};
 use storage::{
     durability_client::{DurabilityClient, DurabilityClientError, WALClient},
+    keyspace::KeyspaceVerification,
     recovery::checkpoint::{Checkpoint, CheckpointCreateError, CheckpointLoadError},
     sequence_number::SequenceNumber,
-    MVCCStorage, StorageDeleteError, StorageOpenError, StorageResetError,
+    MVCCStorage, StorageDeleteError, StorageOpenError, StorageResetError, StorageVerifyError,
 };
 use tracing::{event, Level};
 
 use crate::{
-    transaction::TransactionError,
+    cluster::ClusterError,
+    coordinator::CommitLogError,
+    replication::ReplicaState,
+    transaction::{BlockingTransactionType, TransactionError},
     DatabaseOpenError::FunctionCacheInitialise,
     DatabaseResetError::{
         CorruptionPartialResetKeyGeneratorInUse, CorruptionPartialResetThingVertexGeneratorInUse,
@@ -62,6 +86,29 @@ pub(super) struct Schema {
     pub(super) function_cache: Arc<FunctionCache>,
 }
 
+/// Accounts the type cache of whichever schema is current against the memory budget. The type cache always holds the
+/// whole schema, so it cannot shrink under pressure, but leaves less of the limit to the other components.
+#[derive(Debug)]
+struct TypeCacheMemory {
+    schema: Weak<RwLock<Schema>>,
+}
+
+impl MemoryConsumer for TypeCacheMemory {
+    fn component(&self) -> MemoryComponent {
+        MemoryComponent::TypeCache
+    }
+
+    fn memory_usage(&self) -> u64 {
+        let Some(schema) = self.schema.upgrade() else { return 0 };
+        let types_count = schema.read().unwrap().type_cache.get_types_count();
+        types_count * TYPE_CACHE_BYTES_PER_TYPE_ESTIMATE
+    }
+
+    // every transaction reads the whole type cache, so it cannot shrink; the memory manager takes its excess
+    // out of the budgets of the other components instead
+    fn relieve_pressure(&self, _pressure: MemoryPressure, _target_bytes: u64) {}
+}
+
 type SchemaWriteTransactionState = (bool, usize, VecDeque<TransactionReservationRequest>);
 
 pub struct Database<D> {
@@ -74,8 +121,14 @@ pub struct Database<D> {
 
     pub(super) schema: Arc<RwLock<Schema>>,
     pub(super) query_cache: Arc<QueryCache>,
+    type_cache_memory: Arc<TypeCacheMemory>,
+    pub(super) replica: Mutex<Option<ReplicaState>>,
+    compile_limits: RwLock<CompileLimits>,
+    storage_usage: AtomicU64,
     schema_write_transaction_exclusivity: Mutex<SchemaWriteTransactionState>,
+    statistics_sampler: Arc<Mutex<StatisticsSampler>>,
     _statistics_updater: IntervalRunner,
+    _statistics_sampler: IntervalRunner,
     _checkpointer: IntervalRunner,
 }
 
@@ -97,16 +150,100 @@ impl<D> Database<D> {
         &self.name
     }
 
+    /// The settings stored with the database's schema, as of its latest schema commit.
+    /// Open transactions keep the settings they were opened with.
+    pub fn settings(&self) -> DatabaseSettings {
+        self.schema.read().unwrap().type_cache.get_database_settings()
+    }
+
+    /// The options of transactions on the database that set none of their own
+    pub fn transaction_defaults(&self) -> TransactionOptions {
+        let settings = self.settings();
+        let defaults = TransactionOptions::default();
+        TransactionOptions {
+            schema_lock_acquire_timeout_millis: settings
+                .schema_lock_acquire_timeout_millis
+                .unwrap_or(defaults.schema_lock_acquire_timeout_millis),
+            transaction_timeout_millis: settings
+                .transaction_timeout_millis
+                .unwrap_or(defaults.transaction_timeout_millis),
+            ..defaults
+        }
+    }
+
+    /// Replicas only change by applying their primary's WAL, so they reject write and schema transactions.
+    pub fn is_replica(&self) -> bool {
+        self.replica.lock().unwrap().is_some()
+    }
+
+    /// The bounds on the queries transactions accept, which apply to transactions opened from then on.
+    pub fn compile_limits(&self) -> CompileLimits {
+        *self.compile_limits.read().unwrap()
+    }
+
+    pub fn set_compile_limits(&self, compile_limits: CompileLimits) {
+        *self.compile_limits.write().unwrap() = compile_limits;
+    }
+
+    /// Rejects write transactions while the database uses more storage than its quota,
+    /// and delays them once usage passes the throttling fraction of the quota.
+    /// Usage is the size last recorded by `refresh_storage_usage`, so opening a transaction never measures storage.
+    pub(super) fn admit_write_transaction(&self) -> Result<(), TransactionError> {
+        let quota =
+            self.get_storage_quota().map_err(|typedb_source| TransactionError::ConceptRead { typedb_source })?;
+        let Some(StorageQuota(quota)) = quota else {
+            return Ok(());
+        };
+        let usage = self.storage_usage.load(Ordering::Relaxed);
+        if usage > quota {
+            return Err(TransactionError::StorageQuotaExceeded { usage, quota });
+        }
+        if usage as f64 > quota as f64 * STORAGE_QUOTA_THROTTLE_FRACTION {
+            std::thread::sleep(STORAGE_QUOTA_THROTTLE_DELAY);
+        }
+        Ok(())
+    }
+
+    pub fn get_storage_quota(&self) -> Result<Option<StorageQuota>, Box<ConceptReadError>> {
+        let schema = self.schema.read().unwrap();
+        let type_manager = TypeManager::new(
+            self.definition_key_generator.clone(),
+            self.type_vertex_generator.clone(),
+            Some(schema.type_cache.clone()),
+        );
+        type_manager.get_storage_quota(&self.storage.clone().open_snapshot_read())
+    }
+
+    pub fn get_storage_size_in_bytes(&self) -> u64 {
+        self.storage.estimate_size_in_bytes().expect("Expected storage size in bytes")
+    }
+
+    /// Measures the storage size and records it as the usage checked against the storage quota.
+    pub fn refresh_storage_usage(&self) -> u64 {
+        let usage = self.get_storage_size_in_bytes();
+        self.storage_usage.store(usage, Ordering::Relaxed);
+        usage
+    }
+
+    /// Checks the checksums of all stored data.
+    pub fn verify_storage(&self) -> Result<Vec<KeyspaceVerification>, StorageVerifyError> {
+        self.storage.verify()
+    }
+
     pub(super) fn reserve_write_transaction(&self, timeout_millis: u64) -> Result<(), TransactionError> {
         let (mut guard, timeout_left) =
             self.try_acquire_schema_write_transaction_lock(Duration::from_millis(timeout_millis))?;
         let (has_schema_transaction, running_write_transactions, ref mut notify_queue) = *guard;
 
         if has_schema_transaction || !notify_queue.is_empty() {
+            let blocking =
+                Self::blocking_transaction_type(has_schema_transaction, running_write_transactions, notify_queue);
             let (sender, receiver) = sync_channel::<()>(0);
             notify_queue.push_back(TransactionReservationRequest::Write(sender));
             drop(guard);
-            receiver.recv_timeout(timeout_left).map_err(|source| TransactionError::Timeout { source })?;
+            receiver
+                .recv_timeout(timeout_left)
+                .map_err(|_| TransactionError::ExclusiveAccessTimeout { timeout_millis, blocking })?;
         } else {
             guard.1 = running_write_transactions + 1;
             drop(guard);
@@ -120,10 +257,14 @@ impl<D> Database<D> {
         let (has_schema_transaction, running_write_transactions, ref mut notify_queue) = *guard;
 
         if has_schema_transaction || running_write_transactions > 0 || !notify_queue.is_empty() {
+            let blocking =
+                Self::blocking_transaction_type(has_schema_transaction, running_write_transactions, notify_queue);
             let (sender, receiver) = sync_channel::<()>(0);
             notify_queue.push_back(TransactionReservationRequest::Schema(sender));
             drop(guard);
-            receiver.recv_timeout(timeout_left).map_err(|source| TransactionError::Timeout { source })?;
+            receiver
+                .recv_timeout(timeout_left)
+                .map_err(|_| TransactionError::ExclusiveAccessTimeout { timeout_millis, blocking })?;
         } else {
             guard.0 = true;
             drop(guard);
@@ -178,6 +319,25 @@ impl<D> Database<D> {
         Ok((guard, remaining_timeout))
     }
 
+    /// The transaction a reservation has to wait for: the open schema or write transactions,
+    /// or otherwise the first transaction queued ahead of it.
+    fn blocking_transaction_type(
+        has_schema_transaction: bool,
+        running_write_transactions: usize,
+        notify_queue: &VecDeque<TransactionReservationRequest>,
+    ) -> BlockingTransactionType {
+        if has_schema_transaction {
+            BlockingTransactionType::Schema
+        } else if running_write_transactions > 0 {
+            BlockingTransactionType::Write
+        } else {
+            match notify_queue.front() {
+                Some(TransactionReservationRequest::Schema(_)) => BlockingTransactionType::QueuedSchema,
+                Some(TransactionReservationRequest::Write(_)) | None => BlockingTransactionType::QueuedWrite,
+            }
+        }
+    }
+
     fn fulfill_reservation_requests(
         guard: &mut MutexGuard<'_, (bool, usize, VecDeque<TransactionReservationRequest>)>,
     ) {
@@ -224,19 +384,32 @@ impl<D> Database<D> {
 
 impl Database<WALClient> {
     pub fn open(path: &Path) -> Result<Database<WALClient>, DatabaseOpenError> {
+        Self::open_with_key_management(path, None)
+    }
+
+    /// Opens a database whose WAL may be encrypted. A new database is created encrypted if a key management
+    /// service is given; an existing one stays as it was created, and needs the key management service if encrypted.
+    pub fn open_with_key_management(
+        path: &Path,
+        key_management: Option<&dyn KeyManagementService>,
+    ) -> Result<Database<WALClient>, DatabaseOpenError> {
         use DatabaseOpenError::InvalidUnicodeName;
 
         let file_name = path.file_name().unwrap();
         let name = file_name.to_str().ok_or_else(|| InvalidUnicodeName { name: file_name.to_owned() })?;
 
         if path.exists() {
-            Self::load(path, name)
+            Self::load(path, name, key_management)
         } else {
-            Self::create(path, name)
+            Self::create(path, name, key_management)
         }
     }
 
-    fn create(path: &Path, name: impl AsRef<str>) -> Result<Database<WALClient>, DatabaseOpenError> {
+    fn create(
+        path: &Path,
+        name: impl AsRef<str>,
+        key_management: Option<&dyn KeyManagementService>,
+    ) -> Result<Database<WALClient>, DatabaseOpenError> {
         use DatabaseOpenError::{
             DirectoryCreate, Encoding, FunctionCacheInitialise, StorageOpen, TypeCacheInitialise, WALOpen,
         };
@@ -245,7 +418,7 @@ impl Database<WALClient> {
 
         fs::create_dir(path).map_err(|source| DirectoryCreate { name: name.to_string(), source: Arc::new(source) })?;
 
-        let wal = WAL::create(path).map_err(|error| WALOpen { source: error })?;
+        let wal = WAL::create_with_key_management(path, key_management).map_err(|error| WALOpen { source: error })?;
         let mut wal_client = WALClient::new(wal);
         wal_client.register_record_type::<Statistics>();
 
@@ -277,9 +450,20 @@ impl Database<WALClient> {
         let schema_txn_lock = Arc::new(RwLock::default());
 
         let query_cache = Arc::new(QueryCache::new());
+        let type_cache_memory = register_memory_consumers(&schema, &query_cache);
         let update_statistics =
             make_update_statistics_fn(storage.clone(), schema.clone(), schema_txn_lock.clone(), query_cache.clone());
+        let statistics_sampler = Arc::new(Mutex::new(StatisticsSampler::new()));
+        let sample_statistics = make_sample_statistics_fn(
+            name.to_owned(),
+            storage.clone(),
+            schema.clone(),
+            schema_txn_lock.clone(),
+            query_cache.clone(),
+            statistics_sampler.clone(),
+        );
         let checkpoint_fn = make_checkpoint_fn(path.to_owned(), SequenceNumber::MIN, storage.clone());
+        let storage_usage = storage.estimate_size_in_bytes().expect("Expected storage size in bytes");
 
         Ok(Database::<WALClient> {
             name: name.to_owned(),
@@ -290,13 +474,27 @@ impl Database<WALClient> {
             thing_vertex_generator,
             schema,
             query_cache,
+            type_cache_memory,
+            replica: Mutex::new(None),
+            compile_limits: RwLock::new(CompileLimits::default()),
+            storage_usage: AtomicU64::new(storage_usage),
             schema_write_transaction_exclusivity: Mutex::new((false, 0, VecDeque::with_capacity(100))),
+            statistics_sampler,
             _statistics_updater: IntervalRunner::new(update_statistics, STATISTICS_UPDATE_INTERVAL),
+            _statistics_sampler: IntervalRunner::new_with_initial_delay(
+                sample_statistics,
+                STATISTICS_SAMPLE_INTERVAL,
+                STATISTICS_SAMPLE_INTERVAL,
+            ),
             _checkpointer: IntervalRunner::new(checkpoint_fn, CHECKPOINT_INTERVAL),
         })
     }
 
-    fn load(path: &Path, name: impl AsRef<str>) -> Result<Database<WALClient>, DatabaseOpenError> {
+    fn load(
+        path: &Path,
+        name: impl AsRef<str>,
+        key_management: Option<&dyn KeyManagementService>,
+    ) -> Result<Database<WALClient>, DatabaseOpenError> {
         use DatabaseOpenError::{
             CheckpointCreate, CheckpointLoad, DurabilityClientRead, Encoding, StatisticsInitialise, StorageOpen,
             TypeCacheInitialise, WALOpen,
@@ -311,7 +509,7 @@ impl Database<WALClient> {
         );
 
         event!(Level::TRACE, "Loading database '{}' WAL.", &name);
-        let wal = WAL::load(path).map_err(|err| WALOpen { source: err })?;
+        let wal = WAL::load_with_key_management(path, key_management).map_err(|err| WALOpen { source: err })?;
         let wal_last_sequence_number = wal.previous();
 
         let mut wal_client = WALClient::new(wal);
@@ -370,9 +568,20 @@ impl Database<WALClient> {
         };
 
         let query_cache = Arc::new(QueryCache::new());
+        let type_cache_memory = register_memory_consumers(&schema, &query_cache);
         let update_statistics =
             make_update_statistics_fn(storage.clone(), schema.clone(), schema_txn_lock.clone(), query_cache.clone());
+        let statistics_sampler = Arc::new(Mutex::new(StatisticsSampler::new()));
+        let sample_statistics = make_sample_statistics_fn(
+            name.to_owned(),
+            storage.clone(),
+            schema.clone(),
+            schema_txn_lock.clone(),
+            query_cache.clone(),
+            statistics_sampler.clone(),
+        );
         let checkpoint_fn = make_checkpoint_fn(path.to_owned(), checkpoint_sequence_number, storage.clone());
+        let storage_usage = storage.estimate_size_in_bytes().expect("Expected storage size in bytes");
 
         let database = Database::<WALClient> {
             name: name.to_owned(),
@@ -383,8 +592,18 @@ impl Database<WALClient> {
             thing_vertex_generator,
             schema,
             query_cache,
+            type_cache_memory,
+            replica: Mutex::new(None),
+            compile_limits: RwLock::new(CompileLimits::default()),
+            storage_usage: AtomicU64::new(storage_usage),
             schema_write_transaction_exclusivity: Mutex::new((false, 0, VecDeque::with_capacity(100))),
+            statistics_sampler,
             _statistics_updater: IntervalRunner::new(update_statistics, STATISTICS_UPDATE_INTERVAL),
+            _statistics_sampler: IntervalRunner::new_with_initial_delay(
+                sample_statistics,
+                STATISTICS_SAMPLE_INTERVAL,
+                STATISTICS_SAMPLE_INTERVAL,
+            ),
             _checkpointer: IntervalRunner::new_with_initial_delay(
                 checkpoint_fn,
                 CHECKPOINT_INTERVAL,
@@ -406,10 +625,88 @@ impl Database<WALClient> {
         Ok(())
     }
 
+    /// Writes a copy of the database into an empty directory, from a new checkpoint and the WAL written since.
+    /// Only the WAL files holding the commits after the checkpoint and the last durable statistics are copied.
+    /// A record being written while the WAL is copied may be cut off, and is discarded when the copy is loaded,
+    /// so the copy is a consistent state of the database, as if it had stopped at that point.
+    pub(super) fn clone_into(&self, path: &Path) -> Result<(), DatabaseCloneError> {
+        use DatabaseCloneError::{CheckpointCreate, StatisticsRead, WALCopy};
+
+        // the copy recovers from the checkpoint, which is at least at the current watermark,
+        // and synchronises its statistics from the last durable statistics record
+        let statistics_start = self
+            .storage
+            .durability()
+            .find_last_unsequenced_type::<Statistics>()
+            .map_err(|typedb_source| StatisticsRead { name: self.name.clone(), typedb_source })?
+            .map_or(SequenceNumber::MIN, |statistics| statistics.synchronisation_start());
+        let wal_start = statistics_start.min(self.storage.snapshot_watermark().next());
+
+        let checkpoint = Checkpoint::new(path)
+            .and_then(|checkpoint| {
+                self.storage.checkpoint(&checkpoint)?;
+                checkpoint.finish()?;
+                Ok(checkpoint)
+            })
+            .map_err(|source| CheckpointCreate { name: self.name.clone(), source })?;
+        event!(Level::TRACE, "Cloning database '{}' from checkpoint '{:?}'", &self.name, checkpoint.directory);
+
+        let wal_copy = path.join(WAL::WAL_DIR_NAME);
+        fs::create_dir(&wal_copy)
+            .and_then(|()| {
+                let data_key = WAL::data_key_path(&self.path);
+                for file_path in WAL::file_paths_from(&self.path, wal_start)?.into_iter().chain(data_key) {
+                    fs::copy(&file_path, wal_copy.join(file_path.file_name().unwrap()))?;
+                }
+                Ok(())
+            })
+            .map_err(|source| WALCopy { name: self.name.clone(), source: Arc::new(source) })
+    }
+
+    /// Whether the WAL still starts at the first commit, so that the storage can be rebuilt from the WAL alone.
+    /// A cloned database's WAL only starts at the checkpoint it was cloned from.
+    pub fn wal_holds_every_commit(&self) -> io::Result<bool> {
+        let first = WAL::first_sequence_number(&self.path)?;
+        Ok(first.is_some_and(|first| first <= SequenceNumber::MIN.next()))
+    }
+
+    /// Closes the database and reopens it with its storage rebuilt by replaying the entire WAL,
+    /// ignoring the checkpoints and storage files that may hold corrupt blocks.
+    /// The caller must first check that the WAL holds every commit, see `wal_holds_every_commit`.
+    /// The checkpoints are only removed once the rebuilt storage has opened. If it fails to open, they are restored,
+    /// so that the database can be reopened as it was.
+    pub fn rebuild_storage(
+        self,
+        key_management: Option<&dyn KeyManagementService>,
+    ) -> Result<Database<WALClient>, DatabaseOpenError> {
+        use DatabaseOpenError::CheckpointSetAside;
+
+        let (path, name) = (self.path.clone(), self.name.clone());
+        drop(self);
+        Checkpoint::set_aside_all(&path).map_err(|source| CheckpointSetAside { name: name.clone(), source })?;
+        event!(Level::WARN, "Rebuilding the storage of database '{}' from its WAL.", name);
+        match Self::load(&path, &name, key_management) {
+            Ok(database) => {
+                if let Err(err) = Checkpoint::remove_set_aside(&path) {
+                    event!(Level::WARN, "Could not remove the old checkpoints of database '{}': {:?}", name, err);
+                }
+                Ok(database)
+            }
+            Err(err) => {
+                Checkpoint::restore_set_aside(&path)
+                    .map_err(|source| CheckpointSetAside { name: name.clone(), source })?;
+                Err(err)
+            }
+        }
+    }
+
     #[allow(clippy::drop_non_drop)]
     pub fn delete(self) -> Result<(), DatabaseDeleteError> {
         drop(self._statistics_updater);
+        drop(self._statistics_sampler);
         drop(self._checkpointer);
+        MEMORY_MANAGER.deregister(self.type_cache_memory.as_ref());
+        MEMORY_MANAGER.deregister(self.query_cache.as_ref());
         drop(Arc::into_inner(self.schema).expect("Cannot get exclusive ownership of inner of Arc<Schema>."));
         drop(Arc::into_inner(self.query_cache).expect("Cannot get exclusive ownership of inner of Arc<QueryCache>."));
         drop(
@@ -468,6 +765,14 @@ impl Database<WALClient> {
         Ok(())
     }
 
+    pub fn statistics(&self) -> Arc<Statistics> {
+        self.schema.read().unwrap().thing_statistics.clone()
+    }
+
+    pub fn last_statistics_sample(&self) -> Option<StatisticsSample> {
+        self.statistics_sampler.lock().unwrap().last_sample().cloned()
+    }
+
     pub fn get_metrics(&self) -> DatabaseMetrics {
         let schema = self.schema.read().expect("Expected database schema lock acquisition");
         DatabaseMetrics {
@@ -479,8 +784,9 @@ impl Database<WALClient> {
                 attribute_count: schema.thing_statistics.total_attribute_count,
                 has_count: schema.thing_statistics.total_has_count,
                 role_count: schema.thing_statistics.total_role_count,
-                storage_in_bytes: self.storage.estimate_size_in_bytes().expect("Expected storage size in bytes"),
+                storage_in_bytes: self.refresh_storage_usage(),
                 storage_key_count: self.storage.estimate_key_count().expect("Expected storage key count"),
+                replication_lag: self.replication_lag(),
             },
         }
     }
@@ -492,8 +798,10 @@ fn make_checkpoint_fn(
     storage: Arc<MVCCStorage<WALClient>>,
 ) -> impl FnMut() {
     move || {
+        // checkpoint straight after a lazy format upgrade, so recovery does not have to redo it from the WAL
+        let upgraded = storage.complete_format_upgrades().unwrap();
         let watermark = storage.snapshot_watermark();
-        if prev_checkpoint < watermark {
+        if upgraded || prev_checkpoint < watermark {
             let checkpoint = Checkpoint::new(&path).unwrap();
             storage.checkpoint(&checkpoint).unwrap();
             checkpoint.finish().unwrap();
@@ -509,6 +817,9 @@ fn make_update_statistics_fn(
     query_cache: Arc<QueryCache>,
 ) -> impl Fn() {
     move || {
+        if !schema.read().unwrap().type_cache.get_database_settings().statistics_updates {
+            return;
+        }
         if storage.snapshot_watermark() > (*schema).read().unwrap().thing_statistics.sequence_number {
             let _schema_txn_guard = schema_txn_lock.read().unwrap(); // prevent Schema txns from opening during statistics update
             let mut new_statistics = (*schema.read().unwrap().thing_statistics).clone();
@@ -519,6 +830,66 @@ fn make_update_statistics_fn(
     }
 }
 
+/// Recounts a few types at the sequence number of the current statistics, and corrects any drift in the counts
+/// maintained from commit deltas. The corrections are applied to whichever statistics are current by then.
+fn make_sample_statistics_fn(
+    name: String,
+    storage: Arc<MVCCStorage<WALClient>>,
+    schema: Arc<RwLock<Schema>>,
+    schema_txn_lock: Arc<RwLock<()>>,
+    query_cache: Arc<QueryCache>,
+    sampler: Arc<Mutex<StatisticsSampler>>,
+) -> impl Fn() {
+    move || {
+        if !schema.read().unwrap().type_cache.get_database_settings().statistics_updates {
+            return;
+        }
+        let mut statistics = (*schema.read().unwrap().thing_statistics).clone();
+        let snapshot = storage.clone().open_snapshot_read_at(statistics.sequence_number);
+        // the generators are never used to read, and sharing the database's ones would block a reset
+        let type_manager = Arc::new(TypeManager::new(
+            Arc::new(DefinitionKeyGenerator::new()),
+            Arc::new(TypeVertexGenerator::new()),
+            None,
+        ));
+        let thing_manager = ThingManager::new(
+            Arc::new(ThingVertexGenerator::new()),
+            type_manager,
+            Arc::new(Statistics::new(statistics.sequence_number)),
+        );
+        let mut sampler = sampler.lock().unwrap();
+        let sample = match sampler.sample(&mut statistics, &snapshot, &thing_manager, STATISTICS_SAMPLE_TYPES) {
+            Ok(sample) => sample,
+            Err(err) => {
+                event!(Level::WARN, "Failed to sample the statistics of database '{}': {:?}", name, err);
+                return;
+            }
+        };
+        if !sample.corrected() {
+            return;
+        }
+        event!(Level::DEBUG, "Correcting drifted statistics of database '{}': {:?}", name, sample);
+        let _schema_txn_guard = schema_txn_lock.write().unwrap(); // exclude the statistics updater while correcting
+        let mut corrected_statistics = (*schema.read().unwrap().thing_statistics).clone();
+        sample.apply_corrections(&mut corrected_statistics);
+        if let Err(err) = corrected_statistics.durably_write(storage.durability()) {
+            event!(Level::WARN, "Failed to write the corrected statistics of database '{}': {:?}", name, err);
+            return;
+        }
+        query_cache.may_evict(&corrected_statistics);
+        schema.write().unwrap().thing_statistics = Arc::new(corrected_statistics);
+    }
+}
+
+fn register_memory_consumers(schema: &Arc<RwLock<Schema>>, query_cache: &Arc<QueryCache>) -> Arc<TypeCacheMemory> {
+    let type_cache_memory = Arc::new(TypeCacheMemory { schema: Arc::downgrade(schema) });
+    let weak_type_cache_memory: Weak<dyn MemoryConsumer> = Arc::downgrade(&type_cache_memory) as _;
+    let weak_query_cache: Weak<dyn MemoryConsumer> = Arc::downgrade(query_cache) as _;
+    MEMORY_MANAGER.register(weak_type_cache_memory);
+    MEMORY_MANAGER.register(weak_query_cache);
+    type_cache_memory
+}
+
 typedb_error! {
     pub DatabaseOpenError(component = "Database open", prefix = "DBO") {
         InvalidUnicodeName(1, "Could not open database: invalid unicode name '{name:?}'.", name: OsString),
@@ -536,6 +907,21 @@ typedb_error! {
         FunctionCacheInitialise(13, "Error initialising function cache.", typedb_source: FunctionError),
         FileDelete(14, "Error while deleting file for '{name}'", name: String, source: Arc<io::Error>),
         DirectoryDelete(15, "Error while deleting directory of '{name}'", name: String, source: Arc<io::Error>),
+        CommitLogRecover(16, "Error resolving the multi-database commits interrupted by a restart.", typedb_source: CommitLogError),
+        CheckpointSetAside(17, "Error setting aside or restoring the checkpoints of database '{name}'.", name: String, source: CheckpointCreateError),
+    }
+}
+
+typedb_error! {
+    pub DatabaseSalvageError(component = "Database salvage", prefix = "DBS") {
+        DoesNotExist(1, "Cannot salvage database '{name}' since it does not exist.", name: String),
+        InUse(2, "Cannot salvage database '{name}' since it is in use.", name: String),
+        IsReplica(3, "Cannot salvage database '{name}' since it is a replica, which can be replicated again instead.", name: String),
+        StorageVerify(4, "Error verifying the storage of database '{name}'.", name: String, typedb_source: StorageVerifyError),
+        Rebuild(5, "Error rebuilding the storage of database '{name}' from its WAL. The database was reopened from its checkpoints.", name: String, typedb_source: DatabaseOpenError),
+        IncompleteWAL(6, "Cannot salvage database '{name}' since its WAL does not start at the first commit, as is the case for a cloned database. Restore it from a backup instead.", name: String),
+        WALRead(7, "Error reading the WAL of database '{name}'.", name: String, source: Arc<io::Error>),
+        Reopen(8, "Error rebuilding the storage of database '{name}' from its WAL, and reopening it from its checkpoints. The database is closed.", name: String, typedb_source: DatabaseOpenError),
     }
 }
 
@@ -552,6 +938,19 @@ typedb_error! {
         IsNotBeingImported(9, "Internal error: database '{name}' is not being imported.", name: String),
         DirectoryWrite(10, "Error while writing to data directory for '{name}'.", name: String, source: Arc<io::Error>),
         DatabaseMove(11, "Error while moving database {name} while finalization.", name: String),
+        IsReplica(12, "Cannot create database '{name}' on a read-only replica.", name: String),
+        NotClusterLeader(13, "Cannot create databases on a cluster member which is not the leader.", typedb_source: ClusterError),
+    }
+}
+
+typedb_error! {
+    pub DatabaseCloneError(component = "Database clone", prefix = "DCL") {
+        SourceNotFound(1, "Cannot clone database '{name}' since it does not exist.", name: String),
+        SourceIsReplica(2, "Cannot clone database '{name}' since it is a read-only replica.", name: String),
+        Target(3, "Cannot create the cloned database.", typedb_source: DatabaseCreateError),
+        CheckpointCreate(4, "Error creating a checkpoint of database '{name}'.", name: String, source: CheckpointCreateError),
+        WALCopy(5, "Error copying the WAL of database '{name}'.", name: String, source: Arc<io::Error>),
+        StatisticsRead(6, "Error reading the statistics of database '{name}' from its WAL.", name: String, typedb_source: DurabilityClientError),
     }
 }
 
@@ -595,5 +994,6 @@ typedb_error! {
             10,
             "Corruption warning: Database reset failed partway because the query cache is still in use."
         ),
+        IsReplica(11, "Cannot reset database '{name}' since it is a read-only replica.", name: String),
     }
 }
@@ -5,18 +5,31 @@
  */
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs,
     path::{Path, PathBuf},
-    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    },
 };
 
 use cache::CACHE_DB_NAME_PREFIX;
-use resource::{constants::database::INTERNAL_DATABASE_PREFIX, internal_database_prefix};
-use storage::durability_client::WALClient;
+use compiler::annotation::limits::CompileLimits;
+use durability::encryption::KeyManagementService;
+use resource::{
+    constants::database::{CLUSTER_COMMIT_QUORUM_TIMEOUT, INTERNAL_DATABASE_PREFIX},
+    internal_database_prefix,
+};
+use storage::{durability_client::WALClient, keyspace::KeyspaceVerification};
 use tracing::{event, Level};
 
-use crate::{database::DatabaseCreateError, Database, DatabaseDeleteError, DatabaseOpenError, DatabaseResetError};
+use crate::{
+    cluster::{Cluster, ClusterCommitGate, ClusterError},
+    coordinator::CommitLog,
+    database::DatabaseCreateError,
+    Database, DatabaseCloneError, DatabaseDeleteError, DatabaseOpenError, DatabaseResetError, DatabaseSalvageError,
+};
 
 type DatabasesMap = HashMap<String, Arc<Database<WALClient>>>;
 type Databases = RwLock<DatabasesMap>;
@@ -27,25 +40,77 @@ type DatabasesWriteLock<'a> = RwLockWriteGuard<'a, DatabasesMap>;
 pub struct DatabaseManager {
     data_directory: PathBuf,
     import_directory: PathBuf,
+    commit_log: CommitLog,
     databases: Databases,
+    scratch_database_ids: AtomicU64,
+    cluster: OnceLock<Arc<Cluster>>,
+    compile_limits: RwLock<CompileLimits>,
+    // set while holding the databases lock, so that no user database is created once the server replicates a primary
+    replicates_primary: AtomicBool,
+    encryption: Option<DatabaseEncryption>,
+}
+
+/// Which new databases get an encrypted WAL, and the key management service that wraps their data keys.
+/// Existing databases are always opened with the key management service, whether or not they are selected.
+#[derive(Debug, Clone)]
+pub struct DatabaseEncryption {
+    key_management: Arc<dyn KeyManagementService>,
+    databases: Option<HashSet<String>>,
+}
+
+impl DatabaseEncryption {
+    /// Encrypts the named databases when they are created, or every new database if none are named.
+    pub fn new(key_management: Arc<dyn KeyManagementService>, databases: Option<HashSet<String>>) -> Self {
+        Self { key_management, databases }
+    }
+
+    fn key_management_for(&self, path: &Path, name: &str) -> Option<&dyn KeyManagementService> {
+        let selected = self.databases.as_ref().map_or(true, |databases| databases.contains(name));
+        (path.exists() || selected).then_some(self.key_management.as_ref())
+    }
 }
 
 impl DatabaseManager {
     const IMPORT_DIRECTORY_NAME: &'static str = concat!(internal_database_prefix!(), "import");
+    const COMMIT_LOG_DIRECTORY_NAME: &'static str = concat!(internal_database_prefix!(), "commit_log");
+    const SCRATCH_DATABASE_NAME_PREFIX: &'static str = concat!(internal_database_prefix!(), "scratch_");
 
     pub fn new(data_directory: impl AsRef<Path>) -> Result<Arc<Self>, DatabaseOpenError> {
+        Self::new_with_encryption(data_directory, None)
+    }
+
+    pub fn new_with_encryption(
+        data_directory: impl AsRef<Path>,
+        encryption: Option<DatabaseEncryption>,
+    ) -> Result<Arc<Self>, DatabaseOpenError> {
         let data_directory = data_directory.as_ref().to_owned();
         let import_directory = data_directory.join(Self::IMPORT_DIRECTORY_NAME);
+        let key_management = encryption.as_ref().map(|encryption| encryption.key_management.as_ref());
+        let commit_log =
+            CommitLog::recover(data_directory.join(Self::COMMIT_LOG_DIRECTORY_NAME), &data_directory, key_management)
+                .map_err(|typedb_source| DatabaseOpenError::CommitLogRecover { typedb_source })?;
 
-        let databases = RwLock::new(Self::initialise_databases(&data_directory, &import_directory)?);
+        let databases =
+            RwLock::new(Self::initialise_databases(&data_directory, &import_directory, encryption.as_ref())?);
         Self::cleanup_import_directory(&import_directory)?;
 
-        Ok(Arc::new(Self { data_directory, import_directory, databases }))
+        Ok(Arc::new(Self {
+            data_directory,
+            import_directory,
+            commit_log,
+            databases,
+            scratch_database_ids: AtomicU64::new(0),
+            cluster: OnceLock::new(),
+            compile_limits: RwLock::new(CompileLimits::default()),
+            replicates_primary: AtomicBool::new(false),
+            encryption,
+        }))
     }
 
     fn initialise_databases(
         data_directory: &PathBuf,
         import_directory: &PathBuf,
+        encryption: Option<&DatabaseEncryption>,
     ) -> Result<DatabasesMap, DatabaseOpenError> {
         let entries = fs::read_dir(data_directory).map_err(|error| DatabaseOpenError::DirectoryRead {
             name: Self::file_name_lossy(data_directory),
@@ -77,7 +142,8 @@ impl DatabaseManager {
                 continue;
             }
 
-            let database = Database::<WALClient>::open(&entry_path)?;
+            let key_management = encryption.map(|encryption| encryption.key_management.as_ref());
+            let database = Database::<WALClient>::open_with_key_management(&entry_path, key_management)?;
             assert!(!databases.contains_key(database.name()));
             databases.insert(database.name().to_owned(), Arc::new(database));
         }
@@ -133,21 +199,68 @@ impl DatabaseManager {
     }
 
     pub fn put_database(&self, name: impl AsRef<str>) -> Result<(), DatabaseCreateError> {
-        Self::validate_database_name(name.as_ref())?;
-        self.put_database_unrestricted(name)
+        let name = name.as_ref();
+        Self::validate_database_name(name)?;
+        let mut databases = self.databases.write().map_err(|_| DatabaseCreateError::WriteAccessDenied {})?;
+        self.ensure_accepts_user_databases(name)?;
+        if databases.get(name).is_some_and(|database| database.is_replica()) {
+            return Err(DatabaseCreateError::IsReplica { name: name.to_owned() });
+        }
+        self.put_database_locked(&mut databases, name, false).map(|_| ())
     }
 
     pub fn put_database_unrestricted(&self, name: impl AsRef<str>) -> Result<(), DatabaseCreateError> {
-        let name = name.as_ref();
         let mut databases = self.databases.write().map_err(|_| DatabaseCreateError::WriteAccessDenied {})?;
-        if self.exists_import(&databases, name) {
+        self.put_database_locked(&mut databases, name.as_ref(), false).map(|_| ())
+    }
+
+    /// Gets or creates a database to replicate from a primary. A created database is a read-only replica
+    /// before it becomes visible, so that no user transaction can write to it.
+    pub fn put_replica_database(&self, name: &str) -> Result<Arc<Database<WALClient>>, DatabaseCreateError> {
+        Self::validate_database_name(name)?;
+        let mut databases = self.databases.write().map_err(|_| DatabaseCreateError::WriteAccessDenied {})?;
+        self.put_database_locked(&mut databases, name, true)
+    }
+
+    fn put_database_locked(
+        &self,
+        databases: &mut DatabasesWriteLock<'_>,
+        name: &str,
+        as_replica: bool,
+    ) -> Result<Arc<Database<WALClient>>, DatabaseCreateError> {
+        if self.exists_import(databases, name) {
             return Err(DatabaseCreateError::IsBeingImported { name: name.to_string() });
         }
-        if !databases.contains_key(name) {
-            let database = self.new_public_database(name)?;
-            databases.insert(name.to_string(), Arc::new(database));
+        if let Some(database) = databases.get(name) {
+            return Ok(database.clone());
         }
-        Ok(())
+        let database = self.new_public_database(name)?;
+        if as_replica {
+            database.make_replica();
+        }
+        let database = Arc::new(database);
+        databases.insert(name.to_string(), database.clone());
+        Ok(database)
+    }
+
+    /// Makes every user database a read-only replica of a primary server. From then on, users may not create
+    /// databases, which are only created by replicating the primary's.
+    pub fn make_replicas(&self) {
+        let databases = self.databases.write().unwrap();
+        self.replicates_primary.store(true, Ordering::SeqCst);
+        for (name, database) in databases.iter() {
+            if Self::is_user_database(name) {
+                database.make_replica();
+            }
+        }
+    }
+
+    // called while holding the databases lock, which `make_replicas` also takes to set the flag
+    fn ensure_accepts_user_databases(&self, name: &str) -> Result<(), DatabaseCreateError> {
+        if self.replicates_primary.load(Ordering::SeqCst) {
+            return Err(DatabaseCreateError::IsReplica { name: name.to_owned() });
+        }
+        self.ensure_cluster_leader().map_err(|typedb_source| DatabaseCreateError::NotClusterLeader { typedb_source })
     }
 
     pub fn delete_database(&self, name: impl AsRef<str>) -> Result<(), DatabaseDeleteError> {
@@ -176,6 +289,143 @@ impl DatabaseManager {
         Ok(())
     }
 
+    /// Verifies the storage of a database, and rebuilds it from the WAL if any storage file is corrupt.
+    /// The database is closed while it is rebuilt, so it must not be in use.
+    pub fn salvage_database(&self, name: &str) -> Result<Vec<KeyspaceVerification>, DatabaseSalvageError> {
+        let mut databases = self.databases.write().unwrap();
+        let Some(database) = databases.remove(name) else {
+            return Err(DatabaseSalvageError::DoesNotExist { name: name.to_owned() });
+        };
+        let database = match Arc::try_unwrap(database) {
+            Ok(database) if !database.is_replica() => database,
+            Ok(database) => {
+                databases.insert(name.to_owned(), Arc::new(database));
+                return Err(DatabaseSalvageError::IsReplica { name: name.to_owned() });
+            }
+            Err(database) => {
+                databases.insert(name.to_owned(), database);
+                return Err(DatabaseSalvageError::InUse { name: name.to_owned() });
+            }
+        };
+
+        let mut verifications = match database.verify_storage() {
+            Ok(verifications) => verifications,
+            Err(typedb_source) => {
+                databases.insert(name.to_owned(), Arc::new(database));
+                return Err(DatabaseSalvageError::StorageVerify { name: name.to_owned(), typedb_source });
+            }
+        };
+        if verifications.iter().all(|verification| verification.is_intact()) {
+            databases.insert(name.to_owned(), Arc::new(database));
+            return Ok(verifications);
+        }
+        match database.wal_holds_every_commit() {
+            Ok(true) => (),
+            Ok(false) => {
+                databases.insert(name.to_owned(), Arc::new(database));
+                return Err(DatabaseSalvageError::IncompleteWAL { name: name.to_owned() });
+            }
+            Err(source) => {
+                databases.insert(name.to_owned(), Arc::new(database));
+                return Err(DatabaseSalvageError::WALRead { name: name.to_owned(), source: Arc::new(source) });
+            }
+        }
+
+        let path = database.path.clone();
+        let database = match database.rebuild_storage(self.key_management_for(&path, name)) {
+            Ok(database) => database,
+            Err(typedb_source) => {
+                // the checkpoints are restored when the rebuild fails, so the database reopens as it was
+                let key_management = self.key_management_for(&path, name);
+                let reopened =
+                    Database::<WALClient>::open_with_key_management(&path, key_management).map_err(|reopen_error| {
+                        event!(
+                            Level::ERROR,
+                            "Could not rebuild the storage of database '{}': {:?}",
+                            name,
+                            typedb_source
+                        );
+                        DatabaseSalvageError::Reopen { name: name.to_owned(), typedb_source: reopen_error }
+                    })?;
+                self.configure(&reopened);
+                databases.insert(name.to_owned(), Arc::new(reopened));
+                return Err(DatabaseSalvageError::Rebuild { name: name.to_owned(), typedb_source });
+            }
+        };
+        self.configure(&database);
+        databases.insert(name.to_owned(), Arc::new(database));
+        for corrupt_range in verifications.iter_mut().flat_map(|verification| &mut verification.corrupt_ranges) {
+            corrupt_range.salvaged = true;
+        }
+        Ok(verifications)
+    }
+
+    /// Creates a database from a checkpoint of an existing one. The copy is assembled in the import directory,
+    /// and only becomes visible once it is complete.
+    pub fn clone_database(&self, source: &str, target: impl AsRef<str>) -> Result<(), DatabaseCloneError> {
+        let target = target.as_ref();
+        let source_database =
+            self.database(source).ok_or_else(|| DatabaseCloneError::SourceNotFound { name: source.to_owned() })?;
+        if source_database.is_replica() {
+            return Err(DatabaseCloneError::SourceIsReplica { name: source.to_owned() });
+        }
+
+        let target_path = self
+            .prepare_cloned_database_directory(target)
+            .map_err(|typedb_source| DatabaseCloneError::Target { typedb_source })?;
+        if let Err(err) = source_database.clone_into(&target_path) {
+            if let Err(cleanup_err) = fs::remove_dir_all(&target_path) {
+                event!(Level::WARN, "Could not remove the partial clone '{}': {:?}", target, cleanup_err);
+            }
+            return Err(err);
+        }
+        self.finalise_cloned_database(target, &target_path)
+            .map_err(|typedb_source| DatabaseCloneError::Target { typedb_source })
+    }
+
+    fn prepare_cloned_database_directory(&self, name: &str) -> Result<PathBuf, DatabaseCreateError> {
+        if !self.import_directory.exists() {
+            fs::create_dir(&self.import_directory).map_err(|source| DatabaseCreateError::DirectoryWrite {
+                name: name.to_owned(),
+                source: Arc::new(source),
+            })?;
+        }
+
+        Self::validate_database_name(name)?;
+
+        let databases = self.databases.write().map_err(|_| DatabaseCreateError::WriteAccessDenied {})?;
+        self.ensure_accepts_user_databases(name)?;
+        if self.exists_public(&databases, name) {
+            return Err(DatabaseCreateError::AlreadyExists { name: name.to_owned() });
+        }
+        if self.exists_import(&databases, name) {
+            return Err(DatabaseCreateError::IsBeingImported { name: name.to_owned() });
+        }
+
+        // the directory is created while holding the lock, so that it reserves the name
+        let path = self.import_directory.join(name);
+        fs::create_dir(&path).map_err(|source| DatabaseCreateError::DirectoryWrite {
+            name: name.to_owned(),
+            source: Arc::new(source),
+        })?;
+        Ok(path)
+    }
+
+    fn finalise_cloned_database(&self, name: &str, path: &PathBuf) -> Result<(), DatabaseCreateError> {
+        let mut databases = self.databases.write().map_err(|_| DatabaseCreateError::WriteAccessDenied {})?;
+        if self.exists_public(&databases, name) {
+            fs::remove_dir_all(path).map_err(|source| DatabaseCreateError::DirectoryWrite {
+                name: name.to_owned(),
+                source: Arc::new(source),
+            })?;
+            return Err(DatabaseCreateError::AlreadyExists { name: name.to_owned() });
+        }
+        self.move_directory_to_data(name, path)?;
+        let database = self.new_public_database(name)?;
+        databases.insert(name.to_owned(), Arc::new(database));
+        Ok(())
+    }
+
     pub(crate) fn prepare_imported_database(&self, name: String) -> Result<Database<WALClient>, DatabaseCreateError> {
         if !self.import_directory.exists() {
             fs::create_dir(&self.import_directory).map_err(|source| DatabaseCreateError::DirectoryWrite {
@@ -187,6 +437,7 @@ impl DatabaseManager {
         Self::validate_database_name(&name)?;
 
         let databases = self.databases.write().map_err(|_| DatabaseCreateError::WriteAccessDenied {})?;
+        self.ensure_accepts_user_databases(&name)?;
         if self.exists_public(&databases, &name) {
             return Err(DatabaseCreateError::AlreadyExists { name });
         }
@@ -227,37 +478,72 @@ impl DatabaseManager {
         database.delete()
     }
 
+    /// Opens an empty database in the import directory that is never visible to users, for work that needs a schema
+    /// other than the one of any existing database. The caller deletes it when done, and any leftovers are removed
+    /// with the import directory on the next startup.
+    pub fn prepare_scratch_database(&self) -> Result<Database<WALClient>, DatabaseCreateError> {
+        let name = format!(
+            "{}{}",
+            Self::SCRATCH_DATABASE_NAME_PREFIX,
+            self.scratch_database_ids.fetch_add(1, Ordering::Relaxed)
+        );
+        if !self.import_directory.exists() {
+            fs::create_dir(&self.import_directory).map_err(|source| DatabaseCreateError::DirectoryWrite {
+                name: name.clone(),
+                source: Arc::new(source),
+            })?;
+        }
+        self.new_imported_database(&name)
+    }
+
     pub fn reset_else_recreate_database(&self, name: impl AsRef<str>) -> Result<(), DatabaseResetError> {
+        self.reset_else_recreate_database_as(name.as_ref(), false)
+    }
+
+    fn reset_else_recreate_database_as(&self, name: &str, as_replica: bool) -> Result<(), DatabaseResetError> {
+        let recreate = || {
+            let created =
+                if as_replica { self.put_replica_database(name).map(|_| ()) } else { self.put_database(name) };
+            created.map_err(|typedb_source| DatabaseResetError::DatabaseCreate { typedb_source })
+        };
+
         // TODO: this is a partial implementation, only single threaded and without cooperative transaction shutdown
         // remove from map to make DB unavailable
         let mut databases = self.databases.write().unwrap();
-        let db = databases.remove(name.as_ref());
+        let db = databases.remove(name);
         let result = if let Some(db) = db {
             match Arc::try_unwrap(db) {
+                Ok(unwrapped) if unwrapped.is_replica() && !as_replica => {
+                    databases.insert(name.to_owned(), Arc::new(unwrapped));
+                    return Err(DatabaseResetError::IsReplica { name: name.to_owned() });
+                }
                 Ok(mut unwrapped) => {
                     let reset_result = unwrapped.reset();
-                    databases.insert(name.as_ref().to_owned(), Arc::new(unwrapped));
+                    // the database is a replica before it is visible again, so that no user transaction can write to it
+                    if as_replica {
+                        unwrapped.make_replica();
+                    }
+                    databases.insert(name.to_owned(), Arc::new(unwrapped));
                     reset_result
                 }
                 Err(arc) => {
                     // failed to reset since it's in use - let's re-insert for now instead of losing the reference
-                    databases.insert(name.as_ref().to_owned(), arc);
+                    databases.insert(name.to_owned(), arc);
                     Err(DatabaseResetError::InUse {})
                 }
             }
         } else {
             drop(databases);
-            self.put_database(name).map_err(|typedb_source| DatabaseResetError::DatabaseCreate { typedb_source })?;
-            return Ok(());
+            return recreate();
         };
 
         drop(databases);
         match result {
             Ok(_) => (),
             Err(_) => {
-                self.delete_database(name.as_ref())
+                self.delete_database(name)
                     .map_err(|typedb_source| DatabaseResetError::DatabaseDelete { typedb_source })?;
-                self.put_database(name).map_err(|typedb_source| DatabaseResetError::DatabaseCreate { typedb_source })?
+                recreate()?
             }
         };
         Ok(())
@@ -290,20 +576,97 @@ impl DatabaseManager {
         name.starts_with(INTERNAL_DATABASE_PREFIX)
     }
 
+    pub fn data_directory(&self) -> &PathBuf {
+        &self.data_directory
+    }
+
+    /// Makes the databases part of a cluster, whose leader is the only member that accepts writes.
+    pub fn set_cluster(&self, cluster: Arc<Cluster>) {
+        self.cluster.set(cluster).expect("Cluster membership can only be set once.");
+        for database in self.databases.read().unwrap().values() {
+            self.may_gate_commits(database);
+        }
+    }
+
+    /// Sets the bounds on the queries accepted by every database, as given by the server configuration.
+    pub fn set_compile_limits(&self, compile_limits: CompileLimits) {
+        let databases = self.databases.read().unwrap();
+        *self.compile_limits.write().unwrap() = compile_limits;
+        for database in databases.values() {
+            database.set_compile_limits(compile_limits);
+        }
+    }
+
+    pub fn cluster(&self) -> Option<&Arc<Cluster>> {
+        self.cluster.get()
+    }
+
+    pub fn ensure_cluster_leader(&self) -> Result<(), ClusterError> {
+        match self.cluster.get() {
+            Some(cluster) => cluster.ensure_leader(),
+            None => Ok(()),
+        }
+    }
+
+    /// Discards the database, which becomes an empty replica to be replicated again from the cluster leader.
+    pub fn reset_as_replica(&self, name: &str) -> Result<(), DatabaseResetError> {
+        self.reset_else_recreate_database_as(name, true)
+    }
+
+    /// The latest commit durably received in each user database, used to compare how up to date cluster members are.
+    pub fn replication_received(&self) -> HashMap<String, u64> {
+        self.databases
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(name, _)| Self::is_user_database(name))
+            .map(|(name, database)| (name.clone(), database.replication_received().number()))
+            .collect()
+    }
+
+    pub fn commit_log(&self) -> &CommitLog {
+        &self.commit_log
+    }
+
     pub(crate) fn import_directory(&self) -> &PathBuf {
         &self.import_directory
     }
 
     fn new_public_database(&self, name: &str) -> Result<Database<WALClient>, DatabaseCreateError> {
-        Database::<WALClient>::open(&self.data_directory.join(name))
-            .map_err(|typedb_source| DatabaseCreateError::DatabaseOpen { typedb_source })
+        let database = self.open_database(&self.data_directory.join(name), name)?;
+        self.configure(&database);
+        Ok(database)
+    }
+
+    fn configure(&self, database: &Database<WALClient>) {
+        database.set_compile_limits(*self.compile_limits.read().unwrap());
+        self.may_gate_commits(database);
+    }
+
+    /// Commits to the user databases of a cluster member are only applied once a quorum of members received them.
+    fn may_gate_commits(&self, database: &Database<WALClient>) {
+        if let Some(cluster) = self.cluster.get().filter(|_| Self::is_user_database(database.name())) {
+            let gate =
+                ClusterCommitGate::new(cluster.clone(), database.name().to_owned(), CLUSTER_COMMIT_QUORUM_TIMEOUT);
+            database.storage.set_commit_gate(Some(Arc::new(gate)));
+        }
     }
 
     fn new_imported_database(&self, name: &str) -> Result<Database<WALClient>, DatabaseCreateError> {
-        Database::<WALClient>::open(&self.import_directory.join(name))
+        let database = self.open_database(&self.import_directory.join(name), name)?;
+        database.set_compile_limits(*self.compile_limits.read().unwrap());
+        Ok(database)
+    }
+
+    fn open_database(&self, path: &Path, name: &str) -> Result<Database<WALClient>, DatabaseCreateError> {
+        Database::<WALClient>::open_with_key_management(path, self.key_management_for(path, name))
             .map_err(|typedb_source| DatabaseCreateError::DatabaseOpen { typedb_source })
     }
 
+    fn key_management_for(&self, path: &Path, name: &str) -> Option<&dyn KeyManagementService> {
+        self.encryption.as_ref().and_then(|encryption| encryption.key_management_for(path, name))
+    }
+
     fn exists_public<'a>(&'a self, databases: &'a DatabasesWriteLock<'a>, name: &str) -> bool {
         let exists_public = self.data_directory.join(name).is_dir();
         assert_eq!(
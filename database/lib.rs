@@ -7,10 +7,17 @@
 #![deny(unused_must_use)]
 #![deny(elided_lifetimes_in_paths)]
 
-pub use self::database::{Database, DatabaseDeleteError, DatabaseOpenError, DatabaseResetError};
+pub use durability::encryption::{EncryptionKey, KeyManagementError, KeyManagementService, MasterKeyManagement};
 
+pub use self::database::{
+    Database, DatabaseCloneError, DatabaseDeleteError, DatabaseOpenError, DatabaseResetError, DatabaseSalvageError,
+};
+
+pub mod cluster;
+pub mod coordinator;
 pub mod database;
 pub mod database_manager;
 pub mod migration;
 pub mod query;
+pub mod replication;
 pub mod transaction;
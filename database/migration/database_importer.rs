@@ -12,7 +12,7 @@ use std::{
     time::Duration,
 };
 
-use bytes::{byte_array::ByteArray, Bytes};
+use bytes::{byte_array::ByteArray, util::Base64Formatter, Bytes};
 use cache::{CacheError, SpilloverCache};
 use concept::{
     error::{ConceptReadError, ConceptWriteError},
@@ -38,9 +38,12 @@ use concept::{
         Capability, Ordering, OwnerAPI, PlayerAPI,
     },
 };
-use encoding::value::{label::Label, value::Value};
+use encoding::{
+    graph::thing::{vertex_object::ObjectVertex, ThingVertex},
+    value::{label::Label, value::Value},
+};
 use error::{typedb_error, TypeDBError};
-use options::TransactionOptions;
+use options::{IsolationLevel, TransactionOptions};
 use query::error::QueryError;
 use resource::{
     constants::{common::SECONDS_IN_DAY, snapshot::BUFFER_KEY_INLINE},
@@ -303,6 +306,7 @@ pub struct DatabaseImporter {
     data_transaction: Option<TransactionWrite<WALClient>>,
     transaction_item_count: u64,
     total_item_count: u64,
+    preserve_original_iids: bool,
 }
 
 impl DatabaseImporter {
@@ -328,9 +332,17 @@ impl DatabaseImporter {
             data_transaction: None,
             transaction_item_count: 0,
             total_item_count: 0,
+            preserve_original_iids: false,
         })
     }
 
+    /// Creates imported entities and relations with the IIDs they were exported with, rather than new ones,
+    /// so that identities referenced outside the database remain valid. The imported schema must assign
+    /// the same type IIDs as the exported one.
+    pub fn preserve_original_iids(&mut self) {
+        self.preserve_original_iids = true;
+    }
+
     pub async fn import_schema(&mut self, schema: String) -> Result<(), DatabaseImportError> {
         if schema.trim().is_empty() {
             return Ok(());
@@ -374,9 +386,11 @@ impl DatabaseImporter {
                 .map_err(|typedb_source| DatabaseImportError::ConceptRead { typedb_source })?
                 .ok_or_else(|| DatabaseImportError::UnknownEntityType { label })?;
 
-            let entity = thing_manager
-                .create_entity(&mut snapshot, entity_type)
-                .map_err(|typedb_source| DatabaseImportError::ConceptWrite { typedb_source })?;
+            let entity = match self.original_object_iid(&id)? {
+                Some(vertex) => thing_manager.create_entity_with_iid(&mut snapshot, entity_type, vertex),
+                None => thing_manager.create_entity(&mut snapshot, entity_type),
+            }
+            .map_err(|typedb_source| DatabaseImportError::ConceptWrite { typedb_source })?;
 
             self.process_owned_attributes(&mut snapshot, &thing_manager, entity.into_object(), owned_attributes)?;
             self.fulfill_awaiting_roles(&mut snapshot, &thing_manager, &id, entity.into_object())?;
@@ -400,9 +414,11 @@ impl DatabaseImporter {
 
             debug_assert!(type_manager.get_is_relation_type_independent(&snapshot, relation_type).unwrap());
 
-            let relation = thing_manager
-                .create_relation(&mut snapshot, relation_type)
-                .map_err(|typedb_source| DatabaseImportError::ConceptWrite { typedb_source })?;
+            let relation = match self.original_object_iid(&id)? {
+                Some(vertex) => thing_manager.create_relation_with_iid(&mut snapshot, relation_type, vertex),
+                None => thing_manager.create_relation(&mut snapshot, relation_type),
+            }
+            .map_err(|typedb_source| DatabaseImportError::ConceptWrite { typedb_source })?;
 
             self.process_owned_attributes(&mut snapshot, &thing_manager, relation.into_object(), owned_attributes)?;
             self.process_related_roles(&mut snapshot, &type_manager, &thing_manager, relation, related_role_players)?;
@@ -443,6 +459,16 @@ impl DatabaseImporter {
         )
     }
 
+    fn original_object_iid(&self, original_id: &str) -> Result<Option<ObjectVertex>, DatabaseImportError> {
+        if !self.preserve_original_iids {
+            return Ok(None);
+        }
+        Base64Formatter::parse(original_id)
+            .and_then(|bytes| ObjectVertex::try_decode(&bytes))
+            .map(Some)
+            .ok_or_else(|| DatabaseImportError::InvalidOriginalIID { id: original_id.to_owned() })
+    }
+
     fn process_owned_attributes(
         &mut self,
         snapshot: &mut impl WritableSnapshot,
@@ -974,7 +1000,7 @@ impl DatabaseImporter {
     async fn commit_write_transaction(transaction: TransactionWrite<WALClient>) -> Result<(), DatabaseImportError> {
         spawn_blocking(move || {
             let (_, result) = transaction.commit();
-            result.map_err(|typedb_source| DatabaseImportError::DataCommitFailed { typedb_source })
+            result.map(|_| ()).map_err(|typedb_source| DatabaseImportError::DataCommitFailed { typedb_source })
         })
         .await
         .expect("Expected write transaction commit completion")
@@ -983,7 +1009,7 @@ impl DatabaseImporter {
     async fn commit_schema_transaction(transaction: TransactionSchema<WALClient>) -> Result<(), SchemaCommitError> {
         spawn_blocking(move || {
             let (_, result) = transaction.commit();
-            result
+            result.map(|_| ())
         })
         .await
         .expect("Expected schema transaction commit completion")
@@ -994,6 +1020,10 @@ impl DatabaseImporter {
             parallel: Self::OPTIONS_PARALLEL,
             schema_lock_acquire_timeout_millis: Self::OPTIONS_SCHEMA_LOCK_ACQUIRE_TIMEOUT_MILLIS,
             transaction_timeout_millis: Self::OPTIONS_TRANSACTION_TIMEOUT_MILLIS,
+            cascade_type_deletion: false,
+            defer_validation: true,
+            read_at_sequence_number: None,
+            isolation_level: IsolationLevel::Snapshot,
         }
     }
 }
@@ -1056,5 +1086,6 @@ typedb_error! {
         Finalisation(23, "Error finalizing the imported database.", typedb_source: DatabaseCreateError),
         AccessAfterFinalisation(24, "Tried to modify the imported database's state after finalization. It is a sign of a client bug."),
         CacheError(25, "Error writing import data.", source: CacheError),
+        InvalidOriginalIID(26, "Cannot preserve the original IID '{id}' of an imported instance, since it is not an entity or relation IID.", id: String),
     }
 }
@@ -5,8 +5,11 @@
  */
 use std::{sync::Arc, time::Instant};
 
-use compiler::{query_structure::PipelineStructure, VariablePosition};
-use concept::{thing::thing_manager::ThingManager, type_::type_manager::TypeManager};
+use compiler::{query_structure::PipelineStructure, warning::QueryWarning, VariablePosition};
+use concept::{
+    thing::thing_manager::ThingManager,
+    type_::{type_manager::TypeManager, TypeDeletePolicy},
+};
 use executor::{
     batch::Batch,
     document::ConceptDocument,
@@ -18,6 +21,7 @@ use ir::pipeline::ParameterRegistry;
 use itertools::{Either, Itertools};
 use options::QueryOptions;
 use query::{error::QueryError, query_manager::QueryManager};
+use resource::profile::QueryProfile;
 use storage::{durability_client::WALClient, snapshot::WritableSnapshot};
 use tracing::{event, Level};
 use typeql::query::SchemaQuery;
@@ -36,15 +40,27 @@ pub type WriteQueryResult = Result<WriteQueryAnswer, Box<QueryError>>;
 pub struct WriteQueryAnswer {
     pub query_options: QueryOptions,
     pub answer: Either<WriteQueryBatchAnswer, WriteQueryDocumentsAnswer>,
+    pub warnings: Vec<QueryWarning>,
+    pub query_profile: Arc<QueryProfile>,
 }
 
 impl WriteQueryAnswer {
-    fn new_batch(query_options: QueryOptions, answer: WriteQueryBatchAnswer) -> Self {
-        Self { query_options, answer: Either::Left(answer) }
+    fn new_batch(
+        query_options: QueryOptions,
+        answer: WriteQueryBatchAnswer,
+        warnings: Vec<QueryWarning>,
+        query_profile: Arc<QueryProfile>,
+    ) -> Self {
+        Self { query_options, answer: Either::Left(answer), warnings, query_profile }
     }
 
-    fn new_documents(query_options: QueryOptions, answer: WriteQueryDocumentsAnswer) -> Self {
-        Self { query_options, answer: Either::Right(answer) }
+    fn new_documents(
+        query_options: QueryOptions,
+        answer: WriteQueryDocumentsAnswer,
+        warnings: Vec<QueryWarning>,
+        query_profile: Arc<QueryProfile>,
+    ) -> Self {
+        Self { query_options, answer: Either::Right(answer), warnings, query_profile }
     }
 }
 
@@ -53,17 +69,23 @@ pub fn execute_schema_query(
     query: SchemaQuery,
     source_query: String,
 ) -> (TransactionSchema<WALClient>, Result<(), Box<QueryError>>) {
+    let type_delete_policy = if transaction.transaction_options.cascade_type_deletion {
+        TypeDeletePolicy::Cascade
+    } else {
+        TypeDeletePolicy::Restrict
+    };
     with_transaction_parts!(
         TransactionSchema,
         transaction,
         |inner_snapshot, type_manager, thing_manager, function_manager, query_manager| {
-            query_manager.execute_schema(
+            query_manager.execute_schema_with_policy(
                 &mut inner_snapshot,
                 &type_manager,
                 &thing_manager,
                 &function_manager,
                 query,
                 &source_query,
+                type_delete_policy,
             )
         }
     )
@@ -169,22 +191,26 @@ pub(crate) fn execute_write_query_in<Snapshot: WritableSnapshot + 'static>(
     interrupt: ExecutionInterrupt,
 ) -> (Snapshot, WriteQueryResult) {
     let start_time = Instant::now();
-    let result = query_manager.prepare_write_pipeline(
+    let result = query_manager.prepare_write_pipeline_profiled(
         snapshot,
         type_manager,
         thing_manager,
         function_manager,
         pipeline,
         source_query,
+        query_options.include_query_stats,
     );
     let pipeline = match result {
         Ok(pipeline) => pipeline,
         Err((snapshot, err)) => return (snapshot, Err(err)),
     };
 
+    let mut warnings = pipeline.warnings().to_vec();
     if pipeline.has_fetch() {
         let (iterator, parameters, snapshot, query_profile) = match pipeline.into_documents_iterator(interrupt) {
-            Ok((iterator, ExecutionContext { snapshot, profile, parameters, .. })) => {
+            Ok((iterator, ExecutionContext { snapshot, profile, parameters, warnings: execution_warnings, .. })) => {
+                // write stages run to completion before the iterator is returned
+                warnings.extend(execution_warnings.warnings());
                 (iterator, parameters, snapshot, profile)
             }
             Err((err, ExecutionContext { snapshot, .. })) => {
@@ -213,10 +239,10 @@ pub(crate) fn execute_write_query_in<Snapshot: WritableSnapshot + 'static>(
                 }
             }
         }
-        if query_profile.is_enabled() {
+        if tracing::enabled!(Level::TRACE) {
             let micros = Instant::now().duration_since(start_time).as_micros();
             event!(
-                Level::INFO,
+                Level::TRACE,
                 "Write query done (excluding network request time) in {} micros.\n{}",
                 micros,
                 query_profile
@@ -224,14 +250,17 @@ pub(crate) fn execute_write_query_in<Snapshot: WritableSnapshot + 'static>(
         }
         (
             Arc::into_inner(snapshot).unwrap(),
-            Ok(WriteQueryAnswer::new_documents(query_options, (parameters, documents))),
+            Ok(WriteQueryAnswer::new_documents(query_options, (parameters, documents), warnings, query_profile)),
         )
     } else {
         let named_outputs = pipeline.rows_positions().unwrap();
         let pipeline_structure = pipeline.pipeline_structure().cloned();
         let query_output_descriptor: StreamQueryOutputDescriptor = named_outputs.clone().into_iter().sorted().collect();
         let (iterator, snapshot, query_profile) = match pipeline.into_rows_iterator(interrupt) {
-            Ok((iterator, ExecutionContext { snapshot, profile, .. })) => (iterator, snapshot, profile),
+            Ok((iterator, ExecutionContext { snapshot, profile, warnings: execution_warnings, .. })) => {
+                warnings.extend(execution_warnings.warnings());
+                (iterator, snapshot, profile)
+            }
             Err((err, ExecutionContext { snapshot, .. })) => {
                 return (
                     Arc::into_inner(snapshot).unwrap(),
@@ -246,7 +275,12 @@ pub(crate) fn execute_write_query_in<Snapshot: WritableSnapshot + 'static>(
         let result = match iterator.collect_owned() {
             Ok(batch) => (
                 Arc::into_inner(snapshot).unwrap(),
-                Ok(WriteQueryAnswer::new_batch(query_options, (query_output_descriptor, batch, pipeline_structure))),
+                Ok(WriteQueryAnswer::new_batch(
+                    query_options,
+                    (query_output_descriptor, batch, pipeline_structure),
+                    warnings,
+                    query_profile.clone(),
+                )),
             ),
             Err(err) => (
                 Arc::into_inner(snapshot).unwrap(),
@@ -257,10 +291,10 @@ pub(crate) fn execute_write_query_in<Snapshot: WritableSnapshot + 'static>(
             ),
         };
 
-        if query_profile.is_enabled() {
+        if tracing::enabled!(Level::TRACE) {
             let micros = Instant::now().duration_since(start_time).as_micros();
             event!(
-                Level::INFO,
+                Level::TRACE,
                 "Write query done (excluding network request time) in {} micros.\n{}",
                 micros,
                 query_profile
@@ -0,0 +1,350 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{borrow::Cow, collections::BTreeMap, sync::Arc};
+
+use concept::{
+    thing::statistics::StatisticsError,
+    type_::type_manager::{
+        type_cache::{TypeCache, TypeCacheCreateError},
+        TypeManager,
+    },
+};
+use durability::{DurabilitySequenceNumber, RawRecord};
+use error::typedb_error;
+use function::{function_cache::FunctionCache, FunctionError};
+use storage::{
+    durability_client::{DurabilityClient, DurabilityClientError, DurabilityRecord, WALClient},
+    isolation_manager::{CommitRecord, CommitType, StatusRecord},
+    sequence_number::SequenceNumber,
+    StorageReplicationError,
+};
+
+use crate::Database;
+
+/// Tracks the replication of a database from its primary, whose WAL is applied in order.
+/// Commit records are only applied to storage once the primary reports their status, exactly as in recovery.
+/// Commits are resolved in order, so that every commit after the watermark is pending.
+#[derive(Debug)]
+pub(crate) struct ReplicaState {
+    pending: BTreeMap<SequenceNumber, CommitRecord>,
+    // statuses received for pending commits, which wait for the earlier pending commits to be resolved
+    statuses: BTreeMap<SequenceNumber, bool>,
+    primary_watermark: SequenceNumber,
+    // unsequenced records share the sequence number of the latest commit, so the position in the primary's WAL
+    // is the last sequence number received, and how many records with that sequence number were received
+    cursor: Option<(SequenceNumber, usize)>,
+}
+
+impl ReplicaState {
+    fn new() -> Self {
+        Self {
+            pending: BTreeMap::new(),
+            statuses: BTreeMap::new(),
+            primary_watermark: SequenceNumber::MIN,
+            cursor: None,
+        }
+    }
+}
+
+impl Database<WALClient> {
+    /// Turns the database into a read-only replica. Replicas reject write and schema transactions,
+    /// and may only be modified by applying the WAL records of their primary.
+    pub fn make_replica(&self) {
+        self.replica.lock().unwrap().get_or_insert_with(ReplicaState::new);
+    }
+
+    /// Turns a replica back into a writable database, for example when its server is elected the cluster leader.
+    /// The former primary only sent commits it had validated, and may have acknowledged them once a quorum received
+    /// them, so the commits received without a status are validated again and applied, as recovery does.
+    pub fn make_primary(&self) -> Result<(), DatabaseReplicationError> {
+        let mut replica = self.replica.lock().unwrap();
+        let Some(mut state) = replica.take() else {
+            return Ok(());
+        };
+        let mut schema_commit = None;
+        while let Some((sequence_number, commit_record)) = state.pending.pop_first() {
+            let is_schema_commit = matches!(commit_record.commit_type(), CommitType::Schema);
+            let was_committed = match state.statuses.remove(&sequence_number) {
+                Some(was_committed) => self
+                    .storage
+                    .replicate_commit_status(sequence_number, commit_record, was_committed)
+                    .map(|()| was_committed),
+                None => self.storage.resolve_replicated_commit(sequence_number, commit_record),
+            }
+            .map_err(|typedb_source| DatabaseReplicationError::Storage { typedb_source })?;
+            if is_schema_commit && was_committed {
+                schema_commit = Some(sequence_number);
+            }
+        }
+        drop(replica);
+
+        if let Some(sequence_number) = schema_commit {
+            self.reload_schema_caches(sequence_number)?;
+        }
+        Ok(())
+    }
+
+    /// Discards the commits received from the former primary without a status, which a new primary may not have.
+    /// They are received again from the new primary, from the watermark onwards.
+    pub fn discard_unresolved_replication(&self) -> Result<(), DatabaseReplicationError> {
+        let mut replica = self.replica.lock().unwrap();
+        let Some(state) = replica.as_mut() else {
+            return Ok(());
+        };
+        if let Some(&first_pending) = state.pending.keys().next() {
+            self.storage
+                .discard_replicated_from(first_pending)
+                .map_err(|typedb_source| DatabaseReplicationError::Storage { typedb_source })?;
+        }
+        state.pending.clear();
+        state.statuses.clear();
+        state.cursor = None;
+        Ok(())
+    }
+
+    /// The latest commit visible to readers, which replicas of this database catch up to.
+    pub fn replication_watermark(&self) -> SequenceNumber {
+        self.storage.snapshot_watermark()
+    }
+
+    /// The number of commits the primary had made, as last reported, which are not yet visible on this replica.
+    pub fn replication_lag(&self) -> Option<u64> {
+        let replica = self.replica.lock().unwrap();
+        let watermark = self.storage.snapshot_watermark();
+        replica.as_ref().map(|state| state.primary_watermark.number().saturating_sub(watermark.number()))
+    }
+
+    /// The latest commit durably received from the primary, applied or not, which commits on a cluster leader wait for.
+    pub fn replication_received(&self) -> SequenceNumber {
+        let replica = self.replica.lock().unwrap();
+        let last_pending = replica.as_ref().and_then(|state| state.pending.keys().next_back().copied());
+        last_pending.unwrap_or_else(|| self.storage.snapshot_watermark())
+    }
+
+    /// The sequence number of the primary's WAL from which this replica must read next.
+    pub fn replication_position(&self) -> SequenceNumber {
+        let replica = self.replica.lock().unwrap();
+        match replica.as_ref().and_then(|state| state.cursor) {
+            Some((sequence_number, _)) => sequence_number,
+            None => self.storage.snapshot_watermark().next(),
+        }
+    }
+
+    /// Reads the WAL records from `from` onwards, for replicas to apply. Commits after the watermark are only sent
+    /// once they are validated and awaiting the commit gate, so that replicas never receive a commit which its
+    /// primary would have rejected. The records stop at the first commit which is not.
+    pub fn read_replication_records(
+        &self,
+        from: SequenceNumber,
+        limit: usize,
+    ) -> Result<Vec<RawRecord<'static>>, DatabaseReplicationError> {
+        let watermark = self.storage.snapshot_watermark();
+        let records = self
+            .storage
+            .durability()
+            .iter_from(from)
+            .map_err(|typedb_source| DatabaseReplicationError::DurabilityRead { typedb_source })?;
+        records
+            .take_while(|record| match record {
+                Ok(record) if record.record_type == CommitRecord::RECORD_TYPE && record.sequence_number > watermark => {
+                    self.storage.is_awaiting_commit_gate(record.sequence_number)
+                }
+                _ => true,
+            })
+            .take(limit)
+            .collect::<Result<_, _>>()
+            .map_err(|typedb_source| DatabaseReplicationError::DurabilityRead { typedb_source })
+    }
+
+    /// Applies WAL records read from the primary from `replication_position()` onwards.
+    /// Records at the start of the batch which were already received in the previous batch are skipped.
+    /// The received records are durable once this returns, so that they count towards the primary's commit quorum.
+    pub fn apply_replication_records(
+        &self,
+        records: impl IntoIterator<Item = RawRecord<'static>>,
+        primary_watermark: SequenceNumber,
+    ) -> Result<(), DatabaseReplicationError> {
+        let mut replica = self.replica.lock().unwrap();
+        let Some(state) = replica.as_mut() else {
+            return Err(DatabaseReplicationError::NotReplica { name: self.name().to_owned() });
+        };
+        state.primary_watermark = primary_watermark;
+        let mut already_received = state.cursor.map_or(0, |(_, count)| count);
+        let mut schema_commit = None;
+        let mut is_written = false;
+        for record in records {
+            match &mut state.cursor {
+                Some((sequence_number, count)) if *sequence_number == record.sequence_number => {
+                    if already_received > 0 {
+                        already_received -= 1;
+                        continue;
+                    }
+                    *count += 1;
+                }
+                cursor => {
+                    already_received = 0;
+                    *cursor = Some((record.sequence_number, 1));
+                }
+            }
+            is_written = true;
+            if let Some(sequence_number) = self.apply_replication_record(state, record)? {
+                schema_commit = Some(sequence_number);
+            }
+        }
+        drop(replica);
+        if is_written {
+            self.storage.durability().request_sync().recv().unwrap();
+        }
+
+        if let Some(sequence_number) = schema_commit {
+            self.reload_schema_caches(sequence_number)?;
+        }
+        Ok(())
+    }
+
+    /// Applies one replicated record, returning the sequence number of the last schema commit it applied, if any.
+    fn apply_replication_record(
+        &self,
+        state: &mut ReplicaState,
+        record: RawRecord<'static>,
+    ) -> Result<Option<SequenceNumber>, DatabaseReplicationError> {
+        use DatabaseReplicationError::{RecordDeserialize, StatusWithoutCommit, Storage};
+
+        let RawRecord { sequence_number, record_type, bytes } = record;
+        match record_type {
+            CommitRecord::RECORD_TYPE => {
+                if sequence_number <= self.storage.snapshot_watermark() {
+                    return Ok(None);
+                }
+                let commit_record = CommitRecord::deserialise_from(&mut &*bytes)
+                    .map_err(|source| RecordDeserialize { source: Arc::new(source) })?;
+                // after a restart, commits from the local WAL are received again while their status is pending
+                if sequence_number >= self.storage.durability().current() {
+                    self.storage
+                        .replicate_commit_record(sequence_number, &commit_record)
+                        .map_err(|typedb_source| Storage { typedb_source })?;
+                }
+                state.pending.insert(sequence_number, commit_record);
+                Ok(None)
+            }
+            StatusRecord::RECORD_TYPE => {
+                let status = StatusRecord::deserialise_from(&mut &*bytes)
+                    .map_err(|source| RecordDeserialize { source: Arc::new(source) })?;
+                let commit_sequence_number = status.commit_record_sequence_number();
+                if commit_sequence_number <= self.storage.snapshot_watermark() {
+                    return Ok(None);
+                }
+                if !state.pending.contains_key(&commit_sequence_number) {
+                    return Err(StatusWithoutCommit {
+                        name: self.name().to_owned(),
+                        sequence_number: commit_sequence_number,
+                    });
+                }
+                state.statuses.insert(commit_sequence_number, status.was_committed());
+                self.resolve_in_order(state)
+            }
+            // statistics are recomputed by the replica from the replicated commits
+            _ => Ok(None),
+        }
+    }
+
+    /// Applies or discards the pending commits whose status was received, up to the first one whose status was not.
+    fn resolve_in_order(&self, state: &mut ReplicaState) -> Result<Option<SequenceNumber>, DatabaseReplicationError> {
+        let mut schema_commit = None;
+        while let Some(first_pending) = state.pending.first_entry() {
+            let Some(was_committed) = state.statuses.remove(first_pending.key()) else {
+                break;
+            };
+            let sequence_number = *first_pending.key();
+            let commit_record = first_pending.remove();
+            let is_schema_commit = matches!(commit_record.commit_type(), CommitType::Schema);
+            self.storage
+                .replicate_commit_status(sequence_number, commit_record, was_committed)
+                .map_err(|typedb_source| DatabaseReplicationError::Storage { typedb_source })?;
+            if is_schema_commit && was_committed {
+                schema_commit = Some(sequence_number);
+            }
+        }
+        Ok(schema_commit)
+    }
+
+    /// Rebuilds the schema caches after a replicated schema commit, as the schema commit does on the primary.
+    fn reload_schema_caches(&self, sequence_number: SequenceNumber) -> Result<(), DatabaseReplicationError> {
+        use DatabaseReplicationError::{FunctionCacheUpdate, StatisticsUpdate, TypeCacheUpdate};
+
+        let mut schema_guard = self.schema.write().unwrap();
+        let mut schema = (*schema_guard).clone();
+        let type_cache = TypeCache::new(self.storage.clone(), sequence_number)
+            .map_err(|typedb_source| TypeCacheUpdate { typedb_source })?;
+        schema.type_cache = Arc::new(type_cache);
+        let type_manager = TypeManager::new(
+            self.definition_key_generator.clone(),
+            self.type_vertex_generator.clone(),
+            Some(schema.type_cache.clone()),
+        );
+        let function_cache = FunctionCache::new(self.storage.clone(), &type_manager, sequence_number)
+            .map_err(|typedb_source| FunctionCacheUpdate { typedb_source })?;
+        schema.function_cache = Arc::new(function_cache);
+
+        let mut thing_statistics = (*schema.thing_statistics).clone();
+        thing_statistics.may_synchronise(&self.storage).map_err(|typedb_source| StatisticsUpdate { typedb_source })?;
+        schema.thing_statistics = Arc::new(thing_statistics);
+        self.query_cache.force_reset(&schema.thing_statistics);
+
+        *schema_guard = schema;
+        Ok(())
+    }
+}
+
+/// Writes WAL records in the replication wire format: each record is its big-endian sequence number,
+/// its record type, the big-endian length of its bytes, and the bytes themselves.
+pub fn encode_replication_records(records: &[RawRecord<'static>]) -> Vec<u8> {
+    let mut buffer = Vec::with_capacity(records.iter().map(|record| record.bytes.len() + RECORD_HEADER_LENGTH).sum());
+    for record in records {
+        buffer.extend_from_slice(&record.sequence_number.to_be_bytes());
+        buffer.push(record.record_type);
+        buffer.extend_from_slice(&(record.bytes.len() as u32).to_be_bytes());
+        buffer.extend_from_slice(&record.bytes);
+    }
+    buffer
+}
+
+pub fn decode_replication_records(mut bytes: &[u8]) -> Result<Vec<RawRecord<'static>>, DatabaseReplicationError> {
+    let mut records = Vec::new();
+    while !bytes.is_empty() {
+        if bytes.len() < RECORD_HEADER_LENGTH {
+            return Err(DatabaseReplicationError::RecordsTruncated {});
+        }
+        let (header, rest) = bytes.split_at(RECORD_HEADER_LENGTH);
+        let sequence_number = DurabilitySequenceNumber::from_be_bytes(&header[0..8]);
+        let record_type = header[8];
+        let length = u32::from_be_bytes(header[9..13].try_into().unwrap()) as usize;
+        if rest.len() < length {
+            return Err(DatabaseReplicationError::RecordsTruncated {});
+        }
+        let (record_bytes, rest) = rest.split_at(length);
+        records.push(RawRecord { sequence_number, record_type, bytes: Cow::Owned(record_bytes.to_vec()) });
+        bytes = rest;
+    }
+    Ok(records)
+}
+
+const RECORD_HEADER_LENGTH: usize = DurabilitySequenceNumber::serialised_len() + 1 + size_of::<u32>();
+
+typedb_error! {
+    pub DatabaseReplicationError(component = "Database replication", prefix = "DRP") {
+        NotReplica(1, "Database '{name}' is not a replica.", name: String),
+        DurabilityRead(2, "Error reading the WAL for replication.", typedb_source: DurabilityClientError),
+        RecordDeserialize(3, "Error deserialising a replicated WAL record.", source: Arc<bincode::Error>),
+        RecordsTruncated(4, "Replicated WAL records are truncated."),
+        StatusWithoutCommit(5, "Replica of database '{name}' received the status of commit {sequence_number} before its commit record.", name: String, sequence_number: SequenceNumber),
+        Storage(6, "Error applying replicated WAL records to storage.", typedb_source: StorageReplicationError),
+        TypeCacheUpdate(7, "Error updating the type cache after a replicated schema commit.", typedb_source: TypeCacheCreateError),
+        FunctionCacheUpdate(8, "Error updating the function cache after a replicated schema commit.", typedb_source: FunctionError),
+        StatisticsUpdate(9, "Error updating statistics after a replicated schema commit.", typedb_source: StatisticsError),
+    }
+}
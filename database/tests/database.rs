@@ -4,7 +4,12 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use database::Database;
+use std::time::Duration;
+
+use database::{
+    cluster::{Cluster, ClusterError, ClusterReply, ClusterRole},
+    Database,
+};
 use storage::durability_client::WALClient;
 use test_utils::{create_tmp_dir, init_logging};
 
@@ -18,3 +23,71 @@ fn create_delete_database() {
     let delete_result = db.delete();
     assert!(delete_result.is_ok());
 }
+
+#[test]
+fn cluster_elects_leader_by_quorum_and_persists_votes() {
+    init_logging();
+    let data_directory = create_tmp_dir();
+    let peers = vec!["http://b:8000".to_owned(), "http://c:8000".to_owned()];
+    let cluster = Cluster::open(&data_directory, "http://a:8000".to_owned(), peers.clone(), Duration::ZERO).unwrap();
+    assert!(matches!(cluster.ensure_leader(), Err(ClusterError::NotLeader { .. })));
+
+    let term = cluster.may_start_election().unwrap().expect("Expected an election after the election timeout");
+    assert_eq!(cluster.role(), ClusterRole::Candidate);
+    let is_leader = cluster.handle_vote_reply(term, "http://b:8000", ClusterReply { term, accepted: true }).unwrap();
+    assert!(is_leader);
+    assert!(cluster.ensure_leader().is_ok());
+
+    // a leader of a later term deposes this one, and writes are redirected to it
+    let reply = cluster.handle_heartbeat(term + 1, "http://c:8000").unwrap();
+    assert!(reply.accepted);
+    match cluster.ensure_leader() {
+        Err(ClusterError::NotLeader { leader, .. }) => assert_eq!(leader, "http://c:8000"),
+        other => panic!("Expected a redirect to the new leader, got {other:?}"),
+    }
+    drop(cluster);
+
+    // terms and votes survive restarts, so that no member votes twice in a term
+    let cluster = Cluster::open(&data_directory, "http://a:8000".to_owned(), peers.clone(), Duration::ZERO).unwrap();
+    assert_eq!(cluster.term(), term + 1);
+    assert!(cluster.handle_vote_request(term + 2, "http://b:8000", true).unwrap().accepted);
+    assert!(!cluster.handle_vote_request(term + 2, "http://b:8000", false).unwrap().accepted);
+    drop(cluster);
+
+    let cluster = Cluster::open(&data_directory, "http://a:8000".to_owned(), peers, Duration::ZERO).unwrap();
+    assert!(!cluster.handle_vote_request(term + 2, "http://c:8000", true).unwrap().accepted);
+    assert!(cluster.handle_vote_request(term + 2, "http://b:8000", true).unwrap().accepted);
+}
+
+#[test]
+fn cluster_leader_steps_down_without_commit_quorum() {
+    init_logging();
+    let data_directory = create_tmp_dir();
+    let peers = vec!["http://b:8000".to_owned(), "http://c:8000".to_owned()];
+    let cluster = Cluster::open(&data_directory, "http://a:8000".to_owned(), peers.clone(), Duration::ZERO).unwrap();
+    let term = cluster.may_start_election().unwrap().unwrap();
+    assert!(cluster.handle_vote_reply(term, "http://b:8000", ClusterReply { term, accepted: true }).unwrap());
+    assert!(cluster.has_led());
+
+    // peers have unknown roles until they accept a heartbeat
+    let roles = |cluster: &Cluster| cluster.members().into_iter().map(|member| member.role).collect::<Vec<_>>();
+    assert_eq!(roles(&cluster), vec![Some(ClusterRole::Leader), None, None]);
+    cluster.handle_heartbeat_reply(term, "http://b:8000", ClusterReply { term, accepted: true }).unwrap();
+    assert_eq!(roles(&cluster), vec![Some(ClusterRole::Leader), Some(ClusterRole::Follower), None]);
+
+    cluster.record_follower_progress("http://b:8000", "db", 5);
+    assert!(cluster.await_commit_quorum("db", 5, Duration::ZERO).is_ok());
+    assert!(matches!(
+        cluster.await_commit_quorum("db", 6, Duration::ZERO),
+        Err(ClusterError::CommitQuorumTimeout { .. })
+    ));
+    assert_eq!(cluster.role(), ClusterRole::Follower);
+    assert!(cluster.ensure_leader().is_err());
+    drop(cluster);
+
+    // a former leader replicates its databases again, even after a restart
+    let cluster = Cluster::open(&data_directory, "http://a:8000".to_owned(), peers, Duration::ZERO).unwrap();
+    assert!(cluster.has_led());
+    cluster.clear_has_led().unwrap();
+    assert!(!cluster.has_led());
+}
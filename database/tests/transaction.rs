@@ -4,23 +4,38 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 use std::{
+    borrow::Cow,
     sync::Arc,
     time::{Duration, Instant},
 };
 
+use compiler::annotation::limits::CompileLimits;
+use concept::{
+    thing::object::ObjectAPI,
+    type_::{
+        annotation::AnnotationAbstract, entity_type::EntityTypeAnnotation, DatabaseSettings, HistoryRetention,
+        RelationIndexThreshold, StorageQuota,
+    },
+};
 use database::{
+    coordinator::{commit_atomically, MultiDatabaseCommitError},
+    database::DatabaseCreateError,
     database_manager::DatabaseManager,
-    transaction::{TransactionRead, TransactionSchema, TransactionWrite},
-    Database,
+    replication::{decode_replication_records, encode_replication_records},
+    transaction::{BlockingTransactionType, TransactionError, TransactionRead, TransactionSchema, TransactionWrite},
+    Database, DatabaseCloneError, DatabaseResetError, DatabaseSalvageError,
 };
-use options::TransactionOptions;
-use storage::durability_client::WALClient;
+use encoding::value::{label::Label, value::Value};
+use options::{IsolationLevel, TransactionOptions};
+use resource::profile::StorageCounters;
+use storage::{durability_client::WALClient, snapshot::ReadableSnapshot};
 use test_utils::{create_tmp_dir, init_logging, TempDir};
 use tokio::{
     runtime::Runtime,
     sync::{broadcast, Notify},
     time::sleep,
 };
+use typeql::query::{QueryStructure, SchemaQuery};
 
 const DB_NAME: &str = "test";
 
@@ -140,6 +155,643 @@ fn open_close_read_transaction() {
     tx_read.close()
 }
 
+#[test]
+fn commit_returns_data_version_observed_by_later_reads() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database = create_database(&databases_path);
+
+    let mut tx_schema = open_schema(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+    tx_schema.type_manager.create_entity_type(snapshot, &Label::build("person", None)).unwrap();
+    let schema_version = tx_schema.commit().1.expect("Expected commit").expect("Expected a data version");
+
+    let mut tx_write = open_write(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_write.snapshot).unwrap();
+    let person_type = tx_write.type_manager.get_entity_type(snapshot, &Label::build("person", None)).unwrap().unwrap();
+    tx_write.thing_manager.create_entity(snapshot, person_type).unwrap();
+    let data_version = tx_write.commit().1.expect("Expected commit").expect("Expected a data version");
+    assert!(data_version > schema_version);
+
+    let tx_read = open_read(database.clone());
+    assert!(tx_read.snapshot().open_sequence_number() >= data_version);
+    tx_read.close();
+
+    let empty_version = open_write(database.clone()).commit().1.expect("Expected commit");
+    assert_eq!(empty_version, None);
+}
+
+#[test]
+fn read_transaction_opens_at_previous_sequence_number() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database = create_database(&databases_path);
+
+    let mut tx_schema = open_schema(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+    tx_schema.type_manager.create_entity_type(snapshot, &Label::build("person", None)).unwrap();
+    tx_schema.commit().1.expect("Expected commit");
+
+    let tx_read = open_read(database.clone());
+    let before_insert = tx_read.snapshot().open_sequence_number().number();
+    tx_read.close();
+
+    let mut tx_write = open_write(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_write.snapshot).unwrap();
+    let person_type = tx_write.type_manager.get_entity_type(snapshot, &Label::build("person", None)).unwrap().unwrap();
+    tx_write.thing_manager.create_entity(snapshot, person_type).unwrap();
+    tx_write.commit().1.expect("Expected commit");
+
+    let at_options = |sequence_number| TransactionOptions {
+        read_at_sequence_number: Some(sequence_number),
+        ..TransactionOptions::default()
+    };
+    let tx_read = TransactionRead::open(database.clone(), at_options(before_insert)).unwrap();
+    assert_eq!(tx_read.thing_manager.get_entities(tx_read.snapshot(), StorageCounters::DISABLED).count(), 0);
+    tx_read.close();
+    let tx_read = open_read(database.clone());
+    let latest = tx_read.snapshot().open_sequence_number().number();
+    assert_eq!(tx_read.thing_manager.get_entities(tx_read.snapshot(), StorageCounters::DISABLED).count(), 1);
+    tx_read.close();
+
+    let future_result = TransactionRead::open(database.clone(), at_options(latest + 1));
+    assert!(matches!(future_result, Err(TransactionError::ReadSequenceNumberInFuture { .. })));
+    let write_result = TransactionWrite::open(database.clone(), at_options(before_insert));
+    assert!(matches!(write_result, Err(TransactionError::HistoricalWriteNotSupported { .. })));
+}
+
+#[test]
+fn read_transaction_rejects_sequence_number_outside_history_retention() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+    create_people_databases(&database_manager, &[DB_NAME]);
+    let database = database_manager.database(DB_NAME).unwrap();
+
+    let mut tx_schema = open_schema(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+    tx_schema.type_manager.set_history_retention(snapshot, HistoryRetention(1));
+    tx_schema.commit().1.expect("Expected commit");
+    let tx_read = open_read(database.clone());
+    let before_inserts = tx_read.snapshot().open_sequence_number().number();
+    tx_read.close();
+    for _ in 0..2 {
+        insert_person(database.clone()).commit().1.expect("Expected commit");
+    }
+    let tx_read = open_read(database.clone());
+    let latest = tx_read.snapshot().open_sequence_number().number();
+    tx_read.close();
+
+    let at_options = |sequence_number| TransactionOptions {
+        read_at_sequence_number: Some(sequence_number),
+        ..TransactionOptions::default()
+    };
+    let tx_read = TransactionRead::open(database.clone(), at_options(latest - 1)).unwrap();
+    tx_read.close();
+    let expired_result = TransactionRead::open(database.clone(), at_options(before_inserts));
+    assert!(
+        matches!(expired_result, Err(TransactionError::ReadSequenceNumberNotRetained { oldest, .. }) if oldest == latest - 1)
+    );
+}
+
+#[test]
+fn read_committed_transaction_observes_later_commits() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database = create_database(&databases_path);
+
+    let mut tx_schema = open_schema(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+    tx_schema.type_manager.create_entity_type(snapshot, &Label::build("person", None)).unwrap();
+    tx_schema.commit().1.expect("Expected commit");
+
+    let read_committed_options =
+        TransactionOptions { isolation_level: IsolationLevel::ReadCommitted, ..TransactionOptions::default() };
+    let mut tx_read_committed = TransactionRead::open(database.clone(), read_committed_options.clone()).unwrap();
+    let mut tx_read_snapshot = open_read(database.clone());
+
+    let mut tx_write = open_write(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_write.snapshot).unwrap();
+    let person_type = tx_write.type_manager.get_entity_type(snapshot, &Label::build("person", None)).unwrap().unwrap();
+    tx_write.thing_manager.create_entity(snapshot, person_type).unwrap();
+    tx_write.commit().1.expect("Expected commit");
+
+    let count_entities = |tx: &TransactionRead<WALClient>| {
+        tx.thing_manager.get_entities(tx.snapshot(), StorageCounters::DISABLED).count()
+    };
+    assert_eq!(count_entities(&tx_read_committed), 0);
+    tx_read_committed.advance_snapshot();
+    assert_eq!(count_entities(&tx_read_committed), 1);
+    tx_read_snapshot.advance_snapshot();
+    assert_eq!(count_entities(&tx_read_snapshot), 0);
+    tx_read_committed.close();
+    tx_read_snapshot.close();
+
+    let historical_options = TransactionOptions { read_at_sequence_number: Some(0), ..read_committed_options.clone() };
+    let historical_result = TransactionRead::open(database.clone(), historical_options);
+    assert!(matches!(historical_result, Err(TransactionError::ReadCommittedNotSupported { .. })));
+    let write_result = TransactionWrite::open(database.clone(), read_committed_options);
+    assert!(matches!(write_result, Err(TransactionError::ReadCommittedNotSupported { .. })));
+}
+
+#[test]
+fn deferred_validation_rejects_abstract_instances_on_commit() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database = create_database(&databases_path);
+
+    let mut tx_schema = open_schema(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+    let person_type = tx_schema.type_manager.create_entity_type(snapshot, &Label::build("person", None)).unwrap();
+    person_type
+        .set_annotation(
+            snapshot,
+            &tx_schema.type_manager,
+            &tx_schema.thing_manager,
+            EntityTypeAnnotation::Abstract(AnnotationAbstract),
+            StorageCounters::DISABLED,
+        )
+        .unwrap();
+    tx_schema.commit().1.expect("Expected commit");
+
+    let mut tx_write = open_write(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_write.snapshot).unwrap();
+    let person_type = tx_write.type_manager.get_entity_type(snapshot, &Label::build("person", None)).unwrap().unwrap();
+    assert!(tx_write.thing_manager.create_entity(snapshot, person_type).is_err());
+    tx_write.close();
+
+    let deferred_options = TransactionOptions { defer_validation: true, ..TransactionOptions::default() };
+    let mut tx_write = TransactionWrite::open(database.clone(), deferred_options).unwrap();
+    let snapshot = Arc::get_mut(&mut tx_write.snapshot).unwrap();
+    let person_type = tx_write.type_manager.get_entity_type(snapshot, &Label::build("person", None)).unwrap().unwrap();
+    tx_write.thing_manager.create_entity(snapshot, person_type).unwrap();
+    assert!(tx_write.commit().1.is_err());
+
+    let tx_read = open_read(database.clone());
+    assert_eq!(tx_read.thing_manager.get_entities(tx_read.snapshot(), StorageCounters::DISABLED).count(), 0);
+    tx_read.close();
+}
+
+#[test]
+fn deferred_validation_rejects_attribute_constraint_violations_on_commit() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database = create_database(&databases_path);
+
+    let mut tx_schema = open_schema(database.clone());
+    let define = r#"define
+        entity person, owns name @unique;
+        attribute name, value string @regex("^[A-Z].*$");
+    "#;
+    let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+    tx_schema
+        .query_manager
+        .execute_schema(
+            snapshot,
+            &tx_schema.type_manager,
+            &tx_schema.thing_manager,
+            &tx_schema.function_manager,
+            parse_schema_query(define),
+            define,
+        )
+        .unwrap();
+    tx_schema.commit().1.expect("Expected commit");
+
+    let deferred_options = TransactionOptions { defer_validation: true, ..TransactionOptions::default() };
+    let write_names = |names: &[&str]| {
+        let mut tx_write = TransactionWrite::open(database.clone(), deferred_options.clone()).unwrap();
+        let snapshot = Arc::get_mut(&mut tx_write.snapshot).unwrap();
+        let type_manager = &tx_write.type_manager;
+        let person_type = type_manager.get_entity_type(snapshot, &Label::build("person", None)).unwrap().unwrap();
+        let name_type = type_manager.get_attribute_type(snapshot, &Label::build("name", None)).unwrap().unwrap();
+        for &name in names {
+            let person = tx_write.thing_manager.create_entity(snapshot, person_type).unwrap();
+            let value = Value::String(Cow::Owned(name.to_owned()));
+            // neither the regex nor the uniqueness is checked until commit
+            let name = tx_write.thing_manager.create_attribute(snapshot, name_type, value).unwrap();
+            person.set_has_unordered(snapshot, &tx_write.thing_manager, &name, StorageCounters::DISABLED).unwrap();
+        }
+        tx_write.commit().1
+    };
+
+    assert!(write_names(&["alice"]).is_err());
+    assert!(write_names(&["Alice", "Alice"]).is_err());
+    write_names(&["Alice", "Bob"]).expect("Expected commit");
+
+    let tx_read = open_read(database.clone());
+    assert_eq!(tx_read.thing_manager.get_entities(tx_read.snapshot(), StorageCounters::DISABLED).count(), 2);
+    tx_read.close();
+}
+
+fn parse_schema_query(query: &str) -> SchemaQuery {
+    let QueryStructure::Schema(schema_query) = typeql::parse_query(query).unwrap().into_structure() else {
+        panic!("Expected a schema query");
+    };
+    schema_query
+}
+
+#[test]
+fn schema_dry_run_validates_against_data_without_changing_the_transaction() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database = create_database(&databases_path);
+
+    let mut tx_schema = open_schema(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+    tx_schema.type_manager.create_entity_type(snapshot, &Label::build("person", None)).unwrap();
+    tx_schema.commit().1.expect("Expected commit");
+
+    let mut tx_write = open_write(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_write.snapshot).unwrap();
+    let person_type = tx_write.type_manager.get_entity_type(snapshot, &Label::build("person", None)).unwrap().unwrap();
+    tx_write.thing_manager.create_entity(snapshot, person_type).unwrap();
+    tx_write.commit().1.expect("Expected commit");
+
+    let tx_schema = open_schema(database.clone());
+    let define_animal = "define entity animal;";
+    let schema_diff = tx_schema.dry_run_schema_query(parse_schema_query(define_animal), define_animal).unwrap();
+    assert!(schema_diff.define.iter().any(|statement| statement.contains("animal")), "{schema_diff:?}");
+    let animal_label = Label::build("animal", None);
+    assert!(tx_schema.type_manager.get_entity_type(tx_schema.snapshot.as_ref(), &animal_label).unwrap().is_none());
+
+    let define_function = "define fun people() -> { person }: match $p isa person; return { $p };";
+    let schema_diff = tx_schema.dry_run_schema_query(parse_schema_query(define_function), define_function).unwrap();
+    assert!(schema_diff.define.iter().any(|statement| statement.starts_with("fun people()")), "{schema_diff:?}");
+    let function_key = tx_schema.function_manager.get_function_key(tx_schema.snapshot.as_ref(), "people").unwrap();
+    assert!(function_key.is_none());
+
+    let define_abstract_person = "define entity person @abstract;";
+    let result = tx_schema.dry_run_schema_query(parse_schema_query(define_abstract_person), define_abstract_person);
+    assert!(result.is_err());
+    tx_schema.commit().1.expect("Expected the dry runs to leave nothing to commit");
+
+    let tx_read = open_read(database.clone());
+    assert_eq!(tx_read.thing_manager.get_entities(tx_read.snapshot(), StorageCounters::DISABLED).count(), 1);
+    assert!(tx_read.type_manager.get_entity_type(tx_read.snapshot(), &animal_label).unwrap().is_none());
+    tx_read.close();
+}
+
+#[test]
+fn write_transaction_is_admitted_within_storage_quota() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database = create_database(&databases_path);
+    assert_eq!(database.get_storage_quota().unwrap(), None);
+
+    let mut tx_schema = open_schema(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+    tx_schema.type_manager.set_storage_quota(snapshot, StorageQuota(u64::MAX));
+    tx_schema.commit().1.expect("Expected commit");
+    assert_eq!(database.get_storage_quota().unwrap(), Some(StorageQuota(u64::MAX)));
+
+    let tx_write = TransactionWrite::open(database.clone(), TransactionOptions::default());
+    assert_ok!(tx_write);
+    tx_write.unwrap().close();
+
+    let mut tx_schema = open_schema(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+    tx_schema.type_manager.unset_storage_quota(snapshot);
+    tx_schema.commit().1.expect("Expected commit");
+    assert_eq!(database.get_storage_quota().unwrap(), None);
+}
+
+#[test]
+fn write_transaction_is_rejected_over_storage_quota() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    {
+        let database = create_database(&databases_path);
+        let mut tx_schema = open_schema(database.clone());
+        let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+        tx_schema.type_manager.create_entity_type(snapshot, &Label::build("person", None)).unwrap();
+        tx_schema.type_manager.set_storage_quota(snapshot, StorageQuota(1));
+        tx_schema.commit().1.expect("Expected commit");
+    }
+    // reopening checkpoints the database, which writes its data to storage files that are measured
+    let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+    let database = database_manager.database(DB_NAME).expect("Expected database retrieval");
+    assert!(database.refresh_storage_usage() > 1);
+
+    let write_result = TransactionWrite::open(database.clone(), TransactionOptions::default());
+    assert!(matches!(write_result, Err(TransactionError::StorageQuotaExceeded { quota: 1, .. })));
+
+    let cleanup_result =
+        TransactionWrite::open_exempt_from_storage_quota(database.clone(), TransactionOptions::default());
+    assert_ok!(cleanup_result);
+    cleanup_result.unwrap().close();
+
+    let mut tx_schema = open_schema(database.clone());
+    let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+    tx_schema.type_manager.unset_storage_quota(snapshot);
+    tx_schema.commit().1.expect("Expected commit");
+    open_write(database.clone()).close();
+}
+
+#[test]
+fn replica_applies_primary_wal_and_rejects_writes() {
+    init_logging();
+    let primary_path = create_tmp_dir();
+    let primary = create_database(&primary_path);
+    let replica_path = create_tmp_dir();
+    let replica = create_database(&replica_path);
+    replica.make_replica();
+
+    let mut tx_schema = open_schema(primary.clone());
+    let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+    tx_schema.type_manager.create_entity_type(snapshot, &Label::build("person", None)).unwrap();
+    tx_schema.commit().1.expect("Expected commit");
+
+    let mut tx_write = open_write(primary.clone());
+    let snapshot = Arc::get_mut(&mut tx_write.snapshot).unwrap();
+    let person_type = tx_write.type_manager.get_entity_type(snapshot, &Label::build("person", None)).unwrap().unwrap();
+    tx_write.thing_manager.create_entity(snapshot, person_type).unwrap();
+    tx_write.commit().1.expect("Expected commit");
+
+    let records = primary.read_replication_records(replica.replication_position(), 1024).unwrap();
+    let records = decode_replication_records(&encode_replication_records(&records)).unwrap();
+    replica.apply_replication_records(records, primary.replication_watermark()).unwrap();
+    assert_eq!(replica.replication_lag(), Some(0));
+    assert_eq!(primary.replication_lag(), None);
+
+    let tx_read = open_read(replica.clone());
+    let person_type = tx_read.type_manager.get_entity_type(tx_read.snapshot(), &Label::build("person", None)).unwrap();
+    assert!(person_type.is_some());
+    assert_eq!(tx_read.thing_manager.get_entities(tx_read.snapshot(), StorageCounters::DISABLED).count(), 1);
+    tx_read.close();
+
+    let write_result = TransactionWrite::open(replica.clone(), TransactionOptions::default());
+    assert!(matches!(write_result, Err(TransactionError::DatabaseIsReplica { .. })));
+    let schema_result = TransactionSchema::open(replica.clone(), TransactionOptions::default());
+    assert!(matches!(schema_result, Err(TransactionError::DatabaseIsReplica { .. })));
+}
+
+#[test]
+fn replicas_reject_user_creation_and_reset() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+    database_manager.put_database(DB_NAME).expect("Expected database creation");
+    let replica = database_manager.put_replica_database("replicated").expect("Expected replica creation");
+    assert!(replica.is_replica());
+    drop(replica);
+
+    let put_result = database_manager.put_database("replicated");
+    assert!(matches!(put_result, Err(DatabaseCreateError::IsReplica { .. })));
+    let reset_result = database_manager.reset_else_recreate_database("replicated");
+    assert!(matches!(reset_result, Err(DatabaseResetError::IsReplica { .. })));
+    assert!(database_manager.database("replicated").unwrap().is_replica());
+
+    // once the server replicates a primary, its databases are replicas and users cannot create more
+    database_manager.make_replicas();
+    assert!(database_manager.database(DB_NAME).unwrap().is_replica());
+    let put_result = database_manager.put_database("created");
+    assert!(matches!(put_result, Err(DatabaseCreateError::IsReplica { .. })));
+    assert!(database_manager.database("created").is_none());
+    assert!(database_manager.put_replica_database("created").unwrap().is_replica());
+}
+
+#[test]
+fn clone_contains_source_data_and_accepts_writes_independently() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+    database_manager.put_database(DB_NAME).expect("Expected database creation");
+    let source = database_manager.database(DB_NAME).expect("Expected database retrieval");
+
+    let mut tx_schema = open_schema(source.clone());
+    let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+    tx_schema.type_manager.create_entity_type(snapshot, &Label::build("person", None)).unwrap();
+    tx_schema.commit().1.expect("Expected commit");
+
+    let mut tx_write = open_write(source.clone());
+    let snapshot = Arc::get_mut(&mut tx_write.snapshot).unwrap();
+    let person_type = tx_write.type_manager.get_entity_type(snapshot, &Label::build("person", None)).unwrap().unwrap();
+    tx_write.thing_manager.create_entity(snapshot, person_type).unwrap();
+    tx_write.commit().1.expect("Expected commit");
+
+    database_manager.clone_database(DB_NAME, "test_clone").expect("Expected database clone");
+    let clone_result = database_manager.clone_database(DB_NAME, "test_clone");
+    assert!(matches!(clone_result, Err(DatabaseCloneError::Target { .. })));
+    let clone = database_manager.database("test_clone").expect("Expected cloned database retrieval");
+
+    let mut tx_write = open_write(clone.clone());
+    let snapshot = Arc::get_mut(&mut tx_write.snapshot).unwrap();
+    let person_type = tx_write.type_manager.get_entity_type(snapshot, &Label::build("person", None)).unwrap().unwrap();
+    tx_write.thing_manager.create_entity(snapshot, person_type).unwrap();
+    tx_write.commit().1.expect("Expected commit");
+
+    let tx_read = open_read(clone.clone());
+    assert_eq!(tx_read.thing_manager.get_entities(tx_read.snapshot(), StorageCounters::DISABLED).count(), 2);
+    tx_read.close();
+    let tx_read = open_read(source.clone());
+    assert_eq!(tx_read.thing_manager.get_entities(tx_read.snapshot(), StorageCounters::DISABLED).count(), 1);
+    tx_read.close();
+}
+
+#[test]
+fn settings_apply_on_schema_commit_and_are_carried_by_clones() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+    database_manager.put_database(DB_NAME).expect("Expected database creation");
+    let source = database_manager.database(DB_NAME).expect("Expected database retrieval");
+    assert_eq!(source.settings(), DatabaseSettings::default());
+
+    let settings = DatabaseSettings {
+        relation_index_threshold: RelationIndexThreshold(2),
+        transaction_timeout_millis: Some(1234),
+        schema_lock_acquire_timeout_millis: None,
+        statistics_updates: false,
+    };
+    let mut tx_schema = open_schema(source.clone());
+    let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+    tx_schema
+        .type_manager
+        .set_database_settings(snapshot, &tx_schema.thing_manager, settings, StorageCounters::DISABLED)
+        .unwrap();
+    assert_eq!(source.settings(), DatabaseSettings::default());
+    tx_schema.commit().1.expect("Expected commit");
+
+    assert_eq!(source.settings(), settings);
+    assert_eq!(source.transaction_defaults().transaction_timeout_millis, 1234);
+    let tx_read = open_read(source.clone());
+    assert_eq!(
+        tx_read.type_manager.get_relation_index_threshold(tx_read.snapshot()).unwrap(),
+        RelationIndexThreshold(2)
+    );
+    tx_read.close();
+
+    database_manager.clone_database(DB_NAME, "test_clone").expect("Expected database clone");
+    let clone = database_manager.database("test_clone").expect("Expected cloned database retrieval");
+    assert_eq!(clone.settings(), settings);
+}
+
+fn corrupt_storage_file(database_path: &std::path::Path) {
+    let storage_file = std::fs::read_dir(database_path.join("storage"))
+        .unwrap()
+        .flat_map(|keyspace| std::fs::read_dir(keyspace.unwrap().path()).unwrap())
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|extension| extension == "sst"))
+        .expect("Expected a storage file");
+    let mut file_bytes = std::fs::read(&storage_file).unwrap();
+    file_bytes[16] ^= 0xff;
+    std::fs::write(&storage_file, file_bytes).unwrap();
+}
+
+#[test]
+fn compile_limits_apply_to_existing_and_new_databases() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+    database_manager.put_database(DB_NAME).expect("Expected database creation");
+    assert_eq!(database_manager.database(DB_NAME).unwrap().compile_limits(), CompileLimits::default());
+
+    let limits = CompileLimits { max_pattern_constraints: 2, ..CompileLimits::default() };
+    database_manager.set_compile_limits(limits);
+    database_manager.put_database("test_new").expect("Expected database creation");
+    assert_eq!(database_manager.database(DB_NAME).unwrap().compile_limits(), limits);
+    assert_eq!(database_manager.database("test_new").unwrap().compile_limits(), limits);
+}
+
+#[test]
+fn salvage_rebuilds_cloned_database_whose_wal_starts_at_first_commit() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+    create_people_databases(&database_manager, &[DB_NAME]);
+    insert_person(database_manager.database(DB_NAME).unwrap()).commit().1.expect("Expected commit");
+    database_manager.clone_database(DB_NAME, "test_clone").expect("Expected database clone");
+
+    // the source's WAL fits in a single file, so the clone copies all of it
+    corrupt_storage_file(&databases_path.join("test_clone"));
+    let verifications = database_manager.salvage_database("test_clone").expect("Expected salvage");
+    assert!(verifications.iter().flat_map(|verification| &verification.corrupt_ranges).all(|range| range.salvaged));
+    assert!(verifications.iter().any(|verification| !verification.corrupt_ranges.is_empty()), "{verifications:?}");
+
+    let clone = database_manager.database("test_clone").expect("Expected salvaged database retrieval");
+    assert_eq!(count_people(clone), 1);
+}
+
+#[test]
+fn salvage_refuses_cloned_database_whose_wal_starts_after_first_commit() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+    create_people_databases(&database_manager, &[DB_NAME]);
+    insert_person(database_manager.database(DB_NAME).unwrap()).commit().1.expect("Expected commit");
+    database_manager.clone_database(DB_NAME, "test_clone").expect("Expected database clone");
+
+    // a clone of a database with a longer WAL only copies the files from its checkpoint onwards
+    let clone_path = databases_path.join("test_clone");
+    let wal_file = std::fs::read_dir(clone_path.join("wal")).unwrap().next().unwrap().unwrap().path();
+    std::fs::rename(&wal_file, wal_file.with_file_name(format!("wal-{:025}", 1000))).unwrap();
+    corrupt_storage_file(&clone_path);
+
+    let salvage_result = database_manager.salvage_database("test_clone");
+    assert!(matches!(salvage_result, Err(DatabaseSalvageError::IncompleteWAL { .. })), "{salvage_result:?}");
+    assert!(database_manager.database("test_clone").is_some());
+    assert!(std::fs::read_dir(clone_path.join("checkpoint")).unwrap().next().is_some());
+}
+
+fn create_people_databases(database_manager: &DatabaseManager, names: &[&str]) {
+    for name in names {
+        database_manager.put_database(*name).expect("Expected database creation");
+        let mut tx_schema = open_schema(database_manager.database(name).unwrap());
+        let snapshot = Arc::get_mut(&mut tx_schema.snapshot).unwrap();
+        tx_schema.type_manager.create_entity_type(snapshot, &Label::build("person", None)).unwrap();
+        tx_schema.commit().1.expect("Expected commit");
+    }
+}
+
+fn insert_person(database: Arc<Database<WALClient>>) -> TransactionWrite<WALClient> {
+    let mut tx_write = open_write(database);
+    let snapshot = Arc::get_mut(&mut tx_write.snapshot).unwrap();
+    let person_type = tx_write.type_manager.get_entity_type(snapshot, &Label::build("person", None)).unwrap().unwrap();
+    tx_write.thing_manager.create_entity(snapshot, person_type).unwrap();
+    tx_write
+}
+
+fn count_people(database: Arc<Database<WALClient>>) -> usize {
+    let tx_read = open_read(database);
+    let count = tx_read.thing_manager.get_entities(tx_read.snapshot(), StorageCounters::DISABLED).count();
+    tx_read.close();
+    count
+}
+
+#[test]
+fn multi_database_commit_applies_no_database_if_one_fails() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+    create_people_databases(&database_manager, &["first", "second"]);
+    let first = database_manager.database("first").unwrap();
+    let second = database_manager.database("second").unwrap();
+
+    // a transaction whose snapshot is still in use fails to prepare its commit
+    let failing = insert_person(second.clone());
+    let snapshot_in_use = failing.snapshot.clone();
+    let result = commit_atomically(database_manager.commit_log(), vec![insert_person(first.clone()), failing]);
+    assert!(matches!(result, Err(MultiDatabaseCommitError::PrepareFailed { name, .. }) if name == "second"));
+    drop(snapshot_in_use);
+    assert_eq!(count_people(first.clone()), 0);
+    assert_eq!(count_people(second.clone()), 0);
+
+    commit_atomically(database_manager.commit_log(), vec![insert_person(first.clone()), insert_person(second.clone())])
+        .expect("Expected commit");
+    assert_eq!(count_people(first), 1);
+    assert_eq!(count_people(second), 1);
+}
+
+#[test]
+fn multi_database_commit_interrupted_after_first_prepare_is_rejected_on_restart() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let names = ["first", "second"];
+    {
+        let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+        create_people_databases(&database_manager, &names);
+
+        // the server stops after the first database durably prepared its commit, but before the second did
+        let databases = names.map(|name| database_manager.database(name).unwrap());
+        let _record = database_manager.commit_log().begin(&databases.each_ref().map(|database| &**database)).unwrap();
+        let (_, prepared) = insert_person(databases[0].clone()).try_prepare_commit();
+        let mut prepared = prepared.expect("Expected prepared commit");
+        prepared.await_durable();
+    }
+    let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+    for name in names {
+        assert_eq!(count_people(database_manager.database(name).unwrap()), 0);
+    }
+}
+
+#[test]
+fn multi_database_commit_interrupted_between_phases_is_resolved_on_restart() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let names = ["first", "second"];
+    {
+        let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+        create_people_databases(&database_manager, &names);
+
+        // the server stops after every database prepared its commit, but before any was applied
+        let databases = names.map(|name| database_manager.database(name).unwrap());
+        let mut record =
+            database_manager.commit_log().begin(&databases.each_ref().map(|database| &**database)).unwrap();
+        let mut prepared = Vec::new();
+        for database in &databases {
+            let (_, prepared_transaction) = insert_person(database.clone()).try_prepare_commit();
+            let mut prepared_transaction = prepared_transaction.expect("Expected prepared commit");
+            prepared_transaction.await_durable();
+            prepared.push(prepared_transaction);
+        }
+        record.committed().unwrap();
+    }
+    let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+    for name in names {
+        assert_eq!(count_people(database_manager.database(name).unwrap()), 1);
+    }
+}
+
 /////////////////////////////
 // SCHEMA TRANSACTION LOCK //
 /////////////////////////////
@@ -193,6 +845,30 @@ fn schema_transaction_blocks_concurrent_schema_transactions() {
     });
 }
 
+#[test]
+fn exclusive_access_timeout_reports_blocking_transaction() {
+    init_logging();
+    let databases_path = create_tmp_dir();
+    let database = create_database(&databases_path);
+    let options = TransactionOptions { schema_lock_acquire_timeout_millis: 10, ..TransactionOptions::default() };
+
+    let tx_schema = open_schema(database.clone());
+    let error = TransactionWrite::open(database.clone(), options.clone()).unwrap_err();
+    assert!(matches!(
+        error,
+        TransactionError::ExclusiveAccessTimeout { timeout_millis: 10, blocking: BlockingTransactionType::Schema }
+    ));
+    tx_schema.close();
+
+    let tx_write = open_write(database.clone());
+    let error = TransactionSchema::open(database.clone(), options).unwrap_err();
+    assert!(matches!(
+        error,
+        TransactionError::ExclusiveAccessTimeout { timeout_millis: 10, blocking: BlockingTransactionType::Write }
+    ));
+    tx_write.close();
+}
+
 #[test]
 fn schema_transaction_blocks_concurrent_write_transactions() {
     init_logging();
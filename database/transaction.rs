@@ -4,29 +4,38 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 use std::{
-    fmt::Formatter,
+    fmt::{self, Formatter},
     ops::Deref,
-    sync::{mpsc::RecvTimeoutError, Arc},
+    sync::Arc,
 };
 
 use concept::{
-    error::ConceptWriteError,
+    error::{ConceptReadError, ConceptWriteError},
     thing::{statistics::StatisticsError, thing_manager::ThingManager},
-    type_::type_manager::{
-        type_cache::{TypeCache, TypeCacheCreateError},
-        TypeManager,
+    type_::{
+        type_manager::{
+            schema_diff::SchemaDiff,
+            type_cache::{TypeCache, TypeCacheCreateError},
+            TypeManager,
+        },
+        HistoryRetention, TypeDeletePolicy,
     },
 };
+use encoding::graph::thing::describe_thing_key;
 use error::typedb_error;
 use function::{function_cache::FunctionCache, function_manager::FunctionManager, FunctionError};
-use options::TransactionOptions;
-use query::query_manager::QueryManager;
-use resource::profile::TransactionProfile;
+use options::{IsolationLevel, TransactionOptions};
+use query::{error::QueryError, query_manager::QueryManager};
+use resource::profile::{CommitProfile, StorageCounters, TransactionProfile};
 use storage::{
     durability_client::DurabilityClient,
+    isolation_manager::IsolationConflict,
+    sequence_number::SequenceNumber,
     snapshot::{CommittableSnapshot, ReadSnapshot, SchemaSnapshot, SnapshotError, WritableSnapshot, WriteSnapshot},
+    PreparedCommit,
 };
 use tracing::Level;
+use typeql::query::SchemaQuery;
 
 use crate::Database;
 
@@ -44,12 +53,16 @@ pub struct TransactionRead<D> {
 
 impl<D: DurabilityClient> TransactionRead<D> {
     pub fn open(database: Arc<Database<D>>, transaction_options: TransactionOptions) -> Result<Self, TransactionError> {
-        // TODO: when we implement constructor `open_at`, to open a transaction in the past by
-        //      time/sequence number, we need to check whether
-        //       the statistics that is available is "too far" ahead of the version we're opening (100-1000?)
-        //          note: this can also be the approximate frequency at which we persist statistics snapshots to the WAL!
-        //       this should be a constant defined in constants.rs
-        //       If it's too far in the future, we should find a more appropriate statistics snapshot from the WAL
+        if let Some(sequence_number) = transaction_options.read_at_sequence_number {
+            if transaction_options.isolation_level == IsolationLevel::ReadCommitted {
+                return Err(TransactionError::ReadCommittedNotSupported {});
+            }
+            return Self::open_at(database, SequenceNumber::new(sequence_number), transaction_options);
+        }
+        Ok(Self::open_latest(database, transaction_options))
+    }
+
+    fn open_latest(database: Arc<Database<D>>, transaction_options: TransactionOptions) -> Self {
         let schema = database.schema.read().unwrap();
         let snapshot: ReadSnapshot<D> = database.storage.clone().open_snapshot_read();
         let type_manager = Arc::new(TypeManager::new(
@@ -66,7 +79,69 @@ impl<D: DurabilityClient> TransactionRead<D> {
             database.definition_key_generator.clone(),
             Some(schema.function_cache.clone()),
         ));
-        let query_manager = Arc::new(QueryManager::new(Some(database.query_cache.clone())));
+        let query_manager = Arc::new(
+            QueryManager::new(Some(database.query_cache.clone())).with_compile_limits(database.compile_limits()),
+        );
+
+        drop(schema);
+
+        Self {
+            snapshot: Arc::new(snapshot),
+            type_manager,
+            thing_manager,
+            function_manager,
+            query_manager,
+            database: DatabaseDropGuard::new(database),
+            transaction_options,
+            profile: TransactionProfile::new(tracing::enabled!(Level::TRACE)),
+        }
+    }
+
+    /// Opens a transaction reading the database as it was after the commit at `sequence_number`,
+    /// which must lie within the database's history retention window.
+    /// The schema caches only describe the latest schema, so the historical schema is read from storage,
+    /// while query planning uses the latest statistics.
+    fn open_at(
+        database: Arc<Database<D>>,
+        sequence_number: SequenceNumber,
+        transaction_options: TransactionOptions,
+    ) -> Result<Self, TransactionError> {
+        let schema = database.schema.read().unwrap();
+        let latest = database.storage.snapshot_watermark();
+        if sequence_number > latest {
+            return Err(TransactionError::ReadSequenceNumberInFuture {
+                sequence_number: sequence_number.number(),
+                latest: latest.number(),
+            });
+        }
+        let latest_type_manager = TypeManager::new(
+            database.definition_key_generator.clone(),
+            database.type_vertex_generator.clone(),
+            Some(schema.type_cache.clone()),
+        );
+        let HistoryRetention(retention) = latest_type_manager
+            .get_history_retention(&database.storage.clone().open_snapshot_read_at(latest))
+            .map_err(|typedb_source| TransactionError::ConceptRead { typedb_source })?;
+        if latest.number() - sequence_number.number() > retention {
+            return Err(TransactionError::ReadSequenceNumberNotRetained {
+                sequence_number: sequence_number.number(),
+                oldest: latest.number().saturating_sub(retention),
+            });
+        }
+
+        let snapshot: ReadSnapshot<D> = database.storage.clone().open_snapshot_read_at(sequence_number);
+        let type_manager = Arc::new(TypeManager::new(
+            database.definition_key_generator.clone(),
+            database.type_vertex_generator.clone(),
+            None,
+        ));
+        let thing_manager = Arc::new(ThingManager::new(
+            database.thing_vertex_generator.clone(),
+            type_manager.clone(),
+            schema.thing_statistics.clone(),
+        ));
+        let function_manager = Arc::new(FunctionManager::new(database.definition_key_generator.clone(), None));
+        let query_manager = Arc::new(QueryManager::new(None).with_compile_limits(database.compile_limits()));
 
         drop(schema);
 
@@ -86,6 +161,22 @@ impl<D: DurabilityClient> TransactionRead<D> {
         &*self.snapshot
     }
 
+    /// Moves a read committed transaction on to the latest commit, so that its next query observes the commits made
+    /// since its previous one. Snapshot isolated transactions keep reading the state they were opened at.
+    /// Queries that are still running keep reading from the snapshot they started with.
+    pub fn advance_snapshot(&mut self) {
+        if self.transaction_options.isolation_level != IsolationLevel::ReadCommitted {
+            return;
+        }
+        let Self { snapshot, type_manager, thing_manager, function_manager, query_manager, .. } =
+            Self::open_latest(self.database.database().clone(), self.transaction_options.clone());
+        self.snapshot = snapshot;
+        self.type_manager = type_manager;
+        self.thing_manager = thing_manager;
+        self.function_manager = function_manager;
+        self.query_manager = query_manager;
+    }
+
     pub fn close(self) {
         drop(self)
     }
@@ -105,6 +196,34 @@ pub struct TransactionWrite<D> {
 
 impl<D: DurabilityClient> TransactionWrite<D> {
     pub fn open(database: Arc<Database<D>>, transaction_options: TransactionOptions) -> Result<Self, TransactionError> {
+        Self::open_with_storage_quota(database, transaction_options, true)
+    }
+
+    /// Opens a write transaction regardless of the storage quota, for internal jobs that free storage.
+    pub fn open_exempt_from_storage_quota(
+        database: Arc<Database<D>>,
+        transaction_options: TransactionOptions,
+    ) -> Result<Self, TransactionError> {
+        Self::open_with_storage_quota(database, transaction_options, false)
+    }
+
+    fn open_with_storage_quota(
+        database: Arc<Database<D>>,
+        transaction_options: TransactionOptions,
+        enforce_storage_quota: bool,
+    ) -> Result<Self, TransactionError> {
+        if transaction_options.read_at_sequence_number.is_some() {
+            return Err(TransactionError::HistoricalWriteNotSupported {});
+        }
+        if transaction_options.isolation_level == IsolationLevel::ReadCommitted {
+            return Err(TransactionError::ReadCommittedNotSupported {});
+        }
+        if database.is_replica() {
+            return Err(TransactionError::DatabaseIsReplica { name: database.name().to_owned() });
+        }
+        if enforce_storage_quota {
+            database.admit_write_transaction()?;
+        }
         database.reserve_write_transaction(transaction_options.schema_lock_acquire_timeout_millis)?;
 
         let schema = database.schema.read().unwrap();
@@ -114,16 +233,23 @@ impl<D: DurabilityClient> TransactionWrite<D> {
             database.type_vertex_generator.clone(),
             Some(schema.type_cache.clone()),
         ));
-        let thing_manager = Arc::new(ThingManager::new(
+        let thing_manager = ThingManager::new(
             database.thing_vertex_generator.clone(),
             type_manager.clone(),
             schema.thing_statistics.clone(),
-        ));
+        );
+        let thing_manager = Arc::new(if transaction_options.defer_validation {
+            thing_manager.with_deferred_validation()
+        } else {
+            thing_manager
+        });
         let function_manager = Arc::new(FunctionManager::new(
             database.definition_key_generator.clone(),
             Some(schema.function_cache.clone()),
         ));
-        let query_manager = Arc::new(QueryManager::new(Some(database.query_cache.clone())));
+        let query_manager = Arc::new(
+            QueryManager::new(Some(database.query_cache.clone())).with_compile_limits(database.compile_limits()),
+        );
         drop(schema);
 
         Ok(Self {
@@ -160,14 +286,32 @@ impl<D: DurabilityClient> TransactionWrite<D> {
         }
     }
 
-    pub fn commit(mut self) -> (TransactionProfile, Result<(), DataCommitError>) {
+    /// Returns the sequence number of the commit, which is the data version it created, unless nothing was written.
+    pub fn commit(mut self) -> (TransactionProfile, Result<Option<SequenceNumber>, DataCommitError>)
+    where
+        D: Send + Sync,
+    {
         self.profile.commit_profile().start();
         let (mut profile, result) = self.try_commit();
         profile.commit_profile().end();
         (profile, result)
     }
 
-    pub fn try_commit(self) -> (TransactionProfile, Result<(), DataCommitError>) {
+    pub fn try_commit(self) -> (TransactionProfile, Result<Option<SequenceNumber>, DataCommitError>)
+    where
+        D: Send + Sync,
+    {
+        let (mut profile, result) = self.try_prepare_commit();
+        let result = result.and_then(|prepared| prepared.apply(profile.commit_profile()));
+        (profile, result)
+    }
+
+    /// Finalises the transaction and prepares its commit without applying it,
+    /// so that it may be committed atomically with transactions against other databases.
+    pub fn try_prepare_commit(self) -> (TransactionProfile, Result<PreparedTransactionWrite<D>, DataCommitError>)
+    where
+        D: Send + Sync,
+    {
         let mut profile = self.profile;
         let commit_profile = profile.commit_profile();
         let mut snapshot = match Arc::try_unwrap(self.snapshot) {
@@ -182,9 +326,9 @@ impl<D: DurabilityClient> TransactionWrite<D> {
         };
         commit_profile.things_finalised();
         drop(self.type_manager);
-        match snapshot.commit(commit_profile) {
-            Ok(_) => (profile, Ok(())),
-            Err(err) => (profile, Err(DataCommitError::SnapshotError { typedb_source: err })),
+        match snapshot.prepare_commit(commit_profile) {
+            Ok(prepared) => (profile, Ok(PreparedTransactionWrite { prepared, database: self.database })),
+            Err(err) => (profile, Err(DataCommitError::from_snapshot_error(err))),
         }
     }
 
@@ -197,15 +341,73 @@ impl<D: DurabilityClient> TransactionWrite<D> {
     }
 }
 
+/// A write transaction whose commit has been durably written and validated, but not yet applied.
+/// It keeps the database's write reservation, so schema transactions cannot open until it is applied or aborted.
+#[derive(Debug)]
+pub struct PreparedTransactionWrite<D: DurabilityClient> {
+    prepared: Option<PreparedCommit<D>>,
+    pub database: DatabaseDropGuard<D>,
+}
+
+impl<D: DurabilityClient> PreparedTransactionWrite<D> {
+    /// The sequence number of the commit, or None if the transaction made no writes.
+    pub fn sequence_number(&self) -> Option<SequenceNumber> {
+        self.prepared.as_ref().map(PreparedCommit::sequence_number)
+    }
+
+    pub fn await_durable(&mut self) {
+        if let Some(prepared) = &mut self.prepared {
+            prepared.await_durable();
+        }
+    }
+
+    pub fn apply(self, commit_profile: &mut CommitProfile) -> Result<Option<SequenceNumber>, DataCommitError> {
+        match self.prepared {
+            None => Ok(None),
+            Some(prepared) => prepared.apply(commit_profile).map(Some).map_err(|typedb_source| {
+                DataCommitError::SnapshotError { typedb_source: SnapshotError::Commit { typedb_source } }
+            }),
+        }
+    }
+
+    pub fn abort(self) -> Result<(), DataCommitError> {
+        match self.prepared {
+            None => Ok(()),
+            Some(prepared) => prepared.abort().map_err(|typedb_source| DataCommitError::SnapshotError {
+                typedb_source: SnapshotError::Commit { typedb_source },
+            }),
+        }
+    }
+}
+
 // TODO: when we use typedb_error!, how do we pring stack trace? If we use the stack trace of each of these, we'll end up with a tree!
 //       If there's 1, we can use the stack trace, otherwise, we should list out all the errors?
 
+impl DataCommitError {
+    fn from_snapshot_error(error: SnapshotError) -> Self {
+        match error.isolation_conflict().and_then(|conflict| describe_thing_key(conflict.key())) {
+            Some(conflicting) => Self::IsolationConflict { conflicting, typedb_source: error },
+            None => Self::SnapshotError { typedb_source: error },
+        }
+    }
+
+    /// The conflict with a concurrent commit which made this commit fail, if any.
+    pub fn isolation_conflict(&self) -> Option<&IsolationConflict> {
+        match self {
+            DataCommitError::IsolationConflict { typedb_source, .. }
+            | DataCommitError::SnapshotError { typedb_source, .. } => typedb_source.isolation_conflict(),
+            _ => None,
+        }
+    }
+}
+
 typedb_error! {
     pub DataCommitError(component = "Data commit", prefix = "DCT") {
         SnapshotInUse(1, "Failed to commit since the transaction snapshot is still in use."),
         ConceptWriteErrors(2, "Data commit error.", write_errors: Vec<ConceptWriteError>),
         ConceptWriteErrorsFirst(3, "Data commit error.", typedb_source: Box<ConceptWriteError>),
         SnapshotError(4, "Snapshot error.", typedb_source: SnapshotError),
+        IsolationConflict(5, "Commit conflicts with a concurrent commit on {conflicting}.", conflicting: String, typedb_source: SnapshotError),
     }
 }
 
@@ -223,6 +425,15 @@ pub struct TransactionSchema<D> {
 
 impl<D: DurabilityClient> TransactionSchema<D> {
     pub fn open(database: Arc<Database<D>>, transaction_options: TransactionOptions) -> Result<Self, TransactionError> {
+        if transaction_options.read_at_sequence_number.is_some() {
+            return Err(TransactionError::HistoricalWriteNotSupported {});
+        }
+        if transaction_options.isolation_level == IsolationLevel::ReadCommitted {
+            return Err(TransactionError::ReadCommittedNotSupported {});
+        }
+        if database.is_replica() {
+            return Err(TransactionError::DatabaseIsReplica { name: database.name().to_owned() });
+        }
         database.reserve_schema_transaction(transaction_options.schema_lock_acquire_timeout_millis)?;
 
         let snapshot: SchemaSnapshot<D> = database.storage.clone().open_snapshot_schema();
@@ -239,8 +450,10 @@ impl<D: DurabilityClient> TransactionSchema<D> {
                 schema.thing_statistics.clone(),
             )
         };
+        let thing_manager =
+            if transaction_options.defer_validation { thing_manager.with_deferred_validation() } else { thing_manager };
         let function_manager = Arc::new(FunctionManager::new(database.definition_key_generator.clone(), None));
-        let query_manager = Arc::new(QueryManager::new(None));
+        let query_manager = Arc::new(QueryManager::new(None).with_compile_limits(database.compile_limits()));
 
         Ok(Self {
             snapshot: Arc::new(snapshot),
@@ -276,14 +489,21 @@ impl<D: DurabilityClient> TransactionSchema<D> {
         }
     }
 
-    pub fn commit(mut self) -> (TransactionProfile, Result<(), SchemaCommitError>) {
+    /// Returns the sequence number of the commit, which is the data version it created, unless nothing was written.
+    pub fn commit(mut self) -> (TransactionProfile, Result<Option<SequenceNumber>, SchemaCommitError>)
+    where
+        D: Send + Sync,
+    {
         self.profile.commit_profile().start();
         let (mut profile, result) = self.try_commit(); // TODO include
         profile.commit_profile().end();
         (profile, result)
     }
 
-    fn try_commit(self) -> (TransactionProfile, Result<(), SchemaCommitError>) {
+    fn try_commit(self) -> (TransactionProfile, Result<Option<SequenceNumber>, SchemaCommitError>)
+    where
+        D: Send + Sync,
+    {
         use SchemaCommitError::{
             ConceptWriteErrorsFirst, FunctionError, SnapshotError, StatisticsError, TypeCacheUpdateError,
         };
@@ -338,6 +558,7 @@ impl<D: DurabilityClient> TransactionSchema<D> {
         }
         commit_profile.schema_update_statistics_durably_written();
 
+        let modified_types = TypeCache::modified_types(&snapshot);
         let sequence_number = match snapshot.commit(commit_profile) {
             Ok(sequence_number) => sequence_number,
             Err(typedb_source) => return (profile, Err(SnapshotError { typedb_source })),
@@ -345,7 +566,13 @@ impl<D: DurabilityClient> TransactionSchema<D> {
 
         // `None` means empty commit
         if let Some(sequence_number) = sequence_number {
-            let type_cache = match TypeCache::new(self.database.storage.clone(), sequence_number) {
+            // only the types affected by the commit are re-read, the rest of the cache is reused
+            let type_cache = match TypeCache::new_incremental(
+                &schema.type_cache,
+                self.database.storage.clone(),
+                sequence_number,
+                &modified_types,
+            ) {
                 Ok(type_cache) => type_cache,
                 Err(typedb_source) => return (profile, Err(TypeCacheUpdateError { typedb_source })),
             };
@@ -375,7 +602,7 @@ impl<D: DurabilityClient> TransactionSchema<D> {
         self.database.query_cache.force_reset(&schema.thing_statistics);
 
         *schema_commit_guard = schema;
-        (profile, Ok(()))
+        (profile, Ok(sequence_number))
     }
 
     pub fn rollback(&mut self) {
@@ -385,6 +612,75 @@ impl<D: DurabilityClient> TransactionSchema<D> {
     pub fn close(self) {
         drop(self)
     }
+
+    /// Runs a schema query on a copy of the writes of this transaction, and validates the result as a commit would,
+    /// including the checks against existing data. This transaction is left unchanged.
+    /// Returns the schema changes the query would make on top of this transaction.
+    pub fn dry_run_schema_query(&self, query: SchemaQuery, source_query: &str) -> Result<SchemaDiff, SchemaDryRunError>
+    where
+        D: Send + Sync,
+    {
+        use SchemaCommitError::{ConceptWriteErrorsFirst, FunctionError};
+
+        // the schema lock held by this transaction keeps the copy consistent with it
+        let mut snapshot = self.database.storage.clone().open_snapshot_schema();
+        *snapshot.operations_mut() = self.snapshot.operations().clone();
+        let type_manager = Arc::new(TypeManager::new(
+            self.database.definition_key_generator.clone(),
+            self.database.type_vertex_generator.clone(),
+            None,
+        ));
+        let thing_manager = {
+            let schema = self.database.schema.read().unwrap();
+            ThingManager::new(
+                self.database.thing_vertex_generator.clone(),
+                type_manager.clone(),
+                schema.thing_statistics.clone(),
+            )
+        };
+        let function_manager = FunctionManager::new(self.database.definition_key_generator.clone(), None);
+        let type_delete_policy = if self.transaction_options.cascade_type_deletion {
+            TypeDeletePolicy::Cascade
+        } else {
+            TypeDeletePolicy::Restrict
+        };
+
+        QueryManager::new(None)
+            .execute_schema_with_policy(
+                &mut snapshot,
+                &type_manager,
+                &thing_manager,
+                &function_manager,
+                query,
+                source_query,
+                type_delete_policy,
+            )
+            .map_err(|typedb_source| SchemaDryRunError::QueryFailed { typedb_source })?;
+
+        let validation_error = |typedb_source| SchemaDryRunError::ValidationFailed { typedb_source };
+        if let Err(errs) = type_manager.validate(&snapshot) {
+            let typedb_source = Box::new(errs.into_iter().next().unwrap());
+            return Err(validation_error(ConceptWriteErrorsFirst { typedb_source }));
+        }
+        if let Err(errs) = thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED) {
+            let typedb_source = Box::new(errs.into_iter().next().unwrap());
+            return Err(validation_error(ConceptWriteErrorsFirst { typedb_source }));
+        }
+        if let Err(typedb_source) = function_manager.finalise(&snapshot, &type_manager) {
+            return Err(validation_error(FunctionError { typedb_source }));
+        }
+
+        let mut schema_diff = self
+            .type_manager
+            .get_schema_diff(self.snapshot.as_ref(), &type_manager, &snapshot)
+            .map_err(|typedb_source| SchemaDryRunError::ConceptRead { typedb_source })?;
+        let function_read_error = |typedb_source| SchemaDryRunError::FunctionRead { typedb_source };
+        let functions =
+            self.function_manager.get_function_definitions(self.snapshot.as_ref()).map_err(function_read_error)?;
+        let dry_run_functions = function_manager.get_function_definitions(&snapshot).map_err(function_read_error)?;
+        schema_diff.add_function_changes(&functions, &dry_run_functions);
+        Ok(schema_diff)
+    }
 }
 
 #[macro_export]
@@ -436,7 +732,7 @@ impl<D> DatabaseDropGuard<D> {
         Self { database: Some(database), on_drop_fn: Some(on_drop_fn) }
     }
 
-    fn database(&self) -> &Arc<Database<D>> {
+    pub fn database(&self) -> &Arc<Database<D>> {
         self.database.as_ref().expect("Expected a database in the guard")
     }
 }
@@ -476,9 +772,45 @@ typedb_error! {
     }
 }
 
+typedb_error! {
+    pub SchemaDryRunError(component = "Schema dry run", prefix = "SDR") {
+        QueryFailed(1, "The schema query failed.", typedb_source: Box<QueryError>),
+        ValidationFailed(2, "The schema query would fail to commit.", typedb_source: SchemaCommitError),
+        ConceptRead(3, "Error reading the schema changes.", typedb_source: Box<ConceptReadError>),
+        FunctionRead(4, "Error reading the function changes.", typedb_source: FunctionError),
+    }
+}
+
+/// The transactions that a write or schema transaction waits for to obtain its exclusive access
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BlockingTransactionType {
+    Schema,
+    Write,
+    QueuedSchema,
+    QueuedWrite,
+}
+
+impl fmt::Display for BlockingTransactionType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockingTransactionType::Schema => write!(f, "an open schema transaction"),
+            BlockingTransactionType::Write => write!(f, "open write transactions"),
+            BlockingTransactionType::QueuedSchema => write!(f, "a schema transaction queued ahead of this one"),
+            BlockingTransactionType::QueuedWrite => write!(f, "a write transaction queued ahead of this one"),
+        }
+    }
+}
+
 typedb_error! {
     pub TransactionError(component = "Transaction", prefix = "TXN") {
-        Timeout(1, "Transaction timeout.", source: RecvTimeoutError),
         WriteExclusivityTimeout(2, "Transaction timeout due to an exclusive write access requested by this or a concurrent transaction."),
+        ReadSequenceNumberInFuture(3, "Cannot read at sequence number {sequence_number}, since the latest commit is {latest}.", sequence_number: u64, latest: u64),
+        ReadSequenceNumberNotRetained(4, "Cannot read at sequence number {sequence_number}, since only history from sequence number {oldest} is retained.", sequence_number: u64, oldest: u64),
+        HistoricalWriteNotSupported(5, "Only read transactions can be opened at a previous sequence number."),
+        ConceptRead(6, "Error reading concepts.", typedb_source: Box<ConceptReadError>),
+        StorageQuotaExceeded(7, "The database uses {usage} bytes of storage, exceeding its quota of {quota} bytes.", usage: u64, quota: u64),
+        DatabaseIsReplica(8, "Database '{name}' is a read-only replica, so only read transactions can be opened.", name: String),
+        ReadCommittedNotSupported(9, "Read committed isolation is only supported by read transactions reading the latest commit."),
+        ExclusiveAccessTimeout(10, "Transaction timeout after {timeout_millis} ms waiting for exclusive access, which is blocked by {blocking}.", timeout_millis: u64, blocking: BlockingTransactionType),
     }
 }
@@ -9,7 +9,7 @@ use std::{collections::HashSet, hash::Hash, sync::Arc};
 use resource::constants::database::INTERNAL_DATABASE_PREFIX;
 
 use crate::{
-    metrics::{ActionKind, ClientEndpoint, DatabaseMetrics, LoadKind},
+    metrics::{ActionKind, BackgroundTaskKind, ClientEndpoint, DatabaseMetrics, LoadKind},
     monitoring_server::MonitoringServer,
     reporter::Reporter,
     Diagnostics,
@@ -68,6 +68,8 @@ impl DiagnosticsManager {
         pub fn submit_action_fail(&self, client: ClientEndpoint, database_name: Option<impl AsRef<str> + Hash>, action_kind: ActionKind);
         pub fn increment_load_count(&self, client: ClientEndpoint, database_name: impl AsRef<str> + Hash, connection_: LoadKind);
         pub fn decrement_load_count(&self, client: ClientEndpoint, database_name: impl AsRef<str> + Hash, connection_: LoadKind);
+        pub fn submit_background_task_progress(&self, database_name: impl AsRef<str> + Hash, task_kind: BackgroundTaskKind, completed: u64, total: u64);
+        pub fn submit_background_task_finished(&self, database_name: impl AsRef<str> + Hash, task_kind: BackgroundTaskKind);
     }
 
     pub async fn may_start_reporting(&self) {
@@ -19,8 +19,8 @@ use xxhash_rust::xxh3::Xxh3;
 
 use crate::{
     metrics::{
-        client_endpoints_map, ActionKind, ActionMetrics, ClientEndpoint, DatabaseMetrics, ErrorMetrics, LoadKind,
-        LoadMetrics, ServerMetrics, ServerProperties, ALL_CLIENT_ENDPOINTS,
+        client_endpoints_map, ActionKind, ActionMetrics, BackgroundTaskKind, BackgroundTaskProgress, ClientEndpoint,
+        DatabaseMetrics, ErrorMetrics, LoadKind, LoadMetrics, ServerMetrics, ServerProperties, ALL_CLIENT_ENDPOINTS,
     },
     reports::{
         json_monitoring::to_monitoring_json,
@@ -45,6 +45,7 @@ pub struct Diagnostics {
     load_metrics: RwLock<HashMap<DatabaseHash, LoadMetrics>>,
     action_metrics: HashMap<ClientEndpoint, RwLock<HashMap<DatabaseHashOpt, ActionMetrics>>>,
     error_metrics: HashMap<ClientEndpoint, RwLock<HashMap<DatabaseHashOpt, ErrorMetrics>>>,
+    background_tasks: RwLock<HashMap<(DatabaseHash, BackgroundTaskKind), BackgroundTaskProgress>>,
 
     is_full_reporting: bool,
 }
@@ -64,6 +65,7 @@ impl Diagnostics {
             load_metrics: RwLock::new(HashMap::new()),
             action_metrics: client_endpoints_map!(RwLock::new(HashMap::new())),
             error_metrics: client_endpoints_map!(RwLock::new(HashMap::new())),
+            background_tasks: RwLock::new(HashMap::new()),
 
             is_full_reporting: is_reporting_enabled,
         }
@@ -142,6 +144,28 @@ impl Diagnostics {
         errors.get(&database_hash).expect("Expected database in errors").submit(error_code);
     }
 
+    /// Background tasks are only reported while running, so monitoring shows their latest progress.
+    pub fn submit_background_task_progress(
+        &self,
+        database_name: impl AsRef<str> + Hash,
+        task_kind: BackgroundTaskKind,
+        completed: u64,
+        total: u64,
+    ) {
+        let database_hash = Self::hash_database(database_name);
+        self.lock_background_tasks_write()
+            .insert((database_hash, task_kind), BackgroundTaskProgress::new(completed, total));
+    }
+
+    pub fn submit_background_task_finished(
+        &self,
+        database_name: impl AsRef<str> + Hash,
+        task_kind: BackgroundTaskKind,
+    ) {
+        let database_hash = Self::hash_database(database_name);
+        self.lock_background_tasks_write().remove(&(database_hash, task_kind));
+    }
+
     pub fn take_snapshot(&self) {
         self.lock_load_metrics_read().values().for_each(|metrics| metrics.take_snapshot());
         for client in ALL_CLIENT_ENDPOINTS {
@@ -173,6 +197,18 @@ impl Diagnostics {
         }
     }
 
+    fn lock_background_tasks_read(
+        &self,
+    ) -> RwLockReadGuard<'_, HashMap<(DatabaseHash, BackgroundTaskKind), BackgroundTaskProgress>> {
+        self.background_tasks.read().expect("Expected read lock acquisition")
+    }
+
+    fn lock_background_tasks_write(
+        &self,
+    ) -> RwLockWriteGuard<'_, HashMap<(DatabaseHash, BackgroundTaskKind), BackgroundTaskProgress>> {
+        self.background_tasks.write().expect("Expected write lock acquisition")
+    }
+
     fn hash_database(database_name: impl AsRef<str> + Hash) -> DatabaseHash {
         hash_string_consistently(database_name)
     }
@@ -21,8 +21,8 @@ use sysinfo::{Disks, MemoryRefreshKind, RefreshKind, System};
 
 use crate::{
     reports::{
-        ActionReport, ConnectionLoadReport, DataLoadReport, ErrorReport, LoadReport, OsReport, SchemaLoadReport,
-        ServerPropertiesReport, ServerReport, ServerReportSensitivePart,
+        ActionReport, BackgroundTaskReport, ConnectionLoadReport, DataLoadReport, DatabaseReport, ErrorReport,
+        LoadReport, OsReport, SchemaLoadReport, ServerPropertiesReport, ServerReport, ServerReportSensitivePart,
     },
     DatabaseHash, DatabaseHashOpt,
 };
@@ -192,6 +192,7 @@ impl LoadMetrics {
                 role_count: 0,
                 storage_in_bytes: 0,
                 storage_key_count: 0,
+                replication_lag: None,
             },
             connection: ConnectionLoadMetrics::new(),
             is_deleted: false,
@@ -272,6 +273,7 @@ pub struct DataLoadMetrics {
     pub role_count: u64,
     pub storage_in_bytes: u64,
     pub storage_key_count: u64,
+    pub replication_lag: Option<u64>,
 }
 
 impl DataLoadMetrics {
@@ -284,6 +286,7 @@ impl DataLoadMetrics {
             role_count: self.role_count,
             storage_in_bytes: self.storage_in_bytes,
             storage_key_count: self.storage_key_count,
+            replication_lag: self.replication_lag,
         }
     }
 }
@@ -529,8 +532,28 @@ impl fmt::Display for ActionKind {
             ActionKind::DatabasesAll => write!(f, "DATABASES_ALL"),
             ActionKind::DatabaseSchema => write!(f, "DATABASES_SCHEMA"),
             ActionKind::DatabaseTypeSchema => write!(f, "DATABASES_TYPE_SCHEMA"),
+            ActionKind::DatabaseSchemaDiff => write!(f, "DATABASES_SCHEMA_DIFF"),
+            ActionKind::DatabaseOptions => write!(f, "DATABASES_OPTIONS"),
+            ActionKind::DatabaseOptionsUpdate => write!(f, "DATABASES_OPTIONS_UPDATE"),
+            ActionKind::DatabaseSettings => write!(f, "DATABASES_SETTINGS"),
+            ActionKind::DatabaseSettingsUpdate => write!(f, "DATABASES_SETTINGS_UPDATE"),
+            ActionKind::DatabaseStorage => write!(f, "DATABASES_STORAGE"),
+            ActionKind::DatabaseStorageQuotaUpdate => write!(f, "DATABASES_STORAGE_QUOTA_UPDATE"),
+            ActionKind::DatabaseStorageVerify => write!(f, "DATABASES_STORAGE_VERIFY"),
+            ActionKind::DatabaseStatistics => write!(f, "DATABASES_STATISTICS"),
+            ActionKind::DatabaseLint => write!(f, "DATABASES_LINT"),
+            ActionKind::DatabaseRelationIndexRebuild => write!(f, "DATABASES_RELATION_INDEX_REBUILD"),
+            ActionKind::DatabaseAttributeCleanup => write!(f, "DATABASES_ATTRIBUTE_CLEANUP"),
+            ActionKind::DatabaseTriggers => write!(f, "DATABASES_TRIGGERS"),
+            ActionKind::DatabaseTriggersUpdate => write!(f, "DATABASES_TRIGGERS_UPDATE"),
+            ActionKind::DatabaseTimeToLiveUpdate => write!(f, "DATABASES_TIME_TO_LIVE_UPDATE"),
+            ActionKind::DatabaseTypeRename => write!(f, "DATABASES_TYPE_RENAME"),
+            ActionKind::DatabaseReplication => write!(f, "DATABASES_REPLICATION"),
+            ActionKind::ClusterCoordination => write!(f, "CLUSTER_COORDINATION"),
+            ActionKind::DatabaseClone => write!(f, "DATABASES_CLONE"),
             ActionKind::DatabaseExport => write!(f, "DATABASES_EXPORT"),
             ActionKind::DatabaseDelete => write!(f, "DATABASES_DELETE"),
+            ActionKind::TransactionsAll => write!(f, "TRANSACTIONS_ALL"),
             ActionKind::TransactionOpen => write!(f, "TRANSACTION_OPEN"),
             ActionKind::TransactionClose => write!(f, "TRANSACTION_CLOSE"),
             ActionKind::TransactionCommit => write!(f, "TRANSACTION_COMMIT"),
@@ -538,6 +561,7 @@ impl fmt::Display for ActionKind {
             ActionKind::TransactionQuery => write!(f, "TRANSACTION_QUERY"),
             ActionKind::TransactionAnalyse => write!(f, "TRANSACTION_ANALYSE"),
             ActionKind::OneshotQuery => write!(f, "ONESHOT_QUERY"),
+            ActionKind::MultiDatabaseTransactionOpen => write!(f, "MULTI_DATABASE_TRANSACTION_OPEN"),
         }
     }
 }
@@ -756,8 +780,28 @@ pub enum ActionKind {
     DatabasesAll,
     DatabaseSchema,
     DatabaseTypeSchema,
+    DatabaseSchemaDiff,
+    DatabaseOptions,
+    DatabaseOptionsUpdate,
+    DatabaseSettings,
+    DatabaseSettingsUpdate,
+    DatabaseStorage,
+    DatabaseStorageQuotaUpdate,
+    DatabaseStorageVerify,
+    DatabaseStatistics,
+    DatabaseLint,
+    DatabaseRelationIndexRebuild,
+    DatabaseAttributeCleanup,
+    DatabaseTriggers,
+    DatabaseTriggersUpdate,
+    DatabaseTimeToLiveUpdate,
+    DatabaseTypeRename,
+    DatabaseReplication,
+    ClusterCoordination,
+    DatabaseClone,
     DatabaseExport,
     DatabaseDelete,
+    TransactionsAll,
     TransactionOpen,
     TransactionClose,
     TransactionCommit,
@@ -765,6 +809,7 @@ pub enum ActionKind {
     TransactionAnalyse,
     TransactionQuery,
     OneshotQuery,
+    MultiDatabaseTransactionOpen,
     // ATTENTION: When adding new Kinds, update all_empty_counts_map()!
 }
 
@@ -788,8 +833,28 @@ impl ActionKind {
             (Self::DatabasesAll, ActionInfo::default()),
             (Self::DatabaseSchema, ActionInfo::default()),
             (Self::DatabaseTypeSchema, ActionInfo::default()),
+            (Self::DatabaseSchemaDiff, ActionInfo::default()),
+            (Self::DatabaseOptions, ActionInfo::default()),
+            (Self::DatabaseOptionsUpdate, ActionInfo::default()),
+            (Self::DatabaseSettings, ActionInfo::default()),
+            (Self::DatabaseSettingsUpdate, ActionInfo::default()),
+            (Self::DatabaseStorage, ActionInfo::default()),
+            (Self::DatabaseStorageQuotaUpdate, ActionInfo::default()),
+            (Self::DatabaseStorageVerify, ActionInfo::default()),
+            (Self::DatabaseStatistics, ActionInfo::default()),
+            (Self::DatabaseLint, ActionInfo::default()),
+            (Self::DatabaseRelationIndexRebuild, ActionInfo::default()),
+            (Self::DatabaseAttributeCleanup, ActionInfo::default()),
+            (Self::DatabaseTriggers, ActionInfo::default()),
+            (Self::DatabaseTriggersUpdate, ActionInfo::default()),
+            (Self::DatabaseTimeToLiveUpdate, ActionInfo::default()),
+            (Self::DatabaseTypeRename, ActionInfo::default()),
+            (Self::DatabaseReplication, ActionInfo::default()),
+            (Self::ClusterCoordination, ActionInfo::default()),
+            (Self::DatabaseClone, ActionInfo::default()),
             (Self::DatabaseExport, ActionInfo::default()),
             (Self::DatabaseDelete, ActionInfo::default()),
+            (Self::TransactionsAll, ActionInfo::default()),
             (Self::TransactionOpen, ActionInfo::default()),
             (Self::TransactionClose, ActionInfo::default()),
             (Self::TransactionCommit, ActionInfo::default()),
@@ -797,6 +862,7 @@ impl ActionKind {
             (Self::TransactionQuery, ActionInfo::default()),
             (Self::TransactionAnalyse, ActionInfo::default()),
             (Self::OneshotQuery, ActionInfo::default()),
+            (Self::MultiDatabaseTransactionOpen, ActionInfo::default()),
         ])
     }
 
@@ -819,8 +885,28 @@ impl ActionKind {
             ActionKind::DatabasesAll => "database_alls",
             ActionKind::DatabaseSchema => "database_schemas",
             ActionKind::DatabaseTypeSchema => "database_type_schemas",
+            ActionKind::DatabaseSchemaDiff => "database_schema_diffs",
+            ActionKind::DatabaseOptions => "database_optionses",
+            ActionKind::DatabaseOptionsUpdate => "database_options_updates",
+            ActionKind::DatabaseSettings => "database_settingses",
+            ActionKind::DatabaseSettingsUpdate => "database_settings_updates",
+            ActionKind::DatabaseStorage => "database_storage",
+            ActionKind::DatabaseStorageQuotaUpdate => "database_storage_quota_updates",
+            ActionKind::DatabaseStorageVerify => "database_storage_verifications",
+            ActionKind::DatabaseStatistics => "database_statistics",
+            ActionKind::DatabaseLint => "database_lints",
+            ActionKind::DatabaseRelationIndexRebuild => "database_relation_index_rebuilds",
+            ActionKind::DatabaseAttributeCleanup => "database_attribute_cleanups",
+            ActionKind::DatabaseTriggers => "database_triggerses",
+            ActionKind::DatabaseTriggersUpdate => "database_triggers_updates",
+            ActionKind::DatabaseTimeToLiveUpdate => "database_time_to_live_updates",
+            ActionKind::DatabaseTypeRename => "database_type_renames",
+            ActionKind::DatabaseReplication => "database_replications",
+            ActionKind::ClusterCoordination => "cluster_coordinations",
+            ActionKind::DatabaseClone => "database_clones",
             ActionKind::DatabaseExport => "database_exports",
             ActionKind::DatabaseDelete => "databases_deletes",
+            ActionKind::TransactionsAll => "transaction_alls",
             ActionKind::TransactionOpen => "transaction_opens",
             ActionKind::TransactionClose => "transaction_closes",
             ActionKind::TransactionCommit => "transaction_commits",
@@ -828,6 +914,7 @@ impl ActionKind {
             ActionKind::TransactionQuery => "transaction_queries",
             ActionKind::TransactionAnalyse => "transaction_analyses",
             ActionKind::OneshotQuery => "oneshot_queries",
+            ActionKind::MultiDatabaseTransactionOpen => "multi_database_transaction_opens",
         }
     }
 
@@ -839,6 +926,49 @@ impl ActionKind {
     }
 }
 
+#[derive(Serialize, Debug, Hash, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BackgroundTaskKind {
+    RelationIndexRebuild,
+    OrphanedAttributeCleanup,
+    ExpiredInstanceCleanup,
+}
+
+impl fmt::Display for BackgroundTaskKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackgroundTaskKind::RelationIndexRebuild => write!(f, "RELATION_INDEX_REBUILD"),
+            BackgroundTaskKind::OrphanedAttributeCleanup => write!(f, "ORPHANED_ATTRIBUTE_CLEANUP"),
+            BackgroundTaskKind::ExpiredInstanceCleanup => write!(f, "EXPIRED_INSTANCE_CLEANUP"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub(crate) struct BackgroundTaskProgress {
+    completed: u64,
+    total: u64,
+}
+
+impl BackgroundTaskProgress {
+    pub(crate) fn new(completed: u64, total: u64) -> Self {
+        Self { completed, total }
+    }
+
+    pub(crate) fn to_state_report(
+        &self,
+        database_hash: DatabaseHash,
+        task_kind: BackgroundTaskKind,
+    ) -> BackgroundTaskReport {
+        BackgroundTaskReport {
+            database: DatabaseReport(database_hash),
+            kind: task_kind,
+            completed: self.completed,
+            total: self.total,
+        }
+    }
+}
+
 fn get_delta(lhs: u64, rhs: u64) -> i64 {
     if lhs > rhs {
         (lhs - rhs) as i64
@@ -10,10 +10,10 @@ use serde::Serialize;
 use serde_json::{json, Value};
 
 use crate::{
-    metrics::{ActionKind, ALL_CLIENT_ENDPOINTS},
+    metrics::{ActionKind, BackgroundTaskKind, ALL_CLIENT_ENDPOINTS},
     reports::{
-        serialize_timestamp, ActionReport, DataLoadReport, DatabaseReport, ErrorReport, LoadReport, OsReport,
-        SchemaLoadReport, ServerPropertiesReport, ServerReport, ServerReportSensitivePart,
+        serialize_timestamp, ActionReport, BackgroundTaskReport, DataLoadReport, DatabaseReport, ErrorReport,
+        LoadReport, OsReport, SchemaLoadReport, ServerPropertiesReport, ServerReport, ServerReportSensitivePart,
     },
     Diagnostics,
 };
@@ -30,6 +30,7 @@ pub(crate) struct JsonMonitoringReport {
     pub load: Vec<JsonMonitoringLoadReport>,
     pub actions: Vec<JsonMonitoringActionReport>,
     pub errors: Vec<JsonMonitoringErrorReport>,
+    pub background_tasks: Vec<JsonMonitoringBackgroundTaskReport>,
 }
 
 #[derive(Debug, Serialize)]
@@ -153,6 +154,8 @@ pub(crate) struct JsonMonitoringDataLoadReport {
     pub role_count: u64,
     pub storage_in_bytes: u64,
     pub storage_key_count: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replication_lag: Option<u64>,
 }
 
 impl From<DataLoadReport> for JsonMonitoringDataLoadReport {
@@ -165,6 +168,7 @@ impl From<DataLoadReport> for JsonMonitoringDataLoadReport {
             role_count: value.role_count,
             storage_in_bytes: value.storage_in_bytes,
             storage_key_count: value.storage_key_count,
+            replication_lag: value.replication_lag,
         }
     }
 }
@@ -209,6 +213,24 @@ impl From<ErrorReport> for JsonMonitoringErrorReport {
     }
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonMonitoringBackgroundTaskReport {
+    pub name: BackgroundTaskKind,
+
+    #[serde(flatten)]
+    pub database: DatabaseReport,
+
+    pub completed: u64,
+    pub total: u64,
+}
+
+impl From<BackgroundTaskReport> for JsonMonitoringBackgroundTaskReport {
+    fn from(value: BackgroundTaskReport) -> Self {
+        Self { name: value.kind, database: value.database, completed: value.completed, total: value.total }
+    }
+}
+
 pub(crate) struct JsonMonitoringActionReportsBuilder {
     reports: HashMap<Option<DatabaseReport>, HashMap<ActionKind, JsonMonitoringActionReport>>,
 }
@@ -295,11 +317,18 @@ pub(crate) fn to_monitoring_report(diagnostics: &Diagnostics) -> JsonMonitoringR
         }
     }
 
+    let background_tasks = diagnostics
+        .lock_background_tasks_read()
+        .iter()
+        .map(|(&(database_hash, task_kind), progress)| progress.to_state_report(database_hash, task_kind).into())
+        .collect();
+
     JsonMonitoringReport {
         server_properties,
         server,
         load,
         actions: actions_builder.build(),
         errors: errors_builder.build(),
+        background_tasks,
     }
 }
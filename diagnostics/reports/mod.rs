@@ -11,7 +11,7 @@ use serde::{ser::SerializeStruct, Serialize, Serializer};
 use serde_json::{to_value, Map, Value};
 
 use crate::{
-    metrics::{ActionKind, ClientEndpoint, LoadKind},
+    metrics::{ActionKind, BackgroundTaskKind, ClientEndpoint, LoadKind},
     DatabaseHash,
 };
 
@@ -125,6 +125,7 @@ pub(crate) struct DataLoadReport {
     pub role_count: u64,
     pub storage_in_bytes: u64,
     pub storage_key_count: u64,
+    pub replication_lag: Option<u64>,
 }
 
 pub type ConnectionLoadReport = HashMap<ClientEndpoint, HashMap<LoadKind, u64>>;
@@ -143,3 +144,11 @@ pub(crate) struct ErrorReport {
     pub code: String,
     pub count: i64,
 }
+
+#[derive(Debug)]
+pub(crate) struct BackgroundTaskReport {
+    pub database: DatabaseReport,
+    pub kind: BackgroundTaskKind,
+    pub completed: u64,
+    pub total: u64,
+}
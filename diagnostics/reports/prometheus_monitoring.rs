@@ -92,6 +92,14 @@ pub fn to_prometheus(report: JsonMonitoringReport) -> String {
         }
     }
 
+    writeln!(out, "\n# TYPE typedb_replication_lag gauge").unwrap();
+    for db in &report.load {
+        if let Some(replication_lag) = db.data.as_ref().and_then(|data| data.replication_lag) {
+            writeln!(out, "typedb_replication_lag{{database=\"{}\"}} {}", db.database.as_str(), replication_lag)
+                .unwrap();
+        }
+    }
+
     writeln!(out, "\n# TYPE typedb_attempted_requests_total counter").unwrap();
     for action in &report.actions {
         if let Some(db) = &action.database {
@@ -128,5 +136,21 @@ pub fn to_prometheus(report: JsonMonitoringReport) -> String {
         }
     }
 
+    writeln!(out, "\n# TYPE typedb_background_task_progress gauge").unwrap();
+    for task in &report.background_tasks {
+        writeln!(
+            out,
+            "typedb_background_task_progress{{database=\"{}\", kind=\"{}\", progress=\"completed\"}} {}",
+            task.database.0, task.name, task.completed
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "typedb_background_task_progress{{database=\"{}\", kind=\"{}\", progress=\"total\"}} {}",
+            task.database.0, task.name, task.total
+        )
+        .unwrap();
+    }
+
     out
 }
@@ -19,6 +19,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::wal::WALError;
 
+pub mod encryption;
 pub mod wal;
 
 pub trait DurabilityService {
@@ -48,6 +49,9 @@ pub trait DurabilityService {
         record_type: DurabilityRecordType,
     ) -> Result<Option<RawRecord<'static>>, DurabilityServiceError>;
 
+    /// Removes every record from `sequence_number` onwards, so that the next sequenced record is written at it.
+    fn truncate_from(&self, sequence_number: DurabilitySequenceNumber) -> Result<(), DurabilityServiceError>;
+
     fn delete_durability(self) -> Result<(), DurabilityServiceError>;
 
     fn reset(&mut self) -> Result<(), DurabilityServiceError>;
@@ -0,0 +1,153 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{error::Error, fmt, sync::Arc};
+
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    error::Unspecified,
+    rand::{SecureRandom, SystemRandom},
+};
+
+const KEY_LENGTH: usize = 32;
+
+/// A 256-bit AES key.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionKey([u8; KEY_LENGTH]);
+
+impl EncryptionKey {
+    pub fn new(bytes: [u8; KEY_LENGTH]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn generate() -> Self {
+        let mut bytes = [0; KEY_LENGTH];
+        SystemRandom::new().fill(&mut bytes).expect("Expected the system random number generator to be available");
+        Self(bytes)
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, KeyManagementError> {
+        let hex = hex.trim();
+        if hex.len() != KEY_LENGTH * 2 || !hex.is_ascii() {
+            return Err(KeyManagementError::InvalidKey { expected_bytes: KEY_LENGTH });
+        }
+        let mut bytes = [0; KEY_LENGTH];
+        for (byte, digits) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            *byte = u8::from_str_radix(std::str::from_utf8(digits).unwrap(), 16)
+                .map_err(|_| KeyManagementError::InvalidKey { expected_bytes: KEY_LENGTH })?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EncryptionKey[redacted]")
+    }
+}
+
+/// Wraps and unwraps the data keys that each encrypted database stores next to its data, so that only wrapped keys
+/// are ever written to disk. Implement this to delegate to an external key management service.
+pub trait KeyManagementService: fmt::Debug + Send + Sync {
+    fn wrap_key(&self, key: &EncryptionKey) -> Result<Vec<u8>, KeyManagementError>;
+
+    fn unwrap_key(&self, wrapped_key: &[u8]) -> Result<EncryptionKey, KeyManagementError>;
+}
+
+/// Wraps data keys with a single master key held by the server, e.g. read from its configuration.
+#[derive(Debug)]
+pub struct MasterKeyManagement {
+    cipher: AuthenticatedCipher,
+}
+
+impl MasterKeyManagement {
+    const WRAPPED_KEY_AAD: &'static [u8] = b"typedb-data-key";
+
+    pub fn new(master_key: &EncryptionKey) -> Self {
+        Self { cipher: AuthenticatedCipher::new(master_key) }
+    }
+}
+
+impl KeyManagementService for MasterKeyManagement {
+    fn wrap_key(&self, key: &EncryptionKey) -> Result<Vec<u8>, KeyManagementError> {
+        self.cipher.encrypt(Self::WRAPPED_KEY_AAD, &key.0).map_err(|_| KeyManagementError::Wrap {})
+    }
+
+    fn unwrap_key(&self, wrapped_key: &[u8]) -> Result<EncryptionKey, KeyManagementError> {
+        let bytes =
+            self.cipher.decrypt(Self::WRAPPED_KEY_AAD, wrapped_key).map_err(|_| KeyManagementError::Unwrap {})?;
+        let bytes = bytes.try_into().map_err(|_| KeyManagementError::Unwrap {})?;
+        Ok(EncryptionKey(bytes))
+    }
+}
+
+/// AES-256-GCM with a random nonce per message, stored in front of the ciphertext.
+#[derive(Clone)]
+pub(crate) struct AuthenticatedCipher {
+    key: Arc<LessSafeKey>,
+    random: SystemRandom,
+}
+
+impl AuthenticatedCipher {
+    pub(crate) fn new(key: &EncryptionKey) -> Self {
+        let key = UnboundKey::new(&AES_256_GCM, &key.0).expect("Expected a key of the AES-256 length");
+        Self { key: Arc::new(LessSafeKey::new(key)), random: SystemRandom::new() }
+    }
+
+    pub(crate) fn encrypt(&self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, Unspecified> {
+        let mut nonce = [0; NONCE_LEN];
+        self.random.fill(&mut nonce)?;
+        let mut bytes = Vec::with_capacity(NONCE_LEN + plaintext.len() + AES_256_GCM.tag_len());
+        bytes.extend_from_slice(&nonce);
+        let mut ciphertext = plaintext.to_vec();
+        self.key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(aad), &mut ciphertext)?;
+        bytes.extend_from_slice(&ciphertext);
+        Ok(bytes)
+    }
+
+    pub(crate) fn decrypt(&self, aad: &[u8], bytes: &[u8]) -> Result<Vec<u8>, Unspecified> {
+        if bytes.len() < NONCE_LEN {
+            return Err(Unspecified);
+        }
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)?;
+        let mut plaintext = ciphertext.to_vec();
+        let length = self.key.open_in_place(nonce, Aad::from(aad), &mut plaintext)?.len();
+        plaintext.truncate(length);
+        Ok(plaintext)
+    }
+}
+
+impl fmt::Debug for AuthenticatedCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AuthenticatedCipher[AES-256-GCM]")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum KeyManagementError {
+    InvalidKey { expected_bytes: usize },
+    Wrap {},
+    Unwrap {},
+    Service { message: String },
+}
+
+impl fmt::Display for KeyManagementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        error::todo_display_for_error!(f, self)
+    }
+}
+
+impl Error for KeyManagementError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidKey { .. } => None,
+            Self::Wrap { .. } => None,
+            Self::Unwrap { .. } => None,
+            Self::Service { .. } => None,
+        }
+    }
+}
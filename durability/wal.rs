@@ -28,7 +28,10 @@ use logger::result::ResultExt;
 use resource::constants::storage::WAL_SYNC_INTERVAL_MICROSECONDS;
 use tracing::warn;
 
-use crate::{DurabilityRecordType, DurabilitySequenceNumber, DurabilityService, DurabilityServiceError, RawRecord};
+use crate::{
+    encryption::{AuthenticatedCipher, EncryptionKey, KeyManagementError, KeyManagementService},
+    DurabilityRecordType, DurabilitySequenceNumber, DurabilityService, DurabilityServiceError, RawRecord,
+};
 
 const MAX_WAL_FILE_SIZE: u64 = 16 * 1024 * 1024;
 
@@ -44,8 +47,18 @@ pub struct WAL {
 
 impl WAL {
     pub const WAL_DIR_NAME: &'static str = "wal";
+    const DATA_KEY_FILE_NAME: &'static str = "DATA_KEY";
 
     pub fn create(directory: impl AsRef<Path>) -> Result<Self, DurabilityServiceError> {
+        Self::create_with_key_management(directory, None)
+    }
+
+    /// Creates a WAL whose records are encrypted with a fresh data key when given a key management service.
+    /// Only the data key wrapped by the key management service is stored, next to the WAL files.
+    pub fn create_with_key_management(
+        directory: impl AsRef<Path>,
+        key_management: Option<&dyn KeyManagementService>,
+    ) -> Result<Self, DurabilityServiceError> {
         let directory = directory.as_ref().to_owned();
         let wal_dir = directory.join(Self::WAL_DIR_NAME);
         if wal_dir.exists() {
@@ -54,7 +67,17 @@ impl WAL {
             fs::create_dir_all(wal_dir.clone()).map_err(|err| WALError::CreateError { source: Arc::new(err) })?;
         }
 
-        let files = Files::open(wal_dir.clone())?;
+        let encryption = match key_management {
+            None => None,
+            Some(key_management) => {
+                let key = EncryptionKey::generate();
+                let wrapped_key = key_management.wrap_key(&key).map_err(|source| WALError::KeyManagement { source })?;
+                let encryption = RecordEncryption { cipher: AuthenticatedCipher::new(&key), wrapped_key };
+                encryption.write_wrapped_key(&wal_dir)?;
+                Some(encryption)
+            }
+        };
+        let files = Files::open(wal_dir.clone(), encryption)?;
 
         let files = Arc::new(RwLock::new(files));
         let next = RecordIterator::new(files.read().unwrap(), DurabilitySequenceNumber::MIN)?
@@ -72,12 +95,21 @@ impl WAL {
     }
 
     pub fn load(directory: impl AsRef<Path>) -> Result<Self, DurabilityServiceError> {
+        Self::load_with_key_management(directory, None)
+    }
+
+    /// Loads a WAL, unwrapping its data key with the key management service if its records are encrypted.
+    pub fn load_with_key_management(
+        directory: impl AsRef<Path>,
+        key_management: Option<&dyn KeyManagementService>,
+    ) -> Result<Self, DurabilityServiceError> {
         let directory = directory.as_ref().to_owned();
         let wal_dir = directory.join(Self::WAL_DIR_NAME);
         if !wal_dir.exists() {
             Err(WALError::LoadErrorDirectoryMissing { directory: wal_dir.clone() })?
         }
-        let files = Files::open(wal_dir.clone())?;
+        let encryption = RecordEncryption::may_read_wrapped_key(&wal_dir, key_management)?;
+        let files = Files::open(wal_dir.clone(), encryption)?;
 
         let start_seq_nr = files.files.iter().map(|f| f.start).max().unwrap_or(DurabilitySequenceNumber::MIN);
 
@@ -97,6 +129,25 @@ impl WAL {
         })
     }
 
+    /// The paths of the WAL files in the directory that hold the records from `start` onwards, oldest first.
+    pub fn file_paths_from(directory: impl AsRef<Path>, start: DurabilitySequenceNumber) -> io::Result<Vec<PathBuf>> {
+        let files = Files::list(&directory.as_ref().join(Self::WAL_DIR_NAME))?;
+        let first = files.iter().rposition(|file| file.start <= start).unwrap_or(0);
+        Ok(files.into_iter().skip(first).map(|file| file.path).collect())
+    }
+
+    /// The wrapped data key stored with an encrypted WAL, which a copy of its files needs to be read.
+    pub fn data_key_path(directory: impl AsRef<Path>) -> Option<PathBuf> {
+        let path = directory.as_ref().join(Self::WAL_DIR_NAME).join(Self::DATA_KEY_FILE_NAME);
+        path.exists().then_some(path)
+    }
+
+    /// The sequence number the oldest WAL file in the directory starts at, if the WAL has any files.
+    pub fn first_sequence_number(directory: impl AsRef<Path>) -> io::Result<Option<DurabilitySequenceNumber>> {
+        let files = Files::list(&directory.as_ref().join(Self::WAL_DIR_NAME))?;
+        Ok(files.first().map(|file| file.start))
+    }
+
     fn increment(&self) -> DurabilitySequenceNumber {
         DurabilitySequenceNumber::from(self.next_sequence_number.fetch_add(1, Ordering::Relaxed))
     }
@@ -112,6 +163,49 @@ impl WAL {
     pub fn request_sync(&self, ack_waits_for_sync: bool) -> mpsc::Receiver<()> {
         self.fsync_thread.schedule_next_sync_may_subscribe(ack_waits_for_sync)
     }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.files.read().unwrap().encryption.is_some()
+    }
+}
+
+#[derive(Debug)]
+struct RecordEncryption {
+    cipher: AuthenticatedCipher,
+    wrapped_key: Vec<u8>,
+}
+
+impl RecordEncryption {
+    fn write_wrapped_key(&self, wal_dir: &Path) -> Result<(), WALError> {
+        let path = wal_dir.join(WAL::DATA_KEY_FILE_NAME);
+        StdFile::create(&path)
+            .and_then(|mut file| file.write_all(&self.wrapped_key).and_then(|()| file.sync_all()))
+            .map_err(|err| WALError::DataKeyIO { source: Arc::new(err) })
+    }
+
+    fn may_read_wrapped_key(
+        wal_dir: &Path,
+        key_management: Option<&dyn KeyManagementService>,
+    ) -> Result<Option<Self>, WALError> {
+        let path = wal_dir.join(WAL::DATA_KEY_FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let Some(key_management) = key_management else {
+            return Err(WALError::KeyManagementMissing { directory: wal_dir.to_owned() });
+        };
+        let wrapped_key = fs::read(&path).map_err(|err| WALError::DataKeyIO { source: Arc::new(err) })?;
+        let key = key_management.unwrap_key(&wrapped_key).map_err(|source| WALError::KeyManagement { source })?;
+        Ok(Some(Self { cipher: AuthenticatedCipher::new(&key), wrapped_key }))
+    }
+
+    // binds each record to its position in the log, so records cannot be swapped or replayed undetected
+    fn associated_data(sequence_number: DurabilitySequenceNumber, record_type: DurabilityRecordType) -> [u8; 9] {
+        let mut associated_data = [0; 9];
+        associated_data[..8].copy_from_slice(&sequence_number.to_be_bytes());
+        associated_data[8] = record_type;
+        associated_data
+    }
 }
 
 impl DurabilityService for WAL {
@@ -170,7 +264,7 @@ impl DurabilityService for WAL {
         let files = self.files.read().unwrap();
         let files_newest_first = files.iter().rev();
         for file in files_newest_first {
-            let iterator = FileRecordIterator::new(file, DurabilitySequenceNumber::MIN)?;
+            let iterator = FileRecordIterator::new(file, files.cipher(), DurabilitySequenceNumber::MIN)?;
 
             let mut found_record = None;
             for record_result in iterator {
@@ -187,6 +281,13 @@ impl DurabilityService for WAL {
         Ok(None)
     }
 
+    fn truncate_from(&self, sequence_number: DurabilitySequenceNumber) -> Result<(), DurabilityServiceError> {
+        let mut files = self.files.write().unwrap();
+        files.truncate_from(sequence_number)?;
+        self.next_sequence_number.store(sequence_number.number(), Ordering::SeqCst);
+        Ok(())
+    }
+
     fn delete_durability(self) -> Result<(), DurabilityServiceError> {
         drop(self.fsync_thread);
         let files = Arc::into_inner(self.files)
@@ -210,6 +311,11 @@ pub enum WALError {
     LoadErrorDirectoryMissing { directory: PathBuf },
     Compression { source: Arc<io::Error> },
     Decompression { source: Arc<io::Error> },
+    KeyManagementMissing { directory: PathBuf },
+    KeyManagement { source: KeyManagementError },
+    DataKeyIO { source: Arc<io::Error> },
+    Encryption { sequence_number: DurabilitySequenceNumber },
+    Decryption { sequence_number: DurabilitySequenceNumber },
 }
 
 impl fmt::Display for WALError {
@@ -227,6 +333,11 @@ impl Error for WALError {
             Self::LoadErrorDirectoryMissing { .. } => None,
             Self::Compression { source, .. } => Some(source),
             Self::Decompression { source, .. } => Some(source),
+            Self::KeyManagementMissing { .. } => None,
+            Self::KeyManagement { source, .. } => Some(source),
+            Self::DataKeyIO { source, .. } => Some(source),
+            Self::Encryption { .. } => None,
+            Self::Decryption { .. } => None,
         }
     }
 }
@@ -236,15 +347,21 @@ struct Files {
     directory: PathBuf,
     writer: Option<BufWriter<StdFile>>,
     files: Vec<File>,
+    encryption: Option<RecordEncryption>,
 }
 
 impl Files {
-    fn open(directory: PathBuf) -> Result<Self, DurabilityServiceError> {
-        let (files, writer) = Self::init_files_writer(&directory)?;
-        Ok(Self { directory, writer, files })
+    fn open(directory: PathBuf, encryption: Option<RecordEncryption>) -> Result<Self, DurabilityServiceError> {
+        let cipher = encryption.as_ref().map(|encryption| &encryption.cipher);
+        let (files, writer) = Self::init_files_writer(&directory, cipher)?;
+        Ok(Self { directory, writer, files, encryption })
     }
 
-    fn init_files_writer(directory: &Path) -> Result<(Vec<File>, Option<BufWriter<StdFile>>), DurabilityServiceError> {
+    fn cipher(&self) -> Option<&AuthenticatedCipher> {
+        self.encryption.as_ref().map(|encryption| &encryption.cipher)
+    }
+
+    fn list(directory: &Path) -> io::Result<Vec<File>> {
         let mut files: Vec<File> = directory
             .read_dir()?
             .map_ok(|entry| entry.path())
@@ -254,10 +371,18 @@ impl Files {
             .map(|path| File::open(path?))
             .try_collect()?;
         files.sort_unstable_by(|lhs, rhs| lhs.path.cmp(&rhs.path));
+        Ok(files)
+    }
+
+    fn init_files_writer(
+        directory: &Path,
+        cipher: Option<&AuthenticatedCipher>,
+    ) -> Result<(Vec<File>, Option<BufWriter<StdFile>>), DurabilityServiceError> {
+        let mut files = Self::list(directory)?;
 
         let last = files.last_mut();
         let writer = if let Some(last) = last {
-            last.trim_corrupted_tail()?;
+            last.trim_corrupted_tail(cipher)?;
             Some(File::writer(last)?)
         } else {
             None
@@ -283,6 +408,13 @@ impl Files {
             .map_err(|err| WALError::Compression { source: Arc::new(err) })?;
         encoder.write_all(&record.bytes).map_err(|err| WALError::Compression { source: Arc::new(err) })?;
         encoder.finish().1.map_err(|err| WALError::Compression { source: Arc::new(err) })?;
+        if let Some(encryption) = &self.encryption {
+            let associated_data = RecordEncryption::associated_data(record.sequence_number, record.record_type);
+            compressed_bytes = encryption
+                .cipher
+                .encrypt(&associated_data, &compressed_bytes)
+                .map_err(|_| WALError::Encryption { sequence_number: record.sequence_number })?;
+        }
 
         let writer = self.writer.as_mut().unwrap();
         write_header(
@@ -314,11 +446,30 @@ impl Files {
         std::fs::remove_dir_all(&self.directory)
     }
 
+    fn truncate_from(&mut self, sequence_number: DurabilitySequenceNumber) -> Result<(), DurabilityServiceError> {
+        self.writer = None;
+        while self.files.last().is_some_and(|file| file.start >= sequence_number) {
+            fs::remove_file(&self.files.pop().unwrap().path)?;
+        }
+        match self.files.last_mut() {
+            Some(last) => {
+                last.truncate_from(sequence_number)?;
+                self.writer = Some(last.writer()?);
+            }
+            // the fsync thread expects a file to sync
+            None => self.open_new_file_at(sequence_number)?,
+        }
+        Ok(())
+    }
+
     fn reset(&mut self) -> Result<(), DurabilityServiceError> {
         std::fs::remove_dir_all(&self.directory)?;
         std::fs::create_dir(&self.directory)?;
+        if let Some(encryption) = &self.encryption {
+            encryption.write_wrapped_key(&self.directory)?;
+        }
         self.files.clear();
-        let (files, writer) = Self::init_files_writer(&self.directory)?;
+        let (files, writer) = Self::init_files_writer(&self.directory, self.cipher())?;
         self.files = files;
         self.writer = writer;
         Ok(())
@@ -357,8 +508,8 @@ impl File {
         Ok(Self { start: DurabilitySequenceNumber::from(num), len, path })
     }
 
-    fn trim_corrupted_tail(&mut self) -> Result<(), DurabilityServiceError> {
-        let mut reader = FileReader::new(self.clone())?;
+    fn trim_corrupted_tail(&mut self, cipher: Option<&AuthenticatedCipher>) -> Result<(), DurabilityServiceError> {
+        let mut reader = FileReader::new(self.clone(), cipher)?;
         let mut last_successful_read_pos = 0;
         while let Some(record) = reader.read_one_record().transpose() {
             if record.as_ref().is_ok_and(|record| !record.bytes.is_empty()) {
@@ -381,6 +532,24 @@ impl File {
         Ok(())
     }
 
+    fn truncate_from(&mut self, sequence_number: DurabilitySequenceNumber) -> Result<(), DurabilityServiceError> {
+        // records are skipped by their headers, so their contents need not be decrypted
+        let mut reader = FileReader::new(self.clone(), None)?;
+        let mut truncated_len = 0;
+        while reader
+            .peek_sequence_number()?
+            .is_some_and(|record_sequence_number| record_sequence_number < sequence_number)
+        {
+            reader.skip_one_record()?;
+            truncated_len = reader.reader.stream_position()?;
+        }
+        let file = OpenOptions::new().write(true).open(&self.path)?;
+        file.set_len(truncated_len)?;
+        file.sync_all()?;
+        self.len = truncated_len;
+        Ok(())
+    }
+
     fn writer(&self) -> io::Result<BufWriter<StdFile>> {
         Ok(BufWriter::new(OpenOptions::new().read(true).append(true).create(true).open(&self.path)?))
     }
@@ -390,11 +559,12 @@ impl File {
 struct FileReader {
     file: File,
     reader: BufReader<StdFile>,
+    cipher: Option<AuthenticatedCipher>,
 }
 
 impl FileReader {
-    fn new(file: File) -> io::Result<Self> {
-        Ok(Self { reader: BufReader::new(StdFile::open(&file.path)?), file })
+    fn new(file: File, cipher: Option<&AuthenticatedCipher>) -> io::Result<Self> {
+        Ok(Self { reader: BufReader::new(StdFile::open(&file.path)?), file, cipher: cipher.cloned() })
     }
 
     fn peek_sequence_number(&mut self) -> io::Result<Option<DurabilitySequenceNumber>> {
@@ -423,9 +593,25 @@ impl FileReader {
         let RecordHeader { sequence_number, len, record_type } = self.read_header()?;
 
         let mut decompressed_bytes = Vec::new();
-        lz4::Decoder::new((&mut self.reader).take(len))
-            .and_then(|mut decoder| decoder.read_to_end(&mut decompressed_bytes))
-            .map_err(|err| WALError::Decompression { source: Arc::new(err) })?;
+        match &self.cipher {
+            None => lz4::Decoder::new((&mut self.reader).take(len))
+                .and_then(|mut decoder| decoder.read_to_end(&mut decompressed_bytes))
+                .map_err(|err| WALError::Decompression { source: Arc::new(err) })?,
+            Some(cipher) => {
+                let mut encrypted_bytes = Vec::with_capacity(len as usize);
+                (&mut self.reader).take(len).read_to_end(&mut encrypted_bytes)?;
+                if encrypted_bytes.len() as u64 != len {
+                    return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+                }
+                let associated_data = RecordEncryption::associated_data(sequence_number, record_type);
+                let compressed_bytes = cipher
+                    .decrypt(&associated_data, &encrypted_bytes)
+                    .map_err(|_| WALError::Decryption { sequence_number })?;
+                lz4::Decoder::new(compressed_bytes.as_slice())
+                    .and_then(|mut decoder| decoder.read_to_end(&mut decompressed_bytes))
+                    .map_err(|err| WALError::Decompression { source: Arc::new(err) })?
+            }
+        };
 
         Ok(Some(RawRecord { sequence_number, record_type, bytes: Cow::Owned(decompressed_bytes) }))
     }
@@ -473,7 +659,7 @@ impl<'a> RecordIterator<'a> {
             .enumerate()
             .last()
             .unwrap_or((0, files.files[0].start));
-        let mut reader = FileReader::new(files.files[current].clone())?;
+        let mut reader = FileReader::new(files.files[current].clone(), files.cipher())?;
 
         while current_start < start {
             match reader.peek_sequence_number().transpose() {
@@ -492,7 +678,7 @@ impl<'a> RecordIterator<'a> {
     fn advance_file(&mut self) -> io::Result<Option<()>> {
         self.current += 1;
         if self.current < self.files.files.len() {
-            self.reader = Some(FileReader::new(self.files.files[self.current].clone())?);
+            self.reader = Some(FileReader::new(self.files.files[self.current].clone(), self.files.cipher())?);
             Ok(Some(()))
         } else {
             self.reader.take();
@@ -526,8 +712,12 @@ struct FileRecordIterator<'a> {
 }
 
 impl<'a> FileRecordIterator<'a> {
-    fn new(file: &'a File, start: DurabilitySequenceNumber) -> Result<Self, DurabilityServiceError> {
-        let mut reader = FileReader::new(file.clone())?;
+    fn new(
+        file: &'a File,
+        cipher: Option<&AuthenticatedCipher>,
+        start: DurabilitySequenceNumber,
+    ) -> Result<Self, DurabilityServiceError> {
+        let mut reader = FileReader::new(file.clone(), cipher)?;
 
         let mut current_start = file.start;
         while current_start < start {
@@ -646,8 +836,12 @@ mod test {
     use itertools::Itertools;
     use tempdir::TempDir;
 
-    use super::WAL;
-    use crate::{DurabilityRecordType, DurabilitySequenceNumber, DurabilityService, RawRecord};
+    use super::{File, WALError, FILE_PREFIX, WAL};
+    use crate::{
+        encryption::{EncryptionKey, MasterKeyManagement},
+        DurabilityRecordType, DurabilitySequenceNumber, DurabilityService, DurabilityServiceError, RawRecord,
+    };
+
     #[derive(Debug, PartialEq, Eq, Clone, Copy)]
     struct TestRecord {
         bytes: [u8; 4],
@@ -857,4 +1051,117 @@ mod test {
             matches!(found, RawRecord { bytes, record_type: UnsequencedTestRecord::RECORD_TYPE, .. } if bytes == unsequenced_2.bytes())
         );
     }
+
+    #[test]
+    fn test_wal_truncate_from() {
+        let directory = TempDir::new("wal-test").unwrap();
+
+        let records = [TestRecord { bytes: *b"test" }, TestRecord { bytes: *b"abcd" }, TestRecord { bytes: *b"efgh" }];
+        let unsequenced = UnsequencedTestRecord { bytes: *b"unsq" };
+
+        let wal = create_wal(&directory);
+        let first = wal.sequenced_write(TestRecord::RECORD_TYPE, records[0].bytes()).unwrap();
+        let second = wal.sequenced_write(TestRecord::RECORD_TYPE, records[1].bytes()).unwrap();
+        wal.unsequenced_write(UnsequencedTestRecord::RECORD_TYPE, unsequenced.bytes()).unwrap();
+        wal.sequenced_write(TestRecord::RECORD_TYPE, records[2].bytes()).unwrap();
+        wal.unsequenced_write(UnsequencedTestRecord::RECORD_TYPE, unsequenced.bytes()).unwrap();
+
+        wal.truncate_from(second).unwrap();
+        assert_eq!(wal.current(), second);
+        let remaining = wal.iter_any_from(DurabilitySequenceNumber::MIN).unwrap().map(|res| res.unwrap()).collect_vec();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].sequence_number, first);
+
+        // the next record takes the place of the truncated ones, also after a restart
+        let replacement = TestRecord { bytes: *b"wxyz" };
+        assert_eq!(wal.sequenced_write(TestRecord::RECORD_TYPE, replacement.bytes()).unwrap(), second);
+        drop(wal);
+
+        let wal = load_wal(&directory);
+        let read_records = wal
+            .iter_any_from(DurabilitySequenceNumber::MIN)
+            .unwrap()
+            .map(|res| {
+                let RawRecord { record_type, bytes, .. } = res.unwrap();
+                assert_eq!(record_type, TestRecord::RECORD_TYPE);
+                TestRecord::new(&bytes)
+            })
+            .collect_vec();
+        assert_eq!(read_records, [records[0], replacement]);
+        assert_eq!(wal.current(), second.next());
+    }
+
+    #[test]
+    fn test_wal_file_paths_from() {
+        let directory = TempDir::new("wal-test").unwrap();
+        let wal_dir = directory.path().join(WAL::WAL_DIR_NAME);
+        std::fs::create_dir(&wal_dir).unwrap();
+        let starts = [1, 10, 20].map(DurabilitySequenceNumber::from);
+        for start in starts {
+            std::fs::write(wal_dir.join(File::format_file_name(start)), []).unwrap();
+        }
+
+        let file_starts = |start: u64| {
+            WAL::file_paths_from(&directory, DurabilitySequenceNumber::from(start))
+                .unwrap()
+                .into_iter()
+                .map(|path| File::open(path).unwrap().start)
+                .collect_vec()
+        };
+        // the file holding the start is returned along with every later file
+        assert_eq!(file_starts(1), starts);
+        assert_eq!(file_starts(15), &starts[1..]);
+        assert_eq!(file_starts(20), &starts[2..]);
+        assert_eq!(file_starts(100), &starts[2..]);
+    }
+
+    #[test]
+    fn test_wal_first_sequence_number() {
+        let directory = TempDir::new("wal-test").unwrap();
+        let wal_dir = directory.path().join(WAL::WAL_DIR_NAME);
+        std::fs::create_dir(&wal_dir).unwrap();
+        assert_eq!(WAL::first_sequence_number(&directory).unwrap(), None);
+
+        for start in [20, 10] {
+            std::fs::write(wal_dir.join(File::format_file_name(DurabilitySequenceNumber::from(start))), []).unwrap();
+        }
+        assert_eq!(WAL::first_sequence_number(&directory).unwrap(), Some(DurabilitySequenceNumber::from(10)));
+    }
+
+    #[test]
+    fn test_wal_encrypted_load() {
+        let directory = TempDir::new("wal-test").unwrap();
+        let key_management = MasterKeyManagement::new(&EncryptionKey::generate());
+
+        let record = TestRecord { bytes: *b"test" };
+
+        let mut wal = WAL::create_with_key_management(&directory, Some(&key_management)).unwrap();
+        wal.register_record_type(TestRecord::RECORD_TYPE, TestRecord::RECORD_NAME);
+        assert_true!(wal.is_encrypted());
+        wal.sequenced_write(TestRecord::RECORD_TYPE, record.bytes()).unwrap();
+        drop(wal);
+
+        let wal_file = std::fs::read_dir(directory.path().join(WAL::WAL_DIR_NAME))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.file_name().unwrap().to_string_lossy().starts_with(FILE_PREFIX))
+            .unwrap();
+        let contents = std::fs::read(wal_file).unwrap();
+        assert_true!(!contents.windows(record.bytes().len()).any(|window| window == record.bytes()));
+
+        assert_true!(matches!(
+            WAL::load(&directory),
+            Err(DurabilityServiceError::WAL { source: WALError::KeyManagementMissing { .. } })
+        ));
+
+        let mut wal = WAL::load_with_key_management(&directory, Some(&key_management)).unwrap();
+        wal.register_record_type(TestRecord::RECORD_TYPE, TestRecord::RECORD_NAME);
+        let RawRecord { record_type, bytes, .. } =
+            wal.iter_any_from(DurabilitySequenceNumber::MIN).unwrap().next().unwrap().unwrap();
+        assert_eq!(record_type, TestRecord::RECORD_TYPE);
+        assert_eq!(record, TestRecord::new(&bytes));
+
+        let other_key_management = MasterKeyManagement::new(&EncryptionKey::generate());
+        assert_true!(WAL::load_with_key_management(&directory, Some(&other_key_management)).is_err());
+    }
 }
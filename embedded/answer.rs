@@ -0,0 +1,180 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::Arc;
+
+use answer::{variable_value::VariableValue, Thing, Type};
+use bytes::{util::HexBytesFormatter, Bytes};
+use compiler::VariablePosition;
+use concept::{
+    error::ConceptReadError,
+    thing::{thing_manager::ThingManager, ThingAPI},
+    type_::type_manager::TypeManager,
+};
+use encoding::value::value::Value;
+use executor::batch::Batch;
+use resource::profile::StorageCounters;
+use storage::snapshot::ReadableSnapshot;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryAnswer {
+    /// Answer of a schema query
+    Ok,
+    Rows(Vec<Row>),
+}
+
+impl QueryAnswer {
+    pub fn into_rows(self) -> Vec<Row> {
+        match self {
+            QueryAnswer::Ok => Vec::new(),
+            QueryAnswer::Rows(rows) => rows,
+        }
+    }
+}
+
+/// One answer of a query, with a concept for each selected variable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    columns: Arc<[String]>,
+    concepts: Vec<Option<Concept>>,
+}
+
+impl Row {
+    pub fn column_names(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// The concept of the variable, which is `None` if the variable is unknown or optional and not set in this row.
+    pub fn get(&self, variable: &str) -> Option<&Concept> {
+        let index = self.columns.iter().position(|column| column == variable)?;
+        self.concepts[index].as_ref()
+    }
+
+    pub fn concepts(&self) -> impl Iterator<Item = (&str, Option<&Concept>)> {
+        self.columns.iter().map(String::as_str).zip(self.concepts.iter().map(Option::as_ref))
+    }
+}
+
+/// A concept of a query answer, read out of its transaction so that it can outlive it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Concept {
+    EntityType { label: String },
+    RelationType { label: String },
+    AttributeType { label: String },
+    RoleType { label: String },
+    Entity { iid: String, type_label: String },
+    Relation { iid: String, type_label: String },
+    Attribute { type_label: String, value: Value<'static> },
+    Value(Value<'static>),
+    List(Vec<Concept>),
+}
+
+impl Concept {
+    pub fn label(&self) -> Option<&str> {
+        match self {
+            Concept::EntityType { label }
+            | Concept::RelationType { label }
+            | Concept::AttributeType { label }
+            | Concept::RoleType { label } => Some(label),
+            _ => None,
+        }
+    }
+
+    pub fn type_label(&self) -> Option<&str> {
+        match self {
+            Concept::Entity { type_label, .. }
+            | Concept::Relation { type_label, .. }
+            | Concept::Attribute { type_label, .. } => Some(type_label),
+            _ => None,
+        }
+    }
+
+    /// The value of an attribute or value concept.
+    pub fn value(&self) -> Option<&Value<'static>> {
+        match self {
+            Concept::Attribute { value, .. } | Concept::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn read_rows(
+    batch: Batch,
+    columns: Vec<(String, VariablePosition)>,
+    snapshot: &impl ReadableSnapshot,
+    type_manager: &TypeManager,
+    thing_manager: &ThingManager,
+) -> Result<Vec<Row>, Box<ConceptReadError>> {
+    let (names, positions): (Vec<_>, Vec<_>) = columns.into_iter().unzip();
+    let names: Arc<[String]> = names.into();
+    batch
+        .iter()
+        .map(|row| {
+            let concepts = positions
+                .iter()
+                .map(|position| read_concept(row.get(*position), snapshot, type_manager, thing_manager))
+                .collect::<Result<_, _>>()?;
+            Ok(Row { columns: names.clone(), concepts })
+        })
+        .collect()
+}
+
+fn read_concept(
+    variable_value: &VariableValue<'_>,
+    snapshot: &impl ReadableSnapshot,
+    type_manager: &TypeManager,
+    thing_manager: &ThingManager,
+) -> Result<Option<Concept>, Box<ConceptReadError>> {
+    let concept = match variable_value {
+        VariableValue::None => return Ok(None),
+        VariableValue::Type(type_) => read_type(type_, snapshot, type_manager)?,
+        VariableValue::Thing(thing) => read_thing(thing, snapshot, type_manager, thing_manager)?,
+        VariableValue::Value(value) => Concept::Value(value.clone().into_owned()),
+        VariableValue::ThingList(things) => Concept::List(
+            things
+                .iter()
+                .map(|thing| read_thing(thing, snapshot, type_manager, thing_manager))
+                .collect::<Result<_, _>>()?,
+        ),
+        VariableValue::ValueList(values) => Concept::List(values.iter().cloned().map(Concept::Value).collect()),
+    };
+    Ok(Some(concept))
+}
+
+fn read_type(
+    type_: &Type,
+    snapshot: &impl ReadableSnapshot,
+    type_manager: &TypeManager,
+) -> Result<Concept, Box<ConceptReadError>> {
+    let label = type_.get_label(snapshot, type_manager)?.scoped_name().as_str().to_owned();
+    Ok(match type_ {
+        Type::Entity(_) => Concept::EntityType { label },
+        Type::Relation(_) => Concept::RelationType { label },
+        Type::Attribute(_) => Concept::AttributeType { label },
+        Type::RoleType(_) => Concept::RoleType { label },
+    })
+}
+
+fn read_thing(
+    thing: &Thing,
+    snapshot: &impl ReadableSnapshot,
+    type_manager: &TypeManager,
+    thing_manager: &ThingManager,
+) -> Result<Concept, Box<ConceptReadError>> {
+    let type_label = thing.type_().get_label(snapshot, type_manager)?.scoped_name().as_str().to_owned();
+    Ok(match thing {
+        Thing::Entity(entity) => Concept::Entity { iid: encode_iid(entity.iid()), type_label },
+        Thing::Relation(relation) => Concept::Relation { iid: encode_iid(relation.iid()), type_label },
+        Thing::Attribute(attribute) => {
+            let value = attribute.get_value(snapshot, thing_manager, StorageCounters::DISABLED)?.into_owned();
+            Concept::Attribute { type_label, value }
+        }
+    })
+}
+
+fn encode_iid<const ARRAY_INLINE_SIZE: usize>(iid: Bytes<'_, ARRAY_INLINE_SIZE>) -> String {
+    HexBytesFormatter::owned(Vec::from(iid)).format_iid()
+}
@@ -0,0 +1,111 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+#![deny(unused_must_use)]
+#![deny(elided_lifetimes_in_paths)]
+
+//! In-process access to TypeDB databases, without the network services.
+//! Applications open a data directory, and run TypeQL queries in transactions against its databases:
+//!
+//! ```ignore
+//! let typedb = TypeDB::open("data")?;
+//! typedb.create_database("library")?;
+//! let mut transaction = typedb.transaction("library", TransactionType::Schema)?;
+//! transaction.query("define entity book, owns title; attribute title, value string;")?;
+//! transaction.commit()?;
+//! ```
+
+use std::{path::Path, sync::Arc};
+
+use concept::error::ConceptReadError;
+use database::{
+    database::DatabaseCreateError,
+    database_manager::DatabaseManager,
+    transaction::{DataCommitError, SchemaCommitError, TransactionError},
+    DatabaseDeleteError, DatabaseOpenError,
+};
+use error::typedb_error;
+use executor::pipeline::PipelineExecutionError;
+use query::error::QueryError;
+
+pub use encoding::value::value::Value;
+
+pub use crate::{
+    answer::{Concept, QueryAnswer, Row},
+    transaction::{Transaction, TransactionType},
+};
+
+pub mod answer;
+pub mod transaction;
+
+/// A data directory of TypeDB databases, opened in this process.
+/// The directory must not be opened by a server or another process at the same time.
+#[derive(Debug, Clone)]
+pub struct TypeDB {
+    database_manager: Arc<DatabaseManager>,
+}
+
+impl TypeDB {
+    pub fn open(data_directory: impl AsRef<Path>) -> Result<Self, EmbeddedError> {
+        let database_manager = DatabaseManager::new(data_directory)
+            .map_err(|typedb_source| EmbeddedError::DatabaseOpen { typedb_source })?;
+        Ok(Self { database_manager })
+    }
+
+    pub fn database_names(&self) -> Vec<String> {
+        self.database_manager.database_names()
+    }
+
+    pub fn contains_database(&self, name: &str) -> bool {
+        DatabaseManager::is_user_database(name) && self.database_manager.database(name).is_some()
+    }
+
+    pub fn create_database(&self, name: impl AsRef<str>) -> Result<(), EmbeddedError> {
+        self.database_manager
+            .put_database(name)
+            .map_err(|typedb_source| EmbeddedError::DatabaseCreate { typedb_source })
+    }
+
+    pub fn delete_database(&self, name: impl AsRef<str>) -> Result<(), EmbeddedError> {
+        self.database_manager
+            .delete_database(name)
+            .map_err(|typedb_source| EmbeddedError::DatabaseDelete { typedb_source })
+    }
+
+    /// Opens a transaction with the database's configured transaction defaults.
+    pub fn transaction(
+        &self,
+        database_name: &str,
+        transaction_type: TransactionType,
+    ) -> Result<Transaction, EmbeddedError> {
+        let database = match self.database_manager.database(database_name) {
+            Some(database) if DatabaseManager::is_user_database(database_name) => database,
+            _ => return Err(EmbeddedError::DatabaseNotFound { name: database_name.to_owned() }),
+        };
+        Transaction::open(database, transaction_type)
+    }
+}
+
+typedb_error! {
+    pub EmbeddedError(component = "Embedded", prefix = "EMB") {
+        DatabaseOpen(1, "Error opening the data directory.", typedb_source: DatabaseOpenError),
+        DatabaseNotFound(2, "Database '{name}' does not exist.", name: String),
+        DatabaseCreate(3, "Error creating a database.", typedb_source: DatabaseCreateError),
+        DatabaseDelete(4, "Error deleting a database.", typedb_source: DatabaseDeleteError),
+        TransactionOpen(5, "Error opening a transaction.", typedb_source: TransactionError),
+        TransactionClosed(6, "The transaction is closed, since a schema query in it failed."),
+        QueryParseFailed(7, "Query parsing failed.", typedb_source: typeql::Error),
+        SchemaQueryRequiresSchemaTransaction(8, "Schema queries can only be run in schema transactions."),
+        WriteQueryRequiresWriteTransaction(9, "Write queries can only be run in write or schema transactions."),
+        FetchNotSupported(10, "Fetch queries are not supported in embedded transactions. Select the variables to read instead."),
+        QueryFailed(11, "Query execution failed.", typedb_source: Box<QueryError>),
+        ReadQueryFailed(12, "Read query execution failed.", typedb_source: Box<PipelineExecutionError>),
+        AnswerReadFailed(13, "Error reading the concepts of a query answer.", typedb_source: Box<ConceptReadError>),
+        CommitNotSupported(14, "Read transactions cannot be committed."),
+        DataCommitFailed(15, "Commit failed.", typedb_source: DataCommitError),
+        SchemaCommitFailed(16, "Schema commit failed.", typedb_source: SchemaCommitError),
+    }
+}
@@ -0,0 +1,65 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use test_utils::{create_tmp_dir, init_logging};
+use typedb_embedded::{Concept, EmbeddedError, QueryAnswer, TransactionType, TypeDB, Value};
+
+const DB_NAME: &str = "test";
+
+#[test]
+fn queries_run_in_process_and_answer_with_rows() {
+    init_logging();
+    let data_directory = create_tmp_dir();
+    let typedb = TypeDB::open(&data_directory).expect("Expected data directory to open");
+    typedb.create_database(DB_NAME).expect("Expected database creation");
+    assert_eq!(typedb.database_names(), vec![DB_NAME.to_owned()]);
+
+    let mut transaction = typedb.transaction(DB_NAME, TransactionType::Schema).unwrap();
+    let answer = transaction.query("define entity person, owns name; attribute name, value string;").unwrap();
+    assert_eq!(answer, QueryAnswer::Ok);
+    transaction.commit().expect("Expected schema commit");
+
+    let mut transaction = typedb.transaction(DB_NAME, TransactionType::Write).unwrap();
+    let rows = transaction.query("insert $p isa person, has name 'alice';").unwrap().into_rows();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get("p").and_then(Concept::type_label), Some("person"));
+    transaction.commit().expect("Expected data commit");
+
+    let mut transaction = typedb.transaction(DB_NAME, TransactionType::Read).unwrap();
+    let rows = transaction.query("match $p isa person, has name $n; select $n;").unwrap().into_rows();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].column_names(), ["n".to_owned()]);
+    let name = rows[0].get("n").unwrap();
+    assert_eq!(name.type_label(), Some("name"));
+    assert_eq!(name.value(), Some(&Value::String("alice".into())));
+
+    let write_result = transaction.query("insert $p isa person;");
+    assert!(matches!(write_result, Err(EmbeddedError::WriteQueryRequiresWriteTransaction { .. })));
+    let fetch_result = transaction.query("match $p isa person; fetch { \"name\": $p.name };");
+    assert!(matches!(fetch_result, Err(EmbeddedError::FetchNotSupported { .. })));
+    transaction.close();
+}
+
+#[test]
+fn failed_schema_query_closes_transaction_and_discards_changes() {
+    init_logging();
+    let data_directory = create_tmp_dir();
+    let typedb = TypeDB::open(&data_directory).expect("Expected data directory to open");
+    typedb.create_database(DB_NAME).expect("Expected database creation");
+
+    let mut transaction = typedb.transaction(DB_NAME, TransactionType::Schema).unwrap();
+    transaction.query("define entity person;").unwrap();
+    assert!(transaction.query("define entity person sub missing;").is_err());
+    assert!(!transaction.is_open());
+    assert!(matches!(transaction.commit(), Err(EmbeddedError::TransactionClosed { .. })));
+
+    let mut transaction = typedb.transaction(DB_NAME, TransactionType::Read).unwrap();
+    let rows = transaction.query("match entity $t;").unwrap().into_rows();
+    assert!(rows.is_empty());
+
+    let result = typedb.transaction("missing", TransactionType::Read);
+    assert!(matches!(result, Err(EmbeddedError::DatabaseNotFound { .. })));
+}
@@ -0,0 +1,253 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{collections::HashMap, sync::Arc};
+
+use compiler::VariablePosition;
+use concept::{thing::thing_manager::ThingManager, type_::type_manager::TypeManager};
+use database::{
+    query::{execute_schema_query, execute_write_query_in_schema, execute_write_query_in_write, WriteQueryResult},
+    transaction::{TransactionRead, TransactionSchema, TransactionWrite},
+    Database,
+};
+use executor::{pipeline::stage::StageIterator, ExecutionInterrupt};
+use itertools::Either;
+use options::QueryOptions;
+use storage::{durability_client::WALClient, snapshot::ReadableSnapshot};
+use typeql::query::{stage::Stage, Pipeline, QueryStructure};
+
+use crate::{
+    answer::{read_rows, QueryAnswer},
+    EmbeddedError,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TransactionType {
+    Read,
+    Write,
+    Schema,
+}
+
+/// A transaction against an embedded database. Queries run to completion on the calling thread,
+/// and their answers are read out of the transaction before they are returned.
+///
+/// Dropping a transaction without committing it discards its changes.
+#[derive(Debug)]
+pub struct Transaction {
+    transaction: Option<TransactionInner>,
+}
+
+#[derive(Debug)]
+enum TransactionInner {
+    Read(TransactionRead<WALClient>),
+    Write(TransactionWrite<WALClient>),
+    Schema(TransactionSchema<WALClient>),
+}
+
+impl Transaction {
+    pub(crate) fn open(
+        database: Arc<Database<WALClient>>,
+        transaction_type: TransactionType,
+    ) -> Result<Self, EmbeddedError> {
+        let options = database.transaction_defaults();
+        let transaction = match transaction_type {
+            TransactionType::Read => TransactionRead::open(database, options).map(TransactionInner::Read),
+            TransactionType::Write => TransactionWrite::open(database, options).map(TransactionInner::Write),
+            TransactionType::Schema => TransactionSchema::open(database, options).map(TransactionInner::Schema),
+        }
+        .map_err(|typedb_source| EmbeddedError::TransactionOpen { typedb_source })?;
+        Ok(Self { transaction: Some(transaction) })
+    }
+
+    pub fn transaction_type(&self) -> Option<TransactionType> {
+        self.transaction.as_ref().map(|transaction| match transaction {
+            TransactionInner::Read(_) => TransactionType::Read,
+            TransactionInner::Write(_) => TransactionType::Write,
+            TransactionInner::Schema(_) => TransactionType::Schema,
+        })
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.transaction.is_some()
+    }
+
+    /// Runs a TypeQL query. Pipelines answer with the rows of their selected variables, and schema queries with `Ok`.
+    /// A failed schema query closes the transaction, since its changes may be partially applied.
+    pub fn query(&mut self, query: &str) -> Result<QueryAnswer, EmbeddedError> {
+        let parsed =
+            typeql::parse_query(query).map_err(|typedb_source| EmbeddedError::QueryParseFailed { typedb_source })?;
+        match parsed.into_structure() {
+            QueryStructure::Schema(schema_query) => {
+                match &self.transaction {
+                    None => return Err(EmbeddedError::TransactionClosed {}),
+                    Some(TransactionInner::Schema(_)) => (),
+                    Some(_) => return Err(EmbeddedError::SchemaQueryRequiresSchemaTransaction {}),
+                }
+                let Some(TransactionInner::Schema(transaction)) = self.transaction.take() else { unreachable!() };
+                let (transaction, result) = execute_schema_query(transaction, schema_query, query.to_owned());
+                match result {
+                    Ok(()) => {
+                        self.transaction = Some(TransactionInner::Schema(transaction));
+                        Ok(QueryAnswer::Ok)
+                    }
+                    Err(typedb_source) => {
+                        transaction.close();
+                        Err(EmbeddedError::QueryFailed { typedb_source })
+                    }
+                }
+            }
+            QueryStructure::Pipeline(pipeline) => self.query_pipeline(pipeline, query),
+        }
+    }
+
+    fn query_pipeline(&mut self, pipeline: Pipeline, query: &str) -> Result<QueryAnswer, EmbeddedError> {
+        if pipeline.stages.iter().any(|stage| matches!(stage, Stage::Fetch(_))) {
+            return Err(EmbeddedError::FetchNotSupported {});
+        }
+        let query_options = QueryOptions::default_grpc();
+        let interrupt = ExecutionInterrupt::new_uninterruptible();
+        match self.transaction.take() {
+            None => Err(EmbeddedError::TransactionClosed {}),
+            Some(TransactionInner::Read(transaction)) => {
+                let result = if is_write_pipeline(&pipeline) {
+                    Err(EmbeddedError::WriteQueryRequiresWriteTransaction {})
+                } else {
+                    query_read(&transaction, &pipeline, query)
+                };
+                self.transaction = Some(TransactionInner::Read(transaction));
+                result
+            }
+            Some(TransactionInner::Write(transaction)) => {
+                let (transaction, result) =
+                    execute_write_query_in_write(transaction, query_options, pipeline, query.to_owned(), interrupt);
+                let answer = write_query_answer(
+                    result,
+                    &*transaction.snapshot,
+                    &transaction.type_manager,
+                    &transaction.thing_manager,
+                );
+                self.transaction = Some(TransactionInner::Write(transaction));
+                answer
+            }
+            Some(TransactionInner::Schema(transaction)) => {
+                let (transaction, result) =
+                    execute_write_query_in_schema(transaction, query_options, pipeline, query.to_owned(), interrupt);
+                let answer = write_query_answer(
+                    result,
+                    &*transaction.snapshot,
+                    &transaction.type_manager,
+                    &transaction.thing_manager,
+                );
+                self.transaction = Some(TransactionInner::Schema(transaction));
+                answer
+            }
+        }
+    }
+
+    pub fn commit(mut self) -> Result<(), EmbeddedError> {
+        match self.transaction.take() {
+            None => Err(EmbeddedError::TransactionClosed {}),
+            Some(TransactionInner::Read(transaction)) => {
+                transaction.close();
+                Err(EmbeddedError::CommitNotSupported {})
+            }
+            Some(TransactionInner::Write(transaction)) => {
+                let (_, result) = transaction.commit();
+                result.map(|_| ()).map_err(|typedb_source| EmbeddedError::DataCommitFailed { typedb_source })
+            }
+            Some(TransactionInner::Schema(transaction)) => {
+                let (_, result) = transaction.commit();
+                result.map(|_| ()).map_err(|typedb_source| EmbeddedError::SchemaCommitFailed { typedb_source })
+            }
+        }
+    }
+
+    /// Discards the changes made in the transaction, which stays open.
+    pub fn rollback(&mut self) {
+        match &mut self.transaction {
+            None | Some(TransactionInner::Read(_)) => (),
+            Some(TransactionInner::Write(transaction)) => transaction.rollback(),
+            Some(TransactionInner::Schema(transaction)) => transaction.rollback(),
+        }
+    }
+
+    pub fn close(mut self) {
+        self.close_inner();
+    }
+
+    fn close_inner(&mut self) {
+        match self.transaction.take() {
+            None => (),
+            Some(TransactionInner::Read(transaction)) => transaction.close(),
+            Some(TransactionInner::Write(transaction)) => transaction.close(),
+            Some(TransactionInner::Schema(transaction)) => transaction.close(),
+        }
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        self.close_inner();
+    }
+}
+
+fn is_write_pipeline(pipeline: &Pipeline) -> bool {
+    pipeline
+        .stages
+        .iter()
+        .any(|stage| matches!(stage, Stage::Insert(_) | Stage::Put(_) | Stage::Delete(_) | Stage::Update(_)))
+}
+
+fn query_read(
+    transaction: &TransactionRead<WALClient>,
+    pipeline: &Pipeline,
+    query: &str,
+) -> Result<QueryAnswer, EmbeddedError> {
+    let prepared_pipeline = transaction
+        .query_manager
+        .prepare_read_pipeline(
+            transaction.snapshot.clone(),
+            &transaction.type_manager,
+            transaction.thing_manager.clone(),
+            &transaction.function_manager,
+            pipeline,
+            query,
+        )
+        .map_err(|typedb_source| EmbeddedError::QueryFailed { typedb_source })?;
+    let columns = sorted_columns(prepared_pipeline.rows_positions().ok_or(EmbeddedError::FetchNotSupported {})?);
+    let batch = match prepared_pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()) {
+        Ok((iterator, _)) => iterator.collect_owned(),
+        Err((typedb_source, _)) => return Err(EmbeddedError::ReadQueryFailed { typedb_source }),
+    }
+    .map_err(|typedb_source| EmbeddedError::ReadQueryFailed { typedb_source })?;
+    let rows = read_rows(batch, columns, &*transaction.snapshot, &transaction.type_manager, &transaction.thing_manager)
+        .map_err(|typedb_source| EmbeddedError::AnswerReadFailed { typedb_source })?;
+    Ok(QueryAnswer::Rows(rows))
+}
+
+fn write_query_answer(
+    result: WriteQueryResult,
+    snapshot: &impl ReadableSnapshot,
+    type_manager: &TypeManager,
+    thing_manager: &ThingManager,
+) -> Result<QueryAnswer, EmbeddedError> {
+    let answer = result.map_err(|typedb_source| EmbeddedError::QueryFailed { typedb_source })?;
+    match answer.answer {
+        Either::Left((columns, batch, _)) => {
+            let rows = read_rows(batch, columns, snapshot, type_manager, thing_manager)
+                .map_err(|typedb_source| EmbeddedError::AnswerReadFailed { typedb_source })?;
+            Ok(QueryAnswer::Rows(rows))
+        }
+        Either::Right(_) => Err(EmbeddedError::FetchNotSupported {}),
+    }
+}
+
+/// Orders the selected variables as they are laid out in the answer rows.
+fn sorted_columns(positions: &HashMap<String, VariablePosition>) -> Vec<(String, VariablePosition)> {
+    let mut columns: Vec<_> = positions.iter().map(|(name, position)| (name.clone(), *position)).collect();
+    columns.sort_by_key(|(_, position)| *position);
+    columns
+}
@@ -11,7 +11,7 @@ use bytes::{util::MB, Bytes};
 use rocksdb::{BlockBasedIndexType, BlockBasedOptions, DBCompressionType, SliceTransform};
 use storage::{
     key_value::StorageKey,
-    keyspace::{KeyspaceId, KeyspaceSet},
+    keyspace::{KeyspaceId, KeyspaceSet, KeyspaceUpgrade, StorageFormatVersion},
 };
 
 use crate::layout::prefix::{Prefix, PrefixID};
@@ -21,6 +21,14 @@ pub mod graph;
 pub mod layout;
 pub mod value;
 
+/// The storage format version of all encoding keyspaces. Any change to how existing keys or values are serialised
+/// (e.g. the encoding of `Ordering`) must bump it, together with an upgrade in `STORAGE_FORMAT_UPGRADES`.
+pub const STORAGE_FORMAT_VERSION: StorageFormatVersion = StorageFormatVersion(1);
+
+// Ordered by version. Version 1 is the encoding in use when format versions were introduced, so unversioned
+// keyspaces need no rewrite to reach it.
+const STORAGE_FORMAT_UPGRADES: &[KeyspaceUpgrade] = &[];
+
 /*
  * TODO: things we may want to allow the user to configure, per database:
  * - Bytes per TypeID (max number of types per kind)
@@ -147,6 +155,14 @@ impl KeyspaceSet for EncodingKeyspace {
             EncodingKeyspace::OptimisedPrefix25 => 25,
         })
     }
+
+    fn format_version(&self) -> StorageFormatVersion {
+        STORAGE_FORMAT_VERSION
+    }
+
+    fn format_upgrades(&self) -> &'static [KeyspaceUpgrade] {
+        STORAGE_FORMAT_UPGRADES
+    }
 }
 
 pub trait AsBytes<const INLINE_SIZE: usize> {
@@ -4,15 +4,22 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use bytes::{byte_array::ByteArray, Bytes};
+use bytes::{byte_array::ByteArray, util::HexBytesFormatter, Bytes};
 use resource::constants::snapshot::BUFFER_KEY_INLINE;
 use storage::key_value::StorageKey;
 
-use self::{vertex_attribute::AttributeVertex, vertex_object::ObjectVertex};
+use self::{
+    edge::{ThingEdgeHas, ThingEdgeHasReverse, ThingEdgeLinks},
+    vertex_attribute::AttributeVertex,
+    vertex_object::ObjectVertex,
+};
 use crate::{
     graph::{type_::vertex::TypeID, Typed},
-    layout::prefix::{Prefix, PrefixID},
-    EncodingKeyspace, Keyable, Prefixed,
+    layout::{
+        infix::Infix,
+        prefix::{Prefix, PrefixID},
+    },
+    AsBytes, EncodingKeyspace, Keyable, Prefixed,
 };
 
 pub mod edge;
@@ -33,6 +40,48 @@ const fn max(lhs: usize, rhs: usize) -> usize {
 
 pub const THING_VERTEX_MAX_LENGTH: usize = max(ObjectVertex::LENGTH, AttributeVertex::MAX_LENGTH);
 
+/// Describes the instance, edge or constraint lock stored under `key`, for error messages.
+/// Types are referred to by their IDs. Returns `None` if the key is not recognised as thing data.
+pub fn describe_thing_key(key: &[u8]) -> Option<String> {
+    let &prefix_byte = key.first()?;
+    let iid = |bytes: &[u8]| HexBytesFormatter::borrowed(bytes).format_iid();
+    if prefix_byte == Prefix::VertexEntity.prefix_id().byte || prefix_byte == Prefix::VertexRelation.prefix_id().byte {
+        let object = ObjectVertex::try_decode(key)?;
+        let kind = if prefix_byte == Prefix::VertexEntity.prefix_id().byte { "entity" } else { "relation" };
+        Some(format!("{kind} {} of type {}", iid(key), object.type_id_()))
+    } else if prefix_byte == Prefix::VertexAttribute.prefix_id().byte {
+        let attribute = AttributeVertex::try_decode(key)?;
+        Some(format!("attribute {} of type {}", iid(key), attribute.type_id_()))
+    } else if prefix_byte == Prefix::EdgeHas.prefix_id().byte && key.len() > ThingEdgeHas::LENGTH_PREFIX_FROM_OBJECT {
+        let has = ThingEdgeHas::decode(Bytes::reference(key));
+        Some(format!("ownership of attribute {} by {}", iid(&*has.to().to_bytes()), iid(&*has.from().to_bytes())))
+    } else if prefix_byte == Prefix::EdgeHasReverse.prefix_id().byte
+        && key.len() > ThingEdgeHasReverse::LENGTH_PREFIX_FROM_PREFIX
+    {
+        let has = ThingEdgeHasReverse::decode(Bytes::reference(key));
+        Some(format!("ownership of attribute {} by {}", iid(&*has.from().to_bytes()), iid(&*has.to().to_bytes())))
+    } else if (prefix_byte == Prefix::EdgeLinks.prefix_id().byte
+        || prefix_byte == Prefix::EdgeLinksReverse.prefix_id().byte)
+        && key.len() == ThingEdgeLinks::LENGTH
+    {
+        let links = ThingEdgeLinks::decode(Bytes::reference(key));
+        Some(format!(
+            "player {} of relation {} in role type {}",
+            iid(&*links.player().to_bytes()),
+            iid(&*links.relation().to_bytes()),
+            links.role_id()
+        ))
+    } else if key.starts_with(&Infix::PropertyAnnotationUnique.infix_id().bytes()) {
+        Some("a uniqueness constraint lock".to_owned())
+    } else if key.starts_with(&Infix::PropertyAnnotationCardinality.infix_id().bytes()) {
+        let owner = key.get(Infix::PropertyAnnotationCardinality.infix_id().bytes().len()..)?;
+        let object = owner.get(..ObjectVertex::LENGTH).and_then(ObjectVertex::try_decode)?;
+        Some(format!("a cardinality constraint lock of {}", iid(&*object.to_bytes())))
+    } else {
+        None
+    }
+}
+
 pub trait ThingVertex: Prefixed<BUFFER_KEY_INLINE> + Typed<BUFFER_KEY_INLINE> + Keyable<BUFFER_KEY_INLINE> {
     const FIXED_WIDTH_ENCODING: bool;
 
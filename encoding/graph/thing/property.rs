@@ -37,6 +37,10 @@ pub fn build_object_vertex_property_links_order(
     ObjectVertexProperty::new_suffixed(object_vertex, Infix::PropertyLinksOrder, suffix)
 }
 
+pub fn build_object_vertex_property_creation_time(object_vertex: ObjectVertex) -> ObjectVertexProperty {
+    ObjectVertexProperty::new(object_vertex, Infix::PropertyCreationTime)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd)]
 pub struct ObjectVertexProperty {
     object: ObjectVertex,
@@ -40,7 +40,7 @@ use crate::{
         duration_bytes::DurationBytes, integer_bytes::IntegerBytes, string_bytes::StringBytes,
         struct_bytes::StructBytes,
     },
-    AsBytes, Keyable,
+    AsBytes, Keyable, Prefixed,
 };
 
 #[derive(Debug)]
@@ -157,7 +157,7 @@ impl ThingVertexGenerator {
     {
         let entity_id = self.entity_ids[type_id.as_u16() as usize].fetch_add(1, Ordering::Relaxed);
         let vertex = ObjectVertex::build_entity(type_id, ObjectID::new(entity_id));
-        snapshot.insert(vertex.into_storage_key().into_owned_array());
+        Self::insert_object(vertex, snapshot);
         vertex
     }
 
@@ -167,10 +167,34 @@ impl ThingVertexGenerator {
     {
         let relation_id = self.relation_ids[type_id.as_u16() as usize].fetch_add(1, Ordering::Relaxed);
         let vertex = ObjectVertex::build_relation(type_id, ObjectID::new(relation_id));
-        snapshot.insert(vertex.into_storage_key().into_owned_array());
+        Self::insert_object(vertex, snapshot);
         vertex
     }
 
+    /// Inserts an entity or relation with a provided rather than a generated ID,
+    /// and advances the IDs generated for its type past it, so they never collide.
+    pub fn create_object_with_vertex<Snapshot>(&self, vertex: ObjectVertex, snapshot: &mut Snapshot)
+    where
+        Snapshot: WritableSnapshot,
+    {
+        let ids = match vertex.prefix() {
+            Prefix::VertexEntity => &self.entity_ids,
+            Prefix::VertexRelation => &self.relation_ids,
+            _ => unreachable!("Object vertices are either entities or relations."),
+        };
+        let next_id = vertex.object_id().as_u64().saturating_add(1);
+        ids[vertex.type_id_().as_u16() as usize].fetch_max(next_id, Ordering::Relaxed);
+        Self::insert_object(vertex, snapshot);
+    }
+
+    // A provided ID may have been generated concurrently, before the generator was advanced past it, so every
+    // object is locked: of two transactions creating the same object, only the first to commit succeeds.
+    fn insert_object(vertex: ObjectVertex, snapshot: &mut impl WritableSnapshot) {
+        let storage_key = vertex.into_storage_key().into_owned_array();
+        snapshot.exclusive_lock_add(storage_key.byte_array().clone());
+        snapshot.insert(storage_key);
+    }
+
     pub fn create_attribute_boolean<Snapshot>(
         &self,
         type_id: TypeID,
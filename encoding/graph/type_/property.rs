@@ -244,3 +244,64 @@ pub trait TypeEdgePropertyEncoding: Sized {
             && TypeEdgeProperty::decode(key_bytes).infix() == Self::INFIX
     }
 }
+
+/// A schema-level property of the database as a whole, rather than of any one type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd)]
+pub struct DatabaseProperty {
+    infix: Infix,
+}
+
+impl DatabaseProperty {
+    const KEYSPACE: EncodingKeyspace = EncodingKeyspace::DefaultOptimisedPrefix11;
+    const PREFIX: Prefix = Prefix::PropertyDatabase;
+    pub const FIXED_WIDTH_ENCODING: bool = Self::PREFIX.fixed_width_keys();
+
+    const LENGTH: usize = PrefixID::LENGTH + InfixID::LENGTH;
+
+    pub fn new(infix: Infix) -> Self {
+        Self { infix }
+    }
+
+    pub fn decode(bytes: Bytes<'_, BUFFER_KEY_INLINE>) -> Self {
+        debug_assert_eq!(bytes.length(), Self::LENGTH);
+        debug_assert_eq!(bytes[Self::INDEX_PREFIX], Self::PREFIX.prefix_id().byte);
+        Self { infix: Infix::from_infix_id(InfixID::new((&bytes[Self::range_infix()]).try_into().unwrap())) }
+    }
+
+    pub fn infix(&self) -> Infix {
+        self.infix
+    }
+
+    const fn range_infix() -> Range<usize> {
+        Self::INDEX_PREFIX + 1..Self::INDEX_PREFIX + 1 + InfixID::LENGTH
+    }
+}
+
+impl AsBytes<BUFFER_KEY_INLINE> for DatabaseProperty {
+    fn to_bytes(self) -> Bytes<'static, BUFFER_KEY_INLINE> {
+        let mut array = ByteArray::zeros(Self::LENGTH);
+        array[Self::INDEX_PREFIX] = Self::PREFIX.prefix_id().byte;
+        array[Self::range_infix()].copy_from_slice(&self.infix.infix_id().bytes());
+        Bytes::Array(array)
+    }
+}
+
+impl Keyable<BUFFER_KEY_INLINE> for DatabaseProperty {
+    fn keyspace(&self) -> EncodingKeyspace {
+        Self::KEYSPACE
+    }
+}
+
+impl Prefixed<BUFFER_KEY_INLINE> for DatabaseProperty {}
+
+pub trait DatabasePropertyEncoding: Sized {
+    const INFIX: Infix;
+
+    fn from_value_bytes(value: &[u8]) -> Self;
+
+    fn build_key() -> DatabaseProperty {
+        DatabaseProperty::new(Self::INFIX)
+    }
+
+    fn to_value_bytes(&self) -> Bytes<'static, BUFFER_VALUE_INLINE>;
+}
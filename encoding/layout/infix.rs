@@ -28,6 +28,7 @@ pub enum Infix {
     PropertyValueType,
     PropertyOrdering,
     PropertyRelationTypeIndependent, // system, should not be exposed to the user
+    PropertyTimeToLive,
 
     PropertyAnnotationAbstract,
     PropertyAnnotationDistinct,
@@ -44,6 +45,14 @@ pub enum Infix {
     // Data properties
     PropertyHasOrder,
     PropertyLinksOrder,
+    PropertyCreationTime,
+
+    // Database properties
+    PropertyCommitTriggers,
+    PropertyHistoryRetention,
+    PropertyStorageQuota,
+    PropertyInternalSchemaVersion,
+    PropertyDatabaseSettings,
 }
 
 macro_rules! infix_functions {
@@ -80,6 +89,7 @@ impl Infix {
         PropertyValueType => [1];
         PropertyOrdering => [2];
         PropertyRelationTypeIndependent => [3];
+        PropertyTimeToLive => [4];
 
        // Reserve: range 50 - 99 to store annotations with a value type - see InfixID::<CONSTANTS>
         PropertyAnnotationAbstract => [50];
@@ -95,6 +105,13 @@ impl Infix {
         _PropertyAnnotationLast => [99];
 
         PropertyHasOrder => [100];
-        PropertyLinksOrder => [101]
+        PropertyLinksOrder => [101];
+        PropertyCreationTime => [102];
+
+        PropertyCommitTriggers => [151];
+        PropertyHistoryRetention => [152];
+        PropertyStorageQuota => [153];
+        PropertyInternalSchemaVersion => [154];
+        PropertyDatabaseSettings => [155]
     );
 }
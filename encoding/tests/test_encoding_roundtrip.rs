@@ -0,0 +1,263 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+#![deny(unused_must_use)]
+
+// These properties pin down the on-disk key format: every key must decode back to the value it was encoded from,
+// and the byte-wise order of encoded keys must agree with the in-memory `Ord` that iterators rely on.
+
+use bytes::util::HexBytesFormatter;
+use encoding::{
+    graph::{
+        thing::{
+            describe_thing_key,
+            edge::{ThingEdgeHas, ThingEdgeHasReverse, ThingEdgeLinks},
+            vertex_attribute::{AttributeID, AttributeVertex},
+            vertex_object::{ObjectID, ObjectVertex},
+            ThingVertex,
+        },
+        type_::vertex::{TypeID, TypeVertex},
+        Typed,
+    },
+    layout::prefix::Prefix,
+    value::value::Value,
+    AsBytes,
+};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+const CASES: usize = 10_000;
+// A fixed seed keeps every run on the same cases, so a failure always reproduces
+const SEED: u64 = 0x7970_6564_6220_7233;
+
+fn seeded_rng() -> SmallRng {
+    SmallRng::seed_from_u64(SEED)
+}
+
+fn type_id(rng: &mut SmallRng) -> TypeID {
+    TypeID::new(rng.gen())
+}
+
+fn type_vertex(rng: &mut SmallRng) -> TypeVertex {
+    let prefix = match rng.gen_range(0..4) {
+        0 => Prefix::VertexEntityType,
+        1 => Prefix::VertexRelationType,
+        2 => Prefix::VertexAttributeType,
+        _ => Prefix::VertexRoleType,
+    };
+    TypeVertex::new(prefix.prefix_id(), type_id(rng))
+}
+
+fn role_type_vertex(rng: &mut SmallRng) -> TypeVertex {
+    TypeVertex::new(Prefix::VertexRoleType.prefix_id(), type_id(rng))
+}
+
+fn entity_vertex(rng: &mut SmallRng) -> ObjectVertex {
+    ObjectVertex::build_entity(type_id(rng), ObjectID::new(rng.gen()))
+}
+
+fn relation_vertex(rng: &mut SmallRng) -> ObjectVertex {
+    ObjectVertex::build_relation(type_id(rng), ObjectID::new(rng.gen()))
+}
+
+fn object_vertex(rng: &mut SmallRng) -> ObjectVertex {
+    if rng.gen() {
+        entity_vertex(rng)
+    } else {
+        relation_vertex(rng)
+    }
+}
+
+fn inline_value(rng: &mut SmallRng) -> Value<'static> {
+    match rng.gen_range(0..3) {
+        0 => Value::Boolean(rng.gen()),
+        1 => Value::Integer(rng.gen()),
+        // random bits cover the full range of doubles, but NaN has no total order
+        _ => loop {
+            let double = f64::from_bits(rng.gen());
+            if !double.is_nan() {
+                break Value::Double(double);
+            }
+        },
+    }
+}
+
+fn attribute_vertex(rng: &mut SmallRng) -> AttributeVertex {
+    AttributeVertex::new(type_id(rng), AttributeID::build_inline(inline_value(rng)))
+}
+
+fn links_edge(rng: &mut SmallRng) -> ThingEdgeLinks {
+    let relation = relation_vertex(rng);
+    let player = object_vertex(rng);
+    let role = role_type_vertex(rng);
+    if rng.gen() {
+        ThingEdgeLinks::new_reverse(player, relation, role)
+    } else {
+        ThingEdgeLinks::new(relation, player, role)
+    }
+}
+
+#[test]
+fn type_vertex_roundtrip() {
+    let mut rng = seeded_rng();
+    for _ in 0..CASES {
+        let vertex = type_vertex(&mut rng);
+        let bytes = vertex.to_bytes();
+        assert_eq!(bytes.length(), TypeVertex::LENGTH);
+        assert_eq!(TypeVertex::decode(bytes), vertex);
+    }
+}
+
+#[test]
+fn type_vertex_order_matches_bytes() {
+    let mut rng = seeded_rng();
+    for _ in 0..CASES {
+        let (first, second) = (type_vertex(&mut rng), type_vertex(&mut rng));
+        assert_eq!(first.cmp(&second), first.to_bytes().cmp(&second.to_bytes()), "{first:?} vs {second:?}");
+    }
+}
+
+#[test]
+fn type_id_roundtrip() {
+    for id in 0..=u16::MAX {
+        let id = TypeID::new(id);
+        assert_eq!(TypeID::decode(id.to_bytes()), id);
+    }
+}
+
+#[test]
+fn object_vertex_roundtrip() {
+    let mut rng = seeded_rng();
+    for _ in 0..CASES {
+        let vertex = object_vertex(&mut rng);
+        let bytes = vertex.to_bytes();
+        assert_eq!(bytes.length(), ObjectVertex::LENGTH);
+        assert_eq!(ObjectVertex::decode(&bytes), vertex);
+        assert_eq!(ObjectVertex::try_decode(&bytes), Some(vertex));
+    }
+}
+
+#[test]
+fn object_vertex_order_matches_bytes() {
+    let mut rng = seeded_rng();
+    for _ in 0..CASES {
+        let (first, second) = (object_vertex(&mut rng), object_vertex(&mut rng));
+        assert_eq!(first.cmp(&second), first.to_bytes().cmp(&second.to_bytes()), "{first:?} vs {second:?}");
+    }
+}
+
+#[test]
+fn attribute_vertex_roundtrip() {
+    let mut rng = seeded_rng();
+    for _ in 0..CASES {
+        let vertex = attribute_vertex(&mut rng);
+        let bytes = vertex.to_bytes();
+        assert_eq!(AttributeVertex::decode(&bytes), vertex);
+        assert_eq!(AttributeVertex::try_decode(&bytes), Some(vertex));
+        assert_eq!(vertex.type_id_(), AttributeVertex::decode(&bytes).type_id_());
+    }
+}
+
+#[test]
+fn attribute_vertex_order_matches_bytes() {
+    let mut rng = seeded_rng();
+    for _ in 0..CASES {
+        let (first, second) = (attribute_vertex(&mut rng), attribute_vertex(&mut rng));
+        assert_eq!(first.cmp(&second), first.to_bytes().cmp(&second.to_bytes()), "{first:?} vs {second:?}");
+    }
+}
+
+#[test]
+fn integer_attribute_order_matches_value_order() {
+    let mut rng = seeded_rng();
+    for _ in 0..CASES {
+        let type_id = type_id(&mut rng);
+        let (first_value, second_value): (i64, i64) = (rng.gen(), rng.gen());
+        let first = AttributeVertex::new(type_id, AttributeID::build_inline(Value::Integer(first_value)));
+        let second = AttributeVertex::new(type_id, AttributeID::build_inline(Value::Integer(second_value)));
+        assert_eq!(
+            first_value.cmp(&second_value),
+            first.to_bytes().cmp(&second.to_bytes()),
+            "{first_value} vs {second_value}"
+        );
+    }
+}
+
+#[test]
+fn has_edge_roundtrip() {
+    let mut rng = seeded_rng();
+    for _ in 0..CASES {
+        let (owner, attribute) = (object_vertex(&mut rng), attribute_vertex(&mut rng));
+        let edge = ThingEdgeHas::new(owner, attribute);
+        let decoded = ThingEdgeHas::decode(edge.to_bytes());
+        assert_eq!(decoded, edge);
+        assert_eq!(decoded.from(), owner);
+        assert_eq!(decoded.to(), attribute);
+
+        let reverse = ThingEdgeHasReverse::new(attribute, owner);
+        let decoded = ThingEdgeHasReverse::decode(reverse.to_bytes());
+        assert_eq!(decoded, reverse);
+        assert_eq!(decoded.from(), attribute);
+        assert_eq!(decoded.to(), owner);
+    }
+}
+
+#[test]
+fn has_edge_order_matches_bytes() {
+    let mut rng = seeded_rng();
+    for _ in 0..CASES {
+        let first = ThingEdgeHas::new(object_vertex(&mut rng), attribute_vertex(&mut rng));
+        let second = ThingEdgeHas::new(object_vertex(&mut rng), attribute_vertex(&mut rng));
+        assert_eq!(first.cmp(&second), first.to_bytes().cmp(&second.to_bytes()), "{first:?} vs {second:?}");
+    }
+}
+
+#[test]
+fn links_edge_roundtrip() {
+    let mut rng = seeded_rng();
+    for _ in 0..CASES {
+        let edge = links_edge(&mut rng);
+        let decoded = ThingEdgeLinks::decode(edge.to_bytes());
+        assert_eq!(decoded, edge);
+        assert_eq!(decoded.relation(), edge.relation());
+        assert_eq!(decoded.player(), edge.player());
+        assert_eq!(decoded.role_id(), edge.role_id());
+        assert_eq!(decoded.is_reverse(), edge.is_reverse());
+    }
+}
+
+#[test]
+fn links_edge_order_matches_bytes() {
+    let mut rng = seeded_rng();
+    for _ in 0..CASES {
+        let (first, second) = (links_edge(&mut rng), links_edge(&mut rng));
+        assert_eq!(first.cmp(&second), first.to_bytes().cmp(&second.to_bytes()), "{first:?} vs {second:?}");
+    }
+}
+
+#[test]
+fn thing_keys_are_described() {
+    let mut rng = seeded_rng();
+    for _ in 0..CASES {
+        let (owner, attribute, edge) = (object_vertex(&mut rng), attribute_vertex(&mut rng), links_edge(&mut rng));
+        let owner_iid = HexBytesFormatter::borrowed(&owner.to_bytes()).format_iid();
+        let attribute_iid = HexBytesFormatter::borrowed(&attribute.to_bytes()).format_iid();
+
+        assert!(describe_thing_key(&owner.to_bytes()).unwrap().contains(&owner_iid));
+        assert!(describe_thing_key(&attribute.to_bytes()).unwrap().contains(&attribute_iid));
+
+        let has_keys =
+            [ThingEdgeHas::new(owner, attribute).to_bytes(), ThingEdgeHasReverse::new(attribute, owner).to_bytes()];
+        for has_key in has_keys {
+            let has_description = describe_thing_key(&has_key).unwrap();
+            assert!(has_description.contains(&owner_iid));
+            assert!(has_description.contains(&attribute_iid));
+        }
+
+        let links_description = describe_thing_key(&edge.to_bytes()).unwrap();
+        assert!(links_description.contains(&HexBytesFormatter::borrowed(&edge.relation().to_bytes()).format_iid()));
+        assert!(links_description.contains(&HexBytesFormatter::borrowed(&edge.player().to_bytes()).format_iid()));
+    }
+}
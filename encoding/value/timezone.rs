@@ -4,7 +4,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::fmt;
+use std::{fmt, str::FromStr};
 
 use chrono::{FixedOffset, MappedLocalTime, NaiveDate, NaiveDateTime};
 use chrono_tz::Tz;
@@ -42,6 +42,17 @@ pub enum TimeZone {
 }
 
 impl TimeZone {
+    /// Parses an IANA zone name (case-insensitive), an ISO offset such as `+01:00`, or `Z`.
+    pub fn parse(name: &str) -> Option<Self> {
+        if let Ok(tz) = Tz::from_str_insensitive(name) {
+            Some(Self::IANA(tz))
+        } else if name == "Z" {
+            FixedOffset::east_opt(0).map(Self::Fixed)
+        } else {
+            FixedOffset::from_str(name).ok().map(Self::Fixed)
+        }
+    }
+
     pub(crate) fn encode(self) -> [u8; 4] {
         match self {
             TimeZone::IANA(tz) => encode_u32(encode_tz(tz)),
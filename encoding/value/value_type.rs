@@ -235,6 +235,23 @@ impl ValueTypeCategory {
         }
     }
 
+    /// The category both operands of a comparison are cast to before being compared, if they are comparable at all.
+    /// Mixed numeric operands are widened the same way the expression compiler widens arithmetic operands.
+    pub fn comparison_category(lhs: ValueTypeCategory, rhs: ValueTypeCategory) -> Option<ValueTypeCategory> {
+        match (lhs, rhs) {
+            _ if lhs == rhs => Some(lhs),
+            (ValueTypeCategory::Integer, ValueTypeCategory::Decimal)
+            | (ValueTypeCategory::Decimal, ValueTypeCategory::Integer) => Some(ValueTypeCategory::Decimal),
+            (ValueTypeCategory::Integer | ValueTypeCategory::Decimal, ValueTypeCategory::Double)
+            | (ValueTypeCategory::Double, ValueTypeCategory::Integer | ValueTypeCategory::Decimal) => {
+                Some(ValueTypeCategory::Double)
+            }
+            (ValueTypeCategory::Date, ValueTypeCategory::DateTime)
+            | (ValueTypeCategory::DateTime, ValueTypeCategory::Date) => Some(ValueTypeCategory::DateTime),
+            _ => None,
+        }
+    }
+
     pub fn try_into_value_type(self) -> Option<ValueType> {
         match self {
             ValueTypeCategory::Boolean => Some(ValueType::Boolean),
@@ -4,18 +4,11 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::{
-    array,
-    borrow::Cow,
-    cmp::Ordering,
-    iter::{Map, Take, Zip},
-    vec,
-};
+use std::{borrow::Cow, cmp::Ordering, sync::Arc};
 
 use answer::{variable_value::VariableValue, Thing};
 use encoding::value::value::Value;
 use error::unimplemented_feature;
-use itertools::Itertools;
 use lending_iterator::LendingIterator;
 use resource::{constants::traversal::FIXED_BATCH_ROWS_MAX, profile::StorageCounters};
 use storage::snapshot::ReadableSnapshot;
@@ -122,29 +115,47 @@ impl<'a> From<MaybeOwnedRow<'a>> for FixedBatch {
 impl IntoIterator for FixedBatch {
     type Item = MaybeOwnedRow<'static>;
 
-    type IntoIter = Map<
-        Take<
-            Zip<
-                vec::IntoIter<Vec<VariableValue<'static>>>,
-                Zip<
-                    array::IntoIter<u64, { FIXED_BATCH_ROWS_MAX as usize }>,
-                    array::IntoIter<Provenance, { FIXED_BATCH_ROWS_MAX as usize }>,
-                >,
-            >,
-        >,
-        fn((Vec<VariableValue<'static>>, (u64, Provenance))) -> MaybeOwnedRow<'static>,
-    >;
-
-    fn into_iter(self) -> Self::IntoIter {
-        let rows = if self.width == 0 {
-            vec![vec![]; self.entries as usize]
-        } else {
-            self.data.into_iter().chunks(self.width as usize).into_iter().map(|chunk| chunk.collect_vec()).collect_vec()
-        };
-        rows.into_iter()
-            .zip(self.multiplicities.into_iter().zip(self.provenance.into_iter()))
-            .take(self.entries as usize)
-            .map(|(row, (mult, provenance))| MaybeOwnedRow::new_owned(row, mult, provenance))
+    type IntoIter = FixedBatchIntoIterator;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        // the rows share the batch's values, moved once into a single allocation
+        self.data.truncate(self.entries as usize * self.width as usize);
+        FixedBatchIntoIterator {
+            arena: Arc::from(self.data),
+            width: self.width,
+            entries: self.entries,
+            index: 0,
+            multiplicities: self.multiplicities,
+            provenance: self.provenance,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FixedBatchIntoIterator {
+    arena: Arc<[VariableValue<'static>]>,
+    width: u32,
+    entries: u32,
+    index: u32,
+    multiplicities: [u64; FIXED_BATCH_ROWS_MAX as usize],
+    provenance: [Provenance; FIXED_BATCH_ROWS_MAX as usize],
+}
+
+impl Iterator for FixedBatchIntoIterator {
+    type Item = MaybeOwnedRow<'static>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.entries {
+            return None;
+        }
+        let index = self.index as usize;
+        self.index += 1;
+        Some(MaybeOwnedRow::new_in_arena(
+            self.arena.clone(),
+            row_range(index, self.width),
+            self.multiplicities[index],
+            self.provenance[index],
+        ))
     }
 }
 
@@ -211,6 +222,13 @@ impl Batch {
         &self.multiplicities
     }
 
+    /// The bytes allocated for rows, not counting values that own further allocations.
+    pub(crate) fn allocated_bytes(&self) -> u64 {
+        (self.data.capacity() * std::mem::size_of::<VariableValue<'static>>()
+            + self.multiplicities.capacity() * std::mem::size_of::<u64>()
+            + self.provenance.capacity() * std::mem::size_of::<Provenance>()) as u64
+    }
+
     pub(crate) fn get_row(&self, index: usize) -> MaybeOwnedRow<'_> {
         debug_assert!(index < self.len());
         let slice = &self.data[row_range(index, self.width)];
@@ -17,5 +17,6 @@ typedb_error! {
         CreatingIterator(3, "Error creating iterator from {instruction_name} instruction.", instruction_name: String, typedb_source: Box<ConceptReadError>),
         AdvancingIteratorTo(4, "Error moving iterator (by steps or seek) to target value.", typedb_source: Box<ConceptReadError>),
         ExpressionEvaluate(5, "Error evaluating expression.", typedb_source: ExpressionEvaluationError),
+        ReduceListSizeLimitExceeded(6, "A list reducer collected more than the limit of {limit} values for one group.", limit: usize),
     }
 }
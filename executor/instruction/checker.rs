@@ -13,6 +13,7 @@ use std::{
 use answer::{variable_value::VariableValue, Thing, Type};
 use bytes::byte_array::ByteArray;
 use compiler::{
+    annotation::type_annotations::ComparisonValueTypes,
     executable::match_::instructions::{CheckInstruction, CheckVertex},
     ExecutorVariable,
 };
@@ -23,7 +24,7 @@ use concept::{
 };
 use encoding::{
     graph::thing::THING_VERTEX_MAX_LENGTH,
-    value::{value::Value, ValueEncodable},
+    value::{value::Value, value_type::ValueTypeCategory, ValueEncodable},
     AsBytes,
 };
 use error::unimplemented_feature;
@@ -102,7 +103,7 @@ impl<T> Checker<T> {
         for i in 0..self.checks.len() {
             let check = &self.checks[i];
             match check {
-                CheckInstruction::Comparison { lhs, rhs, comparator } => {
+                CheckInstruction::Comparison { lhs, rhs, comparator, .. } => {
                     if lhs.as_variable() == Some(target_variable) {
                         let rhs_variable_value = get_vertex_value(rhs, row.as_ref(), &context.parameters);
                         let rhs_value = Self::read_value(
@@ -259,9 +260,16 @@ impl<T> Checker<T> {
                 CheckInstruction::LinksDeduplication { role1, player1, role2, player2 } => {
                     self.filter_links_dedup(row, &source, *role1, *player1, *role2, *player2)
                 }
-                CheckInstruction::Comparison { lhs, rhs, comparator } => {
-                    self.filter_comparison(context, row, &source, lhs, rhs, *comparator, storage_counters.clone())?
-                }
+                CheckInstruction::Comparison { lhs, rhs, comparator, value_types } => self.filter_comparison(
+                    context,
+                    row,
+                    &source,
+                    lhs,
+                    rhs,
+                    *comparator,
+                    value_types.as_ref(),
+                    storage_counters.clone(),
+                )?,
                 CheckInstruction::NotNone { variables } => Self::filter_not_none(row, variables),
                 CheckInstruction::Unsatisfiable => false,
             };
@@ -326,9 +334,15 @@ impl<T> Checker<T> {
                     // self.filter_not(context, row, check)
                 }
                 &CheckInstruction::Is { lhs, rhs } => self.filter_is_fn(row, lhs, rhs),
-                CheckInstruction::Comparison { lhs, rhs, comparator } => {
-                    self.filter_comparison_fn(context, row, lhs, rhs, *comparator, storage_counters.clone())
-                }
+                CheckInstruction::Comparison { lhs, rhs, comparator, value_types } => self.filter_comparison_fn(
+                    context,
+                    row,
+                    lhs,
+                    rhs,
+                    *comparator,
+                    value_types.clone(),
+                    storage_counters.clone(),
+                ),
                 CheckInstruction::Unsatisfiable => Box::new(|_: &T| Ok(false)),
             };
             filters.push(filter);
@@ -1125,6 +1139,7 @@ impl<T> Checker<T> {
         lhs: &CheckVertex<ExecutorVariable>,
         rhs: &CheckVertex<ExecutorVariable>,
         comparator: Comparator,
+        value_types: Option<ComparisonValueTypes>,
         storage_counters: StorageCounters,
     ) -> Box<dyn Fn(&T) -> Result<bool, Box<ConceptReadError>>> {
         let maybe_lhs_extractor = lhs.as_variable().and_then(|var| self.extractors.get(&var));
@@ -1162,13 +1177,7 @@ impl<T> Checker<T> {
                 VariableValue::None | VariableValue::Type(_) | VariableValue::Thing(_) => unreachable!(),
             };
             let rhs = rhs.clone()?;
-            if rhs.value_type().is_trivially_castable_to(lhs.value_type().category()) {
-                Ok(Self::cmp_values_fn(&comparator)(&lhs, &rhs.cast(lhs.value_type().category()).unwrap()))
-            } else if lhs.value_type().is_trivially_castable_to(rhs.value_type().category()) {
-                Ok(Self::cmp_values_fn(&comparator)(&lhs.cast(rhs.value_type().category()).unwrap(), &rhs))
-            } else {
-                Ok(false)
-            }
+            Ok(Self::compare_values(comparator, value_types.as_ref(), lhs, rhs))
         })
     }
 
@@ -1180,6 +1189,7 @@ impl<T> Checker<T> {
         lhs: &CheckVertex<ExecutorVariable>,
         rhs: &CheckVertex<ExecutorVariable>,
         comparator: Comparator,
+        value_types: Option<&ComparisonValueTypes>,
         storage_counters: StorageCounters,
     ) -> Result<bool, Box<ConceptReadError>> {
         let lhs = match lhs.as_variable().and_then(|var| self.extractors.get(&var)) {
@@ -1210,13 +1220,26 @@ impl<T> Checker<T> {
             VariableValue::ThingList(_) | VariableValue::ValueList(_) => unimplemented_feature!(Lists),
             VariableValue::None | VariableValue::Type(_) | VariableValue::Thing(_) => unreachable!(),
         };
-        if rhs.value_type().is_trivially_castable_to(lhs.value_type().category()) {
-            Ok(Self::cmp_values_fn(&comparator)(&lhs, &rhs.cast(lhs.value_type().category()).unwrap()))
-        } else if lhs.value_type().is_trivially_castable_to(rhs.value_type().category()) {
-            Ok(Self::cmp_values_fn(&comparator)(&lhs.cast(rhs.value_type().category()).unwrap(), &rhs))
-        } else {
-            Ok(false)
-        }
+        Ok(Self::compare_values(comparator, value_types, lhs, rhs))
+    }
+
+    /// Operands are cast to the value type the annotations resolved for their pair of value types, and pairs without
+    /// one never match. Without annotations, the operands are cast by the same rules at runtime.
+    fn compare_values(
+        comparator: Comparator,
+        value_types: Option<&ComparisonValueTypes>,
+        lhs: Value<'_>,
+        rhs: Value<'_>,
+    ) -> bool {
+        let (lhs_category, rhs_category) = (lhs.value_type().category(), rhs.value_type().category());
+        let category = match value_types {
+            Some(value_types) => value_types.cast_of(lhs_category, rhs_category),
+            None => ValueTypeCategory::comparison_category(lhs_category, rhs_category),
+        };
+        let Some(category) = category else {
+            return false;
+        };
+        Self::cmp_values_fn(&comparator)(&cast_for_comparison(lhs, category), &cast_for_comparison(rhs, category))
     }
 
     fn cmp_values_fn(comparator: &Comparator) -> fn(&Value<'_>, &Value<'_>) -> bool {
@@ -1242,6 +1265,14 @@ impl<T> Checker<T> {
     }
 }
 
+fn cast_for_comparison(value: Value<'_>, category: ValueTypeCategory) -> Value<'_> {
+    if value.value_type().category() == category {
+        value
+    } else {
+        value.cast(category).unwrap()
+    }
+}
+
 fn make_const_extractor<T>(
     vertex: &CheckVertex<ExecutorVariable>,
     row: &MaybeOwnedRow<'_>,
@@ -9,7 +9,7 @@ use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
     fmt,
     iter::Iterator,
-    ops::Bound,
+    ops::{Bound, RangeBounds},
     sync::Arc,
 };
 
@@ -33,6 +33,7 @@ use resource::{constants::traversal::CONSTANT_CONCEPT_LIMIT, profile::StorageCou
 use storage::snapshot::ReadableSnapshot;
 
 use crate::{
+    batch::FixedBatch,
     instruction::{
         checker::Checker,
         iterator::{SortedTupleIterator, TupleIterator, TupleSeekable},
@@ -59,9 +60,16 @@ pub(crate) struct HasExecutor {
     ordered_value_type_categories: Vec<ValueTypeCategory>,
     filter_fn: Arc<HasFilterFn>,
     owner_cache: Option<Vec<Object>>,
+    owner_batch: Option<HasOwnerBatch>,
     checker: Checker<(Has, u64)>,
 }
 
+/// The ownerships of the distinct owners of an input batch, read in a single storage scan.
+struct HasOwnerBatch {
+    owners: Vec<Object>,
+    edges: Arc<[(Has, u64)]>,
+}
+
 impl fmt::Debug for HasExecutor {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "HasExecutor")
@@ -70,6 +78,7 @@ impl fmt::Debug for HasExecutor {
 
 pub(crate) type HasTupleIteratorSingle = HasTupleIterator<HasIterator>;
 pub(crate) type HasTupleIteratorMerged = KMergeBy<HasTupleIterator<HasIterator>, TupleOrderingFn>;
+pub(crate) type HasTupleIteratorBatched = HasTupleIterator<HasBatchIterator>;
 
 pub(super) type HasFilterFn = FilterFn<(Has, u64)>;
 pub(super) type HasFilterMapFn = FilterMapUnchangedFn<(Has, u64)>;
@@ -86,6 +95,7 @@ impl HasExecutor {
         sort_by: ExecutorVariable,
         snapshot: &Snapshot,
         thing_manager: &ThingManager,
+        storage_counters: StorageCounters,
     ) -> Result<Self, Box<ConceptReadError>> {
         debug_assert!(!variable_modes.all_inputs());
         let owner_attribute_types = has.owner_to_attribute_types().clone();
@@ -128,7 +138,7 @@ impl HasExecutor {
                 let instances: Vec<_> = Itertools::try_collect(thing_manager.get_objects_in(
                     snapshot,
                     type_.as_object_type(),
-                    StorageCounters::DISABLED,
+                    storage_counters.clone(),
                 ))?;
                 cache.extend(instances);
             }
@@ -165,10 +175,41 @@ impl HasExecutor {
             ordered_value_type_categories: possible_attribute_value_categories,
             filter_fn,
             owner_cache,
+            owner_batch: None,
             checker,
         })
     }
 
+    /// In BoundFrom mode, reads the ownerships of every owner in the input batch in one range scan,
+    /// keeping those of the executor's attribute types, so that the iterators for the rows of the batch do not each scan storage.
+    pub(crate) fn prepare_batch(
+        &mut self,
+        context: &ExecutionContext<impl ReadableSnapshot + 'static>,
+        batch: &FixedBatch,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptReadError>> {
+        self.owner_batch = None;
+        if self.iterate_mode != BinaryIterateMode::BoundFrom {
+            return Ok(());
+        }
+        let owner = self.has.owner().as_variable().unwrap().as_position().unwrap();
+        let owners: Vec<Object> = (0..batch.len())
+            .map(|index| batch.get_row(index).get(owner).as_thing().as_object())
+            .sorted()
+            .dedup()
+            .collect();
+        if owners.len() < 2 {
+            return Ok(());
+        }
+        let edges: Vec<(Has, u64)> = context
+            .thing_manager()
+            .get_has_from_owners_unordered(&**context.snapshot(), owners.clone(), storage_counters)
+            .filter_ok(|(has, _)| self.attribute_type_range.contains(&has.attribute().type_()))
+            .try_collect()?;
+        self.owner_batch = Some(HasOwnerBatch { owners, edges: edges.into() });
+        Ok(())
+    }
+
     pub(crate) fn get_iterator(
         &self,
         context: &ExecutionContext<impl ReadableSnapshot + 'static>,
@@ -198,17 +239,41 @@ impl HasExecutor {
         match self.iterate_mode {
             BinaryIterateMode::Unbound => {
                 // TODO: we could cache the range byte arrays computed inside the thing_manager, for this case
-
-                // TODO: in the HasReverse case, we look up N iterators (one per type) and link them - here we scan and post-filter
-                //        we should determine which strategy we want long-term
+                let attribute_type_lower_bound_inclusive =
+                    ThingManager::start_type_bound_to_range_start_included_type(self.attribute_type_range.0.as_ref())
+                        .unwrap_or(AttributeType::MIN);
+                if self.owner_attribute_types.len() > 1 {
+                    // scan each owner type's range, rather than the whole range between the owner types, and merge
+                    let iterators = self.owner_attribute_types.keys().map(|owner_type| {
+                        let owner_type = owner_type.as_object_type();
+                        HasTupleIterator::new(
+                            thing_manager.get_has_from_owner_type_range_unordered(
+                                snapshot,
+                                &(Bound::Included(owner_type), Bound::Included(owner_type)),
+                                storage_counters.clone(),
+                            ),
+                            filter_for_row.clone(),
+                            has_to_tuple_owner_attribute,
+                            tuple_owner_attribute_to_has_canonical,
+                            FixedHasBounds::NoneWithLowerBounds(
+                                attribute_type_lower_bound_inclusive,
+                                value_range.0.clone().map(|v| v.into_owned()),
+                            ),
+                        )
+                    });
+                    let merged_tuples: KMergeBy<HasTupleIterator<HasIterator>, TupleOrderingFn> =
+                        KMergeBy::new(iterators, unsafe_compare_result_tuple);
+                    return Ok(TupleIterator::HasMerged(SortedTupleIterator::new(
+                        merged_tuples,
+                        self.tuple_positions.clone(),
+                        &self.variable_modes,
+                    )));
+                }
                 let has_iterator: HasIterator = thing_manager.get_has_from_owner_type_range_unordered(
                     snapshot,
                     &self.owner_type_range,
                     storage_counters,
                 );
-                let attribute_type_lower_bound_inclusive =
-                    ThingManager::start_type_bound_to_range_start_included_type(self.attribute_type_range.0.as_ref())
-                        .unwrap_or(AttributeType::MIN);
                 let as_tuples = HasTupleIterator::new(
                     has_iterator,
                     filter_for_row,
@@ -290,6 +355,25 @@ impl HasExecutor {
             BinaryIterateMode::BoundFrom => {
                 let owner = self.has.owner().as_variable().unwrap().as_position().unwrap();
                 debug_assert!(row.len() > owner.as_usize());
+                let owner_object = row.get(owner).as_thing().as_object();
+                if let Some(batch) =
+                    self.owner_batch.as_ref().filter(|batch| batch.owners.binary_search(&owner_object).is_ok())
+                {
+                    let start = batch.edges.partition_point(|(has, _)| has.owner() < owner_object);
+                    let end = batch.edges.partition_point(|(has, _)| has.owner() <= owner_object);
+                    let as_tuples = HasTupleIterator::new(
+                        HasBatchIterator { edges: batch.edges.clone(), index: start, end },
+                        filter_for_row,
+                        has_to_tuple_attribute_owner,
+                        tuple_attribute_owner_to_has_canonical,
+                        FixedHasBounds::Owner(owner_object),
+                    );
+                    return Ok(TupleIterator::HasBatched(SortedTupleIterator::new(
+                        as_tuples,
+                        self.tuple_positions.clone(),
+                        &self.variable_modes,
+                    )));
+                }
                 let iterator = match row.get(owner) {
                     VariableValue::Thing(Thing::Entity(entity)) => entity
                         .get_has_types_range_unordered_in_value_types(
@@ -316,7 +400,7 @@ impl HasExecutor {
                     filter_for_row,
                     has_to_tuple_attribute_owner,
                     tuple_attribute_owner_to_has_canonical,
-                    FixedHasBounds::Owner(row.get(owner).as_thing().as_object()),
+                    FixedHasBounds::Owner(owner_object),
                 );
                 Ok(TupleIterator::HasSingle(SortedTupleIterator::new(
                     as_tuples,
@@ -334,6 +418,42 @@ impl fmt::Display for HasExecutor {
     }
 }
 
+/// Iterates the ownerships of one owner out of those read for an input batch.
+pub(crate) struct HasBatchIterator {
+    edges: Arc<[(Has, u64)]>,
+    index: usize,
+    end: usize,
+}
+
+impl LendingIterator for HasBatchIterator {
+    type Item<'a> = Result<(Has, u64), Box<ConceptReadError>>;
+
+    fn next(&mut self) -> Option<Self::Item<'_>> {
+        if self.index < self.end {
+            self.index += 1;
+            Some(Ok(self.edges[self.index - 1].clone()))
+        } else {
+            None
+        }
+    }
+}
+
+impl lending_iterator::Seekable<Result<(Has, u64), Box<ConceptReadError>>> for HasBatchIterator {
+    fn seek(&mut self, key: &Result<(Has, u64), Box<ConceptReadError>>) {
+        if let Ok((target, _)) = key {
+            self.index += self.edges[self.index..self.end].partition_point(|(has, _)| has < target);
+        }
+    }
+
+    fn compare_key(&self, item: &Self::Item<'_>, other_item: &Result<(Has, u64), Box<ConceptReadError>>) -> Ordering {
+        match (item, other_item) {
+            (Ok((has, _)), Ok((other_has, _))) => has.cmp(other_has),
+            // arbitrary
+            _ => Ordering::Equal,
+        }
+    }
+}
+
 pub(crate) enum FixedHasBounds {
     NoneWithLowerBounds(AttributeType, Bound<Value<'static>>),
     Owner(Object),
@@ -4,13 +4,17 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::{collections::HashMap, fmt, iter, sync::Arc};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt, iter,
+    sync::Arc,
+};
 
-use answer::variable_value::VariableValue;
+use answer::{variable_value::VariableValue, Thing, Type};
 use compiler::{executable::match_::instructions::thing::IidInstruction, ExecutorVariable};
 use concept::{
     error::ConceptReadError,
-    thing::{attribute::Attribute, object::Object, ThingAPI},
+    thing::{attribute::Attribute, object::Object, thing_manager::ThingManager, ThingAPI},
 };
 use encoding::graph::thing::{vertex_attribute::AttributeVertex, vertex_object::ObjectVertex, ThingVertex};
 use ir::pattern::constraint::Iid;
@@ -23,7 +27,7 @@ use crate::{
         checker::Checker,
         iterator::{NaiiveSeekable, SortedTupleIterator, TupleIterator},
         tuple::{Tuple, TuplePositions, TupleResult},
-        FilterFn, FilterMapUnchangedFn, VariableModes,
+        FilterMapUnchangedFn, VariableModes,
     },
     pipeline::stage::ExecutionContext,
     row::MaybeOwnedRow,
@@ -31,9 +35,9 @@ use crate::{
 
 pub(crate) struct IidExecutor {
     iid: Iid<ExecutorVariable>,
+    types: Arc<BTreeSet<Type>>,
     variable_modes: VariableModes,
     tuple_positions: TuplePositions,
-    filter_fn: Arc<IidFilterFn>,
     checker: Checker<VariableValue<'static>>,
 }
 
@@ -48,7 +52,6 @@ pub(crate) type IidToTupleFn = fn(Result<VariableValue<'static>, Box<ConceptRead
 pub(super) type IidTupleIterator<I> =
     NaiiveSeekable<AsLendingIterator<iter::Map<iter::FilterMap<I, Box<IidFilterMapFn>>, IidToTupleFn>>>;
 
-pub(super) type IidFilterFn = FilterFn<VariableValue<'static>>;
 pub(super) type IidFilterMapFn = FilterMapUnchangedFn<VariableValue<'static>>;
 
 pub(crate) type IidIterator =
@@ -77,12 +80,7 @@ impl IidExecutor {
         let output_tuple_positions = TuplePositions::Single([Some(var)]);
         let checker = Checker::<VariableValue<'_>>::new(checks, HashMap::from_iter([(var, EXTRACT_IDENTITY)]));
 
-        let filter_fn: Arc<IidFilterFn> = Arc::new(move |res| match res {
-            Ok(value) => Ok(types.contains(&value.as_thing().type_())),
-            Err(err) => Err(err.clone()),
-        });
-
-        Self { iid, variable_modes, tuple_positions: output_tuple_positions, filter_fn, checker }
+        Self { iid, types, variable_modes, tuple_positions: output_tuple_positions, checker }
     }
 
     pub(crate) fn get_iterator(
@@ -91,15 +89,10 @@ impl IidExecutor {
         row: MaybeOwnedRow<'_>,
         storage_counters: StorageCounters,
     ) -> Result<TupleIterator, Box<ConceptReadError>> {
-        let filter = self.filter_fn.clone();
         let check = self.checker.filter_fn_for_row(context, &row, storage_counters.clone());
-        let filter_for_row: Box<IidFilterMapFn> = Box::new(move |item| match filter(&item) {
-            Ok(true) => match check(&item) {
-                Ok(true) | Err(_) => Some(item),
-                Ok(false) => None,
-            },
+        let filter_for_row: Box<IidFilterMapFn> = Box::new(move |item| match check(&item) {
+            Ok(true) | Err(_) => Some(item),
             Ok(false) => None,
-            Err(_) => Some(item),
         });
 
         let snapshot = &**context.snapshot();
@@ -109,15 +102,9 @@ impl IidExecutor {
         let bytes = context.parameters().iid(iid_parameter).unwrap();
 
         let instance = if let Some(object) = ObjectVertex::try_decode(bytes) {
-            let object = Object::new(object);
-            thing_manager
-                .instance_exists(snapshot, &object, storage_counters.clone())
-                .map(move |exists| exists.then_some(VariableValue::Thing(object.into())))
+            self.lookup_instance(snapshot, thing_manager, Object::new(object), storage_counters)
         } else if let Some(attribute) = AttributeVertex::try_decode(bytes) {
-            let attribute = Attribute::new(attribute);
-            thing_manager
-                .instance_exists(snapshot, &attribute, storage_counters.clone())
-                .map(move |exists| exists.then_some(VariableValue::Thing(attribute.clone().into())))
+            self.lookup_instance(snapshot, thing_manager, Attribute::new(attribute), storage_counters)
         } else {
             Ok(None)
         };
@@ -131,6 +118,23 @@ impl IidExecutor {
             &self.variable_modes,
         )))
     }
+
+    /// The IID encodes the type of the instance, so IIDs of types excluded by type inference
+    /// are rejected without reading storage.
+    fn lookup_instance<T: ThingAPI + Clone + Into<Thing>>(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        instance: T,
+        storage_counters: StorageCounters,
+    ) -> Result<Option<VariableValue<'static>>, Box<ConceptReadError>> {
+        let thing: Thing = instance.clone().into();
+        if !self.types.contains(&thing.type_()) {
+            return Ok(None);
+        }
+        let exists = thing_manager.instance_exists(snapshot, &instance, storage_counters)?;
+        Ok(exists.then_some(VariableValue::Thing(thing)))
+    }
 }
 
 impl fmt::Display for IidExecutor {
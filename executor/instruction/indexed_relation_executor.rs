@@ -113,6 +113,7 @@ impl IndexedRelationExecutor {
         sort_by: ExecutorVariable,
         snapshot: &impl ReadableSnapshot,
         thing_manager: &ThingManager,
+        storage_counters: StorageCounters,
     ) -> Result<Self, Box<ConceptReadError>> {
         debug_assert!(!variable_modes.all_inputs());
 
@@ -198,7 +199,7 @@ impl IndexedRelationExecutor {
                 let instances: Vec<Object> = Itertools::try_collect(thing_manager.get_objects_in(
                     snapshot,
                     type_.as_object_type(),
-                    StorageCounters::DISABLED,
+                    storage_counters.clone(),
                 ))?;
                 cache.extend(instances);
             }
@@ -132,12 +132,19 @@ impl IsaExecutor {
                 } else {
                     (Bound::Unbounded, Bound::Unbounded)
                 };
+                let start_after = self
+                    .isa
+                    .thing()
+                    .as_variable()
+                    .and_then(|thing_variable| thing_variable.as_position())
+                    .and_then(|position| context.start_after_for(position));
                 let thing_iter = instances_of_all_types_chained(
                     snapshot,
                     thing_manager,
                     self.instance_type_to_types.as_ref(),
                     self.isa.isa_kind(),
                     instances_range,
+                    start_after,
                     storage_counters,
                 )?;
                 let as_tuples = IsaUnboundedSortedThing { inner: thing_iter, filter_map: filter_for_row };
@@ -155,9 +162,14 @@ impl IsaExecutor {
                     unreachable!("Has thing must be an entity or relation.")
                 };
                 let type_ = thing.type_();
-                let supertypes = self.instance_type_to_types.get(&type_).cloned().unwrap_or(TYPES_EMPTY);
+                let types = match self.isa.isa_kind() {
+                    // the thing's own type is the only one `isa!` can bind, so there are no supertypes to copy out
+                    IsaKind::Exact if self.instance_type_to_types.contains_key(&type_) => vec![type_],
+                    IsaKind::Exact => TYPES_EMPTY,
+                    IsaKind::Subtype => self.instance_type_to_types.get(&type_).cloned().unwrap_or(TYPES_EMPTY),
+                };
                 let as_tuples = iter::repeat(thing)
-                    .zip(supertypes)
+                    .zip(types)
                     .map(Ok as _)
                     .filter_map(filter_for_row)
                     .map(isa_to_tuple_type_thing as _);
@@ -469,29 +481,39 @@ impl Iterator for IsaAttributeIterator {
     }
 }
 
+/// Chains the instances of every type, resuming strictly after `start_after` if it is given:
+/// the types ordered before its type are skipped, and the scan of its own type resumes after it.
 pub(super) fn instances_of_all_types_chained(
     snapshot: &impl ReadableSnapshot,
     thing_manager: &ThingManager,
     instance_types_to_types: &BTreeMap<Type, Vec<Type>>,
     isa_kind: IsaKind,
     instance_values_range: (Bound<Value<'_>>, Bound<Value<'_>>),
+    start_after: Option<&Thing>,
     storage_counters: StorageCounters,
 ) -> Result<MultipleTypeIsaIterator, Box<ConceptReadError>> {
     // TODO: this method contains a lot of heap allocations - we clone the Vec<Type> each time!
 
     // object types and attribute types will continue to be sorted, based on their source in the BTreeMap
-    let (attribute_types, object_types) =
-        instance_types_to_types.iter().partition::<Vec<_>, _>(|(type_, _)| matches!(type_, Type::Attribute(_)));
+    let (attribute_types, object_types) = instance_types_to_types
+        .iter()
+        .filter(|(type_, _)| !start_after.is_some_and(|after| **type_ < after.type_()))
+        .partition::<Vec<_>, _>(|(type_, _)| matches!(type_, Type::Attribute(_)));
 
     let object_iters = object_types
         .into_iter()
         .map(|(&type_, types)| {
             let returned_types = if matches!(isa_kind, IsaKind::Subtype) { types.clone() } else { vec![type_] };
-            IsaObjectIterator::new(
-                thing_manager.get_objects_in(snapshot, type_.as_object_type(), storage_counters.clone()),
-                type_,
-                returned_types,
-            )
+            let objects = match start_after.filter(|after| after.type_() == type_) {
+                Some(after) => thing_manager.get_objects_in_after(
+                    snapshot,
+                    type_.as_object_type(),
+                    after.as_object(),
+                    storage_counters.clone(),
+                ),
+                None => thing_manager.get_objects_in(snapshot, type_.as_object_type(), storage_counters.clone()),
+            };
+            IsaObjectIterator::new(objects, type_, returned_types)
         })
         .collect();
 
@@ -504,14 +526,30 @@ pub(super) fn instances_of_all_types_chained(
         })
         .map(|(&type_, types)| {
             let returned_types = if matches!(isa_kind, IsaKind::Subtype) { types.clone() } else { vec![type_] };
-            thing_manager
-                .get_attributes_in_range(
+            let attribute_type = type_.as_attribute_type();
+            let attributes = match start_after.filter(|after| after.type_() == type_) {
+                Some(Thing::Attribute(after))
+                    if matches!(instance_values_range, (Bound::Unbounded, Bound::Unbounded)) =>
+                {
+                    thing_manager.get_attributes_in_after(snapshot, attribute_type, after, storage_counters.clone())
+                }
+                Some(Thing::Attribute(after)) => thing_manager
+                    .get_attributes_in_range(snapshot, attribute_type, &instance_values_range, storage_counters.clone())
+                    .and_then(|mut attributes| {
+                        // the value range bounds the scan, so the attributes up to `after` within it are skipped
+                        while attributes.peek().transpose()?.is_some_and(|attribute| &attribute <= after) {
+                            attributes.next().transpose()?;
+                        }
+                        Ok(attributes)
+                    }),
+                _ => thing_manager.get_attributes_in_range(
                     snapshot,
-                    type_.as_attribute_type(),
+                    attribute_type,
                     &instance_values_range,
                     storage_counters.clone(),
-                )
-                .map(|iterator| IsaAttributeIterator::new(iterator, type_, returned_types))
+                ),
+            };
+            attributes.map(|iterator| IsaAttributeIterator::new(iterator, type_, returned_types))
         })
         .try_collect()?;
 
@@ -158,17 +158,17 @@ pub(super) fn instances_of_types_chained(
     range: &(Bound<Value<'_>>, Bound<Value<'_>>),
     storage_counters: StorageCounters,
 ) -> Result<MultipleTypeIsaReverseIterator, Box<ConceptReadError>> {
+    if isa_kind == IsaKind::Exact {
+        return exact_instances_of_types_chained(snapshot, thing_manager, types, range, storage_counters);
+    }
+
     let (attribute_types, object_types) =
         types.into_iter().partition::<Vec<_>, _>(|type_| matches!(type_, Type::Attribute(_)));
 
     let object_iters = object_types
         .into_iter()
         .flat_map(|type_| {
-            let returned_types = if matches!(isa_kind, IsaKind::Subtype) {
-                type_to_instance_types.get(&type_).unwrap_or(const { &Vec::new() }).clone()
-            } else {
-                vec![type_]
-            };
+            let returned_types = type_to_instance_types.get(&type_).unwrap_or(const { &Vec::new() }).clone();
             returned_types.into_iter().map({
                 let counters = storage_counters.clone();
                 move |subtype| {
@@ -185,11 +185,7 @@ pub(super) fn instances_of_types_chained(
     let attribute_iters = attribute_types
         .into_iter()
         .flat_map(|type_| {
-            let returned_types = if matches!(isa_kind, IsaKind::Subtype) {
-                type_to_instance_types.get(&type_).unwrap_or(const { &Vec::new() }).clone()
-            } else {
-                vec![type_]
-            };
+            let returned_types = type_to_instance_types.get(&type_).unwrap_or(const { &Vec::new() }).clone();
             returned_types.into_iter().map({
                 let counters = storage_counters.clone();
                 move |subtype| {
@@ -209,6 +205,44 @@ pub(super) fn instances_of_types_chained(
     Ok(thing_iter)
 }
 
+/// `isa!` only ever matches instances of the given types themselves, so each type is read straight off its own
+/// instance prefix without looking up or scanning any of its subtypes.
+fn exact_instances_of_types_chained(
+    snapshot: &impl ReadableSnapshot,
+    thing_manager: &ThingManager,
+    types: impl Iterator<Item = Type>,
+    range: &(Bound<Value<'_>>, Bound<Value<'_>>),
+    storage_counters: StorageCounters,
+) -> Result<MultipleTypeIsaReverseIterator, Box<ConceptReadError>> {
+    let (attribute_types, object_types) =
+        types.into_iter().partition::<Vec<_>, _>(|type_| matches!(type_, Type::Attribute(_)));
+
+    let object_iters = object_types
+        .into_iter()
+        .map(|type_| {
+            IsaReverseObjectIterator::new(
+                thing_manager.get_objects_in(snapshot, type_.as_object_type(), storage_counters.clone()),
+                type_,
+            )
+        })
+        .collect_vec();
+
+    let attribute_iters = attribute_types
+        .into_iter()
+        .map(|type_| {
+            let iter = thing_manager.get_attributes_in_range(
+                snapshot,
+                type_.as_attribute_type(),
+                range,
+                storage_counters.clone(),
+            )?;
+            Ok::<_, Box<_>>(IsaReverseAttributeIterator::new(iter, type_))
+        })
+        .try_collect()?;
+
+    Ok(MultipleTypeIsaReverseIterator::new(object_iters, attribute_iters))
+}
+
 pub(crate) struct IsaReverseBoundedSortedThing {
     inner: MultipleTypeIsaReverseIterator,
     filter_map: Box<IsaFilterMapFn>,
@@ -21,7 +21,7 @@ use lending_iterator::{adaptors::Inspect, kmerge::KMergeBy, LendingIterator, Pee
 
 use crate::{
     instruction::{
-        has_executor::{HasTupleIteratorMerged, HasTupleIteratorSingle},
+        has_executor::{HasTupleIteratorBatched, HasTupleIteratorMerged, HasTupleIteratorSingle},
         has_reverse_executor::{HasReverseTupleIteratorMerged, HasReverseTupleIteratorSingle},
         iid_executor::IidIterator,
         indexed_relation_executor::{IndexedRelationTupleIteratorMerged, IndexedRelationTupleIteratorSingle},
@@ -214,6 +214,7 @@ pub(crate) enum TupleIterator {
 
     HasSingle(SortedTupleIterator<HasTupleIteratorSingle>),
     HasMerged(SortedTupleIterator<HasTupleIteratorMerged>),
+    HasBatched(SortedTupleIterator<HasTupleIteratorBatched>),
 
     HasReverseSingle(SortedTupleIterator<HasReverseTupleIteratorSingle>),
     HasReverseMerged(SortedTupleIterator<HasReverseTupleIteratorMerged>),
@@ -279,6 +280,7 @@ impl Display for TupleIterator {
             TupleIterator::IsaReverseBounded(_) => write!(f, "IsaReverseBounded iterator"),
             TupleIterator::HasSingle(_) => write!(f, "HasSingle iterator"),
             TupleIterator::HasMerged(_) => write!(f, "HasMerged iterator"),
+            TupleIterator::HasBatched(_) => write!(f, "HasBatched iterator"),
             TupleIterator::HasReverseSingle(_) => write!(f, "HasReverseSingle iterator"),
             TupleIterator::HasReverseMerged(_) => write!(f, "HasReverseMerged iterator"),
             TupleIterator::LinksSingle(_) => write!(f, "LinksSingle iterator"),
@@ -96,6 +96,7 @@ impl LinksExecutor {
         sort_by: ExecutorVariable,
         snapshot: &impl ReadableSnapshot,
         thing_manager: &ThingManager,
+        storage_counters: StorageCounters,
     ) -> Result<Self, Box<ConceptReadError>> {
         debug_assert!(!variable_modes.all_inputs());
         let relation_player_types = links.relation_to_player_types().clone();
@@ -139,7 +140,7 @@ impl LinksExecutor {
                 let instances: Vec<Relation> = Itertools::try_collect(thing_manager.get_relations_in(
                     snapshot,
                     type_.as_relation_type(),
-                    StorageCounters::DISABLED,
+                    storage_counters.clone(),
                 ))?;
                 cache.extend(instances);
             }
@@ -84,6 +84,7 @@ impl LinksReverseExecutor {
         sort_by: ExecutorVariable,
         snapshot: &impl ReadableSnapshot,
         thing_manager: &ThingManager,
+        storage_counters: StorageCounters,
     ) -> Result<Self, Box<ConceptReadError>> {
         debug_assert!(!variable_modes.all_inputs());
         let player_relation_types = links_reverse.player_to_relation_types().clone();
@@ -131,7 +132,7 @@ impl LinksReverseExecutor {
                 let instances: Vec<Object> = Itertools::try_collect(thing_manager.get_objects_in(
                     snapshot,
                     type_.as_object_type(),
-                    StorageCounters::DISABLED,
+                    storage_counters.clone(),
                 ))?;
                 cache.extend(instances);
             }
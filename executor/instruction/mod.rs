@@ -19,6 +19,7 @@ use resource::profile::StorageCounters;
 use storage::snapshot::ReadableSnapshot;
 
 use crate::{
+    batch::FixedBatch,
     instruction::{
         has_executor::HasExecutor, has_reverse_executor::HasReverseExecutor, iid_executor::IidExecutor,
         indexed_relation_executor::IndexedRelationExecutor, is_executor::IsExecutor, isa_executor::IsaExecutor,
@@ -94,6 +95,7 @@ impl InstructionExecutor {
         snapshot: &impl ReadableSnapshot,
         thing_manager: &ThingManager,
         sort_by: ExecutorVariable,
+        storage_counters: StorageCounters,
     ) -> Result<Self, Box<ConceptReadError>> {
         match instruction {
             ConstraintInstruction::Is(is) => Ok(Self::Is(IsExecutor::new(is, variable_modes, sort_by))),
@@ -123,9 +125,14 @@ impl InstructionExecutor {
             ConstraintInstruction::IsaReverse(isa_reverse) => {
                 Ok(Self::IsaReverse(IsaReverseExecutor::new(isa_reverse, variable_modes, sort_by)))
             }
-            ConstraintInstruction::Has(has) => {
-                Ok(Self::Has(HasExecutor::new(has, variable_modes, sort_by, snapshot, thing_manager)?))
-            }
+            ConstraintInstruction::Has(has) => Ok(Self::Has(HasExecutor::new(
+                has,
+                variable_modes,
+                sort_by,
+                snapshot,
+                thing_manager,
+                storage_counters,
+            )?)),
             ConstraintInstruction::HasReverse(has_reverse) => Ok(Self::HasReverse(HasReverseExecutor::new(
                 has_reverse,
                 variable_modes,
@@ -133,19 +140,32 @@ impl InstructionExecutor {
                 snapshot,
                 thing_manager,
             )?)),
-            ConstraintInstruction::Links(links) => {
-                Ok(Self::Links(LinksExecutor::new(links, variable_modes, sort_by, snapshot, thing_manager)?))
-            }
+            ConstraintInstruction::Links(links) => Ok(Self::Links(LinksExecutor::new(
+                links,
+                variable_modes,
+                sort_by,
+                snapshot,
+                thing_manager,
+                storage_counters,
+            )?)),
             ConstraintInstruction::LinksReverse(links_reverse) => Ok(Self::LinksReverse(LinksReverseExecutor::new(
                 links_reverse,
                 variable_modes,
                 sort_by,
                 snapshot,
                 thing_manager,
+                storage_counters,
             )?)),
-            ConstraintInstruction::IndexedRelation(indexed_relation) => Ok(Self::IndexedRelation(
-                IndexedRelationExecutor::new(indexed_relation, variable_modes, sort_by, snapshot, thing_manager)?,
-            )),
+            ConstraintInstruction::IndexedRelation(indexed_relation) => {
+                Ok(Self::IndexedRelation(IndexedRelationExecutor::new(
+                    indexed_relation,
+                    variable_modes,
+                    sort_by,
+                    snapshot,
+                    thing_manager,
+                    storage_counters,
+                )?))
+            }
         }
     }
 
@@ -177,6 +197,19 @@ impl InstructionExecutor {
         }
     }
 
+    /// Lets executors read ahead for all the rows of an input batch before iterators are opened for them.
+    pub(crate) fn prepare_batch(
+        &mut self,
+        context: &ExecutionContext<impl ReadableSnapshot + 'static>,
+        batch: &FixedBatch,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<ConceptReadError>> {
+        match self {
+            Self::Has(executor) => executor.prepare_batch(context, batch, storage_counters),
+            _ => Ok(()),
+        }
+    }
+
     pub(crate) const fn name(&self) -> &'static str {
         match self {
             Self::Is(_) => "is",
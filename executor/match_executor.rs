@@ -15,7 +15,7 @@ use resource::profile::QueryProfile;
 use storage::snapshot::ReadableSnapshot;
 
 use crate::{
-    batch::{FixedBatch, FixedBatchRowIterator},
+    batch::{FixedBatch, FixedBatchIntoIterator, FixedBatchRowIterator},
     error::ReadExecutionError,
     pipeline::stage::ExecutionContext,
     read::{
@@ -63,6 +63,15 @@ impl MatchExecutor {
         )
     }
 
+    /// Iterates the rows as owned rows, each sharing the values of the batch it was computed in.
+    pub(crate) fn into_owned_iterator<Snapshot: ReadableSnapshot + 'static>(
+        self,
+        context: ExecutionContext<Snapshot>,
+        interrupt: ExecutionInterrupt,
+    ) -> OwnedPatternIterator<Snapshot> {
+        OwnedPatternIterator { batches: BatchIterator::new(self, context, interrupt), current_batch: None }
+    }
+
     pub(super) fn compute_next_batch(
         &mut self,
         context: &ExecutionContext<impl ReadableSnapshot + 'static>,
@@ -100,6 +109,27 @@ impl<Snapshot: ReadableSnapshot + 'static> Iterator for BatchIterator<Snapshot>
     }
 }
 
+pub(crate) struct OwnedPatternIterator<Snapshot> {
+    batches: BatchIterator<Snapshot>,
+    current_batch: Option<FixedBatchIntoIterator>,
+}
+
+impl<Snapshot: ReadableSnapshot + 'static> Iterator for OwnedPatternIterator<Snapshot> {
+    type Item = Result<MaybeOwnedRow<'static>, ReadExecutionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(row) = self.current_batch.as_mut().and_then(Iterator::next) {
+                return Some(Ok(row));
+            }
+            match self.batches.next()? {
+                Ok(batch) => self.current_batch = Some(batch.into_iter()),
+                Err(err) => return Some(Err(*err)),
+            }
+        }
+    }
+}
+
 // Wrappers around
 type PatternRowIterator<Snapshot> = FlatMap<
     AsLendingIterator<BatchIterator<Snapshot>>,
@@ -173,6 +173,9 @@ pub fn execute_delete_connections(
             ConnectionInstruction::Links(role_player) => {
                 role_player.execute(snapshot, thing_manager, parameters, input_output_row, counters)?
             }
+            ConnectionInstruction::LinksAnyRole(role_player) => {
+                role_player.execute(snapshot, thing_manager, parameters, input_output_row, counters)?
+            }
         }
         measurement.end(&step_profile, 1, 1);
         *profile_index += 1;
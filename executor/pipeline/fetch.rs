@@ -73,7 +73,8 @@ impl<Snapshot: ReadableSnapshot + 'static> FetchStageExecutor<Snapshot> {
         context: ExecutionContext<Snapshot>,
         interrupt: ExecutionInterrupt,
     ) -> (impl Iterator<Item = Result<ConceptDocument, Box<PipelineExecutionError>>>, ExecutionContext<Snapshot>) {
-        let ExecutionContext { snapshot, thing_manager, parameters, profile } = context.clone();
+        let ExecutionContext { snapshot, thing_manager, parameters, profile, max_reduce_list_size, .. } =
+            context.clone();
         let executable = self.executable;
         let functions = self.functions;
         let stage_profile = profile.profile_stage(|| String::from("Fetch"), executable.executable_id);
@@ -86,6 +87,7 @@ impl<Snapshot: ReadableSnapshot + 'static> FetchStageExecutor<Snapshot> {
                     parameters.clone(),
                     functions.clone(),
                     profile.clone(),
+                    max_reduce_list_size,
                     stage_profile.clone(),
                     row.as_reference(),
                     interrupt.clone(),
@@ -105,6 +107,7 @@ fn execute_fetch(
     parameters: Arc<ParameterRegistry>,
     functions: Arc<ExecutableFunctionRegistry>,
     query_profile: Arc<QueryProfile>,
+    max_reduce_list_size: usize,
     stage_profile: Arc<StageProfile>,
     row: MaybeOwnedRow<'_>,
     interrupt: ExecutionInterrupt,
@@ -118,8 +121,10 @@ fn execute_fetch(
         parameters,
         functions,
         query_profile,
+        max_reduce_list_size,
         row,
         interrupt,
+        step.storage_counters(),
     )?;
     measurement.end(&step, 1, 1);
     Ok(ConceptDocument { root: node })
@@ -132,13 +137,15 @@ fn execute_fetch_some(
     parameters: Arc<ParameterRegistry>,
     functions_registry: Arc<ExecutableFunctionRegistry>,
     query_profile: Arc<QueryProfile>,
+    max_reduce_list_size: usize,
     row: MaybeOwnedRow<'_>,
     interrupt: ExecutionInterrupt,
+    storage_counters: StorageCounters,
 ) -> Result<DocumentNode, FetchExecutionError> {
     match fetch_some {
         FetchSomeInstruction::SingleVar(position) => variable_value_to_document(row.get(*position).as_reference()),
         FetchSomeInstruction::SingleAttribute(position, attribute_type) => {
-            execute_single_attribute(snapshot, thing_manager, row, position, attribute_type)
+            execute_single_attribute(snapshot, thing_manager, row, position, attribute_type, storage_counters)
         }
         FetchSomeInstruction::SingleFunction(function, variable_positions) => execute_single_function(
             snapshot,
@@ -146,6 +153,7 @@ fn execute_fetch_some(
             parameters,
             functions_registry,
             query_profile,
+            max_reduce_list_size,
             row,
             interrupt,
             variable_positions,
@@ -158,8 +166,10 @@ fn execute_fetch_some(
             parameters,
             functions_registry,
             query_profile.clone(),
+            max_reduce_list_size,
             row,
             interrupt,
+            storage_counters,
         ),
         FetchSomeInstruction::ListFunction(function, variable_positions) => execute_list_function(
             snapshot,
@@ -167,6 +177,7 @@ fn execute_fetch_some(
             parameters,
             functions_registry,
             query_profile,
+            max_reduce_list_size,
             row,
             interrupt,
             variable_positions,
@@ -178,12 +189,13 @@ fn execute_fetch_some(
             parameters,
             functions_registry,
             query_profile,
+            max_reduce_list_size,
             row,
             interrupt,
             subfetch,
         ),
         FetchSomeInstruction::ListAttributesAsList(position, attribute_type) => {
-            execute_list_attributes_as_list(snapshot, thing_manager, row, position, attribute_type)
+            execute_list_attributes_as_list(snapshot, thing_manager, row, position, attribute_type, storage_counters)
         }
         FetchSomeInstruction::ListAttributesFromList(_, _) => {
             Err(FetchExecutionError::Unimplemented { description: "List attributes are not available yet." })
@@ -197,15 +209,18 @@ fn execute_single_attribute(
     row: MaybeOwnedRow<'_>,
     position: &VariablePosition,
     attribute_type: &AttributeType,
+    storage_counters: StorageCounters,
 ) -> Result<DocumentNode, FetchExecutionError> {
     let variable_value = row.get(*position).as_reference();
     match variable_value {
         VariableValue::None => Ok(DocumentNode::Leaf(DocumentLeaf::Empty)),
         VariableValue::Thing(Thing::Entity(entity)) => {
-            execute_attribute_single(entity, *attribute_type, snapshot, thing_manager).map(DocumentNode::Leaf)
+            execute_attribute_single(entity, *attribute_type, snapshot, thing_manager, storage_counters)
+                .map(DocumentNode::Leaf)
         }
         VariableValue::Thing(Thing::Relation(relation)) => {
-            execute_attribute_single(relation, *attribute_type, snapshot, thing_manager).map(DocumentNode::Leaf)
+            execute_attribute_single(relation, *attribute_type, snapshot, thing_manager, storage_counters)
+                .map(DocumentNode::Leaf)
         }
         VariableValue::Thing(Thing::Attribute(_)) => Err(FetchExecutionError::FetchAttributesOfAttribute {}),
         VariableValue::Type(_) => Err(FetchExecutionError::FetchAttributesOfType {}),
@@ -220,6 +235,7 @@ fn execute_single_function(
     parameters: Arc<ParameterRegistry>,
     functions_registry: Arc<ExecutableFunctionRegistry>,
     query_profile: Arc<QueryProfile>,
+    max_reduce_list_size: usize,
     row: MaybeOwnedRow<'_>,
     mut interrupt: ExecutionInterrupt,
     variable_positions: &HashMap<Variable, VariablePosition>,
@@ -231,6 +247,7 @@ fn execute_single_function(
         parameters,
         functions_registry.clone(),
         query_profile,
+        max_reduce_list_size,
         variable_positions,
         row,
         function,
@@ -279,8 +296,10 @@ fn execute_object(
     parameters: Arc<ParameterRegistry>,
     functions: Arc<ExecutableFunctionRegistry>,
     query_profile: Arc<QueryProfile>,
+    max_reduce_list_size: usize,
     row: MaybeOwnedRow<'_>,
     interrupt: ExecutionInterrupt,
+    storage_counters: StorageCounters,
 ) -> Result<DocumentNode, FetchExecutionError> {
     match fetch_object {
         FetchObjectInstruction::Entries(entries) => {
@@ -291,13 +310,15 @@ fn execute_object(
                 parameters,
                 functions,
                 query_profile,
+                max_reduce_list_size,
                 row,
                 interrupt,
+                storage_counters,
             )?;
             Ok(DocumentNode::Map(object))
         }
         FetchObjectInstruction::Attributes(position) => {
-            execute_object_attributes(*position, snapshot, thing_manager, row)
+            execute_object_attributes(*position, snapshot, thing_manager, row, storage_counters)
         }
     }
 }
@@ -308,6 +329,7 @@ fn execute_list_function(
     parameters: Arc<ParameterRegistry>,
     functions_registry: Arc<ExecutableFunctionRegistry>,
     query_profile: Arc<QueryProfile>,
+    max_reduce_list_size: usize,
     row: MaybeOwnedRow<'_>,
     mut interrupt: ExecutionInterrupt,
     variable_positions: &HashMap<Variable, VariablePosition>,
@@ -319,6 +341,7 @@ fn execute_list_function(
         parameters,
         functions_registry.clone(),
         query_profile,
+        max_reduce_list_size,
         variable_positions,
         row,
         function,
@@ -347,6 +370,7 @@ fn execute_list_subfetch(
     parameters: Arc<ParameterRegistry>,
     functions_registry: Arc<ExecutableFunctionRegistry>,
     query_profile: Arc<QueryProfile>,
+    max_reduce_list_size: usize,
     row: MaybeOwnedRow<'_>,
     interrupt: ExecutionInterrupt,
     executable_subfetch: &ExecutableFetchListSubFetch,
@@ -364,7 +388,9 @@ fn execute_list_subfetch(
             Some(fetch.clone()),
             parameters,
             None,
+            None,
             query_profile,
+            max_reduce_list_size,
         )
     } else {
         let max_position = input_position_mapping.values().max().map(|pos| pos.as_usize()).unwrap();
@@ -384,7 +410,9 @@ fn execute_list_subfetch(
             Some(fetch.clone()),
             parameters,
             Some(initial_row),
+            None,
             query_profile,
+            max_reduce_list_size,
         )
     }
     .map_err(|typedb_source| FetchExecutionError::Pipeline { typedb_source })?;
@@ -406,15 +434,18 @@ fn execute_list_attributes_as_list(
     row: MaybeOwnedRow<'_>,
     position: &VariablePosition,
     attribute_type: &AttributeType,
+    storage_counters: StorageCounters,
 ) -> Result<DocumentNode, FetchExecutionError> {
     let variable_value = row.get(*position).as_reference();
     match variable_value {
         VariableValue::None => Ok(DocumentNode::Leaf(DocumentLeaf::Empty)),
         VariableValue::Thing(Thing::Entity(entity)) => {
-            execute_attributes_list(entity, *attribute_type, snapshot, thing_manager).map(DocumentNode::List)
+            execute_attributes_list(entity, *attribute_type, snapshot, thing_manager, storage_counters)
+                .map(DocumentNode::List)
         }
         VariableValue::Thing(Thing::Relation(relation)) => {
-            execute_attributes_list(relation, *attribute_type, snapshot, thing_manager).map(DocumentNode::List)
+            execute_attributes_list(relation, *attribute_type, snapshot, thing_manager, storage_counters)
+                .map(DocumentNode::List)
         }
         VariableValue::Thing(Thing::Attribute(_)) => Err(FetchExecutionError::FetchAttributesOfAttribute {}),
         VariableValue::Type(_) => Err(FetchExecutionError::FetchAttributesOfType {}),
@@ -428,12 +459,17 @@ fn execute_object_attributes(
     snapshot: Arc<impl ReadableSnapshot>,
     thing_manager: Arc<ThingManager>,
     row: MaybeOwnedRow<'_>,
+    storage_counters: StorageCounters,
 ) -> Result<DocumentNode, FetchExecutionError> {
     let concept = row.get(variable_position);
     match concept {
         VariableValue::None => Ok(DocumentNode::Leaf(DocumentLeaf::Empty)),
-        &VariableValue::Thing(Thing::Entity(entity)) => execute_attributes_all(entity, snapshot, thing_manager),
-        &VariableValue::Thing(Thing::Relation(relation)) => execute_attributes_all(relation, snapshot, thing_manager),
+        &VariableValue::Thing(Thing::Entity(entity)) => {
+            execute_attributes_all(entity, snapshot, thing_manager, storage_counters)
+        }
+        &VariableValue::Thing(Thing::Relation(relation)) => {
+            execute_attributes_all(relation, snapshot, thing_manager, storage_counters)
+        }
         VariableValue::Thing(Thing::Attribute(_)) => Err(FetchExecutionError::FetchAttributesOfAttribute {}),
         VariableValue::Type(_) => Err(FetchExecutionError::FetchAttributesOfType {}),
         VariableValue::Value(_) => Err(FetchExecutionError::FetchAttributesOfValue {}),
@@ -445,9 +481,10 @@ fn execute_attributes_all(
     object: impl ObjectAPI,
     snapshot: Arc<impl ReadableSnapshot>,
     thing_manager: Arc<ThingManager>,
+    storage_counters: StorageCounters,
 ) -> Result<DocumentNode, FetchExecutionError> {
     let iter = object
-        .get_has_unordered(snapshot.as_ref(), &thing_manager, StorageCounters::DISABLED)
+        .get_has_unordered(snapshot.as_ref(), &thing_manager, storage_counters.clone())
         .map_err(|err| FetchExecutionError::ConceptRead { typedb_source: err })?;
     let mut map: HashMap<Arc<Label>, DocumentNode> = HashMap::new();
     for result in iter {
@@ -480,8 +517,10 @@ fn execute_attribute_single(
     attribute_type: AttributeType,
     snapshot: Arc<impl ReadableSnapshot>,
     thing_manager: Arc<ThingManager>,
+    storage_counters: StorageCounters,
 ) -> Result<DocumentLeaf, FetchExecutionError> {
-    let iter = prepare_attribute_type_has_iterator(object, attribute_type, &snapshot, &thing_manager)?;
+    let iter =
+        prepare_attribute_type_has_iterator(object, attribute_type, &snapshot, &thing_manager, storage_counters)?;
 
     for result in iter {
         let (has, count) = result.map_err(|source| FetchExecutionError::ConceptRead { typedb_source: source })?;
@@ -503,9 +542,11 @@ fn execute_attributes_list(
     attribute_type: AttributeType,
     snapshot: Arc<impl ReadableSnapshot>,
     thing_manager: Arc<ThingManager>,
+    storage_counters: StorageCounters,
 ) -> Result<DocumentList, FetchExecutionError> {
     let mut list = DocumentList::new();
-    let iter = prepare_attribute_type_has_iterator(object, attribute_type, &snapshot, &thing_manager)?;
+    let iter =
+        prepare_attribute_type_has_iterator(object, attribute_type, &snapshot, &thing_manager, storage_counters)?;
 
     for result in iter {
         let (has, count) = result.map_err(|source| FetchExecutionError::ConceptRead { typedb_source: source })?;
@@ -530,13 +571,14 @@ fn prepare_attribute_type_has_iterator<'a>(
     attribute_type: AttributeType,
     snapshot: &'a Arc<impl ReadableSnapshot>,
     thing_manager: &'a Arc<ThingManager>,
+    storage_counters: StorageCounters,
 ) -> Result<impl Iterator<Item = Result<(Has, u64), Box<ConceptReadError>>> + 'a, FetchExecutionError> {
     let subtypes = attribute_type
         .get_subtypes_transitive(snapshot.as_ref(), thing_manager.type_manager())
         .map_err(|source| FetchExecutionError::ConceptRead { typedb_source: source })?;
     let iter = Iterator::filter(
         object
-            .get_has_types_range_unordered(snapshot.as_ref(), thing_manager.as_ref(), StorageCounters::DISABLED)
+            .get_has_types_range_unordered(snapshot.as_ref(), thing_manager.as_ref(), storage_counters)
             .map_err(|err| FetchExecutionError::ConceptRead { typedb_source: err })?,
         move |result| {
             result.as_ref().is_ok_and(|(has, _count)| {
@@ -553,6 +595,7 @@ fn prepare_single_function_execution<Snapshot: ReadableSnapshot + 'static>(
     parameters: Arc<ParameterRegistry>,
     functions_registry: Arc<ExecutableFunctionRegistry>,
     query_profile: Arc<QueryProfile>,
+    max_reduce_list_size: usize,
     variable_positions: &HashMap<Variable, VariablePosition>,
     row: MaybeOwnedRow<'_>,
     function: &ExecutableFunction,
@@ -569,7 +612,9 @@ fn prepare_single_function_execution<Snapshot: ReadableSnapshot + 'static>(
             .map_err(|err| FetchExecutionError::ConceptRead { typedb_source: err })?;
     let mut pattern_executor = PatternExecutor::new(next_executable_id(), step_executors);
     pattern_executor.prepare(FixedBatch::from(args));
-    Ok((pattern_executor, Arc::new(ExecutionContext::new(snapshot, thing_manager, parameters))))
+    let context =
+        ExecutionContext::new(snapshot, thing_manager, parameters).with_max_reduce_list_size(max_reduce_list_size);
+    Ok((pattern_executor, Arc::new(context)))
 }
 
 fn execute_object_entries(
@@ -579,8 +624,10 @@ fn execute_object_entries(
     parameters: Arc<ParameterRegistry>,
     functions: Arc<ExecutableFunctionRegistry>,
     query_profile: Arc<QueryProfile>,
+    max_reduce_list_size: usize,
     row: MaybeOwnedRow<'_>,
     interrupt: ExecutionInterrupt,
+    storage_counters: StorageCounters,
 ) -> Result<DocumentMap, FetchExecutionError> {
     let mut map = HashMap::with_capacity(entries.len());
     for (id, fetch_some) in entries {
@@ -593,8 +640,10 @@ fn execute_object_entries(
                 parameters.clone(),
                 functions.clone(),
                 query_profile.clone(),
+                max_reduce_list_size,
                 row.as_reference(),
                 interrupt.clone(),
+                storage_counters.clone(),
             )?,
         );
     }
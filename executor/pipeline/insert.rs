@@ -4,7 +4,7 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use compiler::{
     executable::insert::{
@@ -26,11 +26,14 @@ use storage::snapshot::WritableSnapshot;
 use crate::{
     batch::Batch,
     pipeline::{
-        stage::{ExecutionContext, StageAPI},
+        stage::{ExecutionContext, ExecutionWarnings, StageAPI},
         PipelineExecutionError, StageIterator, WrittenRowsIterator,
     },
     row::{MaybeOwnedRow, Row},
-    write::{write_instruction::AsWriteInstruction, WriteError},
+    write::{
+        write_instruction::{AsWriteInstruction, InsertedAttributes},
+        WriteError,
+    },
     ExecutionInterrupt,
 };
 
@@ -86,6 +89,7 @@ where
 
         // once the previous iterator is complete, this must be the exclusive owner of Arc's, so we can get mut:
         let snapshot_mut = Arc::get_mut(&mut context.snapshot).unwrap();
+        let mut inserted_attributes = InsertedAttributes::default();
         for index in 0..batch.len() {
             // TODO: parallelise -- though this requires our snapshots support parallel writes!
             let mut row = batch.get_row_mut(index);
@@ -96,6 +100,8 @@ where
                 &context.thing_manager,
                 &context.parameters,
                 &mut row,
+                &mut inserted_attributes,
+                &context.warnings,
                 &profile,
             ) {
                 return Err((Box::new(PipelineExecutionError::WriteError { typedb_source }), context));
@@ -149,41 +155,63 @@ pub(crate) fn execute_insert(
     thing_manager: &ThingManager,
     parameters: &ParameterRegistry,
     row: &mut Row<'_>,
+    inserted_attributes: &mut InsertedAttributes,
+    warnings: &ExecutionWarnings,
     stage_profile: &StageProfile,
 ) -> Result<(), Box<WriteError>> {
     debug_assert!(row.get_multiplicity() == 1);
     debug_assert!(row.len() == executable.output_row_schema.len());
     let mut profile_index = 0;
+    let conditional_positions = &executable.conditional_positions;
     execute_concept_instructions(
         &executable.concept_instructions,
+        conditional_positions,
         snapshot,
         thing_manager,
         parameters,
         row,
+        inserted_attributes,
+        warnings,
         stage_profile,
         &mut profile_index,
     )?;
     execute_connection_instructions(
         &executable.connection_instructions,
+        conditional_positions,
         snapshot,
         thing_manager,
         parameters,
         row,
+        warnings,
         stage_profile,
         &mut profile_index,
     )?;
     for optional in &executable.optional_inserts {
-        execute_optional_insert(optional, snapshot, thing_manager, parameters, row, stage_profile, &mut profile_index)?;
+        execute_optional_insert(
+            optional,
+            conditional_positions,
+            snapshot,
+            thing_manager,
+            parameters,
+            row,
+            inserted_attributes,
+            warnings,
+            stage_profile,
+            &mut profile_index,
+        )?;
     }
     Ok(())
 }
 
 fn execute_optional_insert(
     optional: &OptionalInsert,
+    conditional_positions: &HashSet<VariablePosition>,
     snapshot: &mut impl WritableSnapshot,
     thing_manager: &ThingManager,
     parameters: &ParameterRegistry,
     row: &mut Row<'_>,
+    inserted_attributes: &mut InsertedAttributes,
+    warnings: &ExecutionWarnings,
     stage_profile: &StageProfile,
     profile_index: &mut usize,
 ) -> Result<(), Box<WriteError>> {
@@ -194,39 +222,67 @@ fn execute_optional_insert(
     }
     execute_concept_instructions(
         &optional.concept_instructions,
+        conditional_positions,
         snapshot,
         thing_manager,
         parameters,
         row,
+        inserted_attributes,
+        warnings,
         stage_profile,
         profile_index,
     )?;
     execute_connection_instructions(
         &optional.connection_instructions,
+        conditional_positions,
         snapshot,
         thing_manager,
         parameters,
         row,
+        warnings,
         stage_profile,
         profile_index,
     )?;
     Ok(())
 }
+fn is_any_conditional_position_empty(
+    mut positions: impl Iterator<Item = VariablePosition>,
+    conditional_positions: &HashSet<VariablePosition>,
+    row: &Row<'_>,
+) -> bool {
+    positions.any(|position| conditional_positions.contains(&position) && row.get(position).is_none())
+}
+
 fn execute_concept_instructions(
     concept_instructions: &[ConceptInstruction],
+    conditional_positions: &HashSet<VariablePosition>,
     snapshot: &mut impl WritableSnapshot,
     thing_manager: &ThingManager,
     parameters: &ParameterRegistry,
     row: &mut Row<'_>,
+    inserted_attributes: &mut InsertedAttributes,
+    warnings: &ExecutionWarnings,
     stage_profile: &StageProfile,
     profile_index: &mut usize,
 ) -> Result<(), Box<WriteError>> {
     for instruction in concept_instructions {
         let step_profile = stage_profile.extend_or_get(*profile_index, || format!("{}", instruction));
+        if is_any_conditional_position_empty(instruction.input_positions(), conditional_positions, row) {
+            warnings.record_skipped_write();
+            *profile_index += 1;
+            continue;
+        }
         let measurement = step_profile.start_measurement();
         match instruction {
             ConceptInstruction::PutAttribute(isa_attr) => {
-                isa_attr.execute(snapshot, thing_manager, parameters, row, step_profile.storage_counters())?;
+                isa_attr.execute_deduplicated(
+                    snapshot,
+                    thing_manager,
+                    parameters,
+                    row,
+                    inserted_attributes,
+                    step_profile.storage_counters(),
+                )?;
             }
             ConceptInstruction::PutObject(isa_object) => {
                 isa_object.execute(snapshot, thing_manager, parameters, row, step_profile.storage_counters())?;
@@ -240,15 +296,22 @@ fn execute_concept_instructions(
 
 fn execute_connection_instructions(
     connection_instructions: &[ConnectionInstruction],
+    conditional_positions: &HashSet<VariablePosition>,
     snapshot: &mut impl WritableSnapshot,
     thing_manager: &ThingManager,
     parameters: &ParameterRegistry,
     row: &mut Row<'_>,
+    warnings: &ExecutionWarnings,
     stage_profile: &StageProfile,
     profile_index: &mut usize,
 ) -> Result<(), Box<WriteError>> {
     for instruction in connection_instructions {
         let step_profile = stage_profile.extend_or_get(*profile_index, || format!("{}", instruction));
+        if is_any_conditional_position_empty(instruction.input_positions(), conditional_positions, row) {
+            warnings.record_skipped_write();
+            *profile_index += 1;
+            continue;
+        }
         let measurement = step_profile.start_measurement();
         match instruction {
             ConnectionInstruction::Has(has) => {
@@ -6,16 +6,20 @@
 
 use std::{iter::Peekable, sync::Arc};
 
-use compiler::executable::{
-    function::ExecutableFunctionRegistry, match_::planner::conjunction_executable::ConjunctionExecutable,
+use answer::Thing;
+use compiler::{
+    executable::{
+        function::ExecutableFunctionRegistry, match_::planner::conjunction_executable::ConjunctionExecutable,
+    },
+    VariablePosition,
 };
 use itertools::{Itertools, UniqueBy};
-use lending_iterator::{adaptors::Map, IntoIter, LendingIterator};
+use lending_iterator::LendingIterator;
 use storage::snapshot::ReadableSnapshot;
 
 use crate::{
     error::ReadExecutionError,
-    match_executor::{MatchExecutor, PatternIterator},
+    match_executor::{MatchExecutor, OwnedPatternIterator},
     pipeline::{
         stage::{ExecutionContext, StageAPI},
         PipelineExecutionError, StageIterator,
@@ -28,6 +32,8 @@ pub struct MatchStageExecutor<PreviousStage> {
     executable: Arc<ConjunctionExecutable>,
     previous: PreviousStage,
     function_registry: Arc<ExecutableFunctionRegistry>,
+    row_budget: Option<u64>,
+    start_after: Option<(VariablePosition, Thing)>,
 }
 
 impl<PreviousStage> MatchStageExecutor<PreviousStage> {
@@ -36,7 +42,18 @@ impl<PreviousStage> MatchStageExecutor<PreviousStage> {
         previous: PreviousStage,
         function_registry: Arc<ExecutableFunctionRegistry>,
     ) -> Self {
-        Self { executable, previous, function_registry }
+        Self { executable, previous, function_registry, row_budget: None, start_after: None }
+    }
+
+    /// Bounds the rows the later stages need from this match, typically set by a following `limit`.
+    pub fn with_row_budget(self, row_budget: Option<u64>) -> Self {
+        Self { row_budget, ..self }
+    }
+
+    /// Answers only the rows whose variable at the position is an instance strictly after the given one,
+    /// resuming the scan of its instances from there.
+    pub fn with_start_after(self, start_after: Option<(VariablePosition, Thing)>) -> Self {
+        Self { start_after, ..self }
     }
 }
 
@@ -54,10 +71,11 @@ where
         (Self::OutputIterator, ExecutionContext<Snapshot>),
         (Box<PipelineExecutionError>, ExecutionContext<Snapshot>),
     > {
-        let Self { previous: previous_stage, executable, function_registry, .. } = self;
+        let Self { previous: previous_stage, executable, function_registry, row_budget, start_after } = self;
         let (previous_iterator, context) = previous_stage.into_iterator(interrupt.clone())?;
         let iterator = previous_iterator;
-        Ok((MatchStageIterator::new(iterator, executable, function_registry, context.clone(), interrupt), context))
+        let match_context = context.clone_with_row_budget(row_budget).clone_with_start_after(start_after);
+        Ok((MatchStageIterator::new(iterator, executable, function_registry, match_context, interrupt), context))
     }
 }
 
@@ -66,7 +84,7 @@ pub struct MatchStageIterator<Snapshot: ReadableSnapshot + 'static, Iterator> {
     executable: Arc<ConjunctionExecutable>,
     function_registry: Arc<ExecutableFunctionRegistry>,
     source_iterator: Iterator,
-    current_iterator: Option<Peekable<UniqueRows<AsOwnedRows<PatternIterator<Snapshot>>>>>,
+    current_iterator: Option<Peekable<UniqueRows<OwnedPatternIterator<Snapshot>>>>,
     interrupt: ExecutionInterrupt,
 }
 
@@ -90,39 +108,47 @@ where
     type Item<'a> = Result<MaybeOwnedRow<'a>, Box<PipelineExecutionError>>;
 
     fn next(&mut self) -> Option<Self::Item<'_>> {
-        while !self.current_iterator.as_mut().is_some_and(|iter| iter.peek().is_some()) {
-            let ExecutionContext { snapshot, thing_manager, profile, .. } = &self.context;
-
-            let input_row = match self.source_iterator.next()? {
-                Ok(row) => row,
-                Err(err) => return Some(Err(err)),
-            };
-
-            let executor = MatchExecutor::new(
-                &self.executable,
-                snapshot,
-                thing_manager,
-                input_row,
-                self.function_registry.clone(),
-                profile,
-            )
-            .map_err(|err| Box::new(PipelineExecutionError::InitialisingMatchIterator { typedb_source: err }));
-
-            match executor {
-                Ok(executor) => {
-                    self.current_iterator = Some(
-                        unique_rows(as_owned_rows(
-                            executor.into_iterator(self.context.clone(), self.interrupt.clone()),
-                        ))
-                        .peekable(),
-                    );
-                }
-                Err(err) => return Some(Err(err)),
-            };
+        loop {
+            while !self.current_iterator.as_mut().is_some_and(|iter| iter.peek().is_some()) {
+                let ExecutionContext { snapshot, thing_manager, profile, .. } = &self.context;
+
+                let input_row = match self.source_iterator.next()? {
+                    Ok(row) => row,
+                    Err(err) => return Some(Err(err)),
+                };
+
+                let executor = MatchExecutor::new(
+                    &self.executable,
+                    snapshot,
+                    thing_manager,
+                    input_row,
+                    self.function_registry.clone(),
+                    profile,
+                )
+                .map_err(|err| Box::new(PipelineExecutionError::InitialisingMatchIterator { typedb_source: err }));
+
+                match executor {
+                    Ok(executor) => {
+                        self.current_iterator = Some(
+                            unique_rows(executor.into_owned_iterator(self.context.clone(), self.interrupt.clone()))
+                                .peekable(),
+                        );
+                    }
+                    Err(err) => return Some(Err(err)),
+                };
+            }
+            // an isa scan of the variable resumes after the instance, but the plan may bind it by another constraint
+            let iterator = self.current_iterator.as_mut().unwrap();
+            if matches!(iterator.peek(), Some(Ok(row)) if !self.context.is_after_start(row)) {
+                iterator.next();
+                continue;
+            }
+            return iterator.next().map(|result| {
+                result.map_err(|err| {
+                    Box::new(PipelineExecutionError::ReadPatternExecution { typedb_source: err.clone() })
+                })
+            });
         }
-        self.current_iterator.as_mut().unwrap().next().map(|result| {
-            result.map_err(|err| Box::new(PipelineExecutionError::ReadPatternExecution { typedb_source: err.clone() }))
-        })
     }
 }
 
@@ -133,27 +159,6 @@ where
 {
 }
 
-type AsOwnedRows<I> = IntoIter<
-    Map<
-        I,
-        fn(Result<MaybeOwnedRow<'_>, &ReadExecutionError>) -> Result<MaybeOwnedRow<'static>, ReadExecutionError>,
-        lending_iterator::higher_order::AdHocHkt<Result<MaybeOwnedRow<'static>, ReadExecutionError>>,
-    >,
->;
-pub(crate) fn as_owned_rows<I>(iter: I) -> AsOwnedRows<I>
-where
-    I: for<'a> LendingIterator<Item<'a> = Result<MaybeOwnedRow<'a>, &'a ReadExecutionError>>,
-{
-    iter.map_static(
-        (|row| match row {
-            Ok(row) => Ok(row.into_owned()),
-            Err(err) => Err(err.clone()),
-        })
-            as fn(Result<MaybeOwnedRow<'_>, &ReadExecutionError>) -> Result<MaybeOwnedRow<'static>, ReadExecutionError>,
-    )
-    .into_iter()
-}
-
 type UniqueRows<I> = UniqueBy<
     I,
     Result<MaybeOwnedRow<'static>, ()>,
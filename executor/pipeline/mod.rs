@@ -7,6 +7,7 @@
 use concept::error::ConceptReadError;
 use error::typedb_error;
 use lending_iterator::LendingIterator;
+use resource::memory::MemoryReservation;
 
 use crate::{
     batch::Batch,
@@ -61,6 +62,12 @@ impl StageIterator for WrittenRowsIterator {
         Ok(self.rows)
     }
 
+    fn collect_owned_reserved(self, reservation: &mut MemoryReservation) -> Result<Batch, Box<PipelineExecutionError>> {
+        let rows = self.collect_owned()?;
+        reservation.resize(rows.allocated_bytes());
+        Ok(rows)
+    }
+
     fn multiplicity_sum_if_collected(&self) -> Option<usize> {
         Some(self.rows.get_multiplicities().iter().sum::<u64>() as usize)
     }
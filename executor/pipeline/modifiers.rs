@@ -14,7 +14,10 @@ use compiler::{
 };
 use ir::pipeline::modifier::SortVariable;
 use lending_iterator::{LendingIterator, Peekable};
-use resource::profile::StorageCounters;
+use resource::{
+    memory::{MemoryComponent, MemoryReservation, MEMORY_MANAGER},
+    profile::StorageCounters,
+};
 use storage::snapshot::ReadableSnapshot;
 
 use crate::{
@@ -56,7 +59,8 @@ where
         let Self { previous, executable, .. } = self;
         let (previous_iterator, context) = previous.into_iterator(interrupt)?;
         // accumulate once, then we will operate in-place
-        let batch = match previous_iterator.collect_owned() {
+        let mut reservation = MEMORY_MANAGER.reserve(MemoryComponent::ExecutorBuffers);
+        let batch = match previous_iterator.collect_owned_reserved(&mut reservation) {
             Ok(batch) => batch,
             Err(err) => return Err((err, context)),
         };
@@ -64,8 +68,13 @@ where
         let profile = context.profile.profile_stage(|| String::from("Sort"), executable.executable_id);
         let step_profile = profile.extend_or_get(0, || String::from("Sort execution"));
         let measurement = step_profile.start_measurement();
-        let sorted_iterator =
-            SortStageIterator::from_unsorted(batch, &executable, &context, step_profile.storage_counters());
+        let sorted_iterator = SortStageIterator::from_unsorted(
+            batch,
+            reservation,
+            &executable,
+            &context,
+            step_profile.storage_counters(),
+        );
         measurement.end(&step_profile, 1, batch_len as u64);
         Ok((sorted_iterator, context))
     }
@@ -75,11 +84,13 @@ pub struct SortStageIterator {
     unsorted: Batch,
     sorted_indices: Vec<usize>,
     next_index_index: usize,
+    _reservation: MemoryReservation,
 }
 
 impl SortStageIterator {
     fn from_unsorted(
         unsorted: Batch,
+        reservation: MemoryReservation,
         sort_executable: &SortExecutable,
         context: &ExecutionContext<impl ReadableSnapshot>,
         storage_counters: StorageCounters,
@@ -93,7 +104,7 @@ impl SortStageIterator {
             })
             .collect();
         let sorted_indices = unsorted.indices_sorted_by(context, &sort_by, storage_counters);
-        Self { unsorted, sorted_indices, next_index_index: 0 }
+        Self { unsorted, sorted_indices, next_index_index: 0, _reservation: reservation }
     }
 }
 
@@ -6,10 +6,11 @@
 
 use std::{collections::HashMap, sync::Arc};
 
-use answer::variable::Variable;
+use answer::{variable::Variable, Thing};
 use compiler::{
     executable::{fetch::executable::ExecutableFetch, function::ExecutableFunctionRegistry, pipeline::ExecutableStage},
     query_structure::{ParametrisedPipelineStructure, PipelineStructure},
+    warning::QueryWarning,
     VariablePosition,
 };
 use concept::thing::thing_manager::ThingManager;
@@ -45,6 +46,7 @@ pub struct Pipeline<Snapshot: ReadableSnapshot, Nonterminals: StageAPI<Snapshot>
     named_outputs: HashMap<String, VariablePosition>,
     pipeline_structure: Option<PipelineStructure>,
     fetch: Option<FetchStageExecutor<Snapshot>>,
+    warnings: Vec<QueryWarning>,
 }
 
 impl<Snapshot: ReadableSnapshot + 'static, Nonterminals: StageAPI<Snapshot>> Pipeline<Snapshot, Nonterminals> {
@@ -61,7 +63,11 @@ impl<Snapshot: ReadableSnapshot + 'static, Nonterminals: StageAPI<Snapshot>> Pip
             .filter_map(|(variable, &position)| variable_names.get(variable).map(|name| (name.clone(), position)))
             .collect::<HashMap<_, _>>();
         let fetch = executable_fetch.map(|executable| FetchStageExecutor::new(executable, executable_functions));
-        Self { named_outputs, last_stage, fetch, pipeline_structure }
+        Self { named_outputs, last_stage, fetch, pipeline_structure, warnings: Vec::new() }
+    }
+
+    pub fn with_warnings(self, warnings: Vec<QueryWarning>) -> Self {
+        Self { warnings, ..self }
     }
 
     pub fn has_fetch(&self) -> bool {
@@ -79,6 +85,10 @@ impl<Snapshot: ReadableSnapshot + 'static, Nonterminals: StageAPI<Snapshot>> Pip
         self.pipeline_structure.as_ref()
     }
 
+    pub fn warnings(&self) -> &[QueryWarning] {
+        &self.warnings
+    }
+
     pub fn into_rows_iterator(
         self,
         execution_interrupt: ExecutionInterrupt,
@@ -126,23 +136,44 @@ impl<Snapshot: ReadableSnapshot + 'static> Pipeline<Snapshot, ReadPipelineStage<
         executable_fetch: Option<Arc<ExecutableFetch>>,
         parameters: Arc<ParameterRegistry>,
         input: Option<MaybeOwnedRow<'_>>,
+        start_after: Option<(&str, Thing)>,
         query_profile: Arc<QueryProfile>,
+        max_reduce_list_size: usize,
     ) -> Result<Self, Box<PipelineError>> {
         let output_variable_positions = executable_stages.last().unwrap().output_row_mapping();
-        let context = ExecutionContext::new_with_profile(snapshot, thing_manager, parameters.clone(), query_profile);
+        // a page resumes the scan of a variable of the first match stage, which every later stage builds on
+        let mut start_after = match start_after {
+            None => None,
+            Some((name, thing)) => {
+                let variable = variable_names.iter().find(|(_, variable_name)| variable_name.as_str() == name);
+                let position = match (variable, executable_stages.first()) {
+                    (Some((variable, _)), Some(ExecutableStage::Match(conjunction_executable))) => {
+                        conjunction_executable.variable_positions().get(variable).copied()
+                    }
+                    _ => None,
+                };
+                let position = position
+                    .ok_or_else(|| Box::new(PipelineError::StartAfterVariableNotMatched { name: name.to_owned() }))?;
+                Some((position, thing))
+            }
+        };
+        let context = ExecutionContext::new_with_profile(snapshot, thing_manager, parameters.clone(), query_profile)
+            .with_max_reduce_list_size(max_reduce_list_size);
         let mut last_stage = ReadPipelineStage::Initial(Box::new(
             input
                 .map(|row| InitialStage::new_with(context.clone(), row))
                 .unwrap_or_else(|| InitialStage::new_empty(context)),
         ));
-        for executable_stage in executable_stages {
+        for (index, executable_stage) in executable_stages.iter().enumerate() {
             match executable_stage {
                 ExecutableStage::Match(conjunction_executable) => {
                     let match_stage = MatchStageExecutor::new(
                         conjunction_executable.clone(),
                         last_stage,
                         executable_functions.clone(),
-                    );
+                    )
+                    .with_row_budget(row_budget_of_stages(&executable_stages[index + 1..]))
+                    .with_start_after(start_after.take());
                     last_stage = ReadPipelineStage::Match(Box::new(match_stage));
                 }
                 ExecutableStage::Insert(_) => {
@@ -209,16 +240,21 @@ impl<Snapshot: WritableSnapshot + 'static> Pipeline<Snapshot, WritePipelineStage
         executable_fetch: Option<Arc<ExecutableFetch>>,
         parameters: Arc<ParameterRegistry>,
         query_profile: Arc<QueryProfile>,
+        max_reduce_list_size: usize,
     ) -> Self {
         let output_variable_positions = executable_stages.last().unwrap().output_row_mapping();
         let context =
-            ExecutionContext::new_with_profile(Arc::new(snapshot), thing_manager, parameters.clone(), query_profile);
+            ExecutionContext::new_with_profile(Arc::new(snapshot), thing_manager, parameters.clone(), query_profile)
+                .with_max_reduce_list_size(max_reduce_list_size);
         let mut last_stage = WritePipelineStage::Initial(Box::new(InitialStage::new_empty(context)));
-        for executable_stage in executable_stages {
+        let row_budgets: Vec<_> =
+            (0..executable_stages.len()).map(|index| row_budget_of_stages(&executable_stages[index + 1..])).collect();
+        for (executable_stage, row_budget) in executable_stages.into_iter().zip(row_budgets) {
             match executable_stage {
                 ExecutableStage::Match(conjunction_executable) => {
                     let match_stage =
-                        MatchStageExecutor::new(conjunction_executable, last_stage, executable_functions.clone());
+                        MatchStageExecutor::new(conjunction_executable, last_stage, executable_functions.clone())
+                            .with_row_budget(row_budget);
                     last_stage = WritePipelineStage::Match(Box::new(match_stage));
                 }
                 ExecutableStage::Insert(insert_executable) => {
@@ -278,8 +314,28 @@ impl<Snapshot: WritableSnapshot + 'static> Pipeline<Snapshot, WritePipelineStage
     }
 }
 
+/// The number of input rows the given stages can consume, bounded only when a `limit` is reached
+/// through stages that keep the number of rows.
+fn row_budget_of_stages(stages: &[ExecutableStage]) -> Option<u64> {
+    let mut skipped: u64 = 0;
+    let mut budget: Option<u64> = None;
+    for stage in stages {
+        match stage {
+            ExecutableStage::Select(_) => (),
+            ExecutableStage::Offset(offset_executable) => skipped = skipped.saturating_add(offset_executable.offset),
+            ExecutableStage::Limit(limit_executable) => {
+                let limit = skipped.saturating_add(limit_executable.limit);
+                budget = Some(budget.map_or(limit, |budget| budget.min(limit)));
+            }
+            _ => break,
+        }
+    }
+    budget
+}
+
 typedb_error! {
     pub PipelineError(component = "Pipeline", prefix = "PIP") {
         InvalidReadPipelineStage(1, "{stage} clause cannot exist in a read pipeline.", stage: String ),
+        StartAfterVariableNotMatched(2, "Cannot resume after an instance of '${name}', since it is not a variable of the first match stage.", name: String),
     }
 }
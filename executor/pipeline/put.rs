@@ -22,6 +22,7 @@ use crate::{
         PipelineExecutionError, WrittenRowsIterator,
     },
     row::MaybeOwnedRow,
+    write::write_instruction::InsertedAttributes,
     ExecutionInterrupt,
 };
 
@@ -135,10 +136,8 @@ fn match_iterator_for_row<Snapshot: ReadableSnapshot + 'static>(
         &context.profile,
     )
     .map_err(|err| Box::new(PipelineExecutionError::InitialisingMatchIterator { typedb_source: err }))?;
-    Ok(crate::pipeline::match_::unique_rows(crate::pipeline::match_::as_owned_rows(
-        executor.into_iterator(context.clone(), interrupt.clone()),
-    ))
-    .peekable())
+    Ok(crate::pipeline::match_::unique_rows(executor.into_owned_iterator(context.clone(), interrupt.clone()))
+        .peekable())
 }
 
 fn perform_inserts<Snapshot: WritableSnapshot>(
@@ -150,6 +149,7 @@ fn perform_inserts<Snapshot: WritableSnapshot>(
 ) -> Result<(), Box<PipelineExecutionError>> {
     let snapshot_mut = Arc::get_mut(&mut context.snapshot).unwrap();
     let stage_profile = context.profile.profile_stage(|| String::from("PutInsert"), executable.executable_id as _);
+    let mut inserted_attributes = InsertedAttributes::default();
     for index in 0..output_batch.len() {
         // TODO: parallelise -- though this requires our snapshots support parallel writes!
         if must_insert[index] {
@@ -160,6 +160,8 @@ fn perform_inserts<Snapshot: WritableSnapshot>(
                 &context.thing_manager,
                 &context.parameters,
                 &mut row,
+                &mut inserted_attributes,
+                &context.warnings,
                 &stage_profile,
             )
             .map_err(|typedb_source| Box::new(PipelineExecutionError::WriteError { typedb_source }))?;
@@ -67,7 +67,9 @@ fn reduce_iterator<Snapshot: ReadableSnapshot>(
     let mut iterator = iterator;
     let mut grouped_reducer = GroupedReducer::new(executable.reduce_rows_executable.clone());
     while let Some(result) = iterator.next() {
-        grouped_reducer.accept(&result?, context)?;
+        grouped_reducer
+            .accept(&result?, context)
+            .map_err(|typedb_source| Box::new(PipelineExecutionError::ReadPatternExecution { typedb_source }))?;
     }
     Ok(grouped_reducer.finalise())
 }
@@ -4,12 +4,21 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 
+use answer::{variable_value::VariableValue, Thing};
+use compiler::{warning::QueryWarning, VariablePosition};
 use concept::{thing::thing_manager::ThingManager, type_::type_manager::TypeManager};
 use ir::pipeline::ParameterRegistry;
 use lending_iterator::LendingIterator;
-use resource::{constants::traversal::BATCH_DEFAULT_CAPACITY, profile::QueryProfile};
+use resource::{
+    constants::{compiler::MAX_REDUCE_LIST_SIZE, traversal::BATCH_DEFAULT_CAPACITY},
+    memory::MemoryReservation,
+    profile::QueryProfile,
+};
 use storage::snapshot::{ReadableSnapshot, WritableSnapshot};
 
 use crate::{
@@ -39,6 +48,14 @@ pub struct ExecutionContext<Snapshot> {
     pub thing_manager: Arc<ThingManager>,
     pub parameters: Arc<ParameterRegistry>,
     pub profile: Arc<QueryProfile>,
+    pub warnings: Arc<ExecutionWarnings>,
+    /// The number of rows the rest of the pipeline needs at most, letting executors stop filling batches early.
+    pub row_budget: Option<u64>,
+    /// Restricts the variable at the position to the instances strictly after the given one, in storage order,
+    /// so that paginated reads resume where the previous page ended instead of skipping an offset.
+    pub start_after: Option<(VariablePosition, Thing)>,
+    /// The most values a list reducer may collect for one group before the query fails.
+    pub max_reduce_list_size: usize,
 }
 
 impl<Snapshot> ExecutionContext<Snapshot> {
@@ -52,7 +69,20 @@ impl<Snapshot> ExecutionContext<Snapshot> {
         parameters: Arc<ParameterRegistry>,
         query_profile: Arc<QueryProfile>,
     ) -> Self {
-        Self { snapshot, thing_manager, parameters, profile: query_profile }
+        Self {
+            snapshot,
+            thing_manager,
+            parameters,
+            profile: query_profile,
+            warnings: Arc::new(ExecutionWarnings::default()),
+            row_budget: None,
+            start_after: None,
+            max_reduce_list_size: MAX_REDUCE_LIST_SIZE,
+        }
+    }
+
+    pub fn with_max_reduce_list_size(self, max_reduce_list_size: usize) -> Self {
+        Self { max_reduce_list_size, ..self }
     }
 
     pub(crate) fn clone_with_replaced_parameters(&self, parameters: Arc<ParameterRegistry>) -> Self {
@@ -61,9 +91,21 @@ impl<Snapshot> ExecutionContext<Snapshot> {
             thing_manager: self.thing_manager.clone(),
             parameters,
             profile: self.profile.clone(),
+            warnings: self.warnings.clone(),
+            row_budget: None,
+            start_after: None,
+            max_reduce_list_size: self.max_reduce_list_size,
         }
     }
 
+    pub(crate) fn clone_with_row_budget(&self, row_budget: Option<u64>) -> Self {
+        Self { row_budget, ..self.clone() }
+    }
+
+    pub(crate) fn clone_with_start_after(&self, start_after: Option<(VariablePosition, Thing)>) -> Self {
+        Self { start_after, ..self.clone() }
+    }
+
     pub(crate) fn snapshot(&self) -> &Arc<Snapshot> {
         &self.snapshot
     }
@@ -79,20 +121,69 @@ impl<Snapshot> ExecutionContext<Snapshot> {
     pub(crate) fn parameters(&self) -> &ParameterRegistry {
         &self.parameters
     }
+
+    pub(crate) fn is_within_row_budget(&self, rows: u32) -> bool {
+        !self.row_budget.is_some_and(|budget| rows as u64 >= budget)
+    }
+
+    /// The instance to resume after, if the variable at the position is restricted by `start_after`.
+    pub(crate) fn start_after_for(&self, position: VariablePosition) -> Option<&Thing> {
+        self.start_after.as_ref().filter(|(start_position, _)| *start_position == position).map(|(_, thing)| thing)
+    }
+
+    pub(crate) fn is_after_start(&self, row: &MaybeOwnedRow<'_>) -> bool {
+        match &self.start_after {
+            None => true,
+            Some((position, after)) => match row.get(*position) {
+                VariableValue::Thing(thing) => thing > after,
+                _ => false,
+            },
+        }
+    }
 }
 
 impl<Snapshot> Clone for ExecutionContext<Snapshot> {
     fn clone(&self) -> Self {
-        let Self { snapshot, thing_manager, parameters, profile } = self;
+        let Self {
+            snapshot,
+            thing_manager,
+            parameters,
+            profile,
+            warnings,
+            row_budget,
+            start_after,
+            max_reduce_list_size,
+        } = self;
         Self {
             snapshot: snapshot.clone(),
             thing_manager: thing_manager.clone(),
             parameters: parameters.clone(),
             profile: profile.clone(),
+            warnings: warnings.clone(),
+            row_budget: *row_budget,
+            start_after: start_after.clone(),
+            max_reduce_list_size: *max_reduce_list_size,
         }
     }
 }
 
+/// Warnings raised while executing a query, which are answered alongside those found while compiling it.
+#[derive(Debug, Default)]
+pub struct ExecutionWarnings {
+    skipped_writes: AtomicUsize,
+}
+
+impl ExecutionWarnings {
+    pub(crate) fn record_skipped_write(&self) {
+        self.skipped_writes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn warnings(&self) -> Vec<QueryWarning> {
+        let skipped_writes = self.skipped_writes.load(Ordering::Relaxed);
+        (skipped_writes > 0).then_some(QueryWarning::WritesSkipped { count: skipped_writes }).into_iter().collect()
+    }
+}
+
 pub trait StageAPI<Snapshot> {
     type OutputIterator: StageIterator;
 
@@ -129,6 +220,32 @@ pub trait StageIterator:
         Ok(batch)
     }
 
+    /// Collects like `collect_owned`, resizing the reservation as the rows are collected,
+    /// so that the memory pressure reflects a large collection while it grows.
+    fn collect_owned_reserved(
+        mut self,
+        reservation: &mut MemoryReservation,
+    ) -> Result<Batch, Box<PipelineExecutionError>> {
+        let mut batch = match self.next() {
+            None => {
+                return Ok(Batch::new(0, 0));
+            }
+            Some(row) => {
+                let row = row?;
+                let mut batch = Batch::new(row.len() as u32, BATCH_DEFAULT_CAPACITY);
+                batch.append_row(row);
+                batch
+            }
+        };
+        reservation.resize(batch.allocated_bytes());
+        while let Some(row) = self.next() {
+            let row = row?;
+            batch.append_row(row);
+            reservation.resize(batch.allocated_bytes());
+        }
+        Ok(batch)
+    }
+
     fn multiplicity_sum_if_collected(&self) -> Option<usize> {
         None
     }
@@ -241,6 +358,20 @@ impl<Snapshot: ReadableSnapshot + 'static> StageIterator for ReadStageIterator<S
             ReadStageIterator::Reduce(iterator) => iterator.collect_owned(),
         }
     }
+
+    fn collect_owned_reserved(self, reservation: &mut MemoryReservation) -> Result<Batch, Box<PipelineExecutionError>> {
+        match self {
+            ReadStageIterator::Initial(iterator) => iterator.collect_owned_reserved(reservation),
+            ReadStageIterator::Match(iterator) => iterator.collect_owned_reserved(reservation),
+            ReadStageIterator::Sort(iterator) => iterator.collect_owned_reserved(reservation),
+            ReadStageIterator::Distinct(iterator) => iterator.collect_owned_reserved(reservation),
+            ReadStageIterator::Offset(iterator) => iterator.collect_owned_reserved(reservation),
+            ReadStageIterator::Limit(iterator) => iterator.collect_owned_reserved(reservation),
+            ReadStageIterator::Select(iterator) => iterator.collect_owned_reserved(reservation),
+            ReadStageIterator::Require(iterator) => iterator.collect_owned_reserved(reservation),
+            ReadStageIterator::Reduce(iterator) => iterator.collect_owned_reserved(reservation),
+        }
+    }
 }
 
 pub enum WritePipelineStage<Snapshot: WritableSnapshot + 'static> {
@@ -373,4 +504,19 @@ impl<Snapshot: WritableSnapshot + 'static> StageIterator for WriteStageIterator<
             WriteStageIterator::Reduce(iterator) => iterator.collect_owned(),
         }
     }
+
+    fn collect_owned_reserved(self, reservation: &mut MemoryReservation) -> Result<Batch, Box<PipelineExecutionError>> {
+        match self {
+            WriteStageIterator::Initial(iterator) => iterator.collect_owned_reserved(reservation),
+            WriteStageIterator::Match(iterator) => iterator.collect_owned_reserved(reservation),
+            WriteStageIterator::Write(iterator) => iterator.collect_owned_reserved(reservation),
+            WriteStageIterator::Sort(iterator) => iterator.collect_owned_reserved(reservation),
+            WriteStageIterator::Distinct(iterator) => iterator.collect_owned_reserved(reservation),
+            WriteStageIterator::Limit(iterator) => iterator.collect_owned_reserved(reservation),
+            WriteStageIterator::Offset(iterator) => iterator.collect_owned_reserved(reservation),
+            WriteStageIterator::Select(iterator) => iterator.collect_owned_reserved(reservation),
+            WriteStageIterator::Require(iterator) => iterator.collect_owned_reserved(reservation),
+            WriteStageIterator::Reduce(iterator) => iterator.collect_owned_reserved(reservation),
+        }
+    }
 }
@@ -9,7 +9,10 @@ use std::{fmt, iter::Peekable, sync::Arc};
 use compiler::executable::{modifiers::SortExecutable, reduce::ReduceRowsExecutable};
 use ir::pipeline::modifier::SortVariable;
 use lending_iterator::LendingIterator;
-use resource::constants::traversal::BATCH_DEFAULT_CAPACITY;
+use resource::{
+    constants::traversal::BATCH_DEFAULT_CAPACITY,
+    memory::{MemoryComponent, MemoryReservation, MEMORY_MANAGER},
+};
 use storage::snapshot::ReadableSnapshot;
 
 use crate::{
@@ -94,7 +97,11 @@ pub(super) enum CollectorEnum {
 }
 
 impl CollectorEnum {
-    pub(crate) fn accept(&mut self, context: &ExecutionContext<impl ReadableSnapshot>, batch: FixedBatch) {
+    pub(crate) fn accept(
+        &mut self,
+        context: &ExecutionContext<impl ReadableSnapshot>,
+        batch: FixedBatch,
+    ) -> Result<(), ReadExecutionError> {
         match self {
             CollectorEnum::Reduce(collector) => collector.accept(context, batch),
             CollectorEnum::Sort(collector) => collector.accept(context, batch),
@@ -126,7 +133,11 @@ impl CollectedStageIterator {
 
 // Actual implementations
 pub(super) trait CollectorTrait {
-    fn accept(&mut self, context: &ExecutionContext<impl ReadableSnapshot>, batch: FixedBatch);
+    fn accept(
+        &mut self,
+        context: &ExecutionContext<impl ReadableSnapshot>,
+        batch: FixedBatch,
+    ) -> Result<(), ReadExecutionError>;
     fn into_iterator(self, context: &ExecutionContext<impl ReadableSnapshot>) -> CollectedStageIterator;
 }
 
@@ -154,10 +165,15 @@ impl ReduceCollector {
 }
 
 impl CollectorTrait for ReduceCollector {
-    fn accept(&mut self, context: &ExecutionContext<impl ReadableSnapshot>, batch: FixedBatch) {
+    fn accept(
+        &mut self,
+        context: &ExecutionContext<impl ReadableSnapshot>,
+        batch: FixedBatch,
+    ) -> Result<(), ReadExecutionError> {
         for row in batch {
-            self.active_reducer.accept(&row, context).unwrap(); // TODO: potentially unsafe unwrap
+            self.active_reducer.accept(&row, context)?;
         }
+        Ok(())
     }
 
     fn into_iterator(self, _context: &ExecutionContext<impl ReadableSnapshot>) -> CollectedStageIterator {
@@ -203,29 +219,44 @@ impl CollectedStageIteratorTrait for ReduceStageIterator {
 pub(super) struct SortCollector {
     sort_on: Arc<Vec<(usize, bool)>>,
     collector: Batch,
+    reservation: MemoryReservation,
 }
 
 impl SortCollector {
     fn new(width: u32, sort_on: Arc<Vec<(usize, bool)>>) -> Self {
         // let output_width = sort_executable.output_width;  // TODO: Get this information into the sort_executable.
-        Self { sort_on, collector: Batch::new(width, BATCH_DEFAULT_CAPACITY) }
+        Self {
+            sort_on,
+            collector: Batch::new(width, BATCH_DEFAULT_CAPACITY),
+            reservation: MEMORY_MANAGER.reserve(MemoryComponent::ExecutorBuffers),
+        }
     }
 }
 
 impl CollectorTrait for SortCollector {
-    fn accept(&mut self, _context: &ExecutionContext<impl ReadableSnapshot>, batch: FixedBatch) {
+    fn accept(
+        &mut self,
+        _context: &ExecutionContext<impl ReadableSnapshot>,
+        batch: FixedBatch,
+    ) -> Result<(), ReadExecutionError> {
         for row in batch {
             self.collector.append_row(row);
         }
+        self.reservation.resize(self.collector.allocated_bytes());
+        Ok(())
     }
 
     fn into_iterator(self, context: &ExecutionContext<impl ReadableSnapshot>) -> CollectedStageIterator {
-        let Self { sort_on, collector } = self;
+        let Self { sort_on, collector, reservation } = self;
         let profile = context.profile.profile_stage(|| String::from("Sort"), 0); // TODO executable id
         let step_profile = profile.extend_or_get(0, || String::from("Sort execution"));
         let sorted_indices =
             collector.indices_sorted_by(context, &sort_on, step_profile.storage_counters()).into_iter().peekable();
-        CollectedStageIterator::Sort(SortStageIterator { unsorted: collector, sorted_indices })
+        CollectedStageIterator::Sort(SortStageIterator {
+            unsorted: collector,
+            sorted_indices,
+            _reservation: reservation,
+        })
     }
 }
 
@@ -233,11 +264,12 @@ impl CollectorTrait for SortCollector {
 pub struct SortStageIterator {
     unsorted: Batch,
     sorted_indices: Peekable<std::vec::IntoIter<usize>>,
+    _reservation: MemoryReservation,
 }
 
 impl CollectedStageIteratorTrait for SortStageIterator {
     fn batch_continue(&mut self) -> Result<Option<FixedBatch>, ReadExecutionError> {
-        let Self { unsorted, sorted_indices } = self;
+        let Self { unsorted, sorted_indices, .. } = self;
         if sorted_indices.peek().is_some() {
             let width = unsorted.get_row(0).len();
             let mut next_batch = FixedBatch::new(width as u32);
@@ -204,7 +204,14 @@ impl IntersectionExecutor {
         let executors: Vec<InstructionExecutor> = instructions
             .into_iter()
             .map(|(instruction, variable_modes)| {
-                InstructionExecutor::new(instruction, variable_modes, &**snapshot, thing_manager, sort_variable)
+                InstructionExecutor::new(
+                    instruction,
+                    variable_modes,
+                    &**snapshot,
+                    thing_manager,
+                    sort_variable,
+                    profile.storage_counters(),
+                )
             })
             .try_collect()?;
 
@@ -235,6 +242,14 @@ impl IntersectionExecutor {
     ) -> Result<(), ReadExecutionError> {
         let measurement = self.profile.start_measurement();
         debug_assert!(self.input.is_none() || self.input.as_mut().unwrap().peek().is_none());
+        for executor in &mut self.instruction_executors {
+            executor.prepare_batch(context, &input_batch, self.profile.storage_counters()).map_err(|err| {
+                ReadExecutionError::CreatingIterator {
+                    instruction_name: executor.name().to_string(),
+                    typedb_source: err,
+                }
+            })?;
+        }
         self.input = Some(Peekable::new(FixedBatchRowIterator::new(Ok(input_batch))));
         debug_assert!(self.input.as_mut().unwrap().peek().is_some());
         self.may_create_intersection_iterators(context)?;
@@ -259,7 +274,7 @@ impl IntersectionExecutor {
             // don't allocate batch until 1 answer is confirmed
             let mut batch = FixedBatch::new(self.output_width);
             batch.append(|mut row| self.write_next_row_into(&mut row));
-            while !batch.is_full() && self.compute_next_row(context)? {
+            while !batch.is_full() && context.is_within_row_budget(batch.len()) && self.compute_next_row(context)? {
                 batch.append(|mut row| self.write_next_row_into(&mut row));
             }
             Some(batch)
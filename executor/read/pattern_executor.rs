@@ -212,7 +212,7 @@ impl PatternExecutor {
                 ControlInstruction::CollectingStage(CollectingStage { index, mut collector }) => {
                     let inner = executors[*index].unwrap_collecting_stage().pattern_mut();
                     while let Some(batch) = inner.compute_next_batch(context, interrupt, tabled_functions)? {
-                        collector.accept(context, batch);
+                        collector.accept(context, batch)?;
                     }
                     let iterator = collector.into_iterator(context);
                     self.control_stack.push(StreamCollected { index, iterator }.into());
@@ -157,6 +157,11 @@ impl StreamModifierResultMapperTrait for SelectMapper {
     }
 }
 
+/// Rows removed by an earlier distinct remain in the batch with multiplicity 0, and are not counted by offset or limit.
+fn count_retained_rows(batch: &FixedBatch) -> u64 {
+    (0..batch.len()).filter(|&index| batch.get_row(index).multiplicity() != 0).count() as u64
+}
+
 #[derive(Debug)]
 pub(super) struct OffsetMapper {
     required: u64,
@@ -171,24 +176,27 @@ impl OffsetMapper {
 
 impl StreamModifierResultMapperTrait for OffsetMapper {
     fn map_output(&mut self, subquery_result: Option<FixedBatch>) -> Option<FixedBatch> {
-        if let Some(input_batch) = subquery_result {
-            if self.current >= self.required {
-                Some(input_batch)
-            } else if (self.required - self.current) >= input_batch.len() as u64 {
-                self.current += input_batch.len() as u64;
-                Some(FixedBatch::EMPTY) // Retry this instruction without returning any rows
+        let input_batch = subquery_result?;
+        if self.current >= self.required {
+            return Some(input_batch);
+        }
+        let counted_rows = count_retained_rows(&input_batch);
+        if self.required - self.current >= counted_rows {
+            self.current += counted_rows;
+            return Some(FixedBatch::EMPTY); // Retry this instruction without returning any rows
+        }
+        let mut output_batch = FixedBatch::new(input_batch.width());
+        for row_index in 0..input_batch.len() {
+            let row = input_batch.get_row(row_index);
+            if row.multiplicity() == 0 {
+                continue;
+            } else if self.current < self.required {
+                self.current += 1;
             } else {
-                let offset_in_batch = (self.required - self.current) as u32;
-                let mut output_batch = FixedBatch::new(input_batch.width());
-                for row_index in offset_in_batch..input_batch.len() {
-                    output_batch.append(|mut output_row| output_row.copy_from_row(input_batch.get_row(row_index)));
-                }
-                self.current = self.required;
-                Some(output_batch)
+                output_batch.append(|mut output_row| output_row.copy_from_row(row));
             }
-        } else {
-            None
         }
+        Some(output_batch)
     }
 }
 
@@ -206,28 +214,27 @@ impl LimitMapper {
 
 impl StreamModifierResultMapperTrait for LimitMapper {
     fn map_output(&mut self, subquery_result: Option<FixedBatch>) -> Option<FixedBatch> {
-        if let Some(input_batch) = subquery_result {
+        let input_batch = subquery_result?;
+        if self.current >= self.required {
+            return None;
+        }
+        let counted_rows = count_retained_rows(&input_batch);
+        if self.required - self.current >= counted_rows {
+            self.current += counted_rows;
+            return Some(input_batch);
+        }
+        let mut output_batch = FixedBatch::new(input_batch.width());
+        for row_index in 0..input_batch.len() {
             if self.current >= self.required {
-                None
-            } else if (self.required - self.current) >= input_batch.len() as u64 {
-                self.current += input_batch.len() as u64;
-                Some(input_batch)
-            } else {
-                let mut output_batch = FixedBatch::new(input_batch.width());
-                let mut i = 0;
-                while self.current < self.required {
-                    output_batch.append(|mut output_row| {
-                        output_row.copy_from_row(input_batch.get_row(i));
-                    });
-                    i += 1;
-                    self.current += 1;
-                }
-                debug_assert!(self.current == self.required);
-                Some(output_batch)
+                break;
+            }
+            let row = input_batch.get_row(row_index);
+            if row.multiplicity() != 0 {
+                output_batch.append(|mut output_row| output_row.copy_from_row(row));
+                self.current += 1;
             }
-        } else {
-            None
         }
+        Some(output_batch)
     }
 }
 
@@ -274,7 +281,13 @@ impl LastMapper {
 impl StreamModifierResultMapperTrait for LastMapper {
     fn map_output(&mut self, subquery_result: Option<FixedBatch>) -> Option<FixedBatch> {
         if let Some(input_batch) = subquery_result {
-            self.last_row = (!input_batch.is_empty()).then(|| input_batch.get_row(input_batch.len() - 1).into_owned());
+            let last_row = (0..input_batch.len())
+                .rev()
+                .map(|index| input_batch.get_row(index))
+                .find(|row| row.multiplicity() != 0);
+            if let Some(last_row) = last_row {
+                self.last_row = Some(last_row.into_owned());
+            }
             Some(FixedBatch::EMPTY) // Retry this instruction without returning any rows
         } else {
             self.last_row.take().map(FixedBatch::from)
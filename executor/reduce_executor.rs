@@ -18,10 +18,7 @@ use resource::profile::StorageCounters;
 use storage::snapshot::ReadableSnapshot;
 
 use crate::{
-    batch::Batch,
-    pipeline::{stage::ExecutionContext, PipelineExecutionError},
-    row::MaybeOwnedRow,
-    Provenance,
+    batch::Batch, error::ReadExecutionError, pipeline::stage::ExecutionContext, row::MaybeOwnedRow, Provenance,
 };
 
 #[derive(Debug)]
@@ -54,7 +51,7 @@ impl GroupedReducer {
         &mut self,
         row: &MaybeOwnedRow<'_>,
         context: &ExecutionContext<Snapshot>,
-    ) -> Result<(), Box<PipelineExecutionError>> {
+    ) -> Result<(), ReadExecutionError> {
         self.reused_group.clear();
         for &pos in &self.rows_executable.input_group_positions {
             self.reused_group.push(row.get(pos).to_owned());
@@ -64,7 +61,7 @@ impl GroupedReducer {
         }
         let reducers = self.grouped_reductions.get_mut(&self.reused_group).unwrap();
         for reducer in reducers {
-            reducer.accept(row, context);
+            reducer.accept(row, context)?;
         }
         Ok(())
     }
@@ -132,23 +129,31 @@ macro_rules! reducer_executor {
             #[derive(Debug, Clone)]
             enum ReducerExecutor {
                 $count([<$count Executor>]),
+                List(ListExecutor),
                 $($variant([<$variant Executor>])),*
             }
 
             impl ReducerExecutor {
-                fn accept<Snapshot: ReadableSnapshot>(&mut self, row: &MaybeOwnedRow<'_>, context: &ExecutionContext<Snapshot>) {
+                fn accept<Snapshot: ReadableSnapshot>(
+                    &mut self,
+                    row: &MaybeOwnedRow<'_>,
+                    context: &ExecutionContext<Snapshot>,
+                ) -> Result<(), ReadExecutionError> {
                     let profile = context.profile.profile_stage(|| String::from("Reduce"), 0); // TODO executable id
                     let step_profile = profile.extend_or_get(0, || String::from("Reduce execution"));
                     let storage_counters = step_profile.storage_counters();
                     match self {
                         Self::$count(reducer) => reducer.accept(row, context, storage_counters),
+                        Self::List(reducer) => return reducer.accept(row, context, storage_counters),
                         $(Self::$variant(reducer) => reducer.accept(row, context, storage_counters)),*
                     }
+                    Ok(())
                 }
 
                 fn finalise(self) -> Option<VariableValue<'static>> {
                     match self {
                         Self::$count(reducer) => reducer.finalise(),
+                        Self::List(reducer) => reducer.finalise(),
                         $(Self::$variant(reducer) => reducer.finalise()),*
                     }
                 }
@@ -158,6 +163,7 @@ macro_rules! reducer_executor {
                 fn build(reduce_ir: &ReduceInstruction<VariablePosition>) -> Self {
                     match *reduce_ir {
                         ReduceInstruction::$count => ReducerExecutor::$count([<$count Executor>]::new()),
+                        ReduceInstruction::List(pos, _) => ReducerExecutor::List(ListExecutor::new(pos)),
                         $(ReduceInstruction::$variant(pos) => Self::$variant([<$variant Executor>]::new(pos))),*
                     }
                 }
@@ -232,6 +238,39 @@ impl ReducerAPI for CountVarExecutor {
     }
 }
 
+/// Unlike the other reducers, a list grows with its group, so it fails once the group has more values than allowed
+#[derive(Debug, Clone)]
+struct ListExecutor {
+    values: Vec<Value<'static>>,
+    target: VariablePosition,
+}
+
+impl ListExecutor {
+    fn new(target: VariablePosition) -> Self {
+        Self { values: Vec::new(), target }
+    }
+
+    fn accept<Snapshot: ReadableSnapshot>(
+        &mut self,
+        row: &MaybeOwnedRow<'_>,
+        context: &ExecutionContext<Snapshot>,
+        storage_counters: StorageCounters,
+    ) -> Result<(), ReadExecutionError> {
+        let Some(value) = extract_value(row, self.target, context, storage_counters) else { return Ok(()) };
+        let repeats = row.multiplicity() as usize;
+        let limit = context.max_reduce_list_size;
+        if self.values.len().saturating_add(repeats) > limit {
+            return Err(ReadExecutionError::ReduceListSizeLimitExceeded { limit });
+        }
+        self.values.extend(std::iter::repeat_n(value, repeats));
+        Ok(())
+    }
+
+    fn finalise(self) -> Option<VariableValue<'static>> {
+        Some(VariableValue::ValueList(Arc::from(self.values)))
+    }
+}
+
 macro_rules! sum_reducer_executors {
     ($($ty:ident($repr:ty) $u64_to_repr:expr),* $(,)?) => {$(
         paste! {
@@ -653,131 +692,73 @@ impl ReducerAPI for MedianDecimalExecutor {
     }
 }
 
-#[derive(Debug, Clone)]
-struct StdIntegerExecutor {
-    sum: i64,
-    sum_squares: i128,
+/// Streaming sample variance over weighted values, using Welford's algorithm to avoid the cancellation
+/// of subtracting large sums of squares.
+#[derive(Debug, Clone, Default)]
+struct WelfordAccumulator {
     count: u64,
-    target: VariablePosition,
+    mean: f64,
+    sum_squared_deviations: f64,
 }
 
-impl StdIntegerExecutor {
-    fn new(target: VariablePosition) -> Self {
-        Self { sum: 0, sum_squares: 0, count: 0, target }
-    }
-}
-
-impl ReducerAPI for StdIntegerExecutor {
-    fn accept<Snapshot: ReadableSnapshot>(
-        &mut self,
-        row: &MaybeOwnedRow<'_>,
-        context: &ExecutionContext<Snapshot>,
-        storage_counters: StorageCounters,
-    ) {
-        if let Some(value) = extract_value(row, self.target, context, storage_counters) {
-            let unwrapped = value.unwrap_integer();
-            self.sum_squares += (unwrapped as i128 * unwrapped as i128) * row.multiplicity() as i128;
-            self.sum += unwrapped * row.multiplicity() as i64;
-            self.count += row.multiplicity();
+impl WelfordAccumulator {
+    fn add(&mut self, value: f64, multiplicity: u64) {
+        if multiplicity == 0 {
+            return;
         }
+        self.count += multiplicity;
+        let delta = value - self.mean;
+        self.mean += delta * multiplicity as f64 / self.count as f64;
+        self.sum_squared_deviations += delta * (value - self.mean) * multiplicity as f64;
     }
 
-    fn finalise(self) -> Option<VariableValue<'static>> {
+    fn sample_variance(&self) -> Option<f64> {
         if self.count > 1 {
-            let sum = self.sum as f64;
-            let sum_squares = self.sum_squares as f64;
-            let n = self.count as f64;
-            let mean = sum / n;
-            let sample_variance: f64 = (sum_squares + n * mean * mean - 2.0 * sum * mean) / (n - 1.0);
-            Some(VariableValue::Value(Value::Double(sample_variance.sqrt())))
+            Some((self.sum_squared_deviations / (self.count - 1) as f64).max(0.0))
         } else {
             None
         }
     }
 }
 
-#[derive(Debug, Clone)]
-struct StdDoubleExecutor {
-    sum: f64,
-    sum_squares: f64,
-    count: u64,
-    target: VariablePosition,
-}
+macro_rules! std_reducer_executors {
+    ($($ty:ident($to_f64:expr)),* $(,)?) => {$(
+        paste! {
+            #[derive(Debug, Clone)]
+            struct [< Std $ty Executor >] {
+                accumulator: WelfordAccumulator,
+                target: VariablePosition,
+            }
 
-impl StdDoubleExecutor {
-    fn new(target: VariablePosition) -> Self {
-        Self { sum: 0.0, sum_squares: 0.0, count: 0, target }
-    }
-}
+            impl [< Std $ty Executor >] {
+                fn new(target: VariablePosition) -> Self {
+                    Self { accumulator: WelfordAccumulator::default(), target }
+                }
+            }
 
-impl ReducerAPI for StdDoubleExecutor {
-    fn accept<Snapshot: ReadableSnapshot>(
-        &mut self,
-        row: &MaybeOwnedRow<'_>,
-        context: &ExecutionContext<Snapshot>,
-        storage_counters: StorageCounters,
-    ) {
-        if let Some(value) = extract_value(row, self.target, context, storage_counters) {
-            let unwrapped = value.unwrap_double();
-            self.sum_squares += (unwrapped * unwrapped) * row.multiplicity() as f64;
-            self.sum += unwrapped * row.multiplicity() as f64;
-            self.count += row.multiplicity();
-        }
-    }
+            impl ReducerAPI for [< Std $ty Executor >] {
+                fn accept<Snapshot: ReadableSnapshot>(
+                    &mut self,
+                    row: &MaybeOwnedRow<'_>,
+                    context: &ExecutionContext<Snapshot>,
+                    storage_counters: StorageCounters,
+                ) {
+                    if let Some(value) = extract_value(row, self.target, context, storage_counters) {
+                        self.accumulator.add($to_f64(value), row.multiplicity());
+                    }
+                }
 
-    fn finalise(self) -> Option<VariableValue<'static>> {
-        if self.count > 1 {
-            let sum = self.sum;
-            let sum_squares = self.sum_squares;
-            let n = self.count as f64;
-            let mean = sum / n;
-            let sample_variance: f64 = (sum_squares + n * mean * mean - 2.0 * sum * mean) / (n - 1.0);
-            Some(VariableValue::Value(Value::Double(sample_variance.sqrt())))
-        } else {
-            None
+                fn finalise(self) -> Option<VariableValue<'static>> {
+                    let variance = self.accumulator.sample_variance()?;
+                    Some(VariableValue::Value(Value::Double(variance.sqrt())))
+                }
+            }
         }
-    }
-}
-
-#[derive(Debug, Clone)]
-struct StdDecimalExecutor {
-    sum: Decimal,
-    sum_squares: Decimal,
-    count: u64,
-    target: VariablePosition,
-}
-
-impl StdDecimalExecutor {
-    fn new(target: VariablePosition) -> Self {
-        Self { sum: Decimal::default(), sum_squares: Decimal::default(), count: 0, target }
-    }
+    )*};
 }
 
-impl ReducerAPI for StdDecimalExecutor {
-    fn accept<Snapshot: ReadableSnapshot>(
-        &mut self,
-        row: &MaybeOwnedRow<'_>,
-        context: &ExecutionContext<Snapshot>,
-        storage_counters: StorageCounters,
-    ) {
-        if let Some(value) = extract_value(row, self.target, context, storage_counters) {
-            let unwrapped = value.unwrap_decimal();
-            self.sum_squares += (unwrapped * unwrapped) * row.multiplicity();
-            self.sum += unwrapped * row.multiplicity();
-            self.count += row.multiplicity();
-        }
-    }
-
-    fn finalise(self) -> Option<VariableValue<'static>> {
-        if self.count > 1 {
-            let sum = self.sum;
-            let sum_squares = self.sum_squares;
-            let n = self.count;
-            let mean = sum / n;
-            let sample_variance: Decimal = (sum_squares + n * mean * mean - 2 * sum * mean) / (n - 1);
-            Some(VariableValue::Value(Value::Double(sample_variance.to_f64().sqrt())))
-        } else {
-            None
-        }
-    }
+std_reducer_executors! {
+    Integer(|value: Value<'_>| value.unwrap_integer() as f64),
+    Double(|value: Value<'_>| value.unwrap_double()),
+    Decimal(|value: Value<'_>| value.unwrap_decimal().to_f64()),
 }
@@ -4,7 +4,15 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::{borrow::Cow, fmt, ops::Deref, slice, vec};
+use std::{
+    borrow::Cow,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Deref, Range},
+    slice,
+    sync::Arc,
+    vec,
+};
 
 use answer::variable_value::VariableValue;
 use compiler::VariablePosition;
@@ -112,9 +120,62 @@ impl fmt::Display for Row<'_> {
     }
 }
 
+/// The values of a row, which rows taken out of one batch can share in a single allocation,
+/// freed once the last of those rows is dropped.
+#[derive(Debug, Clone)]
+enum RowValues<'a> {
+    Borrowed(&'a [VariableValue<'static>]),
+    Owned(Vec<VariableValue<'static>>),
+    Arena(Arc<[VariableValue<'static>]>, Range<usize>),
+}
+
+impl RowValues<'_> {
+    fn into_static(self) -> RowValues<'static> {
+        match self {
+            RowValues::Borrowed(values) => RowValues::Owned(values.to_vec()),
+            RowValues::Owned(values) => RowValues::Owned(values),
+            RowValues::Arena(arena, range) => RowValues::Arena(arena, range),
+        }
+    }
+
+    fn into_vec(self) -> Vec<VariableValue<'static>> {
+        match self {
+            RowValues::Borrowed(values) => values.to_vec(),
+            RowValues::Owned(values) => values,
+            RowValues::Arena(arena, range) => arena[range].to_vec(),
+        }
+    }
+}
+
+impl Deref for RowValues<'_> {
+    type Target = [VariableValue<'static>];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            RowValues::Borrowed(values) => values,
+            RowValues::Owned(values) => values,
+            RowValues::Arena(arena, range) => &arena[range.clone()],
+        }
+    }
+}
+
+impl Hash for RowValues<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl PartialEq for RowValues<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for RowValues<'_> {}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct MaybeOwnedRow<'a> {
-    row: Cow<'a, [VariableValue<'static>]>,
+    row: RowValues<'a>,
     multiplicity: Cow<'a, u64>,
     provenance: Cow<'a, Provenance>, // TODO: Review: are we not better off without the Cow?
 }
@@ -130,7 +191,7 @@ impl<'a> MaybeOwnedRow<'a> {
         provenance: &'a Provenance,
     ) -> Self {
         Self {
-            row: Cow::Borrowed(row),
+            row: RowValues::Borrowed(row),
             multiplicity: Cow::Borrowed(multiplicity),
             provenance: Cow::Borrowed(provenance),
         }
@@ -142,12 +203,25 @@ impl<'a> MaybeOwnedRow<'a> {
 
     // TODO: pub(crate)
     pub fn empty() -> Self {
-        Self { row: Cow::Owned(Vec::new()), multiplicity: Cow::Owned(1), provenance: Cow::Owned(Provenance(0)) }
+        Self { row: RowValues::Owned(Vec::new()), multiplicity: Cow::Owned(1), provenance: Cow::Owned(Provenance(0)) }
     }
 
     // TODO: pub(crate)
     pub fn new_owned(row: Vec<VariableValue<'static>>, multiplicity: u64, provenance: Provenance) -> Self {
-        Self { row: Cow::Owned(row), multiplicity: Cow::Owned(multiplicity), provenance: Cow::Owned(provenance) }
+        Self { row: RowValues::Owned(row), multiplicity: Cow::Owned(multiplicity), provenance: Cow::Owned(provenance) }
+    }
+
+    pub(crate) fn new_in_arena(
+        arena: Arc<[VariableValue<'static>]>,
+        range: Range<usize>,
+        multiplicity: u64,
+        provenance: Provenance,
+    ) -> MaybeOwnedRow<'static> {
+        MaybeOwnedRow {
+            row: RowValues::Arena(arena, range),
+            multiplicity: Cow::Owned(multiplicity),
+            provenance: Cow::Owned(provenance),
+        }
     }
 
     pub fn get(&self, position: VariablePosition) -> &VariableValue<'_> {
@@ -163,28 +237,28 @@ impl<'a> MaybeOwnedRow<'a> {
     }
 
     pub fn row(&self) -> &[VariableValue<'static>] {
-        self.row.as_ref()
+        &self.row
     }
 
+    /// Rows sharing a batch's values keep sharing them, rather than being copied.
     pub fn into_owned(self) -> MaybeOwnedRow<'static> {
-        let (row_vec, multiplicity, provenance) = self.into_owned_parts();
         MaybeOwnedRow {
-            row: Cow::Owned(row_vec),
-            multiplicity: Cow::Owned(multiplicity),
-            provenance: Cow::Owned(provenance),
+            row: self.row.into_static(),
+            multiplicity: Cow::Owned(self.multiplicity.into_owned()),
+            provenance: Cow::Owned(self.provenance.into_owned()),
         }
     }
 
     pub fn as_reference(&self) -> MaybeOwnedRow<'_> {
         MaybeOwnedRow {
-            row: Cow::Borrowed(self.row.as_ref()),
+            row: RowValues::Borrowed(&self.row),
             multiplicity: Cow::Borrowed(self.multiplicity.as_ref()),
             provenance: Cow::Borrowed(self.provenance.as_ref()),
         }
     }
 
     pub fn into_owned_parts(self) -> (Vec<VariableValue<'static>>, u64, Provenance) {
-        (self.row.into_owned(), self.multiplicity.into_owned(), self.provenance.into_owned())
+        (self.row.into_vec(), self.multiplicity.into_owned(), self.provenance.into_owned())
     }
 }
 
@@ -200,8 +274,7 @@ impl IntoIterator for MaybeOwnedRow<'static> {
     type Item = VariableValue<'static>;
     type IntoIter = vec::IntoIter<VariableValue<'static>>;
     fn into_iter(self) -> Self::IntoIter {
-        #[allow(clippy::unnecessary_to_owned)]
-        self.row.into_owned().into_iter()
+        self.row.into_vec().into_iter()
     }
 }
 
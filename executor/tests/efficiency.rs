@@ -525,6 +525,7 @@ fn value_int_equality_isa_reads() {
         lhs: CheckVertex::Variable(var_attr),
         rhs: CheckVertex::Parameter(value_int_2_id),
         comparator: Comparator::Equal,
+        value_types: None,
     }
     .map(&mapping);
     let mut isa_reverse_instruction =
@@ -633,6 +634,7 @@ fn value_int_equality_has_reverse_reads() {
         lhs: CheckVertex::Variable(var_gov_id),
         rhs: CheckVertex::Parameter(value_int_1_id),
         comparator: Comparator::Equal,
+        value_types: None,
     }
     .map(&mapping);
     let mut has_reverse_instruction =
@@ -728,6 +730,7 @@ fn value_int_equality_has_bound_owner() {
         lhs: CheckVertex::Variable(var_gov_id),
         rhs: CheckVertex::Parameter(value_int_1_id),
         comparator: Comparator::Equal,
+        value_types: None,
     }
     .map(&mapping);
     let mut has_instruction = HasInstruction::new(has, Inputs::Single([var_person]), &type_annotations).map(&mapping);
@@ -839,12 +842,14 @@ fn value_int_inequality_has_bound_owner() {
         lhs: CheckVertex::Variable(var_gov_id),
         rhs: CheckVertex::Parameter(value_int_1_id),
         comparator: Comparator::GreaterOrEqual,
+        value_types: None,
     }
     .map(&mapping);
     let lesser_value_check = CheckInstruction::Comparison {
         lhs: CheckVertex::Variable(var_gov_id),
         rhs: CheckVertex::Parameter(value_int_3_id),
         comparator: Comparator::Less,
+        value_types: None,
     }
     .map(&mapping);
     let mut has_instruction = HasInstruction::new(has, Inputs::Single([var_person]), &type_annotations).map(&mapping);
@@ -948,6 +953,7 @@ fn value_inline_string_equality_has_bound_owner() {
         lhs: CheckVertex::Variable(var_name),
         rhs: CheckVertex::Parameter(value_string_abby),
         comparator: Comparator::Equal,
+        value_types: None,
     }
     .map(&mapping);
     let mut has_instruction = HasInstruction::new(has, Inputs::Single([var_person]), &entry_annotations).map(&mapping);
@@ -1053,6 +1059,7 @@ fn value_hashed_string_equality_has_bound_owner() {
         lhs: CheckVertex::Variable(var_name),
         rhs: CheckVertex::Parameter(value_string_hashed),
         comparator: Comparator::Equal,
+        value_types: None,
     }
     .map(&mapping);
     let mut has_instruction = HasInstruction::new(has, Inputs::Single([var_person]), &type_annotations).map(&mapping);
@@ -1168,12 +1175,14 @@ fn value_string_inequality_reduces_has_reads_bound_owner() {
         lhs: CheckVertex::Variable(var_name),
         rhs: CheckVertex::Parameter(value_string_bolton),
         comparator: Comparator::GreaterOrEqual,
+        value_types: None,
     }
     .map(&mapping);
     let lesser_value_check = CheckInstruction::Comparison {
         lhs: CheckVertex::Variable(var_name),
         rhs: CheckVertex::Parameter(value_string_willow),
         comparator: Comparator::Less,
+        value_types: None,
     }
     .map(&mapping);
     let mut has_instruction = HasInstruction::new(has, Inputs::Single([var_person]), &type_annotations).map(&mapping);
@@ -1292,6 +1301,7 @@ fn intersection_seeks() {
         lhs: CheckVertex::Variable(var_age),
         rhs: CheckVertex::Parameter(value_int_10),
         comparator: Comparator::Equal,
+        value_types: None,
     }
     .map(&mapping);
     let mut isa_age = IsaReverseInstruction::new(isa_age, Inputs::None([]), &type_annotations).map(&mapping);
@@ -1475,12 +1485,14 @@ fn intersections_seeks_with_extra_values() {
         lhs: CheckVertex::Variable(var_age),
         rhs: CheckVertex::Parameter(value_int_12),
         comparator: Comparator::Equal,
+        value_types: None,
     }
     .map(&mapping);
     let gov_id_gt_2 = CheckInstruction::Comparison {
         lhs: CheckVertex::Variable(var_gov_id),
         rhs: CheckVertex::Parameter(value_int_2),
         comparator: Comparator::Greater,
+        value_types: None,
     }
     .map(&mapping);
     let mut isa_age = IsaReverseInstruction::new(isa_age, Inputs::None([]), &type_annotations).map(&mapping);
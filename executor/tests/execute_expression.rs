@@ -7,6 +7,7 @@
 use std::collections::HashMap;
 
 use answer::variable::Variable;
+use chrono::Timelike;
 use compiler::annotation::expression::{
     compiled_expression::{ExecutableExpression, ExpressionValueType},
     expression_compiler::ExpressionCompilationContext,
@@ -272,6 +273,33 @@ fn test_functions() {
     assert!(matches!(*source, RepresentationError::ExpressionBuiltinArgumentCountMismatch { .. }));
 }
 
+#[test]
+fn test_in_timezone() {
+    {
+        let (_, expr, params) =
+            compile_expression_via_match("in_timezone(2024-06-01T12:00:00+00:00, \"Europe/London\")", HashMap::new())
+                .unwrap();
+        let result = evaluate_expression(&expr, HashMap::new(), &params).unwrap();
+        let Value::DateTimeTZ(date_time) = as_value!(result) else { panic!("expected a datetime-tz") };
+        assert_eq!(date_time.timezone().to_string(), "Europe/London");
+        assert_eq!(date_time.naive_local().hour(), 13);
+        assert_eq!(date_time.naive_utc().hour(), 12);
+    }
+
+    {
+        let (_, expr, params) =
+            compile_expression_via_match("in_timezone(2024-06-01T12:00:00+00:00, \"Mars/Olympus\")", HashMap::new())
+                .unwrap();
+        assert!(evaluate_expression(&expr, HashMap::new(), &params).is_err());
+    }
+
+    let err = compile_expression_via_match("in_timezone(2024-06-01T12:00:00+00:00)", HashMap::new()).unwrap_err();
+    let PatternDefitionOrExpressionCompileError::PatternDefinition { source } = err else {
+        panic!("wrong error type");
+    };
+    assert!(matches!(*source, RepresentationError::ExpressionBuiltinArgumentCountMismatch { .. }));
+}
+
 #[test]
 fn list_ops() {
     {
@@ -4,13 +4,21 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::{collections::HashMap, iter, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    iter,
+    sync::Arc,
+};
 
-use answer::variable_value::VariableValue;
-use compiler::VariablePosition;
+use answer::{variable_value::VariableValue, Thing};
+use compiler::{annotation::limits::CompileLimits, VariablePosition};
 use concept::{thing::thing_manager::ThingManager, type_::type_manager::TypeManager};
-use encoding::{graph::definition::definition_key_generator::DefinitionKeyGenerator, value::value::Value};
+use encoding::{
+    graph::definition::definition_key_generator::DefinitionKeyGenerator,
+    value::{label::Label, value::Value},
+};
 use executor::{
+    error::ReadExecutionError,
     pipeline::{stage::ExecutionContext, PipelineExecutionError},
     row::MaybeOwnedRow,
     ExecutionInterrupt,
@@ -19,7 +27,7 @@ use function::function_manager::FunctionManager;
 use itertools::Either;
 use lending_iterator::LendingIterator;
 use query::{query_cache::QueryCache, query_manager::QueryManager};
-use resource::profile::CommitProfile;
+use resource::profile::{CommitProfile, StorageCounters};
 use storage::{durability_client::WALClient, snapshot::CommittableSnapshot, MVCCStorage};
 use test_utils::TempDir;
 use test_utils_concept::{load_managers, setup_concept_storage};
@@ -664,6 +672,215 @@ fn reduce_depends_on_cyclic_function() {
     }
 }
 
+#[test]
+fn reduce_into_list() {
+    let custom_schema = r#"define
+        attribute name value string;
+        attribute weight value integer;
+        entity node, owns name, owns weight @card(0..);
+    "#;
+    let context = setup_common(custom_schema);
+    let data = r#"
+    insert
+        $n1 isa node, has name "n1", has weight 12, has weight 34;
+        $n2 isa node, has name "n2", has weight 56;
+    "#;
+    let (rows, _positions) = run_write_query(&context, data).unwrap();
+    assert_eq!(1, rows.len());
+
+    let sorted_weights = |value: &VariableValue<'_>| {
+        let VariableValue::ValueList(values) = value else { panic!("Expected a list, found: {value:?}") };
+        let mut weights: Vec<_> = values.iter().map(|value| value.clone().unwrap_integer()).collect();
+        weights.sort();
+        weights
+    };
+
+    {
+        let query = r#"
+            match $n isa node, has name $name, has weight $w;
+            reduce $weights = list($w) groupby $name;
+            sort $name;
+        "#;
+        let (rows, positions) = run_read_query(&context, query).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(sorted_weights(rows[0].get(*positions.get("weights").unwrap())), vec![12, 34]);
+        assert_eq!(sorted_weights(rows[1].get(*positions.get("weights").unwrap())), vec![56]);
+    }
+
+    {
+        let query = r#"
+            with
+            fun weights_of($n: node) -> integer[]:
+            match
+                $n has weight $w;
+            return list($w);
+
+            match
+                $n isa node, has name "n1";
+                let $weights = weights_of($n);
+        "#;
+        let (rows, positions) = run_read_query(&context, query).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(sorted_weights(rows[0].get(*positions.get("weights").unwrap())), vec![12, 34]);
+    }
+}
+
+#[test]
+fn reduce_into_list_fails_past_the_size_limit() {
+    let custom_schema = r#"define
+        attribute name value string;
+        attribute weight value integer;
+        entity node, owns name, owns weight @card(0..);
+    "#;
+    let limits = CompileLimits { max_reduce_list_size: 2, ..CompileLimits::default() };
+    let context =
+        Context { query_manager: QueryManager::new(None).with_compile_limits(limits), ..setup_common(custom_schema) };
+    let data = r#"
+    insert
+        $n1 isa node, has name "n1", has weight 12, has weight 34, has weight 56;
+        $n2 isa node, has name "n2", has weight 78, has weight 90;
+    "#;
+    let (rows, _positions) = run_write_query(&context, data).unwrap();
+    assert_eq!(1, rows.len());
+
+    let is_size_limit_error = |err: &PipelineExecutionError| {
+        matches!(
+            err,
+            PipelineExecutionError::ReadPatternExecution {
+                typedb_source: ReadExecutionError::ReduceListSizeLimitExceeded { limit: 2 }
+            }
+        )
+    };
+
+    {
+        let query = r#"
+            match $n isa node, has name "n2", has weight $w;
+            reduce $weights = list($w);
+        "#;
+        let (rows, _positions) = run_read_query(&context, query).unwrap();
+        assert_eq!(rows.len(), 1);
+    }
+
+    {
+        let query = r#"
+            match $n isa node, has name $name, has weight $w;
+            reduce $weights = list($w) groupby $name;
+        "#;
+        let err = run_read_query(&context, query).unwrap_err();
+        assert!(is_size_limit_error(&err), "{err:?}");
+    }
+
+    {
+        let query = r#"
+            with
+            fun weights_of($n: node) -> integer[]:
+            match
+                $n has weight $w;
+            return list($w);
+
+            match
+                $n isa node, has name "n1";
+                let $weights = weights_of($n);
+        "#;
+        let err = run_read_query(&context, query).unwrap_err();
+        assert!(is_size_limit_error(&err), "{err:?}");
+    }
+}
+
+#[test]
+fn reduce_over_optional_values() {
+    let custom_schema = r#"define
+        attribute name value string;
+        attribute weight value integer;
+        entity node, owns name, owns weight;
+    "#;
+    let context = setup_common(custom_schema);
+    let data = r#"
+    insert
+        $n1 isa node, has name "n1", has weight 12;
+        $n2 isa node, has name "n2", has weight 34;
+        $n3 isa node, has name "n3";
+    "#;
+    let (rows, _positions) = run_write_query(&context, data).unwrap();
+    assert_eq!(1, rows.len());
+
+    let query = r#"
+        match
+            $n isa node;
+            try { $n has weight $w; };
+        reduce $rows = count, $weights = count($w), $sum = sum($w), $mean = mean($w), $max = max($w);
+    "#;
+    let (rows, positions) = run_read_query(&context, query).unwrap();
+    assert_eq!(rows.len(), 1);
+    let get = |name: &str| rows[0].get(*positions.get(name).unwrap()).clone();
+    assert_eq!(get("rows"), VariableValue::Value(Value::Integer(3)));
+    assert_eq!(get("weights"), VariableValue::Value(Value::Integer(2)));
+    assert_eq!(get("sum"), VariableValue::Value(Value::Integer(46)));
+    assert_eq!(get("mean"), VariableValue::Value(Value::Double(23.0)));
+    assert_eq!(get("max"), VariableValue::Value(Value::Integer(34)));
+}
+
+#[test]
+fn reduce_std_of_large_values() {
+    let custom_schema = r#"define
+        attribute weight value integer;
+        entity node, owns weight;
+    "#;
+    let context = setup_common(custom_schema);
+    let data = r#"
+    insert
+        $n1 isa node, has weight 1000000004;
+        $n2 isa node, has weight 1000000007;
+        $n3 isa node, has weight 1000000013;
+        $n4 isa node, has weight 1000000016;
+    "#;
+    run_write_query(&context, data).unwrap();
+
+    let query = r#"
+        match $n isa node, has weight $w;
+        reduce $std = std($w);
+    "#;
+    let (rows, positions) = run_read_query(&context, query).unwrap();
+    assert_eq!(rows.len(), 1);
+    let VariableValue::Value(Value::Double(std)) = rows[0].get(*positions.get("std").unwrap()).clone() else {
+        panic!("Expected a double standard deviation");
+    };
+    // the deviations from the mean are -6, -3, 3 and 6, so the sample variance is 90 / 3
+    assert!((std - 30f64.sqrt()).abs() < 1e-9, "{std}");
+}
+
+#[test]
+fn reduce_median_counts_each_answer_once() {
+    let custom_schema = r#"define
+        attribute weight value integer;
+        entity node, owns weight;
+    "#;
+    let context = setup_common(custom_schema);
+    let data = r#"
+    insert
+        $n1 isa node, has weight 5;
+        $n2 isa node, has weight 5;
+        $n3 isa node, has weight 9;
+        $n4 isa node, has weight 12;
+    "#;
+    run_write_query(&context, data).unwrap();
+
+    let query = r#"
+        match $n isa node, has weight $w;
+        reduce $median = median($w);
+    "#;
+    let (rows, positions) = run_read_query(&context, query).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get(*positions.get("median").unwrap()), &VariableValue::Value(Value::Double(7.0)));
+
+    let query = r#"
+        match $n isa node, has weight $w; $w > 5;
+        reduce $median = median($w);
+    "#;
+    let (rows, positions) = run_read_query(&context, query).unwrap();
+    assert_eq!(rows[0].get(*positions.get("median").unwrap()), &VariableValue::Value(Value::Double(10.5)));
+}
+
 #[test]
 fn write_pipelines() {
     let context = setup_common(
@@ -748,3 +965,110 @@ fn return_check() {
         assert_eq!(rows[0].get(*positions.get("checked").unwrap()), &VariableValue::Value(Value::Boolean(false)));
     }
 }
+
+#[test]
+fn stream_modifiers_in_function_body() {
+    let context = setup_common(COMMON_SCHEMA);
+    let insert_query_str = r#"insert
+        $p1 isa person, has age 10;
+        $p2 isa person, has age 20;
+        $p3 isa person, has age 30;
+        $p4 isa person, has age 40;
+        $p5 isa person, has age 20;"#;
+    let (rows, _positions) = run_write_query(&context, insert_query_str).unwrap();
+    assert_eq!(1, rows.len());
+
+    let snapshot = context.storage.clone().open_snapshot_read();
+    let age_type = context.type_manager.get_attribute_type(&snapshot, &Label::build("age", None)).unwrap().unwrap();
+    let ages = |values: &[i64]| {
+        values
+            .iter()
+            .map(|value| {
+                let attribute = context
+                    .thing_manager
+                    .get_attribute_with_value(&snapshot, age_type, Value::Integer(*value), StorageCounters::DISABLED)
+                    .unwrap()
+                    .unwrap();
+                VariableValue::Thing(Thing::Attribute(attribute))
+            })
+            .collect::<HashSet<_>>()
+    };
+    let returned_ages = |rows: &[MaybeOwnedRow<'static>], position: VariablePosition| {
+        rows.iter().map(|row| row.get(position).clone()).collect::<HashSet<_>>()
+    };
+
+    {
+        let query = r#"
+            with
+            fun second_and_third_oldest() -> { age }:
+            match
+                $p isa person, has age $a;
+            sort $a desc;
+            offset 1;
+            limit 2;
+            return { $a };
+
+            match
+                let $a in second_and_third_oldest();
+        "#;
+        let (rows, positions) = run_read_query(&context, query).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(returned_ages(&rows, positions["a"]), ages(&[20, 30]));
+    }
+
+    {
+        // duplicates removed by distinct must not count towards the limit
+        let query = r#"
+            with
+            fun youngest_distinct_ages() -> { age }:
+            match
+                $p isa person, has age $a;
+            select $a;
+            sort $a;
+            distinct;
+            limit 3;
+            return { $a };
+
+            match
+                let $a in youngest_distinct_ages();
+        "#;
+        let (rows, positions) = run_read_query(&context, query).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(returned_ages(&rows, positions["a"]), ages(&[10, 20, 30]));
+    }
+}
+
+#[test]
+fn stream_modifier_after_recursive_call_is_rejected() {
+    let custom_schema = r#"define
+        attribute name value string;
+        entity node, owns name @card(0..), plays edge:start, plays edge:end_;
+        relation edge, relates start, relates end_;
+    "#;
+    let context = setup_common(custom_schema);
+    let query = r#"
+        with
+        fun reachable($start: node) -> { node }:
+        match
+            { edge (start: $start, end_: $to); } or
+            { let $middle in reachable($start); edge (start: $middle, end_: $to); };
+        limit 3;
+        return { $to };
+
+        match
+            $start isa node;
+            let $to in reachable($start);
+    "#;
+    let snapshot = Arc::new(context.storage.clone().open_snapshot_read());
+    let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    let result = context.query_manager.prepare_read_pipeline(
+        snapshot,
+        &context.type_manager,
+        context.thing_manager.clone(),
+        &context.function_manager,
+        &pipeline,
+        query,
+    );
+    let error = result.err().expect("Expected the limit after a recursive call to be rejected");
+    assert!(format!("{error:?}").contains("ECP8"), "{error:?}");
+}
@@ -243,6 +243,114 @@ fn traverse_has_unbounded_sorted_from() {
     }
 }
 
+#[test]
+fn traverse_has_bound_from_batch_of_owners() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_database(&mut storage);
+
+    // query:
+    //   match
+    //    $person isa person;
+    //    $person has age $age;
+    // every person arrives in the same input batch, and persons also own names which must not be returned
+
+    // IR
+    let mut translation_context = PipelineTranslationContext::new();
+    let mut value_parameters = ParameterRegistry::new();
+    let mut builder = Block::builder(translation_context.new_block_builder_context(&mut value_parameters));
+    let mut conjunction = builder.conjunction_mut();
+    let var_person_type = conjunction.constraints_mut().get_or_declare_variable("person_type", None).unwrap();
+    let var_age_type = conjunction.constraints_mut().get_or_declare_variable("age_type", None).unwrap();
+    let var_person = conjunction.constraints_mut().get_or_declare_variable("person", None).unwrap();
+    let var_age = conjunction.constraints_mut().get_or_declare_variable("age", None).unwrap();
+
+    let isa_person = conjunction
+        .constraints_mut()
+        .add_isa(IsaKind::Subtype, var_person, var_person_type.into(), None)
+        .unwrap()
+        .clone();
+    let has_age = conjunction.constraints_mut().add_has(var_person, var_age, None).unwrap().clone();
+
+    // add all constraints to make type inference return correct types, though we only plan Isa and Has
+    conjunction.constraints_mut().add_isa(IsaKind::Subtype, var_age, var_age_type.into(), None).unwrap();
+    conjunction.constraints_mut().add_label(var_person_type, PERSON_LABEL.clone()).unwrap();
+    conjunction.constraints_mut().add_label(var_age_type, AGE_LABEL.clone()).unwrap();
+    let entry = builder.finish().unwrap();
+
+    let snapshot = storage.clone().open_snapshot_read();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+
+    let variable_registry = &translation_context.variable_registry;
+    let previous_stage_variable_annotations = &BTreeMap::new();
+    let block_annotations = infer_types(
+        &snapshot,
+        &entry,
+        variable_registry,
+        &type_manager,
+        previous_stage_variable_annotations,
+        &EmptyAnnotatedFunctionSignatures,
+        false,
+    )
+    .unwrap();
+    let entry_annotations = block_annotations.type_annotations_of(entry.conjunction()).unwrap();
+
+    let (row_vars, variable_positions, mapping, named_variables) =
+        position_mapping([var_person, var_age], [var_age_type, var_person_type]);
+    let age_position = variable_positions[&var_age];
+    let age_type = type_manager.get_attribute_type(&snapshot, &AGE_LABEL).unwrap().unwrap();
+
+    // Plan
+    let steps = vec![
+        ExecutionStep::Intersection(IntersectionStep::new(
+            mapping[&var_person],
+            vec![ConstraintInstruction::Isa(
+                IsaInstruction::new(isa_person, Inputs::None([]), &entry_annotations).map(&mapping),
+            )],
+            vec![variable_positions[&var_person]],
+            &named_variables,
+            1,
+        )),
+        ExecutionStep::Intersection(IntersectionStep::new(
+            mapping[&var_age],
+            vec![ConstraintInstruction::Has(
+                HasInstruction::new(has_age, Inputs::Single([var_person]), &entry_annotations).map(&mapping),
+            )],
+            vec![variable_positions[&var_person], variable_positions[&var_age]],
+            &named_variables,
+            2,
+        )),
+    ];
+    let executable =
+        ConjunctionExecutable::new(next_executable_id(), steps, variable_positions, row_vars, PlannerStatistics::new());
+
+    // Executor
+    let snapshot = Arc::new(snapshot);
+    let executor = MatchExecutor::new(
+        &executable,
+        &snapshot,
+        &thing_manager,
+        MaybeOwnedRow::empty(),
+        Arc::new(ExecutableFunctionRegistry::empty()),
+        &QueryProfile::new(false),
+    )
+    .unwrap();
+
+    let context = ExecutionContext::new(snapshot, thing_manager, Arc::default());
+    let iterator = executor.into_iterator(context, ExecutionInterrupt::new_uninterruptible());
+
+    let rows: Vec<Result<MaybeOwnedRow<'static>, Box<ReadExecutionError>>> = iterator
+        .map_static(|row| row.map(|row| row.clone().into_owned()).map_err(|err| Box::new(err.clone())))
+        .collect();
+    assert_eq!(rows.len(), 7);
+
+    for row in rows {
+        let r = row.unwrap();
+        assert_eq!(r.multiplicity(), 1);
+        assert_eq!(r.get(age_position).as_thing().as_attribute().type_(), age_type);
+        print!("{}", r);
+    }
+}
+
 #[test]
 fn traverse_has_bounded_sorted_from_chain_intersect() {
     let (_tmp_dir, mut storage) = create_core_storage();
@@ -26,6 +26,7 @@ use test_utils_encoding::create_core_storage;
 
 const AGE_LABEL: Label = Label::new_static("age");
 const MEMBERSHIP_LABEL: Label = Label::new_static("membership");
+const NAME_LABEL: Label = Label::new_static("name");
 
 struct Context {
     storage: Arc<MVCCStorage<WALClient>>,
@@ -475,6 +476,56 @@ fn test_match_sort() {
     assert_eq!([4, 3, 2, 1], values.as_slice());
 }
 
+#[test]
+fn test_match_offset_limit() {
+    let context = setup_common();
+    let snapshot = context.storage.clone().open_snapshot_write();
+    let insert_query_str = "insert $p isa person, has age 1, has age 2, has age 3, has age 4, has age 5, has age 6;";
+    let insert_query = typeql::parse_query(insert_query_str).unwrap().into_structure().into_pipeline();
+    let pipeline = context
+        .query_manager
+        .prepare_write_pipeline(
+            snapshot,
+            &context.type_manager,
+            context.thing_manager.clone(),
+            &context.function_manager,
+            &insert_query,
+            insert_query_str,
+        )
+        .unwrap();
+    let (mut iterator, ExecutionContext { snapshot, .. }) =
+        pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+
+    assert_matches!(iterator.next(), Some(Ok(_)));
+    assert_matches!(iterator.next(), None);
+    let snapshot = Arc::into_inner(snapshot).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    for (query, expected_rows) in [
+        ("match $age isa age; limit 2;", 2),
+        ("match $age isa age; offset 2; limit 3;", 3),
+        ("match $age isa age; limit 4; offset 3;", 1),
+        ("match $age isa age; limit 10;", 6),
+    ] {
+        let snapshot = Arc::new(context.storage.clone().open_snapshot_read());
+        let match_ = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+        let pipeline = context
+            .query_manager
+            .prepare_read_pipeline(
+                snapshot,
+                &context.type_manager,
+                context.thing_manager.clone(),
+                &context.function_manager,
+                &match_,
+                query,
+            )
+            .unwrap();
+        let (iterator, _) = pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+        let batch = iterator.collect_owned().unwrap();
+        assert_eq!(batch.len(), expected_rows, "{query}");
+    }
+}
+
 #[test]
 fn test_select() {
     let context = setup_common();
@@ -589,3 +640,282 @@ fn test_require() {
         assert!(named_outputs.contains_key("p"));
     }
 }
+
+#[test]
+fn test_unused_variables_are_dropped() {
+    let context = setup_common();
+    let snapshot = context.storage.clone().open_snapshot_write();
+    let insert_query_str = r#"insert
+        $p1 isa person, has name "Alice", has name "Al", has age 1;
+        $p2 isa person, has name "Bob", has age 2;"#;
+    let insert_query = typeql::parse_query(insert_query_str).unwrap().into_structure().into_pipeline();
+    let pipeline = context
+        .query_manager
+        .prepare_write_pipeline(
+            snapshot,
+            &context.type_manager,
+            context.thing_manager.clone(),
+            &context.function_manager,
+            &insert_query,
+            insert_query_str,
+        )
+        .unwrap();
+    let (mut iterator, ExecutionContext { snapshot, .. }) =
+        pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+
+    assert_matches!(iterator.next(), Some(Ok(_)));
+    assert_matches!(iterator.next(), None);
+    let snapshot = Arc::into_inner(snapshot).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    // rows which differ only in dropped variables are all kept
+    for query in [
+        "match $p isa person, has name $n, has age $age; sort $age; select $age;",
+        "match $p isa person, has name $n; match $p has age $age; sort $age; select $age;",
+    ] {
+        let snapshot = Arc::new(context.storage.clone().open_snapshot_read());
+        let match_ = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+        let pipeline = context
+            .query_manager
+            .prepare_read_pipeline(
+                snapshot,
+                &context.type_manager,
+                context.thing_manager.clone(),
+                &context.function_manager,
+                &match_,
+                query,
+            )
+            .unwrap();
+        let named_outputs = pipeline.rows_positions().unwrap().clone();
+        assert!(named_outputs.contains_key("age"));
+        assert!(!named_outputs.contains_key("n"));
+        let (iterator, ExecutionContext { snapshot, .. }) =
+            pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+
+        let batch = iterator.collect_owned().unwrap();
+        let pos = named_outputs["age"];
+        let thing_manager = context.thing_manager.clone();
+        let values = batch
+            .into_iterator_mut()
+            .map_static(move |res| {
+                res.get(pos)
+                    .as_thing()
+                    .as_attribute()
+                    .get_value(&*snapshot, &thing_manager, StorageCounters::DISABLED)
+                    .clone()
+                    .unwrap()
+                    .unwrap_integer()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!([1, 1, 2], values.as_slice(), "{query}");
+    }
+}
+
+#[test]
+fn test_match_insert_puts_shared_attribute_once() {
+    let context = setup_common();
+    // long enough that the value is not inlined in the attribute's IID
+    let name = "a name which is long enough to be hashed into its attribute's identifier".repeat(4);
+    for query_str in [
+        "insert $p1 isa person; $p2 isa person; $p3 isa person; $p4 isa person; $p5 isa person;".to_owned(),
+        format!(r#"match $p isa person; insert $p has name "{name}";"#),
+    ] {
+        let snapshot = context.storage.clone().open_snapshot_write();
+        let query = typeql::parse_query(&query_str).unwrap().into_structure().into_pipeline();
+        let pipeline = context
+            .query_manager
+            .prepare_write_pipeline(
+                snapshot,
+                &context.type_manager,
+                context.thing_manager.clone(),
+                &context.function_manager,
+                &query,
+                &query_str,
+            )
+            .unwrap();
+        let (iterator, ExecutionContext { snapshot, .. }) =
+            pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+        let _ = iterator.count();
+        let snapshot = Arc::into_inner(snapshot).unwrap();
+        snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+    }
+
+    let snapshot = Arc::new(context.storage.clone().open_snapshot_read());
+    let query = "match $n isa name;";
+    let match_ = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    let pipeline = context
+        .query_manager
+        .prepare_read_pipeline(
+            snapshot.clone(),
+            &context.type_manager,
+            context.thing_manager.clone(),
+            &context.function_manager,
+            &match_,
+            query,
+        )
+        .unwrap();
+    let (iterator, _) = pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+    assert_eq!(iterator.collect_owned().unwrap().len(), 1);
+
+    let name_type = context.type_manager.get_attribute_type(&*snapshot, &NAME_LABEL).unwrap().unwrap();
+    let attribute = context
+        .thing_manager
+        .get_attribute_with_value(&*snapshot, name_type, Value::String(name.into()), StorageCounters::DISABLED)
+        .unwrap()
+        .unwrap();
+    assert_eq!(5, attribute.get_owners(&*snapshot, &context.thing_manager, StorageCounters::DISABLED).count());
+}
+
+#[test]
+fn test_match_rows_share_batch_allocation() {
+    let context = setup_common();
+    let snapshot = context.storage.clone().open_snapshot_write();
+    let query_str = r#"insert
+        $p1 isa person, has age 10;
+        $p2 isa person, has age 11;
+        $p3 isa person, has age 12;
+        $p4 isa person, has age 13;
+        $p5 isa person, has age 14;"#;
+    let query = typeql::parse_query(query_str).unwrap().into_structure().into_pipeline();
+    let pipeline = context
+        .query_manager
+        .prepare_write_pipeline(
+            snapshot,
+            &context.type_manager,
+            context.thing_manager.clone(),
+            &context.function_manager,
+            &query,
+            query_str,
+        )
+        .unwrap();
+    let (iterator, ExecutionContext { snapshot, .. }) =
+        pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+    let _ = iterator.count();
+    let snapshot = Arc::into_inner(snapshot).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let snapshot = Arc::new(context.storage.clone().open_snapshot_read());
+    let query = "match $p isa person, has age $a;";
+    let match_ = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    let pipeline = context
+        .query_manager
+        .prepare_read_pipeline(
+            snapshot.clone(),
+            &context.type_manager,
+            context.thing_manager.clone(),
+            &context.function_manager,
+            &match_,
+            query,
+        )
+        .unwrap();
+    let position = pipeline.rows_positions().unwrap()["a"];
+    let (mut iterator, execution_context) =
+        pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+    let mut rows = Vec::new();
+    while let Some(row) = iterator.next() {
+        rows.push(row.unwrap().into_owned());
+    }
+    drop(iterator);
+    drop(execution_context);
+    assert_eq!(rows.len(), 5);
+
+    // the rows of one batch are consecutive ranges of the same values
+    for (row, next_row) in rows.iter().zip(rows.iter().skip(1)) {
+        assert_eq!(row.row().as_ptr().wrapping_add(row.len()), next_row.row().as_ptr());
+    }
+
+    // and outlive the iterator that produced them
+    let mut ages = rows
+        .iter()
+        .map(|row| {
+            row.get(position)
+                .as_thing()
+                .as_attribute()
+                .get_value(&*snapshot, &context.thing_manager, StorageCounters::DISABLED)
+                .unwrap()
+                .unwrap_integer()
+        })
+        .collect::<Vec<_>>();
+    ages.sort();
+    assert_eq!(ages, [10, 11, 12, 13, 14]);
+}
+
+#[test]
+fn test_match_isa_exact() {
+    let context = setup_common();
+    let schema = r#"
+    define
+        entity employee sub person;
+        attribute tag @abstract, value string;
+        attribute nickname sub tag;
+        attribute alias sub tag;
+        entity person owns nickname @card(0..), owns alias @card(0..);
+    "#;
+    let mut snapshot = context.storage.clone().open_snapshot_schema();
+    let define = typeql::parse_query(schema).unwrap().into_structure().into_schema();
+    context
+        .query_manager
+        .execute_schema(
+            &mut snapshot,
+            &context.type_manager,
+            &context.thing_manager,
+            &context.function_manager,
+            define,
+            schema,
+        )
+        .unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+    let (type_manager, thing_manager) = load_managers(context.storage.clone(), None);
+    let query_manager = QueryManager::new(None);
+
+    let snapshot = context.storage.clone().open_snapshot_write();
+    let query_str = r#"insert
+        $p1 isa person, has nickname "a", has alias "b";
+        $p2 isa person, has nickname "c";
+        $e1 isa employee, has nickname "d", has alias "e";
+        $e2 isa employee;
+        $e3 isa employee;"#;
+    let query = typeql::parse_query(query_str).unwrap().into_structure().into_pipeline();
+    let pipeline = query_manager
+        .prepare_write_pipeline(
+            snapshot,
+            &type_manager,
+            thing_manager.clone(),
+            &context.function_manager,
+            &query,
+            query_str,
+        )
+        .unwrap();
+    let (iterator, ExecutionContext { snapshot, .. }) =
+        pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+    let _ = iterator.count();
+    let snapshot = Arc::into_inner(snapshot).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    for (query, expected_rows) in [
+        ("match $p isa! person;", 2),
+        ("match $p isa! employee;", 3),
+        ("match $p isa person;", 5),
+        // a bound thing has exactly one type to bind
+        ("match $p isa person; $p isa! $t;", 5),
+        ("match $p isa! $t; $t label employee;", 3),
+        ("match $n isa! nickname;", 3),
+        ("match $n isa tag;", 5),
+        ("match $n isa! alias; $n > \"b\";", 1),
+    ] {
+        let snapshot = Arc::new(context.storage.clone().open_snapshot_read());
+        let match_ = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+        let pipeline = query_manager
+            .prepare_read_pipeline(
+                snapshot,
+                &type_manager,
+                thing_manager.clone(),
+                &context.function_manager,
+                &match_,
+                query,
+            )
+            .unwrap();
+        let (iterator, _) = pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+        assert_eq!(iterator.collect_owned().unwrap().len(), expected_rows, "{query}");
+    }
+}
@@ -38,7 +38,10 @@ use ir::{
 };
 use itertools::Itertools;
 use lending_iterator::{AsHkt, AsNarrowingIterator, LendingIterator};
-use resource::profile::{CommitProfile, QueryProfile, StorageCounters};
+use resource::{
+    constants::compiler::MAX_REDUCE_LIST_SIZE,
+    profile::{CommitProfile, QueryProfile, StorageCounters},
+};
 use storage::{
     durability_client::WALClient,
     snapshot::{CommittableSnapshot, WritableSnapshot, WriteSnapshot},
@@ -222,6 +225,10 @@ fn execute_insert<Snapshot: WritableSnapshot + 'static>(
             thing_manager,
             parameters: Arc::new(value_parameters),
             profile: Arc::new(QueryProfile::new(false)),
+            warnings: Default::default(),
+            row_budget: None,
+            start_after: None,
+            max_reduce_list_size: MAX_REDUCE_LIST_SIZE,
         },
     );
     let insert_executor = InsertStageExecutor::new(Arc::new(insert_plan), initial);
@@ -313,6 +320,10 @@ fn execute_delete<Snapshot: WritableSnapshot + 'static>(
             thing_manager,
             parameters: Arc::new(value_parameters),
             profile: Arc::new(QueryProfile::new(false)),
+            warnings: Default::default(),
+            row_budget: None,
+            start_after: None,
+            max_reduce_list_size: MAX_REDUCE_LIST_SIZE,
         },
     );
     let delete_executor = DeleteStageExecutor::new(Arc::new(delete_plan), initial);
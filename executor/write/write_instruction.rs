@@ -3,12 +3,20 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
 use answer::{variable_value::VariableValue, Thing, Type};
 use compiler::executable::insert::{
     instructions::{PutAttribute, PutObject},
     ThingPosition, TypeSource, ValueSource,
 };
-use concept::thing::{object::ObjectAPI, thing_manager::ThingManager, ThingAPI};
+use concept::{
+    thing::{attribute::Attribute, object::ObjectAPI, thing_manager::ThingManager, ThingAPI},
+    type_::attribute_type::AttributeType,
+};
 use encoding::value::value::Value;
 use ir::pipeline::ParameterRegistry;
 use itertools::Itertools;
@@ -101,6 +109,67 @@ impl AsWriteInstruction for PutAttribute {
     }
 }
 
+/// The attributes put by a write stage, keyed by type and value, so that an attribute inserted for many rows
+/// is only put into the snapshot once per stage.
+#[derive(Debug, Default)]
+pub(crate) struct InsertedAttributes {
+    // keyed by the value's hash, so a value only has to be copied when its attribute is first put
+    attributes: HashMap<(AttributeType, u64), Vec<(Value<'static>, Attribute)>>,
+    len: usize,
+}
+
+impl InsertedAttributes {
+    const MAX_ATTRIBUTES: usize = 1 << 16;
+
+    fn hash_value(value: &Value<'_>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn get(&self, attribute_type: AttributeType, hash: u64, value: &Value<'_>) -> Option<&Attribute> {
+        let bucket = self.attributes.get(&(attribute_type, hash))?;
+        bucket.iter().find(|(inserted_value, _)| inserted_value == value).map(|(_, attribute)| attribute)
+    }
+
+    fn insert(&mut self, attribute_type: AttributeType, hash: u64, value: Value<'static>, attribute: Attribute) {
+        if self.len < Self::MAX_ATTRIBUTES {
+            self.attributes.entry((attribute_type, hash)).or_default().push((value, attribute));
+            self.len += 1;
+        }
+    }
+}
+
+impl PutAttribute {
+    pub(crate) fn execute_deduplicated(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        thing_manager: &ThingManager,
+        parameters: &ParameterRegistry,
+        row: &mut Row<'_>,
+        inserted_attributes: &mut InsertedAttributes,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<WriteError>> {
+        let attribute_type = try_unwrap_as!(answer::Type::Attribute: get_type(row, &self.type_)).unwrap();
+        let value = get_value(snapshot, thing_manager, storage_counters, row, parameters, &self.value)?;
+        let hash = InsertedAttributes::hash_value(&value);
+        let inserted = match inserted_attributes.get(attribute_type, hash, &value) {
+            Some(attribute) => attribute.clone(),
+            None => {
+                let value = value.into_owned();
+                let inserted = thing_manager
+                    .create_attribute(snapshot, attribute_type, value.clone())
+                    .map_err(|typedb_source| WriteError::ConceptWrite { typedb_source })?;
+                inserted_attributes.insert(attribute_type, hash, value, inserted.clone());
+                inserted
+            }
+        };
+        let ThingPosition(write_to) = &self.write_to;
+        row.set(*write_to, VariableValue::Thing(Thing::Attribute(inserted)));
+        Ok(())
+    }
+}
+
 impl AsWriteInstruction for PutObject {
     fn execute(
         &self,
@@ -182,7 +251,7 @@ impl AsWriteInstruction for compiler::executable::update::instructions::Has {
         let new_attribute = get_thing(row, &self.attribute).as_attribute();
 
         let mut old_attributes = owner
-            .get_has_type_unordered(snapshot, thing_manager, new_attribute.type_(), &.., StorageCounters::DISABLED)
+            .get_has_type_unordered(snapshot, thing_manager, new_attribute.type_(), &.., storage_counters.clone())
             .map_err(|err| WriteError::ConceptRead { typedb_source: err })?
             .take(2)
             .collect_vec()
@@ -227,7 +296,7 @@ impl AsWriteInstruction for compiler::executable::update::instructions::Links {
         let role_type = try_unwrap_as!(answer::Type::RoleType : get_type(row, &self.role)).unwrap();
 
         let mut old_players =
-            relation.get_players_role_type(snapshot, thing_manager, role_type, StorageCounters::DISABLED);
+            relation.get_players_role_type(snapshot, thing_manager, role_type, storage_counters.clone());
         if let Some(old_player) = old_players.next() {
             match old_player {
                 Ok(old_player) => {
@@ -319,3 +388,29 @@ impl AsWriteInstruction for compiler::executable::delete::instructions::Links {
             .map_err(|source| Box::new(WriteError::ConceptWrite { typedb_source: source }))
     }
 }
+
+impl AsWriteInstruction for compiler::executable::delete::instructions::LinksAnyRole {
+    fn execute(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        thing_manager: &ThingManager,
+        _parameters: &ParameterRegistry,
+        row: &mut Row<'_>,
+        storage_counters: StorageCounters,
+    ) -> Result<(), Box<WriteError>> {
+        let relation = get_thing(row, &self.relation).as_relation();
+        let player = get_thing(row, &self.player).as_object();
+        for role_type in &self.role_types {
+            let &answer::Type::RoleType(role_type) = role_type else { unreachable!() };
+            let plays_role = relation
+                .has_role_player(snapshot, thing_manager, player, role_type, storage_counters.clone())
+                .map_err(|typedb_source| Box::new(WriteError::ConceptRead { typedb_source }))?;
+            if plays_role {
+                relation
+                    .remove_player_single(snapshot, thing_manager, role_type, player, storage_counters.clone())
+                    .map_err(|source| Box::new(WriteError::ConceptWrite { typedb_source: source }))?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -27,6 +27,10 @@ impl<FunctionIDType: FunctionIDAPI> Function<FunctionIDType> {
     pub fn name(&self) -> String {
         self.parsed.signature.ident.as_str_unchecked().to_owned()
     }
+
+    pub fn signature(&self) -> &typeql::schema::definable::function::Signature {
+        &self.parsed.signature
+    }
 }
 
 impl<FunctionIDType: FunctionIDAPI> Function<FunctionIDType> {
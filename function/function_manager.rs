@@ -5,14 +5,19 @@
  */
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::Write,
     iter::zip,
     sync::Arc,
 };
 
+use answer::variable::Variable;
 use bytes::{byte_array::ByteArray, Bytes};
-use compiler::annotation::function::{annotate_stored_functions, AnnotatedSchemaFunctions};
+use compiler::annotation::{
+    function::{annotate_stored_functions, AnnotatedSchemaFunctions},
+    pipeline::AnnotatedStage,
+    type_annotations::BlockAnnotations,
+};
 use concept::type_::type_manager::TypeManager;
 use encoding::{
     graph::{
@@ -22,10 +27,16 @@ use encoding::{
         },
         type_::index::NameToFunctionDefinitionIndex,
     },
+    value::label::Label,
     Keyable,
 };
 use ir::{
-    pattern::{conjunction::Conjunction, constraint::Constraint, nested_pattern::NestedPattern},
+    pattern::{
+        conjunction::Conjunction,
+        constraint::{Constraint, RoleName},
+        nested_pattern::NestedPattern,
+        Vertex,
+    },
     pipeline::{
         function::ReturnOperation,
         function_signature::{
@@ -45,7 +56,11 @@ use storage::{
     key_range::KeyRange,
     snapshot::{ReadableSnapshot, WritableSnapshot},
 };
-use typeql::common::Spanned;
+use typeql::{
+    common::Spanned,
+    schema::definable::function::{Output, Signature},
+    type_::{NamedType, NamedTypeAny},
+};
 
 use crate::{function::SchemaFunction, function_cache::FunctionCache, FunctionError};
 
@@ -185,6 +200,50 @@ impl FunctionManager {
         Ok(function)
     }
 
+    /// Rewrites the stored functions that refer to a type about to be renamed, so that they use its new label.
+    /// Renaming a relation type also rewrites it where it scopes a role, and renaming a role type also rewrites
+    /// the role names that refer to it without a scope. These are resolved by type inference, so this must run
+    /// before the type is renamed.
+    pub fn rename_type_references(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        type_manager: &TypeManager,
+        label: &Label,
+        new_label: &Label,
+    ) -> Result<(), FunctionError> {
+        let functions = FunctionReader::get_functions_all(snapshot)
+            .map_err(|typedb_source| FunctionError::FunctionRetrieval { typedb_source })?;
+        let function_index =
+            HashMapFunctionSignatureIndex::build(functions.iter().map(|f| (f.function_id.clone().into(), &f.parsed)));
+        let mut translated = Self::translate_functions(&functions, &function_index)?;
+        let annotated = annotate_stored_functions(&mut translated, snapshot, type_manager)
+            .map_err(|typedb_source| FunctionError::AllFunctionsTypeCheckFailure { typedb_source })?;
+        for function in &functions {
+            let mut renamed_spans = BTreeMap::new();
+            collect_renamed_signature_labels(&function.parsed.signature, label, new_label, &mut renamed_spans);
+            let renamer = LabelRenamer { snapshot: &*snapshot, type_manager, function, label, new_label };
+            for stage in &annotated[&function.function_id].stages {
+                if let AnnotatedStage::Match { block, block_annotations, .. } = stage {
+                    renamer.collect(block.conjunction(), block_annotations, &mut renamed_spans)?;
+                }
+            }
+            if renamed_spans.is_empty() {
+                continue;
+            }
+            // replaced back to front, so that the offsets of the earlier spans stay valid
+            let mut definition = function.parsed.unparsed.clone();
+            for ((begin, end), renamed) in renamed_spans.into_iter().rev() {
+                definition.replace_range(begin..end, &renamed);
+            }
+            SchemaFunction::build(function.function_id.clone(), FunctionDefinition::build_ref(definition.as_str()))?;
+            snapshot.put_val(
+                function.function_id.clone().into_storage_key().into_owned_array(),
+                FunctionDefinition::build_ref(definition.as_str()).into_bytes().into_array(),
+            );
+        }
+        Ok(())
+    }
+
     pub(crate) fn translate_functions(
         functions: &[SchemaFunction],
         function_index: &impl FunctionSignatureIndex,
@@ -223,13 +282,61 @@ impl FunctionManager {
         }
     }
 
-    pub fn get_functions_syntax(&self, snapshot: &impl ReadableSnapshot) -> Result<String, FunctionReadError> {
+    /// Formats the stored functions so that each follows the functions it calls, ordering independent functions
+    /// by name, so that the schema can be re-applied as written.
+    pub fn get_functions_syntax(&self, snapshot: &impl ReadableSnapshot) -> Result<String, FunctionError> {
+        let functions = FunctionReader::get_functions_all(snapshot)
+            .map_err(|typedb_source| FunctionError::FunctionRetrieval { typedb_source })?;
+        let function_index =
+            HashMapFunctionSignatureIndex::build(functions.iter().map(|f| (f.function_id.clone().into(), &f.parsed)));
+        let translated = Self::translate_functions(&functions, &function_index)?;
+        let functions_by_key: HashMap<DefinitionKey, &SchemaFunction> =
+            functions.iter().map(|function| (function.function_id.clone(), function)).collect();
+
         let mut syntax = String::new();
-        for function in FunctionReader::get_functions_all(snapshot)? {
-            write!(&mut syntax, "\n{}", function.parsed.unparsed).map_err(|err| err.into())?;
+        let mut formatted = HashSet::new();
+        for function in functions.iter().sorted_by_key(|function| function.name()) {
+            Self::format_function_syntax_after_callees(
+                &mut syntax,
+                &function.function_id,
+                &functions_by_key,
+                &translated,
+                &mut formatted,
+            )?;
         }
         Ok(syntax)
     }
+
+    /// The definition of each stored function, by function name.
+    pub fn get_function_definitions(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+    ) -> Result<BTreeMap<String, String>, FunctionError> {
+        let functions = FunctionReader::get_functions_all(snapshot)
+            .map_err(|typedb_source| FunctionError::FunctionRetrieval { typedb_source })?;
+        Ok(functions.iter().map(|function| (function.name(), function.parsed.unparsed.trim().to_owned())).collect())
+    }
+
+    fn format_function_syntax_after_callees(
+        syntax: &mut String,
+        function_key: &DefinitionKey,
+        functions_by_key: &HashMap<DefinitionKey, &SchemaFunction>,
+        translated: &HashMap<DefinitionKey, ir::pipeline::function::Function>,
+        formatted: &mut HashSet<DefinitionKey>,
+    ) -> Result<(), FunctionError> {
+        // marked before visiting the callees, so that recursive functions terminate
+        if !formatted.insert(function_key.clone()) {
+            return Ok(());
+        }
+        let callees = function_calls::<DefinitionKey>(&translated[function_key])
+            .unique()
+            .sorted_by_key(|callee| functions_by_key[callee].name());
+        for callee in callees {
+            Self::format_function_syntax_after_callees(syntax, &callee, functions_by_key, translated, formatted)?;
+        }
+        write!(syntax, "\n{}", functions_by_key[function_key].parsed.unparsed.trim())
+            .map_err(|err| FunctionError::FunctionRetrieval { typedb_source: err.into() })
+    }
 }
 
 pub struct FunctionReader {}
@@ -380,6 +487,17 @@ fn negated_function_calls<ID: FunctionIDAPI>(function: &ir::pipeline::function::
     calls.into_iter()
 }
 
+fn function_calls<ID: FunctionIDAPI>(function: &ir::pipeline::function::Function) -> impl Iterator<Item = ID> {
+    let mut calls = Vec::new();
+    for stage in &function.function_body.stages {
+        if let TranslatedStage::Match { block, .. } = stage {
+            // every call is collected when starting as negated
+            collect_negated_function_calls(block.conjunction(), &mut calls, true)
+        }
+    }
+    calls.into_iter()
+}
+
 fn collect_negated_function_calls<ID: FunctionIDAPI>(conjunction: &Conjunction, calls: &mut Vec<ID>, is_negated: bool) {
     if is_negated {
         conjunction.constraints().iter().for_each(|constraint| {
@@ -403,6 +521,131 @@ fn collect_negated_function_calls<ID: FunctionIDAPI>(conjunction: &Conjunction,
     }
 }
 
+fn collect_renamed_signature_labels(
+    signature: &Signature,
+    label: &Label,
+    new_label: &Label,
+    renamed_spans: &mut BTreeMap<(usize, usize), String>,
+) {
+    if label.scope.is_some() {
+        // roles cannot be named in a signature
+        return;
+    }
+    let output_types = match &signature.output {
+        Output::Stream(stream) => &stream.types,
+        Output::Single(single) => &single.types,
+    };
+    let named_types = signature.args.iter().map(|arg| &arg.type_).chain(output_types);
+    for named_type in named_types {
+        let inner = match named_type {
+            NamedTypeAny::Simple(inner) => inner,
+            NamedTypeAny::Optional(optional) => &optional.inner,
+            NamedTypeAny::List(list) => &list.inner,
+        };
+        if let NamedType::Label(type_label) = inner {
+            if type_label.ident.as_str_unchecked() == label.name.as_str() {
+                if let Some(span) = type_label.span() {
+                    renamed_spans.insert((span.begin_offset, span.end_offset), new_label.name.as_str().to_owned());
+                }
+            }
+        }
+    }
+}
+
+struct LabelRenamer<'a, Snapshot> {
+    snapshot: &'a Snapshot,
+    type_manager: &'a TypeManager,
+    function: &'a SchemaFunction,
+    label: &'a Label,
+    new_label: &'a Label,
+}
+
+impl<Snapshot: ReadableSnapshot> LabelRenamer<'_, Snapshot> {
+    fn collect(
+        &self,
+        conjunction: &Conjunction,
+        block_annotations: &BlockAnnotations,
+        renamed_spans: &mut BTreeMap<(usize, usize), String>,
+    ) -> Result<(), FunctionError> {
+        let (label, new_label) = (self.label, self.new_label);
+        for constraint in conjunction.constraints() {
+            for type_label in constraint.vertices().filter_map(Vertex::as_label) {
+                let Some(span) = type_label.source_span() else { continue };
+                let renamed = if type_label == label {
+                    new_label.scoped_name.as_str().to_owned()
+                } else if label.scope.is_none()
+                    && type_label.scope.as_ref().is_some_and(|scope| scope.as_str() == label.name.as_str())
+                {
+                    format!("{}:{}", new_label.name.as_str(), type_label.name.as_str())
+                } else {
+                    continue;
+                };
+                renamed_spans.insert((span.begin_offset, span.end_offset), renamed);
+            }
+            if let Constraint::RoleName(role_name) = constraint {
+                if label.scope.is_some()
+                    && role_name.name() == label.name.as_str()
+                    && self.names_renamed_role(role_name, conjunction, block_annotations)?
+                {
+                    if let Some(span) = role_name.source_span() {
+                        renamed_spans.insert((span.begin_offset, span.end_offset), new_label.name.as_str().to_owned());
+                    }
+                }
+            }
+        }
+
+        for pattern in conjunction.nested_patterns() {
+            match pattern {
+                NestedPattern::Negation(inner) => {
+                    self.collect(inner.conjunction(), block_annotations, renamed_spans)?
+                }
+                NestedPattern::Disjunction(inner) => {
+                    for branch in inner.conjunctions() {
+                        self.collect(branch, block_annotations, renamed_spans)?;
+                    }
+                }
+                NestedPattern::Optional(inner) => {
+                    self.collect(inner.conjunction(), block_annotations, renamed_spans)?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // An unscoped role name refers to the renamed role only if every role type inferred for it is that role.
+    // If it may also be a role of the same name in another relation, it cannot be rewritten either way.
+    fn names_renamed_role(
+        &self,
+        role_name: &RoleName<Variable>,
+        conjunction: &Conjunction,
+        block_annotations: &BlockAnnotations,
+    ) -> Result<bool, FunctionError> {
+        let Some(role_types) = block_annotations
+            .type_annotations_of(conjunction)
+            .and_then(|annotations| annotations.vertex_annotations_of(role_name.type_()))
+        else {
+            return Ok(false);
+        };
+        let mut is_renamed_role = Vec::with_capacity(role_types.len());
+        for role_type in role_types.iter() {
+            let role_label = role_type
+                .get_label(self.snapshot, self.type_manager)
+                .map_err(|typedb_source| FunctionError::ConceptRead { typedb_source })?;
+            is_renamed_role.push(&*role_label == self.label);
+        }
+        match (is_renamed_role.iter().any(|is| *is), is_renamed_role.iter().all(|is| *is)) {
+            (false, _) => Ok(false),
+            (true, true) => Ok(true),
+            (true, false) => Err(FunctionError::AmbiguousRoleRename {
+                name: self.function.name(),
+                role: self.label.name.as_str().to_owned(),
+                relation: self.label.scope.as_ref().map(|scope| scope.as_str().to_owned()).unwrap_or_default(),
+                source_span: role_name.source_span(),
+            }),
+        }
+    }
+}
+
 fn unnegated_function_calls<ID: FunctionIDAPI>(
     function: &ir::pipeline::function::Function,
 ) -> impl Iterator<Item = ID> {
@@ -5,6 +5,7 @@
  */
 
 use compiler::annotation::FunctionAnnotationError;
+use concept::error::ConceptReadError;
 use encoding::error::EncodingError;
 use error::typedb_error;
 use ir::pipeline::{FunctionReadError, FunctionRepresentationError};
@@ -30,5 +31,14 @@ typedb_error! {
         FunctionRetrieval(7, "Error retrieving function.", typedb_source: FunctionReadError),
         CommittedFunctionParseError(8, "Error while parsing committed function.", typedb_source: typeql::Error),
         StratificationViolation(9, "Detected a recursive cycle through a negation, reduction or single return: [{cycle_names}]", cycle_names: String),
+        ConceptRead(10, "Error reading concepts.", typedb_source: Box<ConceptReadError>),
+        AmbiguousRoleRename(
+            11,
+            "Function '{name}' names the role '{role}' where it may also be a role of a relation other than '{relation}', so it cannot be renamed. Constrain the relation type of the role player first.",
+            name: String,
+            role: String,
+            relation: String,
+            source_span: Option<Span>,
+        ),
     }
 }
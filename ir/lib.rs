@@ -11,7 +11,7 @@
 use chrono::{DateTime, NaiveDateTime};
 use encoding::value::timezone::TimeZone;
 use error::typedb_error;
-use typeql::{common::Span, statement::InIterable, token, value::StringLiteral};
+use typeql::{common::Span, statement::InIterable, value::StringLiteral};
 
 use crate::{
     pattern::{expression::ExpressionRepresentationError, variable_category::VariableCategory},
@@ -127,7 +127,7 @@ typedb_error! {
         ExpressionBuiltinArgumentCountMismatch(
             15,
             "Built-in expression function '{builtin}' expects '{expected}' arguments but received '{actual}' arguments.",
-            builtin: token::Function,
+            builtin: String,
             expected: usize,
             actual: usize,
             source_span: Option<Span>,
@@ -290,6 +290,17 @@ pub enum BuiltinValueFunctionID {
     Max,
     Min,
     Len,
+    InTimezone,
+}
+
+impl BuiltinValueFunctionID {
+    /// Value built-ins without a TypeQL keyword are called by name, like user-defined functions.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "in_timezone" => Some(Self::InTimezone),
+            _ => None,
+        }
+    }
 }
 
 impl StructuralEquality for BuiltinValueFunctionID {
@@ -312,6 +323,7 @@ impl fmt::Display for BuiltinValueFunctionID {
             BuiltinValueFunctionID::Max => fmt::Display::fmt(&typeql::token::Function::Max, f),
             BuiltinValueFunctionID::Min => fmt::Display::fmt(&typeql::token::Function::Min, f),
             BuiltinValueFunctionID::Len => fmt::Display::fmt(&typeql::token::Function::Len, f),
+            BuiltinValueFunctionID::InTimezone => f.write_str("in_timezone"),
         }
     }
 }
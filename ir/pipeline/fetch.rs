@@ -133,6 +133,12 @@ pub enum FetchObject {
 }
 
 impl FetchObject {
+    pub fn variables(&self) -> HashSet<Variable> {
+        let mut variables = HashSet::new();
+        self.record_variables_recursive(&mut variables);
+        variables
+    }
+
     pub(crate) fn record_variables_recursive(&self, vars: &mut HashSet<Variable>) {
         match self {
             FetchObject::Entries(entries, _) => {
@@ -0,0 +1,667 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{collections::HashMap, fmt, hash::Hasher};
+
+use answer::variable::Variable;
+use itertools::Itertools;
+use typeql::schema::definable::function::SingleSelector;
+
+use crate::{
+    pattern::{
+        conjunction::Conjunction,
+        constraint::{Constraint, IsaKind, SubKind},
+        disjunction::Disjunction,
+        expression::{Expression, ExpressionTreeNodeId},
+        nested_pattern::NestedPattern,
+        ParameterID, ValueType, Vertex,
+    },
+    pipeline::{
+        fetch::{FetchObject, FetchSome},
+        function::{Function, ReturnOperation},
+        function_signature::FunctionID,
+        modifier::SortVariable,
+        reduce::Reducer,
+        ParameterRegistry,
+    },
+    translation::pipeline::TranslatedStage,
+};
+
+/// A hash of the shape of a query, stable across variable names, the order in which constraints, nested patterns and
+/// fetch entries are written, the values of parameters and the bounds of `offset` and `limit`.
+/// Queries that only differ in these share a fingerprint, so their executions can be aggregated together.
+/// Fingerprints are computed with a fixed hash function over explicit tags for every kind of stage, constraint and
+/// expression, so they can be compared across server builds.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct QueryFingerprint(u64);
+
+impl QueryFingerprint {
+    pub fn of(
+        preamble: &[Function],
+        stages: &[TranslatedStage],
+        fetch: Option<&FetchObject>,
+        parameters: &ParameterRegistry,
+    ) -> Self {
+        CanonicalForm::new(stages).fingerprint(preamble, stages, fetch, parameters)
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for QueryFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// The canonical form of a translated pipeline: constraints and nested patterns of every conjunction are ordered by
+/// their shape, and variables are renamed in order of first appearance in that ordering.
+///
+/// Constraints of the same shape are ordered by a second pass, which names the variables tentatively from the first.
+/// Variables are only ever renamed in the canonical form; the translated IR itself is left untouched.
+#[derive(Debug, Clone)]
+pub struct CanonicalForm {
+    variables: HashMap<Variable, u16>,
+}
+
+impl CanonicalForm {
+    pub fn new(stages: &[TranslatedStage]) -> Self {
+        Self::with_inputs(&[], stages)
+    }
+
+    fn with_inputs(inputs: &[Variable], stages: &[TranslatedStage]) -> Self {
+        let tentative_variables = canonical_variables(inputs, stages, &HashMap::new());
+        Self { variables: canonical_variables(inputs, stages, &tentative_variables) }
+    }
+
+    /// The canonical form of a single conjunction, whose variables are named without regard to the stages around it.
+    pub fn of_conjunction(conjunction: &Conjunction) -> Self {
+        let mut tentative_variables = HashMap::new();
+        name_conjunction_variables(conjunction, &HashMap::new(), &mut tentative_variables);
+        let mut variables = HashMap::new();
+        name_conjunction_variables(conjunction, &tentative_variables, &mut variables);
+        Self { variables }
+    }
+
+    /// The canonical names of all variables of the pipeline.
+    pub fn variables(&self) -> HashMap<Variable, Variable> {
+        self.variables.keys().map(|&variable| (variable, self.variable(variable).unwrap())).collect()
+    }
+
+    /// The canonical name of a variable of the pipeline, which keeps whether the variable is anonymous.
+    pub fn variable(&self, variable: Variable) -> Option<Variable> {
+        let &id = self.variables.get(&variable)?;
+        Some(if variable.is_anonymous() { Variable::new_anonymous(id) } else { Variable::new(id) })
+    }
+
+    /// The constraints of a conjunction of the pipeline, in canonical order.
+    pub fn constraints<'a>(&self, conjunction: &'a Conjunction) -> Vec<&'a Constraint<Variable>> {
+        conjunction
+            .constraints()
+            .iter()
+            .sorted_by_cached_key(|constraint| constraint_shape(constraint, &self.variables))
+            .collect()
+    }
+
+    /// The nested patterns of a conjunction of the pipeline, in canonical order.
+    pub fn nested_patterns<'a>(&self, conjunction: &'a Conjunction) -> Vec<&'a NestedPattern> {
+        conjunction
+            .nested_patterns()
+            .iter()
+            .sorted_by_cached_key(|nested| nested_pattern_shape(nested, &self.variables))
+            .collect()
+    }
+
+    /// The branches of a disjunction of the pipeline, in canonical order.
+    pub fn branches<'a>(&self, disjunction: &'a Disjunction) -> Vec<&'a Conjunction> {
+        disjunction
+            .conjunctions()
+            .iter()
+            .sorted_by_cached_key(|branch| conjunction_shape(branch, &self.variables))
+            .collect()
+    }
+
+    pub fn fingerprint(
+        &self,
+        preamble: &[Function],
+        stages: &[TranslatedStage],
+        fetch: Option<&FetchObject>,
+        parameters: &ParameterRegistry,
+    ) -> QueryFingerprint {
+        let mut hasher = FingerprintHasher::new();
+        preamble.iter().map(function_shape).sorted().for_each(|shape| hasher.write_u64(shape));
+        stages.iter().for_each(|stage| hasher.write_u64(self.stage_shape(stage)));
+        if let Some(fetch) = fetch {
+            hasher.write_u64(self.fetch_shape(fetch, parameters));
+        }
+        QueryFingerprint(hasher.finish())
+    }
+
+    fn stage_shape(&self, stage: &TranslatedStage) -> u64 {
+        let mut hasher = FingerprintHasher::new();
+        match stage {
+            TranslatedStage::Match { block, .. } => {
+                write_tag(&mut hasher, "match");
+                hasher.write_u64(conjunction_shape(block.conjunction(), &self.variables));
+            }
+            TranslatedStage::Insert { block, .. } => {
+                write_tag(&mut hasher, "insert");
+                hasher.write_u64(conjunction_shape(block.conjunction(), &self.variables));
+            }
+            TranslatedStage::Update { block, .. } => {
+                write_tag(&mut hasher, "update");
+                hasher.write_u64(conjunction_shape(block.conjunction(), &self.variables));
+            }
+            TranslatedStage::Put { block, .. } => {
+                write_tag(&mut hasher, "put");
+                hasher.write_u64(conjunction_shape(block.conjunction(), &self.variables));
+            }
+            TranslatedStage::Delete { block, deleted_variables, .. } => {
+                write_tag(&mut hasher, "delete");
+                hasher.write_u64(conjunction_shape(block.conjunction(), &self.variables));
+                self.sorted_variables_shape(deleted_variables.iter(), &mut hasher);
+            }
+            TranslatedStage::Select(select) => {
+                write_tag(&mut hasher, "select");
+                self.sorted_variables_shape(select.variables.iter(), &mut hasher);
+            }
+            TranslatedStage::Require(require) => {
+                write_tag(&mut hasher, "require");
+                self.sorted_variables_shape(require.variables.iter(), &mut hasher);
+            }
+            TranslatedStage::Sort(sort) => {
+                write_tag(&mut hasher, "sort");
+                for sort_variable in &sort.variables {
+                    match sort_variable {
+                        SortVariable::Ascending(_) => write_tag(&mut hasher, "asc"),
+                        SortVariable::Descending(_) => write_tag(&mut hasher, "desc"),
+                    }
+                    variable_shape(sort_variable.variable(), &self.variables, &mut hasher);
+                }
+            }
+            TranslatedStage::Reduce(reduce) => {
+                write_tag(&mut hasher, "reduce");
+                self.sorted_variables_shape(reduce.groupby.iter(), &mut hasher);
+                for assigned_reduction in &reduce.assigned_reductions {
+                    variable_shape(assigned_reduction.assigned, &self.variables, &mut hasher);
+                    self.reducer_shape(&assigned_reduction.reduction, &mut hasher);
+                }
+            }
+            TranslatedStage::Offset(_) => write_tag(&mut hasher, "offset"),
+            TranslatedStage::Limit(_) => write_tag(&mut hasher, "limit"),
+            TranslatedStage::Distinct(_) => write_tag(&mut hasher, "distinct"),
+        }
+        hasher.finish()
+    }
+
+    fn return_shape(&self, return_operation: &ReturnOperation, hasher: &mut impl Hasher) {
+        match return_operation {
+            ReturnOperation::Stream(variables, _) => {
+                write_tag(hasher, "stream");
+                variables.iter().for_each(|&variable| variable_shape(variable, &self.variables, hasher))
+            }
+            ReturnOperation::Single(selector, variables, _) => {
+                write_tag(hasher, "single");
+                match selector {
+                    SingleSelector::First => write_tag(hasher, "first"),
+                    SingleSelector::Last => write_tag(hasher, "last"),
+                }
+                variables.iter().for_each(|&variable| variable_shape(variable, &self.variables, hasher))
+            }
+            ReturnOperation::ReduceCheck(_) => write_tag(hasher, "check"),
+            ReturnOperation::ReduceReducer(reducers, _) => {
+                write_tag(hasher, "reduce");
+                reducers.iter().for_each(|reducer| self.reducer_shape(reducer, hasher))
+            }
+        }
+    }
+
+    /// `count` and `count($x)` share a name, so whether the reducer has a variable is part of its shape.
+    fn reducer_shape(&self, reducer: &Reducer, hasher: &mut impl Hasher) {
+        write_tag(hasher, &reducer.name());
+        match reducer.variable() {
+            Some(variable) => {
+                hasher.write_u8(1);
+                variable_shape(variable, &self.variables, hasher);
+            }
+            None => hasher.write_u8(0),
+        }
+    }
+
+    /// Fetch entries are keyed by parameters in the order they are written, so entries are ordered by their key
+    /// instead. Keys are the names of the fields of the fetched documents, so unlike values they are part of the shape.
+    fn fetch_shape(&self, fetch: &FetchObject, parameters: &ParameterRegistry) -> u64 {
+        let mut hasher = FingerprintHasher::new();
+        match fetch {
+            FetchObject::Entries(entries, _) => {
+                write_tag(&mut hasher, "entries");
+                let entry_shapes = entries.iter().map(|(key, some)| {
+                    let key = parameters.fetch_key(key).map_or("", String::as_str);
+                    (key, self.fetch_some_shape(some, parameters))
+                });
+                hasher.write_usize(entries.len());
+                for (key, shape) in entry_shapes.sorted() {
+                    write_tag(&mut hasher, key);
+                    hasher.write_u64(shape);
+                }
+            }
+            &FetchObject::Attributes(variable, _) => {
+                write_tag(&mut hasher, "attributes");
+                variable_shape(variable, &self.variables, &mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
+    fn fetch_some_shape(&self, some: &FetchSome, parameters: &ParameterRegistry) -> u64 {
+        let mut hasher = FingerprintHasher::new();
+        match some {
+            &FetchSome::SingleVar(variable) => {
+                write_tag(&mut hasher, "variable");
+                variable_shape(variable, &self.variables, &mut hasher);
+            }
+            FetchSome::SingleAttribute(single) => {
+                write_tag(&mut hasher, "attribute");
+                variable_shape(single.variable, &self.variables, &mut hasher);
+                write_tag(&mut hasher, single.attribute.scoped_name().as_str());
+            }
+            FetchSome::SingleFunction(function) => {
+                write_tag(&mut hasher, "function");
+                self.fetch_function_shape(function, &mut hasher);
+            }
+            FetchSome::Object(object) => {
+                write_tag(&mut hasher, "object");
+                hasher.write_u64(self.fetch_shape(object, parameters));
+            }
+            FetchSome::ListFunction(function) => {
+                write_tag(&mut hasher, "list-function");
+                self.fetch_function_shape(function, &mut hasher);
+            }
+            FetchSome::ListSubFetch(sub_fetch) => {
+                write_tag(&mut hasher, "list-sub-fetch");
+                let inputs = sub_fetch
+                    .input_variables
+                    .iter()
+                    .copied()
+                    .sorted_by_key(|variable| self.variables.get(variable).copied().unwrap_or(u16::MAX))
+                    .collect_vec();
+                inputs.iter().for_each(|&input| variable_shape(input, &self.variables, &mut hasher));
+                let canonical_form = CanonicalForm::with_inputs(&inputs, &sub_fetch.stages);
+                sub_fetch.stages.iter().for_each(|stage| hasher.write_u64(canonical_form.stage_shape(stage)));
+                hasher.write_u64(canonical_form.fetch_shape(&sub_fetch.fetch, parameters));
+            }
+            FetchSome::ListAttributesAsList(list) => {
+                write_tag(&mut hasher, "attributes-as-list");
+                variable_shape(list.variable, &self.variables, &mut hasher);
+                write_tag(&mut hasher, list.attribute.scoped_name().as_str());
+            }
+            FetchSome::ListAttributesFromList(list) => {
+                write_tag(&mut hasher, "attributes-from-list");
+                variable_shape(list.variable, &self.variables, &mut hasher);
+                write_tag(&mut hasher, list.attribute.scoped_name().as_str());
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Functions in a fetch take their arguments from the enclosing pipeline.
+    fn fetch_function_shape(&self, function: &Function, hasher: &mut impl Hasher) {
+        function.arguments.iter().for_each(|&argument| variable_shape(argument, &self.variables, hasher));
+        hasher.write_u64(function_shape(function));
+    }
+
+    fn sorted_variables_shape<'a>(&self, variables: impl Iterator<Item = &'a Variable>, hasher: &mut impl Hasher) {
+        let ids = variables
+            .map(|variable| self.variables.get(variable).map_or(u64::MAX, |&id| id as u64))
+            .sorted()
+            .collect_vec();
+        hasher.write_usize(ids.len());
+        ids.into_iter().for_each(|id| hasher.write_u64(id));
+    }
+}
+
+/// Writes a tag with its length, so that consecutive tags cannot run into each other.
+fn write_tag(hasher: &mut impl Hasher, tag: &str) {
+    hasher.write_usize(tag.len());
+    hasher.write(tag.as_bytes());
+}
+
+/// FNV-1a over little-endian integers. Unlike the standard library's default hasher, its output is specified and does
+/// not depend on the platform, so fingerprints stay comparable across releases.
+#[derive(Debug, Clone)]
+struct FingerprintHasher {
+    state: u64,
+}
+
+impl FingerprintHasher {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    fn new() -> Self {
+        Self { state: Self::OFFSET_BASIS }
+    }
+}
+
+impl Hasher for FingerprintHasher {
+    fn finish(&self) -> u64 {
+        self.state
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.state ^= byte as u64;
+            self.state = self.state.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64)
+    }
+
+    fn write_i16(&mut self, value: i16) {
+        self.write(&value.to_le_bytes())
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.write(&value.to_le_bytes())
+    }
+
+    fn write_i64(&mut self, value: i64) {
+        self.write(&value.to_le_bytes())
+    }
+
+    fn write_isize(&mut self, value: isize) {
+        self.write_i64(value as i64)
+    }
+}
+
+fn function_shape(function: &Function) -> u64 {
+    let canonical_form = CanonicalForm::with_inputs(&function.arguments, function.body().stages());
+    let mut hasher = FingerprintHasher::new();
+    function.arguments.iter().for_each(|&argument| variable_shape(argument, &canonical_form.variables, &mut hasher));
+    if let Some(argument_labels) = &function.argument_labels {
+        argument_labels.iter().for_each(|label| write_tag(&mut hasher, &label.to_string()));
+    }
+    if let Some(output) = &function.output {
+        write_tag(&mut hasher, &output.to_string());
+    }
+    function.body().stages().iter().for_each(|stage| hasher.write_u64(canonical_form.stage_shape(stage)));
+    canonical_form.return_shape(&function.body().return_operation, &mut hasher);
+    hasher.finish()
+}
+
+fn canonical_variables(
+    inputs: &[Variable],
+    stages: &[TranslatedStage],
+    tentative_variables: &HashMap<Variable, u16>,
+) -> HashMap<Variable, u16> {
+    let mut variables = HashMap::new();
+    for &input in inputs {
+        name_variable(input, &mut variables);
+    }
+    for stage in stages {
+        match stage {
+            TranslatedStage::Match { block, .. }
+            | TranslatedStage::Insert { block, .. }
+            | TranslatedStage::Update { block, .. }
+            | TranslatedStage::Put { block, .. }
+            | TranslatedStage::Delete { block, .. } => {
+                name_conjunction_variables(block.conjunction(), tentative_variables, &mut variables)
+            }
+            TranslatedStage::Reduce(reduce) => {
+                for assigned_reduction in &reduce.assigned_reductions {
+                    name_variable(assigned_reduction.assigned, &mut variables);
+                }
+            }
+            // modifiers only refer to variables bound by earlier stages
+            TranslatedStage::Select(_)
+            | TranslatedStage::Sort(_)
+            | TranslatedStage::Offset(_)
+            | TranslatedStage::Limit(_)
+            | TranslatedStage::Require(_)
+            | TranslatedStage::Distinct(_) => (),
+        }
+    }
+    variables
+}
+
+fn name_conjunction_variables(
+    conjunction: &Conjunction,
+    tentative_variables: &HashMap<Variable, u16>,
+    variables: &mut HashMap<Variable, u16>,
+) {
+    let constraints = conjunction
+        .constraints()
+        .iter()
+        .sorted_by_cached_key(|constraint| constraint_shape(constraint, tentative_variables));
+    for constraint in constraints {
+        constraint.ids_foreach(|variable| name_variable(variable, variables));
+    }
+    let nested_patterns = conjunction
+        .nested_patterns()
+        .iter()
+        .sorted_by_cached_key(|nested| nested_pattern_shape(nested, tentative_variables));
+    for nested in nested_patterns {
+        match nested {
+            NestedPattern::Disjunction(disjunction) => {
+                let branches = disjunction
+                    .conjunctions()
+                    .iter()
+                    .sorted_by_cached_key(|branch| conjunction_shape(branch, tentative_variables));
+                for branch in branches {
+                    name_conjunction_variables(branch, tentative_variables, variables);
+                }
+            }
+            NestedPattern::Negation(negation) => {
+                name_conjunction_variables(negation.conjunction(), tentative_variables, variables)
+            }
+            NestedPattern::Optional(optional) => {
+                name_conjunction_variables(optional.conjunction(), tentative_variables, variables)
+            }
+        }
+    }
+}
+
+fn name_variable(variable: Variable, variables: &mut HashMap<Variable, u16>) {
+    let next_id = variables.len() as u16;
+    variables.entry(variable).or_insert(next_id);
+}
+
+fn conjunction_shape(conjunction: &Conjunction, variables: &HashMap<Variable, u16>) -> u64 {
+    let mut hasher = FingerprintHasher::new();
+    let constraint_shapes = conjunction.constraints().iter().map(|constraint| constraint_shape(constraint, variables));
+    constraint_shapes.sorted().for_each(|shape| hasher.write_u64(shape));
+    let nested_shapes = conjunction.nested_patterns().iter().map(|nested| nested_pattern_shape(nested, variables));
+    nested_shapes.sorted().for_each(|shape| hasher.write_u64(shape));
+    hasher.finish()
+}
+
+fn nested_pattern_shape(nested: &NestedPattern, variables: &HashMap<Variable, u16>) -> u64 {
+    let mut hasher = FingerprintHasher::new();
+    match nested {
+        NestedPattern::Disjunction(disjunction) => {
+            write_tag(&mut hasher, "or");
+            let branch_shapes = disjunction.conjunctions().iter().map(|branch| conjunction_shape(branch, variables));
+            branch_shapes.sorted().for_each(|shape| hasher.write_u64(shape));
+        }
+        NestedPattern::Negation(negation) => {
+            write_tag(&mut hasher, "not");
+            hasher.write_u64(conjunction_shape(negation.conjunction(), variables))
+        }
+        NestedPattern::Optional(optional) => {
+            write_tag(&mut hasher, "try");
+            hasher.write_u64(conjunction_shape(optional.conjunction(), variables))
+        }
+    }
+    hasher.finish()
+}
+
+/// Variables without a name hash alike, so the shape of a constraint over unnamed variables only depends on its kind,
+/// its types and how its variables are arranged. The name of a constraint is unique to its kind, so it doubles as
+/// its tag.
+fn constraint_shape(constraint: &Constraint<Variable>, variables: &HashMap<Variable, u16>) -> u64 {
+    let mut hasher = FingerprintHasher::new();
+    write_tag(&mut hasher, constraint.name());
+    constraint.vertices().for_each(|vertex| vertex_shape(vertex, variables, &mut hasher));
+    match constraint {
+        Constraint::RoleName(role_name) => write_tag(&mut hasher, role_name.name()),
+        Constraint::Value(value) => value_type_shape(value.value_type(), &mut hasher),
+        Constraint::Isa(isa) => match isa.isa_kind() {
+            IsaKind::Exact => write_tag(&mut hasher, "exact"),
+            IsaKind::Subtype => write_tag(&mut hasher, "subtype"),
+        },
+        Constraint::Sub(sub) => match sub.sub_kind() {
+            SubKind::Exact => write_tag(&mut hasher, "exact"),
+            SubKind::Subtype => write_tag(&mut hasher, "subtype"),
+        },
+        Constraint::ExpressionBinding(binding) => binding
+            .expression()
+            .expression_tree_preorder()
+            .for_each(|expression| expression_shape(expression, variables, &mut hasher)),
+        Constraint::FunctionCallBinding(binding) => {
+            function_id_shape(&binding.function_call().function_id(), &mut hasher);
+            binding
+                .function_call()
+                .argument_ids()
+                .for_each(|variable| variable_shape(variable, variables, &mut hasher));
+        }
+        Constraint::Is(_)
+        | Constraint::Kind(_)
+        | Constraint::Label(_)
+        | Constraint::Iid(_)
+        | Constraint::Links(_)
+        | Constraint::IndexedRelation(_)
+        | Constraint::Has(_)
+        | Constraint::Comparison(_)
+        | Constraint::Owns(_)
+        | Constraint::Relates(_)
+        | Constraint::Plays(_)
+        | Constraint::LinksDeduplication(_)
+        | Constraint::Unsatisfiable(_) => (),
+    }
+    hasher.finish()
+}
+
+fn vertex_shape(vertex: &Vertex<Variable>, variables: &HashMap<Variable, u16>, hasher: &mut impl Hasher) {
+    match vertex {
+        &Vertex::Variable(variable) => {
+            write_tag(hasher, "variable");
+            variable_shape(variable, variables, hasher)
+        }
+        Vertex::Label(label) => {
+            write_tag(hasher, "label");
+            write_tag(hasher, label.scoped_name().as_str())
+        }
+        Vertex::Parameter(parameter) => {
+            write_tag(hasher, "parameter");
+            parameter_shape(parameter, hasher)
+        }
+    }
+}
+
+fn variable_shape(variable: Variable, variables: &HashMap<Variable, u16>, hasher: &mut impl Hasher) {
+    hasher.write_u64(variables.get(&variable).map_or(u64::MAX, |&id| id as u64));
+    hasher.write_u8(variable.is_anonymous() as u8);
+}
+
+fn value_type_shape(value_type: &ValueType, hasher: &mut impl Hasher) {
+    match value_type {
+        ValueType::Builtin(value_type) => {
+            write_tag(hasher, "builtin");
+            write_tag(hasher, &value_type.to_string())
+        }
+        ValueType::Struct(name) => {
+            write_tag(hasher, "struct");
+            write_tag(hasher, name)
+        }
+    }
+}
+
+/// Schema functions are identified by their definition key, which is stable for as long as the function is defined.
+fn function_id_shape(function_id: &FunctionID, hasher: &mut impl Hasher) {
+    match function_id {
+        FunctionID::Builtin(builtin) => {
+            write_tag(hasher, "builtin");
+            write_tag(hasher, &builtin.to_string())
+        }
+        FunctionID::Schema(definition_key) => {
+            write_tag(hasher, "schema");
+            hasher.write_u64(definition_key.definition_id().as_uint() as u64)
+        }
+        &FunctionID::Preamble(index) => {
+            write_tag(hasher, "preamble");
+            hasher.write_usize(index)
+        }
+    }
+}
+
+/// Parameters are identified by their position in the query, which depends on the order constraints are written in,
+/// so only their kind and value type are part of the shape.
+fn parameter_shape(parameter: &ParameterID, hasher: &mut impl Hasher) {
+    match parameter {
+        ParameterID::Value(_, value_type, _) => {
+            write_tag(hasher, "value");
+            value_type_shape(value_type, hasher)
+        }
+        ParameterID::Iid(..) => write_tag(hasher, "iid"),
+        ParameterID::FetchKey(..) => write_tag(hasher, "fetch-key"),
+    }
+}
+
+fn expression_shape(expression: &Expression<Variable>, variables: &HashMap<Variable, u16>, hasher: &mut impl Hasher) {
+    match expression {
+        Expression::Constant(parameter) => {
+            write_tag(hasher, "constant");
+            parameter_shape(parameter, hasher)
+        }
+        &Expression::Variable(variable) => {
+            write_tag(hasher, "variable");
+            variable_shape(variable, variables, hasher)
+        }
+        Expression::ListIndex(list_index) => {
+            write_tag(hasher, "list-index");
+            variable_shape(list_index.list_variable(), variables, hasher)
+        }
+        Expression::ListIndexRange(list_index_range) => {
+            write_tag(hasher, "list-index-range");
+            variable_shape(list_index_range.list_variable(), variables, hasher)
+        }
+        Expression::Operation(operation) => {
+            write_tag(hasher, "operation");
+            write_tag(hasher, &operation.operator().to_string());
+            hasher.write_usize(operation.left_expression_id());
+            hasher.write_usize(operation.right_expression_id());
+        }
+        Expression::BuiltinValueFunctionCall(call) => {
+            write_tag(hasher, "builtin-function");
+            write_tag(hasher, &call.function_id().to_string());
+            expression_ids_shape(call.argument_expression_ids(), hasher);
+        }
+        Expression::List(list) => {
+            write_tag(hasher, "list");
+            expression_ids_shape(list.item_expression_ids(), hasher);
+        }
+    }
+}
+
+fn expression_ids_shape(ids: &[ExpressionTreeNodeId], hasher: &mut impl Hasher) {
+    hasher.write_usize(ids.len());
+    ids.iter().for_each(|&id| hasher.write_usize(id));
+}
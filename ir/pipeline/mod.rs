@@ -32,6 +32,7 @@ use crate::{
 
 pub mod block;
 pub mod fetch;
+pub mod fingerprint;
 pub mod function;
 pub mod function_signature;
 pub mod modifier;
@@ -82,6 +82,8 @@ impl StructuralEquality for AssignedReduction {
     }
 }
 
+/// Reducers skip the rows in which their variable is empty, for example when it is bound in an optional pattern
+/// that did not match, except for `count`, which counts all rows.
 #[derive(Clone, Copy, Debug)]
 pub enum Reducer {
     Count,
@@ -92,6 +94,7 @@ pub enum Reducer {
     Median(Variable),
     Min(Variable),
     Std(Variable),
+    List(Variable),
     // First, Any etc.
 }
 
@@ -106,6 +109,7 @@ impl Reducer {
             Self::Median(_) => typeql::token::ReduceOperator::Median.to_string(),
             Self::Min(_) => typeql::token::ReduceOperator::Min.to_string(),
             Self::Std(_) => typeql::token::ReduceOperator::Std.to_string(),
+            Self::List(_) => typeql::token::ReduceOperator::List.to_string(),
         }
     }
 
@@ -118,7 +122,8 @@ impl Reducer {
             | Self::Mean(var)
             | Self::Median(var)
             | Self::Min(var)
-            | Self::Std(var) => Some(*var),
+            | Self::Std(var)
+            | Self::List(var) => Some(*var),
         }
     }
 }
@@ -10,7 +10,7 @@ use encoding::{
 };
 use ir::{
     pattern::{
-        constraint::IsaKind,
+        constraint::{Constraint, IsaKind},
         variable_category::{VariableCategory, VariableOptionality},
     },
     pipeline::{
@@ -18,7 +18,10 @@ use ir::{
         function_signature::{FunctionID, FunctionSignature, HashMapFunctionSignatureIndex},
         ParameterRegistry,
     },
-    translation::{pipeline::translate_pipeline, PipelineTranslationContext},
+    translation::{
+        pipeline::{translate_pipeline, TranslatedStage},
+        PipelineTranslationContext,
+    },
 };
 
 // TODO: if we re-instante modifiers/stream operators as part of blocks, then we can bring this test back
@@ -252,3 +255,35 @@ fn nested_optional_blocks_in_write() {
     );
     assert!(translation_result.is_err(), "Nested try blocks are not yet supported in write stages: {query}");
 }
+
+#[test]
+fn user_function_named_like_builtin_takes_precedence() {
+    let match_in_timezone = r#"match let $x = in_timezone(2024-06-01T12:00:00+00:00, "Europe/London");"#;
+    let is_function_call = |query: &str| {
+        let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+        let function_index = HashMapFunctionSignatureIndex::build(
+            pipeline.preambles.iter().enumerate().map(|(i, preamble)| (FunctionID::Preamble(i), &preamble.function)),
+        );
+        let translated = translate_pipeline(&function_index, &pipeline).unwrap();
+        let Some(TranslatedStage::Match { block, .. }) = translated.translated_stages.first() else {
+            panic!("expected a match stage");
+        };
+        match &block.conjunction().constraints()[0] {
+            Constraint::FunctionCallBinding(_) => true,
+            Constraint::ExpressionBinding(_) => false,
+            constraint => panic!("unexpected constraint {constraint}"),
+        }
+    };
+
+    assert!(!is_function_call(match_in_timezone));
+
+    let user_defined = format!(
+        r#"
+        with fun in_timezone($time: datetime-tz, $zone: string) -> datetime-tz:
+            match let $shifted = $time;
+            return first $shifted;
+        {match_in_timezone}
+    "#
+    );
+    assert!(is_function_call(&user_defined));
+}
@@ -4,8 +4,14 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use std::collections::HashMap;
+
 use ir::{
-    pipeline::function_signature::HashMapFunctionSignatureIndex,
+    pipeline::{
+        fingerprint::{CanonicalForm, QueryFingerprint},
+        function_signature::HashMapFunctionSignatureIndex,
+        VariableRegistry,
+    },
     translation::{
         function::translate_typeql_function,
         pipeline::{translate_pipeline, TranslatedPipeline},
@@ -287,3 +293,99 @@ fn test_anonymous_non_equivalence() {
 
     assert!(!is_structurally_equivalent(&translated_stages, &different_translated_stages));
 }
+
+fn fingerprint(query: &str) -> QueryFingerprint {
+    let TranslatedPipeline { translated_preamble, translated_stages, translated_fetch, value_parameters, .. } =
+        translate_pipeline(
+            &HashMapFunctionSignatureIndex::empty(),
+            &typeql::parse_query(query).unwrap().into_structure().into_pipeline(),
+        )
+        .unwrap();
+    QueryFingerprint::of(&translated_preamble, &translated_stages, translated_fetch.as_ref(), &value_parameters)
+}
+
+#[test]
+fn test_fingerprint_ignores_names_order_and_parameters() {
+    let query = "
+match
+  $p isa person, has name \"alice\", has age $age;
+  $f links (friend: $p, friend: $q), isa friendship;
+  { $q has age > 10; } or { $q has age < 5; };
+sort $age desc;
+limit 10;
+";
+    let reordered = "
+match
+  $x links (friend: $y, friend: $x_friend), isa friendship;
+  { $x_friend has age < 40; } or { $x_friend has age > 0; };
+  $y has age $years, isa person, has name \"bob\";
+sort $years desc;
+limit 25;
+";
+    assert_eq!(fingerprint(query), fingerprint(reordered));
+}
+
+#[test]
+fn test_fingerprint_distinguishes_shapes() {
+    let query = "match $p isa person, has name $name; sort $name asc;";
+    assert_ne!(fingerprint(query), fingerprint("match $p isa dog, has name $name; sort $name asc;"));
+    assert_ne!(fingerprint(query), fingerprint("match $p isa person, has email $name; sort $name asc;"));
+    assert_ne!(fingerprint(query), fingerprint("match $p isa person, has name $name; sort $name desc;"));
+    assert_ne!(fingerprint(query), fingerprint("match $p isa! person, has name $name; sort $name asc;"));
+    assert_ne!(fingerprint(query), fingerprint("match $p isa person, has name $_; sort $p asc;"));
+
+    let links = "match $r links (friend: $x);";
+    assert_eq!(fingerprint(links), fingerprint("match $f links (friend: $y);"));
+    assert_ne!(fingerprint(links), fingerprint("match $r links (employee: $x);"));
+    assert_ne!(fingerprint("match $t value string;"), fingerprint("match $t value integer;"));
+}
+
+#[test]
+fn test_fingerprint_distinguishes_fetch_keys_and_structure() {
+    let query = "match $p isa person; fetch { \"name\": $p.name, \"age\": $p.age };";
+    assert_eq!(fingerprint(query), fingerprint("match $x isa person; fetch { \"age\": $x.age, \"name\": $x.name };"));
+    assert_ne!(
+        fingerprint(query),
+        fingerprint("match $p isa person; fetch { \"full-name\": $p.name, \"age\": $p.age };")
+    );
+    assert_ne!(fingerprint(query), fingerprint("match $p isa person; fetch { \"name\": $p.age, \"age\": $p.name };"));
+    assert_ne!(fingerprint(query), fingerprint("match $p isa person; fetch { \"name\": [$p.name], \"age\": $p.age };"));
+    assert_ne!(
+        fingerprint(query),
+        fingerprint("match $p isa person; fetch { \"name\": { \"name\": $p.name }, \"age\": $p.age };")
+    );
+    assert_ne!(fingerprint(query), fingerprint("match $p isa person; fetch { $p.* };"));
+}
+
+#[test]
+fn test_canonical_form_renames_variables_by_canonical_order() {
+    let query = "match $p has name $n; $p isa person;";
+    let TranslatedPipeline { translated_stages, variable_registry, .. } = translate_pipeline(
+        &HashMapFunctionSignatureIndex::empty(),
+        &typeql::parse_query(query).unwrap().into_structure().into_pipeline(),
+    )
+    .unwrap();
+    let reordered = "match $person isa person; $person has name $name;";
+    let TranslatedPipeline {
+        translated_stages: reordered_stages, variable_registry: reordered_variable_registry, ..
+    } = translate_pipeline(
+        &HashMapFunctionSignatureIndex::empty(),
+        &typeql::parse_query(reordered).unwrap().into_structure().into_pipeline(),
+    )
+    .unwrap();
+
+    let canonical_form = CanonicalForm::new(&translated_stages);
+    let reordered_canonical_form = CanonicalForm::new(&reordered_stages);
+    let canonical_names = |canonical_form: &CanonicalForm, variable_registry: &VariableRegistry| {
+        variable_registry
+            .variable_names()
+            .iter()
+            .map(|(&variable, name)| (name.clone(), canonical_form.variable(variable).unwrap()))
+            .collect::<HashMap<_, _>>()
+    };
+    let names = canonical_names(&canonical_form, &variable_registry);
+    let reordered_names = canonical_names(&reordered_canonical_form, &reordered_variable_registry);
+    assert_eq!(names["p"], reordered_names["person"]);
+    assert_eq!(names["n"], reordered_names["name"]);
+    assert_ne!(names["p"], names["n"]);
+}
@@ -29,7 +29,7 @@ use crate::{
     },
     pipeline::function_signature::FunctionSignatureIndex,
     translation::{
-        expression::{add_typeql_expression, add_user_defined_function_call, build_expression},
+        expression::{add_typeql_expression, add_user_defined_function_call, build_expression, resolve_named_builtin},
         literal::translate_literal,
         tokens::{checked_identifier, translate_value_type},
     },
@@ -66,7 +66,13 @@ pub(super) fn add_statement(
         }
         typeql::Statement::Assignment(Assignment { lhs, rhs, span }) => {
             let assigned = assignment_pattern_to_variables(constraints, lhs)?;
-            if let typeql::Expression::Function(FunctionCall { name: FunctionName::Identifier(id), args, span }) = rhs {
+            let user_defined_call = match rhs {
+                typeql::Expression::Function(FunctionCall { name: FunctionName::Identifier(id), args, span }) => {
+                    resolve_named_builtin(function_index, id.as_str_unchecked())?.is_none().then_some((id, args, span))
+                }
+                _ => None,
+            };
+            if let Some((id, args, span)) = user_defined_call {
                 add_user_defined_function_call(
                     function_index,
                     constraints,
@@ -560,6 +566,11 @@ fn add_typeql_iterable_binding(
 ) -> Result<(), Box<RepresentationError>> {
     match rhs {
         typeql::Expression::Function(FunctionCall { name: FunctionName::Identifier(identifier), args, span }) => {
+            if resolve_named_builtin(function_index, identifier.as_str_unchecked())?.is_some() {
+                return Err(Box::new(RepresentationError::UnimplementedLanguageFeature {
+                    feature: UnimplementedFeature::LetInBuiltinCall,
+                }));
+            }
             add_user_defined_function_call(
                 function_index,
                 constraints,
@@ -163,6 +163,21 @@ pub fn add_user_defined_function_call(
     Ok(())
 }
 
+/// Resolves a call by name to a value built-in without a TypeQL keyword.
+/// User-defined functions take precedence, so a function named like a built-in keeps the meaning of its callers.
+pub(crate) fn resolve_named_builtin(
+    function_index: &impl FunctionSignatureIndex,
+    name: &str,
+) -> Result<Option<BuiltinValueFunctionID>, Box<RepresentationError>> {
+    let Some(function_id) = BuiltinValueFunctionID::from_name(name) else {
+        return Ok(None);
+    };
+    let user_defined = function_index
+        .get_function_signature(name)
+        .map_err(|typedb_source| RepresentationError::FunctionReadError { typedb_source })?;
+    Ok(user_defined.is_none().then_some(function_id))
+}
+
 fn build_function(
     function_index: &impl FunctionSignatureIndex,
     constraints: &mut ConstraintsBuilder<'_, '_>,
@@ -195,6 +210,19 @@ fn build_function(
             Ok(Expression::Variable(assign))
         }
         FunctionName::Identifier(identifier) => {
+            if let Some(function_id) = resolve_named_builtin(function_index, identifier.as_str_unchecked())? {
+                let args = function_call
+                    .args
+                    .iter()
+                    .map(|expr| build_recursive(function_index, constraints, expr, tree))
+                    .collect::<Result<Vec<_>, _>>()?;
+                check_named_builtin_arg_count(function_id, args.len(), identifier.span())?;
+                return Ok(Expression::BuiltinValueFunctionCall(BuiltinValueFunctionCall::new(
+                    function_id,
+                    args,
+                    identifier.span(),
+                )));
+            }
             let assign = constraints.create_anonymous_variable(identifier.span())?;
             add_user_defined_function_call(
                 function_index,
@@ -221,7 +249,7 @@ fn translate_operator(operator: &ArithmeticOperator) -> Operator {
 }
 
 fn check_builtin_arg_count(
-    builtin: Function,
+    builtin: &str,
     actual: usize,
     expected: usize,
     source_span: Option<Span>,
@@ -230,7 +258,7 @@ fn check_builtin_arg_count(
         Ok(())
     } else {
         Err(Box::new(RepresentationError::ExpressionBuiltinArgumentCountMismatch {
-            builtin,
+            builtin: builtin.to_owned(),
             expected,
             actual,
             source_span,
@@ -238,6 +266,18 @@ fn check_builtin_arg_count(
     }
 }
 
+fn check_named_builtin_arg_count(
+    function_id: BuiltinValueFunctionID,
+    actual: usize,
+    source_span: Option<Span>,
+) -> Result<(), Box<RepresentationError>> {
+    let expected = match function_id {
+        BuiltinValueFunctionID::InTimezone => 2,
+        _ => unreachable!("Built-in '{function_id}' is called by its TypeQL keyword."),
+    };
+    check_builtin_arg_count(&function_id.to_string(), actual, expected, source_span)
+}
+
 fn is_builtin_value_function(typeql_id: &BuiltinFunctionName) -> bool {
     matches!(
         typeql_id.token,
@@ -258,31 +298,31 @@ fn to_builtin_value_function_id(
     let token = typeql_id.token;
     match token {
         Function::Abs => {
-            check_builtin_arg_count(token, args.len(), 1, typeql_id.span())?;
+            check_builtin_arg_count(token.as_str(), args.len(), 1, typeql_id.span())?;
             Ok(BuiltinValueFunctionID::Abs)
         }
         Function::Ceil => {
-            check_builtin_arg_count(token, args.len(), 1, typeql_id.span())?;
+            check_builtin_arg_count(token.as_str(), args.len(), 1, typeql_id.span())?;
             Ok(BuiltinValueFunctionID::Ceil)
         }
         Function::Floor => {
-            check_builtin_arg_count(token, args.len(), 1, typeql_id.span())?;
+            check_builtin_arg_count(token.as_str(), args.len(), 1, typeql_id.span())?;
             Ok(BuiltinValueFunctionID::Floor)
         }
         Function::Round => {
-            check_builtin_arg_count(token, args.len(), 1, typeql_id.span())?;
+            check_builtin_arg_count(token.as_str(), args.len(), 1, typeql_id.span())?;
             Ok(BuiltinValueFunctionID::Round)
         }
         Function::Max => {
-            check_builtin_arg_count(token, args.len(), 2, typeql_id.span())?;
+            check_builtin_arg_count(token.as_str(), args.len(), 2, typeql_id.span())?;
             Ok(BuiltinValueFunctionID::Max)
         }
         Function::Min => {
-            check_builtin_arg_count(token, args.len(), 2, typeql_id.span())?;
+            check_builtin_arg_count(token.as_str(), args.len(), 2, typeql_id.span())?;
             Ok(BuiltinValueFunctionID::Min)
         }
         Function::Len => {
-            check_builtin_arg_count(token, args.len(), 1, typeql_id.span())?;
+            check_builtin_arg_count(token.as_str(), args.len(), 1, typeql_id.span())?;
             Ok(BuiltinValueFunctionID::Len)
         }
         _ => Err(Box::new(RepresentationError::InternalNotAValueBuiltin { token, source_span: typeql_id.span() })),
@@ -296,11 +336,11 @@ fn to_builtin_concept_function_id<T>(
     let token = typeql_id.token;
     match token {
         Function::Iid => {
-            check_builtin_arg_count(token, args.len(), 1, typeql_id.span())?;
+            check_builtin_arg_count(token.as_str(), args.len(), 1, typeql_id.span())?;
             Ok(BuiltinConceptFunctionID::Iid)
         }
         Function::Label => {
-            check_builtin_arg_count(token, args.len(), 1, typeql_id.span())?;
+            check_builtin_arg_count(token.as_str(), args.len(), 1, typeql_id.span())?;
             Ok(BuiltinConceptFunctionID::Label)
         }
         _ => Err(Box::new(RepresentationError::InternalNotAConceptBuiltin { token, source_span: typeql_id.span() })),
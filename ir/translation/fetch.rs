@@ -39,7 +39,7 @@ use crate::{
         FunctionReadError, ParameterRegistry,
     },
     translation::{
-        expression::{add_user_defined_function_call, build_expression},
+        expression::{add_user_defined_function_call, build_expression, resolve_named_builtin},
         fetch::FetchRepresentationError::{
             AnonymousVariableEncountered, InvalidAttributeLabelEncountered, NamedVariableEncountered,
             VariableNotAvailable,
@@ -218,6 +218,16 @@ fn translate_fetch_single(
                     translate_inline_expression_single(parent_context, value_parameters, function_index, expression)
                 }
                 FunctionName::Identifier(name) => {
+                    let named_builtin = resolve_named_builtin(function_index, name.as_str_unchecked())
+                        .map_err(|typedb_source| FetchRepresentationError::SubFetchRepresentation { typedb_source })?;
+                    if named_builtin.is_some() {
+                        return translate_inline_expression_single(
+                            parent_context,
+                            value_parameters,
+                            function_index,
+                            expression,
+                        );
+                    }
                     let checked_name = checked_identifier(name)
                         .map_err(|typedb_source| FetchRepresentationError::SubFetchRepresentation { typedb_source })?;
                     translate_inline_user_function_call_single(
@@ -63,6 +63,7 @@ fn resolve_category_optionality(reduce: &Reducer, variable_registry: &VariableRe
         Reducer::Median(_) => (VariableCategory::Value, true),
         Reducer::Min(_) => (VariableCategory::Value, true),
         Reducer::Std(_) => (VariableCategory::Value, true),
+        Reducer::List(_) => (VariableCategory::ValueList, false),
     }
 }
 
@@ -87,7 +88,8 @@ pub(crate) fn build_reducer(
                 TypeQLReduceOperator::Median => Ok(Reducer::Median(var)),
                 TypeQLReduceOperator::Min => Ok(Reducer::Min(var)),
                 TypeQLReduceOperator::Std => Ok(Reducer::Std(var)),
-                TypeQLReduceOperator::Count | TypeQLReduceOperator::List => unreachable!(), // Not stats
+                TypeQLReduceOperator::List => Ok(Reducer::List(var)),
+                TypeQLReduceOperator::Count => unreachable!(), // Not stats
             }
         }
     }
@@ -10,6 +10,7 @@ use compiler::{
     transformation::StaticOptimiserError,
 };
 use concept::error::ConceptReadError;
+use encoding::value::label::Label;
 use error::typedb_error;
 use executor::pipeline::{pipeline::PipelineError, PipelineExecutionError};
 use function::FunctionError;
@@ -35,5 +36,6 @@ typedb_error! {
         ReadPipelineExecution(15, "Error while executing read pipeline.",  source_query: String, typedb_source: Box<PipelineExecutionError>),
         QueryExecutionClosedEarly(16, "Query execution was closed before it finished, possibly due to transaction close, rollback, commit, or a server-side error (these should be visible in the server logs)."),
         QueryAnalysisFailed(17, "Error while analysing the query.", source_query: String, typedb_source: Box<ConceptReadError>),
+        Rename(18, "Failed to rename type '{label}' to '{new_label}'.", label: Label, new_label: Label, typedb_source: RedefineError),
     }
 }
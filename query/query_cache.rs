@@ -10,15 +10,19 @@ use std::{
 };
 
 use answer::Type;
-use compiler::executable::pipeline::ExecutablePipeline;
+use compiler::{annotation::type_inference_cache::TypeInferenceCache, executable::pipeline::ExecutablePipeline};
 use concept::thing::statistics::Statistics;
 use ir::{
-    pipeline::{fetch::FetchObject, function::Function},
+    pipeline::{fetch::FetchObject, fingerprint::QueryFingerprint, function::Function},
     translation::pipeline::TranslatedStage,
 };
 use moka::sync::{Cache, CacheBuilder};
 use resource::{
-    constants::database::{QUERY_PLAN_CACHE_FLUSH_ANY_STATISTIC_CHANGE_FRACTION, QUERY_PLAN_CACHE_SIZE},
+    constants::{
+        database::{QUERY_PLAN_CACHE_FLUSH_ANY_STATISTIC_CHANGE_FRACTION, QUERY_PLAN_CACHE_SIZE},
+        memory::QUERY_PLAN_BYTES_ESTIMATE,
+    },
+    memory::{MemoryComponent, MemoryConsumer, MemoryPressure},
     perf_counters::QUERY_CACHE_FLUSH,
 };
 use structural_equality::StructuralEquality;
@@ -26,32 +30,39 @@ use structural_equality::StructuralEquality;
 #[derive(Debug)]
 pub struct QueryCache {
     cache: Cache<IRQuery, ExecutablePipeline>,
+    type_inference_cache: TypeInferenceCache,
 }
 
 impl QueryCache {
     pub fn new() -> Self {
         let cache = CacheBuilder::new(QUERY_PLAN_CACHE_SIZE).support_invalidation_closures().build();
-        QueryCache { cache }
+        QueryCache { cache, type_inference_cache: TypeInferenceCache::new() }
+    }
+
+    pub(crate) fn type_inference_cache(&self) -> &TypeInferenceCache {
+        &self.type_inference_cache
     }
 
     pub(crate) fn get(
         &self,
+        fingerprint: QueryFingerprint,
         preamble: Arc<Vec<Function>>,
         stages: Arc<Vec<TranslatedStage>>,
         fetch: Arc<Option<FetchObject>>,
     ) -> Option<ExecutablePipeline> {
-        let key = IRQuery::new(preamble, stages, fetch);
+        let key = IRQuery::new(fingerprint, preamble, stages, fetch);
         self.cache.get(&key)
     }
 
     pub(crate) fn insert(
         &self,
+        fingerprint: QueryFingerprint,
         preamble: Arc<Vec<Function>>,
         stages: Arc<Vec<TranslatedStage>>,
         fetch: Arc<Option<FetchObject>>,
         pipeline: ExecutablePipeline,
     ) {
-        let key = IRQuery::new(preamble, stages, fetch);
+        let key = IRQuery::new(fingerprint, preamble, stages, fetch);
         self.cache.insert(key, pipeline);
     }
 
@@ -83,32 +94,64 @@ impl QueryCache {
 
     pub fn force_reset(&self, _statistics: &Statistics) {
         self.cache.invalidate_all();
+        self.type_inference_cache.invalidate_all();
         QUERY_CACHE_FLUSH.increment();
     }
 }
 
+impl MemoryConsumer for QueryCache {
+    fn component(&self) -> MemoryComponent {
+        MemoryComponent::QueryPlanCache
+    }
+
+    fn memory_usage(&self) -> u64 {
+        self.cache.entry_count() * QUERY_PLAN_BYTES_ESTIMATE
+    }
+
+    fn relieve_pressure(&self, pressure: MemoryPressure, target_bytes: u64) {
+        let keep = (target_bytes / QUERY_PLAN_BYTES_ESTIMATE) as usize;
+        if pressure == MemoryPressure::Exceeded || keep == 0 {
+            self.cache.invalidate_all();
+            self.type_inference_cache.invalidate_all();
+        } else {
+            for (query, _) in self.cache.iter().skip(keep) {
+                self.cache.invalidate(&*query);
+            }
+        }
+        self.cache.run_pending_tasks();
+    }
+}
+
 impl Default for QueryCache {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Plans are looked up by the fingerprint of their query, so that the hash does not depend on the order in which the
+/// query was written, but are only shared by structurally equal queries since compiled plans refer to their variables.
 #[derive(Debug)]
 struct IRQuery {
+    fingerprint: QueryFingerprint,
     preamable: Arc<Vec<Function>>,
     stages: Arc<Vec<TranslatedStage>>,
     fetch: Arc<Option<FetchObject>>,
 }
 
 impl IRQuery {
-    fn new(preamable: Arc<Vec<Function>>, stages: Arc<Vec<TranslatedStage>>, fetch: Arc<Option<FetchObject>>) -> Self {
-        Self { preamable, stages, fetch }
+    fn new(
+        fingerprint: QueryFingerprint,
+        preamable: Arc<Vec<Function>>,
+        stages: Arc<Vec<TranslatedStage>>,
+        fetch: Arc<Option<FetchObject>>,
+    ) -> Self {
+        Self { fingerprint, preamable, stages, fetch }
     }
 }
 
 impl Hash for IRQuery {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.hash_into(state);
+        self.fingerprint.hash(state);
     }
 }
 
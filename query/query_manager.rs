@@ -6,13 +6,23 @@
 
 use std::{collections::HashSet, sync::Arc};
 
+use answer::Thing;
 use compiler::{
-    annotation::pipeline::{annotate_preamble_and_pipeline, AnnotatedPipeline},
+    annotation::{
+        limits::CompileLimits,
+        pipeline::{annotate_preamble_and_pipeline, AnnotatedPipeline},
+        type_inference_cache::TypeInferenceCache,
+    },
     executable::pipeline::{compile_pipeline_and_functions, ExecutablePipeline},
     query_structure::{extract_pipeline_structure_from, extract_query_structure_from},
     transformation::transform::apply_transformations,
+    warning::{implicit_cast_warning, incomparable_comparison_warnings, lint_warnings, QueryWarning},
+};
+use concept::{
+    thing::thing_manager::ThingManager,
+    type_::{type_manager::TypeManager, TypeDeletePolicy},
 };
-use concept::{thing::thing_manager::ThingManager, type_::type_manager::TypeManager};
+use encoding::value::label::Label;
 use executor::pipeline::{
     pipeline::Pipeline,
     stage::{ReadPipelineStage, WritePipelineStage},
@@ -21,6 +31,7 @@ use function::function_manager::{validate_no_cycles, FunctionManager, ReadThroug
 use ir::{
     pipeline::{
         fetch::FetchObject,
+        fingerprint::QueryFingerprint,
         function::Function,
         function_signature::{FunctionID, HashMapFunctionSignatureIndex},
         ParameterRegistry, VariableRegistry,
@@ -48,11 +59,16 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct QueryManager {
     cache: Option<Arc<QueryCache>>,
+    compile_limits: CompileLimits,
 }
 
 impl QueryManager {
     pub fn new(cache: Option<Arc<QueryCache>>) -> Self {
-        Self { cache }
+        Self { cache, compile_limits: CompileLimits::default() }
+    }
+
+    pub fn with_compile_limits(self, compile_limits: CompileLimits) -> Self {
+        Self { compile_limits, ..self }
     }
 
     pub fn execute_schema(
@@ -63,6 +79,27 @@ impl QueryManager {
         function_manager: &FunctionManager,
         query: SchemaQuery,
         source_query: &str,
+    ) -> Result<(), Box<QueryError>> {
+        self.execute_schema_with_policy(
+            snapshot,
+            type_manager,
+            thing_manager,
+            function_manager,
+            query,
+            source_query,
+            TypeDeletePolicy::default(),
+        )
+    }
+
+    pub fn execute_schema_with_policy(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        type_manager: &TypeManager,
+        thing_manager: &ThingManager,
+        function_manager: &FunctionManager,
+        query: SchemaQuery,
+        source_query: &str,
+        type_delete_policy: TypeDeletePolicy,
     ) -> Result<(), Box<QueryError>> {
         event!(Level::TRACE, "Running schema query:\n{}", query);
         let query_profile = QueryProfile::new(tracing::enabled!(Level::TRACE));
@@ -98,7 +135,18 @@ impl QueryManager {
                 })
             }
             SchemaQuery::Undefine(undefine) => {
-                undefine::execute(snapshot, type_manager, thing_manager, function_manager, undefine).map_err(|err| {
+                let profile = query_profile.profile_stage(|| String::from("Undefine"), 0); // TODO executable id
+                let step_profile = profile.extend_or_get(0, || String::from("Undefine execution"));
+                undefine::execute(
+                    snapshot,
+                    type_manager,
+                    thing_manager,
+                    function_manager,
+                    undefine,
+                    type_delete_policy,
+                    step_profile.storage_counters(),
+                )
+                .map_err(|err| {
                     Box::new(QueryError::Undefine { source_query: source_query.to_string(), typedb_source: err })
                 })
             }
@@ -111,6 +159,22 @@ impl QueryManager {
         result
     }
 
+    /// Renames a type as a schema operation, keeping its instances, and rewrites the stored functions
+    /// that refer to it. TypeQL has no `redefine` syntax to relabel a type yet, so it is served by its own endpoint.
+    pub fn rename_type(
+        &self,
+        snapshot: &mut impl WritableSnapshot,
+        type_manager: &TypeManager,
+        function_manager: &FunctionManager,
+        label: &Label,
+        new_label: &Label,
+    ) -> Result<(), Box<QueryError>> {
+        event!(Level::TRACE, "Renaming type '{}' to '{}'", label, new_label);
+        redefine::rename_type(snapshot, type_manager, function_manager, label, new_label).map_err(|typedb_source| {
+            Box::new(QueryError::Rename { label: label.clone(), new_label: new_label.clone(), typedb_source })
+        })
+    }
+
     pub fn prepare_read_pipeline<Snapshot: ReadableSnapshot + 'static>(
         &self,
         snapshot: Arc<Snapshot>,
@@ -119,9 +183,80 @@ impl QueryManager {
         function_manager: &FunctionManager,
         query: &typeql::query::Pipeline,
         source_query: &str,
+    ) -> Result<Pipeline<Snapshot, ReadPipelineStage<Snapshot>>, Box<QueryError>> {
+        self.prepare_read_pipeline_profiled(
+            snapshot,
+            type_manager,
+            thing_manager,
+            function_manager,
+            query,
+            source_query,
+            false,
+        )
+    }
+
+    /// Prepares the pipeline with its query profile enabled if `is_profiled` is set,
+    /// and otherwise only when tracing is enabled.
+    pub fn prepare_read_pipeline_profiled<Snapshot: ReadableSnapshot + 'static>(
+        &self,
+        snapshot: Arc<Snapshot>,
+        type_manager: &TypeManager,
+        thing_manager: Arc<ThingManager>,
+        function_manager: &FunctionManager,
+        query: &typeql::query::Pipeline,
+        source_query: &str,
+        is_profiled: bool,
+    ) -> Result<Pipeline<Snapshot, ReadPipelineStage<Snapshot>>, Box<QueryError>> {
+        self.prepare_read_pipeline_with(
+            snapshot,
+            type_manager,
+            thing_manager,
+            function_manager,
+            query,
+            source_query,
+            is_profiled,
+            None,
+        )
+    }
+
+    /// Prepares the pipeline to answer the page after `after`, an instance of the variable named `variable` in the
+    /// first match stage: the scan of the variable's instances resumes strictly after it.
+    pub fn prepare_read_pipeline_after<Snapshot: ReadableSnapshot + 'static>(
+        &self,
+        snapshot: Arc<Snapshot>,
+        type_manager: &TypeManager,
+        thing_manager: Arc<ThingManager>,
+        function_manager: &FunctionManager,
+        query: &typeql::query::Pipeline,
+        source_query: &str,
+        variable: &str,
+        after: Thing,
+    ) -> Result<Pipeline<Snapshot, ReadPipelineStage<Snapshot>>, Box<QueryError>> {
+        self.prepare_read_pipeline_with(
+            snapshot,
+            type_manager,
+            thing_manager,
+            function_manager,
+            query,
+            source_query,
+            false,
+            Some((variable, after)),
+        )
+    }
+
+    fn prepare_read_pipeline_with<Snapshot: ReadableSnapshot + 'static>(
+        &self,
+        snapshot: Arc<Snapshot>,
+        type_manager: &TypeManager,
+        thing_manager: Arc<ThingManager>,
+        function_manager: &FunctionManager,
+        query: &typeql::query::Pipeline,
+        source_query: &str,
+        is_profiled: bool,
+        start_after: Option<(&str, Thing)>,
     ) -> Result<Pipeline<Snapshot, ReadPipelineStage<Snapshot>>, Box<QueryError>> {
         event!(Level::TRACE, "Running read query:\n{}", query);
-        let mut query_profile = QueryProfile::new(tracing::enabled!(Level::TRACE));
+        let mut query_profile = QueryProfile::new(is_profiled || tracing::enabled!(Level::TRACE));
         let compile_profile = query_profile.compilation_profile();
         compile_profile.start();
         // 1: Translate
@@ -137,10 +272,12 @@ impl QueryManager {
         let arced_stages = Arc::new(translated_stages);
         let arced_fetch = Arc::new(translated_fetch);
         let arced_parameters = Arc::new(parameters);
+        let fingerprint =
+            QueryFingerprint::of(&arced_preamble, &arced_stages, arced_fetch.as_ref().as_ref(), &arced_parameters);
         let executable_pipeline = match self
             .cache
             .as_ref()
-            .and_then(|cache| cache.get(arced_preamble.clone(), arced_stages.clone(), arced_fetch.clone()))
+            .and_then(|cache| cache.get(fingerprint, arced_preamble.clone(), arced_stages.clone(), arced_fetch.clone()))
         {
             Some(executable_pipeline) => {
                 QUERY_CACHE_HITS.increment();
@@ -159,17 +296,25 @@ impl QueryManager {
                     arced_preamble.clone(),
                     arced_stages.clone(),
                     arced_fetch.clone(),
+                    &self.compile_limits,
+                    self.cache.as_ref().map(|cache| cache.type_inference_cache()),
                 )?;
                 if let Some(cache) = self.cache.as_ref() {
-                    cache.insert(arced_preamble, arced_stages, arced_fetch, executable_pipeline.clone())
+                    cache.insert(fingerprint, arced_preamble, arced_stages, arced_fetch, executable_pipeline.clone())
                 }
                 QUERY_CACHE_MISSES.increment();
                 executable_pipeline
             }
         };
+        query_profile.set_fingerprint(fingerprint.as_u64());
 
         let ExecutablePipeline {
-            executable_functions, executable_stages, executable_fetch, pipeline_structure, ..
+            executable_functions,
+            executable_stages,
+            executable_fetch,
+            pipeline_structure,
+            warnings,
+            ..
         } = executable_pipeline;
 
         // 4: Executor
@@ -183,8 +328,11 @@ impl QueryManager {
             executable_fetch,
             arced_parameters,
             None,
+            start_after,
             Arc::new(query_profile),
+            self.compile_limits.max_reduce_list_size,
         )
+        .map(|pipeline| pipeline.with_warnings(warnings))
         .map_err(|typedb_source| {
             Box::new(QueryError::Pipeline { source_query: source_query.to_string(), typedb_source })
         })
@@ -198,9 +346,32 @@ impl QueryManager {
         function_manager: &FunctionManager,
         query: &typeql::query::Pipeline,
         source_query: &str,
+    ) -> Result<Pipeline<Snapshot, WritePipelineStage<Snapshot>>, (Snapshot, Box<QueryError>)> {
+        self.prepare_write_pipeline_profiled(
+            snapshot,
+            type_manager,
+            thing_manager,
+            function_manager,
+            query,
+            source_query,
+            false,
+        )
+    }
+
+    /// Prepares the pipeline with its query profile enabled if `is_profiled` is set,
+    /// and otherwise only when tracing is enabled.
+    pub fn prepare_write_pipeline_profiled<Snapshot: WritableSnapshot>(
+        &self,
+        snapshot: Snapshot,
+        type_manager: &TypeManager,
+        thing_manager: Arc<ThingManager>,
+        function_manager: &FunctionManager,
+        query: &typeql::query::Pipeline,
+        source_query: &str,
+        is_profiled: bool,
     ) -> Result<Pipeline<Snapshot, WritePipelineStage<Snapshot>>, (Snapshot, Box<QueryError>)> {
         event!(Level::TRACE, "Running write query:\n{}", query);
-        let mut query_profile = QueryProfile::new(tracing::enabled!(Level::TRACE));
+        let mut query_profile = QueryProfile::new(is_profiled || tracing::enabled!(Level::TRACE));
         let compile_profile = query_profile.compilation_profile();
         compile_profile.start();
         // 1: Translate
@@ -219,12 +390,13 @@ impl QueryManager {
         let arced_stages = Arc::new(translated_stages);
         let arced_fetch = Arc::new(translated_fetch);
         let arced_parameters = Arc::new(value_parameters);
+        let fingerprint =
+            QueryFingerprint::of(&arced_preamble, &arced_stages, arced_fetch.as_ref().as_ref(), &arced_parameters);
 
-        let executable_pipeline = match self
-            .cache
-            .as_ref()
-            .and_then(|cache| cache.get(arced_preamble.clone(), arced_stages.clone(), arced_fetch.clone()))
-        {
+        let cached_pipeline = self.cache.as_ref().and_then(|cache| {
+            cache.get(fingerprint, arced_preamble.clone(), arced_stages.clone(), arced_fetch.clone())
+        });
+        let executable_pipeline = match cached_pipeline {
             Some(executable_pipeline) => {
                 QUERY_CACHE_HITS.increment();
                 executable_pipeline
@@ -242,11 +414,19 @@ impl QueryManager {
                     arced_preamble.clone(),
                     arced_stages.clone(),
                     arced_fetch.clone(),
+                    &self.compile_limits,
+                    self.cache.as_ref().map(|cache| cache.type_inference_cache()),
                 );
                 match executable_pipeline_result {
                     Ok(executable_pipeline) => {
                         if let Some(cache) = self.cache.as_ref() {
-                            cache.insert(arced_preamble, arced_stages, arced_fetch, executable_pipeline.clone())
+                            cache.insert(
+                                fingerprint,
+                                arced_preamble,
+                                arced_stages,
+                                arced_fetch,
+                                executable_pipeline.clone(),
+                            )
                         }
                         QUERY_CACHE_MISSES.increment();
                         executable_pipeline
@@ -257,9 +437,15 @@ impl QueryManager {
                 }
             }
         };
+        query_profile.set_fingerprint(fingerprint.as_u64());
 
         let ExecutablePipeline {
-            executable_functions, executable_stages, executable_fetch, pipeline_structure, ..
+            executable_functions,
+            executable_stages,
+            executable_fetch,
+            pipeline_structure,
+            warnings,
+            ..
         } = executable_pipeline;
 
         // 4: Executor
@@ -273,7 +459,9 @@ impl QueryManager {
             executable_fetch,
             arced_parameters.clone(),
             Arc::new(query_profile),
-        ))
+            self.compile_limits.max_reduce_list_size,
+        )
+        .with_warnings(warnings))
     }
 
     pub fn analyse<Snapshot: ReadableSnapshot + 'static>(
@@ -328,6 +516,8 @@ impl QueryManager {
             (*arced_preamble).clone(),
             (*arced_stages).clone(),
             (*arced_fetch).clone(),
+            &self.compile_limits,
+            self.cache.as_ref().map(|cache| cache.type_inference_cache()),
         )
         .map_err(|err| QueryError::Annotation { source_query: source_query.to_string(), typedb_source: err })?;
         compile_profile.annotation_finished();
@@ -359,6 +549,68 @@ impl QueryManager {
             annotations: query_structure_annotations,
         })
     }
+
+    /// Translates and annotates the pipeline without executing it, and returns the warnings of compiling it along
+    /// with the structural ones that are only reported by linting.
+    pub fn lint(
+        &self,
+        snapshot: &impl ReadableSnapshot,
+        type_manager: &TypeManager,
+        function_manager: &FunctionManager,
+        query: &typeql::query::Pipeline,
+        source_query: &str,
+    ) -> Result<Vec<QueryWarning>, Box<QueryError>> {
+        event!(Level::TRACE, "Running lint query:\n{}", query);
+        // 1: Translate
+        let TranslatedPipeline {
+            translated_preamble,
+            translated_stages,
+            translated_fetch,
+            mut variable_registry,
+            value_parameters: parameters,
+        } = translate_pipeline(snapshot, function_manager, query, source_query)?;
+        let fetch_variables = translated_fetch.as_ref().map(|fetch| fetch.variables());
+
+        validate_no_cycles(&translated_preamble.iter().enumerate().collect()).map_err(|typedb_source| {
+            Box::new(QueryError::FunctionDefinition { source_query: source_query.to_string(), typedb_source })
+        })?;
+
+        // 2: Annotate
+        let annotated_schema_functions =
+            function_manager.get_annotated_functions(snapshot, type_manager).map_err(|err| {
+                QueryError::FunctionDefinition { source_query: source_query.to_string(), typedb_source: err }
+            })?;
+        let mut annotated_pipeline = annotate_preamble_and_pipeline(
+            snapshot,
+            type_manager,
+            annotated_schema_functions,
+            &mut variable_registry,
+            &parameters,
+            translated_preamble,
+            translated_stages,
+            translated_fetch,
+            &self.compile_limits,
+            self.cache.as_ref().map(|cache| cache.type_inference_cache()),
+        )
+        .map_err(|err| QueryError::Annotation { source_query: source_query.to_string(), typedb_source: err })?;
+
+        // 3: Analyse, before transformations remove the always-empty branches
+        let mut warnings = lint_warnings(&variable_registry, &annotated_pipeline.annotated_stages, fetch_variables);
+        warnings.extend(incomparable_comparison_warnings(
+            &variable_registry,
+            &parameters,
+            &annotated_pipeline.annotated_stages,
+        ));
+        let transformation_warnings =
+            apply_transformations(snapshot, type_manager, &variable_registry, &mut annotated_pipeline).map_err(
+                |typedb_source| {
+                    Box::new(QueryError::Transformation { source_query: source_query.to_string(), typedb_source })
+                },
+            )?;
+        warnings.extend(transformation_warnings);
+        warnings.extend(implicit_cast_warning(&annotated_pipeline.annotated_stages));
+        Ok(warnings)
+    }
 }
 
 fn translate_pipeline<Snapshot: ReadableSnapshot>(
@@ -389,6 +641,8 @@ fn annotate_and_compile_query(
     arced_preamble: Arc<Vec<Function>>,
     arced_stages: Arc<Vec<TranslatedStage>>,
     arced_fetch: Arc<Option<FetchObject>>,
+    compile_limits: &CompileLimits,
+    type_inference_cache: Option<&TypeInferenceCache>,
 ) -> Result<ExecutablePipeline, Box<QueryError>> {
     match validate_no_cycles(&arced_preamble.iter().enumerate().collect()) {
         Ok(_) => {}
@@ -421,6 +675,8 @@ fn annotate_and_compile_query(
         (*arced_preamble).clone(),
         (*arced_stages).clone(),
         (*arced_fetch).clone(),
+        compile_limits,
+        type_inference_cache,
     );
 
     let mut annotated_pipeline = match annotated_pipeline {
@@ -437,8 +693,14 @@ fn annotate_and_compile_query(
         source_query,
     ));
 
-    match apply_transformations(snapshot, &type_manager, &mut annotated_pipeline) {
-        Ok(_) => {}
+    let incomparable_warnings =
+        incomparable_comparison_warnings(variable_registry, &arced_parameters, &annotated_pipeline.annotated_stages);
+    let mut warnings = match apply_transformations(snapshot, &type_manager, &variable_registry, &mut annotated_pipeline)
+    {
+        Ok(mut warnings) => {
+            warnings.extend(incomparable_warnings);
+            warnings
+        }
         Err(err) => {
             return Err(Box::new(QueryError::Transformation {
                 source_query: source_query.to_string(),
@@ -446,11 +708,12 @@ fn annotate_and_compile_query(
             }))
         }
     };
+    warnings.extend(implicit_cast_warning(&annotated_pipeline.annotated_stages));
 
     let AnnotatedPipeline { annotated_preamble, annotated_stages, annotated_fetch } = annotated_pipeline;
 
     // 3: Compile
-    let executable_pipeline = match compile_pipeline_and_functions(
+    let mut executable_pipeline = match compile_pipeline_and_functions(
         thing_manager.statistics(),
         &variable_registry,
         &annotated_schema_functions,
@@ -468,6 +731,7 @@ fn annotate_and_compile_query(
             }))
         }
     };
+    executable_pipeline.warnings = warnings;
     compile_profile.compilation_finished();
     Ok(executable_pipeline)
 }
@@ -98,6 +98,37 @@ pub(crate) fn execute(
     }
 }
 
+/// Rewrites the stored functions that refer to the type, which would no longer type-check, then renames it.
+/// The functions go first, since the roles they name are resolved against the schema as it was.
+pub(crate) fn rename_type(
+    snapshot: &mut impl WritableSnapshot,
+    type_manager: &TypeManager,
+    function_manager: &FunctionManager,
+    label: &Label,
+    new_label: &Label,
+) -> Result<(), RedefineError> {
+    let type_ = resolve_typeql_type(snapshot, type_manager, label)
+        .map_err(|typedb_source| RedefineError::DefinitionResolution { typedb_source })?;
+    function_manager.rename_type_references(snapshot, type_manager, label, new_label).map_err(|typedb_source| {
+        RedefineError::RenameFunctionReferences {
+            type_: label.clone(),
+            new_label: new_label.clone(),
+            typedb_source: Box::new(typedb_source),
+        }
+    })?;
+    match type_ {
+        TypeEnum::Entity(entity_type) => entity_type.rename(snapshot, type_manager, new_label),
+        TypeEnum::Relation(relation_type) => relation_type.rename(snapshot, type_manager, new_label),
+        TypeEnum::Attribute(attribute_type) => attribute_type.rename(snapshot, type_manager, new_label),
+        TypeEnum::RoleType(role_type) => role_type.rename(snapshot, type_manager, new_label),
+    }
+    .map_err(|typedb_source| RedefineError::RenameType {
+        type_: label.clone(),
+        new_label: new_label.clone(),
+        typedb_source,
+    })
+}
+
 fn process_struct_redefinitions(
     snapshot: &mut impl WritableSnapshot,
     type_manager: &TypeManager,
@@ -1330,6 +1361,20 @@ typedb_error! {
             identifier: typeql::Identifier,
             source_span: Option<Span>,
         ),
+        RenameType(
+            40,
+            "Renaming '{type_}' to '{new_label}' failed.",
+            type_: Label,
+            new_label: Label,
+            typedb_source: Box<ConceptWriteError>
+        ),
+        RenameFunctionReferences(
+            41,
+            "Rewriting the functions that refer to '{type_}' to use '{new_label}' failed.",
+            type_: Label,
+            new_label: Label,
+            typedb_source: Box<FunctionError>
+        ),
     }
 }
 
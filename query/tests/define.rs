@@ -6,10 +6,11 @@
 
 use std::sync::Arc;
 
-use encoding::graph::definition::definition_key_generator::DefinitionKeyGenerator;
+use concept::{thing::object::ObjectAPI, type_::TypeDeletePolicy};
+use encoding::{graph::definition::definition_key_generator::DefinitionKeyGenerator, value::label::Label};
 use function::function_manager::FunctionManager;
 use query::query_manager::QueryManager;
-use resource::profile::CommitProfile;
+use resource::profile::{CommitProfile, StorageCounters};
 use storage::snapshot::CommittableSnapshot;
 use test_utils_concept::{load_managers, setup_concept_storage};
 use test_utils_encoding::create_core_storage;
@@ -34,3 +35,375 @@ fn basic() {
         .unwrap();
     snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
 }
+
+#[test]
+fn undefine_type_with_instances() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+    let query_manager = QueryManager::new(None);
+    let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+
+    let define_str = "define entity person;";
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let define = typeql::parse_query(define_str).unwrap().into_structure().into_schema();
+    query_manager
+        .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, define_str)
+        .unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let mut snapshot = storage.clone().open_snapshot_write();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let person_type = type_manager.get_entity_type(&snapshot, &Label::build("person", None)).unwrap().unwrap();
+    thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+    thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let undefine_str = "undefine person;";
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let undefine = typeql::parse_query(undefine_str).unwrap().into_structure().into_schema();
+    let result = query_manager.execute_schema(
+        &mut snapshot,
+        &type_manager,
+        &thing_manager,
+        &function_manager,
+        undefine,
+        undefine_str,
+    );
+    assert!(result.is_err(), "Undefining a type with instances must fail without cascading");
+    drop(snapshot);
+
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let undefine = typeql::parse_query(undefine_str).unwrap().into_structure().into_schema();
+    query_manager
+        .execute_schema_with_policy(
+            &mut snapshot,
+            &type_manager,
+            &thing_manager,
+            &function_manager,
+            undefine,
+            undefine_str,
+            TypeDeletePolicy::Cascade,
+        )
+        .unwrap();
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let snapshot = storage.clone().open_snapshot_read();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    assert!(type_manager.get_entity_type(&snapshot, &Label::build("person", None)).unwrap().is_none());
+    assert!(thing_manager.get_entities(&snapshot, StorageCounters::DISABLED).next().is_none());
+}
+
+#[test]
+fn relates_cardinality_across_role_specialisation() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+    let query_manager = QueryManager::new(None);
+    let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+
+    let define_str = r#"
+    define
+    relation parentship relates parent @card(0..);
+    relation fathership sub parentship, relates father as parent @card(0..);
+    entity person plays fathership:father;
+    "#;
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let define = typeql::parse_query(define_str).unwrap().into_structure().into_schema();
+    query_manager
+        .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, define_str)
+        .unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let mut snapshot = storage.clone().open_snapshot_write();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let person_type = type_manager.get_entity_type(&snapshot, &Label::build("person", None)).unwrap().unwrap();
+    let fathership_type =
+        type_manager.get_relation_type(&snapshot, &Label::build("fathership", None)).unwrap().unwrap();
+    let father_type =
+        type_manager.get_role_type(&snapshot, &Label::build("father", Some("fathership"))).unwrap().unwrap();
+    let fathership = thing_manager.create_relation(&mut snapshot, fathership_type).unwrap();
+    for _ in 0..2 {
+        let person = thing_manager.create_entity(&mut snapshot, person_type).unwrap();
+        fathership
+            .add_player(&mut snapshot, &thing_manager, father_type, person.into_object(), StorageCounters::DISABLED)
+            .unwrap();
+    }
+    thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    // The bound of the specialised role counts the players of its specialisations
+    let redefine_str = "define parentship relates parent @card(1..1);";
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let redefine = typeql::parse_query(redefine_str).unwrap().into_structure().into_schema();
+    query_manager
+        .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, redefine, redefine_str)
+        .unwrap();
+    assert!(thing_manager.finalise(&mut snapshot, StorageCounters::DISABLED).is_err());
+}
+
+#[test]
+fn schema_diff_converges() {
+    let define_schema = |define_str: &str| {
+        let (tmp_dir, mut storage) = create_core_storage();
+        setup_concept_storage(&mut storage);
+        let query_manager = QueryManager::new(None);
+        let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+        let mut snapshot = storage.clone().open_snapshot_schema();
+        let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+        let define = typeql::parse_query(define_str).unwrap().into_structure().into_schema();
+        query_manager
+            .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, define_str)
+            .unwrap();
+        snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+        (tmp_dir, storage)
+    };
+
+    let (_current_dir, current_storage) = define_schema(
+        r#"
+    define
+    attribute name @abstract, value string;
+    attribute nickname sub name, value string @regex("^[a-z]+$");
+    attribute age value integer;
+    entity person owns name @card(0..1), owns age, plays parentship:parent;
+    entity pet owns name;
+    entity animal;
+    entity dog sub animal;
+    relation parentship relates parent, relates child;
+    struct location: city value string;
+    fun person_names($p: person) -> { name }: match $p has name $n; return { $n };
+    fun ages() -> { age }: match $a isa age; return { $a };
+    "#,
+    );
+    let (_target_dir, target_storage) = define_schema(
+        r#"
+    define
+    attribute name @abstract, value string;
+    attribute nickname sub name, value string @regex("^[a-z]*$");
+    attribute email value string;
+    entity person @abstract, owns name @card(1..1), owns email @key, plays parentship:parent;
+    entity student sub person;
+    relation parentship relates parent @card(1..2), relates child;
+    relation fathership sub parentship, relates father as parent;
+    relation pet relates keeper;
+    entity dog sub person;
+    struct location: city value string, country value string?;
+    fun person_names($p: person) -> { nickname }: match $p has nickname $n; return { $n };
+    fun emails() -> { email }: match $e isa email; return { $e };
+    "#,
+    );
+    let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+
+    let current_snapshot = current_storage.clone().open_snapshot_read();
+    let (current_type_manager, _) = load_managers(current_storage.clone(), None);
+    let target_snapshot = target_storage.clone().open_snapshot_read();
+    let (target_type_manager, _) = load_managers(target_storage.clone(), None);
+    let mut diff =
+        current_type_manager.get_schema_diff(&current_snapshot, &target_type_manager, &target_snapshot).unwrap();
+    let target_functions = function_manager.get_function_definitions(&target_snapshot).unwrap();
+    diff.add_function_changes(
+        &function_manager.get_function_definitions(&current_snapshot).unwrap(),
+        &target_functions,
+    );
+    assert!(!diff.is_empty());
+    assert!(diff.undefine.contains(&"pet".to_owned()));
+    assert!(diff.undefine.contains(&"fun ages".to_owned()));
+    assert!(target_type_manager
+        .get_schema_diff(&target_snapshot, &target_type_manager, &target_snapshot)
+        .unwrap()
+        .is_empty());
+    drop(current_snapshot);
+
+    let query_manager = QueryManager::new(None);
+    let mut snapshot = current_storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(current_storage.clone(), None);
+    for query_str in diff.queries() {
+        let query = typeql::parse_query(&query_str).unwrap().into_structure().into_schema();
+        query_manager
+            .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, query, &query_str)
+            .unwrap_or_else(|err| panic!("Migration query failed: {query_str}\n{err:?}"));
+    }
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let current_snapshot = current_storage.clone().open_snapshot_read();
+    let (current_type_manager, _) = load_managers(current_storage.clone(), None);
+    assert_eq!(
+        current_type_manager.get_types_syntax(&current_snapshot).unwrap(),
+        target_type_manager.get_types_syntax(&target_snapshot).unwrap()
+    );
+    assert_eq!(function_manager.get_function_definitions(&current_snapshot).unwrap(), target_functions);
+    assert!(current_type_manager
+        .get_schema_diff(&current_snapshot, &target_type_manager, &target_snapshot)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn schema_syntax_can_be_reapplied() {
+    let define_schema = |define_str: &str| {
+        let (tmp_dir, mut storage) = create_core_storage();
+        setup_concept_storage(&mut storage);
+        let query_manager = QueryManager::new(None);
+        let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+        let mut snapshot = storage.clone().open_snapshot_schema();
+        let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+        let define = typeql::parse_query(define_str).unwrap().into_structure().into_schema();
+        query_manager
+            .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, define_str)
+            .unwrap_or_else(|err| panic!("Define failed: {define_str}\n{err:?}"));
+        snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+        let snapshot = storage.clone().open_snapshot_read();
+        let (type_manager, _) = load_managers(storage.clone(), None);
+        let schema = format!(
+            "define\n{}{}",
+            type_manager.get_types_syntax(&snapshot).unwrap(),
+            function_manager.get_functions_syntax(&snapshot).unwrap()
+        );
+        (tmp_dir, schema)
+    };
+
+    // struct and function names are ordered against their dependencies
+    let (_original_dir, schema) = define_schema(
+        r#"
+    define
+    entity person owns name, owns address;
+    attribute name value string;
+    attribute address value a-address;
+    struct a-address: street value string, location value z-location;
+    struct z-location: city value string;
+    fun a_people() -> { person }:
+      match $p isa person, has name $n; let $n in z_names();
+      return { $p };
+    fun z_names() -> { name }:
+      match $n isa name;
+      return { $n };
+    "#,
+    );
+    assert!(schema.find("struct z-location").unwrap() < schema.find("struct a-address").unwrap(), "{schema}");
+    assert!(schema.find("struct a-address").unwrap() < schema.find("attribute address").unwrap(), "{schema}");
+    assert!(schema.find("fun z_names").unwrap() < schema.find("fun a_people").unwrap(), "{schema}");
+
+    let (_reapplied_dir, reapplied_schema) = define_schema(&schema);
+    assert_eq!(reapplied_schema, schema);
+}
+
+#[test]
+fn rename_type_rewrites_functions() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+    let query_manager = QueryManager::new(None);
+    let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+
+    let define_str = r#"
+    define
+    entity person plays friendship:friend;
+    relation friendship relates friend;
+    fun friends_of($p: person) -> { person }:
+      match $f isa friendship, links (friend: $p, friend: $q); $q isa person;
+      return { $q };
+    fun friend_players() -> integer:
+      match $t plays friendship:friend;
+      return count;
+    "#;
+    let define = typeql::parse_query(define_str).unwrap().into_structure().into_schema();
+    query_manager
+        .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, define_str)
+        .unwrap();
+
+    let rename = |snapshot: &mut _, label: Label, new_label: Label| {
+        query_manager.rename_type(snapshot, &type_manager, &function_manager, &label, &new_label).unwrap()
+    };
+    rename(&mut snapshot, Label::build("person", None), Label::build("human", None));
+    rename(&mut snapshot, Label::build("friendship", None), Label::build("bond", None));
+    rename(&mut snapshot, Label::build_scoped("friend", "bond", None), Label::build_scoped("pal", "bond", None));
+
+    let definitions = function_manager.get_function_definitions(&snapshot).unwrap();
+    let friends_of = &definitions["friends_of"];
+    assert!(friends_of.contains("($p: human) -> { human }"), "{friends_of}");
+    assert!(friends_of.contains("$f isa bond, links (pal: $p, pal: $q); $q isa human;"), "{friends_of}");
+    let friend_players = &definitions["friend_players"];
+    assert!(friend_players.contains("$t plays bond:pal;"), "{friend_players}");
+
+    // the rewritten functions still type-check against the renamed schema
+    function_manager.finalise(&snapshot, &type_manager).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+}
+
+#[test]
+fn rename_role_rewrites_only_the_role_of_its_relation() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+    let query_manager = QueryManager::new(None);
+    let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+
+    let define_str = r#"
+    define
+    entity person plays friendship:friend, plays employment:friend;
+    relation friendship relates friend;
+    relation employment relates friend;
+    fun friends_of($p: person) -> { person }:
+      match $f isa friendship, links (friend: $p, friend: $q); $q isa person;
+      return { $q };
+    fun colleagues_of($p: person) -> { person }:
+      match $e isa employment, links (friend: $p, friend: $q); $q isa person;
+      return { $q };
+    "#;
+    let define = typeql::parse_query(define_str).unwrap().into_structure().into_schema();
+    query_manager
+        .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, define_str)
+        .unwrap();
+
+    let label = Label::build_scoped("friend", "friendship", None);
+    let new_label = Label::build_scoped("pal", "friendship", None);
+    query_manager.rename_type(&mut snapshot, &type_manager, &function_manager, &label, &new_label).unwrap();
+
+    let definitions = function_manager.get_function_definitions(&snapshot).unwrap();
+    let friends_of = &definitions["friends_of"];
+    assert!(friends_of.contains("$f isa friendship, links (pal: $p, pal: $q);"), "{friends_of}");
+    let colleagues_of = &definitions["colleagues_of"];
+    assert!(colleagues_of.contains("$e isa employment, links (friend: $p, friend: $q);"), "{colleagues_of}");
+
+    function_manager.finalise(&snapshot, &type_manager).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+}
+
+#[test]
+fn rename_role_fails_where_its_relation_is_ambiguous() {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+    let query_manager = QueryManager::new(None);
+    let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+
+    let define_str = r#"
+    define
+    entity person plays friendship:friend, plays employment:friend;
+    relation friendship relates friend;
+    relation employment relates friend;
+    fun acquaintances_of($p: person) -> { person }:
+      match $r links (friend: $p, friend: $q); $q isa person;
+      return { $q };
+    "#;
+    let define = typeql::parse_query(define_str).unwrap().into_structure().into_schema();
+    query_manager
+        .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, define_str)
+        .unwrap();
+
+    let label = Label::build_scoped("friend", "friendship", None);
+    let new_label = Label::build_scoped("pal", "friendship", None);
+    // the role may be employment:friend too, so rewriting it would change what the function matches either way
+    let err =
+        query_manager.rename_type(&mut snapshot, &type_manager, &function_manager, &label, &new_label).unwrap_err();
+    assert!(format!("{err:?}").contains("AmbiguousRoleRename"), "{err:?}");
+}
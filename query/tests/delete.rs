@@ -0,0 +1,128 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::Arc;
+
+use concept::{thing::thing_manager::ThingManager, type_::type_manager::TypeManager};
+use encoding::graph::definition::definition_key_generator::DefinitionKeyGenerator;
+use executor::{
+    pipeline::{stage::StageIterator, PipelineExecutionError},
+    ExecutionInterrupt,
+};
+use function::function_manager::FunctionManager;
+use query::query_manager::QueryManager;
+use resource::profile::CommitProfile;
+use storage::{durability_client::WALClient, snapshot::CommittableSnapshot, MVCCStorage};
+use test_utils::TempDir;
+use test_utils_concept::{load_managers, setup_concept_storage};
+use test_utils_encoding::create_core_storage;
+
+const SCHEMA: &str = r#"define
+    entity person, owns name, owns age, plays marriage:spouse, plays marriage:witness;
+    entity company, owns name, owns founded;
+    relation marriage, relates spouse @card(0..), relates witness @card(0..);
+    attribute name, value string;
+    attribute age, value integer;
+    attribute founded, value integer;
+"#;
+
+const DATA: &str = r#"insert
+    $alice isa person, has name "Alice", has age 30;
+    $bob isa person, has name "Bob", has age 40;
+    $carol isa person, has name "Carol";
+    $acme isa company, has name "Acme", has founded 1999;
+    $m isa marriage, links (spouse: $alice, spouse: $bob, witness: $carol);
+"#;
+
+struct Context {
+    _tmp_dir: TempDir,
+    storage: Arc<MVCCStorage<WALClient>>,
+    type_manager: Arc<TypeManager>,
+    thing_manager: Arc<ThingManager>,
+    function_manager: FunctionManager,
+}
+
+fn setup() -> Context {
+    let (tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let define = typeql::parse_query(SCHEMA).unwrap().into_structure().into_schema();
+    QueryManager::new(None)
+        .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, SCHEMA)
+        .unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let context = Context { _tmp_dir: tmp_dir, storage, type_manager, thing_manager, function_manager };
+    write(&context, DATA).unwrap();
+    context
+}
+
+fn write(context: &Context, query: &str) -> Result<(), Box<PipelineExecutionError>> {
+    let snapshot = context.storage.clone().open_snapshot_write();
+    let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    let pipeline = QueryManager::new(None)
+        .prepare_write_pipeline(
+            snapshot,
+            &context.type_manager,
+            context.thing_manager.clone(),
+            &context.function_manager,
+            &pipeline,
+            query,
+        )
+        .unwrap_or_else(|(_, err)| panic!("{err:?}"));
+    let (iterator, execution_context) =
+        pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).map_err(|(err, _)| err)?;
+    iterator.collect_owned()?;
+    let snapshot = Arc::into_inner(execution_context.snapshot).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+    Ok(())
+}
+
+fn count(context: &Context, query: &str) -> usize {
+    let snapshot = Arc::new(context.storage.clone().open_snapshot_read());
+    let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    let pipeline = QueryManager::new(None)
+        .prepare_read_pipeline(
+            snapshot,
+            &context.type_manager,
+            context.thing_manager.clone(),
+            &context.function_manager,
+            &pipeline,
+            query,
+        )
+        .unwrap();
+    let (iterator, _) = pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+    iterator.collect_owned().unwrap().len()
+}
+
+#[test]
+fn has_with_variable_attribute_type_is_deleted() {
+    let context = setup();
+    write(&context, r#"match $p isa person, has name "Alice", has $a; delete has $a of $p;"#).unwrap();
+    assert_eq!(count(&context, r#"match $p isa person, has name "Alice";"#), 0);
+    assert_eq!(count(&context, r#"match $p isa person, has age 30;"#), 0);
+    assert_eq!(count(&context, r#"match $p isa person, has name "Bob", has age 40;"#), 1);
+}
+
+#[test]
+fn has_of_attribute_type_not_owned_fails() {
+    let context = setup();
+    let result = write(&context, r#"match $x has name $n; $f isa founded; delete has $f of $x;"#);
+    assert!(result.is_err());
+    assert_eq!(count(&context, "match $c isa company, has founded $f;"), 1);
+}
+
+#[test]
+fn links_with_inferred_roles_are_deleted_in_every_role_played() {
+    let context = setup();
+    assert_eq!(count(&context, "match $m isa marriage, links (witness: $p);"), 1);
+    write(&context, r#"match $m isa marriage; $p isa person, has name "Carol"; delete links ($p) of $m;"#).unwrap();
+    assert_eq!(count(&context, "match $m isa marriage, links (witness: $p);"), 0);
+    assert_eq!(count(&context, "match $m isa marriage, links (spouse: $p);"), 2);
+}
@@ -0,0 +1,109 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::Arc;
+
+use compiler::annotation::{limits::CompileLimits, AnnotationError, TypeInferenceError};
+use encoding::graph::definition::definition_key_generator::DefinitionKeyGenerator;
+use function::function_manager::FunctionManager;
+use query::{error::QueryError, query_manager::QueryManager};
+use resource::profile::CommitProfile;
+use storage::snapshot::CommittableSnapshot;
+use test_utils_concept::{load_managers, setup_concept_storage};
+use test_utils_encoding::create_core_storage;
+
+const SCHEMA: &str = r#"define
+    entity person, owns name, owns age;
+    attribute name, value string;
+    attribute age, value integer;
+"#;
+
+fn prepare_with_limits(compile_limits: CompileLimits, query: &str) -> Result<(), Box<QueryError>> {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let define = typeql::parse_query(SCHEMA).unwrap().into_structure().into_schema();
+    QueryManager::new(None)
+        .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, SCHEMA)
+        .unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let query_manager = QueryManager::new(None).with_compile_limits(compile_limits);
+    let snapshot = Arc::new(storage.clone().open_snapshot_read());
+    let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    query_manager
+        .prepare_read_pipeline(snapshot, &type_manager, thing_manager, &function_manager, &pipeline, query)
+        .map(|_| ())
+}
+
+#[test]
+fn pattern_within_limits_compiles() {
+    let query = "match $p isa person, has name $n, has age $a;";
+    prepare_with_limits(CompileLimits::default(), query).unwrap();
+}
+
+#[test]
+fn pattern_size_limit_is_enforced() {
+    let limits = CompileLimits { max_pattern_constraints: 2, ..CompileLimits::default() };
+    let query = "match $p isa person, has name $n, has age $a;";
+    let err = prepare_with_limits(limits, query).unwrap_err();
+    assert!(matches!(
+        *err,
+        QueryError::Annotation { typedb_source: AnnotationError::PatternSizeLimitExceeded { limit: 2, .. }, .. }
+    ));
+}
+
+#[test]
+fn disjunction_branch_limit_is_enforced() {
+    let limits = CompileLimits { max_disjunction_branches: 1, ..CompileLimits::default() };
+    let query = "match $p isa person; { $p has name $n; } or { $p has age $a; };";
+    let err = prepare_with_limits(limits, query).unwrap_err();
+    assert!(matches!(
+        *err,
+        QueryError::Annotation { typedb_source: AnnotationError::DisjunctionBranchLimitExceeded { limit: 1, .. }, .. }
+    ));
+}
+
+#[test]
+fn disjunction_branches_multiply() {
+    // two sibling disjunctions of three branches each execute through nine combinations of branches
+    let limits = CompileLimits { max_disjunction_branches: 8, ..CompileLimits::default() };
+    let query = r#"match $p isa person;
+        { $p has name "a"; } or { $p has name "b"; } or { $p has name "c"; };
+        { $p has age 1; } or { $p has age 2; } or { $p has age 3; };"#;
+    let err = prepare_with_limits(limits, query).unwrap_err();
+    assert!(matches!(
+        *err,
+        QueryError::Annotation {
+            typedb_source: AnnotationError::DisjunctionBranchLimitExceeded { branches: 9, limit: 8 },
+            ..
+        }
+    ));
+
+    let limits = CompileLimits { max_disjunction_branches: 9, ..CompileLimits::default() };
+    prepare_with_limits(limits, query).unwrap();
+}
+
+#[test]
+fn type_combination_limit_is_enforced() {
+    // a person may own either attribute type
+    let limits = CompileLimits { max_type_combinations: 1, ..CompileLimits::default() };
+    let query = "match $p isa person, has $a;";
+    let err = prepare_with_limits(limits, query).unwrap_err();
+    assert!(matches!(
+        *err,
+        QueryError::Annotation {
+            typedb_source: AnnotationError::TypeInference {
+                typedb_source: TypeInferenceError::TypeCombinationLimitExceeded { limit: 1, .. }
+            },
+            ..
+        }
+    ));
+}
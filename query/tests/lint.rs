@@ -0,0 +1,77 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::Arc;
+
+use compiler::warning::QueryWarning;
+use encoding::graph::definition::definition_key_generator::DefinitionKeyGenerator;
+use function::function_manager::FunctionManager;
+use query::query_manager::QueryManager;
+use resource::profile::CommitProfile;
+use storage::snapshot::CommittableSnapshot;
+use test_utils_concept::{load_managers, setup_concept_storage};
+use test_utils_encoding::create_core_storage;
+
+const SCHEMA: &str = r#"define
+    entity person, owns name, owns age;
+    entity company, owns name;
+    attribute name, value string;
+    attribute age, value integer;
+"#;
+
+fn lint(query: &str) -> Vec<QueryWarning> {
+    let (_tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let define = typeql::parse_query(SCHEMA).unwrap().into_structure().into_schema();
+    QueryManager::new(None)
+        .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, SCHEMA)
+        .unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let (type_manager, _thing_manager) = load_managers(storage.clone(), None);
+    let snapshot = storage.clone().open_snapshot_read();
+    let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    QueryManager::new(None).lint(&snapshot, &type_manager, &function_manager, &pipeline, query).unwrap()
+}
+
+fn unused_variables(warnings: &[QueryWarning]) -> usize {
+    warnings.iter().filter(|warning| matches!(warning, QueryWarning::UnusedVariable { .. })).count()
+}
+
+#[test]
+fn variables_dropped_after_a_single_use_are_unused() {
+    let warnings = lint("match $p isa person, has name $n, has age $a; select $p, $a; limit 10;");
+    assert_eq!(unused_variables(&warnings), 1, "{warnings:?}");
+
+    let warnings = lint("match $p isa person, has name $n; limit 10;");
+    assert_eq!(unused_variables(&warnings), 0, "{warnings:?}");
+
+    let warnings = lint("match $p isa person, has name $n; sort $n; select $p; limit 10;");
+    assert_eq!(unused_variables(&warnings), 0, "{warnings:?}");
+}
+
+#[test]
+fn scans_without_anchor_or_limit_are_unbounded() {
+    let is_unbounded = |query| lint(query).contains(&QueryWarning::UnboundedScan);
+    assert!(is_unbounded("match $p isa person;"));
+    assert!(is_unbounded("match $p isa person, has age > 30;"));
+    assert!(!is_unbounded("match $p isa person; limit 10;"));
+    assert!(!is_unbounded(r#"match $p isa person, has name "Alice";"#));
+    assert!(!is_unbounded("match $t label person;"));
+}
+
+#[test]
+fn always_empty_branches_are_reported() {
+    let warnings = lint("match { $x isa company, has age $a; } or { $x isa person, has age $a; }; limit 10;");
+    assert!(
+        warnings.iter().any(|warning| matches!(warning, QueryWarning::UnsatisfiableBranchesPruned { count: 1 })),
+        "{warnings:?}"
+    );
+}
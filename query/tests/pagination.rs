@@ -0,0 +1,156 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::Arc;
+
+use answer::Thing;
+use concept::{thing::thing_manager::ThingManager, type_::type_manager::TypeManager};
+use encoding::graph::definition::definition_key_generator::DefinitionKeyGenerator;
+use executor::{
+    pipeline::{pipeline::PipelineError, stage::StageIterator},
+    ExecutionInterrupt,
+};
+use function::function_manager::FunctionManager;
+use itertools::Itertools;
+use query::{error::QueryError, query_manager::QueryManager};
+use resource::profile::CommitProfile;
+use storage::{durability_client::WALClient, snapshot::CommittableSnapshot, MVCCStorage};
+use test_utils::TempDir;
+use test_utils_concept::{load_managers, setup_concept_storage};
+use test_utils_encoding::create_core_storage;
+
+const SCHEMA: &str = r#"define
+    entity person, owns name;
+    attribute name, value string;
+"#;
+
+const DATA: &str = r#"insert
+    $a isa person, has name "Alice";
+    $b isa person, has name "Bob";
+    $c isa person, has name "Carol";
+    $d isa person, has name "Dan";
+    $e isa person, has name "Eve";
+"#;
+
+struct Context {
+    _tmp_dir: TempDir,
+    storage: Arc<MVCCStorage<WALClient>>,
+    type_manager: Arc<TypeManager>,
+    thing_manager: Arc<ThingManager>,
+    function_manager: FunctionManager,
+}
+
+fn setup() -> Context {
+    let (tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let define = typeql::parse_query(SCHEMA).unwrap().into_structure().into_schema();
+    QueryManager::new(None)
+        .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, SCHEMA)
+        .unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let context = Context { _tmp_dir: tmp_dir, storage, type_manager, thing_manager, function_manager };
+    write(&context, DATA);
+    context
+}
+
+fn write(context: &Context, query: &str) {
+    let snapshot = context.storage.clone().open_snapshot_write();
+    let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    let pipeline = QueryManager::new(None)
+        .prepare_write_pipeline(
+            snapshot,
+            &context.type_manager,
+            context.thing_manager.clone(),
+            &context.function_manager,
+            &pipeline,
+            query,
+        )
+        .unwrap_or_else(|(_, err)| panic!("{err:?}"));
+    let (iterator, execution_context) = pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+    iterator.collect_owned().unwrap();
+    let snapshot = Arc::into_inner(execution_context.snapshot).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+}
+
+/// Reads the instances `$p` takes in the page of the query that follows `after`, or in its first page
+fn page(context: &Context, query: &str, after: Option<Thing>) -> Result<Vec<Thing>, Box<QueryError>> {
+    let snapshot = Arc::new(context.storage.clone().open_snapshot_read());
+    let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    let query_manager = QueryManager::new(None);
+    let (type_manager, thing_manager, function_manager) =
+        (&context.type_manager, context.thing_manager.clone(), &context.function_manager);
+    let pipeline = match after {
+        None => query_manager.prepare_read_pipeline(
+            snapshot,
+            type_manager,
+            thing_manager,
+            function_manager,
+            &pipeline,
+            query,
+        ),
+        Some(after) => query_manager.prepare_read_pipeline_after(
+            snapshot,
+            type_manager,
+            thing_manager,
+            function_manager,
+            &pipeline,
+            query,
+            "p",
+            after,
+        ),
+    }?;
+    let position = pipeline.rows_positions().unwrap()["p"];
+    let (iterator, _) = pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+    Ok(iterator.collect_owned().unwrap().iter().map(|row| row.get(position).as_thing().clone()).collect())
+}
+
+fn all_pages(context: &Context, query: &str) -> Vec<Vec<Thing>> {
+    let mut pages = vec![page(context, query, None).unwrap()];
+    while let Some(last) = pages.last().unwrap().last().cloned() {
+        pages.push(page(context, query, Some(last)).unwrap());
+    }
+    pages
+}
+
+#[test]
+fn pages_resume_after_the_last_instance() {
+    let context = setup();
+    let pages = all_pages(&context, "match $p isa person; limit 2;");
+    assert_eq!(pages.iter().map(|page| page.len()).collect_vec(), vec![2, 2, 1, 0]);
+
+    // the pages follow on from each other across every boundary, and together answer every person once
+    let instances = pages.concat();
+    assert!(instances.iter().tuple_windows().all(|(previous, next)| previous < next), "{instances:?}");
+    assert_eq!(instances, page(&context, "match $p isa person;", None).unwrap());
+}
+
+#[test]
+fn resumed_pages_hold_only_later_instances_whichever_constraint_binds_the_variable() {
+    let context = setup();
+    let persons = page(&context, "match $p isa person;", None).unwrap();
+    // the plan may bind the person through its name, in which case its rows are filtered rather than resumed
+    let query = r#"match $p isa person, has name $n; $n like "^[A-E]";"#;
+    let resumed = page(&context, query, Some(persons[1].clone())).unwrap();
+    assert_eq!(resumed.into_iter().sorted().collect_vec(), persons[2..]);
+}
+
+#[test]
+fn resuming_after_an_unknown_variable_fails() {
+    let context = setup();
+    let after = page(&context, "match $p isa person; limit 1;", None).unwrap().remove(0);
+    let err = page(&context, "match $q isa person; limit 1;", Some(after)).unwrap_err();
+    match *err {
+        QueryError::Pipeline { typedb_source, .. } => {
+            assert!(matches!(*typedb_source, PipelineError::StartAfterVariableNotMatched { .. }))
+        }
+        err => panic!("{err:?}"),
+    }
+}
@@ -0,0 +1,171 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::Arc;
+
+use concept::{thing::thing_manager::ThingManager, type_::type_manager::TypeManager};
+use encoding::graph::definition::definition_key_generator::DefinitionKeyGenerator;
+use executor::{pipeline::stage::StageIterator, ExecutionInterrupt};
+use function::function_manager::FunctionManager;
+use query::query_manager::QueryManager;
+use resource::profile::{CommitProfile, QueryProfile};
+use storage::{durability_client::WALClient, snapshot::CommittableSnapshot, MVCCStorage};
+use test_utils::TempDir;
+use test_utils_concept::{load_managers, setup_concept_storage};
+use test_utils_encoding::create_core_storage;
+
+const SCHEMA: &str = r#"define
+    entity person, owns name @card(0..), owns age;
+    attribute name, value string;
+    attribute age, value integer;
+"#;
+
+const DATA: &str = r#"insert
+    $x isa person, has name "Alice", has age 10;
+    $y isa person, has name "Bob", has age 11;
+    $z isa person, has name "Charlie";
+"#;
+
+struct Context {
+    _tmp_dir: TempDir,
+    storage: Arc<MVCCStorage<WALClient>>,
+    type_manager: Arc<TypeManager>,
+    thing_manager: Arc<ThingManager>,
+    function_manager: FunctionManager,
+}
+
+fn setup() -> Context {
+    let (tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let define = typeql::parse_query(SCHEMA).unwrap().into_structure().into_schema();
+    QueryManager::new(None)
+        .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, SCHEMA)
+        .unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let context = Context { _tmp_dir: tmp_dir, storage, type_manager, thing_manager, function_manager };
+    let profile = write(&context, DATA, false);
+    assert!(!profile.is_enabled());
+    context
+}
+
+fn write(context: &Context, query: &str, is_profiled: bool) -> Arc<QueryProfile> {
+    let snapshot = context.storage.clone().open_snapshot_write();
+    let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    let pipeline = QueryManager::new(None)
+        .prepare_write_pipeline_profiled(
+            snapshot,
+            &context.type_manager,
+            context.thing_manager.clone(),
+            &context.function_manager,
+            &pipeline,
+            query,
+            is_profiled,
+        )
+        .unwrap_or_else(|(_, err)| panic!("{err:?}"));
+    let (iterator, execution_context) = pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+    iterator.collect_owned().unwrap();
+    let snapshot = Arc::into_inner(execution_context.snapshot).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+    execution_context.profile
+}
+
+fn read(context: &Context, query: &str, is_profiled: bool) -> (usize, Arc<QueryProfile>) {
+    let snapshot = Arc::new(context.storage.clone().open_snapshot_read());
+    let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    let pipeline = QueryManager::new(None)
+        .prepare_read_pipeline_profiled(
+            snapshot,
+            &context.type_manager,
+            context.thing_manager.clone(),
+            &context.function_manager,
+            &pipeline,
+            query,
+            is_profiled,
+        )
+        .unwrap();
+    let (iterator, execution_context) = pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+    let batch = iterator.collect_owned().unwrap();
+    (batch.len(), execution_context.profile)
+}
+
+#[test]
+fn profiled_read_reports_stats() {
+    let context = setup();
+    let (rows, profile) = read(&context, "match $p isa person, has name $n;", true);
+    assert_eq!(rows, 3);
+    assert!(profile.is_enabled());
+    assert!(profile.compilation_micros() > 0.0);
+    assert!(profile.execution_micros() > 0.0);
+    assert!(profile.storage_reads() > 0);
+    assert!(profile.fingerprint().is_some());
+}
+
+#[test]
+fn unprofiled_read_reports_no_measurements() {
+    let context = setup();
+    let (rows, profile) = read(&context, "match $p isa person, has name $n;", false);
+    assert_eq!(rows, 3);
+    assert!(!profile.is_enabled());
+    assert_eq!(profile.execution_micros(), 0.0);
+    assert_eq!(profile.storage_reads(), 0);
+}
+
+#[test]
+fn profiled_insert_skips_empty_optional_inputs() {
+    let context = setup();
+    let profile =
+        write(&context, "match $p isa person; try { $p has age $a; }; insert $q isa person, has age $a;", true);
+    assert!(profile.is_enabled());
+    let (rows, _) = read(&context, "match $p isa person;", false);
+    assert_eq!(rows, 6);
+    let (rows, _) = read(&context, "match $p isa person, has age $a;", false);
+    assert_eq!(rows, 4);
+}
+
+#[test]
+fn profiled_stages_report_their_own_storage_reads() {
+    let context = setup();
+    let (rows, profile) = read(&context, "match $p isa person, has name $n; sort $n; match $p has age $a;", true);
+    assert_eq!(rows, 2);
+    let stage_reads = profile.stage_storage_reads();
+    assert!(stage_reads.len() >= 2, "{stage_reads:?}");
+    assert_eq!(stage_reads.iter().map(|(_, reads)| reads).sum::<u64>(), profile.storage_reads());
+    // each match reads storage for itself
+    let match_reads = stage_reads.iter().filter(|(description, reads)| description != "Sort" && *reads > 0);
+    assert!(match_reads.count() >= 2, "{stage_reads:?}");
+}
+
+#[test]
+fn profiled_fetch_reports_storage_reads() {
+    let context = setup();
+    let query = r#"match $p isa person; fetch { "names": [$p.name], "age": $p.age };"#;
+    let snapshot = Arc::new(context.storage.clone().open_snapshot_read());
+    let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    let pipeline = QueryManager::new(None)
+        .prepare_read_pipeline_profiled(
+            snapshot,
+            &context.type_manager,
+            context.thing_manager.clone(),
+            &context.function_manager,
+            &pipeline,
+            query,
+            true,
+        )
+        .unwrap();
+    let (iterator, execution_context) =
+        pipeline.into_documents_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+    assert_eq!(iterator.map(Result::unwrap).count(), 3);
+
+    let stage_reads = execution_context.profile.stage_storage_reads();
+    let fetch_reads = stage_reads.iter().find(|(description, _)| description == "Fetch").map(|(_, reads)| *reads);
+    assert!(fetch_reads.is_some_and(|reads| reads > 0), "{stage_reads:?}");
+    assert_eq!(stage_reads.iter().map(|(_, reads)| reads).sum::<u64>(), execution_context.profile.storage_reads());
+}
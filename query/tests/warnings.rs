@@ -0,0 +1,146 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::Arc;
+
+use compiler::warning::QueryWarning;
+use concept::{thing::thing_manager::ThingManager, type_::type_manager::TypeManager};
+use encoding::graph::definition::definition_key_generator::DefinitionKeyGenerator;
+use executor::{pipeline::stage::StageIterator, ExecutionInterrupt};
+use function::function_manager::FunctionManager;
+use query::query_manager::QueryManager;
+use resource::profile::CommitProfile;
+use storage::{durability_client::WALClient, snapshot::CommittableSnapshot, MVCCStorage};
+use test_utils::TempDir;
+use test_utils_concept::{load_managers, setup_concept_storage};
+use test_utils_encoding::create_core_storage;
+
+const SCHEMA: &str = r#"define
+    entity person, owns name, owns age;
+    attribute name, value string;
+    attribute age, value integer;
+"#;
+
+struct Context {
+    _tmp_dir: TempDir,
+    storage: Arc<MVCCStorage<WALClient>>,
+    type_manager: Arc<TypeManager>,
+    thing_manager: Arc<ThingManager>,
+    function_manager: FunctionManager,
+}
+
+fn setup() -> Context {
+    let (tmp_dir, mut storage) = create_core_storage();
+    setup_concept_storage(&mut storage);
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let function_manager = FunctionManager::new(Arc::new(DefinitionKeyGenerator::new()), None);
+    let mut snapshot = storage.clone().open_snapshot_schema();
+    let define = typeql::parse_query(SCHEMA).unwrap().into_structure().into_schema();
+    QueryManager::new(None)
+        .execute_schema(&mut snapshot, &type_manager, &thing_manager, &function_manager, define, SCHEMA)
+        .unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+
+    let (type_manager, thing_manager) = load_managers(storage.clone(), None);
+    let context = Context { _tmp_dir: tmp_dir, storage, type_manager, thing_manager, function_manager };
+    write(&context, r#"insert $x isa person, has name "Alice", has age 10; $y isa person, has name "Bob";"#);
+    context
+}
+
+fn write(context: &Context, query: &str) -> Vec<QueryWarning> {
+    let snapshot = context.storage.clone().open_snapshot_write();
+    let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    let pipeline = QueryManager::new(None)
+        .prepare_write_pipeline(
+            snapshot,
+            &context.type_manager,
+            context.thing_manager.clone(),
+            &context.function_manager,
+            &pipeline,
+            query,
+        )
+        .unwrap_or_else(|(_, err)| panic!("{err:?}"));
+    let mut warnings = pipeline.warnings().to_vec();
+    let (iterator, execution_context) = pipeline.into_rows_iterator(ExecutionInterrupt::new_uninterruptible()).unwrap();
+    iterator.collect_owned().unwrap();
+    warnings.extend(execution_context.warnings.warnings());
+    let snapshot = Arc::into_inner(execution_context.snapshot).unwrap();
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+    warnings
+}
+
+fn compilation_warnings(context: &Context, query: &str) -> Vec<QueryWarning> {
+    let snapshot = Arc::new(context.storage.clone().open_snapshot_read());
+    let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+    let pipeline = QueryManager::new(None)
+        .prepare_read_pipeline(
+            snapshot,
+            &context.type_manager,
+            context.thing_manager.clone(),
+            &context.function_manager,
+            &pipeline,
+            query,
+        )
+        .unwrap();
+    pipeline.warnings().to_vec()
+}
+
+fn implicit_casts(warnings: &[QueryWarning]) -> Option<&QueryWarning> {
+    warnings.iter().find(|warning| matches!(warning, QueryWarning::ImplicitCastsApplied { .. }))
+}
+
+#[test]
+fn casts_to_double_are_lossy() {
+    let context = setup();
+    let warnings = compilation_warnings(&context, "match $p isa person, has age $a; let $x = $a + 1.5;");
+    let warning = implicit_casts(&warnings).unwrap();
+    assert_eq!(warning, &QueryWarning::ImplicitCastsApplied { count: 1, lossy_count: 1 });
+    assert!(warning.to_string().contains("may lose precision"));
+}
+
+#[test]
+fn casts_from_integer_to_decimal_are_exact() {
+    let context = setup();
+    let warnings = compilation_warnings(&context, "match $p isa person, has age $a; let $x = $a + 1.5dec;");
+    let warning = implicit_casts(&warnings).unwrap();
+    assert_eq!(warning, &QueryWarning::ImplicitCastsApplied { count: 1, lossy_count: 0 });
+    assert!(!warning.to_string().contains("precision"));
+}
+
+#[test]
+fn queries_without_casts_have_no_cast_warning() {
+    let context = setup();
+    let warnings = compilation_warnings(&context, "match $p isa person, has age $a; let $x = $a + 1;");
+    assert!(implicit_casts(&warnings).is_none(), "{warnings:?}");
+}
+
+#[test]
+fn writes_skipped_for_empty_optional_variables_are_reported() {
+    let context = setup();
+    let warnings = write(&context, "match $p isa person; try { $p has age $a; }; insert $q isa person, has age $a;");
+    assert!(warnings.contains(&QueryWarning::WritesSkipped { count: 1 }), "{warnings:?}");
+
+    let warnings = write(&context, "match $p isa person, has age $a; insert $q isa person, has age $a;");
+    assert!(!warnings.iter().any(|warning| matches!(warning, QueryWarning::WritesSkipped { .. })), "{warnings:?}");
+}
+
+#[test]
+fn comparisons_between_incomparable_value_types_are_reported() {
+    let context = setup();
+    let warnings = compilation_warnings(&context, "match $p isa person, has name $n; $n > 10;");
+    let warning = warnings
+        .iter()
+        .find(|warning| matches!(warning, QueryWarning::IncomparableComparison { .. }))
+        .unwrap_or_else(|| panic!("{warnings:?}"));
+    let QueryWarning::IncomparableComparison { comparison, lhs_value_types, rhs_value_types } = warning else {
+        unreachable!()
+    };
+    assert!(comparison.ends_with("> 10"), "{comparison}");
+    assert_eq!((lhs_value_types.as_str(), rhs_value_types.as_str()), ("string", "integer"));
+
+    let warnings = compilation_warnings(&context, "match $p isa person, has age $a; $a > 10.5;");
+    assert!(!warnings.iter().any(|warning| matches!(warning, QueryWarning::IncomparableComparison { .. })));
+}
@@ -12,7 +12,7 @@ use concept::{
         annotation::{AnnotationCategory, AnnotationError},
         attribute_type::AttributeTypeAnnotation,
         type_manager::TypeManager,
-        Capability, KindAPI, Ordering, OwnerAPI, PlayerAPI, TypeAPI,
+        Capability, KindAPI, Ordering, OwnerAPI, PlayerAPI, TypeAPI, TypeDeletePolicy,
     },
 };
 use encoding::{
@@ -22,6 +22,7 @@ use encoding::{
 use error::typedb_error;
 use function::{function_manager::FunctionManager, FunctionError};
 use ir::{translation::tokens::translate_annotation_category, LiteralParseError};
+use resource::profile::StorageCounters;
 use storage::snapshot::{ReadableSnapshot, WritableSnapshot};
 use typeql::{
     common::{error::TypeQLError, token::Keyword, Span, Spanned},
@@ -68,13 +69,29 @@ pub(crate) fn execute(
     thing_manager: &ThingManager,
     function_manager: &FunctionManager,
     undefine: Undefine,
+    type_delete_policy: TypeDeletePolicy,
+    storage_counters: StorageCounters,
 ) -> Result<(), UndefineError> {
     process_function_undefinitions(snapshot, function_manager, &undefine.undefinables)?;
     process_specialise_undefinitions(snapshot, type_manager, thing_manager, &undefine.undefinables)?;
     process_capability_annotation_undefinitions(snapshot, type_manager, thing_manager, &undefine.undefinables)?;
-    process_type_capability_undefinitions(snapshot, type_manager, thing_manager, &undefine.undefinables)?;
+    process_type_capability_undefinitions(
+        snapshot,
+        type_manager,
+        thing_manager,
+        &undefine.undefinables,
+        type_delete_policy,
+        storage_counters.clone(),
+    )?;
     process_type_annotation_undefinitions(snapshot, type_manager, &undefine.undefinables)?;
-    process_type_undefinitions(snapshot, type_manager, thing_manager, &undefine.undefinables)?;
+    process_type_undefinitions(
+        snapshot,
+        type_manager,
+        thing_manager,
+        &undefine.undefinables,
+        type_delete_policy,
+        storage_counters,
+    )?;
     process_struct_undefinitions(snapshot, type_manager, thing_manager, &undefine.undefinables)?;
     Ok(())
 }
@@ -116,9 +133,19 @@ fn process_type_capability_undefinitions(
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
     undefinables: &[Undefinable],
+    type_delete_policy: TypeDeletePolicy,
+    storage_counters: StorageCounters,
 ) -> Result<(), UndefineError> {
-    filter_variants!(Undefinable::CapabilityType : undefinables)
-        .try_for_each(|capability| undefine_type_capability(snapshot, type_manager, thing_manager, capability))?;
+    filter_variants!(Undefinable::CapabilityType : undefinables).try_for_each(|capability| {
+        undefine_type_capability(
+            snapshot,
+            type_manager,
+            thing_manager,
+            capability,
+            type_delete_policy,
+            storage_counters.clone(),
+        )
+    })?;
     Ok(())
 }
 
@@ -137,9 +164,12 @@ fn process_type_undefinitions(
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
     undefinables: &[Undefinable],
+    type_delete_policy: TypeDeletePolicy,
+    storage_counters: StorageCounters,
 ) -> Result<(), UndefineError> {
-    filter_variants!(Undefinable::Type : undefinables)
-        .try_for_each(|type_| undefine_type(snapshot, type_manager, thing_manager, type_))?;
+    filter_variants!(Undefinable::Type : undefinables).try_for_each(|type_| {
+        undefine_type(snapshot, type_manager, thing_manager, type_, type_delete_policy, storage_counters.clone())
+    })?;
     Ok(())
 }
 
@@ -436,6 +466,8 @@ fn undefine_type_capability(
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
     capability_undefinable: &CapabilityType,
+    type_delete_policy: TypeDeletePolicy,
+    storage_counters: StorageCounters,
 ) -> Result<(), UndefineError> {
     let label = Label::parse_from(
         checked_identifier(&capability_undefinable.type_.ident)?,
@@ -489,6 +521,8 @@ fn undefine_type_capability(
             &label,
             relates,
             capability_undefinable,
+            type_delete_policy,
+            storage_counters,
         ),
         CapabilityBase::ValueType(value_type) => undefine_type_capability_value_type(
             snapshot,
@@ -676,6 +710,8 @@ fn undefine_type_capability_relates(
     type_label: &Label,
     relates: &TypeQLRelates,
     capability_undefinable: &CapabilityType,
+    type_delete_policy: TypeDeletePolicy,
+    storage_counters: StorageCounters,
 ) -> Result<(), UndefineError> {
     let (role_label, ordering) = type_ref_to_label_and_ordering(type_label, &relates.related)
         .map_err(|typedb_source| UndefineError::DefinitionResolution { typedb_source })?;
@@ -699,11 +735,13 @@ fn undefine_type_capability_relates(
     .map_err(|source| UndefineError::UnexpectedConceptRead { typedb_source: source })?;
     match definition_status {
         DefinableStatus::ExistsSame(None) => unreachable!("Expected existing relates definition"),
-        DefinableStatus::ExistsSame(Some((existing_relates, _))) => {
-            existing_relates.role().delete(snapshot, type_manager, thing_manager).map_err(|source| {
-                UndefineError::DeleteRoleTypeError { source_span: relates.span(), typedb_source: source }
-            })
-        }
+        DefinableStatus::ExistsSame(Some((existing_relates, _))) => existing_relates
+            .role()
+            .delete_with_policy(snapshot, type_manager, thing_manager, type_delete_policy, storage_counters)
+            .map_err(|source| UndefineError::DeleteRoleTypeError {
+                source_span: relates.span(),
+                typedb_source: source,
+            }),
         DefinableStatus::DoesNotExist => Err(UndefineError::RelatesNotDefined {
             type_: type_label.clone(),
             key: Keyword::Relates,
@@ -841,15 +879,31 @@ fn undefine_type(
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
     label_undefinable: &TypeQLLabel,
+    type_delete_policy: TypeDeletePolicy,
+    storage_counters: StorageCounters,
 ) -> Result<(), UndefineError> {
     let label = Label::parse_from(checked_identifier(&label_undefinable.ident)?, label_undefinable.span());
     let type_ = resolve_typeql_type(snapshot, type_manager, &label)
         .map_err(|source| UndefineError::DefinitionResolution { typedb_source: source })?;
 
     match type_ {
-        TypeEnum::Entity(entity_type) => entity_type.delete(snapshot, type_manager, thing_manager),
-        TypeEnum::Relation(relation_type) => relation_type.delete(snapshot, type_manager, thing_manager),
-        TypeEnum::Attribute(attribute_type) => attribute_type.delete(snapshot, type_manager, thing_manager),
+        TypeEnum::Entity(entity_type) => {
+            entity_type.delete_with_policy(snapshot, type_manager, thing_manager, type_delete_policy, storage_counters)
+        }
+        TypeEnum::Relation(relation_type) => relation_type.delete_with_policy(
+            snapshot,
+            type_manager,
+            thing_manager,
+            type_delete_policy,
+            storage_counters,
+        ),
+        TypeEnum::Attribute(attribute_type) => attribute_type.delete_with_policy(
+            snapshot,
+            type_manager,
+            thing_manager,
+            type_delete_policy,
+            storage_counters,
+        ),
         TypeEnum::RoleType(_) => unreachable!("Role undefinition is processed through relates undefinition"),
     }
     .map_err(|err| UndefineError::TypeDeleteError {
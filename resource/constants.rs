@@ -46,20 +46,50 @@ pub mod server {
 
     // TODO: Maybe we start moving these options to separate crates?
     pub const DEFAULT_PREFETCH_SIZE: usize = 32;
+    pub const DEFAULT_ANSWER_BATCH_SIZE: usize = 1_000;
+    pub const DEFAULT_GRPC_ANSWER_WINDOW: usize = 4 * DEFAULT_PREFETCH_SIZE;
     pub const DEFAULT_SCHEMA_LOCK_ACQUIRE_TIMEOUT_MILLIS: u64 = Duration::from_secs(10).as_millis() as u64;
     pub const DEFAULT_TRANSACTION_TIMEOUT_MILLIS: u64 = Duration::from_secs(5 * SECONDS_IN_MINUTE).as_millis() as u64;
     pub const DEFAULT_TRANSACTION_PARALLEL: bool = true;
+    pub const DEFAULT_CASCADE_TYPE_DELETION: bool = false;
+    pub const DEFAULT_DEFER_VALIDATION: bool = false;
+    pub const DEFAULT_STATISTICS_UPDATES: bool = true;
     pub const DEFAULT_INCLUDE_INSTANCE_TYPES: bool = true;
     pub const DEFAULT_INCLUDE_INSTANCE_TYPES_FETCH: bool = false;
+    pub const DEFAULT_INCLUDE_INSTANCE_IIDS: bool = true;
+    pub const DEFAULT_INCLUDE_VALUE_TYPES: bool = true;
     pub const DEFAULT_ANSWER_COUNT_LIMIT_GRPC: Option<usize> = None;
     pub const DEFAULT_ANSWER_COUNT_LIMIT_HTTP: Option<usize> = Some(10_000);
     pub const DEFAULT_INCLUDE_STRUCTURE_HTTP: bool = true; // True for studio backwards compatibility
     pub const DEFAULT_INCLUDE_STRUCTURE_GRPC: bool = false;
+    pub const DEFAULT_OMIT_NULL_COLUMNS: bool = false;
+    pub const DEFAULT_INCLUDE_QUERY_STATS: bool = false;
+    pub const DEFAULT_DRY_RUN: bool = false;
 
     pub const PERF_COUNTERS_ENABLED: bool = true;
 
     pub const MONITORING_DEFAULT_PORT: u16 = 4104;
 
+    pub const DEFAULT_HTTP_QUERY_BODY_LIMIT_BYTES: usize = 4 * 1024 * 1024;
+    pub const DEFAULT_HTTP_BODY_LIMIT_BYTES: usize = 1024 * 1024;
+
+    pub const DEFAULT_HTTP_RATE_LIMITS_ENABLED: bool = false;
+    pub const DEFAULT_HTTP_AUTH_RATE_LIMIT_PER_SECOND: u32 = 5;
+    pub const DEFAULT_HTTP_AUTH_RATE_LIMIT_BURST: u32 = 10;
+    pub const DEFAULT_HTTP_QUERY_RATE_LIMIT_PER_SECOND: u32 = 100;
+    pub const DEFAULT_HTTP_QUERY_RATE_LIMIT_BURST: u32 = 200;
+    pub const DEFAULT_HTTP_ADMIN_RATE_LIMIT_PER_SECOND: u32 = 20;
+    pub const DEFAULT_HTTP_ADMIN_RATE_LIMIT_BURST: u32 = 40;
+
+    pub const COMMIT_TRIGGER_WORKERS: usize = 4;
+    pub const COMMIT_TRIGGER_QUEUE_CAPACITY: usize = 1024;
+
+    pub const DEFAULT_REPLICATION_POLL_INTERVAL_MILLIS: u64 = 1000;
+    pub const REPLICATION_BATCH_MAX_RECORDS: usize = 1024;
+
+    pub const DEFAULT_CLUSTER_ELECTION_TIMEOUT_MILLIS: u64 = 1500;
+    pub const DEFAULT_CLUSTER_HEARTBEAT_INTERVAL_MILLIS: u64 = 300;
+
     pub const SERVER_ID_FILE_NAME: &str = concat!(system_file_prefix!(), "server_id");
     pub const SERVER_ID_LENGTH: u64 = 16;
     pub const SERVER_ID_ALPHABET: [char; 36] = [
@@ -78,6 +108,8 @@ pub mod server {
         Duration::from_secs(DEFAULT_AUTHENTICATION_TOKEN_EXPIRATION_SECONDS);
 
     pub const DATABASE_METRICS_UPDATE_INTERVAL: Duration = Duration::from_secs(10 * SECONDS_IN_MINUTE);
+    pub const ORPHANED_ATTRIBUTE_CLEANUP_INTERVAL: Duration = Duration::from_secs(SECONDS_IN_HOUR);
+    pub const EXPIRED_INSTANCE_CLEANUP_INTERVAL: Duration = Duration::from_secs(SECONDS_IN_MINUTE);
 
     pub const DEFAULT_USER_NAME: &str = "admin";
     pub const DEFAULT_USER_PASSWORD: &str = "password";
@@ -99,6 +131,13 @@ pub mod database {
     pub const STATISTICS_DURABLE_WRITE_SEQ_NUMBERS: usize = 1_000;
     pub const STATISTICS_UPDATE_INTERVAL: Duration = Duration::from_millis(50);
     pub const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(60);
+    // Commit-time statistics deltas are periodically corrected by recounting a few types at a time
+    pub const STATISTICS_SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+    pub const STATISTICS_SAMPLE_TYPES: usize = 8;
+    // Write transactions are delayed once a database's storage exceeds this fraction of its quota
+    pub const STORAGE_QUOTA_THROTTLE_FRACTION: f64 = 0.9;
+    pub const STORAGE_QUOTA_THROTTLE_DELAY: Duration = Duration::from_millis(100);
+    pub const CLUSTER_COMMIT_QUORUM_TIMEOUT: Duration = Duration::from_secs(10);
 
     #[macro_export]
     macro_rules! internal_database_prefix {
@@ -107,11 +146,41 @@ pub mod database {
         };
     }
     pub const INTERNAL_DATABASE_PREFIX: &str = internal_database_prefix!();
+    pub const CLUSTER_STATE_FILE_NAME: &str = concat!(internal_database_prefix!(), "cluster_state");
+}
+
+pub mod memory {
+    use std::time::Duration;
+
+    // Shares of the server memory limit given to each component's budget, in percent
+    pub const TYPE_CACHE_BUDGET_PERCENT: u64 = 10;
+    pub const QUERY_PLAN_CACHE_BUDGET_PERCENT: u64 = 20;
+    pub const EXECUTOR_BUFFERS_BUDGET_PERCENT: u64 = 70;
+    // Components are under pressure beyond this share of their budget, and are asked to shrink back to it
+    pub const MEMORY_PRESSURE_HIGH_PERCENT: u64 = 80;
+    pub const MEMORY_REBALANCE_INTERVAL: Duration = Duration::from_secs(1);
+    // Rough footprints of cached entries, which are too intricate to measure on every insert
+    pub const TYPE_CACHE_BYTES_PER_TYPE_ESTIMATE: u64 = 4 * 1024;
+    pub const QUERY_PLAN_BYTES_ESTIMATE: u64 = 64 * 1024;
 }
 
 pub mod concept {
-    // TODO: this should be parametrised into the database options? Would be great to have it be changable at runtime!
-    pub const RELATION_INDEX_THRESHOLD: u64 = 5;
+    // Used until a database sets its own relation index threshold
+    pub const DEFAULT_RELATION_INDEX_THRESHOLD: u64 = 5;
+    // Used until a database sets its own history retention, in commits behind the latest
+    pub const DEFAULT_HISTORY_RETENTION: u64 = 10_000;
+    // Smallest number of modified concepts worth validating on a separate thread at commit
+    pub const COMMIT_VALIDATION_SHARD_SIZE_MIN: usize = 1024;
+}
+
+pub mod compiler {
+    // Queries exceeding these are rejected before they can exhaust memory in type inference
+    pub const MAX_PATTERN_CONSTRAINTS: usize = 1_000;
+    pub const MAX_DISJUNCTION_BRANCHES: usize = 256;
+    pub const MAX_TYPE_COMBINATIONS: usize = 1_000_000;
+    pub const TYPE_INFERENCE_CACHE_SIZE: u64 = 1_000;
+    // Values collected by a list reducer per group, beyond which the query fails
+    pub const MAX_REDUCE_LIST_SIZE: usize = 10_000;
 }
 
 pub mod traversal {
@@ -133,6 +202,9 @@ pub mod storage {
     pub const COMMIT_WAIT_FOR_FSYNC: bool = true;
 
     pub const ROCKSDB_CACHE_SIZE_MB: u64 = 1024;
+    pub const ROCKSDB_VERIFY_CHECKSUMS: bool = true;
+
+    pub const FORMAT_UPGRADE_BATCH_SIZE: usize = 10_000;
 }
 
 pub mod encoding {
@@ -0,0 +1,416 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+// Caches and buffers are accounted against a single, server-wide memory limit, split into per-component budgets.
+// Caches register as consumers, which report their own usage and are asked to shrink when over budget;
+// short-lived buffers hold reservations instead, and read the pressure on their component to size themselves.
+
+use std::{
+    array, fmt,
+    sync::{
+        atomic::{AtomicU64, AtomicU8, Ordering},
+        Arc, Mutex, Weak,
+    },
+};
+
+use crate::constants::memory::{
+    EXECUTOR_BUFFERS_BUDGET_PERCENT, MEMORY_PRESSURE_HIGH_PERCENT, QUERY_PLAN_CACHE_BUDGET_PERCENT,
+    TYPE_CACHE_BUDGET_PERCENT,
+};
+
+pub static MEMORY_MANAGER: MemoryManager = MemoryManager::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryComponent {
+    TypeCache,
+    QueryPlanCache,
+    ExecutorBuffers,
+}
+
+impl MemoryComponent {
+    pub const ALL: [Self; 3] = [Self::TypeCache, Self::QueryPlanCache, Self::ExecutorBuffers];
+
+    const fn index(self) -> usize {
+        match self {
+            Self::TypeCache => 0,
+            Self::QueryPlanCache => 1,
+            Self::ExecutorBuffers => 2,
+        }
+    }
+
+    const fn budget_percent(self) -> u64 {
+        match self {
+            Self::TypeCache => TYPE_CACHE_BUDGET_PERCENT,
+            Self::QueryPlanCache => QUERY_PLAN_CACHE_BUDGET_PERCENT,
+            Self::ExecutorBuffers => EXECUTOR_BUFFERS_BUDGET_PERCENT,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::TypeCache => "type_cache",
+            Self::QueryPlanCache => "query_plan_cache",
+            Self::ExecutorBuffers => "executor_buffers",
+        }
+    }
+}
+
+impl fmt::Display for MemoryComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MemoryPressure {
+    Normal,
+    High,
+    Exceeded,
+}
+
+impl MemoryPressure {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Normal,
+            1 => Self::High,
+            _ => Self::Exceeded,
+        }
+    }
+
+    fn of(usage: u64, budget: Option<u64>) -> Self {
+        match budget {
+            None => Self::Normal,
+            Some(budget) if usage > budget => Self::Exceeded,
+            Some(budget) if usage > budget / 100 * MEMORY_PRESSURE_HIGH_PERCENT => Self::High,
+            Some(_) => Self::Normal,
+        }
+    }
+}
+
+/// A cache whose memory is accounted for by the memory manager.
+pub trait MemoryConsumer: Send + Sync {
+    fn component(&self) -> MemoryComponent;
+
+    fn memory_usage(&self) -> u64;
+
+    /// Called when the component is over its pressure threshold, to release memory down to about `target_bytes`.
+    /// Consumers that cannot shrink may ignore it, leaving other components' consumers under more pressure.
+    fn relieve_pressure(&self, pressure: MemoryPressure, target_bytes: u64);
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentMemory {
+    pub component: MemoryComponent,
+    pub usage: u64,
+    pub budget: Option<u64>,
+    pub pressure: MemoryPressure,
+}
+
+pub struct MemoryManager {
+    // zero when unlimited
+    limit: AtomicU64,
+    reserved: [AtomicU64; MemoryComponent::ALL.len()],
+    pressure: [AtomicU8; MemoryComponent::ALL.len()],
+    consumers: Mutex<Vec<Weak<dyn MemoryConsumer>>>,
+}
+
+impl MemoryManager {
+    const fn new() -> Self {
+        Self {
+            limit: AtomicU64::new(0),
+            reserved: [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)],
+            pressure: [AtomicU8::new(0), AtomicU8::new(0), AtomicU8::new(0)],
+            consumers: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn set_limit(&self, limit_bytes: Option<u64>) {
+        self.limit.store(limit_bytes.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    pub fn limit(&self) -> Option<u64> {
+        Some(self.limit.load(Ordering::Relaxed)).filter(|&limit| limit != 0)
+    }
+
+    pub fn budget(&self, component: MemoryComponent) -> Option<u64> {
+        self.limit().map(|limit| limit / 100 * component.budget_percent())
+    }
+
+    /// Registers a consumer for as long as it is alive elsewhere.
+    pub fn register(&self, consumer: Weak<dyn MemoryConsumer>) {
+        self.consumers.lock().unwrap().push(consumer);
+    }
+
+    /// Stops accounting for a consumer. Once this returns, the manager holds no strong reference to it.
+    pub fn deregister(&self, consumer: &dyn MemoryConsumer) {
+        let address = consumer as *const dyn MemoryConsumer as *const ();
+        self.consumers.lock().unwrap().retain(|registered| registered.as_ptr() as *const () != address);
+    }
+
+    pub fn reserve(&'static self, component: MemoryComponent) -> MemoryReservation {
+        MemoryReservation { manager: self, component, bytes: 0 }
+    }
+
+    /// The pressure on a component as of the last rebalance.
+    pub fn pressure(&self, component: MemoryComponent) -> MemoryPressure {
+        MemoryPressure::from_u8(self.pressure[component.index()].load(Ordering::Relaxed))
+    }
+
+    /// Scales a batch size down as pressure on the component grows, never below a single element.
+    pub fn batch_size(&self, component: MemoryComponent, default: usize) -> usize {
+        let scaled = match self.pressure(component) {
+            MemoryPressure::Normal => default,
+            MemoryPressure::High => default / 4,
+            MemoryPressure::Exceeded => default / 16,
+        };
+        scaled.max(1)
+    }
+
+    /// Measures every component, and asks the consumers of components over their pressure threshold to shrink.
+    /// The memory a component cannot release beyond its budget is taken out of the budgets of the components
+    /// within theirs, whose consumers are then asked to shrink in its place.
+    pub fn rebalance(&self) -> Vec<ComponentMemory> {
+        // consumers are only upgraded while holding the lock, so that deregistering waits for the rebalance to finish
+        let mut registered = self.consumers.lock().unwrap();
+        registered.retain(|consumer| consumer.strong_count() > 0);
+        let consumers = registered.iter().filter_map(Weak::upgrade).collect::<Vec<_>>();
+
+        let budgets = MemoryComponent::ALL.map(|component| self.budget(component));
+        let usages = self.relieve_pressure(&consumers, &budgets);
+        let budgets = Self::redistribute_excess(budgets, &usages);
+        let usages = self.relieve_pressure(&consumers, &budgets);
+
+        MemoryComponent::ALL
+            .into_iter()
+            .map(|component| {
+                let (usage, budget) = (usages[component.index()], budgets[component.index()]);
+                let pressure = MemoryPressure::of(usage, budget);
+                self.pressure[component.index()].store(pressure as u8, Ordering::Relaxed);
+                ComponentMemory { component, usage, budget, pressure }
+            })
+            .collect()
+    }
+
+    fn measure(&self, consumers: &[Arc<dyn MemoryConsumer>]) -> ([u64; MemoryComponent::ALL.len()], Vec<u64>) {
+        let mut usages: [u64; MemoryComponent::ALL.len()] =
+            array::from_fn(|index| self.reserved[index].load(Ordering::Relaxed));
+        let consumer_usages = consumers.iter().map(|consumer| consumer.memory_usage()).collect::<Vec<_>>();
+        for (consumer, usage) in consumers.iter().zip(&consumer_usages) {
+            usages[consumer.component().index()] += usage;
+        }
+        (usages, consumer_usages)
+    }
+
+    /// Asks the consumers of the components over their pressure threshold to shrink, and returns the usages after.
+    fn relieve_pressure(
+        &self,
+        consumers: &[Arc<dyn MemoryConsumer>],
+        budgets: &[Option<u64>; MemoryComponent::ALL.len()],
+    ) -> [u64; MemoryComponent::ALL.len()] {
+        let (usages, consumer_usages) = self.measure(consumers);
+        let mut is_relieved = false;
+        for component in MemoryComponent::ALL {
+            let (usage, budget) = (usages[component.index()], budgets[component.index()]);
+            let pressure = MemoryPressure::of(usage, budget);
+            if let (MemoryPressure::High | MemoryPressure::Exceeded, Some(budget)) = (pressure, budget) {
+                // every consumer gives up its share of the excess
+                let target_total = budget / 100 * MEMORY_PRESSURE_HIGH_PERCENT;
+                for (consumer, &consumer_usage) in consumers.iter().zip(&consumer_usages) {
+                    if consumer.component() == component && consumer_usage > 0 {
+                        let target = (consumer_usage as u128 * target_total as u128 / usage as u128) as u64;
+                        consumer.relieve_pressure(pressure, target);
+                        is_relieved = true;
+                    }
+                }
+            }
+        }
+        if is_relieved {
+            self.measure(consumers).0
+        } else {
+            usages
+        }
+    }
+
+    /// Splits the usage of the components over budget beyond their budgets between the components within budget,
+    /// in proportion to their budgets.
+    fn redistribute_excess(
+        budgets: [Option<u64>; MemoryComponent::ALL.len()],
+        usages: &[u64; MemoryComponent::ALL.len()],
+    ) -> [Option<u64>; MemoryComponent::ALL.len()] {
+        let excess: u64 =
+            budgets.iter().zip(usages).map(|(&budget, &usage)| usage.saturating_sub(budget.unwrap_or(u64::MAX))).sum();
+        let spare_budget: u64 =
+            budgets.iter().zip(usages).filter_map(|(&budget, &usage)| budget.filter(|&budget| usage <= budget)).sum();
+        if excess == 0 || spare_budget == 0 {
+            return budgets;
+        }
+        let mut redistributed = budgets;
+        for (budget, &usage) in redistributed.iter_mut().zip(usages) {
+            if let Some(budget) = budget {
+                if usage <= *budget {
+                    let share = (excess as u128 * *budget as u128 / spare_budget as u128) as u64;
+                    *budget = budget.saturating_sub(share);
+                }
+            }
+        }
+        redistributed
+    }
+}
+
+impl fmt::Debug for MemoryManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryManager").field("limit", &self.limit()).finish()
+    }
+}
+
+/// Memory held by a short-lived buffer, accounted for until the reservation is dropped.
+#[derive(Debug)]
+pub struct MemoryReservation {
+    manager: &'static MemoryManager,
+    component: MemoryComponent,
+    bytes: u64,
+}
+
+impl MemoryReservation {
+    pub fn resize(&mut self, bytes: u64) {
+        if bytes == self.bytes {
+            return;
+        }
+        let reserved = &self.manager.reserved[self.component.index()];
+        if bytes > self.bytes {
+            reserved.fetch_add(bytes - self.bytes, Ordering::Relaxed);
+        } else {
+            reserved.fetch_sub(self.bytes - bytes, Ordering::Relaxed);
+        }
+        self.bytes = bytes;
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.resize(0);
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Weak,
+    };
+
+    use super::{MemoryComponent, MemoryConsumer, MemoryManager, MemoryPressure};
+
+    const LIMIT: u64 = 1_000_000;
+
+    struct TestConsumer {
+        component: MemoryComponent,
+        usage: AtomicU64,
+        can_shrink: bool,
+    }
+
+    impl TestConsumer {
+        fn register(
+            manager: &MemoryManager,
+            component: MemoryComponent,
+            usage: u64,
+            can_shrink: bool,
+        ) -> Arc<TestConsumer> {
+            let consumer = Arc::new(TestConsumer { component, usage: AtomicU64::new(usage), can_shrink });
+            let weak_consumer: Weak<dyn MemoryConsumer> = Arc::downgrade(&consumer) as _;
+            manager.register(weak_consumer);
+            consumer
+        }
+    }
+
+    impl MemoryConsumer for TestConsumer {
+        fn component(&self) -> MemoryComponent {
+            self.component
+        }
+
+        fn memory_usage(&self) -> u64 {
+            self.usage.load(Ordering::Relaxed)
+        }
+
+        fn relieve_pressure(&self, _pressure: MemoryPressure, target_bytes: u64) {
+            if self.can_shrink {
+                self.usage.fetch_min(target_bytes, Ordering::Relaxed);
+            }
+        }
+    }
+
+    #[test]
+    fn limit_is_split_into_component_budgets() {
+        let manager = MemoryManager::new();
+        assert!(MemoryComponent::ALL.iter().all(|&component| manager.budget(component).is_none()));
+
+        manager.set_limit(Some(LIMIT));
+        let budgets = MemoryComponent::ALL.map(|component| manager.budget(component).unwrap());
+        assert_eq!(budgets, [100_000, 200_000, 700_000]);
+        assert!(budgets.iter().sum::<u64>() <= LIMIT);
+    }
+
+    #[test]
+    fn reservations_set_pressure_levels() {
+        static MANAGER: MemoryManager = MemoryManager::new();
+        MANAGER.set_limit(Some(LIMIT));
+        let component = MemoryComponent::ExecutorBuffers;
+        let mut reservation = MANAGER.reserve(component);
+
+        for (bytes, pressure, batch_size) in [
+            (500_000, MemoryPressure::Normal, 64),
+            (600_000, MemoryPressure::High, 16),
+            (800_000, MemoryPressure::Exceeded, 4),
+        ] {
+            reservation.resize(bytes);
+            MANAGER.rebalance();
+            assert_eq!(MANAGER.pressure(component), pressure);
+            assert_eq!(MANAGER.batch_size(component, 64), batch_size);
+        }
+
+        drop(reservation);
+        let memory = MANAGER.rebalance();
+        assert_eq!(memory[component.index()].usage, 0);
+        assert_eq!(MANAGER.pressure(component), MemoryPressure::Normal);
+    }
+
+    #[test]
+    fn consumers_under_pressure_are_relieved_in_proportion() {
+        let manager = MemoryManager::new();
+        manager.set_limit(Some(LIMIT));
+        let component = MemoryComponent::QueryPlanCache;
+        let large = TestConsumer::register(&manager, component, 150_000, true);
+        let small = TestConsumer::register(&manager, component, 50_000, true);
+
+        manager.rebalance();
+        // the component shrinks back to its pressure threshold, each consumer by its share
+        assert_eq!(large.memory_usage(), 120_000);
+        assert_eq!(small.memory_usage(), 40_000);
+        assert_eq!(manager.pressure(component), MemoryPressure::Normal);
+
+        manager.deregister(large.as_ref());
+        let memory = manager.rebalance();
+        assert_eq!(memory[component.index()].usage, 40_000);
+    }
+
+    #[test]
+    fn excess_that_cannot_be_released_is_taken_from_other_budgets() {
+        let manager = MemoryManager::new();
+        manager.set_limit(Some(LIMIT));
+        let type_cache = TestConsumer::register(&manager, MemoryComponent::TypeCache, 400_000, false);
+        let query_plans = TestConsumer::register(&manager, MemoryComponent::QueryPlanCache, 150_000, true);
+
+        let memory = manager.rebalance();
+        assert_eq!(type_cache.memory_usage(), 400_000);
+        assert_eq!(manager.pressure(MemoryComponent::TypeCache), MemoryPressure::Exceeded);
+        // within its own budget, but asked to make room for the type cache
+        let query_plan_memory = memory[MemoryComponent::QueryPlanCache.index()];
+        assert!(query_plan_memory.budget.unwrap() < 150_000);
+        assert!(query_plans.memory_usage() < 150_000);
+        assert_eq!(query_plan_memory.usage, query_plans.memory_usage());
+        assert!(memory.iter().map(|memory| memory.budget.unwrap()).sum::<u64>() < LIMIT);
+    }
+}
@@ -337,18 +337,34 @@ impl CommitProfileData {
 pub struct QueryProfile {
     compile_profile: CompileProfile,
     stage_profiles: RwLock<HashMap<u64, Arc<StageProfile>>>,
+    fingerprint: Option<u64>,
     enabled: bool,
 }
 
 impl QueryProfile {
     pub fn new(enabled: bool) -> Self {
-        Self { compile_profile: CompileProfile::new(enabled), stage_profiles: RwLock::new(HashMap::new()), enabled }
+        Self {
+            compile_profile: CompileProfile::new(enabled),
+            stage_profiles: RwLock::new(HashMap::new()),
+            fingerprint: None,
+            enabled,
+        }
     }
 
     pub fn is_enabled(&self) -> bool {
         self.enabled
     }
 
+    /// The fingerprint of the shape of the profiled query, which is shared by queries that only differ in variable names,
+    /// constraint order or parameter values. It is recorded whether or not measurements are enabled.
+    pub fn fingerprint(&self) -> Option<u64> {
+        self.fingerprint
+    }
+
+    pub fn set_fingerprint(&mut self, fingerprint: u64) {
+        self.fingerprint = Some(fingerprint);
+    }
+
     pub fn compilation_profile(&mut self) -> &mut CompileProfile {
         &mut self.compile_profile
     }
@@ -372,33 +388,60 @@ impl QueryProfile {
     pub fn stage_profiles(&self) -> &RwLock<HashMap<u64, Arc<StageProfile>>> {
         &self.stage_profiles
     }
+
+    pub fn compilation_micros(&self) -> f64 {
+        self.compile_profile.total_micros()
+    }
+
+    pub fn execution_micros(&self) -> f64 {
+        self.step_profiles()
+            .iter()
+            .filter_map(|step_profile| step_profile.data.as_ref())
+            .map(|data| data.nanos.load(Ordering::SeqCst))
+            .sum::<u64>() as f64
+            / 1000.0
+    }
+
+    /// The number of raw storage seeks and advances made by all execution steps.
+    pub fn storage_reads(&self) -> u64 {
+        self.step_profiles().iter().map(|step_profile| step_profile.storage_reads()).sum()
+    }
+
+    /// The storage reads of each stage or pattern, in the order the stages were compiled.
+    pub fn stage_storage_reads(&self) -> Vec<(String, u64)> {
+        let stage_profiles = self.stage_profiles.read().unwrap();
+        stage_profiles
+            .iter()
+            .sorted_by_key(|(id, _)| *id)
+            .map(|(_, stage_profile)| (stage_profile.description.clone(), stage_profile.storage_reads()))
+            .collect()
+    }
+
+    fn step_profiles(&self) -> Vec<Arc<StepProfile>> {
+        let stage_profiles = self.stage_profiles.read().unwrap();
+        stage_profiles.values().flat_map(|stage_profile| stage_profile.step_profiles.read().unwrap().clone()).collect()
+    }
 }
 
 impl fmt::Display for QueryProfile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let compile_micros = self.compile_profile.total_micros();
+        let total_micros = self.compilation_micros() + self.execution_micros();
         let stage_profiles = self.stage_profiles.read().unwrap();
-        let total_micros = compile_micros
-            + stage_profiles
-                .iter()
-                .map(|(_, stage_profile)| {
-                    stage_profile
-                        .step_profiles
-                        .read()
-                        .unwrap()
-                        .iter()
-                        .map(|step_profile| {
-                            step_profile.data.as_ref().map(|data| data.nanos.load(Ordering::SeqCst)).unwrap_or(0)
-                        })
-                        .sum::<u64>()
-                })
-                .sum::<u64>() as f64
-                / 1000.0;
-        writeln!(f, "Query profile[measurements_enabled={}, total micros: {}]", self.enabled, total_micros)?;
+        write!(f, "Query profile[measurements_enabled={}, total micros: {}", self.enabled, total_micros)?;
+        if let Some(fingerprint) = self.fingerprint {
+            write!(f, ", fingerprint: {:016x}", fingerprint)?;
+        }
+        writeln!(f, "]")?;
         writeln!(f, "{}", self.compile_profile)?;
         for (id, pattern_profile) in stage_profiles.iter().sorted_by_key(|(id, _)| *id) {
             writeln!(f, "  -----")?;
-            writeln!(f, "  Stage or Pattern [id={}] - {}", id, &pattern_profile.description)?;
+            writeln!(
+                f,
+                "  Stage or Pattern [id={}] - {} (storage reads: {})",
+                id,
+                &pattern_profile.description,
+                pattern_profile.storage_reads()
+            )?;
             write!(f, "{}", pattern_profile)?;
         }
         Ok(())
@@ -553,6 +596,10 @@ impl StageProfile {
             Arc::new(StepProfile::new_disabled())
         }
     }
+
+    pub fn storage_reads(&self) -> u64 {
+        self.step_profiles.read().unwrap().iter().map(|step_profile| step_profile.storage_reads()).sum()
+    }
 }
 
 impl fmt::Display for StageProfile {
@@ -613,6 +660,13 @@ impl StepProfile {
             StorageCounters::DISABLED
         }
     }
+
+    /// The number of raw storage seeks and advances made by this step.
+    pub fn storage_reads(&self) -> u64 {
+        self.data
+            .as_ref()
+            .map_or(0, |data| data.storage.get_raw_seek().unwrap_or(0) + data.storage.get_raw_advance().unwrap_or(0))
+    }
 }
 
 impl fmt::Display for StepProfileData {
@@ -620,7 +674,6 @@ impl fmt::Display for StepProfileData {
         let rows = self.rows.load(Ordering::Relaxed);
         let micros = Duration::from_nanos(self.nanos.load(Ordering::Relaxed)).as_micros();
         let micros_per_row: f64 = micros as f64 / rows as f64;
-        // TODO: print storage ops
         write!(
             f,
             "{}\n    ==> batches: {}, rows: {}, micros: {}, micros/row: {:.1} ({})",
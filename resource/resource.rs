@@ -5,6 +5,7 @@
  */
 
 pub mod constants;
+pub mod memory;
 pub mod perf_counters;
 pub mod profile;
 pub mod server_info;
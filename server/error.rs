@@ -6,8 +6,9 @@
 
 use std::{io, net::SocketAddr, sync::Arc};
 
-use database::DatabaseOpenError;
+use database::{cluster::ClusterError, DatabaseOpenError, KeyManagementError};
 use error::typedb_error;
+use system::migration::SystemMigrationError;
 use tokio_rustls::rustls::{
     pki_types::pem::Error as RustlsCertError, server::VerifierBuilderError as RustlsVerifierError,
 };
@@ -40,5 +41,9 @@ typedb_error! {
         HttpTlsFailedConfiguration(22, "Failed to configure TLS for the HTTP server.", source: Arc<tokio_rustls::rustls::Error>),
         HttpTlsUnsetDefaultCryptoProvider(23, "Failed to install default crypto provider for the HTTP server TLS configuration."),
         HttpTlsPemFileError(24, "Invalid PEM file specified for the HTTP server.", source: Arc<tokio_rustls::rustls::pki_types::pem::Error>),
+        Cluster(25, "Could not join the cluster.", typedb_source: ClusterError),
+        SystemDatabaseMigration(26, "Could not migrate the system database.", typedb_source: SystemMigrationError),
+        CouldNotReadStorageEncryptionKey(27, "Could not read the storage encryption key from '{path}'.", path: String, source: Arc<io::Error>),
+        InvalidStorageEncryptionKey(28, "The storage encryption key in '{path}' is invalid: it must be 64 hex digits.", path: String, source: KeyManagementError),
     }
 }
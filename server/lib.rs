@@ -13,7 +13,9 @@ use std::{net::SocketAddr, sync::Arc};
 use axum_server::{tls_rustls::RustlsConfig, Handle};
 use database::database_manager::DatabaseManager;
 use resource::{
-    constants::server::{GRPC_CONNECTION_KEEPALIVE, SERVER_INFO},
+    constants::server::{
+        COMMIT_TRIGGER_QUEUE_CAPACITY, COMMIT_TRIGGER_WORKERS, GRPC_CONNECTION_KEEPALIVE, SERVER_INFO,
+    },
     server_info::ServerInfo,
 };
 use tokio::{
@@ -24,8 +26,11 @@ use tracing::info;
 
 use crate::{
     error::ServerOpenError,
-    parameters::config::{Config, EncryptionConfig},
-    service::{grpc, http},
+    parameters::config::{Config, EncryptionConfig, GrpcEndpointConfig, HttpEndpointConfig},
+    service::{
+        grpc, http, transaction_limiter::TransactionLimiter, transaction_registry::TransactionRegistry,
+        trigger_service::TriggerRunner,
+    },
     state::{BoxServerState, LocalServerState},
 };
 
@@ -106,10 +111,16 @@ impl Server {
             None
         };
 
+        let transaction_limiter = Arc::new(TransactionLimiter::new(self.config.server.max_transactions_per_user));
+        let transaction_registry = Arc::new(TransactionRegistry::new(transaction_limiter));
+        let trigger_runner = Arc::new(TriggerRunner::new(COMMIT_TRIGGER_WORKERS, COMMIT_TRIGGER_QUEUE_CAPACITY));
         let grpc_server = Self::serve_grpc(
             grpc_address,
+            &self.config.server.grpc,
             &self.config.server.encryption,
             self.server_state.clone(),
+            transaction_registry.clone(),
+            trigger_runner.clone(),
             self.shutdown_receiver.clone(),
         );
         let http_server = if let Some(http_address) = http_address_opt {
@@ -117,7 +128,10 @@ impl Server {
                 self.server_info,
                 http_address,
                 &self.config.server.encryption,
+                &self.config.server.http,
                 self.server_state.clone(),
+                transaction_registry,
+                trigger_runner,
                 self.shutdown_receiver,
             );
             Some(server)
@@ -140,12 +154,22 @@ impl Server {
 
     async fn serve_grpc(
         address: SocketAddr,
+        grpc_config: &GrpcEndpointConfig,
         encryption_config: &EncryptionConfig,
         server_state: Arc<BoxServerState>,
+        transaction_registry: Arc<TransactionRegistry>,
+        trigger_runner: Arc<TriggerRunner>,
         mut shutdown_receiver: Receiver<()>,
     ) -> Result<(), ServerOpenError> {
         let authenticator = grpc::authenticator::Authenticator::new(server_state.clone());
-        let service = grpc::typedb_service::TypeDBService::new(address.clone(), server_state.clone());
+        let service = grpc::typedb_service::TypeDBService::new(
+            address.clone(),
+            server_state.clone(),
+            transaction_registry,
+            trigger_runner,
+            grpc_config.answer_window,
+            grpc_config.preserve_imported_iids,
+        );
         let mut grpc_server =
             tonic::transport::Server::builder().http2_keepalive_interval(Some(GRPC_CONNECTION_KEEPALIVE));
         if let Some(tls_config) = grpc::encryption::prepare_tls_config(encryption_config)? {
@@ -168,18 +192,28 @@ impl Server {
         server_info: ServerInfo,
         address: SocketAddr,
         encryption_config: &EncryptionConfig,
+        http_config: &HttpEndpointConfig,
         server_state: Arc<BoxServerState>,
+        transaction_registry: Arc<TransactionRegistry>,
+        trigger_runner: Arc<TriggerRunner>,
         mut shutdown_receiver: Receiver<()>,
     ) -> Result<(), ServerOpenError> {
         let authenticator = http::authenticator::Authenticator::new(server_state.clone());
-        let service = http::typedb_service::TypeDBService::new(server_info, address, server_state.clone());
+        let service = http::typedb_service::TypeDBService::new(
+            server_info,
+            address,
+            server_state.clone(),
+            transaction_registry,
+            trigger_runner,
+        );
         let encryption_config = http::encryption::prepare_tls_config(encryption_config)?;
         let http_service = Arc::new(service);
-        let router_service = http::typedb_service::TypeDBService::create_protected_router(http_service.clone())
-            .layer(authenticator)
-            .merge(http::typedb_service::TypeDBService::create_unprotected_router(http_service))
-            .layer(http::typedb_service::TypeDBService::create_cors_layer())
-            .into_make_service();
+        let router_service =
+            http::typedb_service::TypeDBService::create_protected_router(http_service.clone(), http_config)
+                .layer(authenticator)
+                .merge(http::typedb_service::TypeDBService::create_unprotected_router(http_service, http_config))
+                .layer(http::typedb_service::TypeDBService::create_cors_layer())
+                .into_make_service_with_connect_info::<SocketAddr>();
 
         let shutdown_handle = Handle::new();
         let shutdown_handle_clone = shutdown_handle.clone();
@@ -22,6 +22,14 @@ pub struct CLIArgs {
     #[arg(long = "server.address")]
     pub server_address: Option<String>,
 
+    /// Answers streamed past the prefetch while awaiting a driver's request for more
+    #[arg(long = "server.grpc.answer-window")]
+    pub server_grpc_answer_window: Option<usize>,
+
+    /// Enable/disable keeping the exported IIDs of imported entities and relations
+    #[arg(long = "server.grpc.preserve-imported-iids")]
+    pub server_grpc_preserve_imported_iids: Option<bool>,
+
     /// Enable/disable HTTP endpoint
     #[arg(long = "server.http.enabled")]
     pub server_http_enabled: Option<bool>,
@@ -30,6 +38,18 @@ pub struct CLIArgs {
     #[arg(long = "server.http.address")]
     pub server_http_address: Option<String>,
 
+    /// Maximum request body size, in bytes, accepted by the HTTP query and transaction endpoints
+    #[arg(long = "server.http.body-limits.query-bytes")]
+    pub server_http_body_limits_query_bytes: Option<usize>,
+
+    /// Maximum request body size, in bytes, accepted by all other HTTP endpoints
+    #[arg(long = "server.http.body-limits.default-bytes")]
+    pub server_http_body_limits_default_bytes: Option<usize>,
+
+    /// Enable/disable per-client rate limiting of the HTTP endpoint
+    #[arg(long = "server.http.rate-limits.enabled")]
+    pub server_http_rate_limits_enabled: Option<bool>,
+
     /// The amount of seconds generated authentication tokens will remain valid, specified in seconds.
     /// Use smaller values for better security and bigger values for better authentication performance and convenience
     /// (min: 1 second, max: 1 year).
@@ -52,6 +72,62 @@ pub struct CLIArgs {
     #[arg(long = "server.encryption.ca-certificate", value_name = "FILE")]
     pub server_encryption_ca_certificate: Option<String>,
 
+    /// HTTP address of the primary server (e.g., http://primary:8000). Specify to run this server as a read-only replica
+    #[arg(long = "server.replication.primary-address")]
+    pub server_replication_primary_address: Option<String>,
+
+    /// Username the replica authenticates to the primary with
+    #[arg(long = "server.replication.username")]
+    pub server_replication_username: Option<String>,
+
+    /// Password the replica authenticates to the primary with
+    #[arg(long = "server.replication.password")]
+    pub server_replication_password: Option<String>,
+
+    /// Interval between the replica's polls of the primary's write-ahead log, specified in milliseconds
+    #[arg(long = "server.replication.poll-interval-millis")]
+    pub server_replication_poll_interval_millis: Option<u64>,
+
+    /// HTTP address this server's cluster peers reach it at (e.g., http://node1:8000). Specify to run this server as a cluster member
+    #[arg(long = "server.cluster.address")]
+    pub server_cluster_address: Option<String>,
+
+    /// Comma-separated HTTP addresses of the other cluster members
+    #[arg(long = "server.cluster.peers", value_delimiter = ',')]
+    pub server_cluster_peers: Option<Vec<String>>,
+
+    /// Time without a heartbeat from the leader after which a cluster member starts an election, specified in milliseconds
+    #[arg(long = "server.cluster.election-timeout-millis")]
+    pub server_cluster_election_timeout_millis: Option<u64>,
+
+    /// Interval between the cluster leader's heartbeats, specified in milliseconds
+    #[arg(long = "server.cluster.heartbeat-interval-millis")]
+    pub server_cluster_heartbeat_interval_millis: Option<u64>,
+
+    /// Memory shared by the caches and query execution buffers, specified in bytes
+    #[arg(long = "server.memory-limit-bytes")]
+    pub server_memory_limit_bytes: Option<u64>,
+
+    /// Maximum number of transactions each user may have open at once, over gRPC and HTTP together
+    #[arg(long = "server.max-transactions-per-user")]
+    pub server_max_transactions_per_user: Option<usize>,
+
+    /// Maximum number of constraints across the patterns of a query
+    #[arg(long = "server.query-limits.max-pattern-constraints")]
+    pub server_query_limits_max_pattern_constraints: Option<usize>,
+
+    /// Maximum number of combinations of disjunction branches in a query
+    #[arg(long = "server.query-limits.max-disjunction-branches")]
+    pub server_query_limits_max_disjunction_branches: Option<usize>,
+
+    /// Maximum number of type combinations the schema may admit for the constraints of a pattern
+    #[arg(long = "server.query-limits.max-type-combinations")]
+    pub server_query_limits_max_type_combinations: Option<usize>,
+
+    /// Maximum number of values a list reducer may collect for one group
+    #[arg(long = "server.query-limits.max-reduce-list-size")]
+    pub server_query_limits_max_reduce_list_size: Option<usize>,
+
     /// Path to the data directory
     #[arg(long = "storage.data-directory", value_name = "DIR")]
     pub storage_data_directory: Option<String>,
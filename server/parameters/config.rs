@@ -11,7 +11,18 @@ use std::{
     time::Duration,
 };
 
-use resource::constants::server::{DEFAULT_AUTHENTICATION_TOKEN_EXPIRATION, MONITORING_DEFAULT_PORT};
+use resource::constants::{
+    compiler::{MAX_DISJUNCTION_BRANCHES, MAX_PATTERN_CONSTRAINTS, MAX_REDUCE_LIST_SIZE, MAX_TYPE_COMBINATIONS},
+    server::{
+        DEFAULT_AUTHENTICATION_TOKEN_EXPIRATION, DEFAULT_CLUSTER_ELECTION_TIMEOUT_MILLIS,
+        DEFAULT_CLUSTER_HEARTBEAT_INTERVAL_MILLIS, DEFAULT_GRPC_ANSWER_WINDOW, DEFAULT_HTTP_ADMIN_RATE_LIMIT_BURST,
+        DEFAULT_HTTP_ADMIN_RATE_LIMIT_PER_SECOND, DEFAULT_HTTP_AUTH_RATE_LIMIT_BURST,
+        DEFAULT_HTTP_AUTH_RATE_LIMIT_PER_SECOND, DEFAULT_HTTP_BODY_LIMIT_BYTES, DEFAULT_HTTP_QUERY_BODY_LIMIT_BYTES,
+        DEFAULT_HTTP_QUERY_RATE_LIMIT_BURST, DEFAULT_HTTP_QUERY_RATE_LIMIT_PER_SECOND,
+        DEFAULT_HTTP_RATE_LIMITS_ENABLED, DEFAULT_REPLICATION_POLL_INTERVAL_MILLIS, DEFAULT_USER_NAME,
+        DEFAULT_USER_PASSWORD, MONITORING_DEFAULT_PORT,
+    },
+};
 use serde::Deserialize;
 use serde_with::{serde_as, DurationSeconds};
 
@@ -33,9 +44,65 @@ pub struct Config {
 #[serde(rename_all = "kebab-case")]
 pub struct ServerConfig {
     pub(crate) address: String,
+    #[serde(default)]
+    pub(crate) grpc: GrpcEndpointConfig,
     pub(crate) http: HttpEndpointConfig,
     pub(crate) authentication: AuthenticationConfig,
     pub(crate) encryption: EncryptionConfig,
+    #[serde(default)]
+    pub(crate) replication: ReplicationConfig,
+    #[serde(default)]
+    pub(crate) cluster: ClusterConfig,
+    /// Split into budgets for the type caches, query plan caches and query execution buffers, which are shrunk when
+    /// they approach their budget. Unlimited if unset.
+    #[serde(default)]
+    pub(crate) memory_limit_bytes: Option<u64>,
+    /// Transactions each user may have open at once, over gRPC and HTTP together. Unlimited if unset.
+    #[serde(default)]
+    pub(crate) max_transactions_per_user: Option<usize>,
+    #[serde(default)]
+    pub(crate) query_limits: QueryLimitsConfig,
+}
+
+/// Bounds on the queries the server compiles, so that pathological queries are rejected instead of exhausting memory
+/// in type inference, and on the lists their reducers collect.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct QueryLimitsConfig {
+    pub(crate) max_pattern_constraints: usize,
+    pub(crate) max_disjunction_branches: usize,
+    pub(crate) max_type_combinations: usize,
+    pub(crate) max_reduce_list_size: usize,
+}
+
+impl Default for QueryLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_pattern_constraints: MAX_PATTERN_CONSTRAINTS,
+            max_disjunction_branches: MAX_DISJUNCTION_BRANCHES,
+            max_type_combinations: MAX_TYPE_COMBINATIONS,
+            max_reduce_list_size: MAX_REDUCE_LIST_SIZE,
+        }
+    }
+}
+
+/// Query answers are streamed to drivers in a prefetch, followed by a signal to request more. While the driver's
+/// request is in flight, answers keep streaming for up to its reported network latency, but at most `answer_window`
+/// of them, so slow consumers do not have answers buffered without bound.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct GrpcEndpointConfig {
+    pub(crate) answer_window: usize,
+    /// Whether imported entities and relations keep the IIDs they were exported with, rather than being given new ones.
+    /// The protocol has no import option for it, so it applies to every import.
+    #[serde(default)]
+    pub(crate) preserve_imported_iids: bool,
+}
+
+impl Default for GrpcEndpointConfig {
+    fn default() -> Self {
+        Self { answer_window: DEFAULT_GRPC_ANSWER_WINDOW, preserve_imported_iids: false }
+    }
 }
 
 #[serde_as]
@@ -44,6 +111,63 @@ pub struct ServerConfig {
 pub struct HttpEndpointConfig {
     pub(crate) enabled: bool,
     pub(crate) address: String,
+    #[serde(default)]
+    pub(crate) body_limits: HttpBodyLimitsConfig,
+    #[serde(default)]
+    pub(crate) rate_limits: HttpRateLimitsConfig,
+}
+
+/// Maximum request body sizes, in bytes, per class of HTTP endpoint.
+/// Query endpoints carry the query text, and may be larger than the rest of the API's payloads.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct HttpBodyLimitsConfig {
+    pub(crate) query_bytes: usize,
+    pub(crate) default_bytes: usize,
+}
+
+impl Default for HttpBodyLimitsConfig {
+    fn default() -> Self {
+        Self { query_bytes: DEFAULT_HTTP_QUERY_BODY_LIMIT_BYTES, default_bytes: DEFAULT_HTTP_BODY_LIMIT_BYTES }
+    }
+}
+
+/// Token bucket rate limits per class of HTTP endpoint.
+/// Requests are counted per authenticated user, or per remote address for unauthenticated endpoints.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct HttpRateLimitsConfig {
+    pub(crate) enabled: bool,
+    pub(crate) auth: RateLimitConfig,
+    pub(crate) query: RateLimitConfig,
+    pub(crate) admin: RateLimitConfig,
+}
+
+impl Default for HttpRateLimitsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: DEFAULT_HTTP_RATE_LIMITS_ENABLED,
+            auth: RateLimitConfig {
+                requests_per_second: DEFAULT_HTTP_AUTH_RATE_LIMIT_PER_SECOND,
+                burst: DEFAULT_HTTP_AUTH_RATE_LIMIT_BURST,
+            },
+            query: RateLimitConfig {
+                requests_per_second: DEFAULT_HTTP_QUERY_RATE_LIMIT_PER_SECOND,
+                burst: DEFAULT_HTTP_QUERY_RATE_LIMIT_BURST,
+            },
+            admin: RateLimitConfig {
+                requests_per_second: DEFAULT_HTTP_ADMIN_RATE_LIMIT_PER_SECOND,
+                burst: DEFAULT_HTTP_ADMIN_RATE_LIMIT_BURST,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RateLimitConfig {
+    pub(crate) requests_per_second: u32,
+    pub(crate) burst: u32,
 }
 
 #[serde_as]
@@ -82,10 +206,68 @@ impl Default for EncryptionConfig {
     }
 }
 
+/// Makes this server a read-only replica of the server at the primary's HTTP address.
+/// Replicas poll the primary's write-ahead log, and apply it to their copies of the primary's databases.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ReplicationConfig {
+    pub(crate) primary_address: Option<String>,
+    pub(crate) username: String,
+    pub(crate) password: String,
+    pub(crate) poll_interval_millis: u64,
+}
+
+impl Default for ReplicationConfig {
+    fn default() -> Self {
+        Self {
+            primary_address: None,
+            username: DEFAULT_USER_NAME.to_owned(),
+            password: DEFAULT_USER_PASSWORD.to_owned(),
+            poll_interval_millis: DEFAULT_REPLICATION_POLL_INTERVAL_MILLIS,
+        }
+    }
+}
+
+/// Makes this server a member of a highly available cluster, identified by the HTTP address its peers reach it at.
+/// Members elect a leader, which is the only member accepting writes, and the other members replicate the leader's
+/// databases, authenticating with the replication username and password.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ClusterConfig {
+    pub(crate) address: Option<String>,
+    pub(crate) peers: Vec<String>,
+    pub(crate) election_timeout_millis: u64,
+    pub(crate) heartbeat_interval_millis: u64,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            address: None,
+            peers: Vec::new(),
+            election_timeout_millis: DEFAULT_CLUSTER_ELECTION_TIMEOUT_MILLIS,
+            heartbeat_interval_millis: DEFAULT_CLUSTER_HEARTBEAT_INTERVAL_MILLIS,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) struct StorageConfig {
     pub(crate) data_directory: PathBuf,
+    #[serde(default)]
+    pub(crate) encryption: StorageEncryptionConfig,
+}
+
+/// Encrypts the write-ahead logs of new databases, with per-database data keys wrapped by the master key held in
+/// the key file as 64 hex digits. Databases that are encrypted need the same master key to be loaded again.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct StorageEncryptionConfig {
+    pub(crate) enabled: bool,
+    pub(crate) key_file: Option<PathBuf>,
+    /// Encrypts only the named databases when set, rather than every new database.
+    pub(crate) databases: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -177,13 +359,32 @@ impl ConfigBuilder {
         let CLIArgs {
             config_file_override: _,
             server_address,
+            server_grpc_answer_window,
+            server_grpc_preserve_imported_iids,
             server_http_enabled,
             server_http_address,
+            server_http_body_limits_query_bytes,
+            server_http_body_limits_default_bytes,
+            server_http_rate_limits_enabled,
             server_authentication_token_expiration_seconds,
             server_encryption_enabled,
             server_encryption_certificate,
             server_encryption_certificate_key,
             server_encryption_ca_certificate,
+            server_replication_primary_address,
+            server_replication_username,
+            server_replication_password,
+            server_replication_poll_interval_millis,
+            server_cluster_address,
+            server_cluster_peers,
+            server_cluster_election_timeout_millis,
+            server_cluster_heartbeat_interval_millis,
+            server_memory_limit_bytes,
+            server_max_transactions_per_user,
+            server_query_limits_max_pattern_constraints,
+            server_query_limits_max_disjunction_branches,
+            server_query_limits_max_type_combinations,
+            server_query_limits_max_reduce_list_size,
             storage_data_directory,
             logging_directory,
             diagnostics_reporting_metrics,
@@ -195,8 +396,13 @@ impl ConfigBuilder {
         let Self { config } = self;
         override_config! {
             config.server.address => server_address;
+            config.server.grpc.answer_window => server_grpc_answer_window;
+            config.server.grpc.preserve_imported_iids => server_grpc_preserve_imported_iids;
             config.server.http.enabled => server_http_enabled;
             config.server.http.address => server_http_address;
+            config.server.http.body_limits.query_bytes => server_http_body_limits_query_bytes;
+            config.server.http.body_limits.default_bytes => server_http_body_limits_default_bytes;
+            config.server.http.rate_limits.enabled => server_http_rate_limits_enabled;
             config.server.authentication.token_expiration => server_authentication_token_expiration_seconds.map(|secs| Duration::new(secs, 0));
 
             config.server.encryption.enabled => server_encryption_enabled;
@@ -204,6 +410,23 @@ impl ConfigBuilder {
             config.server.encryption.certificate_key => server_encryption_certificate_key.map(|cert| Some(cert.into()));
             config.server.encryption.ca_certificate => server_encryption_ca_certificate.map(|cert| Some(cert.into()));
 
+            config.server.replication.primary_address => server_replication_primary_address.map(Some);
+            config.server.replication.username => server_replication_username;
+            config.server.replication.password => server_replication_password;
+            config.server.replication.poll_interval_millis => server_replication_poll_interval_millis;
+
+            config.server.cluster.address => server_cluster_address.map(Some);
+            config.server.cluster.peers => server_cluster_peers;
+            config.server.cluster.election_timeout_millis => server_cluster_election_timeout_millis;
+            config.server.cluster.heartbeat_interval_millis => server_cluster_heartbeat_interval_millis;
+
+            config.server.memory_limit_bytes => server_memory_limit_bytes.map(Some);
+            config.server.max_transactions_per_user => server_max_transactions_per_user.map(Some);
+            config.server.query_limits.max_pattern_constraints => server_query_limits_max_pattern_constraints;
+            config.server.query_limits.max_disjunction_branches => server_query_limits_max_disjunction_branches;
+            config.server.query_limits.max_type_combinations => server_query_limits_max_type_combinations;
+            config.server.query_limits.max_reduce_list_size => server_query_limits_max_reduce_list_size;
+
             config.storage.data_directory => storage_data_directory.map(|p| CLIArgs::resolve_path_from_pwd(&p.into()));
             config.logging.directory => logging_directory.map(|p| CLIArgs::resolve_path_from_pwd(&p.into()));
 
@@ -229,8 +452,71 @@ impl ConfigBuilder {
                 message: "Server encryption was enabled, but certificate key was not configured.",
             });
         }
+        let body_limits = &config.server.http.body_limits;
+        if body_limits.query_bytes == 0 || body_limits.default_bytes == 0 {
+            return Err(ConfigError::ValidationError {
+                message: "HTTP request body limits must be greater than zero.",
+            });
+        }
+        let rate_limits = &config.server.http.rate_limits;
+        if rate_limits.enabled
+            && [rate_limits.auth, rate_limits.query, rate_limits.admin]
+                .iter()
+                .any(|limit| limit.requests_per_second == 0 || limit.burst == 0)
+        {
+            return Err(ConfigError::ValidationError {
+                message: "HTTP rate limits were enabled, but some rates or bursts are zero.",
+            });
+        }
+        if config.server.replication.poll_interval_millis == 0 {
+            return Err(ConfigError::ValidationError {
+                message: "Replication poll interval must be greater than zero.",
+            });
+        }
+        if config.server.memory_limit_bytes == Some(0) {
+            return Err(ConfigError::ValidationError { message: "Server memory limit must be greater than zero." });
+        }
+        if config.server.max_transactions_per_user == Some(0) {
+            return Err(ConfigError::ValidationError {
+                message: "Transaction limit per user must be greater than zero.",
+            });
+        }
+        let query_limits = &config.server.query_limits;
+        if query_limits.max_pattern_constraints == 0
+            || query_limits.max_disjunction_branches == 0
+            || query_limits.max_type_combinations == 0
+            || query_limits.max_reduce_list_size == 0
+        {
+            return Err(ConfigError::ValidationError { message: "Query limits must be greater than zero." });
+        }
+        if config.storage.encryption.enabled && config.storage.encryption.key_file.is_none() {
+            return Err(ConfigError::ValidationError {
+                message: "Storage encryption was enabled, but the key file was not configured.",
+            });
+        }
+        let cluster = &config.server.cluster;
+        if cluster.address.is_some() && config.server.replication.primary_address.is_some() {
+            return Err(ConfigError::ValidationError {
+                message:
+                    "Cluster members replicate from the elected leader, and cannot have a fixed replication primary.",
+            });
+        }
+        if cluster.address.is_some() && !config.server.http.enabled {
+            return Err(ConfigError::ValidationError {
+                message: "Cluster members communicate over HTTP, which must be enabled.",
+            });
+        }
+        if cluster.heartbeat_interval_millis == 0
+            || cluster.heartbeat_interval_millis >= cluster.election_timeout_millis
+        {
+            return Err(ConfigError::ValidationError {
+                message: "Cluster heartbeat interval must be greater than zero and less than the election timeout.",
+            });
+        }
         // finalise:
         config.storage.data_directory = Self::resolve_path_from_executable(&config.storage.data_directory);
+        config.storage.encryption.key_file =
+            config.storage.encryption.key_file.as_ref().map(Self::resolve_path_from_executable);
         config.logging.directory = Self::resolve_path_from_executable(&config.logging.directory);
         config.development_mode.enabled = config.development_mode.enabled | Self::IS_DEVELOPMENT_MODE_FORCED;
         Ok(config)
@@ -260,6 +546,26 @@ impl ConfigBuilder {
         self
     }
 
+    pub fn server_http_body_limits(mut self, body_limits: HttpBodyLimitsConfig) -> Self {
+        self.config.server.http.body_limits = body_limits;
+        self
+    }
+
+    pub fn server_http_rate_limits(mut self, rate_limits: HttpRateLimitsConfig) -> Self {
+        self.config.server.http.rate_limits = rate_limits;
+        self
+    }
+
+    pub fn replication(mut self, config: ReplicationConfig) -> Self {
+        self.config.server.replication = config;
+        self
+    }
+
+    pub fn cluster(mut self, config: ClusterConfig) -> Self {
+        self.config.server.cluster = config;
+        self
+    }
+
     pub fn authentication(mut self, config: AuthenticationConfig) -> Self {
         self.config.server.authentication = config;
         self
@@ -367,4 +673,58 @@ pub mod tests {
             assert_true!(matches!(load_and_parse(config_path(), args), Err(ConfigError::ValidationError { .. })));
         }
     }
+
+    #[test]
+    fn query_limits_can_be_overridden_and_must_be_positive() {
+        let args = vec!["--server.query-limits.max-type-combinations", "1000"];
+        let config = load_and_parse(config_path(), args).unwrap();
+        assert_eq!(config.server.query_limits.max_type_combinations, 1000);
+
+        let args = vec!["--server.query-limits.max-reduce-list-size", "100"];
+        let config = load_and_parse(config_path(), args).unwrap();
+        assert_eq!(config.server.query_limits.max_reduce_list_size, 100);
+
+        let args = vec!["--server.query-limits.max-disjunction-branches", "0"];
+        assert_true!(matches!(load_and_parse(config_path(), args), Err(ConfigError::ValidationError { .. })));
+    }
+
+    #[test]
+    fn http_body_limits_can_be_overridden_and_must_be_positive() {
+        let args = vec!["--server.http.body-limits.query-bytes", "1024"];
+        let config = load_and_parse(config_path(), args).unwrap();
+        assert_eq!(config.server.http.body_limits.query_bytes, 1024);
+
+        let args = vec!["--server.http.body-limits.default-bytes", "0"];
+        assert_true!(matches!(load_and_parse(config_path(), args), Err(ConfigError::ValidationError { .. })));
+    }
+
+    #[test]
+    fn transaction_limit_is_unlimited_by_default_and_must_be_positive() {
+        let config = load_and_parse(config_path(), vec![]).unwrap();
+        assert_eq!(config.server.max_transactions_per_user, None);
+
+        let args = vec!["--server.max-transactions-per-user", "8"];
+        let config = load_and_parse(config_path(), args).unwrap();
+        assert_eq!(config.server.max_transactions_per_user, Some(8));
+
+        let args = vec!["--server.max-transactions-per-user", "0"];
+        assert_true!(matches!(load_and_parse(config_path(), args), Err(ConfigError::ValidationError { .. })));
+    }
+
+    #[test]
+    fn grpc_answer_window_can_be_overridden() {
+        let args = vec!["--server.grpc.answer-window", "0"];
+        let config = load_and_parse(config_path(), args).unwrap();
+        assert_eq!(config.server.grpc.answer_window, 0);
+    }
+
+    #[test]
+    fn imported_iids_are_only_preserved_when_enabled() {
+        let config = load_and_parse(config_path(), vec![]).unwrap();
+        assert!(!config.server.grpc.preserve_imported_iids);
+
+        let args = vec!["--server.grpc.preserve-imported-iids", "true"];
+        let config = load_and_parse(config_path(), args).unwrap();
+        assert!(config.server.grpc.preserve_imported_iids);
+    }
 }
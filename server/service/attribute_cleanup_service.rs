@@ -0,0 +1,93 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+use std::{sync::Arc, thread};
+
+use concept::error::ConceptWriteError;
+use database::{
+    transaction::{DataCommitError, TransactionError, TransactionWrite},
+    Database,
+};
+use diagnostics::{diagnostics_manager::DiagnosticsManager, metrics::BackgroundTaskKind};
+use error::typedb_error;
+use options::TransactionOptions;
+use resource::profile::StorageCounters;
+use storage::durability_client::WALClient;
+use tracing::{event, Level};
+
+/// Deletes the attributes of non-independent types which are no longer owned by any instance.
+///
+/// The write transaction is opened before returning, and the cleanup runs on a background thread,
+/// reporting its progress through diagnostics.
+/// Attributes gaining an owner concurrently make the cleanup fail on commit, leaving them in place.
+pub(crate) fn start_orphaned_attribute_cleanup(
+    database: Arc<Database<WALClient>>,
+    diagnostics_manager: Arc<DiagnosticsManager>,
+) -> Result<(), AttributeCleanupError> {
+    let transaction = TransactionWrite::open_exempt_from_storage_quota(database, TransactionOptions::default())
+        .map_err(|typedb_source| AttributeCleanupError::TransactionFailed { typedb_source })?;
+    thread::spawn(move || {
+        let database_name = transaction.database.name().to_owned();
+        if let Err(err) = run_orphaned_attribute_cleanup(transaction, diagnostics_manager) {
+            event!(Level::ERROR, "Orphaned attribute cleanup in database '{}' failed: {:?}", database_name, err);
+        }
+    });
+    Ok(())
+}
+
+/// Deletes the attributes of non-independent types which are no longer owned by any instance,
+/// returning the number of attributes deleted.
+pub(crate) fn cleanup_orphaned_attributes(
+    database: Arc<Database<WALClient>>,
+    diagnostics_manager: Arc<DiagnosticsManager>,
+) -> Result<u64, AttributeCleanupError> {
+    let transaction = TransactionWrite::open_exempt_from_storage_quota(database, TransactionOptions::default())
+        .map_err(|typedb_source| AttributeCleanupError::TransactionFailed { typedb_source })?;
+    run_orphaned_attribute_cleanup(transaction, diagnostics_manager)
+}
+
+fn run_orphaned_attribute_cleanup(
+    mut transaction: TransactionWrite<WALClient>,
+    diagnostics_manager: Arc<DiagnosticsManager>,
+) -> Result<u64, AttributeCleanupError> {
+    let database_name = transaction.database.name().to_owned();
+    let snapshot = Arc::get_mut(&mut transaction.snapshot).expect("Expected owning snapshot for attribute cleanup");
+    let result = transaction.thing_manager.delete_orphaned_attributes(
+        snapshot,
+        |completed, total| {
+            diagnostics_manager.submit_background_task_progress(
+                &database_name,
+                BackgroundTaskKind::OrphanedAttributeCleanup,
+                completed,
+                total,
+            )
+        },
+        StorageCounters::DISABLED,
+    );
+    let result = match result {
+        Ok(deleted) => transaction
+            .commit()
+            .1
+            .map(|_| deleted)
+            .map_err(|typedb_source| AttributeCleanupError::DataCommitFailed { typedb_source }),
+        Err(typedb_source) => {
+            transaction.close();
+            Err(AttributeCleanupError::ConceptWrite { typedb_source })
+        }
+    };
+    diagnostics_manager.submit_background_task_finished(&database_name, BackgroundTaskKind::OrphanedAttributeCleanup);
+    if let Ok(deleted) = result {
+        event!(Level::DEBUG, "Deleted {} orphaned attributes in database '{}'", deleted, database_name);
+    }
+    result
+}
+
+typedb_error! {
+    pub(crate) AttributeCleanupError(component = "Attribute cleanup", prefix = "ACL") {
+        TransactionFailed(1, "Transaction failed.", typedb_source: TransactionError),
+        ConceptWrite(2, "Error writing concepts.", typedb_source: Box<ConceptWriteError>),
+        DataCommitFailed(3, "Failed to commit the attribute cleanup.", typedb_source: DataCommitError),
+    }
+}
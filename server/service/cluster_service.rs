@@ -0,0 +1,221 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use database::{
+    cluster::{Cluster, ClusterError, ClusterReply},
+    database_manager::DatabaseManager,
+};
+use futures::future::join_all;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::{
+    sync::watch::Receiver,
+    time::{interval, timeout},
+};
+use tracing::{event, Level};
+
+use crate::{
+    parameters::config::{ClusterConfig, ReplicationConfig},
+    service::{
+        http::message::cluster::{ClusterHeartbeatPayload, ClusterReplyResponse, ClusterVotePayload},
+        peer_client::PeerClient,
+        replication_service::start_cluster_replication,
+    },
+};
+
+/// Joins this server to the configured cluster. In the background, the leader sends heartbeats to its followers,
+/// and followers start an election when the leader's heartbeats stop. The databases of the leader accept writes,
+/// while the databases of followers are replicas of the leader's.
+pub(crate) fn start_cluster(
+    config: &ClusterConfig,
+    replication_config: &ReplicationConfig,
+    database_manager: Arc<DatabaseManager>,
+    mut shutdown_receiver: Receiver<()>,
+) -> Result<(), ClusterError> {
+    let Some(address) = config.address.clone() else {
+        return Ok(());
+    };
+    let heartbeat_interval = Duration::from_millis(config.heartbeat_interval_millis);
+    let cluster = Cluster::open(
+        database_manager.data_directory(),
+        address,
+        config.peers.clone(),
+        Duration::from_millis(config.election_timeout_millis),
+    )?;
+    database_manager.set_cluster(cluster.clone());
+
+    let driver = ClusterDriver {
+        client: PeerClient::new(replication_config.username.clone(), replication_config.password.clone()),
+        cluster: cluster.clone(),
+        database_manager: database_manager.clone(),
+        request_timeout: heartbeat_interval,
+    };
+    driver.reconcile_database_roles();
+    start_cluster_replication(
+        replication_config,
+        cluster,
+        heartbeat_interval,
+        database_manager,
+        shutdown_receiver.clone(),
+    );
+    tokio::spawn(async move {
+        let mut heartbeat_interval = interval(heartbeat_interval);
+        loop {
+            tokio::select! {
+                _ = heartbeat_interval.tick() => (),
+                _ = shutdown_receiver.changed() => return,
+            }
+            if let Err(err) = driver.tick().await {
+                event!(Level::WARN, "Cluster member '{}' failed to coordinate: {:?}", driver.cluster.address(), err);
+            }
+            driver.reconcile_database_roles();
+        }
+    });
+    Ok(())
+}
+
+/// Votes for a candidate that has received at least the commits this member has received in each database.
+pub(crate) fn handle_vote_request(
+    database_manager: &DatabaseManager,
+    term: u64,
+    candidate: &str,
+    candidate_received: &HashMap<String, u64>,
+) -> Result<ClusterReply, ClusterError> {
+    let cluster = database_manager.cluster().ok_or(ClusterError::NotConfigured {})?;
+    let is_candidate_up_to_date = database_manager.replication_received().iter().all(|(name, received)| {
+        candidate_received.get(name).is_some_and(|candidate_received| candidate_received >= received)
+    });
+    cluster.handle_vote_request(term, candidate, is_candidate_up_to_date)
+}
+
+pub(crate) fn handle_heartbeat(
+    database_manager: &DatabaseManager,
+    term: u64,
+    leader: &str,
+) -> Result<ClusterReply, ClusterError> {
+    let cluster = database_manager.cluster().ok_or(ClusterError::NotConfigured {})?;
+    cluster.handle_heartbeat(term, leader)
+}
+
+struct ClusterDriver {
+    client: PeerClient,
+    cluster: Arc<Cluster>,
+    database_manager: Arc<DatabaseManager>,
+    request_timeout: Duration,
+}
+
+impl ClusterDriver {
+    async fn tick(&self) -> Result<(), ClusterError> {
+        if !self.cluster.is_leader() {
+            self.may_run_election().await?;
+        }
+        // a newly elected leader announces itself immediately, before followers time out
+        if self.cluster.is_leader() {
+            self.send_heartbeats().await?;
+        }
+        Ok(())
+    }
+
+    async fn may_run_election(&self) -> Result<(), ClusterError> {
+        let Some(term) = self.cluster.may_start_election()? else {
+            return Ok(());
+        };
+        let payload = ClusterVotePayload {
+            term,
+            candidate: self.cluster.address().to_owned(),
+            received: self.database_manager.replication_received(),
+        };
+        let replies = join_all(self.cluster.peers().iter().map(|peer| async {
+            (peer, self.request::<_, ClusterReplyResponse>(peer, "cluster/vote", &payload).await)
+        }))
+        .await;
+        for (peer, reply) in replies {
+            if let Some(reply) = reply {
+                self.cluster.handle_vote_reply(term, peer, reply.into())?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn send_heartbeats(&self) -> Result<(), ClusterError> {
+        let term = self.cluster.term();
+        let payload = ClusterHeartbeatPayload { term, leader: self.cluster.address().to_owned() };
+        let replies = join_all(self.cluster.peers().iter().map(|peer| async {
+            (peer, self.request::<_, ClusterReplyResponse>(peer, "cluster/heartbeat", &payload).await)
+        }))
+        .await;
+        for (peer, reply) in replies {
+            if let Some(reply) = reply {
+                self.cluster.handle_heartbeat_reply(term, peer, reply.into())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Unreachable peers are expected while members restart, and are retried on the next tick.
+    async fn request<P: Serialize, T: DeserializeOwned>(&self, peer: &str, path: &str, payload: &P) -> Option<T> {
+        match timeout(self.request_timeout, self.client.post_json(peer, path, payload)).await {
+            Ok(Ok(response)) => Some(response),
+            Ok(Err(err)) => {
+                event!(Level::DEBUG, "Cluster request to '{}' failed: {:?}", peer, err);
+                None
+            }
+            Err(_) => {
+                event!(Level::DEBUG, "Cluster request to '{}' timed out", peer);
+                None
+            }
+        }
+    }
+
+    /// Databases accept writes only on the leader, and are replicas of the leader's databases on followers.
+    /// The databases of a former leader may have diverged from the current leader's, and are replicated again.
+    fn reconcile_database_roles(&self) {
+        let is_leader = self.cluster.is_leader();
+        let is_former_leader = !is_leader && self.cluster.leader().is_some() && self.cluster.has_led();
+        let mut is_reset = true;
+        for database in self.database_manager.databases().values().cloned().collect::<Vec<_>>() {
+            if !DatabaseManager::is_user_database(database.name()) {
+                continue;
+            }
+            if is_former_leader {
+                let name = database.name().to_owned();
+                drop(database);
+                match self.database_manager.reset_as_replica(&name) {
+                    Ok(()) => event!(Level::INFO, "Database '{}' is replicated again from the cluster leader", name),
+                    Err(err) => {
+                        // retried on the next tick, once the database is no longer in use
+                        event!(Level::WARN, "Database '{}' could not be reset to a replica: {:?}", name, err);
+                        is_reset = false;
+                    }
+                }
+                continue;
+            }
+            match (is_leader, database.is_replica()) {
+                (true, true) => match database.make_primary() {
+                    Ok(()) => {
+                        event!(Level::INFO, "Database '{}' accepts writes on the cluster leader", database.name())
+                    }
+                    Err(err) => {
+                        event!(Level::ERROR, "Database '{}' could not stop replicating: {:?}", database.name(), err)
+                    }
+                },
+                (false, false) => database.make_replica(),
+                _ => (),
+            }
+        }
+        if is_former_leader && is_reset {
+            if let Err(err) = self.cluster.clear_has_led() {
+                event!(
+                    Level::ERROR,
+                    "Cluster member '{}' failed to persist its state: {:?}",
+                    self.cluster.address(),
+                    err
+                );
+            }
+        }
+    }
+}
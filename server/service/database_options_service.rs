@@ -0,0 +1,189 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+use std::sync::Arc;
+
+use concept::{
+    error::{ConceptReadError, ConceptWriteError},
+    type_::{DatabaseSettings, HistoryRetention, RelationIndexThreshold, StorageQuota},
+};
+use database::{
+    database_manager::DatabaseManager,
+    transaction::{SchemaCommitError, TransactionError, TransactionRead, TransactionSchema},
+    Database, DatabaseSalvageError,
+};
+use error::typedb_error;
+use options::TransactionOptions;
+use resource::profile::StorageCounters;
+use storage::{durability_client::WALClient, keyspace::KeyspaceVerification, StorageVerifyError};
+use tracing::{event, Level};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct DatabaseOptions {
+    pub relation_index_threshold: u64,
+    /// The number of commits behind the latest at which read transactions may still be opened
+    pub history_retention: u64,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct StorageUsage {
+    pub usage_in_bytes: u64,
+    pub quota_in_bytes: Option<u64>,
+}
+
+pub(crate) fn get_database_options(
+    database: Arc<Database<WALClient>>,
+) -> Result<DatabaseOptions, DatabaseOptionsError> {
+    let transaction = TransactionRead::open(database, TransactionOptions::default())
+        .map_err(|typedb_source| DatabaseOptionsError::TransactionFailed { typedb_source })?;
+    let threshold = transaction.type_manager.get_relation_index_threshold(transaction.snapshot());
+    let retention = transaction.type_manager.get_history_retention(transaction.snapshot());
+    transaction.close();
+    let RelationIndexThreshold(relation_index_threshold) =
+        threshold.map_err(|typedb_source| DatabaseOptionsError::ConceptRead { typedb_source })?;
+    let HistoryRetention(history_retention) =
+        retention.map_err(|typedb_source| DatabaseOptionsError::ConceptRead { typedb_source })?;
+    Ok(DatabaseOptions { relation_index_threshold, history_retention })
+}
+
+/// Changes the relation index threshold in a schema transaction, so the relation index is rebuilt
+/// atomically with the new threshold becoming visible.
+pub(crate) fn set_relation_index_threshold(
+    database: Arc<Database<WALClient>>,
+    threshold: u64,
+) -> Result<(), DatabaseOptionsError> {
+    let mut transaction = TransactionSchema::open(database, TransactionOptions::default())
+        .map_err(|typedb_source| DatabaseOptionsError::TransactionFailed { typedb_source })?;
+    let snapshot = Arc::get_mut(&mut transaction.snapshot).expect("Expected owning snapshot for database options");
+    let result = transaction.type_manager.set_relation_index_threshold(
+        snapshot,
+        &transaction.thing_manager,
+        RelationIndexThreshold(threshold),
+        StorageCounters::DISABLED,
+    );
+    if let Err(typedb_source) = result {
+        transaction.close();
+        return Err(DatabaseOptionsError::ConceptWrite { typedb_source });
+    }
+    let (_, result) = transaction.commit();
+    result.map(|_| ()).map_err(|typedb_source| DatabaseOptionsError::SchemaCommitFailed { typedb_source })
+}
+
+pub(crate) fn set_history_retention(
+    database: Arc<Database<WALClient>>,
+    retention: u64,
+) -> Result<(), DatabaseOptionsError> {
+    let mut transaction = TransactionSchema::open(database, TransactionOptions::default())
+        .map_err(|typedb_source| DatabaseOptionsError::TransactionFailed { typedb_source })?;
+    let snapshot = Arc::get_mut(&mut transaction.snapshot).expect("Expected owning snapshot for database options");
+    transaction.type_manager.set_history_retention(snapshot, HistoryRetention(retention));
+    let (_, result) = transaction.commit();
+    result.map(|_| ()).map_err(|typedb_source| DatabaseOptionsError::SchemaCommitFailed { typedb_source })
+}
+
+pub(crate) fn get_storage_usage(database: Arc<Database<WALClient>>) -> Result<StorageUsage, DatabaseOptionsError> {
+    let quota =
+        database.get_storage_quota().map_err(|typedb_source| DatabaseOptionsError::ConceptRead { typedb_source })?;
+    Ok(StorageUsage {
+        usage_in_bytes: database.get_storage_size_in_bytes(),
+        quota_in_bytes: quota.map(|StorageQuota(quota)| quota),
+    })
+}
+
+/// Sets or, given no quota, removes the storage quota of a database.
+/// Write transactions are throttled as usage nears the quota, and rejected once it is exceeded.
+pub(crate) fn set_storage_quota(
+    database: Arc<Database<WALClient>>,
+    quota_in_bytes: Option<u64>,
+) -> Result<(), DatabaseOptionsError> {
+    let mut transaction = TransactionSchema::open(database, TransactionOptions::default())
+        .map_err(|typedb_source| DatabaseOptionsError::TransactionFailed { typedb_source })?;
+    let snapshot = Arc::get_mut(&mut transaction.snapshot).expect("Expected owning snapshot for database options");
+    match quota_in_bytes {
+        Some(quota) => transaction.type_manager.set_storage_quota(snapshot, StorageQuota(quota)),
+        None => transaction.type_manager.unset_storage_quota(snapshot),
+    }
+    let (_, result) = transaction.commit();
+    result.map(|_| ()).map_err(|typedb_source| DatabaseOptionsError::SchemaCommitFailed { typedb_source })
+}
+
+/// Verifies the storage of a database. Salvaging closes the database to rebuild its storage from the WAL
+/// if any storage file is corrupt, so it fails while the database is in use.
+pub(crate) fn verify_storage(
+    database_manager: &DatabaseManager,
+    name: &str,
+    salvage: bool,
+) -> Result<Vec<KeyspaceVerification>, DatabaseOptionsError> {
+    let verifications = if salvage {
+        database_manager
+            .salvage_database(name)
+            .map_err(|typedb_source| DatabaseOptionsError::StorageSalvage { typedb_source })?
+    } else {
+        let database = database_manager
+            .database(name)
+            .ok_or_else(|| DatabaseOptionsError::DatabaseDoesNotExist { name: name.to_owned() })?;
+        database.verify_storage().map_err(|typedb_source| DatabaseOptionsError::StorageVerify { typedb_source })?
+    };
+    for verification in &verifications {
+        for corrupt_range in &verification.corrupt_ranges {
+            event!(
+                Level::ERROR,
+                "Database '{}' keyspace '{}' has a corrupt storage file '{}' at level {}{}: {}",
+                name,
+                verification.keyspace,
+                corrupt_range.file,
+                corrupt_range.level,
+                if corrupt_range.salvaged { " (rebuilt from the WAL)" } else { "" },
+                corrupt_range.message
+            );
+        }
+    }
+    Ok(verifications)
+}
+
+/// Replaces the settings of the database in a schema transaction, so they are stored with its schema, and carried
+/// along when the database is cloned or replicated. Like the relation index threshold they include, they apply to
+/// transactions opened once the schema commits.
+pub(crate) fn set_database_settings(
+    database: Arc<Database<WALClient>>,
+    settings: DatabaseSettings,
+) -> Result<(), DatabaseOptionsError> {
+    for (name, value) in [
+        ("transactionTimeoutMillis", settings.transaction_timeout_millis),
+        ("schemaLockAcquireTimeoutMillis", settings.schema_lock_acquire_timeout_millis),
+    ] {
+        if value.is_some_and(|value| value == 0 || value > i64::MAX as u64) {
+            return Err(DatabaseOptionsError::InvalidSetting { name: name.to_owned() });
+        }
+    }
+    let mut transaction = TransactionSchema::open(database, TransactionOptions::default())
+        .map_err(|typedb_source| DatabaseOptionsError::TransactionFailed { typedb_source })?;
+    let snapshot = Arc::get_mut(&mut transaction.snapshot).expect("Expected owning snapshot for database options");
+    let result = transaction.type_manager.set_database_settings(
+        snapshot,
+        &transaction.thing_manager,
+        settings,
+        StorageCounters::DISABLED,
+    );
+    if let Err(typedb_source) = result {
+        transaction.close();
+        return Err(DatabaseOptionsError::ConceptWrite { typedb_source });
+    }
+    let (_, result) = transaction.commit();
+    result.map(|_| ()).map_err(|typedb_source| DatabaseOptionsError::SchemaCommitFailed { typedb_source })
+}
+
+typedb_error! {
+    pub(crate) DatabaseOptionsError(component = "Database options", prefix = "DOP") {
+        TransactionFailed(1, "Transaction failed.", typedb_source: TransactionError),
+        ConceptRead(2, "Error reading concepts.", typedb_source: Box<ConceptReadError>),
+        ConceptWrite(3, "Error writing concepts.", typedb_source: Box<ConceptWriteError>),
+        SchemaCommitFailed(4, "Failed to commit the database options.", typedb_source: SchemaCommitError),
+        InvalidSetting(5, "The database setting '{name}' must be a positive integer.", name: String),
+        StorageVerify(6, "Failed to verify the database storage.", typedb_source: StorageVerifyError),
+        StorageSalvage(7, "Failed to salvage the database storage.", typedb_source: DatabaseSalvageError),
+        DatabaseDoesNotExist(8, "Database '{name}' does not exist.", name: String),
+    }
+}
@@ -0,0 +1,131 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+use std::{sync::Arc, time::SystemTime};
+
+use concept::{
+    error::{ConceptReadError, ConceptWriteError},
+    type_::TimeToLive,
+};
+use database::{
+    transaction::{DataCommitError, SchemaCommitError, TransactionError, TransactionSchema, TransactionWrite},
+    Database,
+};
+use diagnostics::{diagnostics_manager::DiagnosticsManager, metrics::BackgroundTaskKind};
+use encoding::value::label::Label;
+use error::typedb_error;
+use options::TransactionOptions;
+use resource::profile::StorageCounters;
+use storage::durability_client::WALClient;
+use tracing::{event, Level};
+
+/// Deletes the entities and relations which have outlived the time to live of their type,
+/// returning the number of instances deleted.
+///
+/// The deletion runs in a write transaction, so snapshots opened earlier keep seeing the expired instances.
+/// Instances modified concurrently make the cleanup fail on commit, leaving them for the next run.
+/// Instances whose deletion would violate a cardinality constraint are kept and reported.
+pub(crate) fn cleanup_expired_instances(
+    database: Arc<Database<WALClient>>,
+    diagnostics_manager: Arc<DiagnosticsManager>,
+) -> Result<u64, ExpiryError> {
+    let mut transaction = TransactionWrite::open_exempt_from_storage_quota(database, TransactionOptions::default())
+        .map_err(|typedb_source| ExpiryError::TransactionFailed { typedb_source })?;
+    let database_name = transaction.database.name().to_owned();
+    let snapshot =
+        Arc::get_mut(&mut transaction.snapshot).expect("Expected owning snapshot for expired instance cleanup");
+    let mut retained = Vec::new();
+    let result = transaction.thing_manager.delete_expired_objects(
+        snapshot,
+        SystemTime::now(),
+        |completed, total| {
+            diagnostics_manager.submit_background_task_progress(
+                &database_name,
+                BackgroundTaskKind::ExpiredInstanceCleanup,
+                completed,
+                total,
+            )
+        },
+        &mut retained,
+        StorageCounters::DISABLED,
+    );
+    let result = match result {
+        Ok(0) => {
+            transaction.close();
+            Ok(0)
+        }
+        Ok(deleted) => transaction
+            .commit()
+            .1
+            .map(|_| deleted)
+            .map_err(|typedb_source| ExpiryError::DataCommitFailed { typedb_source }),
+        Err(typedb_source) => {
+            transaction.close();
+            Err(ExpiryError::ConceptWrite { typedb_source })
+        }
+    };
+    diagnostics_manager.submit_background_task_finished(&database_name, BackgroundTaskKind::ExpiredInstanceCleanup);
+    for violation in &retained {
+        event!(
+            Level::WARN,
+            "Kept an expired instance in database '{}', since deleting it would violate: {:?}",
+            database_name,
+            violation
+        );
+    }
+    if let Ok(deleted @ 1..) = result {
+        event!(Level::INFO, "Deleted {} expired instances in database '{}'", deleted, database_name);
+    }
+    result
+}
+
+/// Sets or, given no duration, removes the time to live of an entity or relation type and its subtypes.
+/// Only instances created while their type has a time to live expire.
+pub(crate) fn set_time_to_live(
+    database: Arc<Database<WALClient>>,
+    type_label: &str,
+    seconds: Option<u64>,
+) -> Result<(), ExpiryError> {
+    let mut transaction = TransactionSchema::open(database, TransactionOptions::default())
+        .map_err(|typedb_source| ExpiryError::TransactionFailed { typedb_source })?;
+    let object_type = match transaction
+        .type_manager
+        .get_object_type(transaction.snapshot.as_ref(), &Label::build(type_label, None))
+    {
+        Ok(Some(object_type)) => object_type,
+        Ok(None) => {
+            transaction.close();
+            return Err(ExpiryError::ObjectTypeNotFound { label: type_label.to_owned() });
+        }
+        Err(typedb_source) => {
+            transaction.close();
+            return Err(ExpiryError::ConceptRead { typedb_source });
+        }
+    };
+    let snapshot = Arc::get_mut(&mut transaction.snapshot).expect("Expected owning snapshot for time to live update");
+    let result = match seconds {
+        Some(seconds) => {
+            transaction.type_manager.set_object_type_time_to_live(snapshot, object_type, TimeToLive(seconds))
+        }
+        None => transaction.type_manager.unset_object_type_time_to_live(snapshot, object_type),
+    };
+    if let Err(typedb_source) = result {
+        transaction.close();
+        return Err(ExpiryError::ConceptWrite { typedb_source });
+    }
+    let (_, result) = transaction.commit();
+    result.map(|_| ()).map_err(|typedb_source| ExpiryError::SchemaCommitFailed { typedb_source })
+}
+
+typedb_error! {
+    pub(crate) ExpiryError(component = "Instance expiry", prefix = "EXP") {
+        TransactionFailed(1, "Transaction failed.", typedb_source: TransactionError),
+        ConceptRead(2, "Error reading concepts.", typedb_source: Box<ConceptReadError>),
+        ConceptWrite(3, "Error writing concepts.", typedb_source: Box<ConceptWriteError>),
+        ObjectTypeNotFound(4, "Entity or relation type '{label}' not found.", label: String),
+        DataCommitFailed(5, "Failed to commit the deletion of expired instances.", typedb_source: DataCommitError),
+        SchemaCommitFailed(6, "Failed to commit the time to live.", typedb_source: SchemaCommitError),
+    }
+}
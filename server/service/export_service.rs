@@ -6,7 +6,7 @@
 use concept::error::ConceptReadError;
 use database::transaction::{TransactionError, TransactionRead};
 use error::typedb_error;
-use ir::pipeline::FunctionReadError;
+use function::FunctionError;
 use storage::durability_client::DurabilityClient;
 
 pub(crate) fn get_transaction_schema<D: DurabilityClient>(
@@ -42,7 +42,7 @@ typedb_error! {
     pub(crate) DatabaseExportError(component = "Database export", prefix = "DBE") {
         TransactionFailed(1, "Transaction failed.", typedb_source: TransactionError),
         ConceptRead(2, "Error reading concepts.", typedb_source: Box<ConceptReadError>),
-        FunctionRead(3, "Error reading functions.", typedb_source: FunctionReadError),
+        FunctionRead(3, "Error reading functions.", typedb_source: FunctionError),
         ShutdownInterrupt(4, "Execution interrupted by a shutdown signal."),
         ClientChannelIsClosed(5, "Client channel is closed."),
     }
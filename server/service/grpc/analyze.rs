@@ -32,12 +32,15 @@ use typedb_protocol::{
     analyzed_conjunction::{constraint as structure_constraint, constraint_vertex},
 };
 
-use crate::service::grpc::{
-    concept::{
-        encode_attribute_type, encode_entity_type, encode_relation_type, encode_role_type, encode_value,
-        encode_value_type,
+use crate::service::{
+    grpc::{
+        concept::{
+            encode_attribute_type, encode_entity_type, encode_relation_type, encode_role_type, encode_value,
+            encode_value_type,
+        },
+        document::encode_kind,
     },
-    document::encode_kind,
+    intern_pool::InternPool,
 };
 
 pub(crate) struct PipelineStructureContext<'a, Snapshot: ReadableSnapshot> {
@@ -739,13 +742,16 @@ fn encode_type(
     type_manager: &TypeManager,
 ) -> Result<typedb_protocol::Type, Box<ConceptReadError>> {
     use typedb_protocol::r#type::Type as TypeProto;
+    let intern_pool = &InternPool::new();
     let encoded = match type_ {
-        Type::Entity(entity) => TypeProto::EntityType(encode_entity_type(entity, snapshot, type_manager)?),
-        Type::Relation(relation) => TypeProto::RelationType(encode_relation_type(relation, snapshot, type_manager)?),
+        Type::Entity(entity) => TypeProto::EntityType(encode_entity_type(entity, snapshot, type_manager, intern_pool)?),
+        Type::Relation(relation) => {
+            TypeProto::RelationType(encode_relation_type(relation, snapshot, type_manager, intern_pool)?)
+        }
         Type::Attribute(attribute) => {
-            TypeProto::AttributeType(encode_attribute_type(attribute, snapshot, type_manager)?)
+            TypeProto::AttributeType(encode_attribute_type(attribute, snapshot, type_manager, intern_pool)?)
         }
-        Type::RoleType(role) => TypeProto::RoleType(encode_role_type(role, snapshot, type_manager)?),
+        Type::RoleType(role) => TypeProto::RoleType(encode_role_type(role, snapshot, type_manager, intern_pool)?),
     };
     Ok(typedb_protocol::Type { r#type: Some(encoded) })
 }
@@ -13,7 +13,7 @@ use concept::{
     thing::{attribute::Attribute, entity::Entity, relation::Relation, thing_manager::ThingManager, ThingAPI},
     type_::{
         attribute_type::AttributeType, entity_type::EntityType, relation_type::RelationType, role_type::RoleType,
-        type_manager::TypeManager, TypeAPI,
+        type_manager::TypeManager,
     },
 };
 use encoding::value::{
@@ -23,6 +23,8 @@ use error::unimplemented_feature;
 use resource::profile::StorageCounters;
 use storage::snapshot::ReadableSnapshot;
 
+use crate::service::intern_pool::InternPool;
+
 pub(crate) fn encode_thing_concept(
     thing: &Thing,
     snapshot: &impl ReadableSnapshot,
@@ -30,6 +32,7 @@ pub(crate) fn encode_thing_concept(
     thing_manager: &ThingManager,
     include_instance_types: bool,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::Concept, Box<ConceptReadError>> {
     let encoded = match thing {
         Thing::Entity(entity) => typedb_protocol::concept::Concept::Entity(encode_entity(
@@ -37,12 +40,14 @@ pub(crate) fn encode_thing_concept(
             snapshot,
             type_manager,
             include_instance_types,
+            intern_pool,
         )?),
         Thing::Relation(relation) => typedb_protocol::concept::Concept::Relation(encode_relation(
             relation,
             snapshot,
             type_manager,
             include_instance_types,
+            intern_pool,
         )?),
         Thing::Attribute(attribute) => typedb_protocol::concept::Concept::Attribute(encode_attribute(
             attribute,
@@ -51,6 +56,7 @@ pub(crate) fn encode_thing_concept(
             thing_manager,
             include_instance_types,
             storage_counters,
+            intern_pool,
         )?),
     };
     Ok(typedb_protocol::Concept { concept: Some(encoded) })
@@ -61,11 +67,12 @@ pub(crate) fn encode_entity(
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
     include_instance_types: bool,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::Entity, Box<ConceptReadError>> {
     Ok(typedb_protocol::Entity {
         iid: Vec::from(entity.iid()),
         entity_type: if include_instance_types {
-            Some(encode_entity_type(&entity.type_(), snapshot, type_manager)?)
+            Some(encode_entity_type(&entity.type_(), snapshot, type_manager, intern_pool)?)
         } else {
             None
         },
@@ -77,11 +84,12 @@ pub(crate) fn encode_relation(
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
     include_instance_types: bool,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::Relation, Box<ConceptReadError>> {
     Ok(typedb_protocol::Relation {
         iid: Vec::from(relation.iid()),
         relation_type: if include_instance_types {
-            Some(encode_relation_type(&relation.type_(), snapshot, type_manager)?)
+            Some(encode_relation_type(&relation.type_(), snapshot, type_manager, intern_pool)?)
         } else {
             None
         },
@@ -95,15 +103,18 @@ pub(crate) fn encode_attribute(
     thing_manager: &ThingManager,
     include_instance_types: bool,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::Attribute, Box<ConceptReadError>> {
+    let attribute_type = if include_instance_types {
+        Some(encode_attribute_type(&attribute.type_(), snapshot, type_manager, intern_pool)?)
+    } else {
+        None
+    };
+    let interned = intern_pool.attribute(attribute, snapshot, thing_manager, storage_counters.clone())?;
     Ok(typedb_protocol::Attribute {
         iid: Vec::from(attribute.iid()),
-        value: Some(encode_value(attribute.get_value(snapshot, thing_manager, storage_counters)?)),
-        attribute_type: if include_instance_types {
-            Some(encode_attribute_type(&attribute.type_(), snapshot, type_manager)?)
-        } else {
-            None
-        },
+        value: Some(encode_value(interned.get_value(snapshot, thing_manager, storage_counters)?)),
+        attribute_type,
     })
 }
 
@@ -111,19 +122,29 @@ pub(crate) fn encode_type_concept(
     type_: &Type,
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::Concept, Box<ConceptReadError>> {
     let encoded = match type_ {
-        Type::Entity(entity) => {
-            typedb_protocol::concept::Concept::EntityType(encode_entity_type(entity, snapshot, type_manager)?)
-        }
-        Type::Relation(relation) => {
-            typedb_protocol::concept::Concept::RelationType(encode_relation_type(relation, snapshot, type_manager)?)
-        }
-        Type::Attribute(attribute) => {
-            typedb_protocol::concept::Concept::AttributeType(encode_attribute_type(attribute, snapshot, type_manager)?)
-        }
+        Type::Entity(entity) => typedb_protocol::concept::Concept::EntityType(encode_entity_type(
+            entity,
+            snapshot,
+            type_manager,
+            intern_pool,
+        )?),
+        Type::Relation(relation) => typedb_protocol::concept::Concept::RelationType(encode_relation_type(
+            relation,
+            snapshot,
+            type_manager,
+            intern_pool,
+        )?),
+        Type::Attribute(attribute) => typedb_protocol::concept::Concept::AttributeType(encode_attribute_type(
+            attribute,
+            snapshot,
+            type_manager,
+            intern_pool,
+        )?),
         Type::RoleType(role) => {
-            typedb_protocol::concept::Concept::RoleType(encode_role_type(role, snapshot, type_manager)?)
+            typedb_protocol::concept::Concept::RoleType(encode_role_type(role, snapshot, type_manager, intern_pool)?)
         }
     };
     Ok(typedb_protocol::Concept { concept: Some(encoded) })
@@ -133,9 +154,10 @@ pub(crate) fn encode_entity_type(
     entity: &EntityType,
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::EntityType, Box<ConceptReadError>> {
     Ok(typedb_protocol::EntityType {
-        label: entity.get_label(snapshot, type_manager)?.scoped_name().as_str().to_string(),
+        label: intern_pool.label(Type::Entity(*entity), snapshot, type_manager)?.to_string(),
     })
 }
 
@@ -143,9 +165,10 @@ pub(crate) fn encode_relation_type(
     relation: &RelationType,
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::RelationType, Box<ConceptReadError>> {
     Ok(typedb_protocol::RelationType {
-        label: relation.get_label(snapshot, type_manager)?.scoped_name().as_str().to_string(),
+        label: intern_pool.label(Type::Relation(*relation), snapshot, type_manager)?.to_string(),
     })
 }
 
@@ -153,9 +176,10 @@ pub(crate) fn encode_attribute_type(
     attribute: &AttributeType,
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::AttributeType, Box<ConceptReadError>> {
     Ok(typedb_protocol::AttributeType {
-        label: attribute.get_label(snapshot, type_manager)?.scoped_name().as_str().to_string(),
+        label: intern_pool.label(Type::Attribute(*attribute), snapshot, type_manager)?.to_string(),
         value_type: {
             attribute
                 .get_value_type_without_source(snapshot, type_manager)?
@@ -169,8 +193,11 @@ pub(crate) fn encode_role_type(
     role: &RoleType,
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::RoleType, Box<ConceptReadError>> {
-    Ok(typedb_protocol::RoleType { label: role.get_label(snapshot, type_manager)?.scoped_name().as_str().to_owned() })
+    Ok(typedb_protocol::RoleType {
+        label: intern_pool.label(Type::RoleType(*role), snapshot, type_manager)?.to_string(),
+    })
 }
 
 pub(crate) fn encode_value_type(
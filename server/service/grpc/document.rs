@@ -15,8 +15,12 @@ use itertools::Itertools;
 use resource::{constants::server::DEFAULT_INCLUDE_INSTANCE_TYPES_FETCH, profile::StorageCounters};
 use storage::snapshot::ReadableSnapshot;
 
-use crate::service::grpc::concept::{
-    encode_attribute, encode_attribute_type, encode_entity_type, encode_relation_type, encode_role_type, encode_value,
+use crate::service::{
+    grpc::concept::{
+        encode_attribute, encode_attribute_type, encode_entity_type, encode_relation_type, encode_role_type,
+        encode_value,
+    },
+    intern_pool::InternPool,
 };
 
 pub(crate) fn encode_document(
@@ -26,9 +30,18 @@ pub(crate) fn encode_document(
     thing_manager: &ThingManager,
     parameters: &ParameterRegistry,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::ConceptDocument, Box<ConceptReadError>> {
     Ok(typedb_protocol::ConceptDocument {
-        root: Some(encode_node(document.root, snapshot, type_manager, thing_manager, parameters, storage_counters)?),
+        root: Some(encode_node(
+            document.root,
+            snapshot,
+            type_manager,
+            thing_manager,
+            parameters,
+            storage_counters,
+            intern_pool,
+        )?),
     })
 }
 
@@ -39,6 +52,7 @@ fn encode_node(
     thing_manager: &ThingManager,
     parameters: &ParameterRegistry,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::concept_document::Node, Box<ConceptReadError>> {
     match node {
         DocumentNode::List(list) => Ok(typedb_protocol::concept_document::Node {
@@ -49,6 +63,7 @@ fn encode_node(
                 thing_manager,
                 parameters,
                 storage_counters,
+                intern_pool,
             )?)),
         }),
         DocumentNode::Map(map) => Ok(typedb_protocol::concept_document::Node {
@@ -59,6 +74,7 @@ fn encode_node(
                 thing_manager,
                 parameters,
                 storage_counters,
+                intern_pool,
             )?)),
         }),
         DocumentNode::Leaf(leaf) => Ok(typedb_protocol::concept_document::Node {
@@ -68,6 +84,7 @@ fn encode_node(
                 type_manager,
                 thing_manager,
                 storage_counters,
+                intern_pool,
             )?)),
         }),
     }
@@ -80,14 +97,22 @@ fn encode_map(
     thing_manager: &ThingManager,
     parameters: &ParameterRegistry,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::concept_document::node::Map, Box<ConceptReadError>> {
     let encoded_map = match map {
         DocumentMap::UserKeys(map) => {
             let mut encoded_map = HashMap::with_capacity(map.len());
             for (key, value) in map.into_iter() {
                 let key_name = parameters.fetch_key(&key).expect("Expected key in parameters to get its name");
-                let encoded_value =
-                    encode_node(value, snapshot, type_manager, thing_manager, parameters, storage_counters.clone())?;
+                let encoded_value = encode_node(
+                    value,
+                    snapshot,
+                    type_manager,
+                    thing_manager,
+                    parameters,
+                    storage_counters.clone(),
+                    intern_pool,
+                )?;
                 encoded_map.insert(key_name.to_owned(), encoded_value);
             }
             encoded_map
@@ -95,8 +120,15 @@ fn encode_map(
         DocumentMap::GeneratedKeys(map) => {
             let mut encoded_map = HashMap::with_capacity(map.len());
             for (key, value) in map.into_iter() {
-                let encoded_value =
-                    encode_node(value, snapshot, type_manager, thing_manager, parameters, storage_counters.clone())?;
+                let encoded_value = encode_node(
+                    value,
+                    snapshot,
+                    type_manager,
+                    thing_manager,
+                    parameters,
+                    storage_counters.clone(),
+                    intern_pool,
+                )?;
                 encoded_map.insert(key.scoped_name().as_str().to_owned(), encoded_value);
             }
             encoded_map
@@ -113,11 +145,14 @@ fn encode_list(
     thing_manager: &ThingManager,
     parameters: &ParameterRegistry,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::concept_document::node::List, Box<ConceptReadError>> {
     let encoded_list = list
         .list
         .into_iter()
-        .map(|node| encode_node(node, snapshot, type_manager, thing_manager, parameters, storage_counters.clone()))
+        .map(|node| {
+            encode_node(node, snapshot, type_manager, thing_manager, parameters, storage_counters.clone(), intern_pool)
+        })
         .try_collect()?;
     Ok(typedb_protocol::concept_document::node::List { list: encoded_list })
 }
@@ -128,6 +163,7 @@ fn encode_leaf(
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::concept_document::node::Leaf, Box<ConceptReadError>> {
     match leaf {
         DocumentLeaf::Empty => Ok(typedb_protocol::concept_document::node::Leaf {
@@ -142,6 +178,7 @@ fn encode_leaf(
                         &entity_type,
                         snapshot,
                         type_manager,
+                        intern_pool,
                     )?)
                 }
                 Concept::Type(Type::Relation(relation_type)) => {
@@ -149,6 +186,7 @@ fn encode_leaf(
                         &relation_type,
                         snapshot,
                         type_manager,
+                        intern_pool,
                     )?)
                 }
                 Concept::Type(Type::Attribute(attribute_type)) => {
@@ -156,6 +194,7 @@ fn encode_leaf(
                         &attribute_type,
                         snapshot,
                         type_manager,
+                        intern_pool,
                     )?)
                 }
                 Concept::Type(Type::RoleType(role_type)) => {
@@ -163,6 +202,7 @@ fn encode_leaf(
                         &role_type,
                         snapshot,
                         type_manager,
+                        intern_pool,
                     )?)
                 }
                 Concept::Thing(Thing::Entity(_entity)) => {
@@ -179,6 +219,7 @@ fn encode_leaf(
                         thing_manager,
                         DEFAULT_INCLUDE_INSTANCE_TYPES_FETCH, // TODO: May it be affected by QueryOptions?
                         storage_counters,
+                        intern_pool,
                     )?)
                 }
                 Concept::Value(value) => {
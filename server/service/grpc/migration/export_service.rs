@@ -10,7 +10,7 @@ use std::{
 };
 
 use database::{migration::Checksums, transaction::TransactionRead, Database};
-use options::TransactionOptions;
+use options::{IsolationLevel, TransactionOptions};
 use resource::{constants::common::SECONDS_IN_DAY, profile::StorageCounters, server_info::ServerInfo};
 use storage::durability_client::WALClient;
 use tokio::sync::{mpsc::Sender, watch};
@@ -276,6 +276,10 @@ impl DatabaseExportService {
             parallel: Self::OPTIONS_PARALLEL,
             schema_lock_acquire_timeout_millis: Self::OPTIONS_SCHEMA_LOCK_ACQUIRE_TIMEOUT_MILLIS,
             transaction_timeout_millis: Self::OPTIONS_TRANSACTION_TIMEOUT_MILLIS,
+            cascade_type_deletion: false,
+            defer_validation: false,
+            read_at_sequence_number: None,
+            isolation_level: IsolationLevel::Snapshot,
         }
     }
 }
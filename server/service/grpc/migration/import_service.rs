@@ -56,6 +56,8 @@ pub(crate) struct DatabaseImportService {
     response_sender: ResponseSender,
     shutdown_receiver: watch::Receiver<()>,
 
+    preserve_imported_iids: bool,
+
     database_importer: Option<DatabaseImporter>,
     is_done: bool,
     start: Option<Instant>,
@@ -68,6 +70,7 @@ impl DatabaseImportService {
         request_stream: Streaming<ProtocolClient>,
         response_sender: ResponseSender,
         shutdown_receiver: watch::Receiver<()>,
+        preserve_imported_iids: bool,
     ) -> Self {
         Self {
             database_manager,
@@ -75,6 +78,7 @@ impl DatabaseImportService {
             request_stream,
             response_sender,
             shutdown_receiver,
+            preserve_imported_iids,
             database_importer: None,
             is_done: false,
             start: None,
@@ -184,8 +188,11 @@ impl DatabaseImportService {
             });
         }
 
-        let database_importer = DatabaseImporter::new(self.database_manager.clone(), name)
+        let mut database_importer = DatabaseImporter::new(self.database_manager.clone(), name)
             .map_err(|typedb_source| DatabaseImportServiceError::DatabaseImport { typedb_source })?;
+        if self.preserve_imported_iids {
+            database_importer.preserve_original_iids();
+        }
         self.database_importer = Some(database_importer);
 
         self.database_importer
@@ -5,6 +5,7 @@
  */
 
 pub mod analyze;
+mod answer_encoder;
 pub(crate) mod authenticator;
 pub(crate) mod concept;
 mod diagnostics;
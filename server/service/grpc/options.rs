@@ -4,24 +4,36 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use options::{QueryOptions, TransactionOptions};
+//! The protocol has no field for some of the options, which protocol requests therefore leave at their defaults:
+//! they can only be set over HTTP.
+
+use options::{QueryOptions, TemporalFormat, TransactionOptions};
 use resource::constants::server::{
-    DEFAULT_ANSWER_COUNT_LIMIT_GRPC, DEFAULT_INCLUDE_INSTANCE_TYPES, DEFAULT_PREFETCH_SIZE,
-    DEFAULT_SCHEMA_LOCK_ACQUIRE_TIMEOUT_MILLIS, DEFAULT_TRANSACTION_PARALLEL, DEFAULT_TRANSACTION_TIMEOUT_MILLIS,
+    DEFAULT_ANSWER_BATCH_SIZE, DEFAULT_ANSWER_COUNT_LIMIT_GRPC, DEFAULT_DRY_RUN, DEFAULT_INCLUDE_INSTANCE_IIDS,
+    DEFAULT_INCLUDE_INSTANCE_TYPES, DEFAULT_INCLUDE_QUERY_STATS, DEFAULT_INCLUDE_VALUE_TYPES,
+    DEFAULT_OMIT_NULL_COLUMNS, DEFAULT_PREFETCH_SIZE,
 };
 use typedb_protocol::options::{Query as QueryOptionsProto, Transaction as TransactionOptionsProto};
 
-pub(crate) fn transaction_options_from_proto(proto: Option<TransactionOptionsProto>) -> TransactionOptions {
+/// Resolves the options of a transaction, taking those not set from the defaults, such as the settings of the database.
+pub(crate) fn transaction_options_from_proto(
+    proto: Option<TransactionOptionsProto>,
+    defaults: TransactionOptions,
+) -> TransactionOptions {
     let Some(proto) = proto else {
-        return TransactionOptions::default();
+        return defaults;
     };
 
     TransactionOptions {
-        parallel: proto.parallel.unwrap_or(DEFAULT_TRANSACTION_PARALLEL),
+        parallel: proto.parallel.unwrap_or(defaults.parallel),
         schema_lock_acquire_timeout_millis: proto
             .schema_lock_acquire_timeout_millis
-            .unwrap_or(DEFAULT_SCHEMA_LOCK_ACQUIRE_TIMEOUT_MILLIS),
-        transaction_timeout_millis: proto.transaction_timeout_millis.unwrap_or(DEFAULT_TRANSACTION_TIMEOUT_MILLIS),
+            .unwrap_or(defaults.schema_lock_acquire_timeout_millis),
+        transaction_timeout_millis: proto.transaction_timeout_millis.unwrap_or(defaults.transaction_timeout_millis),
+        cascade_type_deletion: defaults.cascade_type_deletion,
+        defer_validation: defaults.defer_validation,
+        read_at_sequence_number: None,
+        isolation_level: defaults.isolation_level,
     }
 }
 
@@ -32,8 +44,17 @@ pub(crate) fn query_options_from_proto(proto: Option<QueryOptionsProto>) -> Quer
 
     QueryOptions {
         include_instance_types: proto.include_instance_types.unwrap_or(DEFAULT_INCLUDE_INSTANCE_TYPES),
+        // These options only shape JSON answers, so they are HTTP-only: protocol answers are typed and always
+        // carry IIDs, value types and temporal values in full
+        include_instance_iids: DEFAULT_INCLUDE_INSTANCE_IIDS,
+        include_value_types: DEFAULT_INCLUDE_VALUE_TYPES,
+        temporal_format: TemporalFormat::default(),
         answer_count_limit: DEFAULT_ANSWER_COUNT_LIMIT_GRPC,
         prefetch_size: proto.prefetch_size.map(|value| value as usize).unwrap_or(DEFAULT_PREFETCH_SIZE),
+        answer_batch_size: DEFAULT_ANSWER_BATCH_SIZE,
         include_query_structure: proto.include_query_structure.unwrap_or(false),
+        omit_null_columns: DEFAULT_OMIT_NULL_COLUMNS,
+        include_query_stats: DEFAULT_INCLUDE_QUERY_STATS,
+        dry_run: DEFAULT_DRY_RUN,
     }
 }
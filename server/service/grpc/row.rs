@@ -14,6 +14,7 @@ use storage::snapshot::ReadableSnapshot;
 
 use crate::service::{
     grpc::concept::{encode_thing_concept, encode_type_concept, encode_value},
+    intern_pool::InternPool,
     IncludeInvolvedBlocks,
 };
 
@@ -26,6 +27,7 @@ pub(crate) fn encode_row(
     include_instance_types: bool,
     include_involved_blocks: &IncludeInvolvedBlocks,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::ConceptRow, Box<ConceptReadError>> {
     // TODO: multiplicity?
     let mut encoded_row = Vec::with_capacity(columns.len());
@@ -38,6 +40,7 @@ pub(crate) fn encode_row(
             thing_manager,
             include_instance_types,
             storage_counters.clone(),
+            intern_pool,
         )?;
         encoded_row.push(typedb_protocol::RowEntry { entry: Some(row_entry) });
     }
@@ -69,12 +72,16 @@ pub(crate) fn encode_row_entry(
     thing_manager: &ThingManager,
     include_instance_types: bool,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<typedb_protocol::row_entry::Entry, Box<ConceptReadError>> {
     match variable_value {
         VariableValue::None => Ok(typedb_protocol::row_entry::Entry::Empty(typedb_protocol::row_entry::Empty {})),
-        VariableValue::Type(type_) => {
-            Ok(typedb_protocol::row_entry::Entry::Concept(encode_type_concept(type_, snapshot, type_manager)?))
-        }
+        VariableValue::Type(type_) => Ok(typedb_protocol::row_entry::Entry::Concept(encode_type_concept(
+            type_,
+            snapshot,
+            type_manager,
+            intern_pool,
+        )?)),
         VariableValue::Thing(thing) => Ok(typedb_protocol::row_entry::Entry::Concept(encode_thing_concept(
             thing,
             snapshot,
@@ -82,6 +89,7 @@ pub(crate) fn encode_row_entry(
             thing_manager,
             include_instance_types,
             storage_counters.clone(),
+            intern_pool,
         )?)),
         VariableValue::Value(value) => Ok(typedb_protocol::row_entry::Entry::Value(encode_value(value.as_reference()))),
         VariableValue::ThingList(thing_list) => {
@@ -94,6 +102,7 @@ pub(crate) fn encode_row_entry(
                     thing_manager,
                     include_instance_types,
                     storage_counters.clone(),
+                    intern_pool,
                 )?);
             }
             Ok(typedb_protocol::row_entry::Entry::ConceptList(typedb_protocol::row_entry::ConceptList {
@@ -6,6 +6,7 @@
 
 use std::{
     collections::{HashMap, VecDeque},
+    mem,
     ops::{
         ControlFlow,
         ControlFlow::{Break, Continue},
@@ -18,10 +19,7 @@ use compiler::query_structure::PipelineStructure;
 use concept::{thing::thing_manager::ThingManager, type_::type_manager::TypeManager};
 use database::{
     database_manager::DatabaseManager,
-    query::{
-        execute_schema_query, execute_write_query_in_schema, execute_write_query_in_write, StreamQueryOutputDescriptor,
-        WriteQueryAnswer, WriteQueryResult,
-    },
+    query::{StreamQueryOutputDescriptor, WriteQueryAnswer, WriteQueryResult},
     transaction::{TransactionRead, TransactionSchema, TransactionWrite},
 };
 use diagnostics::{
@@ -39,7 +37,10 @@ use itertools::{Either, Itertools};
 use lending_iterator::LendingIterator;
 use options::QueryOptions;
 use query::error::QueryError;
-use resource::profile::{EncodingProfile, QueryProfile, StorageCounters};
+use resource::{
+    memory::{MemoryComponent, MEMORY_MANAGER},
+    profile::{EncodingProfile, QueryProfile, StorageCounters},
+};
 use storage::snapshot::ReadableSnapshot;
 use tokio::{
     sync::{
@@ -57,14 +58,14 @@ use typedb_protocol::{
     query::Type::{Read, Write},
     transaction::{stream_signal::Req, Server as ProtocolServer},
 };
-use typeql::{parse_query, query::SchemaQuery};
+use typeql::query::SchemaQuery;
 use uuid::Uuid;
 
 use crate::service::{
     grpc::{
         analyze::{encode_analyzed_pipeline_for_query, encode_analyzed_query},
+        answer_encoder::ProtocolAnswerEncoder,
         diagnostics::run_with_diagnostics_async,
-        document::encode_document,
         error::{IntoGrpcStatus, IntoProtocolErrorMessage, ProtocolError},
         options::{query_options_from_proto, transaction_options_from_proto},
         response_builders::transaction::{
@@ -77,13 +78,20 @@ use crate::service::{
             transaction_server_res_parts_query_part, transaction_server_res_query_res,
             transaction_server_res_rollback_res,
         },
-        row::encode_row,
     },
+    intern_pool::InternPool,
     may_encode_pipeline_structure,
+    transaction_registry::{
+        next_control, TransactionControl, TransactionEndpoint, TransactionRegistration, TransactionReservation,
+    },
     transaction_service::{
-        init_transaction_timeout, is_write_pipeline, with_readable_transaction, Transaction, TransactionServiceError,
+        dispatch_analyse_query, dispatch_query, execute_schema_query_in_transaction, init_transaction_timeout,
+        is_write_pipeline, spawn_blocking_execute_write_query, validate_query_options, with_readable_transaction,
+        AnswerEncoder, DispatchedQuery, QueueOptions, QueuedQuery, SchemaQueryOutcome, Transaction,
+        TransactionServiceError,
     },
-    IncludeInvolvedBlocks,
+    trigger_service::{commit_with_triggers, TriggerRunner},
+    IncludeInvolvedBlocks, TransactionType,
 };
 
 macro_rules! unwrap_or_execute_and_return {
@@ -102,20 +110,25 @@ macro_rules! unwrap_or_execute_and_return {
 pub(crate) struct TransactionService {
     database_manager: Arc<DatabaseManager>,
     diagnostics_manager: Arc<DiagnosticsManager>,
+    trigger_runner: Arc<TriggerRunner>,
 
     request_stream: Streaming<typedb_protocol::transaction::Client>,
     response_sender: Sender<Result<ProtocolServer, Status>>,
     query_interrupt_sender: broadcast::Sender<InterruptType>,
     query_interrupt_receiver: ExecutionInterrupt,
     shutdown_receiver: watch::Receiver<()>,
+    reservation: Option<TransactionReservation>,
+    registration: Option<TransactionRegistration>,
 
     timeout_at: Instant,
     schema_lock_acquire_timeout_millis: Option<u64>,
     network_latency_millis: Option<u64>,
+    answer_window: usize,
 
     is_open: bool,
     transaction: Option<Transaction>,
-    query_queue: VecDeque<(Uuid, QueueOptions, typeql::query::Pipeline, String)>,
+    intern_pool: InternPool,
+    query_queue: VecDeque<QueuedQuery<Uuid>>,
     query_responders: HashMap<Uuid, (JoinHandle<()>, QueryStreamTransmitter)>,
     running_write_query: Option<(Uuid, JoinHandle<(Transaction, WriteQueryResult)>)>,
 }
@@ -221,15 +234,16 @@ impl StreamQueryResponse {
 
 enum StreamingCondition {
     Count(usize),
-    Duration(Instant, usize),
+    // streams for up to the network latency, but no more than the answer window
+    Duration(Instant, usize, usize),
 }
 
 impl StreamingCondition {
     fn continue_(&self, iteration: usize) -> bool {
         match self {
             StreamingCondition::Count(count) => iteration < *count,
-            StreamingCondition::Duration(start_time, limit_millis) => {
-                (Instant::now().duration_since(*start_time).as_millis() as usize) < *limit_millis
+            StreamingCondition::Duration(start_time, limit_millis, window) => {
+                iteration < *window && (Instant::now().duration_since(*start_time).as_millis() as usize) < *limit_millis
             }
         }
     }
@@ -239,28 +253,36 @@ impl TransactionService {
     pub(crate) fn new(
         database_manager: Arc<DatabaseManager>,
         diagnostics_manager: Arc<DiagnosticsManager>,
+        trigger_runner: Arc<TriggerRunner>,
         request_stream: Streaming<typedb_protocol::transaction::Client>,
         response_sender: Sender<Result<ProtocolServer, Status>>,
+        reservation: TransactionReservation,
         shutdown_receiver: watch::Receiver<()>,
+        answer_window: usize,
     ) -> Self {
         let (query_interrupt_sender, query_interrupt_receiver) = broadcast::channel(1);
 
         Self {
             database_manager,
             diagnostics_manager,
+            trigger_runner,
 
             request_stream,
             response_sender,
             query_interrupt_sender,
             query_interrupt_receiver: ExecutionInterrupt::new(query_interrupt_receiver),
             shutdown_receiver,
+            reservation: Some(reservation),
+            registration: None,
 
             timeout_at: init_transaction_timeout(None),
             schema_lock_acquire_timeout_millis: None,
             network_latency_millis: None,
+            answer_window,
 
             is_open: false,
             transaction: None,
+            intern_pool: InternPool::new(),
             query_queue: VecDeque::with_capacity(20),
             query_responders: HashMap::new(),
             running_write_query: None,
@@ -293,6 +315,9 @@ impl TransactionService {
                             Ok(Continue(()))
                         }
                     }
+                    control = next_control(&mut self.registration) => {
+                        self.handle_control(control).await
+                    }
                     next = self.request_stream.next() => {
                         self.handle_next(next).await
                     }
@@ -309,6 +334,9 @@ impl TransactionService {
                         self.do_close().await;
                         return;
                     }
+                    control = next_control(&mut self.registration) => {
+                        self.handle_control(control).await
+                    }
                     next = self.request_stream.next() => {
                         self.handle_next(next).await
                     }
@@ -335,6 +363,22 @@ impl TransactionService {
         }
     }
 
+    async fn handle_control(&mut self, control: TransactionControl) -> Result<ControlFlow<(), ()>, Status> {
+        match control {
+            TransactionControl::StagedWrites(responder) => {
+                // the transaction is unavailable while a write query runs, in which case the size is unknown
+                let _ = responder.send(self.transaction.as_ref().and_then(Transaction::staged_writes));
+                Ok(Continue(()))
+            }
+            TransactionControl::ForceClose(responder) => {
+                event!(Level::TRACE, "Transaction force-closed, closing transaction service.");
+                self.do_close().await;
+                let _ = responder.send(());
+                Err(TransactionServiceError::TransactionForceClosed {}.into_error_message().into_status())
+            }
+        }
+    }
+
     // TODO: any method using `Result<ControlFlow<(), ()>, Status>` should really be `ControlFlow<Result<(), Status>, ()>`
     async fn handle_next(
         &mut self,
@@ -504,9 +548,6 @@ impl TransactionService {
     ) -> Result<ControlFlow<(), ()>, Status> {
         let receive_time = Instant::now();
         self.network_latency_millis = Some(open_req.network_latency_millis);
-        let transaction_options = transaction_options_from_proto(open_req.options);
-        let transaction_timeout_millis = transaction_options.transaction_timeout_millis;
-
         let transaction_type = typedb_protocol::transaction::Type::try_from(open_req.r#type)
             .map_err(|_| ProtocolError::UnrecognisedTransactionType { enum_variant: open_req.r#type }.into_status())?;
 
@@ -514,6 +555,13 @@ impl TransactionService {
         let database = self.database_manager.database(database_name.as_ref()).ok_or_else(|| {
             TransactionServiceError::DatabaseNotFound { name: database_name.clone() }.into_error_message().into_status()
         })?;
+        let transaction_options = transaction_options_from_proto(open_req.options, database.transaction_defaults());
+        let transaction_timeout_millis = transaction_options.transaction_timeout_millis;
+        if transaction_type != typedb_protocol::transaction::Type::Read {
+            self.database_manager.ensure_cluster_leader().map_err(|typedb_source| {
+                TransactionServiceError::Cluster { typedb_source }.into_error_message().into_status()
+            })?;
+        }
 
         let transaction = match transaction_type {
             typedb_protocol::transaction::Type::Read => {
@@ -548,6 +596,20 @@ impl TransactionService {
             }
         };
         self.diagnostics_manager.increment_load_count(ClientEndpoint::Grpc, &database_name, transaction.load_kind());
+        let transaction_type = match &transaction {
+            Transaction::Read(_) => TransactionType::Read,
+            Transaction::Write(_) => TransactionType::Write,
+            Transaction::Schema(_) => TransactionType::Schema,
+        };
+        self.registration = self.reservation.take().map(|reservation| {
+            reservation.register(
+                database_name,
+                transaction_type,
+                transaction_timeout_millis,
+                None,
+                TransactionEndpoint::Grpc,
+            )
+        });
         self.transaction = Some(transaction);
         self.timeout_at = init_transaction_timeout(Some(transaction_timeout_millis));
         self.is_open = true;
@@ -581,6 +643,7 @@ impl TransactionService {
         self.finish_queued_write_queries(InterruptType::TransactionCommitted).await?;
 
         let diagnostics_manager = self.diagnostics_manager.clone();
+        let trigger_runner = self.trigger_runner.clone();
         match self.transaction.take().expect("Expected existing transaction") {
             Transaction::Read(transaction) => {
                 self.transaction = Some(Transaction::Read(transaction));
@@ -592,7 +655,7 @@ impl TransactionService {
                     transaction.database.name(),
                     LoadKind::WriteTransactions,
                 );
-                let (profile, commit_result) = transaction.commit();
+                let (profile, commit_result) = commit_with_triggers(transaction, &trigger_runner);
                 if profile.is_enabled() {
                     event!(Level::INFO, "commit done.\n{}", profile);
                 }
@@ -657,6 +720,8 @@ impl TransactionService {
             Transaction::Schema(mut transaction) => {
                 transaction.rollback();
                 self.transaction = Some(Transaction::Schema(transaction));
+                // rolled back schema queries may have relabelled types
+                self.intern_pool = InternPool::new();
             }
         };
 
@@ -673,6 +738,7 @@ impl TransactionService {
     }
 
     async fn do_close(&mut self) {
+        self.registration = None;
         self.interrupt_and_close_responders(InterruptType::TransactionClosed).await;
         let _ = self.cancel_queued_read_queries(InterruptType::TransactionClosed).await;
         let _ = self.finish_running_write_query_no_transmit(InterruptType::TransactionClosed).await;
@@ -845,20 +911,13 @@ impl TransactionService {
         analyse_req: typedb_protocol::analyze::Req,
     ) -> Result<ControlFlow<(), ()>, Status> {
         let query = analyse_req.query;
-        let parsed = match parse_query(&query) {
-            Ok(parsed) => parsed,
+        let pipeline = match dispatch_analyse_query(&query) {
+            Ok(pipeline) => pipeline,
             Err(err) => {
-                let response = ImmediateAnalyzeResponse::non_fatal_err(TransactionServiceError::QueryParseFailed {
-                    typedb_source: err,
-                });
+                let response = ImmediateAnalyzeResponse::non_fatal_err(err);
                 return Ok(Self::respond_analyze_response(&self.response_sender, req_id, response).await);
             }
         };
-        let typeql::query::QueryStructure::Pipeline(pipeline) = parsed.into_structure() else {
-            let response =
-                ImmediateAnalyzeResponse::non_fatal_err(TransactionServiceError::AnalyseQueryExpectsPipeline {});
-            return Ok(Self::respond_analyze_response(&self.response_sender, req_id, response).await);
-        };
 
         if !self.query_queue.is_empty() || self.running_write_query.is_some() {
             self.query_queue.push_back((req_id, QueueOptions::Analyze, pipeline, query));
@@ -877,51 +936,36 @@ impl TransactionService {
         query_req: typedb_protocol::query::Req,
     ) -> Result<ControlFlow<(), ()>, Status> {
         let query_options = query_options_from_proto(query_req.options);
-        if query_options.prefetch_size < 1 {
-            let response = ImmediateQueryResponse::non_fatal_err(TransactionServiceError::InvalidPrefetchSize {
-                value: query_options.prefetch_size,
-            });
-            return Ok(Self::respond_query_response(&self.response_sender, req_id, response).await);
-        }
-
         let query = query_req.query;
-        let parsed = match parse_query(&query) {
-            Ok(parsed) => parsed,
+        let dispatched = validate_query_options(&query_options).and_then(|_| dispatch_query(&query, &query_options));
+        let dispatched = match dispatched {
+            Ok(dispatched) => dispatched,
             Err(err) => {
-                let response = ImmediateQueryResponse::non_fatal_err(TransactionServiceError::QueryParseFailed {
-                    typedb_source: err,
-                });
+                let response = ImmediateQueryResponse::non_fatal_err(err);
                 return Ok(Self::respond_query_response(&self.response_sender, req_id, response).await);
             }
         };
-        match parsed.into_structure() {
-            typeql::query::QueryStructure::Schema(schema_query) => {
+        match dispatched {
+            DispatchedQuery::Schema(schema_query) => {
                 // schema queries are handled immediately so there is a query response or a fatal Status
-                let response = self.handle_query_schema(schema_query, query).await?;
+                let response = self.handle_query_schema(schema_query, query, query_options.dry_run).await?;
                 Ok(Self::respond_query_response(&self.response_sender, req_id, response).await)
             }
-            typeql::query::QueryStructure::Pipeline(pipeline) => {
-                #[allow(clippy::collapsible_else_if)]
-                if is_write_pipeline(&pipeline) {
-                    if !self.query_queue.is_empty() || self.running_write_query.is_some() {
-                        self.query_queue.push_back((req_id, QueueOptions::Query(query_options), pipeline, query));
-                        // queued queries are not handled yet so there will be no query response yet
-                        Ok(Continue(()))
-                    } else {
-                        self.run_write_query(req_id, query_options, pipeline, query).await;
-                        Ok(Continue(()))
-                    }
-                } else {
-                    if !self.query_queue.is_empty() || self.running_write_query.is_some() {
-                        self.query_queue.push_back((req_id, QueueOptions::Query(query_options), pipeline, query));
-                        // queued queries are not handled yet so there will be no query response yet
-                        Ok(Continue(()))
-                    } else {
-                        self.run_and_activate_read_transmitter(req_id, query_options, pipeline, query);
-                        // running read queries have no response on the main loop and will respond asynchronously
-                        Ok(Continue(()))
-                    }
-                }
+            DispatchedQuery::Write(pipeline) | DispatchedQuery::Read(pipeline)
+                if !self.query_queue.is_empty() || self.running_write_query.is_some() =>
+            {
+                self.query_queue.push_back((req_id, QueueOptions::Query(query_options), pipeline, query));
+                // queued queries are not handled yet so there will be no query response yet
+                Ok(Continue(()))
+            }
+            DispatchedQuery::Write(pipeline) => {
+                self.run_write_query(req_id, query_options, pipeline, query).await;
+                Ok(Continue(()))
+            }
+            DispatchedQuery::Read(pipeline) => {
+                self.run_and_activate_read_transmitter(req_id, query_options, pipeline, query);
+                // running read queries have no response on the main loop and will respond asynchronously
+                Ok(Continue(()))
             }
         }
     }
@@ -930,32 +974,23 @@ impl TransactionService {
         &mut self,
         query: SchemaQuery,
         source_query: String,
+        dry_run: bool,
     ) -> Result<ImmediateQueryResponse, Status> {
         self.interrupt_and_close_responders(InterruptType::SchemaQueryExecution).await;
         let _ = self.cancel_queued_read_queries(InterruptType::SchemaQueryExecution).await;
         self.finish_queued_write_queries(InterruptType::SchemaQueryExecution).await?;
 
-        if let Some(transaction) = self.transaction.take() {
-            match transaction {
-                Transaction::Schema(schema_transaction) => {
-                    let (transaction, result) =
-                        spawn_blocking(move || execute_schema_query(schema_transaction, query, source_query))
-                            .await
-                            .expect("Expected schema query execution finishing");
-                    self.transaction = Some(Transaction::Schema(transaction));
-                    let message_ok_done =
-                        result.map(|_| query_res_ok_done(typedb_protocol::query::Type::Schema)).map_err(|err| {
-                            TransactionServiceError::TxnAbortSchemaQueryFailed { typedb_source: *err }
-                                .into_error_message()
-                                .into_status()
-                        })?;
-                    return Ok(ImmediateQueryResponse::ok(message_ok_done));
-                }
-                transaction => self.transaction = Some(transaction),
+        let outcome = execute_schema_query_in_transaction(&mut self.transaction, query, source_query, dry_run).await;
+        // schema queries can relabel types, so labels interned before them may be stale
+        self.intern_pool = InternPool::new();
+        match outcome {
+            // the protocol has no answer carrying the schema changes, so a dry run only reports that it would succeed
+            SchemaQueryOutcome::Done | SchemaQueryOutcome::DryRun(_) => {
+                Ok(ImmediateQueryResponse::ok(query_res_ok_done(typedb_protocol::query::Type::Schema)))
             }
+            SchemaQueryOutcome::Rejected(err) => Ok(ImmediateQueryResponse::non_fatal_err(err)),
+            SchemaQueryOutcome::Failed(err) => Err(err.into_error_message().into_status()),
         }
-
-        Ok(ImmediateQueryResponse::non_fatal_err(TransactionServiceError::SchemaQueryRequiresSchemaTransaction {}))
     }
 
     async fn run_analyse_query(&mut self, req_id: Uuid, pipeline: typeql::query::Pipeline, source_query: String) {
@@ -1000,7 +1035,15 @@ impl TransactionService {
     ) {
         debug_assert!(self.running_write_query.is_none());
         self.interrupt_and_close_responders(InterruptType::WriteQueryExecution).await;
-        let handle = match self.spawn_blocking_execute_write_query(query_options, pipeline, source_query) {
+        debug_assert!(self.transaction.is_some());
+        let interrupt = self.query_interrupt_receiver.clone();
+        let handle = match spawn_blocking_execute_write_query(
+            &mut self.transaction,
+            query_options,
+            pipeline,
+            source_query,
+            interrupt,
+        ) {
             Ok(handle) => {
                 // running write queries have no valid response yet (until they finish) and will respond asynchronously
                 handle
@@ -1020,7 +1063,9 @@ impl TransactionService {
     }
 
     fn activate_write_transmitter(&mut self, req_id: Uuid, answer: WriteQueryAnswer) {
-        let prefetch_size = answer.query_options.prefetch_size;
+        let prefetch_size =
+            MEMORY_MANAGER.batch_size(MemoryComponent::ExecutorBuffers, answer.query_options.prefetch_size);
+        let answer_batch_size = answer.query_options.answer_batch_size;
         let (sender, receiver) = channel(prefetch_size);
         let answer_reader = self.write_query_answer_reader(answer, sender);
         let stream_transmitter = QueryStreamTransmitter::start_new(
@@ -1028,7 +1073,9 @@ impl TransactionService {
             receiver,
             req_id,
             prefetch_size,
+            answer_batch_size,
             self.network_latency_millis.unwrap() as usize,
+            MEMORY_MANAGER.batch_size(MemoryComponent::ExecutorBuffers, self.answer_window),
         );
         self.query_responders.insert(req_id, (answer_reader, stream_transmitter));
     }
@@ -1040,7 +1087,8 @@ impl TransactionService {
         pipeline: typeql::query::Pipeline,
         source_query: String,
     ) {
-        let prefetch_size = query_options.prefetch_size;
+        let prefetch_size = MEMORY_MANAGER.batch_size(MemoryComponent::ExecutorBuffers, query_options.prefetch_size);
+        let answer_batch_size = query_options.answer_batch_size;
         let (sender, receiver) = channel(prefetch_size);
         let worker_handle = self.blocking_read_query_worker(sender, query_options, pipeline, source_query);
         let stream_transmitter = QueryStreamTransmitter::start_new(
@@ -1048,39 +1096,13 @@ impl TransactionService {
             receiver,
             req_id,
             prefetch_size,
+            answer_batch_size,
             self.network_latency_millis.unwrap() as usize,
+            MEMORY_MANAGER.batch_size(MemoryComponent::ExecutorBuffers, self.answer_window),
         );
         self.query_responders.insert(req_id, (worker_handle, stream_transmitter));
     }
 
-    fn spawn_blocking_execute_write_query(
-        &mut self,
-        query_options: QueryOptions,
-        pipeline: typeql::query::Pipeline,
-        source_query: String,
-    ) -> Result<JoinHandle<(Transaction, WriteQueryResult)>, TransactionServiceError> {
-        debug_assert!(self.running_write_query.is_none());
-        debug_assert!(self.transaction.is_some());
-        let interrupt = self.query_interrupt_receiver.clone();
-        match self.transaction.take() {
-            Some(Transaction::Schema(schema_transaction)) => Ok(spawn_blocking(move || {
-                let (transaction, result) =
-                    execute_write_query_in_schema(schema_transaction, query_options, pipeline, source_query, interrupt);
-                (Transaction::Schema(transaction), result)
-            })),
-            Some(Transaction::Write(write_transaction)) => Ok(spawn_blocking(move || {
-                let (transaction, result) =
-                    execute_write_query_in_write(write_transaction, query_options, pipeline, source_query, interrupt);
-                (Transaction::Write(transaction), result)
-            })),
-            Some(Transaction::Read(transaction)) => {
-                self.transaction = Some(Transaction::Read(transaction));
-                Err(TransactionServiceError::WriteQueryRequiresSchemaOrWriteTransaction {})
-            }
-            None => Err(TransactionServiceError::NoOpenTransaction {}),
-        }
-    }
-
     // Write query is already executed, but for simplicity, we convert it to something that conform to the same API as the read path
     fn write_query_answer_reader(
         &self,
@@ -1093,8 +1115,10 @@ impl TransactionService {
             let thing_manager = transaction.thing_manager.clone();
             let timeout_at = self.timeout_at;
             let interrupt = self.query_interrupt_receiver.clone();
+            let intern_pool = self.intern_pool.clone();
             tokio::spawn(async move {
                 let encoding_profile = EncodingProfile::new(tracing::enabled!(Level::TRACE));
+                let encoder = ProtocolAnswerEncoder::new(&answer.query_options, intern_pool);
                 match answer.answer {
                     Either::Left((output_descriptor, batch, pipeline_structure)) => {
                         Self::submit_write_query_batch_answer(
@@ -1105,6 +1129,7 @@ impl TransactionService {
                             answer.query_options,
                             batch,
                             pipeline_structure.as_ref(),
+                            encoder,
                             sender,
                             timeout_at,
                             interrupt,
@@ -1119,6 +1144,7 @@ impl TransactionService {
                             thing_manager,
                             parameters,
                             documents,
+                            encoder,
                             sender,
                             timeout_at,
                             interrupt,
@@ -1139,6 +1165,7 @@ impl TransactionService {
         query_options: QueryOptions,
         batch: Batch,
         pipeline_structure: Option<&PipelineStructure>,
+        mut encoder: impl AnswerEncoder<Row = typedb_protocol::ConceptRow>,
         sender: Sender<StreamQueryResponse>,
         timeout_at: Instant,
         mut interrupt: ExecutionInterrupt,
@@ -1178,13 +1205,12 @@ impl TransactionService {
                 return;
             }
 
-            let encoded_row = encode_row(
+            let encoded_row = encoder.encode_row(
                 row,
                 &output_descriptor,
                 snapshot.as_ref(),
                 &type_manager,
                 &thing_manager,
-                query_options.include_instance_types,
                 &include_involved_blocks,
                 storage_counters.clone(),
             );
@@ -1211,6 +1237,7 @@ impl TransactionService {
         thing_manager: Arc<ThingManager>,
         parameters: Arc<ParameterRegistry>,
         documents: Vec<ConceptDocument>,
+        mut encoder: impl AnswerEncoder<Document = typedb_protocol::ConceptDocument>,
         sender: Sender<StreamQueryResponse>,
         timeout_at: Instant,
         mut interrupt: ExecutionInterrupt,
@@ -1236,7 +1263,7 @@ impl TransactionService {
                 return;
             }
 
-            let encoded_document = encode_document(
+            let encoded_document = encoder.encode_document(
                 document,
                 snapshot.as_ref(),
                 &type_manager,
@@ -1262,15 +1289,17 @@ impl TransactionService {
     }
 
     fn blocking_read_query_worker(
-        &self,
+        &mut self,
         sender: Sender<StreamQueryResponse>,
         query_options: QueryOptions,
         pipeline: typeql::query::Pipeline,
         source_query: String,
     ) -> JoinHandle<()> {
         debug_assert!(self.query_queue.is_empty() && self.running_write_query.is_none() && self.transaction.is_some());
+        self.transaction.as_mut().unwrap().advance_read_snapshot();
         let timeout_at = self.timeout_at;
         let interrupt = self.query_interrupt_receiver.clone();
+        let intern_pool = self.intern_pool.clone();
         with_readable_transaction!(self.transaction.as_ref().unwrap(), |transaction| {
             let snapshot = transaction.snapshot.clone();
             let type_manager = transaction.type_manager.clone();
@@ -1300,6 +1329,7 @@ impl TransactionService {
                     snapshot,
                     &type_manager,
                     thing_manager,
+                    intern_pool,
                     start_time,
                 );
             })
@@ -1316,10 +1346,12 @@ impl TransactionService {
         snapshot: Arc<Snapshot>,
         type_manager: &TypeManager,
         thing_manager: Arc<ThingManager>,
+        intern_pool: InternPool,
         start_time: Instant,
     ) {
         let query_profile: Arc<QueryProfile>;
         let encoding_profile: EncodingProfile;
+        let mut encoder = ProtocolAnswerEncoder::new(&query_options, intern_pool);
 
         if pipeline.has_fetch() {
             let initial_response = StreamQueryResponse::init_ok_documents(Read);
@@ -1358,7 +1390,7 @@ impl TransactionService {
                     Self::submit_response_sync(sender, StreamQueryResponse::done_err(err));
                 });
 
-                let encoded_document = encode_document(
+                let encoded_document = encoder.encode_document(
                     document,
                     snapshot.as_ref(),
                     type_manager,
@@ -1429,13 +1461,12 @@ impl TransactionService {
                     Self::submit_response_sync(sender, StreamQueryResponse::done_err(err));
                 });
 
-                let encoded_row = encode_row(
+                let encoded_row = encoder.encode_row(
                     row,
                     &descriptor,
                     snapshot.as_ref(),
                     type_manager,
                     &thing_manager,
-                    query_options.include_instance_types,
                     &include_involved_blocks,
                     encoding_profile.storage_counters(),
                 );
@@ -1508,7 +1539,9 @@ struct QueryStreamTransmitter {
     response_sender: Sender<Result<ProtocolServer, Status>>,
     req_id: Uuid,
     prefetch_size: usize,
+    answer_batch_size: usize,
     network_latency_millis: usize,
+    answer_window: usize,
 
     transmitter_task: Option<JoinHandle<ControlFlow<(), Receiver<StreamQueryResponse>>>>,
 }
@@ -1519,12 +1552,16 @@ impl QueryStreamTransmitter {
         query_response_receiver: Receiver<StreamQueryResponse>,
         req_id: Uuid,
         prefetch_size: usize,
+        answer_batch_size: usize,
         network_latency_millis: usize,
+        answer_window: usize,
     ) -> Self {
         let transmitter_task = tokio::spawn(Self::respond_stream_parts(
             response_sender.clone(),
             prefetch_size,
+            answer_batch_size,
             network_latency_millis,
+            answer_window,
             req_id,
             query_response_receiver,
         ));
@@ -1532,7 +1569,9 @@ impl QueryStreamTransmitter {
             response_sender,
             req_id,
             prefetch_size,
+            answer_batch_size,
             network_latency_millis,
+            answer_window,
             transmitter_task: Some(transmitter_task),
         }
     }
@@ -1547,7 +1586,9 @@ impl QueryStreamTransmitter {
                     self.transmitter_task = Some(tokio::spawn(Self::respond_stream_parts(
                         self.response_sender.clone(),
                         self.prefetch_size,
+                        self.answer_batch_size,
                         self.network_latency_millis,
+                        self.answer_window,
                         self.req_id,
                         query_response_receiver,
                     )));
@@ -1558,14 +1599,25 @@ impl QueryStreamTransmitter {
         } else {
             let sender = self.response_sender.clone();
             let prefetch = self.prefetch_size;
+            let answer_batch_size = self.answer_batch_size;
             let latency = self.network_latency_millis;
+            let answer_window = self.answer_window;
             let req_id = self.req_id;
             // append another parts responding operation to run once the existing one has finished
             self.transmitter_task = Some(tokio::spawn(async move {
                 let control = task.await.unwrap();
                 match control {
                     Continue(query_response_receiver) => {
-                        Self::respond_stream_parts(sender, prefetch, latency, req_id, query_response_receiver).await
+                        Self::respond_stream_parts(
+                            sender,
+                            prefetch,
+                            answer_batch_size,
+                            latency,
+                            answer_window,
+                            req_id,
+                            query_response_receiver,
+                        )
+                        .await
                     }
                     Break(()) => Break(()),
                 }
@@ -1583,7 +1635,9 @@ impl QueryStreamTransmitter {
     async fn respond_stream_parts(
         response_sender: Sender<Result<ProtocolServer, Status>>,
         prefetch_size: usize,
+        answer_batch_size: usize,
         network_latency_millis: usize,
+        answer_window: usize,
         req_id: Uuid,
         query_response_receiver: Receiver<StreamQueryResponse>,
     ) -> ControlFlow<(), Receiver<StreamQueryResponse>> {
@@ -1593,16 +1647,19 @@ impl QueryStreamTransmitter {
             req_id,
             query_response_receiver,
             StreamingCondition::Count(prefetch_size),
+            answer_batch_size,
         )
         .await?;
         send_ok_message_else_return_break!(response_sender, transaction_server_res_part_stream_signal_continue(req_id));
 
-        // stream LATENCY number of answers
+        // stream LATENCY number of answers, bounded by the window so a slow driver does not have answers pile up
+        // unread; the rest wait for its request to continue
         let query_response_receiver = Self::respond_stream_while_or_finish(
             &response_sender,
             req_id,
             query_response_receiver,
-            StreamingCondition::Duration(Instant::now(), network_latency_millis),
+            StreamingCondition::Duration(Instant::now(), network_latency_millis, answer_window),
+            answer_batch_size,
         )
         .await?;
         Continue(query_response_receiver)
@@ -1613,6 +1670,7 @@ impl QueryStreamTransmitter {
         req_id: Uuid,
         mut query_response_receiver: Receiver<StreamQueryResponse>,
         streaming_condition: StreamingCondition,
+        answer_batch_size: usize,
     ) -> ControlFlow<(), Receiver<StreamQueryResponse>> {
         let mut rows: Vec<typedb_protocol::ConceptRow> = Vec::new();
         let mut documents: Vec<typedb_protocol::ConceptDocument> = Vec::new();
@@ -1665,8 +1723,18 @@ impl QueryStreamTransmitter {
                         );
                         return Break(());
                     }
-                    StreamQueryResponse::StreamNextRow(concept_row) => rows.push(concept_row),
-                    StreamQueryResponse::StreamNextDocument(concept_document) => documents.push(concept_document),
+                    StreamQueryResponse::StreamNextRow(concept_row) => {
+                        rows.push(concept_row);
+                        if rows.len() >= answer_batch_size {
+                            Self::send_rows(response_sender, req_id, mem::take(&mut rows)).await?;
+                        }
+                    }
+                    StreamQueryResponse::StreamNextDocument(concept_document) => {
+                        documents.push(concept_document);
+                        if documents.len() >= answer_batch_size {
+                            Self::send_documents(response_sender, req_id, mem::take(&mut documents)).await?;
+                        }
+                    }
                 },
             }
             iteration += 1;
@@ -1730,15 +1798,3 @@ impl QueryStreamTransmitter {
         Continue(())
     }
 }
-
-#[derive(Debug, Clone, Eq, PartialEq)]
-enum QueueOptions {
-    Query(QueryOptions),
-    Analyze,
-}
-
-impl QueueOptions {
-    fn is_query(&self) -> bool {
-        matches!(self, QueueOptions::Query(_))
-    }
-}
@@ -45,7 +45,9 @@ use crate::{
             transaction_service::TransactionService,
             ConnectionID,
         },
+        transaction_registry::TransactionRegistry,
         transaction_service::TRANSACTION_REQUEST_BUFFER_SIZE,
+        trigger_service::TriggerRunner,
     },
     state::{BoxServerState, ServerStateError},
 };
@@ -54,11 +56,22 @@ use crate::{
 pub(crate) struct TypeDBService {
     address: SocketAddr,
     server_state: Arc<BoxServerState>,
+    transaction_registry: Arc<TransactionRegistry>,
+    trigger_runner: Arc<TriggerRunner>,
+    answer_window: usize,
+    preserve_imported_iids: bool,
 }
 
 impl TypeDBService {
-    pub(crate) fn new(address: SocketAddr, server_state: Arc<BoxServerState>) -> Self {
-        Self { address, server_state }
+    pub(crate) fn new(
+        address: SocketAddr,
+        server_state: Arc<BoxServerState>,
+        transaction_registry: Arc<TransactionRegistry>,
+        trigger_runner: Arc<TriggerRunner>,
+        answer_window: usize,
+        preserve_imported_iids: bool,
+    ) -> Self {
+        Self { address, server_state, transaction_registry, trigger_runner, answer_window, preserve_imported_iids }
     }
 }
 
@@ -231,6 +244,7 @@ impl typedb_protocol::type_db_server::TypeDb for TypeDBService {
             request_stream,
             response_sender,
             self.server_state.shutdown_receiver(),
+            self.preserve_imported_iids,
         );
         tokio::spawn(async move { service.listen().await });
         let stream: ReceiverStream<Result<DatabasesImportServerProto, Status>> = ReceiverStream::new(response_receiver);
@@ -437,14 +451,26 @@ impl typedb_protocol::type_db_server::TypeDb for TypeDBService {
         &self,
         request: Request<Streaming<TransactionClientProto>>,
     ) -> Result<Response<Self::transactionStream>, Status> {
+        let Accessor(owner) =
+            Accessor::from_extensions(&request.extensions()).map_err(|err| err.into_error_message().into_status())?;
+        // each transaction stream carries a single transaction, which is registered once it opens
+        let transaction_reservation = self.transaction_registry.reserve(&owner).ok_or_else(|| {
+            Status::resource_exhausted(format!(
+                "User '{owner}' already has the maximum of {} open transactions. Close a transaction and retry.",
+                self.transaction_registry.limit().unwrap_or_default()
+            ))
+        })?;
         let request_stream = request.into_inner();
         let (response_sender, response_receiver) = channel(TRANSACTION_REQUEST_BUFFER_SIZE);
         let mut service = TransactionService::new(
             self.server_state.database_manager(),
             self.server_state.diagnostics_manager(),
+            self.trigger_runner.clone(),
             request_stream,
             response_sender,
+            transaction_reservation,
             self.server_state.shutdown_receiver(),
+            self.answer_window,
         );
         tokio::spawn(async move { service.listen().await });
         let stream: ReceiverStream<Result<TransactionServerProto, Status>> = ReceiverStream::new(response_receiver);
@@ -4,11 +4,29 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use database::{database::DatabaseCreateError, DatabaseDeleteError};
+use std::iter::successors;
+
+use axum::response::{IntoResponse, Response};
+use database::{
+    cluster::ClusterError,
+    database::DatabaseCreateError,
+    transaction::{DataCommitError, TransactionError},
+    DatabaseCloneError, DatabaseDeleteError,
+};
 use error::{typedb_error, TypeDBError};
+use http::{header::RETRY_AFTER, HeaderValue, StatusCode};
+use storage::isolation_manager::IsolationConflict;
 
 use crate::{
-    authentication::AuthenticationError, service::transaction_service::TransactionServiceError, state::ServerStateError,
+    authentication::AuthenticationError,
+    service::{
+        http::message::{
+            body::JsonBody,
+            error::{ErrorResponse, ErrorSourceResponse},
+        },
+        transaction_service::TransactionServiceError,
+    },
+    state::ServerStateError,
 };
 
 typedb_error!(
@@ -27,6 +45,24 @@ typedb_error!(
         Transaction(16, "Transaction error.", typedb_source: TransactionServiceError),
         QueryClose(17, "Error while closing single-query transaction.", typedb_source: TransactionServiceError),
         QueryCommit(18, "Error while committing single-query transaction.", typedb_source: TransactionServiceError),
+        DocumentsNotAcceptable(19, "Concept document answers cannot be encoded as '{media_type}'.", media_type: String),
+        PayloadTooLarge(20, "Request body exceeds the size limit of this endpoint: {details}", details: String),
+        InvalidJsonField(21, "Invalid JSON body at '{path}': {details}", path: String, details: String),
+        RateLimited(
+            22,
+            "Too many {class} requests from this client. Retry after {retry_after_seconds} second(s).",
+            class: String,
+            retry_after_seconds: u64
+        ),
+        InvalidSnapshotToken(23, "Invalid snapshot token: {details}.", details: String),
+        SnapshotTokenRequiresRead(24, "Snapshot tokens can only be used to open read transactions."),
+        DatabaseClone(25, "Database clone error.", typedb_source: DatabaseCloneError),
+        TransactionLimitReached(
+            26,
+            "User '{owner}' already has the maximum of {limit} open transactions. Close a transaction and retry.",
+            owner: String,
+            limit: usize
+        ),
     }
 );
 
@@ -50,4 +86,154 @@ impl HttpServiceError {
     pub(crate) fn transaction_timeout() -> Self {
         Self::Transaction { typedb_source: TransactionServiceError::TransactionTimeout {} }
     }
+
+    /// The conflict with a concurrent commit which made a transaction commit fail, if any.
+    pub(crate) fn commit_isolation_conflict(&self) -> Option<&IsolationConflict> {
+        match self {
+            Self::Transaction { typedb_source, .. } | Self::QueryCommit { typedb_source, .. } => match typedb_source {
+                TransactionServiceError::DataCommitFailed { typedb_source, .. } => typedb_source.isolation_conflict(),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Commits failing due to a concurrent commit may succeed if the whole transaction is run again.
+    pub(crate) fn is_retryable(&self) -> bool {
+        self.commit_isolation_conflict().is_some()
+    }
+
+    pub(crate) fn status_code(&self) -> StatusCode {
+        match self {
+            HttpServiceError::Internal { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            HttpServiceError::JsonBodyExpected { .. } => StatusCode::BAD_REQUEST,
+            HttpServiceError::RequestTimeout { .. } => StatusCode::REQUEST_TIMEOUT,
+            HttpServiceError::NotFound { .. } => StatusCode::NOT_FOUND,
+            HttpServiceError::UnknownVersion { .. } => StatusCode::NOT_FOUND,
+            HttpServiceError::MissingPathParameter { .. } => StatusCode::NOT_FOUND,
+            HttpServiceError::InvalidPathParameter { .. } => StatusCode::BAD_REQUEST,
+            HttpServiceError::State { typedb_source } => match typedb_source {
+                ServerStateError::Unimplemented { .. } => StatusCode::NOT_IMPLEMENTED,
+                ServerStateError::OperationNotPermitted { .. } => StatusCode::FORBIDDEN,
+                ServerStateError::DatabaseDoesNotExist { .. } => StatusCode::NOT_FOUND,
+                ServerStateError::UserDoesNotExist { .. } => StatusCode::NOT_FOUND,
+                ServerStateError::FailedToOpenPrerequisiteTransaction { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::ConceptReadError { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::FunctionReadError { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::UserCannotBeCreated { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::UserCannotBeRetrieved { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::UserCannotBeUpdated { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::UserCannotBeDeleted { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::DatabaseExport { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::SchemaDiff { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::DatabaseOptions { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::RelationIndexRebuild { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::AttributeCleanup { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::CommitTriggers { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::Expiry { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::Statistics { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::Lint { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::TypeRename { .. } => StatusCode::BAD_REQUEST,
+                ServerStateError::Replication { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+                ServerStateError::Cluster { typedb_source: ClusterError::NotConfigured { .. } } => {
+                    StatusCode::NOT_FOUND
+                }
+                ServerStateError::Cluster { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            },
+            HttpServiceError::Authentication { .. } => StatusCode::UNAUTHORIZED,
+            HttpServiceError::DatabaseCreate { .. } => StatusCode::BAD_REQUEST,
+            HttpServiceError::DatabaseDelete { .. } => StatusCode::BAD_REQUEST,
+            HttpServiceError::DatabaseClone { typedb_source: DatabaseCloneError::SourceNotFound { .. } } => {
+                StatusCode::NOT_FOUND
+            }
+            HttpServiceError::DatabaseClone { .. } => StatusCode::BAD_REQUEST,
+            HttpServiceError::Transaction { typedb_source } => match typedb_source {
+                TransactionServiceError::DatabaseNotFound { .. } => StatusCode::NOT_FOUND,
+                TransactionServiceError::CannotCommitReadTransaction { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::CannotRollbackReadTransaction { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::TransactionFailed {
+                    typedb_source: TransactionError::ReadSequenceNumberNotRetained { .. },
+                    ..
+                } => StatusCode::GONE,
+                TransactionServiceError::TransactionFailed {
+                    typedb_source: TransactionError::ExclusiveAccessTimeout { .. },
+                    ..
+                } => StatusCode::REQUEST_TIMEOUT,
+                TransactionServiceError::TransactionFailed { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::DataCommitFailed {
+                    typedb_source: DataCommitError::IsolationConflict { .. },
+                    ..
+                } => StatusCode::CONFLICT,
+                TransactionServiceError::DataCommitFailed { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::SchemaCommitFailed { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::QueryParseFailed { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::SchemaQueryRequiresSchemaTransaction { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::WriteQueryRequiresSchemaOrWriteTransaction { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::TxnAbortSchemaQueryFailed { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::QueryFailed { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::AnalyseQueryFailed { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::AnalyseQueryExpectsPipeline { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::NoOpenTransaction { .. } => StatusCode::NOT_FOUND,
+                TransactionServiceError::QueryInterrupted { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::QueryStreamNotFound { .. } => StatusCode::NOT_FOUND,
+                TransactionServiceError::ServiceFailedQueueCleanup { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::PipelineExecution { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::TransactionTimeout { .. } => StatusCode::REQUEST_TIMEOUT,
+                TransactionServiceError::InvalidPrefetchSize { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::InvalidAnswerBatchSize { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::SchemaQueryDryRunFailed { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::DryRunRequiresSchemaQuery { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::TransactionForceClosed { .. } => StatusCode::NOT_FOUND,
+                TransactionServiceError::Cluster { typedb_source: ClusterError::NotLeader { .. } } => {
+                    StatusCode::MISDIRECTED_REQUEST
+                }
+                TransactionServiceError::Cluster { .. } => StatusCode::SERVICE_UNAVAILABLE,
+                TransactionServiceError::DatabaseNotInTransaction { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::DatabaseListedTwice { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::QueryDatabaseNotNamed { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::MultiDatabaseAnalyseNotSupported { .. } => StatusCode::BAD_REQUEST,
+                TransactionServiceError::MultiDatabaseCommitFailed { .. } => StatusCode::BAD_REQUEST,
+            },
+            HttpServiceError::QueryClose { .. } => StatusCode::BAD_REQUEST,
+            HttpServiceError::QueryCommit { .. } => StatusCode::BAD_REQUEST,
+            HttpServiceError::DocumentsNotAcceptable { .. } => StatusCode::NOT_ACCEPTABLE,
+            HttpServiceError::PayloadTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            HttpServiceError::InvalidJsonField { .. } => StatusCode::BAD_REQUEST,
+            HttpServiceError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            HttpServiceError::InvalidSnapshotToken { .. } => StatusCode::BAD_REQUEST,
+            HttpServiceError::SnapshotTokenRequiresRead { .. } => StatusCode::BAD_REQUEST,
+            HttpServiceError::TransactionLimitReached { .. } => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    pub(crate) fn encode(&self) -> ErrorResponse {
+        let root = self.root_source_typedb_error();
+        let sources = successors(Some(self as &(dyn TypeDBError + Sync)), |error| error.source_typedb_error())
+            .map(|error| ErrorSourceResponse {
+                code: error.code().to_string(),
+                prefix: error.code_prefix().to_string(),
+                number: error.code_number(),
+                component: error.component().to_string(),
+                description: error.format_description(),
+            })
+            .collect();
+        ErrorResponse {
+            code: root.code().to_string(),
+            component: root.component().to_string(),
+            message: self.format_source_trace(),
+            status: self.status_code().as_u16(),
+            retryable: self.is_retryable(),
+            sources,
+        }
+    }
+}
+
+impl IntoResponse for HttpServiceError {
+    fn into_response(self) -> Response {
+        let mut response = (self.status_code(), JsonBody(self.encode())).into_response();
+        if let HttpServiceError::RateLimited { retry_after_seconds, .. } = &self {
+            response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(*retry_after_seconds));
+        }
+        response
+    }
 }
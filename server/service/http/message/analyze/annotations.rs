@@ -19,9 +19,12 @@ use serde::{Deserialize, Serialize};
 use storage::snapshot::ReadableSnapshot;
 
 use super::structure::{encode_analyzed_pipeline, AnalyzedFunctionResponse};
-use crate::service::http::message::query::concept::{
-    encode_attribute_type, encode_entity_type, encode_relation_type, encode_role_type, encode_value_type,
-    AttributeTypeResponse, EntityTypeResponse, RelationTypeResponse, RoleTypeResponse,
+use crate::service::{
+    http::message::query::concept::{
+        encode_attribute_type, encode_entity_type, encode_relation_type, encode_role_type, encode_value_type,
+        AttributeTypeResponse, EntityTypeResponse, RelationTypeResponse, RoleTypeResponse,
+    },
+    intern_pool::InternPool,
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -180,17 +183,26 @@ fn encode_type_annotation(
     type_manager: &TypeManager,
     type_: &Type,
 ) -> Result<SingleTypeAnnotationResponse, Box<ConceptReadError>> {
+    let intern_pool = &InternPool::new();
     match type_ {
         Type::Entity(entity) => {
-            Ok(SingleTypeAnnotationResponse::Entity(encode_entity_type(entity, snapshot, type_manager)?))
-        }
-        Type::Relation(relation) => {
-            Ok(SingleTypeAnnotationResponse::Relation(encode_relation_type(relation, snapshot, type_manager)?))
+            Ok(SingleTypeAnnotationResponse::Entity(encode_entity_type(entity, snapshot, type_manager, intern_pool)?))
         }
-        Type::Attribute(attribute) => {
-            Ok(SingleTypeAnnotationResponse::Attribute(encode_attribute_type(attribute, snapshot, type_manager)?))
+        Type::Relation(relation) => Ok(SingleTypeAnnotationResponse::Relation(encode_relation_type(
+            relation,
+            snapshot,
+            type_manager,
+            intern_pool,
+        )?)),
+        Type::Attribute(attribute) => Ok(SingleTypeAnnotationResponse::Attribute(encode_attribute_type(
+            attribute,
+            snapshot,
+            type_manager,
+            intern_pool,
+        )?)),
+        Type::RoleType(role) => {
+            Ok(SingleTypeAnnotationResponse::Role(encode_role_type(role, snapshot, type_manager, intern_pool)?))
         }
-        Type::RoleType(role) => Ok(SingleTypeAnnotationResponse::Role(encode_role_type(role, snapshot, type_manager)?)),
     }
 }
 
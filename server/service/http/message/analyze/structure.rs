@@ -30,8 +30,11 @@ use super::annotations::{
     encode_variable_type_annotations_and_modifiers, ConjunctionAnnotationsResponse, FunctionReturnAnnotationsResponse,
     VariableAnnotationsResponse,
 };
-use crate::service::http::message::query::concept::{
-    encode_type_concept, encode_value, EntityTypeResponse, ValueResponse,
+use crate::service::{
+    http::message::query::concept::{
+        encode_type_concept, encode_value, ConceptEncodingOptions, EntityTypeResponse, ValueResponse,
+    },
+    intern_pool::InternPool,
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -465,19 +468,19 @@ fn encode_structure_vertex(
         }
         Vertex::Label(label) => {
             let r#type = if let Some(type_) = context.get_type(label) {
-                encode_type_concept(&type_, context.snapshot, context.type_manager)?
+                encode_type_concept(&type_, context.snapshot, context.type_manager, &InternPool::new())?
             } else if let Some(type_) = get_type_annotation_from_label(context.snapshot, context.type_manager, label)? {
-                encode_type_concept(&type_, context.snapshot, context.type_manager)?
+                encode_type_concept(&type_, context.snapshot, context.type_manager, &InternPool::new())?
             } else {
                 debug_assert!(false, "This should be unreachable, but we don't want crashes");
                 let label = format!("ERROR_UNRESOLVED:{}", label.scoped_name.as_str());
-                serde_json::json!(EntityTypeResponse { label })
+                serde_json::json!(EntityTypeResponse { label: label.into() })
             };
             StructureVertex::Label { r#type }
         }
         Vertex::Parameter(param) => {
             let value = context.get_parameter_value(param).unwrap();
-            StructureVertex::Value(encode_value(value))
+            StructureVertex::Value(encode_value(value, &ConceptEncodingOptions::default()))
         }
     };
     Ok(vertex)
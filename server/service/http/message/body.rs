@@ -5,11 +5,14 @@
  */
 use axum::{
     async_trait,
+    body::Bytes,
     extract::{FromRequest, Request},
     response::{IntoResponse, Response},
     Json,
 };
+use http::{header::CONTENT_TYPE, HeaderMap, StatusCode};
 use serde::{de::DeserializeOwned, Serialize};
+use serde_json::error::Category;
 
 use crate::service::http::error::HttpServiceError;
 
@@ -36,13 +39,44 @@ where
     type Rejection = HttpServiceError;
 
     async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
-        Json::from_request(req, state)
-            .await
-            .map(|json: Json<T>| json.into())
-            .map_err(|err| Self::Rejection::JsonBodyExpected { details: err.body_text() })
+        if !has_json_content_type(req.headers()) {
+            return Err(Self::Rejection::JsonBodyExpected {
+                details: "Expected request with `Content-Type: application/json`".to_string(),
+            });
+        }
+        // the body size limit of the endpoint is applied while buffering
+        let bytes = Bytes::from_request(req, state).await.map_err(|err| match err.status() {
+            StatusCode::PAYLOAD_TOO_LARGE => Self::Rejection::PayloadTooLarge { details: err.body_text() },
+            _ => Self::Rejection::JsonBodyExpected { details: err.body_text() },
+        })?;
+        deserialize_json(&bytes).map(Self)
     }
 }
 
+fn has_json_content_type(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers.get(CONTENT_TYPE).and_then(|value| value.to_str().ok()) else {
+        return false;
+    };
+    let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    essence == "application/json" || (essence.starts_with("application/") && essence.ends_with("+json"))
+}
+
+fn deserialize_json<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, HttpServiceError> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|err| {
+        let path = err.path().to_string();
+        let source = err.into_inner();
+        match source.classify() {
+            Category::Data => HttpServiceError::InvalidJsonField { path, details: source.to_string() },
+            Category::Io | Category::Syntax | Category::Eof => {
+                HttpServiceError::JsonBodyExpected { details: source.to_string() }
+            }
+        }
+    })?;
+    deserializer.end().map_err(|err| HttpServiceError::JsonBodyExpected { details: err.to_string() })?;
+    Ok(value)
+}
+
 impl<T: Serialize> IntoResponse for JsonBody<T> {
     fn into_response(self) -> Response {
         Json(self.0).into_response()
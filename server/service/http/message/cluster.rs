@@ -0,0 +1,70 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+
+use database::cluster::{ClusterMember, ClusterReply};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterResponse {
+    pub term: u64,
+    pub leader: Option<String>,
+    pub members: Vec<ClusterMemberResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterMemberResponse {
+    pub address: String,
+    pub role: String,
+}
+
+pub(crate) fn encode_cluster(term: u64, leader: Option<String>, members: Vec<ClusterMember>) -> ClusterResponse {
+    let members = members
+        .into_iter()
+        .map(|member| ClusterMemberResponse {
+            address: member.address,
+            role: member.role.map_or_else(|| "unknown".to_owned(), |role| role.to_string()),
+        })
+        .collect();
+    ClusterResponse { term, leader, members }
+}
+
+/// Requests a vote for `candidate` in `term`. The latest commit the candidate received in each database
+/// lets the voter check that the candidate is at least as up to date as itself.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterVotePayload {
+    pub term: u64,
+    pub candidate: String,
+    pub received: HashMap<String, u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterHeartbeatPayload {
+    pub term: u64,
+    pub leader: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterReplyResponse {
+    pub term: u64,
+    pub accepted: bool,
+}
+
+pub(crate) fn encode_cluster_reply(reply: ClusterReply) -> ClusterReplyResponse {
+    ClusterReplyResponse { term: reply.term, accepted: reply.accepted }
+}
+
+impl From<ClusterReplyResponse> for ClusterReply {
+    fn from(response: ClusterReplyResponse) -> Self {
+        ClusterReply { term: response.term, accepted: response.accepted }
+    }
+}
@@ -4,10 +4,17 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+use concept::type_::{CommitTrigger, DatabaseSettings, RelationIndexThreshold, TriggerFailurePolicy};
 use itertools::Itertools;
+use resource::constants::server::DEFAULT_STATISTICS_UPDATES;
 use serde::{Deserialize, Serialize};
+use storage::keyspace::{CorruptRange, KeyspaceVerification};
 
-use crate::service::http::message::from_request_parts_impl;
+use crate::service::{
+    database_options_service::{DatabaseOptions, StorageUsage},
+    http::message::from_request_parts_impl,
+    statistics_service::{DatabaseStatistics, StatisticsSampleSummary},
+};
 
 #[derive(Debug)]
 pub(crate) struct DatabasePath {
@@ -35,3 +42,352 @@ pub struct DatabaseResponse {
 pub(crate) fn encode_database(name: String) -> DatabaseResponse {
     DatabaseResponse { name }
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaDiffPayload {
+    pub schema: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseOptionsPayload {
+    pub relation_index_threshold: Option<u64>,
+    pub history_retention: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseOptionsResponse {
+    pub relation_index_threshold: u64,
+    pub history_retention: u64,
+}
+
+/// The settings of a database, which replace the previous ones as a whole. Timeouts that are not set fall back on
+/// the server defaults, and the other settings on their defaults.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DatabaseSettingsPayload {
+    pub relation_index_threshold: Option<u64>,
+    pub transaction_timeout_millis: Option<u64>,
+    pub schema_lock_acquire_timeout_millis: Option<u64>,
+    pub statistics_updates: Option<bool>,
+}
+
+impl From<DatabaseSettingsPayload> for DatabaseSettings {
+    fn from(payload: DatabaseSettingsPayload) -> Self {
+        Self {
+            relation_index_threshold: payload
+                .relation_index_threshold
+                .map_or_else(RelationIndexThreshold::default, RelationIndexThreshold),
+            transaction_timeout_millis: payload.transaction_timeout_millis,
+            schema_lock_acquire_timeout_millis: payload.schema_lock_acquire_timeout_millis,
+            statistics_updates: payload.statistics_updates.unwrap_or(DEFAULT_STATISTICS_UPDATES),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseSettingsResponse {
+    pub relation_index_threshold: u64,
+    pub transaction_timeout_millis: Option<u64>,
+    pub schema_lock_acquire_timeout_millis: Option<u64>,
+    pub statistics_updates: bool,
+}
+
+pub(crate) fn encode_database_settings(settings: DatabaseSettings) -> DatabaseSettingsResponse {
+    DatabaseSettingsResponse {
+        relation_index_threshold: settings.relation_index_threshold.0,
+        transaction_timeout_millis: settings.transaction_timeout_millis,
+        schema_lock_acquire_timeout_millis: settings.schema_lock_acquire_timeout_millis,
+        statistics_updates: settings.statistics_updates,
+    }
+}
+
+/// Sets the storage quota of a database, in bytes, or removes it when `quotaInBytes` is null.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageQuotaPayload {
+    pub quota_in_bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageUsageResponse {
+    pub usage_in_bytes: u64,
+    pub quota_in_bytes: Option<u64>,
+}
+
+/// With `salvage`, a database with corrupt storage files is closed and its storage rebuilt from the WAL.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageVerifyQuery {
+    #[serde(default)]
+    pub salvage: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageVerifyResponse {
+    pub intact: bool,
+    pub keyspaces: Vec<KeyspaceVerificationResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyspaceVerificationResponse {
+    pub name: String,
+    pub files_checked: usize,
+    pub keys_checked: u64,
+    pub corrupt_ranges: Vec<CorruptRangeResponse>,
+}
+
+/// Keys are hex-encoded; a missing key leaves that end of the range open.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorruptRangeResponse {
+    pub file: String,
+    pub level: i32,
+    pub start_key: Option<String>,
+    pub end_key: Option<String>,
+    pub entries: u64,
+    pub message: String,
+    pub salvaged: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationIndexRebuildPayload {
+    pub relation_type: String,
+}
+
+/// Selects the WAL records a replica reads, from the sequence number `from` onwards.
+/// Cluster members also report their address and the latest commit they durably received, which commits wait for.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicationRecordsQuery {
+    pub from: u64,
+    pub limit: Option<usize>,
+    pub member: Option<String>,
+    pub received: Option<u64>,
+}
+
+/// Names the database that a clone of an existing database is created as.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseCloneQuery {
+    pub target: String,
+}
+
+/// Renames a type, keeping its instances. Role types are given by their scoped label, such as `friendship:friend`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeRenamePayload {
+    #[serde(rename = "type")]
+    pub type_label: String,
+    pub new_label: String,
+}
+
+/// Sets the time to live of an entity or relation type, in seconds, or removes it when `seconds` is null.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeToLivePayload {
+    #[serde(rename = "type")]
+    pub type_label: String,
+    pub seconds: Option<u64>,
+}
+
+pub(crate) fn encode_database_options(options: DatabaseOptions) -> DatabaseOptionsResponse {
+    DatabaseOptionsResponse {
+        relation_index_threshold: options.relation_index_threshold,
+        history_retention: options.history_retention,
+    }
+}
+
+pub(crate) fn encode_storage_usage(usage: StorageUsage) -> StorageUsageResponse {
+    StorageUsageResponse { usage_in_bytes: usage.usage_in_bytes, quota_in_bytes: usage.quota_in_bytes }
+}
+
+pub(crate) fn encode_storage_verification(verifications: Vec<KeyspaceVerification>) -> StorageVerifyResponse {
+    StorageVerifyResponse {
+        intact: verifications.iter().all(KeyspaceVerification::is_intact),
+        keyspaces: verifications
+            .into_iter()
+            .map(|verification| KeyspaceVerificationResponse {
+                name: verification.keyspace.to_owned(),
+                files_checked: verification.files_checked,
+                keys_checked: verification.keys_checked,
+                corrupt_ranges: verification.corrupt_ranges.into_iter().map(encode_corrupt_range).collect_vec(),
+            })
+            .collect_vec(),
+    }
+}
+
+fn encode_corrupt_range(range: CorruptRange) -> CorruptRangeResponse {
+    let encode_key = |key: Vec<u8>| key.iter().map(|byte| format!("{byte:02x}")).join("");
+    CorruptRangeResponse {
+        file: range.file,
+        level: range.level,
+        start_key: range.start_key.map(encode_key),
+        end_key: range.end_key.map(encode_key),
+        entries: range.entries,
+        message: range.message,
+        salvaged: range.salvaged,
+    }
+}
+
+/// Planner statistics, as of `sequenceNumber`. `lastSample` holds the recounts of the latest background sample.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStatisticsResponse {
+    pub sequence_number: u64,
+    pub total_entity_count: u64,
+    pub total_relation_count: u64,
+    pub total_attribute_count: u64,
+    pub total_has_count: u64,
+    pub total_role_count: u64,
+    pub instance_counts: Vec<InstanceCountResponse>,
+    pub has_counts: Vec<HasCountResponse>,
+    pub links_counts: Vec<LinksCountResponse>,
+    pub last_sample: Option<StatisticsSampleResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceCountResponse {
+    #[serde(rename = "type")]
+    pub type_label: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HasCountResponse {
+    pub owner: String,
+    pub attribute: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinksCountResponse {
+    pub relation: String,
+    pub role: String,
+    pub player: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatisticsSampleResponse {
+    pub sequence_number: u64,
+    pub types: Vec<TypeSampleResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeSampleResponse {
+    #[serde(rename = "type")]
+    pub type_label: String,
+    pub recorded_instances: u64,
+    pub counted_instances: u64,
+    pub corrected_edge_counts: usize,
+}
+
+pub(crate) fn encode_database_statistics(statistics: DatabaseStatistics) -> DatabaseStatisticsResponse {
+    DatabaseStatisticsResponse {
+        sequence_number: statistics.sequence_number,
+        total_entity_count: statistics.total_entity_count,
+        total_relation_count: statistics.total_relation_count,
+        total_attribute_count: statistics.total_attribute_count,
+        total_has_count: statistics.total_has_count,
+        total_role_count: statistics.total_role_count,
+        instance_counts: statistics
+            .instance_counts
+            .into_iter()
+            .map(|count| InstanceCountResponse { type_label: count.type_label, count: count.count })
+            .collect_vec(),
+        has_counts: statistics
+            .has_counts
+            .into_iter()
+            .map(|count| HasCountResponse {
+                owner: count.owner_label,
+                attribute: count.attribute_label,
+                count: count.count,
+            })
+            .collect_vec(),
+        links_counts: statistics
+            .links_counts
+            .into_iter()
+            .map(|count| LinksCountResponse {
+                relation: count.relation_label,
+                role: count.role_label,
+                player: count.player_label,
+                count: count.count,
+            })
+            .collect_vec(),
+        last_sample: statistics.last_sample.map(encode_statistics_sample),
+    }
+}
+
+fn encode_statistics_sample(sample: StatisticsSampleSummary) -> StatisticsSampleResponse {
+    StatisticsSampleResponse {
+        sequence_number: sample.sequence_number,
+        types: sample
+            .types
+            .into_iter()
+            .map(|type_sample| TypeSampleResponse {
+                type_label: type_sample.type_label,
+                recorded_instances: type_sample.recorded_instances,
+                counted_instances: type_sample.counted_instances,
+                corrected_edge_counts: type_sample.corrected_edge_counts,
+            })
+            .collect_vec(),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitTriggersPayload {
+    pub triggers: Vec<CommitTriggerPayload>,
+}
+
+/// The schema function named `function`, called after commits inserting instances of `type` or its subtypes with
+/// each inserted instance as its argument. Failed triggers are logged, after being rerun up to `retryAttempts` times
+/// if given.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitTriggerPayload {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_label: String,
+    pub function: String,
+    pub retry_attempts: Option<u32>,
+}
+
+impl From<CommitTriggerPayload> for CommitTrigger {
+    fn from(payload: CommitTriggerPayload) -> Self {
+        let failure_policy = match payload.retry_attempts {
+            None => TriggerFailurePolicy::Log,
+            Some(attempts) => TriggerFailurePolicy::Retry { attempts },
+        };
+        CommitTrigger { name: payload.name, type_label: payload.type_label, function: payload.function, failure_policy }
+    }
+}
+
+pub(crate) fn encode_commit_triggers(triggers: Vec<CommitTrigger>) -> CommitTriggersPayload {
+    let triggers = triggers
+        .into_iter()
+        .map(|trigger| CommitTriggerPayload {
+            name: trigger.name,
+            type_label: trigger.type_label,
+            function: trigger.function,
+            retry_attempts: match trigger.failure_policy {
+                TriggerFailurePolicy::Log => None,
+                TriggerFailurePolicy::Retry { attempts } => Some(attempts),
+            },
+        })
+        .collect();
+    CommitTriggersPayload { triggers }
+}
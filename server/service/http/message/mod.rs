@@ -7,9 +7,11 @@
 pub mod analyze;
 pub mod authentication;
 pub(crate) mod body;
+pub mod cluster;
 pub mod database;
 pub mod error;
 pub mod query;
+pub(crate) mod snapshot;
 pub mod transaction;
 pub mod user;
 pub(crate) mod version;
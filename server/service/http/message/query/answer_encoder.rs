@@ -0,0 +1,80 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use compiler::VariablePosition;
+use concept::{error::ConceptReadError, thing::thing_manager::ThingManager, type_::type_manager::TypeManager};
+use executor::{document::ConceptDocument, row::MaybeOwnedRow};
+use ir::pipeline::ParameterRegistry;
+use options::QueryOptions;
+use resource::profile::StorageCounters;
+use storage::snapshot::ReadableSnapshot;
+
+use crate::service::{
+    http::message::query::{concept::ConceptEncodingOptions, document::encode_document, row::encode_row},
+    intern_pool::InternPool,
+    transaction_service::AnswerEncoder,
+    IncludeInvolvedBlocks,
+};
+
+pub(crate) struct JsonAnswerEncoder {
+    options: ConceptEncodingOptions,
+    intern_pool: InternPool,
+}
+
+impl JsonAnswerEncoder {
+    pub(crate) fn new(query_options: &QueryOptions, intern_pool: InternPool) -> Self {
+        Self { options: ConceptEncodingOptions::from(query_options), intern_pool }
+    }
+}
+
+impl AnswerEncoder for JsonAnswerEncoder {
+    type Row = serde_json::Value;
+    type Document = serde_json::Value;
+
+    fn encode_row(
+        &mut self,
+        row: MaybeOwnedRow<'_>,
+        columns: &[(String, VariablePosition)],
+        snapshot: &impl ReadableSnapshot,
+        type_manager: &TypeManager,
+        thing_manager: &ThingManager,
+        include_involved_blocks: &IncludeInvolvedBlocks,
+        storage_counters: StorageCounters,
+    ) -> Result<Self::Row, Box<ConceptReadError>> {
+        encode_row(
+            row,
+            columns,
+            snapshot,
+            type_manager,
+            thing_manager,
+            &self.options,
+            include_involved_blocks,
+            storage_counters,
+            &self.intern_pool,
+        )
+    }
+
+    fn encode_document(
+        &mut self,
+        document: ConceptDocument,
+        snapshot: &impl ReadableSnapshot,
+        type_manager: &TypeManager,
+        thing_manager: &ThingManager,
+        parameters: &ParameterRegistry,
+        storage_counters: StorageCounters,
+    ) -> Result<Self::Document, Box<ConceptReadError>> {
+        encode_document(
+            document,
+            snapshot,
+            type_manager,
+            thing_manager,
+            parameters,
+            &self.options,
+            storage_counters,
+            &self.intern_pool,
+        )
+    }
+}
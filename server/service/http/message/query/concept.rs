@@ -4,25 +4,32 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::borrow::Cow;
+use std::{borrow::Cow, sync::Arc};
 
 use answer::{Thing, Type};
 use bytes::{util::HexBytesFormatter, Bytes};
+use chrono::NaiveTime;
 use concept::{
     error::ConceptReadError,
     thing::{attribute::Attribute, entity::Entity, relation::Relation, thing_manager::ThingManager, ThingAPI},
     type_::{
         attribute_type::AttributeType, entity_type::EntityType, relation_type::RelationType, role_type::RoleType,
-        type_manager::TypeManager, TypeAPI,
+        type_manager::TypeManager,
     },
 };
 use encoding::value::{value::Value, value_type::ValueType, ValueEncodable};
 use error::unimplemented_feature;
-use resource::profile::StorageCounters;
+use options::{QueryOptions, TemporalFormat};
+use resource::{
+    constants::server::{DEFAULT_INCLUDE_INSTANCE_IIDS, DEFAULT_INCLUDE_INSTANCE_TYPES, DEFAULT_INCLUDE_VALUE_TYPES},
+    profile::StorageCounters,
+};
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 use serde_json::json;
 use storage::snapshot::ReadableSnapshot;
 
+use crate::service::intern_pool::InternPool;
+
 // TODO: Should probably be merged with JSON from behaviour/steps/query_answer_context.rs.
 // Now, it's easier to have symmetry between two services, and we don't have the capacity to merge
 // these (BDDs will check if this code is correct)
@@ -32,17 +39,48 @@ macro_rules! count_fields {
     ($head:ident $(, $tail:ident)*) => { 1 + count_fields!($($tail),*) };
 }
 
+/// Controls which parts of a concept are written into its JSON answer, and how its values are rendered
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ConceptEncodingOptions {
+    pub include_instance_types: bool,
+    pub include_instance_iids: bool,
+    pub include_value_types: bool,
+    pub temporal_format: TemporalFormat,
+}
+
+impl Default for ConceptEncodingOptions {
+    fn default() -> Self {
+        Self {
+            include_instance_types: DEFAULT_INCLUDE_INSTANCE_TYPES,
+            include_instance_iids: DEFAULT_INCLUDE_INSTANCE_IIDS,
+            include_value_types: DEFAULT_INCLUDE_VALUE_TYPES,
+            temporal_format: TemporalFormat::default(),
+        }
+    }
+}
+
+impl From<&QueryOptions> for ConceptEncodingOptions {
+    fn from(options: &QueryOptions) -> Self {
+        Self {
+            include_instance_types: options.include_instance_types,
+            include_instance_iids: options.include_instance_iids,
+            include_value_types: options.include_value_types,
+            temporal_format: options.temporal_format,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "kind", rename = "entity")]
 pub struct EntityResponse {
-    pub iid: String,
+    pub iid: Option<String>,
     pub r#type: Option<EntityTypeResponse>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "kind", rename = "relation")]
 pub struct RelationResponse {
-    pub iid: String,
+    pub iid: Option<String>,
     pub r#type: Option<RelationTypeResponse>,
 }
 
@@ -50,7 +88,7 @@ pub struct RelationResponse {
 #[serde(rename_all = "camelCase", tag = "kind", rename = "attribute")]
 pub struct AttributeResponse {
     pub value: serde_json::Value,
-    pub value_type: String,
+    pub value_type: Option<String>,
     pub r#type: Option<AttributeTypeResponse>,
 }
 
@@ -58,46 +96,46 @@ pub struct AttributeResponse {
 #[serde(rename_all = "camelCase", tag = "kind", rename = "value")]
 pub struct ValueResponse {
     pub value: serde_json::Value,
-    pub value_type: String,
+    pub value_type: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "kind", rename = "entityType")]
 pub struct EntityTypeResponse {
-    pub label: String,
+    pub label: Arc<str>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "kind", rename = "relationType")]
 pub struct RelationTypeResponse {
-    pub label: String,
+    pub label: Arc<str>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "kind", rename = "attributeType")]
 pub struct AttributeTypeResponse {
-    pub label: String,
+    pub label: Arc<str>,
     pub value_type: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "kind", rename = "roleType")]
 pub struct RoleTypeResponse {
-    pub label: String,
+    pub label: Arc<str>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TypeDocument {
     kind: String,
-    label: String,
+    label: Arc<str>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct AttributeTypeDocument {
     kind: String,
-    label: String,
+    label: Arc<str>,
     value_type: String,
 }
 
@@ -146,16 +184,17 @@ pub fn encode_thing_concept(
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
-    include_instance_types: bool,
+    options: &ConceptEncodingOptions,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<serde_json::Value, Box<ConceptReadError>> {
     let response = match thing {
         Thing::Entity(entity) => {
-            serde_json::to_value(encode_entity(entity, snapshot, type_manager, include_instance_types)?)
+            serde_json::to_value(encode_entity(entity, snapshot, type_manager, options, intern_pool)?)
                 .expect("Expected json value conversion")
         }
         Thing::Relation(relation) => {
-            serde_json::to_value(encode_relation(relation, snapshot, type_manager, include_instance_types)?)
+            serde_json::to_value(encode_relation(relation, snapshot, type_manager, options, intern_pool)?)
                 .expect("Expected json value conversion")
         }
         Thing::Attribute(attribute) => serde_json::to_value(encode_attribute(
@@ -163,8 +202,9 @@ pub fn encode_thing_concept(
             snapshot,
             type_manager,
             thing_manager,
-            include_instance_types,
+            options,
             storage_counters,
+            intern_pool,
         )?)
         .expect("Expected json value conversion"),
     };
@@ -175,12 +215,13 @@ pub fn encode_entity(
     entity: &Entity,
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
-    include_instance_types: bool,
+    options: &ConceptEncodingOptions,
+    intern_pool: &InternPool,
 ) -> Result<EntityResponse, Box<ConceptReadError>> {
     Ok(EntityResponse {
-        iid: encode_iid(entity.iid()),
-        r#type: if include_instance_types {
-            Some(encode_entity_type(&entity.type_(), snapshot, type_manager)?)
+        iid: options.include_instance_iids.then(|| encode_iid(entity.iid())),
+        r#type: if options.include_instance_types {
+            Some(encode_entity_type(&entity.type_(), snapshot, type_manager, intern_pool)?)
         } else {
             None
         },
@@ -191,12 +232,13 @@ pub fn encode_relation(
     relation: &Relation,
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
-    include_instance_types: bool,
+    options: &ConceptEncodingOptions,
+    intern_pool: &InternPool,
 ) -> Result<RelationResponse, Box<ConceptReadError>> {
     Ok(RelationResponse {
-        iid: encode_iid(relation.iid()),
-        r#type: if include_instance_types {
-            Some(encode_relation_type(&relation.type_(), snapshot, type_manager)?)
+        iid: options.include_instance_iids.then(|| encode_iid(relation.iid())),
+        r#type: if options.include_instance_types {
+            Some(encode_relation_type(&relation.type_(), snapshot, type_manager, intern_pool)?)
         } else {
             None
         },
@@ -208,18 +250,21 @@ pub fn encode_attribute(
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
-    include_instance_types: bool,
+    options: &ConceptEncodingOptions,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<AttributeResponse, Box<ConceptReadError>> {
-    let value = attribute.get_value(snapshot, thing_manager, storage_counters)?;
+    let r#type = if options.include_instance_types {
+        Some(encode_attribute_type(&attribute.type_(), snapshot, type_manager, intern_pool)?)
+    } else {
+        None
+    };
+    let interned = intern_pool.attribute(attribute, snapshot, thing_manager, storage_counters.clone())?;
+    let value = interned.get_value(snapshot, thing_manager, storage_counters)?;
     Ok(AttributeResponse {
-        value_type: encode_value_value_type(&value),
-        value: encode_value_value(value),
-        r#type: if include_instance_types {
-            Some(encode_attribute_type(&attribute.type_(), snapshot, type_manager)?)
-        } else {
-            None
-        },
+        value_type: options.include_value_types.then(|| encode_value_value_type(&value)),
+        value: encode_value_value(value, options.temporal_format),
+        r#type,
     })
 }
 
@@ -231,19 +276,20 @@ pub fn encode_type_concept(
     type_: &Type,
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
+    intern_pool: &InternPool,
 ) -> Result<serde_json::Value, Box<ConceptReadError>> {
     let encoded = match type_ {
         Type::Entity(entity) => {
-            json!(encode_entity_type(entity, snapshot, type_manager)?)
+            json!(encode_entity_type(entity, snapshot, type_manager, intern_pool)?)
         }
         Type::Relation(relation) => {
-            json!(encode_relation_type(relation, snapshot, type_manager)?)
+            json!(encode_relation_type(relation, snapshot, type_manager, intern_pool)?)
         }
         Type::Attribute(attribute) => {
-            json!(encode_attribute_type(attribute, snapshot, type_manager)?)
+            json!(encode_attribute_type(attribute, snapshot, type_manager, intern_pool)?)
         }
         Type::RoleType(role) => {
-            json!(encode_role_type(role, snapshot, type_manager)?)
+            json!(encode_role_type(role, snapshot, type_manager, intern_pool)?)
         }
     };
     Ok(encoded)
@@ -253,25 +299,28 @@ pub fn encode_entity_type(
     entity: &EntityType,
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
+    intern_pool: &InternPool,
 ) -> Result<EntityTypeResponse, Box<ConceptReadError>> {
-    Ok(EntityTypeResponse { label: entity.get_label(snapshot, type_manager)?.scoped_name().as_str().to_string() })
+    Ok(EntityTypeResponse { label: intern_pool.label(Type::Entity(*entity), snapshot, type_manager)? })
 }
 
 pub fn encode_relation_type(
     relation: &RelationType,
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
+    intern_pool: &InternPool,
 ) -> Result<RelationTypeResponse, Box<ConceptReadError>> {
-    Ok(RelationTypeResponse { label: relation.get_label(snapshot, type_manager)?.scoped_name().as_str().to_string() })
+    Ok(RelationTypeResponse { label: intern_pool.label(Type::Relation(*relation), snapshot, type_manager)? })
 }
 
 pub fn encode_attribute_type(
     attribute: &AttributeType,
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
+    intern_pool: &InternPool,
 ) -> Result<AttributeTypeResponse, Box<ConceptReadError>> {
     Ok(AttributeTypeResponse {
-        label: attribute.get_label(snapshot, type_manager)?.scoped_name().as_str().to_string(),
+        label: intern_pool.label(Type::Attribute(*attribute), snapshot, type_manager)?,
         value_type: {
             attribute
                 .get_value_type_without_source(snapshot, type_manager)?
@@ -285,20 +334,21 @@ pub fn encode_role_type(
     role: &RoleType,
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
+    intern_pool: &InternPool,
 ) -> Result<RoleTypeResponse, Box<ConceptReadError>> {
-    Ok(RoleTypeResponse { label: role.get_label(snapshot, type_manager)?.scoped_name().as_str().to_string() })
+    Ok(RoleTypeResponse { label: intern_pool.label(Type::RoleType(*role), snapshot, type_manager)? })
 }
 
-pub fn encode_value(value: Value<'_>) -> ValueResponse {
-    let value_type = encode_value_value_type(&value);
-    ValueResponse { value: encode_value_value(value), value_type }
+pub fn encode_value(value: Value<'_>, options: &ConceptEncodingOptions) -> ValueResponse {
+    let value_type = options.include_value_types.then(|| encode_value_value_type(&value));
+    ValueResponse { value: encode_value_value(value, options.temporal_format), value_type }
 }
 
 pub fn encode_value_value_type(value: &Value<'_>) -> String {
     value.value_type().to_string()
 }
 
-pub fn encode_value_value(value: Value<'_>) -> serde_json::Value {
+pub fn encode_value_value(value: Value<'_>, temporal_format: TemporalFormat) -> serde_json::Value {
     match value {
         Value::Boolean(bool) => json!(bool),
         Value::Integer(integer) => json!(integer),
@@ -307,6 +357,15 @@ pub fn encode_value_value(value: Value<'_>) -> serde_json::Value {
             Cow::Borrowed(s) => s.to_string(),
             Cow::Owned(s) => s.clone(),
         }),
+        Value::Date(date) if temporal_format == TemporalFormat::EpochMillis => {
+            json!(date.and_time(NaiveTime::MIN).and_utc().timestamp_millis())
+        }
+        Value::DateTime(date_time) if temporal_format == TemporalFormat::EpochMillis => {
+            json!(date_time.and_utc().timestamp_millis())
+        }
+        Value::DateTimeTZ(date_time_tz) if temporal_format == TemporalFormat::EpochMillis => {
+            json!(date_time_tz.timestamp_millis())
+        }
         Value::Decimal(_) | Value::Date(_) | Value::DateTime(_) | Value::DateTimeTZ(_) | Value::Duration(_) => {
             json!(value.to_string())
         }
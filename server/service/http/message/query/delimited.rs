@@ -0,0 +1,153 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use axum::response::{IntoResponse, Response};
+use http::{header, HeaderMap, HeaderValue};
+use serde_json::Value;
+use typeql::query::{stage::Stage, QueryStructure};
+
+use crate::service::http::{error::HttpServiceError, transaction_service::QueryAnswer};
+
+const CSV_MEDIA_TYPE: &str = "text/csv";
+const TSV_MEDIA_TYPE: &str = "text/tab-separated-values";
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum DelimitedFormat {
+    Csv,
+    Tsv,
+}
+
+impl DelimitedFormat {
+    /// Returns the delimited format requested by the `Accept` header, unless JSON is preferred over it.
+    /// Quality values are not considered: the first supported media range wins.
+    pub(crate) fn from_accept(headers: &HeaderMap) -> Option<Self> {
+        let accept = headers.get(header::ACCEPT)?.to_str().ok()?;
+        for media_range in accept.split(',') {
+            let media_type = media_range.split(';').next().unwrap_or_default().trim();
+            match media_type {
+                CSV_MEDIA_TYPE => return Some(Self::Csv),
+                TSV_MEDIA_TYPE => return Some(Self::Tsv),
+                "application/json" | "application/*" | "*/*" => return None,
+                _ => (),
+            }
+        }
+        None
+    }
+
+    /// Rejects queries answering with documents, which have no delimited encoding, before they are executed.
+    /// Queries failing to parse are let through, so that the parsing error is reported by the execution.
+    pub(crate) fn check_answerable(&self, query: &str) -> Result<(), HttpServiceError> {
+        let Ok(parsed) = typeql::parse_query(query) else {
+            return Ok(());
+        };
+        match parsed.into_structure() {
+            QueryStructure::Pipeline(pipeline) if matches!(pipeline.stages.last(), Some(Stage::Fetch(_))) => {
+                Err(HttpServiceError::DocumentsNotAcceptable { media_type: self.media_type().to_string() })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn media_type(&self) -> &'static str {
+        match self {
+            Self::Csv => CSV_MEDIA_TYPE,
+            Self::Tsv => TSV_MEDIA_TYPE,
+        }
+    }
+
+    fn delimiter(&self) -> char {
+        match self {
+            Self::Csv => ',',
+            Self::Tsv => '\t',
+        }
+    }
+
+    fn escape_field(&self, field: &str) -> String {
+        match self {
+            // RFC 4180: fields containing delimiters, quotes or line breaks are quoted, with quotes doubled
+            Self::Csv => {
+                if field.contains([',', '"', '\n', '\r']) {
+                    format!("\"{}\"", field.replace('"', "\"\""))
+                } else {
+                    field.to_owned()
+                }
+            }
+            // TSV fields cannot contain tabs or line breaks, so these are written as backslash escapes
+            Self::Tsv => field.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r"),
+        }
+    }
+
+    fn encode_record<'a>(&self, fields: impl Iterator<Item = &'a str>, encoded: &mut String) {
+        for (index, field) in fields.enumerate() {
+            if index > 0 {
+                encoded.push(self.delimiter());
+            }
+            encoded.push_str(&self.escape_field(field));
+        }
+        encoded.push_str("\r\n");
+    }
+}
+
+/// Renders JSON-encoded rows as delimited text, with a header record of the variable names.
+pub(crate) fn encode_rows_delimited(format: DelimitedFormat, columns: &[String], rows: &[Value]) -> String {
+    let mut encoded = String::new();
+    format.encode_record(columns.iter().map(String::as_str), &mut encoded);
+    for row in rows {
+        let fields: Vec<String> =
+            columns.iter().map(|column| scalarise_concept(&row["data"][column.as_str()])).collect();
+        format.encode_record(fields.iter().map(String::as_str), &mut encoded);
+    }
+    encoded
+}
+
+/// Objects are represented by their IIDs, types by their labels, and attributes and values by their values.
+/// Lists and structs have no scalar form, so they are written as JSON.
+fn scalarise_concept(concept: &Value) -> String {
+    match concept {
+        Value::Null => String::new(),
+        Value::Object(object) => match object.get("kind").and_then(Value::as_str) {
+            Some("entity" | "relation") => scalarise_value(&object["iid"]),
+            Some("attribute" | "value") => scalarise_value(&object["value"]),
+            Some("entityType" | "relationType" | "attributeType" | "roleType") => scalarise_value(&object["label"]),
+            _ => concept.to_string(),
+        },
+        Value::Array(concepts) => {
+            Value::Array(concepts.iter().map(|concept| Value::String(scalarise_concept(concept))).collect()).to_string()
+        }
+        _ => scalarise_value(concept),
+    }
+}
+
+fn scalarise_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(string) => string.clone(),
+        Value::Bool(_) | Value::Number(_) | Value::Array(_) | Value::Object(_) => value.to_string(),
+    }
+}
+
+pub(crate) struct DelimitedQueryAnswer(pub QueryAnswer, pub DelimitedFormat);
+
+impl IntoResponse for DelimitedQueryAnswer {
+    fn into_response(self) -> Response {
+        let Self(answer, format) = self;
+        let code = answer.status_code();
+        let body = match answer {
+            QueryAnswer::ResOk(_) | QueryAnswer::ResSchemaChanges(_) => String::new(),
+            QueryAnswer::ResRows((_, columns, rows, _, _, _)) => encode_rows_delimited(format, &columns, &rows),
+            // rejected by `check_answerable` before execution
+            QueryAnswer::ResDocuments(_) => {
+                return HttpServiceError::DocumentsNotAcceptable { media_type: format.media_type().to_string() }
+                    .into_response()
+            }
+        };
+        let content_type = HeaderValue::from_static(match format {
+            DelimitedFormat::Csv => "text/csv; charset=utf-8",
+            DelimitedFormat::Tsv => "text/tab-separated-values; charset=utf-8",
+        });
+        (code, [(header::CONTENT_TYPE, content_type)], body).into_response()
+    }
+}
@@ -11,13 +11,16 @@ use concept::{error::ConceptReadError, thing::thing_manager::ThingManager, type_
 use executor::document::{ConceptDocument, DocumentLeaf, DocumentList, DocumentMap, DocumentNode};
 use ir::pipeline::ParameterRegistry;
 use itertools::Itertools;
-use resource::{constants::server::DEFAULT_INCLUDE_INSTANCE_TYPES_FETCH, profile::StorageCounters};
+use resource::profile::StorageCounters;
 use serde_json::json;
 use storage::snapshot::ReadableSnapshot;
 
-use crate::service::http::message::query::concept::{
-    encode_attribute, encode_attribute_type, encode_entity_type, encode_relation_type, encode_role_type, encode_value,
-    AttributeTypeDocument, TypeDocument,
+use crate::service::{
+    http::message::query::concept::{
+        encode_attribute, encode_attribute_type, encode_entity_type, encode_relation_type, encode_role_type,
+        encode_value, AttributeTypeDocument, ConceptEncodingOptions, TypeDocument,
+    },
+    intern_pool::InternPool,
 };
 
 pub fn encode_document(
@@ -26,9 +29,20 @@ pub fn encode_document(
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
     parameters: &ParameterRegistry,
+    options: &ConceptEncodingOptions,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<serde_json::Value, Box<ConceptReadError>> {
-    Ok(json!(encode_node(document.root, snapshot, type_manager, thing_manager, parameters, storage_counters)?))
+    Ok(json!(encode_node(
+        document.root,
+        snapshot,
+        type_manager,
+        thing_manager,
+        parameters,
+        options,
+        storage_counters,
+        intern_pool
+    )?))
 }
 
 fn encode_node(
@@ -37,17 +51,33 @@ fn encode_node(
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
     parameters: &ParameterRegistry,
+    options: &ConceptEncodingOptions,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<serde_json::Value, Box<ConceptReadError>> {
     match node {
-        DocumentNode::List(list) => {
-            Ok(json!(encode_list(list, snapshot, type_manager, thing_manager, parameters, storage_counters)?))
-        }
-        DocumentNode::Map(map) => {
-            Ok(json!(encode_map(map, snapshot, type_manager, thing_manager, parameters, storage_counters)?))
-        }
+        DocumentNode::List(list) => Ok(json!(encode_list(
+            list,
+            snapshot,
+            type_manager,
+            thing_manager,
+            parameters,
+            options,
+            storage_counters,
+            intern_pool
+        )?)),
+        DocumentNode::Map(map) => Ok(json!(encode_map(
+            map,
+            snapshot,
+            type_manager,
+            thing_manager,
+            parameters,
+            options,
+            storage_counters,
+            intern_pool
+        )?)),
         DocumentNode::Leaf(leaf) => {
-            Ok(json!(encode_leaf(leaf, snapshot, type_manager, thing_manager, storage_counters)?))
+            Ok(json!(encode_leaf(leaf, snapshot, type_manager, thing_manager, options, storage_counters, intern_pool)?))
         }
     }
 }
@@ -58,15 +88,25 @@ fn encode_map(
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
     parameters: &ParameterRegistry,
+    options: &ConceptEncodingOptions,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<serde_json::Value, Box<ConceptReadError>> {
     let encoded_map = match map {
         DocumentMap::UserKeys(map) => {
             let mut encoded_map = HashMap::with_capacity(map.len());
             for (key, value) in map.into_iter() {
                 let key_name = parameters.fetch_key(&key).expect("Expected key in parameters to get its name");
-                let encoded_value =
-                    encode_node(value, snapshot, type_manager, thing_manager, parameters, storage_counters.clone())?;
+                let encoded_value = encode_node(
+                    value,
+                    snapshot,
+                    type_manager,
+                    thing_manager,
+                    parameters,
+                    options,
+                    storage_counters.clone(),
+                    intern_pool,
+                )?;
                 encoded_map.insert(key_name.to_owned(), encoded_value);
             }
             encoded_map
@@ -74,8 +114,16 @@ fn encode_map(
         DocumentMap::GeneratedKeys(map) => {
             let mut encoded_map = HashMap::with_capacity(map.len());
             for (key, value) in map.into_iter() {
-                let encoded_value =
-                    encode_node(value, snapshot, type_manager, thing_manager, parameters, storage_counters.clone())?;
+                let encoded_value = encode_node(
+                    value,
+                    snapshot,
+                    type_manager,
+                    thing_manager,
+                    parameters,
+                    options,
+                    storage_counters.clone(),
+                    intern_pool,
+                )?;
                 encoded_map.insert(key.scoped_name().as_str().to_owned(), encoded_value);
             }
             encoded_map
@@ -90,12 +138,25 @@ fn encode_list(
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
     parameters: &ParameterRegistry,
+    options: &ConceptEncodingOptions,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<serde_json::Value, Box<ConceptReadError>> {
     let encoded_list: Vec<serde_json::Value> = list
         .list
         .into_iter()
-        .map(|node| encode_node(node, snapshot, type_manager, thing_manager, parameters, storage_counters.clone()))
+        .map(|node| {
+            encode_node(
+                node,
+                snapshot,
+                type_manager,
+                thing_manager,
+                parameters,
+                options,
+                storage_counters.clone(),
+                intern_pool,
+            )
+        })
         .try_collect()
         .expect("Expected json value list conversion");
     Ok(json!(encoded_list))
@@ -106,27 +167,39 @@ fn encode_leaf(
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
+    options: &ConceptEncodingOptions,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<serde_json::Value, Box<ConceptReadError>> {
-    let include_instance_types = DEFAULT_INCLUDE_INSTANCE_TYPES_FETCH; // TODO: May it be affected by QueryOptions?
     match leaf {
         DocumentLeaf::Empty => Ok(serde_json::Value::Null),
         DocumentLeaf::Concept(concept) => Ok(json!(match concept {
             Concept::Type(Type::Entity(entity_type)) => {
-                json!(Into::<TypeDocument>::into(encode_entity_type(&entity_type, snapshot, type_manager)?))
+                json!(Into::<TypeDocument>::into(encode_entity_type(
+                    &entity_type,
+                    snapshot,
+                    type_manager,
+                    intern_pool
+                )?))
             }
             Concept::Type(Type::Relation(relation_type)) => {
-                json!(Into::<TypeDocument>::into(encode_relation_type(&relation_type, snapshot, type_manager)?))
+                json!(Into::<TypeDocument>::into(encode_relation_type(
+                    &relation_type,
+                    snapshot,
+                    type_manager,
+                    intern_pool
+                )?))
             }
             Concept::Type(Type::Attribute(attribute_type)) => {
                 json!(Into::<AttributeTypeDocument>::into(encode_attribute_type(
                     &attribute_type,
                     snapshot,
-                    type_manager
+                    type_manager,
+                    intern_pool
                 )?))
             }
             Concept::Type(Type::RoleType(role_type)) => {
-                json!(Into::<TypeDocument>::into(encode_role_type(&role_type, snapshot, type_manager)?))
+                json!(Into::<TypeDocument>::into(encode_role_type(&role_type, snapshot, type_manager, intern_pool)?))
             }
             Concept::Thing(Thing::Entity(_)) => {
                 unreachable!("Entities are not represented as documents")
@@ -140,12 +213,13 @@ fn encode_leaf(
                     snapshot,
                     type_manager,
                     thing_manager,
-                    include_instance_types,
+                    options,
                     storage_counters,
+                    intern_pool,
                 )?)
             }
             Concept::Value(value) => {
-                Into::<serde_json::Value>::into(encode_value(value))
+                Into::<serde_json::Value>::into(encode_value(value, options))
             }
         })),
         DocumentLeaf::Kind(kind) => Ok(json!(kind.name())),
@@ -5,36 +5,78 @@
  */
 
 use axum::response::{IntoResponse, Response};
-use options::QueryOptions;
+use compiler::warning::QueryWarning;
+use concept::type_::type_manager::schema_diff::SchemaDiff;
+use options::{QueryOptions, TemporalFormat};
 use resource::constants::server::{
-    DEFAULT_ANSWER_COUNT_LIMIT_HTTP, DEFAULT_INCLUDE_INSTANCE_TYPES, DEFAULT_INCLUDE_STRUCTURE_HTTP,
-    DEFAULT_PREFETCH_SIZE,
+    DEFAULT_ANSWER_BATCH_SIZE, DEFAULT_ANSWER_COUNT_LIMIT_HTTP, DEFAULT_DRY_RUN, DEFAULT_INCLUDE_INSTANCE_IIDS,
+    DEFAULT_INCLUDE_INSTANCE_TYPES, DEFAULT_INCLUDE_QUERY_STATS, DEFAULT_INCLUDE_STRUCTURE_HTTP,
+    DEFAULT_INCLUDE_VALUE_TYPES, DEFAULT_OMIT_NULL_COLUMNS, DEFAULT_PREFETCH_SIZE,
 };
+use resource::profile::QueryProfile;
 use serde::{Deserialize, Serialize};
 
 use crate::service::{
     http::{
         message::{analyze::structure::AnalyzedPipelineResponse, body::JsonBody, transaction::TransactionOpenPayload},
-        transaction_service::QueryAnswer,
+        transaction_service::{QueryAnswer, QueryAnswerWarning},
     },
     AnswerType, QueryType,
 };
 
+pub(crate) mod answer_encoder;
 pub mod concept;
+pub(crate) mod delimited;
 pub mod document;
 pub mod row;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryOptionsPayload {
     pub include_instance_types: Option<bool>,
+    pub include_instance_iids: Option<bool>,
+    pub include_value_types: Option<bool>,
+    pub temporal_format: Option<TemporalFormatPayload>,
     pub answer_count_limit: Option<u64>,
+    pub prefetch_rows: Option<u64>,
+    pub answer_batch_size: Option<u64>,
     pub include_query_structure: Option<bool>,
+    pub omit_null_columns: Option<bool>,
+    pub include_query_stats: Option<bool>,
+    pub dry_run: Option<bool>,
 }
 
 impl Default for QueryOptionsPayload {
     fn default() -> Self {
-        Self { include_instance_types: None, answer_count_limit: None, include_query_structure: None }
+        Self {
+            include_instance_types: None,
+            include_instance_iids: None,
+            include_value_types: None,
+            temporal_format: None,
+            answer_count_limit: None,
+            prefetch_rows: None,
+            answer_batch_size: None,
+            include_query_structure: None,
+            omit_null_columns: None,
+            include_query_stats: None,
+            dry_run: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum TemporalFormatPayload {
+    Iso8601,
+    EpochMillis,
+}
+
+impl Into<TemporalFormat> for TemporalFormatPayload {
+    fn into(self) -> TemporalFormat {
+        match self {
+            TemporalFormatPayload::Iso8601 => TemporalFormat::Iso8601,
+            TemporalFormatPayload::EpochMillis => TemporalFormat::EpochMillis,
+        }
     }
 }
 
@@ -42,12 +84,22 @@ impl Into<QueryOptions> for QueryOptionsPayload {
     fn into(self) -> QueryOptions {
         QueryOptions {
             include_instance_types: self.include_instance_types.unwrap_or(DEFAULT_INCLUDE_INSTANCE_TYPES),
+            include_instance_iids: self.include_instance_iids.unwrap_or(DEFAULT_INCLUDE_INSTANCE_IIDS),
+            include_value_types: self.include_value_types.unwrap_or(DEFAULT_INCLUDE_VALUE_TYPES),
+            temporal_format: self.temporal_format.map(Into::into).unwrap_or_default(),
             answer_count_limit: self
                 .answer_count_limit
                 .map(|option| Some(option as usize))
                 .unwrap_or(DEFAULT_ANSWER_COUNT_LIMIT_HTTP),
-            prefetch_size: DEFAULT_PREFETCH_SIZE as usize,
+            prefetch_size: self.prefetch_rows.map(|option| option as usize).unwrap_or(DEFAULT_PREFETCH_SIZE),
+            answer_batch_size: self
+                .answer_batch_size
+                .map(|option| option as usize)
+                .unwrap_or(DEFAULT_ANSWER_BATCH_SIZE),
             include_query_structure: self.include_query_structure.unwrap_or(DEFAULT_INCLUDE_STRUCTURE_HTTP),
+            omit_null_columns: self.omit_null_columns.unwrap_or(DEFAULT_OMIT_NULL_COLUMNS),
+            include_query_stats: self.include_query_stats.unwrap_or(DEFAULT_INCLUDE_QUERY_STATS),
+            dry_run: self.dry_run.unwrap_or(DEFAULT_DRY_RUN),
         }
     }
 }
@@ -57,6 +109,8 @@ impl Into<QueryOptions> for QueryOptionsPayload {
 pub struct TransactionQueryPayload {
     pub query_options: Option<QueryOptionsPayload>,
     pub query: String,
+    /// The database to query, which queries in multi-database transactions must name
+    pub database_name: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -65,6 +119,9 @@ pub struct QueryPayload {
     pub query_options: Option<QueryOptionsPayload>,
     pub query: String,
     pub commit: Option<bool>,
+    pub max_conflict_retries: Option<u32>,
+    /// Pins a read query to the data version of an earlier answer, instead of the latest data
+    pub snapshot_token: Option<String>,
 
     #[serde(flatten)]
     pub transaction_open_payload: TransactionOpenPayload,
@@ -77,39 +134,150 @@ pub struct QueryAnswerResponse {
     pub answer_type: AnswerType,
     pub answers: Option<Vec<serde_json::Value>>,
     pub query: Option<AnalyzedPipelineResponse>,
+    /// The message of the first warning, kept for clients which predate the structured warnings
     pub warning: Option<String>,
+    #[serde(default)]
+    pub warnings: Vec<QueryWarningResponse>,
+    #[serde(default)]
+    pub stats: Option<QueryStatsResponse>,
+    #[serde(default)]
+    pub schema_changes: Option<SchemaChangesResponse>,
+}
+
+/// The TypeQL statements a dry run schema query would apply, on top of the schema changes already in the transaction.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaChangesResponse {
+    pub define: Vec<String>,
+    pub redefine: Vec<String>,
+    pub undefine: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryStatsResponse {
+    pub rows_returned: usize,
+    pub planning_micros: f64,
+    pub execution_micros: f64,
+    pub storage_reads: u64,
+    #[serde(default)]
+    pub stages: Vec<QueryStageStatsResponse>,
+    #[serde(default)]
+    pub fingerprint: Option<String>,
+}
+
+/// The storage reads attributed to one stage of the query pipeline, or to a pattern or function within it.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryStageStatsResponse {
+    pub description: String,
+    pub storage_reads: u64,
+}
+
+/// Statistics of an executed query, which must have been prepared with its profile enabled.
+/// Execution time is only spent in the executor, excluding answer encoding and transmission.
+pub(crate) fn encode_query_stats(query_profile: &QueryProfile, rows_returned: usize) -> QueryStatsResponse {
+    QueryStatsResponse {
+        rows_returned,
+        planning_micros: query_profile.compilation_micros(),
+        execution_micros: query_profile.execution_micros(),
+        storage_reads: query_profile.storage_reads(),
+        stages: query_profile
+            .stage_storage_reads()
+            .into_iter()
+            .map(|(description, storage_reads)| QueryStageStatsResponse { description, storage_reads })
+            .collect(),
+        fingerprint: query_profile.fingerprint().map(|fingerprint| format!("{fingerprint:016x}")),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryWarningResponse {
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintPayload {
+    pub query: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintResponse {
+    pub warnings: Vec<QueryWarningResponse>,
 }
 
 pub(crate) fn encode_query_ok_answer(query_type: QueryType) -> QueryAnswerResponse {
-    QueryAnswerResponse { answer_type: AnswerType::Ok, query_type, answers: None, query: None, warning: None }
+    QueryAnswerResponse {
+        answer_type: AnswerType::Ok,
+        query_type,
+        answers: None,
+        query: None,
+        warning: None,
+        warnings: Vec::new(),
+        stats: None,
+        schema_changes: None,
+    }
+}
+
+pub(crate) fn encode_query_schema_changes_answer(schema_diff: SchemaDiff) -> QueryAnswerResponse {
+    let SchemaDiff { define, redefine, undefine } = schema_diff;
+    QueryAnswerResponse {
+        schema_changes: Some(SchemaChangesResponse { define, redefine, undefine }),
+        ..encode_query_ok_answer(QueryType::Schema)
+    }
 }
 
 pub(crate) fn encode_query_rows_answer(
     query_type: QueryType,
     rows: Vec<serde_json::Value>,
     pipeline_structure: Option<AnalyzedPipelineResponse>,
-    warning: Option<String>,
+    warnings: &[QueryAnswerWarning],
+    stats: Option<QueryStatsResponse>,
 ) -> QueryAnswerResponse {
     QueryAnswerResponse {
         answer_type: AnswerType::ConceptRows,
         query_type,
         answers: Some(rows),
         query: pipeline_structure,
-        warning,
+        warning: warnings.first().map(|warning| warning.to_string()),
+        warnings: encode_query_warnings(warnings),
+        stats,
+        schema_changes: None,
     }
 }
 
 pub(crate) fn encode_query_documents_answer(
     query_type: QueryType,
     documents: Vec<serde_json::Value>,
-    warning: Option<String>,
+    warnings: &[QueryAnswerWarning],
+    stats: Option<QueryStatsResponse>,
 ) -> QueryAnswerResponse {
     QueryAnswerResponse {
         answer_type: AnswerType::ConceptDocuments,
         answers: Some(documents),
         query_type,
         query: None,
-        warning,
+        warning: warnings.first().map(|warning| warning.to_string()),
+        warnings: encode_query_warnings(warnings),
+        stats,
+        schema_changes: None,
+    }
+}
+
+fn encode_query_warnings(warnings: &[QueryAnswerWarning]) -> Vec<QueryWarningResponse> {
+    warnings.iter().map(|warning| QueryWarningResponse { code: warning.code(), message: warning.to_string() }).collect()
+}
+
+pub(crate) fn encode_lint_warnings(warnings: Vec<QueryWarning>) -> LintResponse {
+    LintResponse {
+        warnings: warnings
+            .iter()
+            .map(|warning| QueryWarningResponse { code: warning.code(), message: warning.to_string() })
+            .collect(),
     }
 }
 
@@ -118,20 +286,46 @@ impl IntoResponse for QueryAnswer {
         let code = self.status_code();
         let body = match self {
             QueryAnswer::ResOk(query_type) => JsonBody(encode_query_ok_answer(query_type)),
-            QueryAnswer::ResRows((query_type, rows, pipeline_structure, warning)) => {
-                JsonBody(encode_query_rows_answer(
-                    query_type,
-                    rows,
-                    pipeline_structure,
-                    warning.map(|warning| warning.to_string()),
-                ))
+            QueryAnswer::ResSchemaChanges(schema_diff) => JsonBody(encode_query_schema_changes_answer(schema_diff)),
+            QueryAnswer::ResRows((query_type, _, rows, pipeline_structure, warnings, stats)) => {
+                JsonBody(encode_query_rows_answer(query_type, rows, pipeline_structure, &warnings, stats))
+            }
+            QueryAnswer::ResDocuments((query_type, documents, warnings, stats)) => {
+                JsonBody(encode_query_documents_answer(query_type, documents, &warnings, stats))
             }
-            QueryAnswer::ResDocuments((query_type, documents, warning)) => JsonBody(encode_query_documents_answer(
-                query_type,
-                documents,
-                warning.map(|warning| warning.to_string()),
-            )),
         };
         (code, body).into_response()
     }
 }
+
+#[cfg(test)]
+pub mod tests {
+    use options::QueryOptions;
+
+    use super::{QueryOptionsPayload, TransactionQueryPayload};
+    use crate::service::transaction_service::{validate_query_options, TransactionServiceError};
+
+    #[test]
+    fn answer_batch_size_and_prefetch_rows_are_read_from_the_payload() {
+        let payload: QueryOptionsPayload =
+            serde_json::from_str(r#"{"answerBatchSize": 10, "prefetchRows": 5}"#).unwrap();
+        let query_options: QueryOptions = payload.into();
+        assert_eq!(query_options.answer_batch_size, 10);
+        assert_eq!(query_options.prefetch_size, 5);
+        assert!(validate_query_options(&query_options).is_ok());
+
+        let payload: QueryOptionsPayload = serde_json::from_str(r#"{"answerBatchSize": 0}"#).unwrap();
+        let result = validate_query_options(&payload.into());
+        assert!(matches!(result, Err(TransactionServiceError::InvalidAnswerBatchSize { value: 0 })), "{result:?}");
+    }
+
+    #[test]
+    fn transaction_queries_name_a_database_only_optionally() {
+        let payload: TransactionQueryPayload = serde_json::from_str(r#"{"query": "match $x isa person;"}"#).unwrap();
+        assert_eq!(payload.database_name, None);
+
+        let payload: TransactionQueryPayload =
+            serde_json::from_str(r#"{"query": "match $x isa person;", "databaseName": "people"}"#).unwrap();
+        assert_eq!(payload.database_name.as_deref(), Some("people"));
+    }
+}
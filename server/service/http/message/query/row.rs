@@ -17,7 +17,8 @@ use serde_json::json;
 use storage::snapshot::ReadableSnapshot;
 
 use crate::service::{
-    http::message::query::concept::{encode_thing_concept, encode_type_concept, encode_value},
+    http::message::query::concept::{encode_thing_concept, encode_type_concept, encode_value, ConceptEncodingOptions},
+    intern_pool::InternPool,
     IncludeInvolvedBlocks,
 };
 
@@ -34,9 +35,10 @@ pub fn encode_row<'a>(
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
-    include_instance_types: bool,
+    options: &ConceptEncodingOptions,
     include_involved_blocks: &IncludeInvolvedBlocks,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<serde_json::Value, Box<ConceptReadError>> {
     // TODO: multiplicity?
     let mut encoded_row = HashMap::with_capacity(columns.len());
@@ -47,8 +49,9 @@ pub fn encode_row<'a>(
             snapshot,
             type_manager,
             thing_manager,
-            include_instance_types,
+            options,
             storage_counters.clone(),
+            intern_pool,
         )?;
         encoded_row.insert(variable.as_str(), row_entry);
     }
@@ -62,26 +65,48 @@ pub fn encode_row<'a>(
     Ok(json!(EncodedRow { data: encoded_row, involved_blocks }))
 }
 
+/// Returns the columns bound in at least one row, leaving out those empty in every row, such as variables bound only
+/// in optional patterns that never matched. Columns are kept when there are no rows, since nothing is known about them.
+pub(crate) fn bound_columns<'a>(
+    columns: &[(String, VariablePosition)],
+    rows: impl IntoIterator<Item = MaybeOwnedRow<'a>>,
+) -> Vec<(String, VariablePosition)> {
+    let mut is_bound = vec![false; columns.len()];
+    let mut has_rows = false;
+    for row in rows {
+        has_rows = true;
+        for ((_, position), is_bound) in columns.iter().zip(is_bound.iter_mut()) {
+            *is_bound |= !matches!(row.get(*position), VariableValue::None);
+        }
+    }
+    if !has_rows {
+        return columns.to_vec();
+    }
+    columns.iter().zip(is_bound).filter(|(_, is_bound)| *is_bound).map(|(column, _)| column.clone()).collect()
+}
+
 pub fn encode_row_entry(
     variable_value: &VariableValue<'_>,
     snapshot: &impl ReadableSnapshot,
     type_manager: &TypeManager,
     thing_manager: &ThingManager,
-    include_instance_types: bool,
+    options: &ConceptEncodingOptions,
     storage_counters: StorageCounters,
+    intern_pool: &InternPool,
 ) -> Result<serde_json::Value, Box<ConceptReadError>> {
     match variable_value {
         VariableValue::None => Ok(json!(serde_json::Value::Null)),
-        VariableValue::Type(type_) => Ok(json!(encode_type_concept(type_, snapshot, type_manager)?)),
+        VariableValue::Type(type_) => Ok(json!(encode_type_concept(type_, snapshot, type_manager, intern_pool)?)),
         VariableValue::Thing(thing) => Ok(json!(encode_thing_concept(
             thing,
             snapshot,
             type_manager,
             thing_manager,
-            include_instance_types,
-            storage_counters
+            options,
+            storage_counters,
+            intern_pool,
         )?)),
-        VariableValue::Value(value) => Ok(json!(encode_value(value.as_reference()))),
+        VariableValue::Value(value) => Ok(json!(encode_value(value.as_reference(), options))),
         VariableValue::ThingList(thing_list) => {
             let mut encoded = Vec::with_capacity(thing_list.len());
             for thing in thing_list.iter() {
@@ -90,8 +115,9 @@ pub fn encode_row_entry(
                     snapshot,
                     type_manager,
                     thing_manager,
-                    include_instance_types,
+                    options,
                     storage_counters.clone(),
+                    intern_pool,
                 )?);
             }
             Ok(json!(encoded))
@@ -99,9 +125,46 @@ pub fn encode_row_entry(
         VariableValue::ValueList(value_list) => {
             let mut encoded = Vec::with_capacity(value_list.len());
             for value in value_list.iter() {
-                encoded.push(encode_value(value.as_reference()))
+                encoded.push(encode_value(value.as_reference(), options))
             }
             Ok(json!(encoded))
         }
     }
 }
+
+#[cfg(test)]
+pub mod tests {
+    use answer::variable_value::VariableValue;
+    use compiler::VariablePosition;
+    use encoding::value::value::Value;
+    use executor::{row::MaybeOwnedRow, Provenance};
+
+    use super::bound_columns;
+
+    fn row(values: Vec<VariableValue<'static>>) -> MaybeOwnedRow<'static> {
+        MaybeOwnedRow::new_owned(values, 1, Provenance(0))
+    }
+
+    #[test]
+    fn columns_empty_in_every_row_are_left_out() {
+        let columns: Vec<_> = ["a", "b", "c"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| (name.to_owned(), VariablePosition::new(i as u32)))
+            .collect();
+        let rows = [
+            row(vec![VariableValue::Value(Value::Integer(1)), VariableValue::None, VariableValue::None]),
+            row(vec![VariableValue::None, VariableValue::None, VariableValue::Value(Value::Boolean(true))]),
+        ];
+        let bound = bound_columns(&columns, rows.iter().map(MaybeOwnedRow::as_reference));
+        let names: Vec<_> = bound.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["a", "c"]);
+        assert_eq!(bound[1].1, VariablePosition::new(2));
+    }
+
+    #[test]
+    fn columns_are_kept_without_rows() {
+        let columns = vec![("a".to_owned(), VariablePosition::new(0))];
+        assert_eq!(bound_columns(&columns, []), columns);
+    }
+}
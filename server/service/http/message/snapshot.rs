@@ -0,0 +1,69 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use itertools::Itertools;
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::service::http::error::HttpServiceError;
+
+pub(crate) const SNAPSHOT_TOKEN_HEADER: &str = "typedb-snapshot-token";
+
+/// Pins a read query to the data version of an earlier answer, without any state held by the server.
+/// Any server with the same data (such as a replica, or the same server after a restart) can re-open the snapshot,
+/// as long as the data version is still within the database's history retention window.
+///
+/// The token is opaque to clients. It is checksummed to reject tokens that were truncated or edited.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct SnapshotToken {
+    pub(crate) database_name: String,
+    pub(crate) sequence_number: u64,
+}
+
+impl SnapshotToken {
+    const VERSION: &'static str = "v1";
+    const SEPARATOR: char = '.';
+
+    pub(crate) fn new(database_name: String, sequence_number: u64) -> Self {
+        Self { database_name, sequence_number }
+    }
+
+    pub(crate) fn encode(&self) -> String {
+        let body = self.encode_body();
+        format!("{body}{}{:016x}", Self::SEPARATOR, xxh3_64(body.as_bytes()))
+    }
+
+    pub(crate) fn decode(token: &str) -> Result<Self, HttpServiceError> {
+        let invalid = |details: &str| HttpServiceError::InvalidSnapshotToken { details: details.to_string() };
+        let Some((version, sequence_number, database_name, checksum)) = token.split(Self::SEPARATOR).collect_tuple()
+        else {
+            return Err(invalid("malformed token"));
+        };
+        if version != Self::VERSION {
+            return Err(invalid("unsupported token version"));
+        }
+        let sequence_number = u64::from_str_radix(sequence_number, 16).map_err(|_| invalid("malformed token"))?;
+        let database_name = decode_hex(database_name)
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(|| invalid("malformed token"))?;
+        let decoded = Self { database_name, sequence_number };
+        if format!("{:016x}", xxh3_64(decoded.encode_body().as_bytes())) != checksum {
+            return Err(invalid("checksum mismatch"));
+        }
+        Ok(decoded)
+    }
+
+    fn encode_body(&self) -> String {
+        let database_name = self.database_name.bytes().map(|byte| format!("{byte:02x}")).join("");
+        format!("{}{sep}{:x}{sep}{database_name}", Self::VERSION, self.sequence_number, sep = Self::SEPARATOR)
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()).collect()
+}
@@ -6,21 +6,21 @@
 
 use axum::response::{IntoResponse, Response};
 use http::StatusCode;
-use options::TransactionOptions;
-use resource::constants::server::{
-    DEFAULT_SCHEMA_LOCK_ACQUIRE_TIMEOUT_MILLIS, DEFAULT_TRANSACTION_PARALLEL, DEFAULT_TRANSACTION_TIMEOUT_MILLIS,
-};
+use options::{IsolationLevel, TransactionOptions};
+use resource::constants::server::DEFAULT_TRANSACTION_PARALLEL;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::service::{
     http::{
-        error::HttpServiceError, message::from_request_parts_impl, transaction_service::TransactionServiceResponse,
+        error::HttpServiceError,
+        message::{body::JsonBody, from_request_parts_impl},
+        transaction_service::TransactionServiceResponse,
     },
     TransactionType,
 };
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionOpenPayload {
     pub database_name: String,
@@ -28,32 +28,79 @@ pub struct TransactionOpenPayload {
     pub transaction_options: Option<TransactionOptionsPayload>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Opens a write transaction against each of the databases, which are committed together.
+/// Queries in the transaction name the database they run against.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiDatabaseTransactionOpenPayload {
+    pub database_names: Vec<String>,
+    pub transaction_options: Option<TransactionOptionsPayload>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionOptionsPayload {
     // pub parallel: Option<bool>, // TODO: Uncomment when introduced
     pub schema_lock_acquire_timeout_millis: Option<u64>,
     pub transaction_timeout_millis: Option<u64>,
+    pub cascade_type_deletion: Option<bool>,
+    pub defer_validation: Option<bool>,
+    pub read_at_sequence_number: Option<u64>,
+    pub isolation_level: Option<IsolationLevelPayload>,
 }
 
 impl Default for TransactionOptionsPayload {
     fn default() -> Self {
-        Self { schema_lock_acquire_timeout_millis: None, transaction_timeout_millis: None }
+        Self {
+            schema_lock_acquire_timeout_millis: None,
+            transaction_timeout_millis: None,
+            cascade_type_deletion: None,
+            defer_validation: None,
+            read_at_sequence_number: None,
+            isolation_level: None,
+        }
     }
 }
 
-impl Into<TransactionOptions> for TransactionOptionsPayload {
-    fn into(self) -> TransactionOptions {
+impl TransactionOptionsPayload {
+    /// Resolves the options, taking those not set from the defaults, such as the settings of the database.
+    pub(crate) fn into_transaction_options(self, defaults: TransactionOptions) -> TransactionOptions {
         TransactionOptions {
             parallel: DEFAULT_TRANSACTION_PARALLEL,
             schema_lock_acquire_timeout_millis: self
                 .schema_lock_acquire_timeout_millis
-                .unwrap_or(DEFAULT_SCHEMA_LOCK_ACQUIRE_TIMEOUT_MILLIS),
-            transaction_timeout_millis: self.transaction_timeout_millis.unwrap_or(DEFAULT_TRANSACTION_TIMEOUT_MILLIS),
+                .unwrap_or(defaults.schema_lock_acquire_timeout_millis),
+            transaction_timeout_millis: self.transaction_timeout_millis.unwrap_or(defaults.transaction_timeout_millis),
+            cascade_type_deletion: self.cascade_type_deletion.unwrap_or(defaults.cascade_type_deletion),
+            defer_validation: self.defer_validation.unwrap_or(defaults.defer_validation),
+            read_at_sequence_number: self.read_at_sequence_number,
+            isolation_level: self.isolation_level.map(Into::into).unwrap_or(defaults.isolation_level),
         }
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum IsolationLevelPayload {
+    Snapshot,
+    ReadCommitted,
+}
+
+impl Into<IsolationLevel> for IsolationLevelPayload {
+    fn into(self) -> IsolationLevel {
+        match self {
+            IsolationLevelPayload::Snapshot => IsolationLevel::Snapshot,
+            IsolationLevelPayload::ReadCommitted => IsolationLevel::ReadCommitted,
+        }
+    }
+}
+
+impl Into<TransactionOptions> for TransactionOptionsPayload {
+    fn into(self) -> TransactionOptions {
+        self.into_transaction_options(TransactionOptions::default())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionResponse {
@@ -64,6 +111,39 @@ pub(crate) fn encode_transaction(transaction_id: Uuid) -> TransactionResponse {
     TransactionResponse { transaction_id }
 }
 
+/// The data version is the sequence number of the commit, which is absent if the transaction wrote nothing.
+/// Read transactions opened with it as their read sequence number, on this server or a replica, observe the commit.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitResponse {
+    pub data_version: Option<u64>,
+}
+
+pub(crate) fn encode_commit(data_version: Option<u64>) -> CommitResponse {
+    CommitResponse { data_version }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsResponse {
+    pub transactions: Vec<TransactionSummaryResponse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSummaryResponse {
+    pub transaction_id: Uuid,
+    pub owner: String,
+    pub database_name: String,
+    pub transaction_type: TransactionType,
+    pub age_millis: u64,
+    pub staged_writes: Option<usize>,
+}
+
+pub(crate) fn encode_transactions(transactions: Vec<TransactionSummaryResponse>) -> TransactionsResponse {
+    TransactionsResponse { transactions }
+}
+
 #[derive(Debug)]
 pub(crate) struct TransactionPath {
     pub(crate) transaction_id: Uuid,
@@ -75,8 +155,14 @@ impl IntoResponse for TransactionServiceResponse {
     fn into_response(self) -> Response {
         match self {
             TransactionServiceResponse::Ok => StatusCode::OK.into_response(),
+            TransactionServiceResponse::Committed(data_version) => {
+                JsonBody(encode_commit(data_version)).into_response()
+            }
             TransactionServiceResponse::Query(query) => query.into_response(),
             TransactionServiceResponse::QueryAnalyse(query) => query.into_response(),
+            TransactionServiceResponse::Released(_) => {
+                HttpServiceError::Internal { details: "unexpected transaction response".to_string() }.into_response()
+            }
             TransactionServiceResponse::Err(typedb_source) => {
                 HttpServiceError::Transaction { typedb_source }.into_response()
             }
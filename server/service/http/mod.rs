@@ -9,5 +9,7 @@ mod diagnostics;
 pub(crate) mod encryption;
 mod error;
 pub mod message;
+pub(crate) mod multi_database_transaction_service;
+pub(crate) mod rate_limiter;
 pub(crate) mod transaction_service;
 pub(crate) mod typedb_service;
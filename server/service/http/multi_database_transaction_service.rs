@@ -0,0 +1,293 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{
+    mem,
+    ops::{
+        ControlFlow,
+        ControlFlow::{Break, Continue},
+    },
+    sync::Arc,
+};
+
+use database::{coordinator::commit_atomically, database_manager::DatabaseManager};
+use diagnostics::diagnostics_manager::DiagnosticsManager;
+use itertools::Itertools;
+use options::{QueryOptions, TransactionOptions};
+use tokio::{
+    sync::{
+        mpsc::{channel, error::SendError, Receiver},
+        oneshot, watch,
+    },
+    task::spawn_blocking,
+    time::Instant,
+};
+use tracing::{event, Level};
+
+use crate::service::{
+    http::transaction_service::{
+        respond_transaction_response, TransactionRequest, TransactionResponder, TransactionService,
+        TransactionServiceResponse,
+    },
+    transaction_registry::{next_control, HttpTransactionRequestSender, TransactionControl, TransactionRegistration},
+    transaction_service::{init_transaction_timeout, TransactionServiceError, TRANSACTION_REQUEST_BUFFER_SIZE},
+    trigger_service::TriggerRunner,
+    TransactionType,
+};
+
+/// Serves a write transaction spanning several databases.
+/// Each database has a transaction service of its own, which answers the queries naming that database.
+/// On commit, every database hands over its transaction, and they are committed together with two-phase commit.
+#[derive(Debug)]
+pub(crate) struct MultiDatabaseTransactionService {
+    database_manager: Arc<DatabaseManager>,
+    diagnostics_manager: Arc<DiagnosticsManager>,
+    trigger_runner: Arc<TriggerRunner>,
+
+    request_stream: Receiver<(TransactionRequest, TransactionResponder)>,
+    shutdown_receiver: watch::Receiver<()>,
+    registration: Option<TransactionRegistration>,
+
+    timeout_at: Instant,
+    transactions: Vec<(String, HttpTransactionRequestSender)>,
+}
+
+impl MultiDatabaseTransactionService {
+    pub(crate) fn new(
+        database_manager: Arc<DatabaseManager>,
+        diagnostics_manager: Arc<DiagnosticsManager>,
+        trigger_runner: Arc<TriggerRunner>,
+        request_stream: Receiver<(TransactionRequest, TransactionResponder)>,
+        shutdown_receiver: watch::Receiver<()>,
+    ) -> Self {
+        Self {
+            database_manager,
+            diagnostics_manager,
+            trigger_runner,
+
+            request_stream,
+            shutdown_receiver,
+            registration: None,
+
+            timeout_at: init_transaction_timeout(None),
+            transactions: Vec::new(),
+        }
+    }
+
+    /// Registers the open transaction, so that it is listed and can be force-closed until it closes
+    pub(crate) fn register(&mut self, registration: TransactionRegistration) {
+        self.registration = Some(registration);
+    }
+
+    /// Opens a write transaction against each database, with its options.
+    /// The transaction times out with the earliest timeout of the databases.
+    pub(crate) async fn open(
+        &mut self,
+        databases: Vec<(String, TransactionOptions)>,
+    ) -> Result<u64, TransactionServiceError> {
+        let receive_time = Instant::now();
+        if let Some(name) = databases.iter().map(|(name, _)| name).duplicates().next() {
+            return Err(TransactionServiceError::DatabaseListedTwice { name: name.clone() });
+        }
+
+        let transaction_timeout_millis = databases.iter().map(|(_, options)| options.transaction_timeout_millis).min();
+        for (database_name, options) in databases {
+            let (request_sender, request_stream) = channel(TRANSACTION_REQUEST_BUFFER_SIZE);
+            let mut transaction_service = TransactionService::new(
+                self.database_manager.clone(),
+                self.diagnostics_manager.clone(),
+                self.trigger_runner.clone(),
+                request_stream,
+                self.shutdown_receiver.clone(),
+            );
+            // if a later database fails to open, the transactions already open close once this service drops
+            transaction_service.open(TransactionType::Write, database_name.clone(), options).await?;
+            tokio::spawn(async move { transaction_service.listen().await });
+            self.transactions.push((database_name, request_sender));
+        }
+        self.timeout_at = init_transaction_timeout(transaction_timeout_millis);
+
+        let processing_time_millis = Instant::now().duration_since(receive_time).as_millis() as u64;
+        Ok(processing_time_millis)
+    }
+
+    pub(crate) async fn listen(&mut self) {
+        loop {
+            let control = tokio::select! { biased;
+                _ = self.shutdown_receiver.changed() => {
+                    event!(Level::TRACE, "Shutdown signal received, closing multi-database transaction service.");
+                    self.do_close().await;
+                    return;
+                }
+                _ = tokio::time::sleep_until(self.timeout_at) => {
+                    event!(Level::TRACE, "Transaction timeout met, closing multi-database transaction service.");
+                    self.do_close().await;
+                    return;
+                }
+                control = next_control(&mut self.registration) => {
+                    self.handle_control(control).await
+                }
+                next = self.request_stream.recv() => {
+                    self.handle_next(next).await
+                }
+            };
+
+            if let Break(()) = control {
+                event!(Level::TRACE, "Stream ended, closing multi-database transaction service.");
+                self.do_close().await;
+                return;
+            }
+        }
+    }
+
+    async fn handle_control(&mut self, control: TransactionControl) -> ControlFlow<(), ()> {
+        match control {
+            TransactionControl::StagedWrites(responder) => {
+                // the writes are staged by the transaction of each database, which answers no control requests
+                let _ = responder.send(None);
+                Continue(())
+            }
+            TransactionControl::ForceClose(responder) => {
+                event!(Level::TRACE, "Transaction force-closed, closing multi-database transaction service.");
+                self.do_close().await;
+                let _ = responder.send(());
+                Break(())
+            }
+        }
+    }
+
+    async fn handle_next(&mut self, next: Option<(TransactionRequest, TransactionResponder)>) -> ControlFlow<(), ()> {
+        let Some((request, responder)) = next else {
+            return Break(());
+        };
+        match request {
+            TransactionRequest::DatabaseQuery(database_name, query_options, query) => {
+                self.forward_query(database_name, query_options, query, responder).await
+            }
+            TransactionRequest::Query(_, _) => {
+                Self::respond_error(responder, TransactionServiceError::QueryDatabaseNotNamed {})
+            }
+            TransactionRequest::AnalyseQuery(_) => {
+                Self::respond_error(responder, TransactionServiceError::MultiDatabaseAnalyseNotSupported {})
+            }
+            TransactionRequest::Commit => self.handle_commit(responder).await,
+            TransactionRequest::Rollback => self.handle_rollback(responder).await,
+            TransactionRequest::Close => {
+                self.do_close().await;
+                let _ = respond_transaction_response(responder, TransactionServiceResponse::Ok);
+                Break(())
+            }
+            TransactionRequest::Release => {
+                unreachable!("Expected only the transactions of each database to be released")
+            }
+        }
+    }
+
+    /// The transaction of the database answers the query itself, so that it is queued, streamed and interrupted
+    /// as in a transaction against that database alone
+    async fn forward_query(
+        &mut self,
+        database_name: String,
+        query_options: QueryOptions,
+        query: String,
+        responder: TransactionResponder,
+    ) -> ControlFlow<(), ()> {
+        let Some((_, request_sender)) = self.transactions.iter().find(|(name, _)| *name == database_name) else {
+            return Self::respond_error(
+                responder,
+                TransactionServiceError::DatabaseNotInTransaction { name: database_name },
+            );
+        };
+        let request = TransactionRequest::Query(query_options, query);
+        if let Err(SendError((_, responder))) = request_sender.send((request, responder)).await {
+            // the transaction of the database closed, such as on a failed write, so the whole transaction closes
+            let _ = respond_transaction_response(
+                responder,
+                TransactionServiceResponse::Err(TransactionServiceError::NoOpenTransaction {}),
+            );
+            return Break(());
+        }
+        Continue(())
+    }
+
+    async fn handle_commit(&mut self, responder: TransactionResponder) -> ControlFlow<(), ()> {
+        let mut transactions = Vec::with_capacity(self.transactions.len());
+        // the transactions not yet handed over close once their request senders drop
+        for (_, request_sender) in mem::take(&mut self.transactions) {
+            let error = match Self::request(&request_sender, TransactionRequest::Release).await {
+                TransactionServiceResponse::Released(transaction) => {
+                    transactions.push(transaction);
+                    continue;
+                }
+                TransactionServiceResponse::Err(error) => error,
+                _ => unreachable!("Expected a released transaction or an error"),
+            };
+            transactions.into_iter().for_each(|transaction| transaction.close());
+            let _ = respond_transaction_response(responder, TransactionServiceResponse::Err(error));
+            return Break(());
+        }
+
+        let database_manager = self.database_manager.clone();
+        let result = spawn_blocking(move || commit_atomically(database_manager.commit_log(), transactions))
+            .await
+            .expect("Expected multi-database commit completion");
+        let response = match result {
+            // each database commits at a data version of its own, so there is no single one to answer with
+            Ok(()) => TransactionServiceResponse::Committed(None),
+            Err(typedb_source) => {
+                TransactionServiceResponse::Err(TransactionServiceError::MultiDatabaseCommitFailed { typedb_source })
+            }
+        };
+        let _ = respond_transaction_response(responder, response);
+        Break(())
+    }
+
+    async fn handle_rollback(&mut self, responder: TransactionResponder) -> ControlFlow<(), ()> {
+        let mut first_error = None;
+        for (_, request_sender) in &self.transactions {
+            if let TransactionServiceResponse::Err(error) =
+                Self::request(request_sender, TransactionRequest::Rollback).await
+            {
+                first_error.get_or_insert(error);
+            }
+        }
+        match first_error {
+            None => match respond_transaction_response(responder, TransactionServiceResponse::Ok) {
+                Ok(()) => Continue(()),
+                Err(_) => Break(()),
+            },
+            Some(error) => {
+                let _ = respond_transaction_response(responder, TransactionServiceResponse::Err(error));
+                Break(())
+            }
+        }
+    }
+
+    async fn do_close(&mut self) {
+        self.registration = None;
+        for (_, request_sender) in mem::take(&mut self.transactions) {
+            let _ = Self::request(&request_sender, TransactionRequest::Close).await;
+        }
+    }
+
+    async fn request(
+        request_sender: &HttpTransactionRequestSender,
+        request: TransactionRequest,
+    ) -> TransactionServiceResponse {
+        let (sender, receiver) = oneshot::channel();
+        if request_sender.send((request, TransactionResponder(sender))).await.is_err() {
+            return TransactionServiceResponse::Err(TransactionServiceError::NoOpenTransaction {});
+        }
+        receiver.await.unwrap_or(TransactionServiceResponse::Err(TransactionServiceError::NoOpenTransaction {}))
+    }
+
+    fn respond_error(responder: TransactionResponder, error: TransactionServiceError) -> ControlFlow<(), ()> {
+        match respond_transaction_response(responder, TransactionServiceResponse::Err(error)) {
+            Ok(()) => Continue(()),
+            Err(_) => Break(()),
+        }
+    }
+}
@@ -0,0 +1,289 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+use std::{
+    collections::HashMap,
+    convert, fmt,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{body::Body, extract::ConnectInfo, response::IntoResponse};
+use futures::future::BoxFuture;
+use http::{Request, Response};
+use tower::{Layer, Service};
+
+use crate::{
+    authentication::Accessor,
+    parameters::config::{HttpRateLimitsConfig, RateLimitConfig},
+    service::http::error::HttpServiceError,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum EndpointClass {
+    Auth,
+    Query,
+    Admin,
+}
+
+impl fmt::Display for EndpointClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EndpointClass::Auth => write!(f, "authentication"),
+            EndpointClass::Query => write!(f, "query"),
+            EndpointClass::Admin => write!(f, "administration"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+enum Client {
+    User(String),
+    Address(IpAddr),
+}
+
+/// Token bucket rate limiter for one class of endpoints.
+/// Requests are keyed by the authenticated user if there is one, and by the remote address otherwise,
+/// so it must be layered inside the authenticator.
+#[derive(Clone, Debug)]
+pub(crate) struct RateLimiter {
+    class: EndpointClass,
+    limit: Option<RateLimitConfig>,
+    buckets: Arc<Mutex<HashMap<Client, TokenBucket>>>,
+}
+
+impl RateLimiter {
+    const MAX_TRACKED_CLIENTS: usize = 1 << 16;
+
+    pub(crate) fn new(class: EndpointClass, config: &HttpRateLimitsConfig) -> Self {
+        let limit = config.enabled.then_some(match class {
+            EndpointClass::Auth => config.auth,
+            EndpointClass::Query => config.query,
+            EndpointClass::Admin => config.admin,
+        });
+        Self { class, limit, buckets: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    fn check<T>(&self, request: &Request<T>) -> Result<(), HttpServiceError> {
+        let Some(limit) = self.limit else {
+            return Ok(());
+        };
+        let Some(client) = Self::client(request) else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= Self::MAX_TRACKED_CLIENTS && !buckets.contains_key(&client) {
+            // full buckets hold no state a new bucket would not, so idle clients can be forgotten
+            buckets.retain(|_, bucket| !bucket.is_full(&limit, now));
+        }
+        let bucket = buckets.entry(client).or_insert_with(|| TokenBucket::new(&limit, now));
+        bucket.try_acquire(&limit, now).map_err(|retry_after| HttpServiceError::RateLimited {
+            class: self.class.to_string(),
+            retry_after_seconds: retry_after.as_secs_f64().ceil() as u64,
+        })
+    }
+
+    fn client<T>(request: &Request<T>) -> Option<Client> {
+        if let Some(Accessor(username)) = request.extensions().get::<Accessor>() {
+            Some(Client::User(username.clone()))
+        } else {
+            request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(address)| Client::Address(address.ip()))
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: &RateLimitConfig, now: Instant) -> Self {
+        Self { tokens: limit.burst as f64, last_refill: now }
+    }
+
+    fn refill(&mut self, limit: &RateLimitConfig, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.requests_per_second as f64).min(limit.burst as f64);
+        self.last_refill = now;
+    }
+
+    fn is_full(&mut self, limit: &RateLimitConfig, now: Instant) -> bool {
+        self.refill(limit, now);
+        self.tokens >= limit.burst as f64
+    }
+
+    fn try_acquire(&mut self, limit: &RateLimitConfig, now: Instant) -> Result<(), Duration> {
+        self.refill(limit, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / limit.requests_per_second as f64))
+        }
+    }
+}
+
+impl<S: Clone> Layer<S> for RateLimiter {
+    type Service = RateLimitedService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RateLimitedService::new(service, self.clone())
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct RateLimitedService<S> {
+    inner: S,
+    rate_limiter: RateLimiter,
+}
+
+impl<S> RateLimitedService<S> {
+    pub(crate) fn new(inner: S, rate_limiter: RateLimiter) -> Self {
+        Self { inner, rate_limiter }
+    }
+}
+
+impl<S> Service<Request<Body>> for RateLimitedService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = convert::Infallible> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = S::Response;
+
+    type Error = S::Error;
+
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let checked = self.rate_limiter.check(&request);
+        let mut inner = self.inner.clone();
+        Box::pin(async move {
+            match checked {
+                Ok(()) => inner.call(request).await,
+                Err(err) => Ok(err.into_response()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::time::{Duration, Instant};
+
+    use http::Request;
+
+    use super::{Client, EndpointClass, RateLimiter, TokenBucket};
+    use crate::{
+        authentication::Accessor,
+        parameters::config::{HttpRateLimitsConfig, RateLimitConfig},
+        service::http::error::HttpServiceError,
+    };
+
+    const LIMIT: RateLimitConfig = RateLimitConfig { requests_per_second: 2, burst: 3 };
+
+    fn config() -> HttpRateLimitsConfig {
+        HttpRateLimitsConfig {
+            enabled: true,
+            auth: RateLimitConfig { requests_per_second: 1, burst: 1 },
+            query: RateLimitConfig { requests_per_second: 1, burst: 3 },
+            admin: LIMIT,
+        }
+    }
+
+    fn request_by(username: &str) -> Request<()> {
+        let mut request = Request::new(());
+        request.extensions_mut().insert(Accessor(username.to_owned()));
+        request
+    }
+
+    #[test]
+    fn bucket_allows_burst_then_refills_at_rate() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(&LIMIT, start);
+        for _ in 0..LIMIT.burst {
+            bucket.try_acquire(&LIMIT, start).unwrap();
+        }
+        assert_eq!(bucket.try_acquire(&LIMIT, start), Err(Duration::from_millis(500)));
+
+        let later = start + Duration::from_millis(500);
+        bucket.try_acquire(&LIMIT, later).unwrap();
+        assert!(bucket.try_acquire(&LIMIT, later).is_err());
+
+        // refilling stops at the burst
+        let much_later = later + Duration::from_secs(60);
+        assert!(bucket.is_full(&LIMIT, much_later));
+        for _ in 0..LIMIT.burst {
+            bucket.try_acquire(&LIMIT, much_later).unwrap();
+        }
+        assert!(bucket.try_acquire(&LIMIT, much_later).is_err());
+    }
+
+    #[test]
+    fn limits_apply_per_client_and_per_class() {
+        let query_limiter = RateLimiter::new(EndpointClass::Query, &config());
+        let admin_limiter = RateLimiter::new(EndpointClass::Admin, &config());
+        for _ in 0..3 {
+            query_limiter.check(&request_by("alice")).unwrap();
+        }
+        let limited = query_limiter.check(&request_by("alice"));
+        assert!(matches!(limited, Err(HttpServiceError::RateLimited { retry_after_seconds: 1, .. })), "{limited:?}");
+        query_limiter.check(&request_by("bob")).unwrap();
+
+        // each class has its own limit and buckets
+        for _ in 0..LIMIT.burst {
+            admin_limiter.check(&request_by("alice")).unwrap();
+        }
+        assert!(admin_limiter.check(&request_by("alice")).is_err());
+    }
+
+    #[test]
+    fn disabled_limits_and_unidentified_clients_are_not_limited() {
+        let disabled_limiter =
+            RateLimiter::new(EndpointClass::Auth, &HttpRateLimitsConfig { enabled: false, ..config() });
+        let limiter = RateLimiter::new(EndpointClass::Auth, &config());
+        for _ in 0..10 {
+            disabled_limiter.check(&request_by("alice")).unwrap();
+            limiter.check(&Request::new(())).unwrap();
+        }
+    }
+
+    #[test]
+    fn full_buckets_are_evicted_once_too_many_clients_are_tracked() {
+        let limiter = RateLimiter::new(EndpointClass::Admin, &config());
+        let now = Instant::now();
+        {
+            let mut buckets = limiter.buckets.lock().unwrap();
+            for i in 0..RateLimiter::MAX_TRACKED_CLIENTS {
+                let mut bucket = TokenBucket::new(&LIMIT, now);
+                if i % 2 == 0 {
+                    bucket.tokens = 0.0;
+                }
+                buckets.insert(Client::User(format!("user-{i}")), bucket);
+            }
+        }
+        // a tracked client does not cause eviction
+        limiter.check(&request_by("user-1")).unwrap();
+        assert_eq!(limiter.buckets.lock().unwrap().len(), RateLimiter::MAX_TRACKED_CLIENTS);
+
+        limiter.check(&request_by("new")).unwrap();
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.len(), RateLimiter::MAX_TRACKED_CLIENTS / 2 + 2);
+        assert!(buckets.contains_key(&Client::User("user-0".to_owned())));
+        assert!(buckets.contains_key(&Client::User("user-1".to_owned())));
+        assert!(!buckets.contains_key(&Client::User("user-3".to_owned())));
+        assert!(buckets.contains_key(&Client::User("new".to_owned())));
+    }
+}
@@ -14,14 +14,14 @@ use std::{
     sync::Arc,
 };
 
-use compiler::{executable::ExecutableCompilationError, query_structure::PipelineStructure};
-use concept::{thing::thing_manager::ThingManager, type_::type_manager::TypeManager};
+use compiler::{executable::ExecutableCompilationError, query_structure::PipelineStructure, warning::QueryWarning};
+use concept::{
+    thing::thing_manager::ThingManager,
+    type_::type_manager::{schema_diff::SchemaDiff, TypeManager},
+};
 use database::{
     database_manager::DatabaseManager,
-    query::{
-        execute_schema_query, execute_write_query_in_schema, execute_write_query_in_write, StreamQueryOutputDescriptor,
-        WriteQueryAnswer, WriteQueryResult,
-    },
+    query::{StreamQueryOutputDescriptor, WriteQueryAnswer, WriteQueryResult},
     transaction::{TransactionRead, TransactionSchema, TransactionWrite},
 };
 use diagnostics::{
@@ -32,6 +32,7 @@ use executor::{
     batch::Batch,
     document::ConceptDocument,
     pipeline::{pipeline::Pipeline, stage::ReadPipelineStage, PipelineExecutionError},
+    row::MaybeOwnedRow,
     ExecutionInterrupt, InterruptType,
 };
 use http::StatusCode;
@@ -40,15 +41,15 @@ use itertools::{Either, Itertools};
 use lending_iterator::LendingIterator;
 use options::{QueryOptions, TransactionOptions};
 use query::error::QueryError;
-use resource::profile::StorageCounters;
-use storage::snapshot::ReadableSnapshot;
+use resource::profile::{QueryProfile, StorageCounters};
+use storage::{durability_client::WALClient, snapshot::ReadableSnapshot};
 use tokio::{
     sync::{broadcast, mpsc::Receiver, oneshot, watch},
     task::{spawn_blocking, JoinHandle},
     time::Instant,
 };
 use tracing::{event, Level};
-use typeql::{parse_query, query::SchemaQuery};
+use typeql::query::SchemaQuery;
 use uuid::Uuid;
 
 use crate::service::{
@@ -58,12 +59,18 @@ use crate::service::{
             structure::{encode_analyzed_pipeline_for_studio, AnalyzedPipelineResponse},
             AnalysedQueryResponse,
         },
-        query::{document::encode_document, row::encode_row},
+        query::{answer_encoder::JsonAnswerEncoder, encode_query_stats, row::bound_columns, QueryStatsResponse},
     },
+    intern_pool::InternPool,
     may_encode_pipeline_structure,
+    transaction_registry::{next_control, TransactionControl, TransactionRegistration},
     transaction_service::{
-        init_transaction_timeout, is_write_pipeline, with_readable_transaction, Transaction, TransactionServiceError,
+        dispatch_analyse_query, dispatch_query, execute_schema_query_in_transaction, init_transaction_timeout,
+        is_write_pipeline, spawn_blocking_execute_write_query, validate_query_options, with_readable_transaction,
+        AnswerEncoder, DispatchedQuery, QueueOptions, QueuedQuery, SchemaQueryOutcome, Transaction,
+        TransactionServiceError,
     },
+    trigger_service::{commit_with_triggers, TriggerRunner},
     IncludeInvolvedBlocks, QueryType, TransactionType,
 };
 
@@ -111,10 +118,13 @@ macro_rules! unwrap_or_execute_else_respond_error_and_return_break {
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) enum TransactionRequest {
     Query(QueryOptions, String),
+    DatabaseQuery(String, QueryOptions, String),
     AnalyseQuery(String),
     Commit,
     Rollback,
     Close,
+    /// Finishes the queued writes and hands over the write transaction, to be committed with others
+    Release,
 }
 
 pub(crate) struct TransactionResponder(pub(crate) oneshot::Sender<TransactionServiceResponse>);
@@ -132,7 +142,7 @@ fn respond_query_response(
     respond_transaction_response(responder, TransactionServiceResponse::Query(response))
 }
 
-fn respond_transaction_response(
+pub(crate) fn respond_transaction_response(
     responder: TransactionResponder,
     response: TransactionServiceResponse,
 ) -> Result<(), TransactionServiceResponse> {
@@ -147,77 +157,110 @@ fn respond_transaction_response(
 pub(crate) struct TransactionService {
     database_manager: Arc<DatabaseManager>,
     diagnostics_manager: Arc<DiagnosticsManager>,
+    trigger_runner: Arc<TriggerRunner>,
 
     request_stream: Receiver<(TransactionRequest, TransactionResponder)>,
     query_interrupt_sender: broadcast::Sender<InterruptType>,
     query_interrupt_receiver: ExecutionInterrupt,
     shutdown_receiver: watch::Receiver<()>,
+    registration: Option<TransactionRegistration>,
 
     timeout_at: Instant,
     schema_lock_acquire_timeout_millis: Option<u64>,
 
+    database_name: String,
     transaction: Option<Transaction>,
-    query_queue: VecDeque<(TransactionResponder, QueueOptions, typeql::query::Pipeline, String)>,
+    intern_pool: InternPool,
+    query_queue: VecDeque<QueuedQuery<TransactionResponder>>,
     running_write_query: Option<(TransactionResponder, JoinHandle<(Transaction, WriteQueryResult)>)>,
 }
 
 #[derive(Debug)]
 pub(crate) enum TransactionServiceResponse {
     Ok,
+    Committed(Option<u64>),
     Query(QueryAnswer),
     QueryAnalyse(AnalysedQueryResponse),
+    Released(TransactionWrite<WALClient>),
     Err(TransactionServiceError),
 }
 
 #[derive(Debug)]
 pub(crate) enum QueryAnswer {
     ResOk(QueryType),
-    ResRows((QueryType, Vec<serde_json::Value>, Option<AnalyzedPipelineResponse>, Option<QueryAnswerWarning>)),
-    ResDocuments((QueryType, Vec<serde_json::Value>, Option<QueryAnswerWarning>)),
+    ResSchemaChanges(SchemaDiff),
+    ResRows(
+        (
+            QueryType,
+            Vec<String>,
+            Vec<serde_json::Value>,
+            Option<AnalyzedPipelineResponse>,
+            Vec<QueryAnswerWarning>,
+            Option<QueryStatsResponse>,
+        ),
+    ),
+    ResDocuments((QueryType, Vec<serde_json::Value>, Vec<QueryAnswerWarning>, Option<QueryStatsResponse>)),
 }
 
 impl QueryAnswer {
     pub(crate) fn query_type(&self) -> QueryType {
         match self {
             QueryAnswer::ResOk(query_type) => *query_type,
-            QueryAnswer::ResRows((query_type, _, _, _)) => *query_type,
-            QueryAnswer::ResDocuments((query_type, _, _)) => *query_type,
+            QueryAnswer::ResSchemaChanges(_) => QueryType::Schema,
+            QueryAnswer::ResRows((query_type, _, _, _, _, _)) => *query_type,
+            QueryAnswer::ResDocuments((query_type, _, _, _)) => *query_type,
         }
     }
 
     pub(crate) fn status_code(&self) -> StatusCode {
         match self {
-            QueryAnswer::ResOk(_) => StatusCode::OK,
-            QueryAnswer::ResRows((_, _, _, warning)) => match warning {
-                None => StatusCode::OK,
-                Some(warning) => warning.status_code(),
-            },
-            QueryAnswer::ResDocuments((_, _, warning)) => match warning {
-                None => StatusCode::OK,
-                Some(warning) => warning.status_code(),
-            },
+            QueryAnswer::ResOk(_) | QueryAnswer::ResSchemaChanges(_) => StatusCode::OK,
+            QueryAnswer::ResRows((_, _, _, _, warnings, _)) | QueryAnswer::ResDocuments((_, _, warnings, _)) => {
+                warnings
+                    .iter()
+                    .map(QueryAnswerWarning::status_code)
+                    .find(|status_code| *status_code != StatusCode::OK)
+                    .unwrap_or(StatusCode::OK)
+            }
         }
     }
 }
 
 #[derive(Debug)]
 pub(crate) enum QueryAnswerWarning {
+    Compilation { warning: QueryWarning },
     ReadResultsLimitExceeded { limit: usize },
     WriteResultsLimitExceeded { limit: usize },
 }
 
 impl QueryAnswerWarning {
+    const PREFIX: &'static str = "HWN";
+
+    pub(crate) fn code(&self) -> String {
+        match self {
+            QueryAnswerWarning::Compilation { warning } => warning.code(),
+            QueryAnswerWarning::ReadResultsLimitExceeded { .. } => format!("{}1", Self::PREFIX),
+            QueryAnswerWarning::WriteResultsLimitExceeded { .. } => format!("{}2", Self::PREFIX),
+        }
+    }
+
     pub(crate) fn status_code(&self) -> StatusCode {
         match self {
+            QueryAnswerWarning::Compilation { .. } => StatusCode::OK,
             QueryAnswerWarning::ReadResultsLimitExceeded { .. } => StatusCode::PARTIAL_CONTENT,
             QueryAnswerWarning::WriteResultsLimitExceeded { .. } => StatusCode::PARTIAL_CONTENT,
         }
     }
+
+    fn from_compilation(warnings: &[QueryWarning]) -> Vec<Self> {
+        warnings.iter().map(|warning| QueryAnswerWarning::Compilation { warning: warning.clone() }).collect()
+    }
 }
 
 impl fmt::Display for QueryAnswerWarning {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            QueryAnswerWarning::Compilation { warning } => write!(f, "{warning}"),
             QueryAnswerWarning::ReadResultsLimitExceeded { limit } => write!(f, "Read query results limit ({limit}) exceeded. Not all answers are returned."),
             QueryAnswerWarning::WriteResultsLimitExceeded { limit } => write!(f, "Write query results limit ({limit}) exceeded. Not all answers are returned, but all the requested writes are completed.")
         }
@@ -228,6 +271,7 @@ impl TransactionService {
     pub(crate) fn new(
         database_manager: Arc<DatabaseManager>,
         diagnostics_manager: Arc<DiagnosticsManager>,
+        trigger_runner: Arc<TriggerRunner>,
         request_stream: Receiver<(TransactionRequest, TransactionResponder)>,
         shutdown_receiver: watch::Receiver<()>,
     ) -> Self {
@@ -235,21 +279,30 @@ impl TransactionService {
         Self {
             database_manager,
             diagnostics_manager,
+            trigger_runner,
 
             request_stream,
             query_interrupt_sender,
             query_interrupt_receiver: ExecutionInterrupt::new(query_interrupt_receiver),
             shutdown_receiver,
+            registration: None,
 
             timeout_at: init_transaction_timeout(None),
             schema_lock_acquire_timeout_millis: None,
 
+            database_name: String::new(),
             transaction: None,
+            intern_pool: InternPool::new(),
             query_queue: VecDeque::with_capacity(20),
             running_write_query: None,
         }
     }
 
+    /// Registers the open transaction, so that it is listed and can be force-closed until it closes
+    pub(crate) fn register(&mut self, registration: TransactionRegistration) {
+        self.registration = Some(registration);
+    }
+
     pub(crate) async fn open(
         &mut self,
         type_: TransactionType,
@@ -263,6 +316,11 @@ impl TransactionService {
             .database_manager
             .database(database_name.as_ref())
             .ok_or_else(|| TransactionServiceError::DatabaseNotFound { name: database_name.clone() })?;
+        if type_ != TransactionType::Read {
+            self.database_manager
+                .ensure_cluster_leader()
+                .map_err(|typedb_source| TransactionServiceError::Cluster { typedb_source })?;
+        }
 
         let transaction = match type_ {
             TransactionType::Read => {
@@ -294,6 +352,7 @@ impl TransactionService {
             }
         };
         self.diagnostics_manager.increment_load_count(ClientEndpoint::Http, &database_name, transaction.load_kind());
+        self.database_name = database_name;
         self.transaction = Some(transaction);
         self.timeout_at = init_transaction_timeout(Some(transaction_timeout_millis));
 
@@ -301,6 +360,14 @@ impl TransactionService {
         Ok(processing_time_millis)
     }
 
+    /// The data version read by an open read transaction, which is stable for the transaction's lifetime.
+    pub(crate) fn snapshot_sequence_number(&self) -> Option<u64> {
+        match &self.transaction {
+            Some(Transaction::Read(transaction)) => Some(transaction.snapshot.open_sequence_number().number()),
+            _ => None,
+        }
+    }
+
     pub(crate) async fn listen(&mut self) {
         loop {
             let control = if let Some((_, write_query_worker)) = &mut self.running_write_query {
@@ -324,6 +391,9 @@ impl TransactionService {
                             Break(()) => Break(())
                         }
                     }
+                    control = next_control(&mut self.registration) => {
+                        self.handle_control(control).await
+                    }
                     next = self.request_stream.recv() => {
                         self.handle_next(next).await
                     }
@@ -340,6 +410,9 @@ impl TransactionService {
                         self.do_close().await;
                         return;
                     }
+                    control = next_control(&mut self.registration) => {
+                        self.handle_control(control).await
+                    }
                     next = self.request_stream.recv() => {
                         self.handle_next(next).await
                     }
@@ -357,6 +430,22 @@ impl TransactionService {
         }
     }
 
+    async fn handle_control(&mut self, control: TransactionControl) -> ControlFlow<(), ()> {
+        match control {
+            TransactionControl::StagedWrites(responder) => {
+                // the transaction is unavailable while a write query runs, in which case the size is unknown
+                let _ = responder.send(self.transaction.as_ref().and_then(Transaction::staged_writes));
+                Continue(())
+            }
+            TransactionControl::ForceClose(responder) => {
+                event!(Level::TRACE, "Transaction force-closed, closing transaction service.");
+                self.do_close().await;
+                let _ = responder.send(());
+                Break(())
+            }
+        }
+    }
+
     // TODO: any method using `Result<ControlFlow<(), ()>, Status>` should really be `ControlFlow<Result<(), Status>, ()>`
     async fn handle_next(&mut self, next: Option<(TransactionRequest, TransactionResponder)>) -> ControlFlow<(), ()> {
         match next {
@@ -365,10 +454,19 @@ impl TransactionService {
                 TransactionRequest::Query(query_options, query) => {
                     self.handle_query(query_options, query, response_sender).await
                 }
+                TransactionRequest::DatabaseQuery(database_name, query_options, query) => {
+                    if database_name != self.database_name {
+                        let error = TransactionServiceError::DatabaseNotInTransaction { name: database_name };
+                        respond_else_return_break!(response_sender, TransactionServiceResponse::Err(error));
+                        return Continue(());
+                    }
+                    self.handle_query(query_options, query, response_sender).await
+                }
                 TransactionRequest::AnalyseQuery(query) => self.handle_analyse_query(query, response_sender).await,
                 TransactionRequest::Commit => self.handle_commit(response_sender).await,
                 TransactionRequest::Rollback => self.handle_rollback(response_sender).await,
                 TransactionRequest::Close => self.handle_close(response_sender).await,
+                TransactionRequest::Release => self.handle_release(response_sender).await,
             },
         }
     }
@@ -393,6 +491,7 @@ impl TransactionService {
         }
 
         let diagnostics_manager = self.diagnostics_manager.clone();
+        let trigger_runner = self.trigger_runner.clone();
         match self.transaction.take().expect("Expected existing transaction") {
             Transaction::Read(transaction) => {
                 self.transaction = Some(Transaction::Read(transaction));
@@ -404,12 +503,13 @@ impl TransactionService {
                     transaction.database.name(),
                     LoadKind::WriteTransactions,
                 );
-                unwrap_or_execute_else_respond_error_and_return_break!(
-                    transaction.commit().1,
+                let data_version = unwrap_or_execute_else_respond_error_and_return_break!(
+                    commit_with_triggers(transaction, &trigger_runner).1,
                     responder,
                     |typedb_source| { TransactionServiceError::DataCommitFailed { typedb_source } }
                 );
-                respond_else_return_break!(responder, TransactionServiceResponse::Ok);
+                let data_version = data_version.map(|sequence_number| sequence_number.number());
+                respond_else_return_break!(responder, TransactionServiceResponse::Committed(data_version));
                 Break(())
             })
             .await
@@ -420,12 +520,13 @@ impl TransactionService {
                     transaction.database.name(),
                     LoadKind::SchemaTransactions,
                 );
-                unwrap_or_execute_else_respond_error_and_return_break!(
+                let data_version = unwrap_or_execute_else_respond_error_and_return_break!(
                     transaction.commit().1,
                     responder,
                     |typedb_source| { TransactionServiceError::SchemaCommitFailed { typedb_source } }
                 );
-                respond_else_return_break!(responder, TransactionServiceResponse::Ok);
+                let data_version = data_version.map(|sequence_number| sequence_number.number());
+                respond_else_return_break!(responder, TransactionServiceResponse::Committed(data_version));
                 Break(())
             })
             .await
@@ -433,6 +534,35 @@ impl TransactionService {
         }
     }
 
+    async fn handle_release(&mut self, responder: TransactionResponder) -> ControlFlow<(), ()> {
+        // as for a commit, the running and queued writes are finished so that they are part of the transaction
+        if let Break(()) = self.finish_running_write_query_no_transmit(InterruptType::TransactionCommitted).await {
+            return Break(());
+        }
+        self.interrupt(InterruptType::TransactionCommitted).await;
+        if let Break(()) = self.cancel_queued_read_queries(InterruptType::TransactionCommitted).await {
+            respond_error_and_return_break!(responder, TransactionServiceError::ServiceFailedQueueCleanup {});
+        }
+        if let Break(()) = self.finish_queued_write_queries(InterruptType::TransactionCommitted).await {
+            respond_error_and_return_break!(responder, TransactionServiceError::ServiceFailedQueueCleanup {});
+        }
+
+        match self.transaction.take().expect("Expected existing transaction") {
+            Transaction::Write(transaction) => {
+                self.diagnostics_manager.decrement_load_count(
+                    ClientEndpoint::Http,
+                    transaction.database.name(),
+                    LoadKind::WriteTransactions,
+                );
+                let _ = respond_transaction_response(responder, TransactionServiceResponse::Released(transaction));
+                Break(())
+            }
+            Transaction::Read(_) | Transaction::Schema(_) => {
+                unreachable!("Expected only the write transactions of multi-database transactions to be released")
+            }
+        }
+    }
+
     async fn handle_rollback(&mut self, responder: TransactionResponder) -> ControlFlow<(), ()> {
         // interrupt all queries, cancel writes, then rollback
         self.interrupt(InterruptType::TransactionRolledback).await;
@@ -460,6 +590,8 @@ impl TransactionService {
             Transaction::Schema(mut transaction) => {
                 transaction.rollback();
                 self.transaction = Some(Transaction::Schema(transaction));
+                // rolled back schema queries may have relabelled types
+                self.intern_pool = InternPool::new();
                 respond_else_return_break!(responder, TransactionServiceResponse::Ok);
                 Continue(())
             }
@@ -473,6 +605,7 @@ impl TransactionService {
     }
 
     async fn do_close(&mut self) {
+        self.registration = None;
         self.interrupt(InterruptType::TransactionClosed).await;
         let _ = self.cancel_queued_read_queries(InterruptType::TransactionClosed).await;
         let _ = self.finish_running_write_query_no_transmit(InterruptType::TransactionClosed).await;
@@ -632,20 +765,18 @@ impl TransactionService {
         query: String,
         responder: TransactionResponder,
     ) -> ControlFlow<(), ()> {
-        let parsed = match parse_query(&query) {
-            Ok(parsed) => parsed,
-            Err(err) => {
-                let _ = respond_transaction_response(
-                    responder,
-                    TransactionServiceResponse::Err(TransactionServiceError::QueryParseFailed { typedb_source: err }),
-                );
-                return Continue(());
-            }
-        };
-        match parsed.into_structure() {
-            typeql::query::QueryStructure::Schema(schema_query) => {
+        let dispatched =
+            match validate_query_options(&query_options).and_then(|_| dispatch_query(&query, &query_options)) {
+                Ok(dispatched) => dispatched,
+                Err(err) => {
+                    let _ = respond_transaction_response(responder, TransactionServiceResponse::Err(err));
+                    return Continue(());
+                }
+            };
+        match dispatched {
+            DispatchedQuery::Schema(schema_query) => {
                 // schema queries are handled immediately so there is a query response or a fatal Status
-                match self.handle_query_schema(schema_query, query).await {
+                match self.handle_query_schema(schema_query, query, query_options.dry_run).await {
                     Ok(response) => {
                         respond_else_return_break!(responder, response);
                         Continue(())
@@ -653,34 +784,18 @@ impl TransactionService {
                     Err(err) => respond_error_and_return_break!(responder, err),
                 }
             }
-            typeql::query::QueryStructure::Pipeline(pipeline) => {
-                #[allow(clippy::collapsible_else_if)]
-                if is_write_pipeline(&pipeline) {
-                    if !self.query_queue.is_empty() || self.running_write_query.is_some() {
-                        self.query_queue.push_back((responder, QueueOptions::Query(query_options), pipeline, query));
-                        // queued queries are not handled yet so there will be no query response yet
-                        Continue(())
-                    } else {
-                        self.run_write_query(responder, query_options, pipeline, query).await
-                    }
-                } else {
-                    if !self.query_queue.is_empty() || self.running_write_query.is_some() {
-                        self.query_queue.push_back((responder, QueueOptions::Query(query_options), pipeline, query));
-                        // queued queries are not handled yet so there will be no query response yet
-                        Continue(())
-                    } else {
-                        self.blocking_read_query_worker(
-                            responder,
-                            query_options,
-                            pipeline,
-                            query,
-                            StorageCounters::DISABLED,
-                        )
-                        .await
-                        .expect("Expected read query completion")
-                    }
-                }
+            DispatchedQuery::Write(pipeline) | DispatchedQuery::Read(pipeline)
+                if !self.query_queue.is_empty() || self.running_write_query.is_some() =>
+            {
+                self.query_queue.push_back((responder, QueueOptions::Query(query_options), pipeline, query));
+                // queued queries are not handled yet so there will be no query response yet
+                Continue(())
             }
+            DispatchedQuery::Write(pipeline) => self.run_write_query(responder, query_options, pipeline, query).await,
+            DispatchedQuery::Read(pipeline) => self
+                .blocking_read_query_worker(responder, query_options, pipeline, query, StorageCounters::DISABLED)
+                .await
+                .expect("Expected read query completion"),
         }
     }
 
@@ -688,6 +803,7 @@ impl TransactionService {
         &mut self,
         query: SchemaQuery,
         source_query: String,
+        dry_run: bool,
     ) -> Result<TransactionServiceResponse, TransactionServiceError> {
         self.interrupt(InterruptType::SchemaQueryExecution).await;
         if let Break(()) = self.cancel_queued_read_queries(InterruptType::SchemaQueryExecution).await {
@@ -697,26 +813,17 @@ impl TransactionService {
             return Err(TransactionServiceError::ServiceFailedQueueCleanup {});
         }
 
-        if let Some(transaction) = self.transaction.take() {
-            match transaction {
-                Transaction::Schema(schema_transaction) => {
-                    let (transaction, result) =
-                        spawn_blocking(move || execute_schema_query(schema_transaction, query, source_query))
-                            .await
-                            .expect("Expected schema query execution finishing");
-                    self.transaction = Some(Transaction::Schema(transaction));
-                    match result {
-                        Ok(_) => return Ok(TransactionServiceResponse::Query(QueryAnswer::ResOk(QueryType::Schema))),
-                        Err(err) => {
-                            return Err(TransactionServiceError::TxnAbortSchemaQueryFailed { typedb_source: *err });
-                        }
-                    }
-                }
-                transaction => self.transaction = Some(transaction),
+        let outcome = execute_schema_query_in_transaction(&mut self.transaction, query, source_query, dry_run).await;
+        // schema queries can relabel types, so labels interned before them may be stale
+        self.intern_pool = InternPool::new();
+        match outcome {
+            SchemaQueryOutcome::Done => Ok(TransactionServiceResponse::Query(QueryAnswer::ResOk(QueryType::Schema))),
+            SchemaQueryOutcome::DryRun(schema_diff) => {
+                Ok(TransactionServiceResponse::Query(QueryAnswer::ResSchemaChanges(schema_diff)))
             }
+            SchemaQueryOutcome::Rejected(err) => Ok(TransactionServiceResponse::Err(err)),
+            SchemaQueryOutcome::Failed(err) => Err(err),
         }
-
-        Ok(TransactionServiceResponse::Err(TransactionServiceError::SchemaQueryRequiresSchemaTransaction {}))
     }
 
     async fn run_write_query(
@@ -728,7 +835,15 @@ impl TransactionService {
     ) -> ControlFlow<(), ()> {
         debug_assert!(self.running_write_query.is_none());
         self.interrupt(InterruptType::WriteQueryExecution).await;
-        match self.spawn_blocking_execute_write_query(query_options, pipeline, source_query) {
+        debug_assert!(self.transaction.is_some());
+        let interrupt = self.query_interrupt_receiver.clone();
+        match spawn_blocking_execute_write_query(
+            &mut self.transaction,
+            query_options,
+            pipeline,
+            source_query,
+            interrupt,
+        ) {
             Ok(handle) => {
                 // running write queries have no valid response yet (until they finish) and will respond asynchronously
                 self.running_write_query = Some((responder, tokio::spawn(async move { handle.await.unwrap() })));
@@ -753,7 +868,9 @@ impl TransactionService {
             let thing_manager = transaction.thing_manager.clone();
             let timeout_at = self.timeout_at;
             let interrupt = self.query_interrupt_receiver.clone();
+            let intern_pool = self.intern_pool.clone();
             tokio::spawn(async move {
+                let encoder = JsonAnswerEncoder::new(&answer.query_options, intern_pool);
                 match answer.answer {
                     Either::Left((output_descriptor, batch, pipeline_structure)) => {
                         Self::submit_write_query_batch_answer(
@@ -764,6 +881,9 @@ impl TransactionService {
                             output_descriptor,
                             pipeline_structure,
                             batch,
+                            encoder,
+                            answer.warnings,
+                            answer.query_profile,
                             responder,
                             timeout_at,
                             interrupt,
@@ -779,6 +899,9 @@ impl TransactionService {
                             answer.query_options,
                             parameters,
                             documents,
+                            encoder,
+                            answer.warnings,
+                            answer.query_profile,
                             responder,
                             timeout_at,
                             interrupt,
@@ -793,34 +916,6 @@ impl TransactionService {
         })
     }
 
-    fn spawn_blocking_execute_write_query(
-        &mut self,
-        query_options: QueryOptions,
-        pipeline: typeql::query::Pipeline,
-        source_query: String,
-    ) -> Result<JoinHandle<(Transaction, WriteQueryResult)>, TransactionServiceError> {
-        debug_assert!(self.running_write_query.is_none());
-        debug_assert!(self.transaction.is_some());
-        let interrupt = self.query_interrupt_receiver.clone();
-        match self.transaction.take() {
-            Some(Transaction::Schema(schema_transaction)) => Ok(spawn_blocking(move || {
-                let (transaction, result) =
-                    execute_write_query_in_schema(schema_transaction, query_options, pipeline, source_query, interrupt);
-                (Transaction::Schema(transaction), result)
-            })),
-            Some(Transaction::Write(write_transaction)) => Ok(spawn_blocking(move || {
-                let (transaction, result) =
-                    execute_write_query_in_write(write_transaction, query_options, pipeline, source_query, interrupt);
-                (Transaction::Write(transaction), result)
-            })),
-            Some(Transaction::Read(transaction)) => {
-                self.transaction = Some(Transaction::Read(transaction));
-                Err(TransactionServiceError::WriteQueryRequiresSchemaOrWriteTransaction {})
-            }
-            None => Err(TransactionServiceError::NoOpenTransaction {}),
-        }
-    }
-
     async fn submit_write_query_batch_answer(
         snapshot: Arc<impl ReadableSnapshot>,
         type_manager: Arc<TypeManager>,
@@ -829,14 +924,22 @@ impl TransactionService {
         output_descriptor: StreamQueryOutputDescriptor,
         pipeline_structure: Option<PipelineStructure>,
         batch: Batch,
+        mut encoder: impl AnswerEncoder<Row = serde_json::Value>,
+        compilation_warnings: Vec<QueryWarning>,
+        query_profile: Arc<QueryProfile>,
         responder: TransactionResponder,
         timeout_at: Instant,
         mut interrupt: ExecutionInterrupt,
         storage_counters: StorageCounters,
     ) -> ControlFlow<(), ()> {
         let mut result = vec![];
+        let output_descriptor = if query_options.omit_null_columns {
+            bound_columns(&output_descriptor, batch.iter())
+        } else {
+            output_descriptor
+        };
         let mut batch_iterator = batch.into_iterator();
-        let mut warning = None;
+        let mut warnings = QueryAnswerWarning::from_compilation(&compilation_warnings);
         let may_encode_result =
             may_encode_pipeline_structure(&query_options, pipeline_structure.as_ref(), |structure| {
                 encode_analyzed_pipeline_for_studio(snapshot.as_ref(), &type_manager, structure)
@@ -854,18 +957,17 @@ impl TransactionService {
             // TODO: Consider multiplicity?
             if let Some(limit) = query_options.answer_count_limit {
                 if result.len() >= limit {
-                    warning = Some(QueryAnswerWarning::WriteResultsLimitExceeded { limit });
+                    warnings.push(QueryAnswerWarning::WriteResultsLimitExceeded { limit });
                     break;
                 }
             }
 
-            let encoded_row = encode_row(
+            let encoded_row = encoder.encode_row(
                 row,
                 &output_descriptor,
                 snapshot.as_ref(),
                 &type_manager,
                 &thing_manager,
-                query_options.include_instance_types,
                 &include_involved_blocks,
                 storage_counters.clone(),
             );
@@ -881,9 +983,11 @@ impl TransactionService {
                 }
             }
         }
+        let columns = output_descriptor.into_iter().map(|(name, _)| name).collect();
+        let stats = query_options.include_query_stats.then(|| encode_query_stats(&query_profile, result.len()));
         match respond_query_response(
             responder,
-            QueryAnswer::ResRows((QueryType::Write, result, encoded_structure, warning)),
+            QueryAnswer::ResRows((QueryType::Write, columns, result, encoded_structure, warnings, stats)),
         ) {
             Ok(_) => Continue(()),
             Err(_) => Break(()),
@@ -897,25 +1001,28 @@ impl TransactionService {
         query_options: QueryOptions,
         parameters: Arc<ParameterRegistry>,
         documents: Vec<ConceptDocument>,
+        mut encoder: impl AnswerEncoder<Document = serde_json::Value>,
+        compilation_warnings: Vec<QueryWarning>,
+        query_profile: Arc<QueryProfile>,
         responder: TransactionResponder,
         timeout_at: Instant,
         mut interrupt: ExecutionInterrupt,
         storage_counters: StorageCounters,
     ) -> ControlFlow<(), ()> {
         let mut result = Vec::with_capacity(documents.len());
-        let mut warning = None;
+        let mut warnings = QueryAnswerWarning::from_compilation(&compilation_warnings);
         for document in documents {
             check_timeout_else_respond_error_and_return_break!(timeout_at, responder);
             check_interrupt_else_respond_error_and_return_break!(interrupt, responder);
             // TODO: Consider multiplicity?
             if let Some(limit) = query_options.answer_count_limit {
                 if result.len() >= limit {
-                    warning = Some(QueryAnswerWarning::WriteResultsLimitExceeded { limit });
+                    warnings.push(QueryAnswerWarning::WriteResultsLimitExceeded { limit });
                     break;
                 }
             }
 
-            let encoded_document = encode_document(
+            let encoded_document = encoder.encode_document(
                 document,
                 snapshot.as_ref(),
                 &type_manager,
@@ -935,14 +1042,16 @@ impl TransactionService {
                 }
             }
         }
-        match respond_query_response(responder, QueryAnswer::ResDocuments((QueryType::Write, result, warning))) {
+        let stats = query_options.include_query_stats.then(|| encode_query_stats(&query_profile, result.len()));
+        match respond_query_response(responder, QueryAnswer::ResDocuments((QueryType::Write, result, warnings, stats)))
+        {
             Ok(_) => Continue(()),
             Err(_) => Break(()),
         }
     }
 
     fn blocking_read_query_worker(
-        &self,
+        &mut self,
         responder: TransactionResponder,
         query_options: QueryOptions,
         pipeline: typeql::query::Pipeline,
@@ -950,8 +1059,10 @@ impl TransactionService {
         storage_counters: StorageCounters,
     ) -> JoinHandle<ControlFlow<(), ()>> {
         debug_assert!(self.query_queue.is_empty() && self.running_write_query.is_none() && self.transaction.is_some());
+        self.transaction.as_mut().unwrap().advance_read_snapshot();
         let timeout_at = self.timeout_at;
         let interrupt = self.query_interrupt_receiver.clone();
+        let intern_pool = self.intern_pool.clone();
         with_readable_transaction!(self.transaction.as_ref().unwrap(), |transaction| {
             let snapshot = transaction.snapshot.clone();
             let type_manager = transaction.type_manager.clone();
@@ -959,13 +1070,14 @@ impl TransactionService {
             let function_manager = transaction.function_manager.clone();
             let query_manager = transaction.query_manager.clone();
             spawn_blocking(move || {
-                let pipeline_result = query_manager.prepare_read_pipeline(
+                let pipeline_result = query_manager.prepare_read_pipeline_profiled(
                     snapshot.clone(),
                     &type_manager,
                     thing_manager.clone(),
                     &function_manager,
                     &pipeline,
                     &source_query,
+                    query_options.include_query_stats,
                 );
                 let pipeline = match pipeline_result {
                     Ok(pipeline) => pipeline,
@@ -987,6 +1099,7 @@ impl TransactionService {
                     snapshot,
                     &type_manager,
                     thing_manager,
+                    intern_pool,
                     storage_counters,
                 )
             })
@@ -1003,8 +1116,11 @@ impl TransactionService {
         snapshot: Arc<Snapshot>,
         type_manager: &TypeManager,
         thing_manager: Arc<ThingManager>,
+        intern_pool: InternPool,
         storage_counters: StorageCounters,
     ) -> ControlFlow<(), ()> {
+        let compilation_warnings = pipeline.warnings().to_vec();
+        let mut encoder = JsonAnswerEncoder::new(&query_options, intern_pool);
         let query_profile = if pipeline.has_fetch() {
             let (iterator, context) = unwrap_or_execute_else_respond_error_and_return_break!(
                 pipeline.into_documents_iterator(interrupt.clone()),
@@ -1021,11 +1137,11 @@ impl TransactionService {
 
             let parameters = context.parameters;
             let mut result = vec![];
-            let mut warning = None;
+            let mut warnings = QueryAnswerWarning::from_compilation(&compilation_warnings);
             for next in iterator {
                 if let Some(limit) = query_options.answer_count_limit {
                     if result.len() >= limit {
-                        warning = Some(QueryAnswerWarning::ReadResultsLimitExceeded { limit });
+                        warnings.push(QueryAnswerWarning::ReadResultsLimitExceeded { limit });
                         break;
                     }
                 }
@@ -1038,7 +1154,7 @@ impl TransactionService {
                         TransactionServiceError::PipelineExecution { typedb_source: *typedb_source }
                     });
 
-                let encoded_document = encode_document(
+                let encoded_document = encoder.encode_document(
                     document,
                     snapshot.as_ref(),
                     type_manager,
@@ -1058,14 +1174,20 @@ impl TransactionService {
                     }
                 }
             }
+            let stats = query_options.include_query_stats.then(|| encode_query_stats(&context.profile, result.len()));
             respond_else_return_break!(
                 responder,
-                TransactionServiceResponse::Query(QueryAnswer::ResDocuments((QueryType::Read, result, warning)))
+                TransactionServiceResponse::Query(QueryAnswer::ResDocuments((
+                    QueryType::Read,
+                    result,
+                    warnings,
+                    stats
+                )))
             );
             context.profile
         } else {
             let named_outputs = pipeline.rows_positions().unwrap();
-            let descriptor: StreamQueryOutputDescriptor = named_outputs.clone().into_iter().sorted().collect();
+            let mut descriptor: StreamQueryOutputDescriptor = named_outputs.clone().into_iter().sorted().collect();
             let may_encode_result =
                 may_encode_pipeline_structure(&query_options, pipeline.pipeline_structure(), |structure| {
                     encode_analyzed_pipeline_for_studio(snapshot.as_ref(), &type_manager, structure)
@@ -1090,11 +1212,13 @@ impl TransactionService {
             );
 
             let mut result = vec![];
-            let mut warning = None;
+            // when omitting null columns, rows are held back until it is known which columns to encode
+            let mut held_rows = vec![];
+            let mut warnings = QueryAnswerWarning::from_compilation(&compilation_warnings);
             while let Some(next) = iterator.next() {
                 if let Some(limit) = query_options.answer_count_limit {
-                    if result.len() >= limit {
-                        warning = Some(QueryAnswerWarning::ReadResultsLimitExceeded { limit });
+                    if result.len() + held_rows.len() >= limit {
+                        warnings.push(QueryAnswerWarning::ReadResultsLimitExceeded { limit });
                         break;
                     }
                 }
@@ -1105,14 +1229,17 @@ impl TransactionService {
                 let row = unwrap_or_execute_else_respond_error_and_return_break!(next, responder, |typedb_source| {
                     TransactionServiceError::PipelineExecution { typedb_source: *typedb_source }
                 });
+                if query_options.omit_null_columns {
+                    held_rows.push(row.into_owned());
+                    continue;
+                }
 
-                let encoded_row = encode_row(
+                let encoded_row = encoder.encode_row(
                     row,
                     &descriptor,
                     snapshot.as_ref(),
                     type_manager,
                     &thing_manager,
-                    query_options.include_instance_types,
                     &include_involved_blocks,
                     storage_counters.clone(),
                 );
@@ -1128,37 +1255,61 @@ impl TransactionService {
                     }
                 }
             }
+            if query_options.omit_null_columns {
+                descriptor = bound_columns(&descriptor, held_rows.iter().map(MaybeOwnedRow::as_reference));
+                for row in held_rows {
+                    check_timeout_else_respond_error_and_return_break!(timeout_at, responder);
+                    let encoded_row = encoder.encode_row(
+                        row,
+                        &descriptor,
+                        snapshot.as_ref(),
+                        type_manager,
+                        &thing_manager,
+                        &include_involved_blocks,
+                        storage_counters.clone(),
+                    );
+                    match encoded_row {
+                        Ok(encoded_row) => result.push(encoded_row),
+                        Err(typedb_source) => {
+                            respond_error_and_return_break!(
+                                responder,
+                                TransactionServiceError::PipelineExecution {
+                                    typedb_source: PipelineExecutionError::ConceptRead { typedb_source }
+                                }
+                            );
+                        }
+                    }
+                }
+            }
+            let columns = descriptor.into_iter().map(|(name, _)| name).collect();
+            let stats = query_options.include_query_stats.then(|| encode_query_stats(&context.profile, result.len()));
             respond_else_return_break!(
                 responder,
                 TransactionServiceResponse::Query(QueryAnswer::ResRows((
                     QueryType::Read,
+                    columns,
                     result,
                     encoded_structure,
-                    warning
+                    warnings,
+                    stats
                 )))
             );
             context.profile
         };
-        if query_profile.is_enabled() {
-            event!(Level::INFO, "Read query done (including network request time).\n{}", query_profile);
+        if tracing::enabled!(Level::TRACE) {
+            event!(Level::TRACE, "Read query done (including network request time).\n{}", query_profile);
         }
         Continue(())
     }
 
     async fn handle_analyse_query(&mut self, query: String, responder: TransactionResponder) -> ControlFlow<(), ()> {
-        let parsed = match parse_query(&query) {
-            Ok(parsed) => parsed,
+        let pipeline = match dispatch_analyse_query(&query) {
+            Ok(pipeline) => pipeline,
             Err(err) => {
-                let _ = respond_transaction_response(
-                    responder,
-                    TransactionServiceResponse::Err(TransactionServiceError::QueryParseFailed { typedb_source: err }),
-                );
+                let _ = respond_transaction_response(responder, TransactionServiceResponse::Err(err));
                 return Continue(());
             }
         };
-        let typeql::query::QueryStructure::Pipeline(pipeline) = parsed.into_structure() else {
-            respond_error_and_return_break!(responder, TransactionServiceError::AnalyseQueryExpectsPipeline {});
-        };
         if !self.query_queue.is_empty() || self.running_write_query.is_some() {
             // queued queries are not handled yet so there will be no query response yet
             self.query_queue.push_back((responder, QueueOptions::Analyze, pipeline, query));
@@ -1212,15 +1363,3 @@ impl TransactionService {
         .expect("Expected read query completion")
     }
 }
-
-#[derive(Debug, Clone, Eq, PartialEq)]
-enum QueueOptions {
-    Query(QueryOptions),
-    Analyze,
-}
-
-impl QueueOptions {
-    fn is_query(&self) -> bool {
-        matches!(self, QueueOptions::Query(_))
-    }
-}
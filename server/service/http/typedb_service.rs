@@ -4,32 +4,34 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use axum::{
-    extract::State,
-    response::{IntoResponse, Redirect},
+    extract::{DefaultBodyLimit, Query, State},
+    response::{IntoResponse, Redirect, Response},
     routing::{delete, get, post, put},
     Router,
 };
-use concurrency::TokioIntervalRunner;
+use concept::type_::CommitTrigger;
 use diagnostics::metrics::ActionKind;
-use http::StatusCode;
+use http::{header::CONTENT_TYPE, HeaderMap, HeaderValue, StatusCode};
+use itertools::Itertools;
 use options::{QueryOptions, TransactionOptions};
-use resource::{constants::common::SECONDS_IN_MINUTE, server_info::ServerInfo};
+use resource::server_info::ServerInfo;
+use storage::isolation_manager::IsolationConflict;
 use system::concepts::{Credential, User};
 use tokio::{
-    sync::{
-        mpsc::{channel, Sender},
-        oneshot, RwLock,
-    },
-    time::timeout,
+    sync::{mpsc::channel, oneshot},
+    task::spawn_blocking,
+    time::{sleep, timeout},
 };
 use tower_http::cors::CorsLayer;
+use user::permission_manager::PermissionManager;
 use uuid::Uuid;
 
 use crate::{
     authentication::Accessor,
+    parameters::config::HttpEndpointConfig,
     service::{
         http::{
             diagnostics::{run_with_diagnostics, run_with_diagnostics_async},
@@ -38,73 +40,63 @@ use crate::{
                 analyze::{AnalysedQueryResponse, TransactionAnalyzePayload},
                 authentication::{encode_token, SigninPayload},
                 body::{JsonBody, PlainTextBody},
-                database::{encode_database, encode_databases, DatabasePath},
-                query::{QueryOptionsPayload, QueryPayload, TransactionQueryPayload},
-                transaction::{encode_transaction, TransactionOpenPayload, TransactionPath},
+                cluster::{encode_cluster, encode_cluster_reply, ClusterHeartbeatPayload, ClusterVotePayload},
+                database::{
+                    encode_commit_triggers, encode_database, encode_database_options, encode_database_settings,
+                    encode_database_statistics, encode_databases, encode_storage_usage, encode_storage_verification,
+                    CommitTriggersPayload, DatabaseCloneQuery, DatabaseOptionsPayload, DatabasePath,
+                    DatabaseSettingsPayload, RelationIndexRebuildPayload, ReplicationRecordsQuery, SchemaDiffPayload,
+                    StorageQuotaPayload, StorageVerifyQuery, TimeToLivePayload, TypeRenamePayload,
+                },
+                query::{
+                    delimited::{DelimitedFormat, DelimitedQueryAnswer},
+                    encode_lint_warnings, LintPayload, QueryOptionsPayload, QueryPayload, TransactionQueryPayload,
+                },
+                snapshot::{SnapshotToken, SNAPSHOT_TOKEN_HEADER},
+                transaction::{
+                    encode_transaction, encode_transactions, MultiDatabaseTransactionOpenPayload,
+                    TransactionOpenPayload, TransactionPath, TransactionSummaryResponse,
+                },
                 user::{encode_user, encode_users, CreateUserPayload, UpdateUserPayload, UserPath},
                 version::{encode_server_version, ProtocolVersion, PROTOCOL_VERSION_LATEST},
             },
+            multi_database_transaction_service::MultiDatabaseTransactionService,
+            rate_limiter::{EndpointClass, RateLimiter},
             transaction_service::{
                 QueryAnswer, TransactionRequest, TransactionResponder, TransactionService, TransactionServiceResponse,
             },
         },
+        replication_service::REPLICATION_WATERMARK_HEADER,
+        transaction_registry::{RegisteredTransaction, TransactionEndpoint, TransactionRegistry},
         transaction_service::TRANSACTION_REQUEST_BUFFER_SIZE,
-        QueryType,
+        trigger_service::TriggerRunner,
+        QueryType, TransactionType,
     },
     state::BoxServerState,
 };
 
-type TransactionRequestSender = Sender<(TransactionRequest, TransactionResponder)>;
-
-#[derive(Clone, Debug)]
-struct TransactionInfo {
-    pub owner: String,
-    pub database_name: String,
-    pub request_sender: TransactionRequestSender,
-    pub transaction_timeout_millis: u64,
-}
-
 #[derive(Clone, Debug)]
 pub(crate) struct TypeDBService {
     server_info: ServerInfo,
     address: SocketAddr,
     server_state: Arc<BoxServerState>,
-    transaction_services: Arc<RwLock<HashMap<Uuid, TransactionInfo>>>,
-    _transaction_cleanup_job: Arc<TokioIntervalRunner>,
+    transaction_registry: Arc<TransactionRegistry>,
+    trigger_runner: Arc<TriggerRunner>,
 }
 
 impl TypeDBService {
-    const TRANSACTION_CHECK_INTERVAL: Duration = Duration::from_secs(5 * SECONDS_IN_MINUTE);
     const QUERY_ENDPOINT_COMMIT_DEFAULT: bool = true;
+    const QUERY_ENDPOINT_CONFLICT_RETRIES_MAX: u32 = 10;
+    const QUERY_ENDPOINT_CONFLICT_RETRY_BACKOFF: Duration = Duration::from_millis(10);
 
-    pub(crate) fn new(server_info: ServerInfo, address: SocketAddr, server_state: Arc<BoxServerState>) -> Self {
-        let transaction_request_senders = Arc::new(RwLock::new(HashMap::new()));
-
-        let controlled_transactions = transaction_request_senders.clone();
-        let transaction_cleanup_job = Arc::new(TokioIntervalRunner::new_with_initial_delay(
-            move || {
-                let transactions = controlled_transactions.clone();
-                async move {
-                    Self::cleanup_closed_transactions(transactions).await;
-                }
-            },
-            Self::TRANSACTION_CHECK_INTERVAL,
-            Self::TRANSACTION_CHECK_INTERVAL,
-            false,
-        ));
-
-        Self {
-            server_info,
-            address,
-            server_state,
-            transaction_services: transaction_request_senders,
-            _transaction_cleanup_job: transaction_cleanup_job,
-        }
-    }
-
-    async fn cleanup_closed_transactions(transactions: Arc<RwLock<HashMap<Uuid, TransactionInfo>>>) {
-        let mut transactions = transactions.write().await;
-        transactions.retain(|_, info| !info.request_sender.is_closed());
+    pub(crate) fn new(
+        server_info: ServerInfo,
+        address: SocketAddr,
+        server_state: Arc<BoxServerState>,
+        transaction_registry: Arc<TransactionRegistry>,
+        trigger_runner: Arc<TriggerRunner>,
+    ) -> Self {
+        Self { server_info, address, server_state, transaction_registry, trigger_runner }
     }
 
     pub(crate) fn address(&self) -> &SocketAddr {
@@ -115,36 +107,117 @@ impl TypeDBService {
         service: &TypeDBService,
         owner: String,
         payload: TransactionOpenPayload,
-    ) -> Result<(TransactionInfo, u64), HttpServiceError> {
+    ) -> Result<(Uuid, RegisteredTransaction, u64), HttpServiceError> {
+        let Some(transaction_reservation) = service.transaction_registry.reserve(&owner) else {
+            let limit = service.transaction_registry.limit().unwrap_or_default();
+            return Err(HttpServiceError::TransactionLimitReached { owner, limit });
+        };
         let (request_sender, request_stream) = channel(TRANSACTION_REQUEST_BUFFER_SIZE);
-        let options =
-            payload.transaction_options.map(|options| options.into()).unwrap_or_else(|| TransactionOptions::default());
+        let defaults = service
+            .server_state
+            .databases_get(&payload.database_name)
+            .map(|database| database.transaction_defaults())
+            .unwrap_or_default();
+        let options = payload.transaction_options.unwrap_or_default().into_transaction_options(defaults);
         let transaction_timeout_millis = options.transaction_timeout_millis;
         let mut transaction_service = TransactionService::new(
             service.server_state.database_manager(),
             service.server_state.diagnostics_manager(),
+            service.trigger_runner.clone(),
             request_stream,
             service.server_state.shutdown_receiver(),
         );
 
         let database_name = payload.database_name;
+        let transaction_type = payload.transaction_type;
+
+        let processing_time = transaction_service
+            .open(transaction_type, database_name.clone(), options)
+            .await
+            .map_err(|typedb_source| HttpServiceError::Transaction { typedb_source })?;
+
+        let registration = transaction_reservation.register(
+            database_name,
+            transaction_type,
+            transaction_timeout_millis,
+            transaction_service.snapshot_sequence_number(),
+            TransactionEndpoint::Http(request_sender),
+        );
+        let transaction_id = registration.transaction_id();
+        // the service has not started, so the transaction is still registered
+        let transaction = service.transaction_registry.get(&transaction_id).expect("Expected registered transaction");
+        transaction_service.register(registration);
+        tokio::spawn(async move { transaction_service.listen().await });
+        Ok((transaction_id, transaction, processing_time))
+    }
+
+    async fn multi_database_transaction_new(
+        service: &TypeDBService,
+        owner: String,
+        payload: MultiDatabaseTransactionOpenPayload,
+    ) -> Result<(Uuid, u64), HttpServiceError> {
+        let Some(transaction_reservation) = service.transaction_registry.reserve(&owner) else {
+            let limit = service.transaction_registry.limit().unwrap_or_default();
+            return Err(HttpServiceError::TransactionLimitReached { owner, limit });
+        };
+        let (request_sender, request_stream) = channel(TRANSACTION_REQUEST_BUFFER_SIZE);
+        let options_payload = payload.transaction_options.unwrap_or_default();
+        let databases: Vec<_> = payload
+            .database_names
+            .into_iter()
+            .map(|database_name| {
+                let defaults = service
+                    .server_state
+                    .databases_get(&database_name)
+                    .map(|database| database.transaction_defaults())
+                    .unwrap_or_default();
+                let options = options_payload.clone().into_transaction_options(defaults);
+                (database_name, options)
+            })
+            .collect();
+        let transaction_timeout_millis = databases
+            .iter()
+            .map(|(_, options)| options.transaction_timeout_millis)
+            .min()
+            .unwrap_or(TransactionOptions::default().transaction_timeout_millis);
+        let database_names = databases.iter().map(|(database_name, _)| database_name.as_str()).join(", ");
+        let mut transaction_service = MultiDatabaseTransactionService::new(
+            service.server_state.database_manager(),
+            service.server_state.diagnostics_manager(),
+            service.trigger_runner.clone(),
+            request_stream,
+            service.server_state.shutdown_receiver(),
+        );
 
         let processing_time = transaction_service
-            .open(payload.transaction_type, database_name.clone(), options)
+            .open(databases)
             .await
             .map_err(|typedb_source| HttpServiceError::Transaction { typedb_source })?;
 
+        let registration = transaction_reservation.register(
+            database_names,
+            TransactionType::Write,
+            transaction_timeout_millis,
+            None,
+            TransactionEndpoint::Http(request_sender),
+        );
+        let transaction_id = registration.transaction_id();
+        transaction_service.register(registration);
         tokio::spawn(async move { transaction_service.listen().await });
-        Ok((TransactionInfo { owner, database_name, request_sender, transaction_timeout_millis }, processing_time))
+        Ok((transaction_id, processing_time))
     }
 
     async fn transaction_request(
-        transaction: &TransactionInfo,
+        transaction: &RegisteredTransaction,
         request: TransactionRequest,
         error_if_closed: bool,
     ) -> Result<TransactionServiceResponse, HttpServiceError> {
+        // transactions opened over gRPC only take requests on their own stream
+        let TransactionEndpoint::Http(request_sender) = &transaction.endpoint else {
+            return Err(HttpServiceError::no_open_transaction());
+        };
         let (result_sender, result_receiver) = oneshot::channel();
-        if let Err(_) = transaction.request_sender.send((request, TransactionResponder(result_sender))).await {
+        if let Err(_) = request_sender.send((request, TransactionResponder(result_sender))).await {
             return match error_if_closed {
                 false => Ok(TransactionServiceResponse::Ok),
                 true => Err(HttpServiceError::no_open_transaction()),
@@ -160,14 +233,34 @@ impl TypeDBService {
             Err(_) => Err(HttpServiceError::transaction_timeout()),
         }
     }
+
     fn build_analyse_query_request(query: String) -> TransactionRequest {
         TransactionRequest::AnalyseQuery(query)
     }
 
-    fn build_query_request(query_options_payload: Option<QueryOptionsPayload>, query: String) -> TransactionRequest {
+    fn build_query_request(
+        query_options_payload: Option<QueryOptionsPayload>,
+        query: String,
+        database_name: Option<String>,
+    ) -> TransactionRequest {
         let query_options =
             query_options_payload.map(|options| options.into()).unwrap_or_else(|| QueryOptions::default_http());
-        TransactionRequest::Query(query_options, query)
+        match database_name {
+            None => TransactionRequest::Query(query_options, query),
+            Some(database_name) => TransactionRequest::DatabaseQuery(database_name, query_options, query),
+        }
+    }
+
+    fn encode_query_response(
+        transaction_response: TransactionServiceResponse,
+        delimited_format: Option<DelimitedFormat>,
+    ) -> Response {
+        match (transaction_response, delimited_format) {
+            (TransactionServiceResponse::Query(query_response), Some(format)) => {
+                DelimitedQueryAnswer(query_response, format).into_response()
+            }
+            (transaction_response, _) => transaction_response.into_response(),
+        }
     }
 
     fn try_get_query_response(
@@ -176,7 +269,10 @@ impl TypeDBService {
         match transaction_response {
             TransactionServiceResponse::Query(query_response) => Ok(query_response),
             TransactionServiceResponse::Err(typedb_source) => Err(HttpServiceError::Transaction { typedb_source }),
-            TransactionServiceResponse::QueryAnalyse(_) | TransactionServiceResponse::Ok => {
+            TransactionServiceResponse::QueryAnalyse(_)
+            | TransactionServiceResponse::Committed(_)
+            | TransactionServiceResponse::Released(_)
+            | TransactionServiceResponse::Ok => {
                 Err(HttpServiceError::Internal { details: "unexpected transaction response".to_string() })
             }
         }
@@ -188,43 +284,88 @@ impl TypeDBService {
         match transaction_response {
             TransactionServiceResponse::QueryAnalyse(query_response) => Ok(query_response),
             TransactionServiceResponse::Err(typedb_source) => Err(HttpServiceError::Transaction { typedb_source }),
-            TransactionServiceResponse::Query(_) | TransactionServiceResponse::Ok => {
+            TransactionServiceResponse::Query(_)
+            | TransactionServiceResponse::Committed(_)
+            | TransactionServiceResponse::Released(_)
+            | TransactionServiceResponse::Ok => {
                 Err(HttpServiceError::Internal { details: "unexpected transaction response".to_string() })
             }
         }
     }
 
-    pub(crate) fn create_protected_router<T>(service: Arc<TypeDBService>) -> Router<T> {
+    pub(crate) fn create_protected_router<T>(service: Arc<TypeDBService>, config: &HttpEndpointConfig) -> Router<T> {
+        let query_router = Router::new()
+            .route("/:version/transactions", get(Self::transactions))
+            .route("/:version/transactions/open", post(Self::transaction_open))
+            .route("/:version/transactions/open/multi-database", post(Self::multi_database_transaction_open))
+            .route("/:version/transactions/:transaction-id", delete(Self::transactions_force_close))
+            .route("/:version/transactions/:transaction-id/commit", post(Self::transactions_commit))
+            .route("/:version/transactions/:transaction-id/close", post(Self::transactions_close))
+            .route("/:version/transactions/:transaction-id/rollback", post(Self::transactions_rollback))
+            .route("/:version/transactions/:transaction-id/analyze", post(Self::transactions_analyse))
+            .route("/:version/transactions/:transaction-id/query", post(Self::transactions_query))
+            .route("/:version/query", post(Self::query))
+            .layer(DefaultBodyLimit::max(config.body_limits.query_bytes))
+            .layer(RateLimiter::new(EndpointClass::Query, &config.rate_limits));
+        // cluster members coordinate at a fixed rate, and must not be throttled, or followers would start elections
+        // and replicas would fall behind
+        let cluster_router = Router::new()
+            .route("/:version/cluster", get(Self::cluster))
+            .route("/:version/cluster/vote", post(Self::cluster_vote))
+            .route("/:version/cluster/heartbeat", post(Self::cluster_heartbeat))
+            .route("/:version/replication/databases/:database-name/wal", get(Self::replication_records))
+            .layer(DefaultBodyLimit::max(config.body_limits.default_bytes));
         Router::new()
             .route("/:version/databases", get(Self::databases))
             .route("/:version/databases/:database-name", get(Self::databases_get))
             .route("/:version/databases/:database-name", post(Self::databases_create))
             .route("/:version/databases/:database-name", delete(Self::databases_delete))
+            .route("/:version/databases/:database-name/clone", post(Self::databases_clone))
             .route("/:version/databases/:database-name/schema", get(Self::databases_schema))
             .route("/:version/databases/:database-name/type-schema", get(Self::databases_type_schema))
+            .route("/:version/databases/:database-name/schema-diff", post(Self::databases_schema_diff))
+            .route("/:version/databases/:database-name/options", get(Self::databases_options))
+            .route("/:version/databases/:database-name/options", put(Self::databases_options_update))
+            .route("/:version/databases/:database-name/settings", get(Self::databases_settings))
+            .route("/:version/databases/:database-name/settings", put(Self::databases_settings_update))
+            .route("/:version/databases/:database-name/storage", get(Self::databases_storage))
+            .route("/:version/databases/:database-name/storage", put(Self::databases_storage_quota_update))
+            .route("/:version/databases/:database-name/storage/verify", post(Self::databases_storage_verify))
+            .route("/:version/databases/:database-name/statistics", get(Self::databases_statistics))
+            .route("/:version/databases/:database-name/lint", post(Self::databases_lint))
+            .route(
+                "/:version/databases/:database-name/relation-index/rebuild",
+                post(Self::databases_relation_index_rebuild),
+            )
+            .route("/:version/databases/:database-name/attributes/cleanup", post(Self::databases_attribute_cleanup))
+            .route("/:version/databases/:database-name/triggers", get(Self::databases_triggers))
+            .route("/:version/databases/:database-name/triggers", put(Self::databases_triggers_update))
+            .route("/:version/databases/:database-name/ttl", put(Self::databases_time_to_live_update))
+            .route("/:version/databases/:database-name/types/rename", post(Self::databases_type_rename))
             .route("/:version/users", get(Self::users))
             .route("/:version/users/:username", get(Self::users_get))
             .route("/:version/users/:username", post(Self::users_create))
             .route("/:version/users/:username", put(Self::users_update))
             .route("/:version/users/:username", delete(Self::users_delete))
-            .route("/:version/transactions/open", post(Self::transaction_open))
-            .route("/:version/transactions/:transaction-id/commit", post(Self::transactions_commit))
-            .route("/:version/transactions/:transaction-id/close", post(Self::transactions_close))
-            .route("/:version/transactions/:transaction-id/rollback", post(Self::transactions_rollback))
-            .route("/:version/transactions/:transaction-id/analyze", post(Self::transactions_analyse))
-            .route("/:version/transactions/:transaction-id/query", post(Self::transactions_query))
-            .route("/:version/query", post(Self::query))
+            .layer(DefaultBodyLimit::max(config.body_limits.default_bytes))
+            .layer(RateLimiter::new(EndpointClass::Admin, &config.rate_limits))
+            .merge(query_router)
+            .merge(cluster_router)
             .with_state(service)
     }
 
-    pub(crate) fn create_unprotected_router<T>(service: Arc<TypeDBService>) -> Router<T> {
+    pub(crate) fn create_unprotected_router<T>(service: Arc<TypeDBService>, config: &HttpEndpointConfig) -> Router<T> {
+        let auth_router = Router::new()
+            .route("/:version/signin", post(Self::signin))
+            .layer(RateLimiter::new(EndpointClass::Auth, &config.rate_limits));
         Router::new()
             .route("/", get(Self::redirect_to_latest_version))
             .route("/:version", get(Self::redirect_to_version))
             .route("/health", get(Self::health))
             .route("/:version/health", get(Self::health))
             .route("/:version/version", get(Self::version))
-            .route("/:version/signin", post(Self::signin))
+            .merge(auth_router)
+            .layer(DefaultBodyLimit::max(config.body_limits.default_bytes))
             .with_state(service)
     }
 
@@ -338,6 +479,28 @@ impl TypeDBService {
         )
     }
 
+    async fn databases_clone(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+        Query(query): Query<DatabaseCloneQuery>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics_async(
+            service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseClone,
+            || async {
+                let server_state = service.server_state.clone();
+                let name = database_path.database_name.clone();
+                spawn_blocking(move || server_state.databases_clone(&name, &query.target))
+                    .await
+                    .map_err(|err| HttpServiceError::Internal { details: err.to_string() })?
+                    .map_err(|typedb_source| HttpServiceError::DatabaseClone { typedb_source })
+            },
+        )
+        .await
+    }
+
     async fn databases_schema(
         _version: ProtocolVersion,
         State(service): State<Arc<TypeDBService>>,
@@ -376,6 +539,418 @@ impl TypeDBService {
         )
     }
 
+    async fn databases_schema_diff(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+        JsonBody(payload): JsonBody<SchemaDiffPayload>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseSchemaDiff,
+            || {
+                service
+                    .server_state
+                    .database_schema_diff(database_path.database_name.clone(), payload.schema)
+                    .map(|migration| PlainTextBody(migration))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_options(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseOptions,
+            || {
+                service
+                    .server_state
+                    .database_options(database_path.database_name.clone())
+                    .map(|options| JsonBody(encode_database_options(options)))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_options_update(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+        JsonBody(payload): JsonBody<DatabaseOptionsPayload>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseOptionsUpdate,
+            || {
+                service
+                    .server_state
+                    .database_options_update(
+                        database_path.database_name.clone(),
+                        payload.relation_index_threshold,
+                        payload.history_retention,
+                    )
+                    .map(|options| JsonBody(encode_database_options(options)))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_settings(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseSettings,
+            || {
+                service
+                    .server_state
+                    .database_settings(database_path.database_name.clone())
+                    .map(|settings| JsonBody(encode_database_settings(settings)))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_settings_update(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+        JsonBody(payload): JsonBody<DatabaseSettingsPayload>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseSettingsUpdate,
+            || {
+                service
+                    .server_state
+                    .database_settings_update(database_path.database_name.clone(), payload.into())
+                    .map(|settings| JsonBody(encode_database_settings(settings)))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_storage(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseStorage,
+            || {
+                service
+                    .server_state
+                    .database_storage(database_path.database_name.clone())
+                    .map(|usage| JsonBody(encode_storage_usage(usage)))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_storage_quota_update(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+        JsonBody(payload): JsonBody<StorageQuotaPayload>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseStorageQuotaUpdate,
+            || {
+                service
+                    .server_state
+                    .database_storage_quota_update(database_path.database_name.clone(), payload.quota_in_bytes)
+                    .map(|usage| JsonBody(encode_storage_usage(usage)))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_storage_verify(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+        Query(query): Query<StorageVerifyQuery>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics_async(
+            service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseStorageVerify,
+            || async {
+                let server_state = service.server_state.clone();
+                let name = database_path.database_name.clone();
+                spawn_blocking(move || server_state.database_verify(name, query.salvage))
+                    .await
+                    .map_err(|err| HttpServiceError::Internal { details: err.to_string() })?
+                    .map(|verifications| JsonBody(encode_storage_verification(verifications)))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+        .await
+    }
+
+    async fn databases_statistics(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseStatistics,
+            || {
+                service
+                    .server_state
+                    .database_statistics(database_path.database_name.clone())
+                    .map(|statistics| JsonBody(encode_database_statistics(statistics)))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_lint(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+        JsonBody(payload): JsonBody<LintPayload>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseLint,
+            || {
+                service
+                    .server_state
+                    .database_lint(database_path.database_name.clone(), payload.query)
+                    .map(|warnings| JsonBody(encode_lint_warnings(warnings)))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_relation_index_rebuild(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+        JsonBody(payload): JsonBody<RelationIndexRebuildPayload>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseRelationIndexRebuild,
+            || {
+                service
+                    .server_state
+                    .database_relation_index_rebuild(database_path.database_name.clone(), payload.relation_type)
+                    .map(|_| StatusCode::ACCEPTED)
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_attribute_cleanup(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseAttributeCleanup,
+            || {
+                service
+                    .server_state
+                    .database_attribute_cleanup(database_path.database_name.clone())
+                    .map(|_| StatusCode::ACCEPTED)
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_triggers(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseTriggers,
+            || {
+                service
+                    .server_state
+                    .database_triggers(database_path.database_name.clone())
+                    .map(|triggers| JsonBody(encode_commit_triggers(triggers)))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_triggers_update(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+        JsonBody(payload): JsonBody<CommitTriggersPayload>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseTriggersUpdate,
+            || {
+                let triggers = payload.triggers.into_iter().map(CommitTrigger::from).collect();
+                service
+                    .server_state
+                    .database_triggers_update(database_path.database_name.clone(), triggers)
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_time_to_live_update(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+        JsonBody(payload): JsonBody<TimeToLivePayload>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseTimeToLiveUpdate,
+            || {
+                service
+                    .server_state
+                    .database_time_to_live_update(
+                        database_path.database_name.clone(),
+                        payload.type_label,
+                        payload.seconds,
+                    )
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn databases_type_rename(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        database_path: DatabasePath,
+        JsonBody(payload): JsonBody<TypeRenamePayload>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseTypeRename,
+            || {
+                service
+                    .server_state
+                    .database_type_rename(database_path.database_name.clone(), payload.type_label, payload.new_label)
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn replication_records(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        accessor: Accessor,
+        database_path: DatabasePath,
+        Query(query): Query<ReplicationRecordsQuery>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            Some(&database_path.database_name),
+            ActionKind::DatabaseReplication,
+            || {
+                let batch = service
+                    .server_state
+                    .database_replication_records(
+                        database_path.database_name.clone(),
+                        query.from,
+                        query.limit,
+                        query.member.clone().zip(query.received),
+                        accessor,
+                    )
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })?;
+                let mut headers = HeaderMap::new();
+                headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/octet-stream"));
+                headers.insert(REPLICATION_WATERMARK_HEADER, HeaderValue::from(batch.primary_watermark));
+                Ok((headers, batch.records))
+            },
+        )
+    }
+
+    async fn cluster(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        accessor: Accessor,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            None::<&str>,
+            ActionKind::ClusterCoordination,
+            || {
+                service
+                    .server_state
+                    .cluster(accessor)
+                    .map(|cluster| JsonBody(encode_cluster(cluster.term(), cluster.leader(), cluster.members())))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn cluster_vote(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        accessor: Accessor,
+        JsonBody(payload): JsonBody<ClusterVotePayload>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            None::<&str>,
+            ActionKind::ClusterCoordination,
+            || {
+                service
+                    .server_state
+                    .cluster_vote(payload.term, payload.candidate, payload.received, accessor)
+                    .map(|reply| JsonBody(encode_cluster_reply(reply)))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
+    async fn cluster_heartbeat(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        accessor: Accessor,
+        JsonBody(payload): JsonBody<ClusterHeartbeatPayload>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics(
+            &service.server_state.diagnostics_manager(),
+            None::<&str>,
+            ActionKind::ClusterCoordination,
+            || {
+                service
+                    .server_state
+                    .cluster_heartbeat(payload.term, payload.leader, accessor)
+                    .map(|reply| JsonBody(encode_cluster_reply(reply)))
+                    .map_err(|typedb_source| HttpServiceError::State { typedb_source })
+            },
+        )
+    }
+
     async fn users(
         _version: ProtocolVersion,
         State(service): State<Arc<TypeDBService>>,
@@ -469,6 +1044,38 @@ impl TypeDBService {
         .await
     }
 
+    async fn transactions(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        Accessor(accessor): Accessor,
+    ) -> impl IntoResponse {
+        run_with_diagnostics_async(
+            service.server_state.diagnostics_manager(),
+            None::<&str>,
+            ActionKind::TransactionsAll,
+            || async {
+                if !PermissionManager::exec_transactions_all_permitted(&accessor) {
+                    return Err(HttpServiceError::operation_not_permitted());
+                }
+                let summaries = service.transaction_registry.summaries().await;
+                Ok(JsonBody(encode_transactions(
+                    summaries
+                        .into_iter()
+                        .map(|summary| TransactionSummaryResponse {
+                            transaction_id: summary.transaction_id,
+                            owner: summary.owner,
+                            database_name: summary.database_name,
+                            transaction_type: summary.transaction_type,
+                            age_millis: summary.age.as_millis() as u64,
+                            staged_writes: summary.staged_writes,
+                        })
+                        .collect(),
+                )))
+            },
+        )
+        .await
+    }
+
     async fn transaction_open(
         _version: ProtocolVersion,
         State(service): State<Arc<TypeDBService>>,
@@ -480,10 +1087,27 @@ impl TypeDBService {
             Some(payload.database_name.clone()),
             ActionKind::TransactionOpen,
             || async {
-                let (transaction_info, _processing_time) = Self::transaction_new(&service, accessor, payload).await?;
-                let uuid = Uuid::new_v4();
-                service.transaction_services.write().await.insert(uuid, transaction_info);
-                Ok(JsonBody(encode_transaction(uuid)))
+                let (transaction_id, _, _processing_time) = Self::transaction_new(&service, accessor, payload).await?;
+                Ok(JsonBody(encode_transaction(transaction_id)))
+            },
+        )
+        .await
+    }
+
+    async fn multi_database_transaction_open(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        Accessor(accessor): Accessor,
+        JsonBody(payload): JsonBody<MultiDatabaseTransactionOpenPayload>,
+    ) -> impl IntoResponse {
+        run_with_diagnostics_async(
+            service.server_state.diagnostics_manager(),
+            None::<&str>,
+            ActionKind::MultiDatabaseTransactionOpen,
+            || async {
+                let (transaction_id, _processing_time) =
+                    Self::multi_database_transaction_new(&service, accessor, payload).await?;
+                Ok(JsonBody(encode_transaction(transaction_id)))
             },
         )
         .await
@@ -496,8 +1120,7 @@ impl TypeDBService {
         path: TransactionPath,
     ) -> impl IntoResponse {
         let uuid = path.transaction_id;
-        let senders = service.transaction_services.read().await;
-        let transaction = senders.get(&uuid).ok_or(HttpServiceError::no_open_transaction())?;
+        let transaction = service.transaction_registry.get(&uuid).ok_or(HttpServiceError::no_open_transaction())?;
 
         run_with_diagnostics_async(
             service.server_state.diagnostics_manager(),
@@ -507,7 +1130,16 @@ impl TypeDBService {
                 if accessor != transaction.owner {
                     return Err(HttpServiceError::operation_not_permitted());
                 }
-                Self::transaction_request(&transaction, TransactionRequest::Commit, true).await
+                let commit_response = Self::transaction_request(&transaction, TransactionRequest::Commit, true).await?;
+                let data_version = match &commit_response {
+                    TransactionServiceResponse::Committed(data_version) => *data_version,
+                    _ => None,
+                };
+                let mut response = commit_response.into_response();
+                if let Some(data_version) = data_version {
+                    Self::insert_snapshot_token(&mut response, transaction.database_name.clone(), data_version);
+                }
+                Ok(response)
             },
         )
         .await
@@ -520,8 +1152,7 @@ impl TypeDBService {
         path: TransactionPath,
     ) -> impl IntoResponse {
         let uuid = path.transaction_id;
-        let senders = service.transaction_services.read().await;
-        let Some(transaction) = senders.get(&uuid) else {
+        let Some(transaction) = service.transaction_registry.get(&uuid) else {
             return Ok(TransactionServiceResponse::Ok);
         };
 
@@ -539,6 +1170,33 @@ impl TypeDBService {
         .await
     }
 
+    async fn transactions_force_close(
+        _version: ProtocolVersion,
+        State(service): State<Arc<TypeDBService>>,
+        Accessor(accessor): Accessor,
+        path: TransactionPath,
+    ) -> impl IntoResponse {
+        // checked before the lookup, so that users without permission cannot probe which transactions exist
+        if !PermissionManager::exec_transaction_force_close_permitted(&accessor) {
+            return Err(HttpServiceError::operation_not_permitted());
+        }
+        let uuid = path.transaction_id;
+        let transaction = service.transaction_registry.get(&uuid).ok_or(HttpServiceError::no_open_transaction())?;
+
+        run_with_diagnostics_async(
+            service.server_state.diagnostics_manager(),
+            Some(transaction.database_name.clone()),
+            ActionKind::TransactionClose,
+            || async {
+                match service.transaction_registry.force_close(&uuid).await {
+                    true => Ok(TransactionServiceResponse::Ok),
+                    false => Err(HttpServiceError::no_open_transaction()),
+                }
+            },
+        )
+        .await
+    }
+
     async fn transactions_rollback(
         _version: ProtocolVersion,
         State(service): State<Arc<TypeDBService>>,
@@ -546,8 +1204,7 @@ impl TypeDBService {
         path: TransactionPath,
     ) -> impl IntoResponse {
         let uuid = path.transaction_id;
-        let senders = service.transaction_services.read().await;
-        let transaction = senders.get(&uuid).ok_or(HttpServiceError::no_open_transaction())?;
+        let transaction = service.transaction_registry.get(&uuid).ok_or(HttpServiceError::no_open_transaction())?;
 
         run_with_diagnostics_async(
             service.server_state.diagnostics_manager(),
@@ -571,8 +1228,7 @@ impl TypeDBService {
         JsonBody(payload): JsonBody<TransactionAnalyzePayload>,
     ) -> impl IntoResponse {
         let uuid = path.transaction_id;
-        let senders = service.transaction_services.read().await;
-        let transaction = senders.get(&uuid).ok_or(HttpServiceError::no_open_transaction())?;
+        let transaction = service.transaction_registry.get(&uuid).ok_or(HttpServiceError::no_open_transaction())?;
 
         run_with_diagnostics_async(
             service.server_state.diagnostics_manager(),
@@ -593,11 +1249,12 @@ impl TypeDBService {
         State(service): State<Arc<TypeDBService>>,
         Accessor(accessor): Accessor,
         path: TransactionPath,
+        headers: HeaderMap,
         JsonBody(payload): JsonBody<TransactionQueryPayload>,
     ) -> impl IntoResponse {
         let uuid = path.transaction_id;
-        let senders = service.transaction_services.read().await;
-        let transaction = senders.get(&uuid).ok_or(HttpServiceError::no_open_transaction())?;
+        let transaction = service.transaction_registry.get(&uuid).ok_or(HttpServiceError::no_open_transaction())?;
+        let delimited_format = DelimitedFormat::from_accept(&headers);
 
         run_with_diagnostics_async(
             service.server_state.diagnostics_manager(),
@@ -607,12 +1264,16 @@ impl TypeDBService {
                 if accessor != transaction.owner {
                     return Err(HttpServiceError::operation_not_permitted());
                 }
-                Self::transaction_request(
+                if let Some(format) = delimited_format {
+                    format.check_answerable(&payload.query)?;
+                }
+                let transaction_response = Self::transaction_request(
                     &transaction,
-                    Self::build_query_request(payload.query_options, payload.query),
+                    Self::build_query_request(payload.query_options, payload.query, payload.database_name),
                     true,
                 )
-                .await
+                .await?;
+                Ok(Self::encode_query_response(transaction_response, delimited_format))
             },
         )
         .await
@@ -622,46 +1283,184 @@ impl TypeDBService {
         _version: ProtocolVersion,
         State(service): State<Arc<TypeDBService>>,
         Accessor(accessor): Accessor,
+        headers: HeaderMap,
         JsonBody(payload): JsonBody<QueryPayload>,
     ) -> impl IntoResponse {
+        let delimited_format = DelimitedFormat::from_accept(&headers);
         run_with_diagnostics_async(
             service.server_state.diagnostics_manager(),
             Some(payload.transaction_open_payload.database_name.clone()),
             ActionKind::OneshotQuery,
             || async {
-                let (transaction_info, _processing_time) =
-                    Self::transaction_new(&service, accessor, payload.transaction_open_payload).await?;
-
-                let transaction_response = Self::transaction_request(
-                    &transaction_info,
-                    Self::build_query_request(payload.query_options, payload.query),
-                    true,
-                )
-                .await?;
-                let query_response = Self::try_get_query_response(transaction_response)?;
-
-                let commit = match query_response.query_type() {
-                    QueryType::Read => false,
-                    QueryType::Write | QueryType::Schema => {
-                        payload.commit.unwrap_or(Self::QUERY_ENDPOINT_COMMIT_DEFAULT)
-                    }
-                };
-
-                let close_response = match commit {
-                    true => Self::transaction_request(&transaction_info, TransactionRequest::Commit, true),
-                    false => Self::transaction_request(&transaction_info, TransactionRequest::Close, true),
+                if let Some(format) = delimited_format {
+                    format.check_answerable(&payload.query)?;
                 }
-                .await?;
-                if let TransactionServiceResponse::Err(typedb_source) = close_response {
-                    return match commit {
-                        true => Err(HttpServiceError::QueryCommit { typedb_source }),
-                        false => Err(HttpServiceError::QueryClose { typedb_source }),
-                    };
+                let retries = payload.max_conflict_retries.unwrap_or(0).min(Self::QUERY_ENDPOINT_CONFLICT_RETRIES_MAX);
+                let mut attempt = 0;
+                loop {
+                    match Self::query_once(&service, accessor.clone(), &payload, delimited_format).await {
+                        Err(err)
+                            if attempt < retries
+                                && err.commit_isolation_conflict().is_some_and(IsolationConflict::is_write_write) =>
+                        {
+                            sleep(Self::conflict_retry_backoff(attempt)).await;
+                            attempt += 1;
+                        }
+                        result => return result,
+                    }
                 }
-
-                Ok(TransactionServiceResponse::Query(query_response))
             },
         )
         .await
     }
+
+    async fn query_once(
+        service: &TypeDBService,
+        accessor: String,
+        payload: &QueryPayload,
+        delimited_format: Option<DelimitedFormat>,
+    ) -> Result<Response, HttpServiceError> {
+        let mut transaction_open_payload = payload.transaction_open_payload.clone();
+        if let Some(token) = &payload.snapshot_token {
+            Self::pin_to_snapshot_token(&mut transaction_open_payload, token)?;
+        }
+        let (_, transaction_info, _processing_time) =
+            Self::transaction_new(service, accessor, transaction_open_payload).await?;
+
+        let transaction_response = Self::transaction_request(
+            &transaction_info,
+            Self::build_query_request(payload.query_options.clone(), payload.query.clone(), None),
+            true,
+        )
+        .await?;
+        let query_response = Self::try_get_query_response(transaction_response)?;
+
+        let commit = match query_response.query_type() {
+            QueryType::Read => false,
+            QueryType::Write | QueryType::Schema => payload.commit.unwrap_or(Self::QUERY_ENDPOINT_COMMIT_DEFAULT),
+        };
+
+        let close_response = match commit {
+            true => Self::transaction_request(&transaction_info, TransactionRequest::Commit, true),
+            false => Self::transaction_request(&transaction_info, TransactionRequest::Close, true),
+        }
+        .await?;
+        let data_version = match close_response {
+            TransactionServiceResponse::Err(typedb_source) => {
+                return match commit {
+                    true => Err(HttpServiceError::QueryCommit { typedb_source }),
+                    false => Err(HttpServiceError::QueryClose { typedb_source }),
+                };
+            }
+            TransactionServiceResponse::Committed(data_version) => data_version,
+            _ => None,
+        };
+
+        let mut response =
+            Self::encode_query_response(TransactionServiceResponse::Query(query_response), delimited_format);
+        // writes are pinned to the version they committed, so that later reads with the token observe them
+        if let Some(sequence_number) = data_version.or(transaction_info.snapshot_sequence_number) {
+            Self::insert_snapshot_token(&mut response, transaction_info.database_name.clone(), sequence_number);
+        }
+        Ok(response)
+    }
+
+    /// Opens the transaction at the data version of the snapshot token.
+    /// Only read transactions on the database the token was issued for can use it.
+    fn pin_to_snapshot_token(
+        transaction_open_payload: &mut TransactionOpenPayload,
+        token: &str,
+    ) -> Result<(), HttpServiceError> {
+        let token = SnapshotToken::decode(token)?;
+        if transaction_open_payload.transaction_type != TransactionType::Read {
+            return Err(HttpServiceError::SnapshotTokenRequiresRead {});
+        }
+        if token.database_name != transaction_open_payload.database_name {
+            return Err(HttpServiceError::InvalidSnapshotToken {
+                details: "the token was issued for another database".to_string(),
+            });
+        }
+        let mut options = transaction_open_payload.transaction_options.take().unwrap_or_default();
+        options.read_at_sequence_number = Some(token.sequence_number);
+        transaction_open_payload.transaction_options = Some(options);
+        Ok(())
+    }
+
+    fn insert_snapshot_token(response: &mut Response, database_name: String, sequence_number: u64) {
+        let token = SnapshotToken::new(database_name, sequence_number).encode();
+        response.headers_mut().insert(
+            SNAPSHOT_TOKEN_HEADER,
+            HeaderValue::from_str(&token).expect("Expected snapshot tokens to be valid header values"),
+        );
+    }
+
+    /// Exponential backoff with jitter, so that conflicting retries are unlikely to collide again.
+    fn conflict_retry_backoff(attempt: u32) -> Duration {
+        Self::QUERY_ENDPOINT_CONFLICT_RETRY_BACKOFF.saturating_mul(1 << attempt).mul_f64(1.0 + rand::random::<f64>())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::{
+        HttpServiceError, Response, SnapshotToken, TransactionOpenPayload, TypeDBService, SNAPSHOT_TOKEN_HEADER,
+    };
+    use crate::service::TransactionType;
+
+    fn read_payload(database_name: &str) -> TransactionOpenPayload {
+        TransactionOpenPayload {
+            database_name: database_name.to_owned(),
+            transaction_type: TransactionType::Read,
+            transaction_options: None,
+        }
+    }
+
+    #[test]
+    fn snapshot_token_round_trips_into_read_transaction_options() {
+        let database_name = "peop.le-ü";
+        let token = SnapshotToken::new(database_name.to_owned(), 0x1234);
+        let encoded = token.encode();
+        assert_eq!(SnapshotToken::decode(&encoded).unwrap(), token);
+
+        let mut response = Response::new(axum::body::Body::empty());
+        TypeDBService::insert_snapshot_token(&mut response, database_name.to_owned(), 0x1234);
+        assert_eq!(response.headers()[SNAPSHOT_TOKEN_HEADER].to_str().unwrap(), encoded);
+
+        let mut payload = read_payload(database_name);
+        TypeDBService::pin_to_snapshot_token(&mut payload, &encoded).unwrap();
+        assert_eq!(payload.transaction_options.unwrap().read_at_sequence_number, Some(0x1234));
+    }
+
+    #[test]
+    fn tampered_snapshot_token_is_rejected() {
+        let encoded = SnapshotToken::new("people".to_owned(), 10).encode();
+        let (body, last_checksum_digit) = encoded.split_at(encoded.len() - 1);
+        let tampered_checksum = format!("{body}{}", if last_checksum_digit == "0" { '1' } else { '0' });
+        let tampered_sequence_number = encoded.replacen(".a.", ".b.", 1);
+        assert_ne!(tampered_sequence_number, encoded);
+
+        for token in [tampered_checksum, tampered_sequence_number, encoded[..encoded.len() - 4].to_owned()] {
+            let result = TypeDBService::pin_to_snapshot_token(&mut read_payload("people"), &token);
+            assert!(matches!(result, Err(HttpServiceError::InvalidSnapshotToken { .. })), "{result:?}");
+        }
+    }
+
+    #[test]
+    fn snapshot_token_is_rejected_for_another_database() {
+        let encoded = SnapshotToken::new("people".to_owned(), 10).encode();
+        let mut payload = read_payload("places");
+        let result = TypeDBService::pin_to_snapshot_token(&mut payload, &encoded);
+        assert!(matches!(result, Err(HttpServiceError::InvalidSnapshotToken { .. })), "{result:?}");
+        assert!(payload.transaction_options.is_none());
+    }
+
+    #[test]
+    fn snapshot_token_is_rejected_for_write_transactions() {
+        let encoded = SnapshotToken::new("people".to_owned(), 10).encode();
+        for transaction_type in [TransactionType::Write, TransactionType::Schema] {
+            let mut payload = TransactionOpenPayload { transaction_type, ..read_payload("people") };
+            let result = TypeDBService::pin_to_snapshot_token(&mut payload, &encoded);
+            assert!(matches!(result, Err(HttpServiceError::SnapshotTokenRequiresRead { .. })), "{result:?}");
+        }
+    }
 }
@@ -0,0 +1,119 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+};
+
+use answer::Type;
+use concept::{
+    error::ConceptReadError,
+    thing::{attribute::Attribute, thing_manager::ThingManager},
+    type_::{type_manager::TypeManager, TypeAPI},
+};
+use encoding::value::value::Value;
+use resource::profile::StorageCounters;
+use storage::snapshot::ReadableSnapshot;
+
+/// Interns the type labels and string attribute values encoded into answers, so that a label or string value
+/// repeated across many rows is looked up once per transaction rather than once per row.
+///
+/// Clones share the interned entries, so every answer stream of a transaction encodes from one pool. Labels can
+/// change under schema queries, so transactions start a new pool after each one.
+///
+/// String attributes are interned with their value already read, since long strings are stored out of line.
+#[derive(Debug, Clone, Default)]
+pub struct InternPool {
+    labels: Arc<RwLock<HashMap<Type, Arc<str>>>>,
+    string_attributes: Arc<RwLock<HashSet<Attribute>>>,
+}
+
+impl InternPool {
+    const MAX_STRING_ATTRIBUTES: usize = 1 << 16;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn label(
+        &self,
+        type_: Type,
+        snapshot: &impl ReadableSnapshot,
+        type_manager: &TypeManager,
+    ) -> Result<Arc<str>, Box<ConceptReadError>> {
+        if let Some(label) = self.labels.read().unwrap().get(&type_) {
+            return Ok(label.clone());
+        }
+        let label = match type_ {
+            Type::Entity(entity) => entity.get_label(snapshot, type_manager)?,
+            Type::Relation(relation) => relation.get_label(snapshot, type_manager)?,
+            Type::Attribute(attribute) => attribute.get_label(snapshot, type_manager)?,
+            Type::RoleType(role) => role.get_label(snapshot, type_manager)?,
+        };
+        let label: Arc<str> = Arc::from(label.scoped_name().as_str());
+        Ok(self.labels.write().unwrap().entry(type_).or_insert(label).clone())
+    }
+
+    /// Returns the attribute with its value read, sharing the value of an interned string attribute if there is one.
+    pub fn attribute(
+        &self,
+        attribute: &Attribute,
+        snapshot: &impl ReadableSnapshot,
+        thing_manager: &ThingManager,
+        storage_counters: StorageCounters,
+    ) -> Result<Attribute, Box<ConceptReadError>> {
+        if let Some(interned) = self.string_attributes.read().unwrap().get(attribute) {
+            return Ok(interned.clone());
+        }
+        let attribute = attribute.clone();
+        if !matches!(attribute.get_value(snapshot, thing_manager, storage_counters)?, Value::String(_)) {
+            return Ok(attribute);
+        }
+        let mut string_attributes = self.string_attributes.write().unwrap();
+        if string_attributes.len() < Self::MAX_STRING_ATTRIBUTES {
+            string_attributes.insert(attribute.clone());
+        }
+        Ok(attribute)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use answer::Type;
+    use database::{database_manager::DatabaseManager, transaction::TransactionSchema};
+    use encoding::value::label::Label;
+    use options::TransactionOptions;
+    use test_utils::create_tmp_dir;
+
+    use super::InternPool;
+
+    #[test]
+    fn clones_share_interned_labels() {
+        let databases_path = create_tmp_dir();
+        let database_manager = DatabaseManager::new(&databases_path).unwrap();
+        database_manager.put_database("test").unwrap();
+        let database = database_manager.database("test").unwrap();
+        let mut transaction = TransactionSchema::open(database, TransactionOptions::default()).unwrap();
+        let snapshot = Arc::get_mut(&mut transaction.snapshot).unwrap();
+        let person = transaction.type_manager.create_entity_type(snapshot, &Label::build("person", None)).unwrap();
+
+        let pool = InternPool::new();
+        let stream_pool = pool.clone();
+        let label = pool.label(Type::Entity(person), transaction.snapshot.as_ref(), &transaction.type_manager).unwrap();
+        let stream_label =
+            stream_pool.label(Type::Entity(person), transaction.snapshot.as_ref(), &transaction.type_manager).unwrap();
+        assert_eq!(&*label, "person");
+        assert!(Arc::ptr_eq(&label, &stream_label));
+
+        let fresh_label = InternPool::new()
+            .label(Type::Entity(person), transaction.snapshot.as_ref(), &transaction.type_manager)
+            .unwrap();
+        assert!(!Arc::ptr_eq(&label, &fresh_label));
+    }
+}
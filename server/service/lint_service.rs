@@ -0,0 +1,47 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+use std::sync::Arc;
+
+use compiler::warning::QueryWarning;
+use database::{
+    transaction::{TransactionError, TransactionRead},
+    Database,
+};
+use error::typedb_error;
+use options::TransactionOptions;
+use query::error::QueryError;
+use storage::durability_client::WALClient;
+use typeql::query::QueryStructure;
+
+/// Parses and annotates the query pipeline against the schema of `database` without executing it,
+/// and returns the warnings found on the way.
+pub(crate) fn lint_query(database: Arc<Database<WALClient>>, query: String) -> Result<Vec<QueryWarning>, LintError> {
+    let parsed = typeql::parse_query(&query).map_err(|typedb_source| LintError::QueryParseFailed { typedb_source })?;
+    let QueryStructure::Pipeline(pipeline) = parsed.into_structure() else {
+        return Err(LintError::QueryNotPipeline {});
+    };
+
+    let transaction = TransactionRead::open(database, TransactionOptions::default())
+        .map_err(|typedb_source| LintError::TransactionFailed { typedb_source })?;
+    let warnings = transaction.query_manager.lint(
+        transaction.snapshot(),
+        &transaction.type_manager,
+        &transaction.function_manager,
+        &pipeline,
+        &query,
+    );
+    transaction.close();
+    warnings.map_err(|typedb_source| LintError::QueryCompilationFailed { typedb_source })
+}
+
+typedb_error! {
+    pub(crate) LintError(component = "Query lint", prefix = "LNT") {
+        QueryParseFailed(1, "Query parsing failed.", typedb_source: typeql::Error),
+        QueryNotPipeline(2, "Only query pipelines can be linted, not schema queries."),
+        TransactionFailed(3, "Transaction failed.", typedb_source: TransactionError),
+        QueryCompilationFailed(4, "The query could not be compiled.", typedb_source: Box<QueryError>),
+    }
+}
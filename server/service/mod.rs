@@ -8,11 +8,26 @@ use concept::error::ConceptReadError;
 use options::QueryOptions;
 use serde::{Deserialize, Serialize};
 
+pub(crate) mod attribute_cleanup_service;
+pub(crate) mod cluster_service;
+pub(crate) mod database_options_service;
+pub(crate) mod expiry_service;
 pub(crate) mod export_service;
 pub(crate) mod grpc;
 pub mod http;
 mod import_service;
+pub(crate) mod intern_pool;
+pub(crate) mod lint_service;
+pub(crate) mod peer_client;
+pub(crate) mod relation_index_service;
+pub(crate) mod replication_service;
+pub(crate) mod schema_diff_service;
+pub(crate) mod statistics_service;
+pub(crate) mod transaction_limiter;
+pub(crate) mod transaction_registry;
 mod transaction_service;
+pub(crate) mod trigger_service;
+pub(crate) mod type_rename_service;
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialOrd, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
@@ -0,0 +1,143 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use error::typedb_error;
+use hyper::{
+    body::{to_bytes, Bytes},
+    client::HttpConnector,
+    header::{AUTHORIZATION, CONTENT_TYPE},
+    Body, Client, Method, Request, Response, StatusCode,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::service::http::message::authentication::{SigninPayload, TokenResponse};
+
+/// HTTP client for requests to other servers, such as a replication primary or cluster peers,
+/// which are addressed by their HTTP addresses. Each server is signed in to once, and its token reused.
+pub(crate) struct PeerClient {
+    client: Client<HttpConnector>,
+    username: String,
+    password: String,
+    tokens: Mutex<HashMap<String, String>>,
+}
+
+impl PeerClient {
+    const API_VERSION: &'static str = "v1";
+
+    pub(crate) fn new(username: String, password: String) -> Self {
+        Self {
+            client: Client::builder().build(HttpConnector::new()),
+            username,
+            password,
+            tokens: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Drops the token for the server, so that it is signed in to again, for example after it restarted.
+    pub(crate) fn forget_token(&self, address: &str) {
+        self.tokens.lock().unwrap().remove(address);
+    }
+
+    pub(crate) async fn get(&self, address: &str, path: &str) -> Result<Response<Body>, PeerError> {
+        let token = self.token(address).await?;
+        self.send(address, self.request(Method::GET, address, path, Some(&token), Body::empty())?).await
+    }
+
+    pub(crate) async fn get_json<T: DeserializeOwned>(&self, address: &str, path: &str) -> Result<T, PeerError> {
+        let response = self.get(address, path).await?;
+        Self::decode_json(address, Self::read_body(address, response).await?)
+    }
+
+    pub(crate) async fn post_json<P: Serialize, T: DeserializeOwned>(
+        &self,
+        address: &str,
+        path: &str,
+        payload: &P,
+    ) -> Result<T, PeerError> {
+        let token = self.token(address).await?;
+        let request = self.request(Method::POST, address, path, Some(&token), Self::encode_json(payload)?)?;
+        let response = self.send(address, request).await?;
+        Self::decode_json(address, Self::read_body(address, response).await?)
+    }
+
+    pub(crate) async fn read_body(address: &str, response: Response<Body>) -> Result<Bytes, PeerError> {
+        to_bytes(response.into_body())
+            .await
+            .map_err(|source| PeerError::Transport { address: address.to_owned(), source: Arc::new(source) })
+    }
+
+    async fn token(&self, address: &str) -> Result<String, PeerError> {
+        if let Some(token) = self.tokens.lock().unwrap().get(address) {
+            return Ok(token.clone());
+        }
+        let payload = SigninPayload { username: self.username.clone(), password: self.password.clone() };
+        let request = self.request(Method::POST, address, "signin", None, Self::encode_json(&payload)?)?;
+        let response = self.send(address, request).await?;
+        let response: TokenResponse = Self::decode_json(address, Self::read_body(address, response).await?)?;
+        self.tokens.lock().unwrap().insert(address.to_owned(), response.token.clone());
+        Ok(response.token)
+    }
+
+    fn request(
+        &self,
+        method: Method,
+        address: &str,
+        path: &str,
+        token: Option<&str>,
+        body: Body,
+    ) -> Result<Request<Body>, PeerError> {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(format!("{}/{}/{}", address.trim_end_matches('/'), Self::API_VERSION, path))
+            .header(CONTENT_TYPE, "application/json");
+        if let Some(token) = token {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        request.body(body).map_err(|source| PeerError::InvalidRequest { source: Arc::new(source) })
+    }
+
+    async fn send(&self, address: &str, request: Request<Body>) -> Result<Response<Body>, PeerError> {
+        let response = self
+            .client
+            .request(request)
+            .await
+            .map_err(|source| PeerError::Transport { address: address.to_owned(), source: Arc::new(source) })?;
+        match response.status() {
+            StatusCode::OK => Ok(response),
+            StatusCode::UNAUTHORIZED => {
+                self.forget_token(address);
+                Err(PeerError::Rejected { address: address.to_owned(), status: StatusCode::UNAUTHORIZED.to_string() })
+            }
+            status => Err(PeerError::Rejected { address: address.to_owned(), status: status.to_string() }),
+        }
+    }
+
+    fn encode_json<P: Serialize>(payload: &P) -> Result<Body, PeerError> {
+        serde_json::to_string(payload)
+            .map(Body::from)
+            .map_err(|source| PeerError::InvalidPayload { details: source.to_string() })
+    }
+
+    fn decode_json<T: DeserializeOwned>(address: &str, bytes: Bytes) -> Result<T, PeerError> {
+        serde_json::from_slice(&bytes)
+            .map_err(|source| PeerError::InvalidResponse { address: address.to_owned(), details: source.to_string() })
+    }
+}
+
+typedb_error! {
+    pub(crate) PeerError(component = "Peer", prefix = "PER") {
+        InvalidRequest(1, "Error building a request to another server.", source: Arc<hyper::http::Error>),
+        InvalidPayload(2, "Error encoding a request to another server: {details}", details: String),
+        Transport(3, "Error communicating with server '{address}'.", address: String, source: Arc<hyper::Error>),
+        Rejected(4, "Server '{address}' rejected a request with status '{status}'.", address: String, status: String),
+        InvalidResponse(5, "Invalid response from server '{address}': {details}", address: String, details: String),
+    }
+}
@@ -0,0 +1,126 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+use std::{iter, sync::Arc, thread};
+
+use concept::{
+    error::{ConceptReadError, ConceptWriteError},
+    type_::{relation_type::RelationType, TypeAPI},
+};
+use database::{
+    transaction::{SchemaCommitError, TransactionError, TransactionSchema},
+    Database,
+};
+use diagnostics::{diagnostics_manager::DiagnosticsManager, metrics::BackgroundTaskKind};
+use encoding::value::label::Label;
+use error::typedb_error;
+use itertools::Itertools;
+use options::TransactionOptions;
+use resource::profile::StorageCounters;
+use storage::durability_client::WALClient;
+use tracing::{event, Level};
+
+/// Rebuilds or drops the role player index of the instances of a relation type and its subtypes,
+/// according to whether each type currently qualifies for the index.
+///
+/// The schema transaction is opened before returning, so concurrent schema changes are rejected up front.
+/// The rebuild itself runs on a background thread, reporting its progress through diagnostics.
+pub(crate) fn start_relation_index_rebuild(
+    database: Arc<Database<WALClient>>,
+    relation_type_label: &str,
+    diagnostics_manager: Arc<DiagnosticsManager>,
+) -> Result<(), RelationIndexRebuildError> {
+    let mut transaction = TransactionSchema::open(database, TransactionOptions::default())
+        .map_err(|typedb_source| RelationIndexRebuildError::TransactionFailed { typedb_source })?;
+    let relation_types = match get_relation_types(&transaction, relation_type_label) {
+        Ok(relation_types) => relation_types,
+        Err(err) => {
+            transaction.close();
+            return Err(err);
+        }
+    };
+
+    thread::spawn(move || {
+        let database_name = transaction.database.name().to_owned();
+        let result = rebuild_relation_index(&mut transaction, &relation_types, |completed, total| {
+            diagnostics_manager.submit_background_task_progress(
+                &database_name,
+                BackgroundTaskKind::RelationIndexRebuild,
+                completed,
+                total,
+            )
+        });
+        let result = match result {
+            Ok(()) => transaction
+                .commit()
+                .1
+                .map(|_| ())
+                .map_err(|typedb_source| RelationIndexRebuildError::SchemaCommitFailed { typedb_source }),
+            Err(err) => {
+                transaction.close();
+                Err(err)
+            }
+        };
+        if let Err(err) = result {
+            event!(Level::ERROR, "Relation index rebuild in database '{}' failed: {:?}", database_name, err);
+        }
+        diagnostics_manager.submit_background_task_finished(&database_name, BackgroundTaskKind::RelationIndexRebuild);
+    });
+    Ok(())
+}
+
+fn get_relation_types(
+    transaction: &TransactionSchema<WALClient>,
+    relation_type_label: &str,
+) -> Result<Vec<RelationType>, RelationIndexRebuildError> {
+    let snapshot = transaction.snapshot.as_ref();
+    let type_manager = &transaction.type_manager;
+    let relation_type = type_manager
+        .get_relation_type(snapshot, &Label::build(relation_type_label, None))
+        .map_err(|typedb_source| RelationIndexRebuildError::ConceptRead { typedb_source })?
+        .ok_or_else(|| RelationIndexRebuildError::RelationTypeNotFound { label: relation_type_label.to_owned() })?;
+    let subtypes = relation_type
+        .get_subtypes_transitive(snapshot, type_manager)
+        .map_err(|typedb_source| RelationIndexRebuildError::ConceptRead { typedb_source })?;
+    Ok(iter::once(relation_type).chain(subtypes.iter().copied()).collect_vec())
+}
+
+fn rebuild_relation_index(
+    transaction: &mut TransactionSchema<WALClient>,
+    relation_types: &[RelationType],
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<(), RelationIndexRebuildError> {
+    let snapshot =
+        Arc::get_mut(&mut transaction.snapshot).expect("Expected owning snapshot for relation index rebuild");
+    // Progress accumulates over the relation types, whose relations are only counted once they are reached
+    let mut completed_before = 0;
+    for &relation_type in relation_types {
+        let mut completed_in_type = 0;
+        transaction
+            .thing_manager
+            .rebuild_relation_index(
+                snapshot,
+                relation_type,
+                |completed, total| {
+                    completed_in_type = completed;
+                    on_progress(completed_before + completed, completed_before + total)
+                },
+                StorageCounters::DISABLED,
+            )
+            .map_err(|typedb_source| RelationIndexRebuildError::ConceptWrite { typedb_source })?;
+        completed_before += completed_in_type;
+    }
+    Ok(())
+}
+
+typedb_error! {
+    pub(crate) RelationIndexRebuildError(component = "Relation index rebuild", prefix = "RIR") {
+        TransactionFailed(1, "Transaction failed.", typedb_source: TransactionError),
+        RelationTypeNotFound(2, "Relation type '{label}' not found.", label: String),
+        ConceptRead(3, "Error reading concepts.", typedb_source: Box<ConceptReadError>),
+        ConceptWrite(4, "Error writing concepts.", typedb_source: Box<ConceptWriteError>),
+        SchemaCommitFailed(5, "Failed to commit the rebuilt relation index.", typedb_source: SchemaCommitError),
+    }
+}
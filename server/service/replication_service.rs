@@ -0,0 +1,237 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::{sync::Arc, time::Duration};
+
+use database::{
+    cluster::Cluster,
+    database::DatabaseCreateError,
+    database_manager::DatabaseManager,
+    replication::{decode_replication_records, encode_replication_records, DatabaseReplicationError},
+    Database,
+};
+use error::typedb_error;
+use resource::constants::server::REPLICATION_BATCH_MAX_RECORDS;
+use storage::{durability_client::WALClient, sequence_number::SequenceNumber};
+use tokio::{sync::watch::Receiver, task::spawn_blocking, time::interval};
+use tracing::{event, Level};
+
+use crate::{
+    parameters::config::ReplicationConfig,
+    service::{
+        http::message::database::DatabasesResponse,
+        peer_client::{PeerClient, PeerError},
+    },
+};
+
+pub(crate) const REPLICATION_WATERMARK_HEADER: &str = "typedb-replication-watermark";
+
+#[derive(Debug)]
+pub(crate) struct ReplicationBatch {
+    pub(crate) records: Vec<u8>,
+    pub(crate) primary_watermark: u64,
+}
+
+/// Reads a batch of the database's WAL for a replica, starting from the replica's position.
+pub(crate) fn read_replication_batch(
+    database: Arc<Database<WALClient>>,
+    from: u64,
+    limit: Option<usize>,
+) -> Result<ReplicationBatch, ReplicationError> {
+    let limit = limit.unwrap_or(REPLICATION_BATCH_MAX_RECORDS).min(REPLICATION_BATCH_MAX_RECORDS);
+    // read the watermark first, so that the records up to it are in this batch or the following ones
+    let primary_watermark = database.replication_watermark().number();
+    let records = database
+        .read_replication_records(SequenceNumber::new(from), limit)
+        .map_err(|typedb_source| ReplicationError::DatabaseReplication { typedb_source })?;
+    Ok(ReplicationBatch { records: encode_replication_records(&records), primary_watermark })
+}
+
+/// Makes all user databases read-only replicas, and polls the primary in the background to keep them up to date.
+/// Databases created on the primary are created on the replica when they are first seen.
+pub(crate) fn start_replication(
+    config: &ReplicationConfig,
+    database_manager: Arc<DatabaseManager>,
+    shutdown_receiver: Receiver<()>,
+) {
+    let Some(primary_address) = config.primary_address.clone() else {
+        return;
+    };
+    database_manager.make_replicas();
+    let source = ReplicationSource::Primary(primary_address);
+    let poll_interval = Duration::from_millis(config.poll_interval_millis);
+    spawn_replicator(Replicator::new(config, source, database_manager), poll_interval, shutdown_receiver);
+}
+
+/// Polls the current cluster leader in the background, while this cluster member is a follower.
+/// The member's databases are made replicas or primaries as its role changes, by the cluster service.
+pub(crate) fn start_cluster_replication(
+    config: &ReplicationConfig,
+    cluster: Arc<Cluster>,
+    poll_interval: Duration,
+    database_manager: Arc<DatabaseManager>,
+    shutdown_receiver: Receiver<()>,
+) {
+    let source = ReplicationSource::ClusterLeader(cluster);
+    spawn_replicator(Replicator::new(config, source, database_manager), poll_interval, shutdown_receiver);
+}
+
+fn spawn_replicator(replicator: Replicator, poll_interval: Duration, mut shutdown_receiver: Receiver<()>) {
+    tokio::spawn(async move {
+        let mut poll_interval = interval(poll_interval);
+        let mut replicated_primary = None;
+        loop {
+            tokio::select! {
+                _ = poll_interval.tick() => (),
+                _ = shutdown_receiver.changed() => return,
+            }
+            let Some(primary_address) = replicator.resolve_primary() else {
+                continue;
+            };
+            if replicated_primary.as_ref() != Some(&primary_address) {
+                if let Err(err) = replicator.discard_unresolved_replication() {
+                    event!(Level::WARN, "Replication from '{}' failed: {:?}", primary_address, err);
+                    continue;
+                }
+                replicated_primary = Some(primary_address.clone());
+            }
+            if let Err(err) = replicator.poll(&primary_address).await {
+                event!(Level::WARN, "Replication from '{}' failed: {:?}", primary_address, err);
+            }
+        }
+    });
+}
+
+#[derive(Debug)]
+enum ReplicationSource {
+    Primary(String),
+    ClusterLeader(Arc<Cluster>),
+}
+
+struct Replicator {
+    client: PeerClient,
+    source: ReplicationSource,
+    database_manager: Arc<DatabaseManager>,
+}
+
+impl Replicator {
+    fn new(config: &ReplicationConfig, source: ReplicationSource, database_manager: Arc<DatabaseManager>) -> Self {
+        Self { client: PeerClient::new(config.username.clone(), config.password.clone()), source, database_manager }
+    }
+
+    /// The server to replicate from, which is the current leader for cluster members that are followers.
+    fn resolve_primary(&self) -> Option<String> {
+        match &self.source {
+            ReplicationSource::Primary(address) => Some(address.clone()),
+            ReplicationSource::ClusterLeader(cluster) => match cluster.leader() {
+                Some(leader) if !cluster.is_leader() && leader != cluster.address() => Some(leader),
+                _ => None,
+            },
+        }
+    }
+
+    /// Commits received from a former primary without a status may be missing from the new one's WAL,
+    /// so they are received again from the new primary.
+    fn discard_unresolved_replication(&self) -> Result<(), ReplicationError> {
+        for database in self.database_manager.databases().values() {
+            if DatabaseManager::is_user_database(database.name()) {
+                database
+                    .discard_unresolved_replication()
+                    .map_err(|typedb_source| ReplicationError::DatabaseReplication { typedb_source })?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn poll(&self, primary_address: &str) -> Result<(), ReplicationError> {
+        let databases: DatabasesResponse = self
+            .client
+            .get_json(primary_address, "databases")
+            .await
+            .map_err(|typedb_source| ReplicationError::Primary { typedb_source })?;
+        for database in databases.databases {
+            let database = self.get_or_create_replica(&database.name)?;
+            self.replicate(primary_address, database).await?;
+        }
+        Ok(())
+    }
+
+    fn get_or_create_replica(&self, name: &str) -> Result<Arc<Database<WALClient>>, ReplicationError> {
+        if let Some(database) = self.database_manager.database(name) {
+            if !database.is_replica() {
+                return Err(ReplicationError::DatabaseNotReplica { name: name.to_owned() });
+            }
+            return Ok(database);
+        }
+        let database = self
+            .database_manager
+            .put_replica_database(name)
+            .map_err(|typedb_source| ReplicationError::DatabaseCreate { typedb_source })?;
+        if !database.is_replica() {
+            return Err(ReplicationError::DatabaseNotReplica { name: name.to_owned() });
+        }
+        event!(Level::INFO, "Created replica of database '{}'", name);
+        Ok(database)
+    }
+
+    async fn replicate(
+        &self,
+        primary_address: &str,
+        database: Arc<Database<WALClient>>,
+    ) -> Result<(), ReplicationError> {
+        loop {
+            let mut path = format!(
+                "replication/databases/{}/wal?from={}&limit={}",
+                database.name(),
+                database.replication_position().number(),
+                REPLICATION_BATCH_MAX_RECORDS
+            );
+            if let ReplicationSource::ClusterLeader(cluster) = &self.source {
+                let received = database.replication_received().number();
+                path.push_str(&format!("&member={}&received={received}", cluster.address()));
+            }
+            let response = self
+                .client
+                .get(primary_address, &path)
+                .await
+                .map_err(|typedb_source| ReplicationError::Primary { typedb_source })?;
+            let primary_watermark = response
+                .headers()
+                .get(REPLICATION_WATERMARK_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| ReplicationError::InvalidResponse { details: "missing watermark".to_owned() })?;
+            let bytes = PeerClient::read_body(primary_address, response)
+                .await
+                .map_err(|typedb_source| ReplicationError::Primary { typedb_source })?;
+            let records = decode_replication_records(&bytes)
+                .map_err(|typedb_source| ReplicationError::DatabaseReplication { typedb_source })?;
+            let is_caught_up = match &self.source {
+                // the next request reports the received records to the leader, whose commits wait for them
+                ReplicationSource::ClusterLeader(_) => records.is_empty(),
+                ReplicationSource::Primary(_) => records.len() < REPLICATION_BATCH_MAX_RECORDS,
+            };
+            let replica = database.clone();
+            spawn_blocking(move || replica.apply_replication_records(records, SequenceNumber::new(primary_watermark)))
+                .await
+                .map_err(|_| ReplicationError::InvalidResponse { details: "replication task failed".to_owned() })?
+                .map_err(|typedb_source| ReplicationError::DatabaseReplication { typedb_source })?;
+            if is_caught_up {
+                return Ok(());
+            }
+        }
+    }
+}
+
+typedb_error! {
+    pub(crate) ReplicationError(component = "Replication", prefix = "RPL") {
+        DatabaseReplication(1, "Error replicating database.", typedb_source: DatabaseReplicationError),
+        DatabaseCreate(2, "Error creating a replica of a database.", typedb_source: DatabaseCreateError),
+        DatabaseNotReplica(3, "Database '{name}' exists on the primary, but this server's copy is not a replica.", name: String),
+        Primary(4, "Error communicating with the primary.", typedb_source: PeerError),
+        InvalidResponse(5, "Invalid response from the primary: {details}", details: String),
+    }
+}
@@ -0,0 +1,108 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+use std::sync::Arc;
+
+use concept::{error::ConceptReadError, type_::type_manager::schema_diff::SchemaDiff};
+use database::{
+    database::DatabaseCreateError,
+    database_manager::DatabaseManager,
+    query::execute_schema_query,
+    transaction::{TransactionError, TransactionRead, TransactionSchema},
+    Database, DatabaseDeleteError,
+};
+use error::typedb_error;
+use function::FunctionError;
+use options::TransactionOptions;
+use query::error::QueryError;
+use storage::durability_client::WALClient;
+use typeql::query::{QueryStructure, SchemaQuery};
+
+/// Produces the TypeQL migration converging the schema of `database` onto the `define` query `target_schema`.
+/// The target schema is defined in a scratch database, which is deleted before returning.
+pub(crate) fn get_schema_diff(
+    database_manager: &DatabaseManager,
+    database: Arc<Database<WALClient>>,
+    target_schema: String,
+) -> Result<String, SchemaDiffError> {
+    let parsed = typeql::parse_query(&target_schema)
+        .map_err(|typedb_source| SchemaDiffError::TargetSchemaParseFailed { typedb_source })?;
+    let QueryStructure::Schema(schema_query @ SchemaQuery::Define(_)) = parsed.into_structure() else {
+        return Err(SchemaDiffError::TargetSchemaNotDefine {});
+    };
+
+    let scratch_database = Arc::new(
+        database_manager
+            .prepare_scratch_database()
+            .map_err(|typedb_source| SchemaDiffError::ScratchDatabaseCreate { typedb_source })?,
+    );
+    let diff = diff_against_scratch_database(database, scratch_database.clone(), schema_query, target_schema);
+    let Some(scratch_database) = Arc::into_inner(scratch_database) else {
+        return Err(SchemaDiffError::ScratchDatabaseInUse {});
+    };
+    scratch_database.delete().map_err(|typedb_source| SchemaDiffError::ScratchDatabaseDelete { typedb_source })?;
+    diff
+}
+
+fn diff_against_scratch_database(
+    database: Arc<Database<WALClient>>,
+    scratch_database: Arc<Database<WALClient>>,
+    schema_query: SchemaQuery,
+    target_schema: String,
+) -> Result<String, SchemaDiffError> {
+    let target_transaction = TransactionSchema::open(scratch_database, TransactionOptions::default())
+        .map_err(|typedb_source| SchemaDiffError::TransactionFailed { typedb_source })?;
+    let (target_transaction, result) = execute_schema_query(target_transaction, schema_query, target_schema);
+    if let Err(typedb_source) = result {
+        target_transaction.close();
+        return Err(SchemaDiffError::TargetSchemaDefineFailed { typedb_source });
+    }
+
+    let transaction = match TransactionRead::open(database, TransactionOptions::default()) {
+        Ok(transaction) => transaction,
+        Err(typedb_source) => {
+            target_transaction.close();
+            return Err(SchemaDiffError::TransactionFailed { typedb_source });
+        }
+    };
+    let diff = build_schema_diff(&transaction, &target_transaction);
+    transaction.close();
+    target_transaction.close();
+    Ok(diff?.to_string())
+}
+
+fn build_schema_diff(
+    transaction: &TransactionRead<WALClient>,
+    target_transaction: &TransactionSchema<WALClient>,
+) -> Result<SchemaDiff, SchemaDiffError> {
+    let mut diff = transaction
+        .type_manager
+        .get_schema_diff(transaction.snapshot(), &target_transaction.type_manager, target_transaction.snapshot.as_ref())
+        .map_err(|typedb_source| SchemaDiffError::ConceptRead { typedb_source })?;
+    let functions = transaction
+        .function_manager
+        .get_function_definitions(transaction.snapshot())
+        .map_err(|typedb_source| SchemaDiffError::FunctionRead { typedb_source })?;
+    let target_functions = target_transaction
+        .function_manager
+        .get_function_definitions(target_transaction.snapshot.as_ref())
+        .map_err(|typedb_source| SchemaDiffError::FunctionRead { typedb_source })?;
+    diff.add_function_changes(&functions, &target_functions);
+    Ok(diff)
+}
+
+typedb_error! {
+    pub(crate) SchemaDiffError(component = "Schema diff", prefix = "SDF") {
+        TargetSchemaParseFailed(1, "Target schema parsing failed.", typedb_source: typeql::Error),
+        TargetSchemaNotDefine(2, "The target schema must be a single 'define' query."),
+        ScratchDatabaseCreate(3, "Failed to create a scratch database for the target schema.", typedb_source: DatabaseCreateError),
+        ScratchDatabaseDelete(4, "Failed to delete the scratch database of the target schema.", typedb_source: DatabaseDeleteError),
+        TransactionFailed(5, "Transaction failed.", typedb_source: TransactionError),
+        TargetSchemaDefineFailed(6, "The target schema could not be defined.", typedb_source: Box<QueryError>),
+        ConceptRead(7, "Error reading concepts.", typedb_source: Box<ConceptReadError>),
+        ScratchDatabaseInUse(8, "The scratch database of the target schema is still in use and cannot be deleted."),
+        FunctionRead(9, "Error reading functions.", typedb_source: FunctionError),
+    }
+}
@@ -0,0 +1,205 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+use std::{collections::HashMap, hash::Hash, sync::Arc};
+
+use concept::{
+    error::ConceptReadError,
+    thing::statistics_sampler::SampledType,
+    type_::{type_manager::TypeManager, TypeAPI},
+};
+use database::{
+    transaction::{TransactionError, TransactionRead},
+    Database,
+};
+use error::typedb_error;
+use options::TransactionOptions;
+use storage::{durability_client::WALClient, snapshot::ReadableSnapshot};
+
+/// The planner statistics of a database, by type label. Types that are no longer defined are left out.
+#[derive(Debug, Clone)]
+pub struct DatabaseStatistics {
+    pub sequence_number: u64,
+    pub total_entity_count: u64,
+    pub total_relation_count: u64,
+    pub total_attribute_count: u64,
+    pub total_has_count: u64,
+    pub total_role_count: u64,
+    pub instance_counts: Vec<InstanceCount>,
+    pub has_counts: Vec<HasCount>,
+    pub links_counts: Vec<LinksCount>,
+    pub last_sample: Option<StatisticsSampleSummary>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InstanceCount {
+    pub type_label: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct HasCount {
+    pub owner_label: String,
+    pub attribute_label: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LinksCount {
+    pub relation_label: String,
+    pub role_label: String,
+    pub player_label: String,
+    pub count: u64,
+}
+
+/// The recounts of the most recent background sample, against the counts recorded from commits.
+#[derive(Debug, Clone)]
+pub struct StatisticsSampleSummary {
+    pub sequence_number: u64,
+    pub types: Vec<TypeSampleSummary>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TypeSampleSummary {
+    pub type_label: String,
+    pub recorded_instances: u64,
+    pub counted_instances: u64,
+    pub corrected_edge_counts: usize,
+}
+
+pub(crate) fn get_database_statistics(
+    database: Arc<Database<WALClient>>,
+) -> Result<DatabaseStatistics, StatisticsServiceError> {
+    let statistics = database.statistics();
+    let last_sample = database.last_statistics_sample();
+    let transaction = TransactionRead::open(database, TransactionOptions::default())
+        .map_err(|typedb_source| StatisticsServiceError::TransactionFailed { typedb_source })?;
+    let snapshot = transaction.snapshot();
+    let type_manager = &transaction.type_manager;
+
+    let entity_labels = labels(snapshot, type_manager, type_manager.get_entity_types(snapshot))?;
+    let relation_labels = labels(snapshot, type_manager, type_manager.get_relation_types(snapshot))?;
+    let attribute_labels = labels(snapshot, type_manager, type_manager.get_attribute_types(snapshot))?;
+    let object_labels = labels(snapshot, type_manager, type_manager.get_object_types(snapshot))?;
+    let role_labels = labels(snapshot, type_manager, type_manager.get_role_types(snapshot))?;
+
+    let mut instance_counts = Vec::new();
+    instance_counts.extend(instance_counts_of(&entity_labels, &statistics.entity_counts));
+    instance_counts.extend(instance_counts_of(&relation_labels, &statistics.relation_counts));
+    instance_counts.extend(instance_counts_of(&attribute_labels, &statistics.attribute_counts));
+
+    let mut has_counts = Vec::new();
+    for (owner_type, attribute_counts) in &statistics.has_attribute_counts {
+        let Some(owner_label) = object_labels.get(owner_type) else { continue };
+        for (attribute_type, &count) in attribute_counts {
+            let Some(attribute_label) = attribute_labels.get(attribute_type) else { continue };
+            if count != 0 {
+                has_counts.push(HasCount {
+                    owner_label: owner_label.clone(),
+                    attribute_label: attribute_label.clone(),
+                    count,
+                });
+            }
+        }
+    }
+    has_counts
+        .sort_by(|lhs, rhs| (&lhs.owner_label, &lhs.attribute_label).cmp(&(&rhs.owner_label, &rhs.attribute_label)));
+
+    let mut links_counts = Vec::new();
+    for (relation_type, role_player_counts) in &statistics.relation_role_player_counts {
+        let Some(relation_label) = relation_labels.get(relation_type) else { continue };
+        for (role_type, player_counts) in role_player_counts {
+            let Some(role_label) = role_labels.get(role_type) else { continue };
+            for (player_type, &count) in player_counts {
+                let Some(player_label) = object_labels.get(player_type) else { continue };
+                if count != 0 {
+                    links_counts.push(LinksCount {
+                        relation_label: relation_label.clone(),
+                        role_label: role_label.clone(),
+                        player_label: player_label.clone(),
+                        count,
+                    });
+                }
+            }
+        }
+    }
+    links_counts.sort_by(|lhs, rhs| {
+        (&lhs.relation_label, &lhs.role_label, &lhs.player_label).cmp(&(
+            &rhs.relation_label,
+            &rhs.role_label,
+            &rhs.player_label,
+        ))
+    });
+
+    let last_sample = last_sample.map(|sample| StatisticsSampleSummary {
+        sequence_number: sample.sequence_number.number(),
+        types: sample
+            .types
+            .iter()
+            .filter_map(|type_sample| {
+                let type_label = match type_sample.type_ {
+                    SampledType::Entity(entity_type) => entity_labels.get(&entity_type),
+                    SampledType::Relation(relation_type) => relation_labels.get(&relation_type),
+                    SampledType::Attribute(attribute_type) => attribute_labels.get(&attribute_type),
+                }?;
+                Some(TypeSampleSummary {
+                    type_label: type_label.clone(),
+                    recorded_instances: type_sample.recorded_instances,
+                    counted_instances: type_sample.counted_instances,
+                    corrected_edge_counts: type_sample.corrected_edge_counts,
+                })
+            })
+            .collect(),
+    });
+    transaction.close();
+
+    Ok(DatabaseStatistics {
+        sequence_number: statistics.sequence_number.number(),
+        total_entity_count: statistics.total_entity_count,
+        total_relation_count: statistics.total_relation_count,
+        total_attribute_count: statistics.total_attribute_count,
+        total_has_count: statistics.total_has_count,
+        total_role_count: statistics.total_role_count,
+        instance_counts,
+        has_counts,
+        links_counts,
+        last_sample,
+    })
+}
+
+fn labels<T: TypeAPI>(
+    snapshot: &impl ReadableSnapshot,
+    type_manager: &TypeManager,
+    types: Result<Vec<T>, Box<ConceptReadError>>,
+) -> Result<HashMap<T, String>, StatisticsServiceError> {
+    let read_error = |typedb_source| StatisticsServiceError::ConceptRead { typedb_source };
+    types
+        .map_err(read_error)?
+        .into_iter()
+        .map(|type_| {
+            let label = type_.get_label(snapshot, type_manager).map_err(read_error)?;
+            Ok((type_, label.scoped_name().as_str().to_owned()))
+        })
+        .collect()
+}
+
+fn instance_counts_of<T: Hash + Eq>(labels: &HashMap<T, String>, counts: &HashMap<T, u64>) -> Vec<InstanceCount> {
+    let mut instance_counts: Vec<_> = labels
+        .iter()
+        .map(|(type_, label)| InstanceCount {
+            type_label: label.clone(),
+            count: counts.get(type_).copied().unwrap_or(0),
+        })
+        .collect();
+    instance_counts.sort_by(|lhs, rhs| lhs.type_label.cmp(&rhs.type_label));
+    instance_counts
+}
+
+typedb_error! {
+    pub(crate) StatisticsServiceError(component = "Statistics service", prefix = "DST") {
+        TransactionFailed(1, "Transaction failed.", typedb_source: TransactionError),
+        ConceptRead(2, "Error reading concepts.", typedb_source: Box<ConceptReadError>),
+    }
+}
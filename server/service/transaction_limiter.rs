@@ -0,0 +1,86 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// Counts the transactions each user has open, over the gRPC and HTTP endpoints together.
+/// A transaction holds a permit for as long as it is open, which releases its slot when dropped.
+#[derive(Debug)]
+pub(crate) struct TransactionLimiter {
+    limit: Option<usize>,
+    open_transactions: Mutex<HashMap<String, usize>>,
+}
+
+impl TransactionLimiter {
+    pub(crate) fn new(limit: Option<usize>) -> Self {
+        Self { limit, open_transactions: Mutex::new(HashMap::new()) }
+    }
+
+    pub(crate) fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Reserves a transaction slot for the owner, or returns None if they already have the maximum open.
+    pub(crate) fn acquire(self: &Arc<Self>, owner: &str) -> Option<TransactionPermit> {
+        let mut open_transactions = self.open_transactions.lock().unwrap();
+        let open_count = open_transactions.entry(owner.to_owned()).or_default();
+        if self.limit.is_some_and(|limit| *open_count >= limit) {
+            return None;
+        }
+        *open_count += 1;
+        Some(TransactionPermit { limiter: self.clone(), owner: owner.to_owned() })
+    }
+
+    fn release(&self, owner: &str) {
+        let mut open_transactions = self.open_transactions.lock().unwrap();
+        if let Some(open_count) = open_transactions.get_mut(owner) {
+            *open_count -= 1;
+            if *open_count == 0 {
+                open_transactions.remove(owner);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TransactionPermit {
+    limiter: Arc<TransactionLimiter>,
+    owner: String,
+}
+
+impl Drop for TransactionPermit {
+    fn drop(&mut self) {
+        self.limiter.release(&self.owner);
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use super::TransactionLimiter;
+
+    #[test]
+    fn permits_are_limited_per_owner_and_released_on_drop() {
+        let limiter = Arc::new(TransactionLimiter::new(Some(2)));
+        let first = limiter.acquire("alice").unwrap();
+        let _second = limiter.acquire("alice").unwrap();
+        assert!(limiter.acquire("alice").is_none());
+        assert!(limiter.acquire("bob").is_some());
+
+        drop(first);
+        assert!(limiter.acquire("alice").is_some());
+    }
+
+    #[test]
+    fn unlimited_limiter_always_permits() {
+        let limiter = Arc::new(TransactionLimiter::new(None));
+        let permits: Vec<_> = (0..100).map(|_| limiter.acquire("alice").unwrap()).collect();
+        assert_eq!(permits.len(), 100);
+    }
+}
@@ -0,0 +1,315 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+use std::{
+    collections::HashMap,
+    future,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use futures::future::join_all;
+use tokio::{
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        oneshot,
+    },
+    time::{timeout, Instant},
+};
+use uuid::Uuid;
+
+use crate::service::{
+    http::transaction_service::{TransactionRequest, TransactionResponder},
+    transaction_limiter::{TransactionLimiter, TransactionPermit},
+    TransactionType,
+};
+
+const TRANSACTION_CONTROL_BUFFER_SIZE: usize = 4;
+
+pub(crate) type HttpTransactionRequestSender = Sender<(TransactionRequest, TransactionResponder)>;
+
+/// Requests that every transaction service answers between queries, whichever endpoint opened its transaction
+#[derive(Debug)]
+pub(crate) enum TransactionControl {
+    StagedWrites(oneshot::Sender<Option<usize>>),
+    ForceClose(oneshot::Sender<()>),
+}
+
+/// How the endpoint that opened a transaction reaches it. gRPC transactions are reached through their own stream.
+#[derive(Clone, Debug)]
+pub(crate) enum TransactionEndpoint {
+    Grpc,
+    Http(HttpTransactionRequestSender),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct RegisteredTransaction {
+    pub(crate) owner: String,
+    pub(crate) database_name: String,
+    pub(crate) transaction_type: TransactionType,
+    pub(crate) opened_at: Instant,
+    pub(crate) transaction_timeout_millis: u64,
+    pub(crate) snapshot_sequence_number: Option<u64>,
+    pub(crate) endpoint: TransactionEndpoint,
+    control_sender: Sender<TransactionControl>,
+}
+
+#[derive(Debug)]
+pub(crate) struct TransactionSummary {
+    pub(crate) transaction_id: Uuid,
+    pub(crate) owner: String,
+    pub(crate) database_name: String,
+    pub(crate) transaction_type: TransactionType,
+    pub(crate) age: Duration,
+    pub(crate) staged_writes: Option<usize>,
+}
+
+/// The transactions open over the gRPC and HTTP endpoints together, so that they are listed, limited and
+/// force-closed the same way whichever endpoint opened them.
+/// A transaction is registered once it is open, and deregistered when its service drops the registration.
+#[derive(Debug)]
+pub(crate) struct TransactionRegistry {
+    limiter: Arc<TransactionLimiter>,
+    transactions: RwLock<HashMap<Uuid, RegisteredTransaction>>,
+}
+
+impl TransactionRegistry {
+    pub(crate) const SUMMARY_TIMEOUT: Duration = Duration::from_millis(100);
+
+    pub(crate) fn new(limiter: Arc<TransactionLimiter>) -> Self {
+        Self { limiter, transactions: RwLock::new(HashMap::new()) }
+    }
+
+    pub(crate) fn limit(&self) -> Option<usize> {
+        self.limiter.limit()
+    }
+
+    /// Reserves one of the owner's transaction slots, or returns None if they already have the maximum open.
+    pub(crate) fn reserve(self: &Arc<Self>, owner: &str) -> Option<TransactionReservation> {
+        let permit = self.limiter.acquire(owner)?;
+        Some(TransactionReservation { registry: self.clone(), owner: owner.to_owned(), permit })
+    }
+
+    pub(crate) fn get(&self, transaction_id: &Uuid) -> Option<RegisteredTransaction> {
+        self.transactions.read().unwrap().get(transaction_id).cloned()
+    }
+
+    /// Probes all transactions at once, so that busy transactions delay the listing by one timeout rather than each
+    pub(crate) async fn summaries(&self) -> Vec<TransactionSummary> {
+        // release the registry before contacting the transactions, which must not block opening or closing others
+        let transactions: Vec<(Uuid, RegisteredTransaction)> =
+            self.transactions.read().unwrap().iter().map(|(id, transaction)| (*id, transaction.clone())).collect();
+        join_all(transactions.into_iter().map(|(transaction_id, transaction)| async move {
+            let staged_writes = Self::staged_writes(&transaction).await;
+            TransactionSummary {
+                transaction_id,
+                owner: transaction.owner,
+                database_name: transaction.database_name,
+                transaction_type: transaction.transaction_type,
+                age: transaction.opened_at.elapsed(),
+                staged_writes,
+            }
+        }))
+        .await
+    }
+
+    /// Transactions answer between queries, so a busy or closing transaction reports no size rather than blocking
+    async fn staged_writes(transaction: &RegisteredTransaction) -> Option<usize> {
+        let (sender, receiver) = oneshot::channel();
+        transaction.control_sender.try_send(TransactionControl::StagedWrites(sender)).ok()?;
+        timeout(Self::SUMMARY_TIMEOUT, receiver).await.ok()?.ok()?
+    }
+
+    /// Closes the transaction whoever owns it, once its running query finishes.
+    /// Returns false if the transaction is not open.
+    pub(crate) async fn force_close(&self, transaction_id: &Uuid) -> bool {
+        let Some(transaction) = self.get(transaction_id) else {
+            return false;
+        };
+        let (sender, receiver) = oneshot::channel();
+        if transaction.control_sender.send(TransactionControl::ForceClose(sender)).await.is_err() {
+            return false;
+        }
+        // a service that stops before answering has closed the transaction all the same
+        let _ = timeout(Duration::from_millis(transaction.transaction_timeout_millis), receiver).await;
+        true
+    }
+
+    fn deregister(&self, transaction_id: &Uuid) {
+        self.transactions.write().unwrap().remove(transaction_id);
+    }
+}
+
+/// A transaction slot reserved for an owner before the transaction opens
+#[derive(Debug)]
+pub(crate) struct TransactionReservation {
+    registry: Arc<TransactionRegistry>,
+    owner: String,
+    permit: TransactionPermit,
+}
+
+impl TransactionReservation {
+    pub(crate) fn register(
+        self,
+        database_name: String,
+        transaction_type: TransactionType,
+        transaction_timeout_millis: u64,
+        snapshot_sequence_number: Option<u64>,
+        endpoint: TransactionEndpoint,
+    ) -> TransactionRegistration {
+        let Self { registry, owner, permit } = self;
+        let transaction_id = Uuid::new_v4();
+        let (control_sender, control_receiver) = channel(TRANSACTION_CONTROL_BUFFER_SIZE);
+        let transaction = RegisteredTransaction {
+            owner,
+            database_name,
+            transaction_type,
+            opened_at: Instant::now(),
+            transaction_timeout_millis,
+            snapshot_sequence_number,
+            endpoint,
+            control_sender,
+        };
+        registry.transactions.write().unwrap().insert(transaction_id, transaction);
+        TransactionRegistration { registry, transaction_id, control_receiver, _permit: permit }
+    }
+}
+
+/// Keeps an open transaction registered, and its owner's slot taken, until it is dropped
+#[derive(Debug)]
+pub(crate) struct TransactionRegistration {
+    registry: Arc<TransactionRegistry>,
+    transaction_id: Uuid,
+    control_receiver: Receiver<TransactionControl>,
+    _permit: TransactionPermit,
+}
+
+impl TransactionRegistration {
+    pub(crate) fn transaction_id(&self) -> Uuid {
+        self.transaction_id
+    }
+}
+
+impl Drop for TransactionRegistration {
+    fn drop(&mut self) {
+        self.registry.deregister(&self.transaction_id);
+    }
+}
+
+/// Waits for the next control request to the transaction, or forever while it is not registered
+pub(crate) async fn next_control(registration: &mut Option<TransactionRegistration>) -> TransactionControl {
+    match registration {
+        // the registry keeps a sender for as long as the transaction is registered
+        Some(registration) => match registration.control_receiver.recv().await {
+            Some(control) => control,
+            None => future::pending().await,
+        },
+        None => future::pending().await,
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+
+    use tokio::time::Instant;
+
+    use super::{next_control, TransactionControl, TransactionEndpoint, TransactionRegistry};
+    use crate::service::{transaction_limiter::TransactionLimiter, TransactionType};
+
+    fn registry(limit: Option<usize>) -> Arc<TransactionRegistry> {
+        Arc::new(TransactionRegistry::new(Arc::new(TransactionLimiter::new(limit))))
+    }
+
+    #[tokio::test]
+    async fn summaries_probe_transactions_concurrently() {
+        let registry = registry(None);
+        // busy transactions are registered but never answer
+        let mut busy_registrations = Vec::new();
+        for _ in 0..10 {
+            let reservation = registry.reserve("admin").unwrap();
+            busy_registrations.push(reservation.register(
+                "typedb".to_owned(),
+                TransactionType::Write,
+                1000,
+                None,
+                TransactionEndpoint::Grpc,
+            ));
+        }
+        let mut idle_registration = Some(registry.reserve("admin").unwrap().register(
+            "typedb".to_owned(),
+            TransactionType::Write,
+            1000,
+            None,
+            TransactionEndpoint::Grpc,
+        ));
+        let idle_transaction_id = idle_registration.as_ref().unwrap().transaction_id();
+        tokio::spawn(async move {
+            loop {
+                match next_control(&mut idle_registration).await {
+                    TransactionControl::StagedWrites(responder) => {
+                        let _ = responder.send(Some(3));
+                    }
+                    TransactionControl::ForceClose(_) => unreachable!(),
+                }
+            }
+        });
+
+        let start = Instant::now();
+        let summaries = registry.summaries().await;
+        assert!(start.elapsed() < TransactionRegistry::SUMMARY_TIMEOUT * 5);
+        assert_eq!(summaries.len(), 11);
+        for summary in summaries {
+            match summary.transaction_id == idle_transaction_id {
+                true => assert_eq!(summary.staged_writes, Some(3)),
+                false => assert_eq!(summary.staged_writes, None),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn dropped_registrations_are_deregistered_and_release_their_slot() {
+        let registry = registry(Some(1));
+        let registration = registry.reserve("alice").unwrap().register(
+            "typedb".to_owned(),
+            TransactionType::Read,
+            1000,
+            None,
+            TransactionEndpoint::Grpc,
+        );
+        let transaction_id = registration.transaction_id();
+        assert!(registry.reserve("alice").is_none());
+        assert!(registry.get(&transaction_id).is_some());
+
+        drop(registration);
+        assert!(registry.get(&transaction_id).is_none());
+        assert!(!registry.force_close(&transaction_id).await);
+        assert!(registry.reserve("alice").is_some());
+    }
+
+    #[tokio::test]
+    async fn force_close_reaches_the_transaction_service() {
+        let registry = registry(None);
+        let mut registration = Some(registry.reserve("alice").unwrap().register(
+            "typedb".to_owned(),
+            TransactionType::Write,
+            1000,
+            None,
+            TransactionEndpoint::Grpc,
+        ));
+        let transaction_id = registration.as_ref().unwrap().transaction_id();
+        let service = tokio::spawn(async move {
+            let TransactionControl::ForceClose(responder) = next_control(&mut registration).await else {
+                unreachable!()
+            };
+            drop(registration);
+            let _ = responder.send(());
+        });
+
+        assert!(registry.force_close(&transaction_id).await);
+        service.await.unwrap();
+        assert!(registry.get(&transaction_id).is_none());
+    }
+}
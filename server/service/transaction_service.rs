@@ -6,19 +6,46 @@
 
 use std::time::Duration;
 
-use database::transaction::{
-    DataCommitError, SchemaCommitError, TransactionError, TransactionRead, TransactionSchema, TransactionWrite,
+use compiler::VariablePosition;
+use concept::{
+    error::ConceptReadError,
+    thing::thing_manager::ThingManager,
+    type_::type_manager::{schema_diff::SchemaDiff, TypeManager},
+};
+use database::{
+    cluster::ClusterError,
+    coordinator::MultiDatabaseCommitError,
+    query::{execute_schema_query, execute_write_query_in_schema, execute_write_query_in_write, WriteQueryResult},
+    transaction::{
+        DataCommitError, SchemaCommitError, SchemaDryRunError, TransactionError, TransactionRead, TransactionSchema,
+        TransactionWrite,
+    },
 };
 use diagnostics::metrics::LoadKind;
 use error::typedb_error;
-use executor::{pipeline::PipelineExecutionError, InterruptType};
+use executor::{
+    document::ConceptDocument, pipeline::PipelineExecutionError, row::MaybeOwnedRow, ExecutionInterrupt, InterruptType,
+};
+use ir::pipeline::ParameterRegistry;
+use options::QueryOptions;
 use query::error::QueryError;
-use resource::constants::server::DEFAULT_TRANSACTION_TIMEOUT_MILLIS;
-use storage::durability_client::WALClient;
-use tokio::time::Instant;
-use typeql::query::stage::Stage;
+use resource::{constants::server::DEFAULT_TRANSACTION_TIMEOUT_MILLIS, profile::StorageCounters};
+use storage::{
+    durability_client::WALClient,
+    snapshot::{ReadableSnapshot, WritableSnapshot},
+};
+use tokio::{
+    task::{spawn_blocking, JoinHandle},
+    time::Instant,
+};
+use typeql::{
+    parse_query,
+    query::{stage::Stage, Pipeline, QueryStructure, SchemaQuery},
+};
 use uuid::Uuid;
 
+use crate::service::IncludeInvolvedBlocks;
+
 pub(crate) const TRANSACTION_REQUEST_BUFFER_SIZE: usize = 10;
 
 #[derive(Debug)]
@@ -40,6 +67,13 @@ macro_rules! with_readable_transaction {
 pub(crate) use with_readable_transaction;
 
 impl Transaction {
+    /// Lets read committed transactions observe the latest commit before running their next query
+    pub fn advance_read_snapshot(&mut self) {
+        if let Transaction::Read(transaction) = self {
+            transaction.advance_snapshot();
+        }
+    }
+
     pub fn load_kind(&self) -> LoadKind {
         match self {
             Transaction::Read(_) => LoadKind::ReadTransactions,
@@ -51,6 +85,179 @@ impl Transaction {
     pub fn database_name(&self) -> &str {
         with_readable_transaction!(self, |transaction| { transaction.database.name() })
     }
+
+    /// The number of writes buffered for the commit, which read transactions never have
+    pub fn staged_writes(&self) -> Option<usize> {
+        match self {
+            Transaction::Read(_) => None,
+            Transaction::Write(transaction) => Some(transaction.snapshot.operations().len()),
+            Transaction::Schema(transaction) => Some(transaction.snapshot.operations().len()),
+        }
+    }
+}
+
+/// What a transaction service does with a query it has parsed, independent of the protocol it arrived on
+pub(crate) enum DispatchedQuery {
+    Schema(SchemaQuery),
+    Write(Pipeline),
+    Read(Pipeline),
+}
+
+pub(crate) fn dispatch_query(
+    query: &str,
+    query_options: &QueryOptions,
+) -> Result<DispatchedQuery, TransactionServiceError> {
+    let parsed =
+        parse_query(query).map_err(|typedb_source| TransactionServiceError::QueryParseFailed { typedb_source })?;
+    match parsed.into_structure() {
+        QueryStructure::Schema(schema_query) => Ok(DispatchedQuery::Schema(schema_query)),
+        QueryStructure::Pipeline(_) if query_options.dry_run => {
+            Err(TransactionServiceError::DryRunRequiresSchemaQuery {})
+        }
+        QueryStructure::Pipeline(pipeline) if is_write_pipeline(&pipeline) => Ok(DispatchedQuery::Write(pipeline)),
+        QueryStructure::Pipeline(pipeline) => Ok(DispatchedQuery::Read(pipeline)),
+    }
+}
+
+pub(crate) fn dispatch_analyse_query(query: &str) -> Result<Pipeline, TransactionServiceError> {
+    let parsed =
+        parse_query(query).map_err(|typedb_source| TransactionServiceError::QueryParseFailed { typedb_source })?;
+    match parsed.into_structure() {
+        QueryStructure::Pipeline(pipeline) => Ok(pipeline),
+        QueryStructure::Schema(_) => Err(TransactionServiceError::AnalyseQueryExpectsPipeline {}),
+    }
+}
+
+pub(crate) fn validate_query_options(query_options: &QueryOptions) -> Result<(), TransactionServiceError> {
+    if query_options.prefetch_size < 1 {
+        return Err(TransactionServiceError::InvalidPrefetchSize { value: query_options.prefetch_size });
+    }
+    if query_options.answer_batch_size < 1 {
+        return Err(TransactionServiceError::InvalidAnswerBatchSize { value: query_options.answer_batch_size });
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum QueueOptions {
+    Query(QueryOptions),
+    Analyze,
+}
+
+impl QueueOptions {
+    pub(crate) fn is_query(&self) -> bool {
+        matches!(self, QueueOptions::Query(_))
+    }
+}
+
+/// A query waiting behind a running or queued write query, with whatever the protocol responds to it through
+pub(crate) type QueuedQuery<Responder> = (Responder, QueueOptions, Pipeline, String);
+
+pub(crate) enum SchemaQueryOutcome {
+    Done,
+    // the query was only validated, and would make these changes
+    DryRun(SchemaDiff),
+    // the transaction is left open and usable
+    Rejected(TransactionServiceError),
+    // the transaction must be aborted
+    Failed(TransactionServiceError),
+}
+
+pub(crate) async fn execute_schema_query_in_transaction(
+    transaction: &mut Option<Transaction>,
+    query: SchemaQuery,
+    source_query: String,
+    dry_run: bool,
+) -> SchemaQueryOutcome {
+    match transaction.take() {
+        Some(Transaction::Schema(schema_transaction)) if dry_run => {
+            // a dry run leaves the transaction unchanged, so its failures do not abort it
+            let (schema_transaction, result) = spawn_blocking(move || {
+                let result = schema_transaction.dry_run_schema_query(query, &source_query);
+                (schema_transaction, result)
+            })
+            .await
+            .expect("Expected schema query dry run finishing");
+            *transaction = Some(Transaction::Schema(schema_transaction));
+            match result {
+                Ok(diff) => SchemaQueryOutcome::DryRun(diff),
+                Err(typedb_source) => {
+                    SchemaQueryOutcome::Rejected(TransactionServiceError::SchemaQueryDryRunFailed { typedb_source })
+                }
+            }
+        }
+        Some(Transaction::Schema(schema_transaction)) => {
+            let (schema_transaction, result) =
+                spawn_blocking(move || execute_schema_query(schema_transaction, query, source_query))
+                    .await
+                    .expect("Expected schema query execution finishing");
+            *transaction = Some(Transaction::Schema(schema_transaction));
+            match result {
+                Ok(_) => SchemaQueryOutcome::Done,
+                Err(err) => SchemaQueryOutcome::Failed(TransactionServiceError::TxnAbortSchemaQueryFailed {
+                    typedb_source: *err,
+                }),
+            }
+        }
+        other => {
+            *transaction = other;
+            SchemaQueryOutcome::Rejected(TransactionServiceError::SchemaQueryRequiresSchemaTransaction {})
+        }
+    }
+}
+
+pub(crate) fn spawn_blocking_execute_write_query(
+    transaction: &mut Option<Transaction>,
+    query_options: QueryOptions,
+    pipeline: Pipeline,
+    source_query: String,
+    interrupt: ExecutionInterrupt,
+) -> Result<JoinHandle<(Transaction, WriteQueryResult)>, TransactionServiceError> {
+    match transaction.take() {
+        Some(Transaction::Schema(schema_transaction)) => Ok(spawn_blocking(move || {
+            let (transaction, result) =
+                execute_write_query_in_schema(schema_transaction, query_options, pipeline, source_query, interrupt);
+            (Transaction::Schema(transaction), result)
+        })),
+        Some(Transaction::Write(write_transaction)) => Ok(spawn_blocking(move || {
+            let (transaction, result) =
+                execute_write_query_in_write(write_transaction, query_options, pipeline, source_query, interrupt);
+            (Transaction::Write(transaction), result)
+        })),
+        Some(Transaction::Read(read_transaction)) => {
+            *transaction = Some(Transaction::Read(read_transaction));
+            Err(TransactionServiceError::WriteQueryRequiresSchemaOrWriteTransaction {})
+        }
+        None => Err(TransactionServiceError::NoOpenTransaction {}),
+    }
+}
+
+/// Encodes query answers for one protocol. The transaction services iterate the answers the same way
+/// and differ only in the encoder, which holds the per-query encoding options and label cache.
+pub(crate) trait AnswerEncoder {
+    type Row;
+    type Document;
+
+    fn encode_row(
+        &mut self,
+        row: MaybeOwnedRow<'_>,
+        columns: &[(String, VariablePosition)],
+        snapshot: &impl ReadableSnapshot,
+        type_manager: &TypeManager,
+        thing_manager: &ThingManager,
+        include_involved_blocks: &IncludeInvolvedBlocks,
+        storage_counters: StorageCounters,
+    ) -> Result<Self::Row, Box<ConceptReadError>>;
+
+    fn encode_document(
+        &mut self,
+        document: ConceptDocument,
+        snapshot: &impl ReadableSnapshot,
+        type_manager: &TypeManager,
+        thing_manager: &ThingManager,
+        parameters: &ParameterRegistry,
+        storage_counters: StorageCounters,
+    ) -> Result<Self::Document, Box<ConceptReadError>>;
 }
 
 pub(crate) fn is_write_pipeline(pipeline: &typeql::query::Pipeline) -> bool {
@@ -96,5 +303,15 @@ typedb_error! {
         InvalidPrefetchSize(18, "Invalid query option: prefetch size should be >= 1, got {value} instead.", value: usize),
         AnalyseQueryExpectsPipeline(19, "Query analyse received a schema query.Only query pipeline can be analysed."),
         AnalyseQueryFailed(20, "Analysing the query failed.", typedb_source: QueryError),
+        Cluster(21, "Cluster error.", typedb_source: ClusterError),
+        InvalidAnswerBatchSize(22, "Invalid query option: answer batch size should be >= 1, got {value} instead.", value: usize),
+        SchemaQueryDryRunFailed(23, "Schema query dry run failed.", typedb_source: SchemaDryRunError),
+        DryRunRequiresSchemaQuery(24, "Invalid query option: only schema queries can be dry run."),
+        TransactionForceClosed(25, "The transaction was closed by an administrator."),
+        DatabaseNotInTransaction(26, "Database '{name}' is not part of the transaction.", name: String),
+        DatabaseListedTwice(27, "Database '{name}' is listed more than once.", name: String),
+        QueryDatabaseNotNamed(28, "Queries in a multi-database transaction must name the database they run against."),
+        MultiDatabaseAnalyseNotSupported(29, "Queries cannot be analysed in a multi-database transaction."),
+        MultiDatabaseCommitFailed(30, "Multi-database transaction commit failed.", typedb_source: MultiDatabaseCommitError),
     }
 }
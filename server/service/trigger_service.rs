@@ -0,0 +1,409 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+use std::{
+    iter,
+    sync::{
+        mpsc::{sync_channel, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use bytes::util::HexBytesFormatter;
+use concept::{
+    error::ConceptReadError,
+    thing::ThingAPI,
+    type_::{CommitTrigger, CommitTriggers, TriggerFailurePolicy, TypeAPI},
+};
+use database::{
+    query::execute_write_query_in_write,
+    transaction::{
+        DataCommitError, SchemaCommitError, TransactionError, TransactionRead, TransactionSchema, TransactionWrite,
+    },
+    Database,
+};
+use encoding::value::label::Label;
+use error::typedb_error;
+use executor::ExecutionInterrupt;
+use function::function_manager::FunctionManager;
+use ir::pipeline::FunctionReadError;
+use itertools::Itertools;
+use options::{QueryOptions, TransactionOptions};
+use query::error::QueryError;
+use resource::profile::TransactionProfile;
+use storage::{durability_client::WALClient, sequence_number::SequenceNumber, snapshot::ReadableSnapshot};
+use tracing::{event, Level};
+use typeql::{query::QueryStructure, schema::definable::function::Output};
+
+const CHANGED_VARIABLE: &str = "$changed";
+const RETRY_BACKOFF: Duration = Duration::from_millis(100);
+const RETRY_BACKOFF_MAX: Duration = Duration::from_secs(10);
+
+type Activations = Vec<(CommitTrigger, Vec<String>)>;
+
+/// Runs the commit triggers activated by committed transactions on a fixed number of worker threads.
+/// Commits never wait for their triggers: activations arriving while the queue is full are dropped and logged.
+///
+/// Activations are only held in memory. Those queued, running or waiting to be retried when the server stops
+/// or crashes are lost, so a trigger may not run for some commits.
+#[derive(Debug)]
+pub(crate) struct TriggerRunner {
+    sender: SyncSender<(Arc<Database<WALClient>>, Activations)>,
+}
+
+impl TriggerRunner {
+    pub(crate) fn new(workers: usize, queue_capacity: usize) -> Self {
+        let (sender, receiver) = sync_channel::<(Arc<Database<WALClient>>, Activations)>(queue_capacity);
+        let receiver = Arc::new(Mutex::new(receiver));
+        for worker in 0..workers {
+            let receiver = receiver.clone();
+            thread::Builder::new()
+                .name(format!("commit-trigger-{worker}"))
+                .spawn(move || loop {
+                    // the workers stop once the runner, and with it the sender, is dropped
+                    let Ok((database, activations)) = receiver.lock().unwrap().recv() else { break };
+                    run_activations(database, activations);
+                })
+                .expect("Expected commit trigger worker thread to start");
+        }
+        Self { sender }
+    }
+
+    fn submit(&self, database: Arc<Database<WALClient>>, activations: Activations) {
+        if let Err(TrySendError::Full((database, activations))) = self.sender.try_send((database, activations)) {
+            let names = activations.iter().map(|(trigger, _)| trigger.name.as_str()).collect::<Vec<_>>();
+            event!(
+                Level::WARN,
+                "Dropped commit triggers {:?} in database '{}', since too many trigger runs are queued",
+                names,
+                database.name()
+            );
+        }
+    }
+}
+
+/// Commits a write transaction, then queues the commit triggers activated by its inserts to run in follow-up
+/// transactions. Trigger failures are handled by each trigger's failure policy, and never fail the commit itself.
+pub(crate) fn commit_with_triggers(
+    transaction: TransactionWrite<WALClient>,
+    trigger_runner: &TriggerRunner,
+) -> (TransactionProfile, Result<Option<SequenceNumber>, DataCommitError>) {
+    let database = transaction.database.database().clone();
+    let activations = match collect_activations(&transaction) {
+        Ok(activations) => activations,
+        Err(err) => {
+            event!(Level::WARN, "Failed to collect commit triggers in database '{}': {:?}", database.name(), err);
+            Vec::new()
+        }
+    };
+    let (profile, result) = transaction.commit();
+    if result.is_ok() && !activations.is_empty() {
+        trigger_runner.submit(database, activations);
+    }
+    (profile, result)
+}
+
+/// Pairs each trigger with the IIDs of the instances the transaction inserted of its type or subtypes.
+fn collect_activations(transaction: &TransactionWrite<WALClient>) -> Result<Activations, Box<ConceptReadError>> {
+    let snapshot = transaction.snapshot.as_ref();
+    let type_manager = &transaction.type_manager;
+    let CommitTriggers(triggers) = type_manager.get_commit_triggers(snapshot)?;
+    let mut activations = Vec::new();
+    for trigger in triggers {
+        let Some(object_type) = type_manager.get_object_type(snapshot, &Label::build(&trigger.type_label, None))?
+        else {
+            continue;
+        };
+        let subtypes = object_type.get_subtypes_transitive(snapshot, type_manager)?;
+        let iids: Vec<String> = iter::once(object_type)
+            .chain(subtypes.iter().copied())
+            .flat_map(|type_| transaction.thing_manager.get_objects_inserted(snapshot, type_))
+            .map(|object| HexBytesFormatter::borrowed(&object.iid()).format_iid())
+            .collect();
+        if !iids.is_empty() {
+            activations.push((trigger, iids));
+        }
+    }
+    Ok(activations)
+}
+
+fn run_activations(database: Arc<Database<WALClient>>, activations: Activations) {
+    for (trigger, iids) in activations {
+        let reruns = match trigger.failure_policy {
+            TriggerFailurePolicy::Log => 0,
+            TriggerFailurePolicy::Retry { attempts } => attempts,
+        };
+        let mut result = run_trigger(database.clone(), &trigger, &iids);
+        for attempt in 0..reruns {
+            if result.is_ok() {
+                break;
+            }
+            thread::sleep(retry_backoff(attempt));
+            result = run_trigger(database.clone(), &trigger, &iids);
+        }
+        if let Err(err) = result {
+            event!(
+                Level::WARN,
+                "Commit trigger '{}' in database '{}' failed: {:?}",
+                trigger.name,
+                database.name(),
+                err
+            );
+        }
+    }
+}
+
+/// Doubles the wait before each rerun of a failing trigger, so that a transient failure, such as a conflict with
+/// the transactions that activated it, has time to clear.
+fn retry_backoff(attempt: u32) -> Duration {
+    RETRY_BACKOFF.saturating_mul(1 << attempt.min(16)).min(RETRY_BACKOFF_MAX)
+}
+
+/// Calls the trigger's function once per inserted instance, all in one write transaction.
+/// The follow-up commit does not activate triggers itself, so triggers cannot cascade.
+fn run_trigger(
+    database: Arc<Database<WALClient>>,
+    trigger: &CommitTrigger,
+    iids: &[String],
+) -> Result<(), CommitTriggerError> {
+    let mut transaction = TransactionWrite::open(database, TransactionOptions::default())
+        .map_err(|typedb_source| CommitTriggerError::TransactionFailed { typedb_source })?;
+    // the function is looked up again in each run, so that it may be redefined after the trigger is set
+    let call = match function_call(&transaction.function_manager, transaction.snapshot.as_ref(), &trigger.function) {
+        Ok(call) => call,
+        Err(err) => {
+            transaction.close();
+            return Err(err);
+        }
+    };
+    for iid in iids {
+        let query = format!("match {CHANGED_VARIABLE} iid {iid};\n{call}");
+        let pipeline = match parse_pipeline(&query) {
+            Ok(pipeline) => pipeline,
+            Err(err) => {
+                transaction.close();
+                return Err(err);
+            }
+        };
+        let (returned, result) = execute_write_query_in_write(
+            transaction,
+            QueryOptions::default_grpc(),
+            pipeline,
+            query,
+            ExecutionInterrupt::new_uninterruptible(),
+        );
+        transaction = returned;
+        if let Err(typedb_source) = result {
+            transaction.close();
+            return Err(CommitTriggerError::QueryFailed { typedb_source });
+        }
+    }
+    transaction.commit().1.map(|_| ()).map_err(|typedb_source| CommitTriggerError::DataCommitFailed { typedb_source })
+}
+
+/// The statement calling the named schema function with `$changed`, which must be its only argument.
+fn function_call(
+    function_manager: &FunctionManager,
+    snapshot: &impl ReadableSnapshot,
+    name: &str,
+) -> Result<String, CommitTriggerError> {
+    let function_key = function_manager
+        .get_function_key(snapshot, name)
+        .map_err(|typedb_source| CommitTriggerError::FunctionRead { typedb_source })?
+        .ok_or_else(|| CommitTriggerError::FunctionNotFound { name: name.to_owned() })?;
+    let function = function_manager
+        .get_function(snapshot, function_key, name)
+        .map_err(|typedb_source| CommitTriggerError::FunctionRead { typedb_source })?;
+    let signature = function.signature();
+    if signature.args.len() != 1 {
+        return Err(CommitTriggerError::FunctionArgumentCount { name: name.to_owned(), count: signature.args.len() });
+    }
+    let (returned, assignment) = match &signature.output {
+        Output::Stream(stream) => (stream.types.len(), "in"),
+        Output::Single(single) => (single.types.len(), "="),
+    };
+    let results = (0..returned).map(|index| format!("$result{index}")).join(", ");
+    Ok(format!("let {results} {assignment} {name}({CHANGED_VARIABLE});"))
+}
+
+fn parse_pipeline(query: &str) -> Result<typeql::query::Pipeline, CommitTriggerError> {
+    let parsed =
+        typeql::parse_query(query).map_err(|typedb_source| CommitTriggerError::QueryParseFailed { typedb_source })?;
+    match parsed.into_structure() {
+        QueryStructure::Pipeline(pipeline) => Ok(pipeline),
+        QueryStructure::Schema(_) => Err(CommitTriggerError::SchemaQueryNotSupported {}),
+    }
+}
+
+pub(crate) fn get_commit_triggers(
+    database: Arc<Database<WALClient>>,
+) -> Result<Vec<CommitTrigger>, CommitTriggerError> {
+    let transaction = TransactionRead::open(database, TransactionOptions::default())
+        .map_err(|typedb_source| CommitTriggerError::TransactionFailed { typedb_source })?;
+    let triggers = transaction.type_manager.get_commit_triggers(transaction.snapshot());
+    transaction.close();
+    let CommitTriggers(triggers) =
+        triggers.map_err(|typedb_source| CommitTriggerError::ConceptRead { typedb_source })?;
+    Ok(triggers)
+}
+
+/// Replaces the commit triggers of a database, after checking that each names an entity or relation type
+/// and a schema function taking one argument, in a schema transaction so that the triggers change atomically with the schema.
+pub(crate) fn set_commit_triggers(
+    database: Arc<Database<WALClient>>,
+    triggers: Vec<CommitTrigger>,
+) -> Result<(), CommitTriggerError> {
+    let mut transaction = TransactionSchema::open(database, TransactionOptions::default())
+        .map_err(|typedb_source| CommitTriggerError::TransactionFailed { typedb_source })?;
+    if let Err(err) = validate_triggers(&transaction, &triggers) {
+        transaction.close();
+        return Err(err);
+    }
+    let snapshot = Arc::get_mut(&mut transaction.snapshot).expect("Expected owning snapshot for commit triggers");
+    transaction.type_manager.set_commit_triggers(snapshot, CommitTriggers(triggers));
+    let (_, result) = transaction.commit();
+    result.map(|_| ()).map_err(|typedb_source| CommitTriggerError::SchemaCommitFailed { typedb_source })
+}
+
+fn validate_triggers(
+    transaction: &TransactionSchema<WALClient>,
+    triggers: &[CommitTrigger],
+) -> Result<(), CommitTriggerError> {
+    for (index, trigger) in triggers.iter().enumerate() {
+        if triggers[..index].iter().any(|other| other.name == trigger.name) {
+            return Err(CommitTriggerError::DuplicateTriggerName { name: trigger.name.clone() });
+        }
+        let object_type = transaction
+            .type_manager
+            .get_object_type(transaction.snapshot.as_ref(), &Label::build(&trigger.type_label, None))
+            .map_err(|typedb_source| CommitTriggerError::ConceptRead { typedb_source })?;
+        if object_type.is_none() {
+            return Err(CommitTriggerError::ObjectTypeNotFound { label: trigger.type_label.clone() });
+        }
+        let call = function_call(&transaction.function_manager, transaction.snapshot.as_ref(), &trigger.function)?;
+        parse_pipeline(&format!("match {CHANGED_VARIABLE} iid 0x00;\n{call}"))?;
+    }
+    Ok(())
+}
+
+typedb_error! {
+    pub(crate) CommitTriggerError(component = "Commit trigger", prefix = "CTR") {
+        TransactionFailed(1, "Transaction failed.", typedb_source: TransactionError),
+        ConceptRead(2, "Error reading concepts.", typedb_source: Box<ConceptReadError>),
+        DuplicateTriggerName(3, "More than one commit trigger is named '{name}'.", name: String),
+        ObjectTypeNotFound(4, "Entity or relation type '{label}' not found.", label: String),
+        QueryParseFailed(5, "Trigger function call parsing failed.", typedb_source: typeql::Error),
+        SchemaQueryNotSupported(6, "Trigger queries cannot be schema queries."),
+        QueryFailed(7, "Trigger function call failed.", typedb_source: Box<QueryError>),
+        DataCommitFailed(8, "Failed to commit the trigger's transaction.", typedb_source: DataCommitError),
+        SchemaCommitFailed(9, "Failed to commit the commit triggers.", typedb_source: SchemaCommitError),
+        FunctionRead(10, "Error reading the trigger's function.", typedb_source: FunctionReadError),
+        FunctionNotFound(11, "Schema function '{name}' not found.", name: String),
+        FunctionArgumentCount(12, "Trigger function '{name}' must take exactly one argument, but takes {count}.", name: String, count: usize),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use concept::type_::{CommitTrigger, TriggerFailurePolicy};
+    use database::{
+        database_manager::DatabaseManager,
+        query::{execute_schema_query, execute_write_query_in_write},
+        transaction::{TransactionSchema, TransactionWrite},
+        Database,
+    };
+    use executor::ExecutionInterrupt;
+    use options::{QueryOptions, TransactionOptions};
+    use storage::durability_client::WALClient;
+    use test_utils::create_tmp_dir;
+
+    use super::{
+        collect_activations, function_call, parse_pipeline, retry_backoff, run_trigger, set_commit_triggers,
+        CommitTriggerError, RETRY_BACKOFF, RETRY_BACKOFF_MAX,
+    };
+
+    const SCHEMA: &str = r#"
+        define
+        entity order, owns status;
+        attribute status, value string;
+        fun order_statuses($order: order) -> { status }:
+            match $order has status $status;
+            return { $status };
+        fun shared_statuses($order: order, $other: order) -> { status }:
+            match $order has status $status; $other has status $status;
+            return { $status };
+    "#;
+    const INSERT_ORDER: &str = r#"insert $order isa order, has status "received";"#;
+
+    fn setup_database() -> Arc<Database<WALClient>> {
+        let databases_path = create_tmp_dir();
+        let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+        database_manager.put_database("orders").expect("Expected database creation");
+        let database = database_manager.database("orders").expect("Expected database retrieval");
+
+        let transaction = TransactionSchema::open(database.clone(), TransactionOptions::default()).unwrap();
+        let schema = typeql::parse_query(SCHEMA).unwrap().into_structure().into_schema();
+        let (transaction, result) = execute_schema_query(transaction, schema, SCHEMA.to_owned());
+        result.unwrap();
+        transaction.commit().1.unwrap();
+        database
+    }
+
+    fn trigger(function: &str) -> CommitTrigger {
+        CommitTrigger {
+            name: "on-order".to_owned(),
+            type_label: "order".to_owned(),
+            function: function.to_owned(),
+            failure_policy: TriggerFailurePolicy::Retry { attempts: 2 },
+        }
+    }
+
+    #[test]
+    fn committed_inserts_call_their_trigger_function() {
+        let database = setup_database();
+        set_commit_triggers(database.clone(), vec![trigger("order_statuses")]).unwrap();
+
+        let transaction = TransactionWrite::open(database.clone(), TransactionOptions::default()).unwrap();
+        let (transaction, result) = execute_write_query_in_write(
+            transaction,
+            QueryOptions::default_grpc(),
+            parse_pipeline(INSERT_ORDER).unwrap(),
+            INSERT_ORDER.to_owned(),
+            ExecutionInterrupt::new_uninterruptible(),
+        );
+        assert!(result.is_ok());
+        let activations = collect_activations(&transaction).unwrap();
+        let call = function_call(&transaction.function_manager, transaction.snapshot.as_ref(), "order_statuses");
+        assert_eq!(call.unwrap(), "let $result0 in order_statuses($changed);");
+        transaction.commit().1.unwrap();
+
+        let [(activated, iids)] = activations.as_slice() else { panic!("Expected one activation: {activations:?}") };
+        assert_eq!(activated, &trigger("order_statuses"));
+        assert_eq!(iids.len(), 1);
+        run_trigger(database, activated, iids).unwrap();
+    }
+
+    #[test]
+    fn triggers_must_call_a_function_taking_one_argument() {
+        let database = setup_database();
+        let result = set_commit_triggers(database.clone(), vec![trigger("missing")]);
+        assert!(matches!(result, Err(CommitTriggerError::FunctionNotFound { .. })), "{result:?}");
+        let result = set_commit_triggers(database.clone(), vec![trigger("shared_statuses")]);
+        assert!(matches!(result, Err(CommitTriggerError::FunctionArgumentCount { count: 2, .. })), "{result:?}");
+    }
+
+    #[test]
+    fn retry_backoff_doubles_up_to_its_maximum() {
+        assert_eq!(retry_backoff(0), RETRY_BACKOFF);
+        assert_eq!(retry_backoff(1), RETRY_BACKOFF * 2);
+        assert_eq!(retry_backoff(3), RETRY_BACKOFF * 8);
+        assert_eq!(retry_backoff(40), RETRY_BACKOFF_MAX);
+        assert!(retry_backoff(40) < Duration::from_secs(60));
+    }
+}
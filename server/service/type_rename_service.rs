@@ -0,0 +1,116 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+use std::sync::Arc;
+
+use database::{
+    transaction::{SchemaCommitError, TransactionError, TransactionSchema},
+    Database,
+};
+use encoding::value::label::Label;
+use error::typedb_error;
+use options::TransactionOptions;
+use query::error::QueryError;
+use storage::durability_client::WALClient;
+
+/// Renames a type in a schema transaction, keeping its instances, and rewrites the stored functions that refer to it.
+/// Role types are given by their scoped label, such as `friendship:friend`.
+pub(crate) fn rename_type(
+    database: Arc<Database<WALClient>>,
+    label: &str,
+    new_label: &str,
+) -> Result<(), TypeRenameError> {
+    let mut transaction = TransactionSchema::open(database, TransactionOptions::default())
+        .map_err(|typedb_source| TypeRenameError::TransactionFailed { typedb_source })?;
+    let snapshot = Arc::get_mut(&mut transaction.snapshot).expect("Expected owning snapshot for type rename");
+    let result = transaction.query_manager.rename_type(
+        snapshot,
+        &transaction.type_manager,
+        &transaction.function_manager,
+        &Label::parse_from(label, None),
+        &Label::parse_from(new_label, None),
+    );
+    if let Err(typedb_source) = result {
+        transaction.close();
+        return Err(TypeRenameError::RenameFailed { typedb_source });
+    }
+    let (_, result) = transaction.commit();
+    result.map(|_| ()).map_err(|typedb_source| TypeRenameError::SchemaCommitFailed { typedb_source })
+}
+
+typedb_error! {
+    pub(crate) TypeRenameError(component = "Type rename", prefix = "TRN") {
+        TransactionFailed(1, "Transaction failed.", typedb_source: TransactionError),
+        RenameFailed(2, "Failed to rename the type.", typedb_source: Box<QueryError>),
+        SchemaCommitFailed(3, "Failed to commit the type rename.", typedb_source: SchemaCommitError),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use database::{
+        database_manager::DatabaseManager,
+        query::{execute_schema_query, execute_write_query_in_write},
+        transaction::{TransactionSchema, TransactionWrite},
+    };
+    use executor::ExecutionInterrupt;
+    use options::{QueryOptions, TransactionOptions};
+    use storage::durability_client::WALClient;
+    use test_utils::create_tmp_dir;
+
+    use super::{rename_type, TypeRenameError};
+
+    const SCHEMA: &str = r#"
+        define
+        entity person, owns name, plays friendship:friend;
+        relation friendship, relates friend;
+        attribute name, value string;
+        fun names_of($person: person) -> { name }:
+            match $person has name $name;
+            return { $name };
+    "#;
+
+    fn run_write_query(transaction: TransactionWrite<WALClient>, query: &str) -> usize {
+        let pipeline = typeql::parse_query(query).unwrap().into_structure().into_pipeline();
+        let (transaction, result) = execute_write_query_in_write(
+            transaction,
+            QueryOptions::default_grpc(),
+            pipeline,
+            query.to_owned(),
+            ExecutionInterrupt::new_uninterruptible(),
+        );
+        let answer = result.unwrap_or_else(|err| panic!("Expected '{query}' to succeed: {err:?}"));
+        let (_, rows, _) = answer.answer.left().expect("Expected a row answer");
+        transaction.commit().1.unwrap();
+        rows.len()
+    }
+
+    #[test]
+    fn renamed_types_keep_their_instances_and_functions() {
+        let databases_path = create_tmp_dir();
+        let database_manager = DatabaseManager::new(&databases_path).expect("Expected database manager");
+        database_manager.put_database("people").expect("Expected database creation");
+        let database = database_manager.database("people").expect("Expected database retrieval");
+
+        let transaction = TransactionSchema::open(database.clone(), TransactionOptions::default()).unwrap();
+        let schema = typeql::parse_query(SCHEMA).unwrap().into_structure().into_schema();
+        let (transaction, result) = execute_schema_query(transaction, schema, SCHEMA.to_owned());
+        result.unwrap();
+        transaction.commit().1.unwrap();
+        let transaction = TransactionWrite::open(database.clone(), TransactionOptions::default()).unwrap();
+        run_write_query(transaction, r#"insert $p isa person, has name "Alice"; (friend: $p) isa friendship;"#);
+
+        rename_type(database.clone(), "person", "human").unwrap();
+        rename_type(database.clone(), "friendship:friend", "friendship:pal").unwrap();
+
+        // the instances are kept, and the stored function type-checks against the new label
+        let transaction = TransactionWrite::open(database.clone(), TransactionOptions::default()).unwrap();
+        let query = "match $h isa human; (pal: $h) isa friendship; let $name in names_of($h);";
+        assert_eq!(run_write_query(transaction, query), 1);
+
+        let result = rename_type(database.clone(), "person", "people");
+        assert!(matches!(result, Err(TypeRenameError::RenameFailed { .. })), "{result:?}");
+    }
+}
@@ -5,6 +5,7 @@
  */
 
 use std::{
+    collections::HashMap,
     fmt::Debug,
     fs,
     path::{Path, PathBuf},
@@ -12,11 +13,18 @@ use std::{
 };
 
 use async_trait::async_trait;
-use concept::error::ConceptReadError;
+use compiler::{annotation::limits::CompileLimits, warning::QueryWarning};
+use concept::{
+    error::ConceptReadError,
+    type_::{CommitTrigger, DatabaseSettings},
+};
 use concurrency::IntervalRunner;
 use database::{
-    database::DatabaseCreateError, database_manager::DatabaseManager, transaction::TransactionRead, Database,
-    DatabaseDeleteError,
+    cluster::{Cluster, ClusterError, ClusterReply},
+    database::DatabaseCreateError,
+    database_manager::{DatabaseEncryption, DatabaseManager},
+    transaction::TransactionRead,
+    Database, DatabaseCloneError, DatabaseDeleteError, EncryptionKey, MasterKeyManagement,
 };
 use diagnostics::{diagnostics_manager::DiagnosticsManager, Diagnostics};
 use error::typedb_error;
@@ -24,15 +32,26 @@ use ir::pipeline::FunctionReadError;
 use options::TransactionOptions;
 use rand::prelude::SliceRandom;
 use resource::{
-    constants::server::{DATABASE_METRICS_UPDATE_INTERVAL, SERVER_ID_ALPHABET, SERVER_ID_FILE_NAME, SERVER_ID_LENGTH},
+    constants::{
+        memory::MEMORY_REBALANCE_INTERVAL,
+        server::{
+            DATABASE_METRICS_UPDATE_INTERVAL, EXPIRED_INSTANCE_CLEANUP_INTERVAL, ORPHANED_ATTRIBUTE_CLEANUP_INTERVAL,
+            SERVER_ID_ALPHABET, SERVER_ID_FILE_NAME, SERVER_ID_LENGTH,
+        },
+    },
+    memory::{MemoryPressure, MEMORY_MANAGER},
     server_info::ServerInfo,
 };
-use storage::durability_client::{DurabilityClient, WALClient};
+use storage::{
+    durability_client::{DurabilityClient, WALClient},
+    keyspace::KeyspaceVerification,
+};
 use system::{
     concepts::{Credential, User},
     initialise_system_database,
 };
 use tokio::sync::watch::Receiver;
+use tracing::{event, Level};
 use user::{
     errors::{UserCreateError, UserDeleteError, UserGetError, UserUpdateError},
     initialise_default_user,
@@ -45,8 +64,27 @@ use crate::{
         credential_verifier::CredentialVerifier, token_manager::TokenManager, Accessor, AuthenticationError,
     },
     error::ServerOpenError,
-    parameters::config::{Config, DiagnosticsConfig},
-    service::export_service::{get_transaction_schema, get_transaction_type_schema, DatabaseExportError},
+    parameters::config::{Config, DiagnosticsConfig, StorageEncryptionConfig},
+    service::{
+        attribute_cleanup_service::{
+            cleanup_orphaned_attributes, start_orphaned_attribute_cleanup, AttributeCleanupError,
+        },
+        cluster_service::{handle_heartbeat, handle_vote_request, start_cluster},
+        database_options_service::{
+            get_database_options, get_storage_usage, set_database_settings, set_history_retention,
+            set_relation_index_threshold, set_storage_quota, verify_storage, DatabaseOptions, DatabaseOptionsError,
+            StorageUsage,
+        },
+        expiry_service::{cleanup_expired_instances, set_time_to_live, ExpiryError},
+        export_service::{get_transaction_schema, get_transaction_type_schema, DatabaseExportError},
+        lint_service::{lint_query, LintError},
+        relation_index_service::{start_relation_index_rebuild, RelationIndexRebuildError},
+        replication_service::{read_replication_batch, start_replication, ReplicationBatch, ReplicationError},
+        schema_diff_service::{get_schema_diff, SchemaDiffError},
+        statistics_service::{get_database_statistics, DatabaseStatistics, StatisticsServiceError},
+        trigger_service::{get_commit_triggers, set_commit_triggers, CommitTriggerError},
+        type_rename_service::{rename_type, TypeRenameError},
+    },
 };
 
 pub type BoxServerState = Box<dyn ServerState + Send + Sync>;
@@ -61,10 +99,88 @@ pub trait ServerState: Debug {
 
     fn databases_create(&self, name: &str) -> Result<(), DatabaseCreateError>;
 
+    fn databases_clone(&self, name: &str, target: &str) -> Result<(), DatabaseCloneError>;
+
     fn database_schema(&self, name: String) -> Result<String, ServerStateError>;
 
     fn database_type_schema(&self, name: String) -> Result<String, ServerStateError>;
 
+    fn database_schema_diff(&self, name: String, target_schema: String) -> Result<String, ServerStateError>;
+
+    fn database_options(&self, name: String) -> Result<DatabaseOptions, ServerStateError>;
+
+    fn database_options_update(
+        &self,
+        name: String,
+        relation_index_threshold: Option<u64>,
+        history_retention: Option<u64>,
+    ) -> Result<DatabaseOptions, ServerStateError>;
+
+    fn database_settings(&self, name: String) -> Result<DatabaseSettings, ServerStateError>;
+
+    fn database_settings_update(
+        &self,
+        name: String,
+        settings: DatabaseSettings,
+    ) -> Result<DatabaseSettings, ServerStateError>;
+
+    fn database_storage(&self, name: String) -> Result<StorageUsage, ServerStateError>;
+
+    fn database_storage_quota_update(
+        &self,
+        name: String,
+        quota_in_bytes: Option<u64>,
+    ) -> Result<StorageUsage, ServerStateError>;
+
+    fn database_verify(&self, name: String, salvage: bool) -> Result<Vec<KeyspaceVerification>, ServerStateError>;
+
+    fn database_statistics(&self, name: String) -> Result<DatabaseStatistics, ServerStateError>;
+
+    fn database_lint(&self, name: String, query: String) -> Result<Vec<QueryWarning>, ServerStateError>;
+
+    fn database_relation_index_rebuild(&self, name: String, relation_type: String) -> Result<(), ServerStateError>;
+
+    fn database_attribute_cleanup(&self, name: String) -> Result<(), ServerStateError>;
+
+    fn database_triggers(&self, name: String) -> Result<Vec<CommitTrigger>, ServerStateError>;
+
+    fn database_triggers_update(&self, name: String, triggers: Vec<CommitTrigger>) -> Result<(), ServerStateError>;
+
+    fn database_time_to_live_update(
+        &self,
+        name: String,
+        type_label: String,
+        seconds: Option<u64>,
+    ) -> Result<(), ServerStateError>;
+
+    fn database_type_rename(&self, name: String, label: String, new_label: String) -> Result<(), ServerStateError>;
+
+    fn database_replication_records(
+        &self,
+        name: String,
+        from: u64,
+        limit: Option<usize>,
+        member_progress: Option<(String, u64)>,
+        accessor: Accessor,
+    ) -> Result<ReplicationBatch, ServerStateError>;
+
+    fn cluster(&self, accessor: Accessor) -> Result<Arc<Cluster>, ServerStateError>;
+
+    fn cluster_vote(
+        &self,
+        term: u64,
+        candidate: String,
+        candidate_received: HashMap<String, u64>,
+        accessor: Accessor,
+    ) -> Result<ClusterReply, ServerStateError>;
+
+    fn cluster_heartbeat(
+        &self,
+        term: u64,
+        leader: String,
+        accessor: Accessor,
+    ) -> Result<ClusterReply, ServerStateError>;
+
     fn database_delete(&self, name: &str) -> Result<(), DatabaseDeleteError>;
 
     fn users_get(&self, name: &str, accessor: Accessor) -> Result<User, ServerStateError>;
@@ -110,6 +226,9 @@ pub struct LocalServerState {
     token_manager: Arc<TokenManager>,
     diagnostics_manager: Arc<DiagnosticsManager>,
     _database_diagnostics_updater: IntervalRunner,
+    _orphaned_attribute_cleaner: IntervalRunner,
+    _expired_instance_cleaner: IntervalRunner,
+    _memory_rebalancer: IntervalRunner,
     shutdown_receiver: Receiver<()>,
 }
 
@@ -129,9 +248,19 @@ impl LocalServerState {
 
         let deployment_id = deployment_id.unwrap_or(server_id.clone());
 
-        let database_manager = DatabaseManager::new(storage_directory)
+        MEMORY_MANAGER.set_limit(config.server.memory_limit_bytes);
+        let database_encryption = Self::initialise_database_encryption(&config.storage.encryption)?;
+        let database_manager = DatabaseManager::new_with_encryption(storage_directory, database_encryption)
             .map_err(|err| ServerOpenError::DatabaseOpen { typedb_source: err })?;
-        let system_database = initialise_system_database(&database_manager);
+        let query_limits = &config.server.query_limits;
+        database_manager.set_compile_limits(CompileLimits {
+            max_pattern_constraints: query_limits.max_pattern_constraints,
+            max_disjunction_branches: query_limits.max_disjunction_branches,
+            max_type_combinations: query_limits.max_type_combinations,
+            max_reduce_list_size: query_limits.max_reduce_list_size,
+        });
+        let system_database = initialise_system_database(&database_manager)
+            .map_err(|typedb_source| ServerOpenError::SystemDatabaseMigration { typedb_source })?;
 
         let user_manager = Arc::new(UserManager::new(system_database));
         initialise_default_user(&user_manager);
@@ -154,6 +283,15 @@ impl LocalServerState {
             .await,
         );
 
+        start_replication(&config.server.replication, database_manager.clone(), shutdown_receiver.clone());
+        start_cluster(
+            &config.server.cluster,
+            &config.server.replication,
+            database_manager.clone(),
+            shutdown_receiver.clone(),
+        )
+        .map_err(|typedb_source| ServerOpenError::Cluster { typedb_source })?;
+
         Ok(Self {
             server_info,
             database_manager: database_manager.clone(),
@@ -162,13 +300,69 @@ impl LocalServerState {
             token_manager,
             diagnostics_manager: diagnostics_manager.clone(),
             _database_diagnostics_updater: IntervalRunner::new(
-                move || Self::synchronize_database_metrics(diagnostics_manager.clone(), database_manager.clone()),
+                {
+                    let diagnostics_manager = diagnostics_manager.clone();
+                    let database_manager = database_manager.clone();
+                    move || Self::synchronize_database_metrics(diagnostics_manager.clone(), database_manager.clone())
+                },
                 DATABASE_METRICS_UPDATE_INTERVAL,
             ),
+            _orphaned_attribute_cleaner: IntervalRunner::new_with_initial_delay(
+                {
+                    let diagnostics_manager = diagnostics_manager.clone();
+                    let database_manager = database_manager.clone();
+                    move || Self::cleanup_orphaned_attributes(diagnostics_manager.clone(), database_manager.clone())
+                },
+                ORPHANED_ATTRIBUTE_CLEANUP_INTERVAL,
+                ORPHANED_ATTRIBUTE_CLEANUP_INTERVAL,
+            ),
+            _expired_instance_cleaner: IntervalRunner::new_with_initial_delay(
+                move || Self::cleanup_expired_instances(diagnostics_manager.clone(), database_manager.clone()),
+                EXPIRED_INSTANCE_CLEANUP_INTERVAL,
+                EXPIRED_INSTANCE_CLEANUP_INTERVAL,
+            ),
+            _memory_rebalancer: IntervalRunner::new(Self::rebalance_memory, MEMORY_REBALANCE_INTERVAL),
             shutdown_receiver,
         })
     }
 
+    fn rebalance_memory() {
+        for component_memory in MEMORY_MANAGER.rebalance() {
+            if component_memory.pressure != MemoryPressure::Normal {
+                event!(
+                    Level::DEBUG,
+                    "Memory pressure on {}: {} bytes used of a {:?} byte budget.",
+                    component_memory.component,
+                    component_memory.usage,
+                    component_memory.budget
+                );
+            }
+        }
+    }
+
+    fn initialise_database_encryption(
+        config: &StorageEncryptionConfig,
+    ) -> Result<Option<DatabaseEncryption>, ServerOpenError> {
+        if !config.enabled {
+            return Ok(None);
+        }
+        let key_file = config.key_file.as_ref().expect("Storage encryption key file must be validated in config");
+        let path = key_file.to_str().unwrap_or("").to_owned();
+        let hex = fs::read_to_string(key_file).map_err(|source| ServerOpenError::CouldNotReadStorageEncryptionKey {
+            path: path.clone(),
+            source: Arc::new(source),
+        })?;
+        let master_key = EncryptionKey::from_hex(&hex)
+            .map_err(|source| ServerOpenError::InvalidStorageEncryptionKey { path, source })?;
+        event!(
+            Level::WARN,
+            "Storage encryption covers database write-ahead logs only: checkpoints and storage files are written in \
+            plaintext, and should be kept on an encrypted volume."
+        );
+        let databases = config.databases.as_ref().map(|databases| databases.iter().cloned().collect());
+        Ok(Some(DatabaseEncryption::new(Arc::new(MasterKeyManagement::new(&master_key)), databases)))
+    }
+
     fn may_initialise_storage_directory(storage_directory: &Path) -> Result<(), ServerOpenError> {
         debug_assert!(storage_directory.is_absolute());
         if !storage_directory.exists() {
@@ -262,6 +456,37 @@ impl LocalServerState {
         diagnostics_manager.submit_database_metrics(metrics);
     }
 
+    fn cleanup_orphaned_attributes(
+        diagnostics_manager: Arc<DiagnosticsManager>,
+        database_manager: Arc<DatabaseManager>,
+    ) {
+        let databases = database_manager
+            .databases()
+            .values()
+            .filter(|database| DatabaseManager::is_user_database(database.name()) && !database.is_replica())
+            .cloned()
+            .collect::<Vec<_>>();
+        for database in databases {
+            if let Err(err) = cleanup_orphaned_attributes(database.clone(), diagnostics_manager.clone()) {
+                event!(Level::WARN, "Orphaned attribute cleanup in database '{}' failed: {:?}", database.name(), err);
+            }
+        }
+    }
+
+    fn cleanup_expired_instances(diagnostics_manager: Arc<DiagnosticsManager>, database_manager: Arc<DatabaseManager>) {
+        let databases = database_manager
+            .databases()
+            .values()
+            .filter(|database| DatabaseManager::is_user_database(database.name()) && !database.is_replica())
+            .cloned()
+            .collect::<Vec<_>>();
+        for database in databases {
+            if let Err(err) = cleanup_expired_instances(database.clone(), diagnostics_manager.clone()) {
+                event!(Level::WARN, "Expired instance cleanup in database '{}' failed: {:?}", database.name(), err);
+            }
+        }
+    }
+
     pub fn get_database_schema<D: DurabilityClient>(database: Arc<Database<D>>) -> Result<String, ServerStateError> {
         let transaction = TransactionRead::open(database, TransactionOptions::default())
             .map_err(|err| ServerStateError::FailedToOpenPrerequisiteTransaction {})?;
@@ -299,6 +524,10 @@ impl ServerState for LocalServerState {
         self.database_manager.put_database(name)
     }
 
+    fn databases_clone(&self, name: &str, target: &str) -> Result<(), DatabaseCloneError> {
+        self.database_manager.clone_database(name, target)
+    }
+
     fn database_schema(&self, name: String) -> Result<String, ServerStateError> {
         match self.database_manager.database(&name) {
             Some(db) => Self::get_database_schema(db),
@@ -316,6 +545,220 @@ impl ServerState for LocalServerState {
         }
     }
 
+    fn database_schema_diff(&self, name: String, target_schema: String) -> Result<String, ServerStateError> {
+        match self.database_manager.database(&name) {
+            None => Err(ServerStateError::DatabaseDoesNotExist { name }),
+            Some(database) => get_schema_diff(&self.database_manager, database, target_schema)
+                .map_err(|typedb_source| ServerStateError::SchemaDiff { typedb_source }),
+        }
+    }
+
+    fn database_options(&self, name: String) -> Result<DatabaseOptions, ServerStateError> {
+        match self.database_manager.database(&name) {
+            None => Err(ServerStateError::DatabaseDoesNotExist { name }),
+            Some(database) => get_database_options(database)
+                .map_err(|typedb_source| ServerStateError::DatabaseOptions { typedb_source }),
+        }
+    }
+
+    fn database_options_update(
+        &self,
+        name: String,
+        relation_index_threshold: Option<u64>,
+        history_retention: Option<u64>,
+    ) -> Result<DatabaseOptions, ServerStateError> {
+        let Some(database) = self.database_manager.database(&name) else {
+            return Err(ServerStateError::DatabaseDoesNotExist { name });
+        };
+        if let Some(threshold) = relation_index_threshold {
+            set_relation_index_threshold(database.clone(), threshold)
+                .map_err(|typedb_source| ServerStateError::DatabaseOptions { typedb_source })?;
+        }
+        if let Some(retention) = history_retention {
+            set_history_retention(database.clone(), retention)
+                .map_err(|typedb_source| ServerStateError::DatabaseOptions { typedb_source })?;
+        }
+        get_database_options(database).map_err(|typedb_source| ServerStateError::DatabaseOptions { typedb_source })
+    }
+
+    fn database_settings(&self, name: String) -> Result<DatabaseSettings, ServerStateError> {
+        match self.database_manager.database(&name) {
+            None => Err(ServerStateError::DatabaseDoesNotExist { name }),
+            Some(database) => Ok(database.settings()),
+        }
+    }
+
+    fn database_settings_update(
+        &self,
+        name: String,
+        settings: DatabaseSettings,
+    ) -> Result<DatabaseSettings, ServerStateError> {
+        let Some(database) = self.database_manager.database(&name) else {
+            return Err(ServerStateError::DatabaseDoesNotExist { name });
+        };
+        set_database_settings(database.clone(), settings)
+            .map_err(|typedb_source| ServerStateError::DatabaseOptions { typedb_source })?;
+        Ok(database.settings())
+    }
+
+    fn database_storage(&self, name: String) -> Result<StorageUsage, ServerStateError> {
+        match self.database_manager.database(&name) {
+            None => Err(ServerStateError::DatabaseDoesNotExist { name }),
+            Some(database) => {
+                get_storage_usage(database).map_err(|typedb_source| ServerStateError::DatabaseOptions { typedb_source })
+            }
+        }
+    }
+
+    fn database_storage_quota_update(
+        &self,
+        name: String,
+        quota_in_bytes: Option<u64>,
+    ) -> Result<StorageUsage, ServerStateError> {
+        let Some(database) = self.database_manager.database(&name) else {
+            return Err(ServerStateError::DatabaseDoesNotExist { name });
+        };
+        set_storage_quota(database.clone(), quota_in_bytes)
+            .map_err(|typedb_source| ServerStateError::DatabaseOptions { typedb_source })?;
+        get_storage_usage(database).map_err(|typedb_source| ServerStateError::DatabaseOptions { typedb_source })
+    }
+
+    fn database_verify(&self, name: String, salvage: bool) -> Result<Vec<KeyspaceVerification>, ServerStateError> {
+        if self.database_manager.database(&name).is_none() {
+            return Err(ServerStateError::DatabaseDoesNotExist { name });
+        }
+        verify_storage(&self.database_manager, &name, salvage)
+            .map_err(|typedb_source| ServerStateError::DatabaseOptions { typedb_source })
+    }
+
+    fn database_statistics(&self, name: String) -> Result<DatabaseStatistics, ServerStateError> {
+        match self.database_manager.database(&name) {
+            None => Err(ServerStateError::DatabaseDoesNotExist { name }),
+            Some(database) => get_database_statistics(database)
+                .map_err(|typedb_source| ServerStateError::Statistics { typedb_source }),
+        }
+    }
+
+    fn database_lint(&self, name: String, query: String) -> Result<Vec<QueryWarning>, ServerStateError> {
+        match self.database_manager.database(&name) {
+            None => Err(ServerStateError::DatabaseDoesNotExist { name }),
+            Some(database) => {
+                lint_query(database, query).map_err(|typedb_source| ServerStateError::Lint { typedb_source })
+            }
+        }
+    }
+
+    fn database_relation_index_rebuild(&self, name: String, relation_type: String) -> Result<(), ServerStateError> {
+        match self.database_manager.database(&name) {
+            None => Err(ServerStateError::DatabaseDoesNotExist { name }),
+            Some(database) => start_relation_index_rebuild(database, &relation_type, self.diagnostics_manager.clone())
+                .map_err(|typedb_source| ServerStateError::RelationIndexRebuild { typedb_source }),
+        }
+    }
+
+    fn database_attribute_cleanup(&self, name: String) -> Result<(), ServerStateError> {
+        match self.database_manager.database(&name) {
+            None => Err(ServerStateError::DatabaseDoesNotExist { name }),
+            Some(database) => start_orphaned_attribute_cleanup(database, self.diagnostics_manager.clone())
+                .map_err(|typedb_source| ServerStateError::AttributeCleanup { typedb_source }),
+        }
+    }
+
+    fn database_triggers(&self, name: String) -> Result<Vec<CommitTrigger>, ServerStateError> {
+        match self.database_manager.database(&name) {
+            None => Err(ServerStateError::DatabaseDoesNotExist { name }),
+            Some(database) => get_commit_triggers(database)
+                .map_err(|typedb_source| ServerStateError::CommitTriggers { typedb_source }),
+        }
+    }
+
+    fn database_triggers_update(&self, name: String, triggers: Vec<CommitTrigger>) -> Result<(), ServerStateError> {
+        match self.database_manager.database(&name) {
+            None => Err(ServerStateError::DatabaseDoesNotExist { name }),
+            Some(database) => set_commit_triggers(database, triggers)
+                .map_err(|typedb_source| ServerStateError::CommitTriggers { typedb_source }),
+        }
+    }
+
+    fn database_time_to_live_update(
+        &self,
+        name: String,
+        type_label: String,
+        seconds: Option<u64>,
+    ) -> Result<(), ServerStateError> {
+        match self.database_manager.database(&name) {
+            None => Err(ServerStateError::DatabaseDoesNotExist { name }),
+            Some(database) => set_time_to_live(database, &type_label, seconds)
+                .map_err(|typedb_source| ServerStateError::Expiry { typedb_source }),
+        }
+    }
+
+    fn database_type_rename(&self, name: String, label: String, new_label: String) -> Result<(), ServerStateError> {
+        match self.database_manager.database(&name) {
+            None => Err(ServerStateError::DatabaseDoesNotExist { name }),
+            Some(database) => rename_type(database, &label, &new_label)
+                .map_err(|typedb_source| ServerStateError::TypeRename { typedb_source }),
+        }
+    }
+
+    fn database_replication_records(
+        &self,
+        name: String,
+        from: u64,
+        limit: Option<usize>,
+        member_progress: Option<(String, u64)>,
+        accessor: Accessor,
+    ) -> Result<ReplicationBatch, ServerStateError> {
+        if !PermissionManager::exec_database_replication_permitted(accessor.0.as_str()) {
+            return Err(ServerStateError::OperationNotPermitted {});
+        }
+        let Some(database) = self.database_manager.database(&name) else {
+            return Err(ServerStateError::DatabaseDoesNotExist { name });
+        };
+        if let (Some(cluster), Some((member, received))) = (self.database_manager.cluster(), member_progress) {
+            cluster.record_follower_progress(&member, &name, received);
+        }
+        read_replication_batch(database, from, limit)
+            .map_err(|typedb_source| ServerStateError::Replication { typedb_source })
+    }
+
+    fn cluster(&self, accessor: Accessor) -> Result<Arc<Cluster>, ServerStateError> {
+        if !PermissionManager::exec_cluster_coordination_permitted(accessor.0.as_str()) {
+            return Err(ServerStateError::OperationNotPermitted {});
+        }
+        self.database_manager
+            .cluster()
+            .cloned()
+            .ok_or(ServerStateError::Cluster { typedb_source: ClusterError::NotConfigured {} })
+    }
+
+    fn cluster_vote(
+        &self,
+        term: u64,
+        candidate: String,
+        candidate_received: HashMap<String, u64>,
+        accessor: Accessor,
+    ) -> Result<ClusterReply, ServerStateError> {
+        if !PermissionManager::exec_cluster_coordination_permitted(accessor.0.as_str()) {
+            return Err(ServerStateError::OperationNotPermitted {});
+        }
+        handle_vote_request(&self.database_manager, term, &candidate, &candidate_received)
+            .map_err(|typedb_source| ServerStateError::Cluster { typedb_source })
+    }
+
+    fn cluster_heartbeat(
+        &self,
+        term: u64,
+        leader: String,
+        accessor: Accessor,
+    ) -> Result<ClusterReply, ServerStateError> {
+        if !PermissionManager::exec_cluster_coordination_permitted(accessor.0.as_str()) {
+            return Err(ServerStateError::OperationNotPermitted {});
+        }
+        handle_heartbeat(&self.database_manager, term, &leader)
+            .map_err(|typedb_source| ServerStateError::Cluster { typedb_source })
+    }
+
     fn database_delete(&self, name: &str) -> Result<(), DatabaseDeleteError> {
         self.database_manager.delete_database(name)
     }
@@ -426,5 +869,16 @@ typedb_error! {
         UserCannotBeUpdated(10, "Unable to update user", typedb_source: UserUpdateError),
         UserCannotBeDeleted(11, "Unable to delete user", typedb_source: UserDeleteError),
         DatabaseExport(12, "Database export error", typedb_source: DatabaseExportError),
+        SchemaDiff(13, "Schema diff error", typedb_source: SchemaDiffError),
+        DatabaseOptions(14, "Database options error", typedb_source: DatabaseOptionsError),
+        RelationIndexRebuild(15, "Relation index rebuild error", typedb_source: RelationIndexRebuildError),
+        AttributeCleanup(16, "Attribute cleanup error", typedb_source: AttributeCleanupError),
+        CommitTriggers(18, "Commit triggers error", typedb_source: CommitTriggerError),
+        Expiry(19, "Instance expiry error", typedb_source: ExpiryError),
+        Replication(20, "Replication error", typedb_source: ReplicationError),
+        Cluster(21, "Cluster error", typedb_source: ClusterError),
+        Statistics(22, "Database statistics error", typedb_source: StatisticsServiceError),
+        Lint(23, "Query lint error", typedb_source: LintError),
+        TypeRename(24, "Type rename error", typedb_source: TypeRenameError),
     }
 }
@@ -95,6 +95,8 @@ pub trait DurabilityClient {
         &self,
     ) -> Result<Option<Record>, DurabilityClientError>;
 
+    fn truncate_from(&self, sequence_number: SequenceNumber) -> Result<(), DurabilityClientError>;
+
     fn delete_durability(self) -> Result<(), DurabilityClientError>;
 
     fn reset(&mut self) -> Result<(), DurabilityClientError>;
@@ -201,6 +203,10 @@ impl DurabilityClient for WALClient {
         }
     }
 
+    fn truncate_from(&self, sequence_number: SequenceNumber) -> Result<(), DurabilityClientError> {
+        self.wal.truncate_from(sequence_number).map_err(|err| DurabilityClientError::ServiceError { source: err })
+    }
+
     fn delete_durability(self) -> Result<(), DurabilityClientError> {
         self.wal.delete_durability().map_err(|err| DurabilityClientError::ServiceError { source: err })
     }
@@ -215,6 +221,7 @@ typedb_error! {
         SerializeError(1, "Durability client failed to serialise/deserialise durability record", source: Arc<bincode::Error>),
         ServiceError(2, "Error from durability service.", source: DurabilityServiceError),
         CompressionError(3, "Error while compressing durability record.", source: Arc<io::Error>),
+        SyncFailed(4, "Durability service stopped before the records were synced."),
     }
 }
 
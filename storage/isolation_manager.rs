@@ -19,10 +19,11 @@ use std::{
     },
 };
 
+use bytes::{byte_array::ByteArray, util::HexBytesFormatter};
 use durability::DurabilityRecordType;
 use logger::result::ResultExt;
 use primitive::maybe_owns::MaybeOwns;
-use resource::constants::storage::TIMELINE_WINDOW_SIZE;
+use resource::constants::{snapshot::BUFFER_KEY_INLINE, storage::TIMELINE_WINDOW_SIZE};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -76,6 +77,16 @@ impl IsolationManager {
         Ok(())
     }
 
+    /// Aborts a commit after it was validated, in place of applying it.
+    pub(crate) fn aborted(&self, sequence_number: SequenceNumber) -> Result<(), ExpectedWindowError> {
+        self.timeline
+            .try_get_window(sequence_number)
+            .ok_or(ExpectedWindowError { sequence_number })?
+            .set_aborted(sequence_number);
+        self.timeline.may_increment_watermark(sequence_number);
+        Ok(())
+    }
+
     pub(crate) fn load_validated(&self, sequence_number: SequenceNumber, commit_record: CommitRecord) {
         let window = self.timeline.get_or_create_window(sequence_number);
         window.insert_pending(sequence_number, commit_record);
@@ -318,20 +329,39 @@ enum CommitDependency {
 
 #[derive(Debug, Clone)]
 pub enum IsolationConflict {
-    DeletingRequiredKey,
-    RequireDeletedKey,
-    ExclusiveLock,
+    DeletingRequiredKey { key: ByteArray<BUFFER_KEY_INLINE> },
+    RequireDeletedKey { key: ByteArray<BUFFER_KEY_INLINE> },
+    ExclusiveLock { key: ByteArray<BUFFER_KEY_INLINE> },
+}
+
+impl IsolationConflict {
+    /// The key written or locked by both commits, without its keyspace.
+    pub fn key(&self) -> &ByteArray<BUFFER_KEY_INLINE> {
+        match self {
+            IsolationConflict::DeletingRequiredKey { key }
+            | IsolationConflict::RequireDeletedKey { key }
+            | IsolationConflict::ExclusiveLock { key } => key,
+        }
+    }
+
+    /// Whether both commits only contended for the same lock, rather than one depending on data the other deletes.
+    pub fn is_write_write(&self) -> bool {
+        matches!(self, IsolationConflict::ExclusiveLock { .. })
+    }
 }
 
 impl fmt::Display for IsolationConflict {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            IsolationConflict::DeletingRequiredKey => {
-                write!(f, "Transaction deletes data a concurrent commit requires.")
+            IsolationConflict::DeletingRequiredKey { .. } => {
+                write!(f, "Transaction deletes data a concurrent commit requires")
             }
-            IsolationConflict::RequireDeletedKey => write!(f, "Transaction uses data a concurrent commit deletes."),
-            IsolationConflict::ExclusiveLock => write!(f, "Transaction uses a lock held by a concurrent commit."),
-        }
+            IsolationConflict::RequireDeletedKey { .. } => {
+                write!(f, "Transaction uses data a concurrent commit deletes")
+            }
+            IsolationConflict::ExclusiveLock { .. } => write!(f, "Transaction uses a lock held by a concurrent commit"),
+        }?;
+        write!(f, " (key: {}).", HexBytesFormatter::borrowed(self.key()))
     }
 }
 
@@ -754,7 +784,7 @@ impl CommitRecord {
                 }
                 if matches!(write, Write::Delete) && matches!(predecessor_locks.get(key), Some(LockType::Unmodifiable))
                 {
-                    return CommitDependency::Conflict(IsolationConflict::DeletingRequiredKey);
+                    return CommitDependency::Conflict(IsolationConflict::DeletingRequiredKey { key: key.clone() });
                 }
             }
 
@@ -763,7 +793,7 @@ impl CommitRecord {
             for (key, lock) in locks.iter() {
                 if matches!(lock, LockType::Unmodifiable) {
                     if let Some(Write::Delete) = predecessor_writes.get(key) {
-                        return CommitDependency::Conflict(IsolationConflict::RequireDeletedKey);
+                        return CommitDependency::Conflict(IsolationConflict::RequireDeletedKey { key: key.clone() });
                     }
                 }
             }
@@ -771,7 +801,7 @@ impl CommitRecord {
 
         for (key, lock) in locks.iter() {
             if matches!(lock, LockType::Exclusive) && matches!(predecessor_locks.get(key), Some(LockType::Exclusive)) {
-                return CommitDependency::Conflict(IsolationConflict::ExclusiveLock);
+                return CommitDependency::Conflict(IsolationConflict::ExclusiveLock { key: key.clone() });
             }
         }
 
@@ -814,11 +844,11 @@ impl StatusRecord {
         StatusRecord { commit_record_sequence_number: sequence_number, was_committed: committed }
     }
 
-    pub(crate) fn was_committed(&self) -> bool {
+    pub fn was_committed(&self) -> bool {
         self.was_committed
     }
 
-    pub(crate) fn commit_record_sequence_number(&self) -> SequenceNumber {
+    pub fn commit_record_sequence_number(&self) -> SequenceNumber {
         self.commit_record_sequence_number
     }
 }
@@ -8,12 +8,15 @@ use std::{cmp::Ordering, error::Error, fmt, sync::Arc};
 
 use bytes::byte_array::ByteArray;
 use lending_iterator::{LendingIterator, Peekable, Seekable};
-use resource::profile::StorageCounters;
+use resource::{
+    constants::snapshot::{BUFFER_KEY_INLINE, BUFFER_VALUE_INLINE},
+    profile::StorageCounters,
+};
 
 use super::{MVCCKey, MVCCStorage, StorageOperation, MVCC_KEY_INLINE_SIZE};
 use crate::{
-    key_range::KeyRange,
-    key_value::{StorageKey, StorageKeyReference},
+    key_range::{KeyRange, RangeStart},
+    key_value::{StorageKey, StorageKeyArray, StorageKeyReference},
     keyspace::{iterator::KeyspaceRangeIterator, IteratorPool, KeyspaceError, KeyspaceId},
     sequence_number::SequenceNumber,
 };
@@ -136,6 +139,113 @@ impl Seekable<[u8]> for MVCCRangeIterator {
     }
 }
 
+/// Iterates the keys visible at a sequence number in descending order, yielding owned copies
+/// since the versions of each key must be read past before its visible version is known.
+pub(crate) struct MVCCReverseRangeIterator {
+    storage_name: Arc<String>,
+    keyspace_id: KeyspaceId,
+    iterator: Peekable<KeyspaceRangeIterator>,
+    open_sequence_number: SequenceNumber,
+    storage_counters: StorageCounters,
+    excluded_start: Option<ByteArray<BUFFER_KEY_INLINE>>,
+}
+
+impl MVCCReverseRangeIterator {
+    pub(crate) fn new<D, const PS: usize>(
+        storage: &MVCCStorage<D>,
+        iterpool: &IteratorPool,
+        range: &KeyRange<StorageKey<'_, PS>>,
+        open_sequence_number: SequenceNumber,
+        storage_counters: StorageCounters,
+    ) -> Self {
+        let keyspace = storage.get_keyspace(range.start().get_value().keyspace_id());
+        let mapped_range = range.map(|key| key.as_bytes(), |fixed_width| fixed_width);
+        let iterator = keyspace.iterate_range_reverse(iterpool, &mapped_range, storage_counters.clone());
+        let excluded_start = match range.start() {
+            RangeStart::ExcludeFirstWithPrefix(start) => Some(ByteArray::copy(start.bytes())),
+            RangeStart::Inclusive(_) | RangeStart::ExcludePrefix(_) => None,
+        };
+        MVCCReverseRangeIterator {
+            storage_name: storage.name(),
+            keyspace_id: keyspace.id(),
+            iterator: Peekable::new(iterator),
+            open_sequence_number,
+            storage_counters,
+            excluded_start,
+        }
+    }
+
+    fn accept(
+        &self,
+        key: ByteArray<BUFFER_KEY_INLINE>,
+        version: Option<(StorageOperation, ByteArray<BUFFER_VALUE_INLINE>)>,
+    ) -> Option<(StorageKeyArray<BUFFER_KEY_INLINE>, ByteArray<BUFFER_VALUE_INLINE>)> {
+        match version? {
+            _ if self.excluded_start.as_ref() == Some(&key) => None,
+            (StorageOperation::Insert, value) => {
+                self.storage_counters.increment_advance_mvcc_visible();
+                Some((StorageKeyArray::new_raw(self.keyspace_id, key), value))
+            }
+            (StorageOperation::Delete, _) => {
+                self.storage_counters.increment_advance_mvcc_deleted();
+                None
+            }
+        }
+    }
+}
+
+impl Iterator for MVCCReverseRangeIterator {
+    type Item = Result<(StorageKeyArray<BUFFER_KEY_INLINE>, ByteArray<BUFFER_VALUE_INLINE>), MVCCReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // versions of a key are ordered newest first, so in reverse the newest visible version of a key
+        // is the last visible version read before the key changes
+        let mut current: Option<(
+            ByteArray<BUFFER_KEY_INLINE>,
+            Option<(StorageOperation, ByteArray<BUFFER_VALUE_INLINE>)>,
+        )> = None;
+        loop {
+            let (raw_key, value) = match self.iterator.peek() {
+                None => break,
+                Some(Ok((raw_key, value))) => (*raw_key, *value),
+                Some(Err(error)) => {
+                    let error = MVCCReadError::Keyspace {
+                        storage_name: self.storage_name.clone(),
+                        source: Arc::new(error.clone()),
+                    };
+                    self.iterator.next();
+                    return Some(Err(error));
+                }
+            };
+            let mvcc_key = MVCCKey::wrap_slice(raw_key);
+            if current.as_ref().is_some_and(|(key, _)| &**key != mvcc_key.key()) {
+                let (key, version) = current.take().unwrap();
+                if let Some(accepted) = self.accept(key, version) {
+                    return Some(Ok(accepted));
+                }
+                continue;
+            }
+            let version = if mvcc_key.is_visible_to(self.open_sequence_number) {
+                Some((mvcc_key.operation(), ByteArray::copy(value)))
+            } else {
+                self.storage_counters.increment_advance_mvcc_invisible();
+                None
+            };
+            match current.as_mut() {
+                Some((_, current_version)) => {
+                    if version.is_some() {
+                        *current_version = version;
+                    }
+                }
+                None => current = Some((ByteArray::copy(mvcc_key.key()), version)),
+            }
+            self.iterator.next();
+        }
+        let (key, version) = current?;
+        self.accept(key, version).map(Ok)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum MVCCReadError {
     Keyspace { storage_name: Arc<String>, source: Arc<KeyspaceError> },
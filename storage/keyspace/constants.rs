@@ -10,3 +10,8 @@ pub(crate) mod rocksdb {
     pub(crate) const PROPERTY_ESTIMATE_LIVE_DATA_SIZE: &str = "rocksdb.estimate-live-data-size";
     pub(crate) const PROPERTY_ESTIMATE_NUM_KEYS: &str = "rocksdb.estimate-num-keys";
 }
+
+// Each keyspace keeps its storage format version in a separate column family, so it is never seen by MVCC iterators
+// but is still captured by RocksDB checkpoints.
+pub(crate) const FORMAT_COLUMN_FAMILY_NAME: &str = "storage_format";
+pub(crate) const FORMAT_VERSION_KEY: &[u8] = b"version";
@@ -0,0 +1,66 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+
+/// The version of the key/value encoding stored in a keyspace. It is persisted alongside the keyspace data, so that
+/// it travels with checkpoints, and compared on open against the version the `KeyspaceSet` declares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StorageFormatVersion(pub u32);
+
+impl StorageFormatVersion {
+    /// Keyspaces written before format versions were recorded, or rebuilt from the WAL without a checkpoint.
+    pub const UNVERSIONED: Self = Self(0);
+
+    pub(crate) const fn to_be_bytes(self) -> [u8; 4] {
+        self.0.to_be_bytes()
+    }
+
+    pub(crate) fn from_be_bytes(bytes: &[u8]) -> Self {
+        Self(u32::from_be_bytes(bytes.try_into().expect("Storage format version must be 4 bytes")))
+    }
+}
+
+impl fmt::Display for StorageFormatVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpgradeMode {
+    /// Rewrites every key before the storage finishes opening.
+    Eager,
+    /// Lets the storage open with keys in both the old and new format, and rewrites them in the background.
+    /// Readers must accept either format until the keyspace reports the new version.
+    Lazy,
+}
+
+/// What a format upgrade does with a single key/value pair. Keys are given without their MVCC suffix, which the
+/// storage preserves on any replacement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyRewrite {
+    Keep,
+    Replace { key: Vec<u8>, value: Vec<u8> },
+    Delete,
+}
+
+/// A step bringing a keyspace up to `version`.
+///
+/// Rewrites must be idempotent and keep keys that are already in the new format: a keyspace rebuilt from the WAL has
+/// no recorded version, so every upgrade is re-run over data written both before and after it was first applied.
+pub struct KeyspaceUpgrade {
+    pub version: StorageFormatVersion,
+    pub description: &'static str,
+    pub mode: UpgradeMode,
+    pub rewrite: fn(&[u8], &[u8]) -> KeyRewrite,
+}
+
+impl fmt::Debug for KeyspaceUpgrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "KeyspaceUpgrade[version={}, mode={:?}, description={}]", self.version, self.mode, self.description)
+    }
+}
@@ -26,6 +26,7 @@ enum ContinueCondition {
     ExactPrefix(ByteArray<48>),
     EndPrefixInclusive(ByteArray<48>),
     EndPrefixExclusive(ByteArray<48>),
+    StartInclusive(ByteArray<48>),
     Always,
 }
 
@@ -66,6 +67,43 @@ impl KeyspaceRangeIterator {
         KeyspaceRangeIterator { iterator, continue_condition, keyspace_name: keyspace.name(), is_finished: false }
     }
 
+    /// Creates an iterator over the range in descending key order.
+    /// A start that excludes its first key is treated as inclusive, leaving the exclusion to the caller.
+    pub(crate) fn new_reverse<'a, const INLINE_BYTES: usize>(
+        keyspace: &'a Keyspace,
+        iterpool: &IteratorPool,
+        range: &KeyRange<Bytes<'a, INLINE_BYTES>>,
+        storage_counters: StorageCounters,
+    ) -> Self {
+        let start: ByteArray<48> = match range.start() {
+            RangeStart::Inclusive(bytes) | RangeStart::ExcludeFirstWithPrefix(bytes) => ByteArray::from(&**bytes),
+            RangeStart::ExcludePrefix(bytes) => {
+                let mut cloned = ByteArray::from(&**bytes);
+                cloned.increment().unwrap();
+                cloned
+            }
+        };
+        let end_prefix_exclusive = |prefix: &[u8]| {
+            let mut end: ByteArray<48> = ByteArray::from(prefix);
+            // a prefix of only 0xFF bytes has no successor, so the range is unbounded above
+            end.increment().ok().map(|()| end)
+        };
+        let end_exclusive = match range.end() {
+            RangeEnd::WithinStartAsPrefix => end_prefix_exclusive(range.start().get_value()),
+            RangeEnd::EndPrefixInclusive(end) => end_prefix_exclusive(end),
+            RangeEnd::EndPrefixExclusive(end) => Some(ByteArray::from(&**end)),
+            RangeEnd::Unbounded => None,
+        };
+        let raw_iterator = iterpool.get_iterator_unprefixed(keyspace);
+        let iterator = DBIterator::new_from_reverse(raw_iterator, end_exclusive.as_deref(), storage_counters);
+        KeyspaceRangeIterator {
+            iterator,
+            continue_condition: ContinueCondition::StartInclusive(start),
+            keyspace_name: keyspace.name(),
+            is_finished: false,
+        }
+    }
+
     fn may_skip_start(iterator: &mut DBIterator, excluded_value: &[u8]) {
         if iterator.peek().is_some_and(|result| result.is_ok_and(|(key, _)| key == excluded_value)) {
             iterator.next();
@@ -89,6 +127,7 @@ impl KeyspaceRangeIterator {
                         // otherwise, the key is longer and we check the corresponding ranges
                         end_exclusive.starts_with(key) || &key[0..end_exclusive.len()] < end_exclusive
                     }
+                    ContinueCondition::StartInclusive(start) => *key >= &**start,
                     ContinueCondition::Always => true,
                 }
             }
@@ -13,11 +13,20 @@ use std::{
 
 use bytes::{util::MB, Bytes};
 use itertools::Itertools;
-use resource::{constants::storage::ROCKSDB_CACHE_SIZE_MB, profile::StorageCounters};
-use rocksdb::{checkpoint::Checkpoint, IteratorMode, Options, ReadOptions, WriteBatch, WriteOptions, DB};
+use resource::{
+    constants::storage::{ROCKSDB_CACHE_SIZE_MB, ROCKSDB_VERIFY_CHECKSUMS},
+    profile::StorageCounters,
+};
+use rocksdb::{
+    checkpoint::Checkpoint, ColumnFamilyDescriptor, ErrorKind, IteratorMode, Options, ReadOptions, WriteBatch,
+    WriteOptions, DB, DEFAULT_COLUMN_FAMILY_NAME,
+};
 use serde::{Deserialize, Serialize};
 
-use super::{constants, iterator, IteratorPool};
+use super::{
+    constants, iterator, CorruptRange, IteratorPool, KeyRewrite, KeyspaceUpgrade, KeyspaceVerification,
+    StorageFormatVersion,
+};
 use crate::{key_range::KeyRange, write_batches::WriteBatches};
 
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -52,6 +61,15 @@ pub trait KeyspaceSet: Copy {
         options
     }
     fn prefix_length(&self) -> Option<usize>;
+
+    fn format_version(&self) -> StorageFormatVersion {
+        StorageFormatVersion::UNVERSIONED
+    }
+
+    /// Upgrades ordered by version, none of them beyond `format_version()`.
+    fn format_upgrades(&self) -> &'static [KeyspaceUpgrade] {
+        &[]
+    }
 }
 
 #[derive(Debug)]
@@ -135,6 +153,10 @@ impl Keyspaces {
         Ok(())
     }
 
+    pub(crate) fn verify(&self) -> Result<Vec<KeyspaceVerification>, KeyspaceError> {
+        self.keyspaces.iter().map(|keyspace| keyspace.verify()).collect()
+    }
+
     pub(crate) fn reset(&mut self) -> Result<(), KeyspaceError> {
         for keyspace in self.keyspaces.iter_mut() {
             keyspace.reset()?
@@ -204,13 +226,21 @@ impl Keyspace {
         use KeyspaceOpenError::RocksDB;
         let name = keyspace.name();
         let path = storage_path.join(name);
-        let kv_storage = DB::open(options, &path).map_err(|error| RocksDB { name, source: error })?;
+        let mut db_options = options.clone();
+        db_options.create_missing_column_families(true);
+        let column_families = [
+            ColumnFamilyDescriptor::new(DEFAULT_COLUMN_FAMILY_NAME, options.clone()),
+            ColumnFamilyDescriptor::new(constants::FORMAT_COLUMN_FAMILY_NAME, Options::default()),
+        ];
+        let kv_storage = DB::open_cf_descriptors(&db_options, &path, column_families)
+            .map_err(|error| RocksDB { name, source: error })?;
         Ok(Self::new(path, keyspace, kv_storage))
     }
 
     fn new(path: PathBuf, keyspace: impl KeyspaceSet, kv_storage: DB) -> Self {
         // initial read options, should be customised to this storage's properties
-        let read_options = ReadOptions::default();
+        let mut read_options = ReadOptions::default();
+        read_options.set_verify_checksums(ROCKSDB_VERIFY_CHECKSUMS);
         let mut write_options = WriteOptions::default();
         write_options.disable_wal(true);
         let prefix_length = keyspace.prefix_length();
@@ -220,6 +250,7 @@ impl Keyspace {
     pub(super) fn new_read_options(&self) -> ReadOptions {
         let mut options = ReadOptions::default();
         options.set_total_order_seek(true); // Set this to 'false' to use bloom-filters
+        options.set_verify_checksums(ROCKSDB_VERIFY_CHECKSUMS);
         options
     }
 
@@ -269,6 +300,15 @@ impl Keyspace {
         iterator::KeyspaceRangeIterator::new(self, iterpool, range, storage_counters)
     }
 
+    pub(crate) fn iterate_range_reverse<const PREFIX_INLINE_SIZE: usize>(
+        &self,
+        iterpool: &IteratorPool,
+        range: &KeyRange<Bytes<'_, PREFIX_INLINE_SIZE>>,
+        storage_counters: StorageCounters,
+    ) -> iterator::KeyspaceRangeIterator {
+        iterator::KeyspaceRangeIterator::new_reverse(self, iterpool, range, storage_counters)
+    }
+
     pub(crate) fn write(&self, write_batch: WriteBatch) -> Result<(), KeyspaceError> {
         self.kv_storage
             .write_opt(write_batch, &self.write_options)
@@ -297,6 +337,114 @@ impl Keyspace {
         Ok(())
     }
 
+    pub(crate) fn format_version(&self) -> Result<Option<StorageFormatVersion>, KeyspaceError> {
+        let column_family = self.format_column_family();
+        self.kv_storage
+            .get_pinned_cf(column_family, constants::FORMAT_VERSION_KEY)
+            .map(|option| option.map(|value| StorageFormatVersion::from_be_bytes(&value)))
+            .map_err(|error| KeyspaceError::Get { name: self.name, source: error })
+    }
+
+    pub(crate) fn set_format_version(&self, version: StorageFormatVersion) -> Result<(), KeyspaceError> {
+        // the version must survive a crash even though data writes skip the RocksDB WAL: the keys it describes are
+        // already flushed by the upgrade that precedes it
+        let column_family = self.format_column_family();
+        self.kv_storage
+            .put_cf(column_family, constants::FORMAT_VERSION_KEY, version.to_be_bytes())
+            .and_then(|()| self.kv_storage.flush_cf(column_family))
+            .map_err(|error| KeyspaceError::Put { name: self.name, source: error })
+    }
+
+    fn format_column_family(&self) -> &rocksdb::ColumnFamily {
+        self.kv_storage
+            .cf_handle(constants::FORMAT_COLUMN_FAMILY_NAME)
+            .expect("Keyspaces are always opened with the format column family")
+    }
+
+    /// Applies `rewrite` to every raw key/value pair, committing in batches of `batch_size` rewritten keys.
+    /// The iterator reads from an implicit snapshot, so keys written by the rewrite are not revisited.
+    pub(crate) fn rewrite_all(
+        &self,
+        batch_size: usize,
+        mut rewrite: impl FnMut(&[u8], &[u8]) -> KeyRewrite,
+    ) -> Result<usize, KeyspaceError> {
+        let mut rewritten = 0;
+        let mut batch = WriteBatch::default();
+        for entry in self.kv_storage.iterator(IteratorMode::Start) {
+            let (key, value) = entry.map_err(|err| KeyspaceError::Iterate { name: self.name, source: err })?;
+            match rewrite(&key, &value) {
+                KeyRewrite::Keep => continue,
+                KeyRewrite::Delete => batch.delete(&key),
+                KeyRewrite::Replace { key: new_key, value: new_value } => {
+                    if new_key != *key {
+                        batch.delete(&key);
+                    }
+                    batch.put(new_key, new_value);
+                }
+            }
+            rewritten += 1;
+            if batch.len() >= batch_size {
+                self.write(std::mem::take(&mut batch))?;
+            }
+        }
+        if !batch.is_empty() {
+            self.write(batch)?;
+        }
+        self.kv_storage.flush().map_err(|error| KeyspaceError::BatchWrite { name: self.name, source: error })?;
+        Ok(rewritten)
+    }
+
+    /// Reads the key range of every live storage file with checksums verified, bypassing the block cache so that
+    /// blocks are read from disk. The key range of a file may overlap files at other levels, so a corrupt block
+    /// is reported against the file named by the error, which is not necessarily the file whose range was read.
+    pub(crate) fn verify(&self) -> Result<KeyspaceVerification, KeyspaceError> {
+        let mut files =
+            self.kv_storage.live_files().map_err(|source| KeyspaceError::Verify { name: self.name, source })?;
+        files.retain(|file| file.column_family_name == DEFAULT_COLUMN_FAMILY_NAME);
+
+        let mut keys_checked = 0;
+        let mut corrupt_ranges = Vec::new();
+        for file in &files {
+            let mut read_options = self.new_read_options();
+            read_options.set_verify_checksums(true);
+            read_options.fill_cache(false);
+            let mut iterator = self.kv_storage.raw_iterator_opt(read_options);
+            match &file.start_key {
+                Some(start_key) => iterator.seek(start_key),
+                None => iterator.seek_to_first(),
+            }
+            while let Some(key) = iterator.key() {
+                if file.end_key.as_ref().is_some_and(|end_key| key > end_key.as_slice()) {
+                    break;
+                }
+                keys_checked += 1;
+                iterator.next();
+            }
+            match iterator.status() {
+                Ok(()) => (),
+                Err(error) if error.kind() == ErrorKind::Corruption => {
+                    let message = error.into_string();
+                    let corrupt_file =
+                        files.iter().find(|candidate| message.contains(candidate.name.as_str())).unwrap_or(file);
+                    if !corrupt_ranges.iter().any(|range: &CorruptRange| range.file == corrupt_file.name) {
+                        corrupt_ranges.push(CorruptRange {
+                            file: corrupt_file.name.clone(),
+                            level: corrupt_file.level,
+                            start_key: corrupt_file.start_key.clone(),
+                            end_key: corrupt_file.end_key.clone(),
+                            entries: corrupt_file.num_entries,
+                            message,
+                            salvaged: false,
+                        });
+                    }
+                }
+                Err(source) => return Err(KeyspaceError::Verify { name: self.name, source }),
+            }
+        }
+
+        Ok(KeyspaceVerification { keyspace: self.name, files_checked: files.len(), keys_checked, corrupt_ranges })
+    }
+
     pub(crate) fn reset(&mut self) -> Result<(), KeyspaceError> {
         let iterator = self.kv_storage.iterator(IteratorMode::Start);
         for entry in iterator {
@@ -398,6 +546,7 @@ pub enum KeyspaceError {
     Iterate { name: &'static str, source: rocksdb::Error },
     DeleteRange { name: &'static str, source: rocksdb::Error },
     Property { name: &'static str, source: rocksdb::Error },
+    Verify { name: &'static str, source: rocksdb::Error },
 }
 
 impl fmt::Display for KeyspaceError {
@@ -415,6 +564,7 @@ impl Error for KeyspaceError {
             Self::Iterate { source, .. } => Some(source),
             Self::DeleteRange { source, .. } => Some(source),
             Self::Property { source, .. } => Some(source),
+            Self::Verify { source, .. } => Some(source),
         }
     }
 }
@@ -4,16 +4,20 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
+pub use format::{KeyRewrite, KeyspaceUpgrade, StorageFormatVersion, UpgradeMode};
 pub(crate) use keyspace::{Keyspace, KeyspaceCheckpointError, KeyspaceError, Keyspaces, KEYSPACE_MAXIMUM_COUNT};
 pub use keyspace::{KeyspaceDeleteError, KeyspaceId, KeyspaceOpenError, KeyspaceSet, KeyspaceValidationError};
 use rocksdb::{DBRawIterator, DB};
+pub use verify::{CorruptRange, KeyspaceVerification};
 
 use crate::snapshot::pool::{PoolRecycleGuard, Poolable, SinglePool};
 
 mod constants;
+mod format;
 pub mod iterator;
 mod keyspace;
 mod raw_iterator;
+mod verify;
 
 impl Poolable for DBRawIterator<'static> {}
 
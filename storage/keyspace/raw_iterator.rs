@@ -49,6 +49,7 @@ impl IteratorItemState {
 pub(super) struct DBIterator {
     iterator: PoolRecycleGuard<DBRawIterator<'static>>,
     storage_counters: StorageCounters,
+    reverse: bool,
     // NOTE: when item is empty, that means that the kv pair the Rocks iterator _is currently pointing to_
     //       has been yielded to the user, and the underlying iterator needs to be advanced before  reading
     state: IteratorItemState,
@@ -62,11 +63,32 @@ impl DBIterator {
     ) -> Self {
         iterator.seek(start);
         storage_counters.increment_raw_seek();
-        let mut this = Self { iterator, state: IteratorItemState::None, storage_counters };
+        let mut this = Self { iterator, state: IteratorItemState::None, storage_counters, reverse: false };
         this.record_iterator_state(); // initialise with the first state read from the seek'ed value
         this
     }
 
+    /// Creates an iterator moving backwards from the last key before `end_exclusive`, or from the last key.
+    pub(super) fn new_from_reverse(
+        mut iterator: PoolRecycleGuard<DBRawIterator<'static>>,
+        end_exclusive: Option<&[u8]>,
+        storage_counters: StorageCounters,
+    ) -> Self {
+        match end_exclusive {
+            Some(end) => {
+                iterator.seek_for_prev(end);
+                if iterator.key() == Some(end) {
+                    iterator.prev();
+                }
+            }
+            None => iterator.seek_to_last(),
+        }
+        storage_counters.increment_raw_seek();
+        let mut this = Self { iterator, state: IteratorItemState::None, storage_counters, reverse: true };
+        this.record_iterator_state();
+        this
+    }
+
     pub(super) fn peek(&mut self) -> Option<<Self as LendingIterator>::Item<'_>> {
         let state = self.next_internal();
         self.state = state;
@@ -83,7 +105,11 @@ impl DBIterator {
             self.state.take_value_else_retain()
         } else {
             self.storage_counters.increment_raw_advance();
-            self.iterator.next();
+            if self.reverse {
+                self.iterator.prev();
+            } else {
+                self.iterator.next();
+            }
             self.record_iterator_state();
             self.state.take_value_else_retain()
         }
@@ -125,7 +151,8 @@ impl Seekable<[u8]> for DBIterator {
         if matches!(&self.state, IteratorItemState::Finished) {
             return;
         } else if let IteratorItemState::Some((item_key, _)) = &self.state {
-            match (*item_key).cmp(key) {
+            let ordering = if self.reverse { key.cmp(item_key) } else { (*item_key).cmp(key) };
+            match ordering {
                 Ordering::Less => {
                     // fall through
                 }
@@ -138,7 +165,11 @@ impl Seekable<[u8]> for DBIterator {
             }
         }
         self.state.take_value_else_retain();
-        self.iterator.seek(key);
+        if self.reverse {
+            self.iterator.seek_for_prev(key);
+        } else {
+            self.iterator.seek(key);
+        }
         self.storage_counters.increment_raw_seek();
         self.record_iterator_state()
     }
@@ -0,0 +1,33 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+/// The outcome of reading every block of a keyspace with checksum verification.
+#[derive(Debug, Clone)]
+pub struct KeyspaceVerification {
+    pub keyspace: &'static str,
+    pub files_checked: usize,
+    pub keys_checked: u64,
+    pub corrupt_ranges: Vec<CorruptRange>,
+}
+
+impl KeyspaceVerification {
+    pub fn is_intact(&self) -> bool {
+        self.corrupt_ranges.is_empty()
+    }
+}
+
+/// The key range of a storage file in which a block failed checksum verification.
+/// Salvaging rebuilds the storage from the WAL, replacing the file.
+#[derive(Debug, Clone)]
+pub struct CorruptRange {
+    pub file: String,
+    pub level: i32,
+    pub start_key: Option<Vec<u8>>,
+    pub end_key: Option<Vec<u8>>,
+    pub entries: u64,
+    pub message: String,
+    pub salvaged: bool,
+}
@@ -35,6 +35,7 @@ pub struct Checkpoint {
 
 impl Checkpoint {
     const CHECKPOINT_DIR_NAME: &'static str = "checkpoint";
+    const SET_ASIDE_CHECKPOINT_DIR_NAME: &'static str = "checkpoint-set-aside";
     const STORAGE_METADATA_FILE_NAME: &'static str = "STORAGE_METADATA";
 
     pub fn new(storage_path: &Path) -> Result<Self, CheckpointCreateError> {
@@ -110,6 +111,50 @@ impl Checkpoint {
         Ok(())
     }
 
+    /// Moves every checkpoint aside, so that the storage is next loaded by replaying the entire WAL.
+    /// The checkpoints are kept until they are either restored or removed.
+    pub fn set_aside_all(storage_path: &Path) -> Result<(), CheckpointCreateError> {
+        let checkpoint_dir = storage_path.join(Self::CHECKPOINT_DIR_NAME);
+        let set_aside_dir = storage_path.join(Self::SET_ASIDE_CHECKPOINT_DIR_NAME);
+        Self::remove_dir_if_exists(&set_aside_dir)?;
+        if checkpoint_dir.exists() {
+            fs::rename(&checkpoint_dir, &set_aside_dir).map_err(|error| CheckpointCreateError::CheckpointDirMove {
+                dir: checkpoint_dir,
+                source: Arc::new(error),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the current checkpoints with the ones moved aside by `set_aside_all`.
+    pub fn restore_set_aside(storage_path: &Path) -> Result<(), CheckpointCreateError> {
+        let checkpoint_dir = storage_path.join(Self::CHECKPOINT_DIR_NAME);
+        let set_aside_dir = storage_path.join(Self::SET_ASIDE_CHECKPOINT_DIR_NAME);
+        if set_aside_dir.exists() {
+            Self::remove_dir_if_exists(&checkpoint_dir)?;
+            fs::rename(&set_aside_dir, &checkpoint_dir).map_err(|error| CheckpointCreateError::CheckpointDirMove {
+                dir: set_aside_dir,
+                source: Arc::new(error),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Removes the checkpoints moved aside by `set_aside_all`.
+    pub fn remove_set_aside(storage_path: &Path) -> Result<(), CheckpointCreateError> {
+        Self::remove_dir_if_exists(&storage_path.join(Self::SET_ASIDE_CHECKPOINT_DIR_NAME))
+    }
+
+    fn remove_dir_if_exists(dir: &Path) -> Result<(), CheckpointCreateError> {
+        if dir.exists() {
+            fs::remove_dir_all(dir).map_err(|error| CheckpointCreateError::OldCheckpointRemove {
+                dir: dir.to_owned(),
+                source: Arc::new(error),
+            })?;
+        }
+        Ok(())
+    }
+
     pub fn open_latest(storage_path: &Path) -> Result<Option<Self>, CheckpointLoadError> {
         let checkpoint_dir = storage_path.join(Self::CHECKPOINT_DIR_NAME);
         find_latest_checkpoint(&checkpoint_dir).map(|path| path.map(|p| Checkpoint { directory: p }))
@@ -236,6 +281,7 @@ pub enum CheckpointCreateError {
     ExtensionSerialise { name: String, source: Arc<bincode::Error> },
 
     OldCheckpointRemove { dir: PathBuf, source: Arc<io::Error> },
+    CheckpointDirMove { dir: PathBuf, source: Arc<io::Error> },
 }
 
 impl fmt::Display for CheckpointCreateError {
@@ -257,6 +303,7 @@ impl Error for CheckpointCreateError {
             Self::ExtensionIO { source, .. } => Some(source),
             Self::ExtensionSerialise { source, .. } => Some(source),
             Self::OldCheckpointRemove { source, .. } => Some(source),
+            Self::CheckpointDirMove { source, .. } => Some(source),
         }
     }
 }
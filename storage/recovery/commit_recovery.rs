@@ -4,7 +4,10 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 
 use durability::RawRecord;
 use error::typedb_error;
@@ -80,6 +83,36 @@ pub fn load_commit_data_from(
     Ok(recovered_commits)
 }
 
+/// Rejects every commit from the start onwards which was written but never resolved,
+/// so that recovery does not validate and apply them. Returns the rejected commits.
+pub fn reject_unresolved_commits_from(
+    start: SequenceNumber,
+    durability_client: &impl DurabilityClient,
+) -> Result<Vec<SequenceNumber>, DurabilityClientError> {
+    let mut unresolved = BTreeSet::new();
+    for record in durability_client.iter_from(start)? {
+        let RawRecord { sequence_number, record_type, bytes } = record?;
+        match record_type {
+            CommitRecord::RECORD_TYPE if sequence_number >= start => {
+                unresolved.insert(sequence_number);
+            }
+            StatusRecord::RECORD_TYPE => {
+                let status = StatusRecord::deserialise_from(&mut &*bytes)?;
+                unresolved.remove(&status.commit_record_sequence_number);
+            }
+            _ => (),
+        }
+    }
+    if unresolved.is_empty() {
+        return Ok(Vec::new());
+    }
+    for sequence_number in &unresolved {
+        durability_client.unsequenced_write(&StatusRecord::new(*sequence_number, false))?;
+    }
+    durability_client.request_sync().recv().map_err(|_| DurabilityClientError::SyncFailed {})?;
+    Ok(unresolved.into_iter().collect())
+}
+
 pub(crate) fn apply_recovered(
     recovered_commits: BTreeMap<SequenceNumber, RecoveryCommitStatus>,
     durability_client: &impl DurabilityClient,
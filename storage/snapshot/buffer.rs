@@ -28,7 +28,7 @@ use crate::{
     snapshot::{lock::LockType, write::Write},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct OperationsBuffer {
     write_buffers: [WriteBuffer; KEYSPACE_MAXIMUM_COUNT],
     locks: BTreeMap<ByteArray<BUFFER_KEY_INLINE>, LockType>,
@@ -95,7 +95,7 @@ impl OperationsBuffer {
         }
     }
 
-    pub(crate) fn len(&self) -> usize {
+    pub fn len(&self) -> usize {
         self.write_buffers().map(|w| w.writes().len()).sum()
     }
 }
@@ -116,7 +116,7 @@ impl<'a> IntoIterator for &'a OperationsBuffer {
 //       3) We would benefit hugely from a table where writes are never moved, so we can freely
 //          take references to existing writes without having to Clone them out every time... This
 //          might lead us to a RocksDB-like Buffer+Index structure
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct WriteBuffer {
     pub(crate) keyspace_id: KeyspaceId,
     writes: BTreeMap<ByteArray<BUFFER_KEY_INLINE>, Write>,
@@ -202,6 +202,28 @@ impl WriteBuffer {
         )
     }
 
+    pub(crate) fn iterate_range_reverse<const INLINE: usize>(
+        &self,
+        range: KeyRange<Bytes<'_, INLINE>>,
+    ) -> BufferRangeIterator {
+        let (range_start, range_end, _) = range.into_raw();
+        let exclusive_end_bytes = Self::compute_exclusive_end(&range_start, &range_end);
+        let end = if matches!(range_end, RangeEnd::Unbounded) {
+            Bound::Unbounded
+        } else {
+            Bound::Excluded(&*exclusive_end_bytes)
+        };
+        let start_as_bound = Self::range_start_as_bound(range_start);
+        let start_bytes = start_as_bound.as_ref().map(|bytes| bytes.as_ref());
+        BufferRangeIterator::new(
+            self.writes
+                .range::<[u8], _>((start_bytes, end))
+                .rev()
+                .map(|(key, val)| (StorageKeyArray::new_raw(self.keyspace_id, key.clone()), val.clone()))
+                .collect::<Vec<_>>(),
+        )
+    }
+
     // TODO: if the iterate_range becomes zero-copy, then we can eliminate this method
     pub(crate) fn any_not_deleted_in_range<const INLINE: usize>(&self, range: KeyRange<Bytes<'_, INLINE>>) -> bool {
         let (range_start, range_end, _) = range.into_raw();
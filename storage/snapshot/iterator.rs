@@ -10,6 +10,7 @@ use std::{
     error::Error,
     fmt,
     hash::Hash,
+    iter::Peekable,
     sync::Arc,
 };
 
@@ -18,7 +19,7 @@ use lending_iterator::{LendingIterator, Seekable};
 use resource::constants::snapshot::{BUFFER_KEY_INLINE, BUFFER_VALUE_INLINE};
 
 use crate::{
-    iterator::{MVCCRangeIterator, MVCCReadError},
+    iterator::{MVCCRangeIterator, MVCCReadError, MVCCReverseRangeIterator},
     key_value::{StorageKey, StorageKeyArray, StorageKeyReference},
     snapshot::{buffer::BufferRangeIterator, write::Write},
 };
@@ -228,6 +229,67 @@ impl LendingIterator for SnapshotRangeIterator {
     }
 }
 
+/// Iterates a range of the snapshot in descending key order, merging the buffered writes over storage.
+pub struct SnapshotReverseRangeIterator {
+    storage_iterator: Option<Peekable<MVCCReverseRangeIterator>>,
+    buffered_iterator: Option<BufferRangeIterator>,
+}
+
+impl SnapshotReverseRangeIterator {
+    pub(crate) fn new(mvcc_iterator: MVCCReverseRangeIterator, buffered_iterator: Option<BufferRangeIterator>) -> Self {
+        SnapshotReverseRangeIterator { storage_iterator: Some(mvcc_iterator.peekable()), buffered_iterator }
+    }
+
+    // for testing
+    pub fn new_empty() -> Self {
+        SnapshotReverseRangeIterator { storage_iterator: None, buffered_iterator: None }
+    }
+
+    fn storage_next(
+        &mut self,
+    ) -> Option<Result<(StorageKeyArray<BUFFER_KEY_INLINE>, ByteArray<BUFFER_VALUE_INLINE>), Arc<SnapshotIteratorError>>>
+    {
+        self.storage_iterator
+            .as_mut()?
+            .next()
+            .map(|result| result.map_err(|source| Arc::new(SnapshotIteratorError::MVCCRead { source })))
+    }
+}
+
+impl Iterator for SnapshotReverseRangeIterator {
+    type Item =
+        Result<(StorageKeyArray<BUFFER_KEY_INLINE>, ByteArray<BUFFER_VALUE_INLINE>), Arc<SnapshotIteratorError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let storage_key = match self.storage_iterator.as_mut().and_then(|iter| iter.peek()) {
+                None => None,
+                Some(Ok((key, _))) => Some(key.clone()),
+                Some(Err(_)) => return self.storage_next(),
+            };
+            let Some((buffered_key, _)) = self.buffered_iterator.as_mut().and_then(|iter| iter.peek()) else {
+                return self.storage_next();
+            };
+            let ordering = match &storage_key {
+                None => Ordering::Greater,
+                Some(storage_key) => buffered_key.cmp(storage_key),
+            };
+            match ordering {
+                Ordering::Less => return self.storage_next(),
+                Ordering::Equal => {
+                    // the buffered write replaces the stored value
+                    let _ = self.storage_next();
+                }
+                Ordering::Greater => (),
+            }
+            let (key, write) = self.buffered_iterator.as_mut().unwrap().next().unwrap();
+            if !write.is_delete() {
+                return Some(Ok((key, write.into_value())));
+            }
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 enum ReadyItemSource {
     Storage,
@@ -16,7 +16,7 @@ use resource::{
 
 use crate::{
     durability_client::DurabilityClient,
-    isolation_manager::{CommitRecord, CommitType, ReaderDropGuard},
+    isolation_manager::{CommitRecord, CommitType, IsolationConflict, ReaderDropGuard},
     iterator::MVCCReadError,
     key_range::KeyRange,
     key_value::{StorageKey, StorageKeyArray, StorageKeyReference},
@@ -24,11 +24,11 @@ use crate::{
     sequence_number::SequenceNumber,
     snapshot::{
         buffer::{BufferRangeIterator, OperationsBuffer},
-        iterator::SnapshotRangeIterator,
+        iterator::{SnapshotRangeIterator, SnapshotReverseRangeIterator},
         lock::LockType,
         write::Write,
     },
-    MVCCStorage, StorageCommitError,
+    MVCCStorage, PreparedCommit, StorageCommitError,
 };
 
 macro_rules! get_mapped_method {
@@ -80,6 +80,15 @@ pub trait ReadableSnapshot {
         storage_counters: StorageCounters,
     ) -> SnapshotRangeIterator;
 
+    /// Iterates the range in descending key order.
+    /// The instruction iterators only seek forwards, so query execution does not read through this:
+    /// a descending `sort` still collects its input before sorting it.
+    fn iterate_range_reverse<const PS: usize>(
+        &self,
+        range: &KeyRange<StorageKey<'_, PS>>,
+        storage_counters: StorageCounters,
+    ) -> SnapshotReverseRangeIterator;
+
     fn any_in_range<const PS: usize>(&self, range: &KeyRange<StorageKey<'_, PS>>, buffered_only: bool) -> bool;
 
     // --- we are slightly breaking the abstraction and Rust model by mimicking polymorphism for the following methods ---
@@ -263,6 +272,20 @@ impl<D> ReadableSnapshot for ReadSnapshot<D> {
         SnapshotRangeIterator::new(mvcc_iterator, None)
     }
 
+    fn iterate_range_reverse<const PS: usize>(
+        &self,
+        range: &KeyRange<StorageKey<'_, PS>>,
+        storage_counters: StorageCounters,
+    ) -> SnapshotReverseRangeIterator {
+        let mvcc_iterator = self.storage.iterate_range_reverse(
+            self.iterator_pool(),
+            range,
+            self.open_sequence_number,
+            storage_counters,
+        );
+        SnapshotReverseRangeIterator::new(mvcc_iterator, None)
+    }
+
     fn any_in_range<const PS: usize>(&self, range: &KeyRange<StorageKey<'_, PS>>, buffered_only: bool) -> bool {
         !buffered_only
             && self
@@ -336,6 +359,24 @@ impl<D> WriteSnapshot<D> {
     }
 }
 
+impl<D: DurabilityClient> WriteSnapshot<D> {
+    /// Durably writes and validates the snapshot's commit without applying it, so that it may be committed
+    /// atomically with snapshots of other storages. Empty snapshots have nothing to prepare.
+    pub fn prepare_commit(
+        self,
+        commit_profile: &mut CommitProfile,
+    ) -> Result<Option<PreparedCommit<D>>, SnapshotError> {
+        if self.operations.is_writes_empty() && self.operations.locks_empty() {
+            Ok(None)
+        } else {
+            match self.storage.clone().snapshot_prepare(self, commit_profile) {
+                Ok(prepared) => Ok(Some(prepared)),
+                Err(error) => Err(SnapshotError::Commit { typedb_source: error }),
+            }
+        }
+    }
+}
+
 impl<D> ReadableSnapshot for WriteSnapshot<D> {
     const IMMUTABLE_SCHEMA: bool = true;
 
@@ -388,6 +429,24 @@ impl<D> ReadableSnapshot for WriteSnapshot<D> {
         SnapshotRangeIterator::new(storage_iterator, Some(buffered_iterator))
     }
 
+    fn iterate_range_reverse<const PS: usize>(
+        &self,
+        range: &KeyRange<StorageKey<'_, PS>>,
+        storage_counters: StorageCounters,
+    ) -> SnapshotReverseRangeIterator {
+        let buffered_iterator = self
+            .operations
+            .writes_in(range.start().get_value().keyspace_id())
+            .iterate_range_reverse(range.clone().map(|k| k.as_bytes(), |fixed| fixed));
+        let storage_iterator = self.storage.iterate_range_reverse(
+            self.iterator_pool(),
+            range,
+            self.open_sequence_number,
+            storage_counters,
+        );
+        SnapshotReverseRangeIterator::new(storage_iterator, Some(buffered_iterator))
+    }
+
     fn any_in_range<const PS: usize>(&self, range: &KeyRange<StorageKey<'_, PS>>, buffered_only: bool) -> bool {
         let buffered = self
             .operations
@@ -547,6 +606,24 @@ impl<D> ReadableSnapshot for SchemaSnapshot<D> {
         SnapshotRangeIterator::new(storage_iterator, Some(buffered_iterator))
     }
 
+    fn iterate_range_reverse<const PS: usize>(
+        &self,
+        range: &KeyRange<StorageKey<'_, PS>>,
+        storage_counters: StorageCounters,
+    ) -> SnapshotReverseRangeIterator {
+        let buffered_iterator = self
+            .operations
+            .writes_in(range.start().get_value().keyspace_id())
+            .iterate_range_reverse(range.clone().map(|k| k.as_bytes(), |fixed| fixed));
+        let storage_iterator = self.storage.iterate_range_reverse(
+            self.iterator_pool(),
+            range,
+            self.open_sequence_number,
+            storage_counters,
+        );
+        SnapshotReverseRangeIterator::new(storage_iterator, Some(buffered_iterator))
+    }
+
     fn any_in_range<const PS: usize>(&self, range: &KeyRange<StorageKey<'_, PS>>, buffered_only: bool) -> bool {
         let buffered = self
             .operations
@@ -624,6 +701,18 @@ typedb_error! {
     }
 }
 
+impl SnapshotError {
+    /// The conflict with a concurrent commit which made this commit fail, if any.
+    pub fn isolation_conflict(&self) -> Option<&IsolationConflict> {
+        match self {
+            SnapshotError::Commit { typedb_source: StorageCommitError::Isolation { conflict, .. }, .. } => {
+                Some(conflict)
+            }
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum SnapshotGetError {
     MVCCRead { source: MVCCReadError },
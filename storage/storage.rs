@@ -9,10 +9,11 @@
 #![allow(clippy::module_inception)]
 
 use std::{
+    collections::BTreeSet,
     error::Error,
-    fs, io,
+    fmt, fs, io, mem,
     path::{Path, PathBuf},
-    sync::{atomic::Ordering, Arc},
+    sync::{atomic::Ordering, mpsc, Arc, Mutex, RwLock},
     thread::sleep,
     time::Duration,
 };
@@ -25,21 +26,25 @@ use keyspace::KeyspaceDeleteError;
 use lending_iterator::LendingIterator;
 use logger::{error, result::ResultExt};
 use resource::{
-    constants::{snapshot::BUFFER_VALUE_INLINE, storage::WATERMARK_WAIT_INTERVAL_MICROSECONDS},
+    constants::{
+        snapshot::BUFFER_VALUE_INLINE,
+        storage::{FORMAT_UPGRADE_BATCH_SIZE, WATERMARK_WAIT_INTERVAL_MICROSECONDS},
+    },
     profile::{CommitProfile, StorageCounters},
 };
-use tracing::trace;
+use tracing::{info, trace};
 
 use crate::{
     durability_client::{DurabilityClient, DurabilityClientError},
     error::{MVCCStorageError, MVCCStorageErrorKind},
     isolation_manager::{CommitRecord, IsolationManager, StatusRecord, ValidatedCommit},
-    iterator::MVCCRangeIterator,
+    iterator::{MVCCRangeIterator, MVCCReverseRangeIterator},
     key_range::KeyRange,
     key_value::{StorageKey, StorageKeyReference},
     keyspace::{
-        iterator::KeyspaceRangeIterator, IteratorPool, Keyspace, KeyspaceError, KeyspaceId, KeyspaceOpenError,
-        KeyspaceSet, Keyspaces,
+        iterator::KeyspaceRangeIterator, IteratorPool, KeyRewrite, Keyspace, KeyspaceError, KeyspaceId,
+        KeyspaceOpenError, KeyspaceSet, KeyspaceUpgrade, KeyspaceVerification, Keyspaces, StorageFormatVersion,
+        UpgradeMode,
     },
     recovery::{
         checkpoint::{Checkpoint, CheckpointCreateError, CheckpointLoadError},
@@ -47,6 +52,7 @@ use crate::{
     },
     sequence_number::SequenceNumber,
     snapshot::{write::Write, CommittableSnapshot, ReadSnapshot, SchemaSnapshot, WriteSnapshot},
+    write_batches::WriteBatches,
 };
 
 pub mod durability_client;
@@ -68,6 +74,24 @@ pub struct MVCCStorage<Durability> {
     keyspaces: Keyspaces,
     durability_client: Durability,
     isolation_manager: IsolationManager,
+    pending_format_upgrades: Mutex<Vec<PendingFormatUpgrade>>,
+    commit_gate: RwLock<Option<Arc<dyn CommitGate>>>,
+    gated_commits: Mutex<BTreeSet<SequenceNumber>>,
+}
+
+/// Holds back commits which were durably written and validated from being applied, for example until they are
+/// replicated. A commit the gate rejects is aborted, so it never becomes visible.
+pub trait CommitGate: fmt::Debug + Send + Sync {
+    /// Blocks until the commit may be applied, or returns the reason it must be aborted.
+    fn await_commit(&self, sequence_number: SequenceNumber) -> Result<(), String>;
+}
+
+#[derive(Debug)]
+struct PendingFormatUpgrade {
+    keyspace_id: KeyspaceId,
+    target_version: StorageFormatVersion,
+    // starts with a lazy upgrade: any eager upgrades after it must wait for it to be applied
+    upgrades: &'static [KeyspaceUpgrade],
 }
 
 impl<Durability> MVCCStorage<Durability> {
@@ -92,6 +116,15 @@ impl<Durability> MVCCStorage<Durability> {
         })?;
         Self::register_durability_record_types(&mut durability_client);
         let keyspaces = Self::create_keyspaces::<KS>(name.as_ref(), &storage_dir)?;
+        for keyspace in KS::iter() {
+            keyspaces.get(keyspace.id()).set_format_version(keyspace.format_version()).map_err(|source| {
+                StorageOpenError::FormatVersionAccess {
+                    name: name.as_ref().to_owned(),
+                    keyspace: keyspace.name(),
+                    source,
+                }
+            })?;
+        }
 
         let isolation_manager = IsolationManager::new(durability_client.current());
         Ok(Self {
@@ -100,6 +133,9 @@ impl<Durability> MVCCStorage<Durability> {
             durability_client,
             keyspaces,
             isolation_manager,
+            pending_format_upgrades: Mutex::new(Vec::new()),
+            commit_gate: RwLock::new(None),
+            gated_commits: Mutex::new(BTreeSet::new()),
         })
     }
 
@@ -148,8 +184,134 @@ impl<Durability> MVCCStorage<Durability> {
                 .map_err(|error| RecoverFromCheckpoint { name: name.to_owned(), typedb_source: error })?,
         };
 
+        let pending_format_upgrades = Self::upgrade_format::<KS>(name, &keyspaces)?;
+
         let isolation_manager = IsolationManager::new(next_sequence_number);
-        Ok(Self { name: Arc::new(name.to_owned()), path: storage_dir, durability_client, keyspaces, isolation_manager })
+        Ok(Self {
+            name: Arc::new(name.to_owned()),
+            path: storage_dir,
+            durability_client,
+            keyspaces,
+            isolation_manager,
+            pending_format_upgrades: Mutex::new(pending_format_upgrades),
+            commit_gate: RwLock::new(None),
+            gated_commits: Mutex::new(BTreeSet::new()),
+        })
+    }
+
+    /// Brings every keyspace to the format version its `KeyspaceSet` declares, applying eager upgrades immediately.
+    /// Upgrades from the first lazy one onwards are returned, to be applied by `complete_format_upgrades()`.
+    fn upgrade_format<KS: KeyspaceSet>(
+        name: &str,
+        keyspaces: &Keyspaces,
+    ) -> Result<Vec<PendingFormatUpgrade>, StorageOpenError> {
+        use StorageOpenError::{FormatVersionAccess, FormatVersionUnsupported};
+
+        let mut pending = Vec::new();
+        for keyspace_set in KS::iter() {
+            let keyspace = keyspaces.get(keyspace_set.id());
+            let target_version = keyspace_set.format_version();
+            let version = keyspace
+                .format_version()
+                .map_err(|source| FormatVersionAccess { name: name.to_owned(), keyspace: keyspace.name(), source })?
+                .unwrap_or(StorageFormatVersion::UNVERSIONED);
+            if version > target_version {
+                return Err(FormatVersionUnsupported {
+                    name: name.to_owned(),
+                    keyspace: keyspace.name(),
+                    version: version.0,
+                    supported_version: target_version.0,
+                });
+            }
+
+            let upgrades = keyspace_set.format_upgrades();
+            debug_assert!(upgrades.windows(2).all(|pair| pair[0].version < pair[1].version));
+            debug_assert!(upgrades.last().map_or(true, |upgrade| upgrade.version <= target_version));
+            let remaining = &upgrades[upgrades.partition_point(|upgrade| upgrade.version <= version)..];
+            let eager_count = remaining.iter().take_while(|upgrade| upgrade.mode == UpgradeMode::Eager).count();
+            Self::apply_format_upgrades(name, keyspace, &remaining[..eager_count])?;
+            if eager_count < remaining.len() {
+                pending.push(PendingFormatUpgrade {
+                    keyspace_id: keyspace_set.id(),
+                    target_version,
+                    upgrades: &remaining[eager_count..],
+                });
+            } else if version < target_version {
+                keyspace.set_format_version(target_version).map_err(|source| FormatVersionAccess {
+                    name: name.to_owned(),
+                    keyspace: keyspace.name(),
+                    source,
+                })?;
+            }
+        }
+        Ok(pending)
+    }
+
+    fn apply_format_upgrades(
+        name: &str,
+        keyspace: &Keyspace,
+        upgrades: &[KeyspaceUpgrade],
+    ) -> Result<(), StorageOpenError> {
+        use StorageOpenError::{FormatUpgrade, FormatVersionAccess};
+
+        for upgrade in upgrades {
+            info!(
+                "Upgrading keyspace '{}' of database '{}' to storage format version {} ({}).",
+                keyspace.name(),
+                name,
+                upgrade.version,
+                upgrade.description
+            );
+            let rewritten = keyspace
+                .rewrite_all(FORMAT_UPGRADE_BATCH_SIZE, |raw_key, value| {
+                    let mvcc_key = MVCCKey::wrap_slice(raw_key);
+                    match (upgrade.rewrite)(mvcc_key.key(), value) {
+                        KeyRewrite::Replace { key, value } => KeyRewrite::Replace {
+                            key: MVCCKey::build(&key, mvcc_key.sequence_number(), mvcc_key.operation())
+                                .bytes()
+                                .to_vec(),
+                            value,
+                        },
+                        rewrite => rewrite,
+                    }
+                })
+                .map_err(|source| FormatUpgrade {
+                    name: name.to_owned(),
+                    keyspace: keyspace.name(),
+                    version: upgrade.version.0,
+                    description: upgrade.description.to_owned(),
+                    source,
+                })?;
+            trace!("Rewrote {} keys in keyspace '{}'.", rewritten, keyspace.name());
+            keyspace.set_format_version(upgrade.version).map_err(|source| FormatVersionAccess {
+                name: name.to_owned(),
+                keyspace: keyspace.name(),
+                source,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Applies the lazy format upgrades left over from opening the storage. Returns whether any were applied.
+    pub fn complete_format_upgrades(&self) -> Result<bool, StorageOpenError> {
+        let mut pending = self.pending_format_upgrades.lock().unwrap();
+        if pending.is_empty() {
+            return Ok(false);
+        }
+        while let Some(next) = pending.first() {
+            let keyspace = self.keyspaces.get(next.keyspace_id);
+            Self::apply_format_upgrades(&self.name, keyspace, next.upgrades)?;
+            keyspace.set_format_version(next.target_version).map_err(|source| {
+                StorageOpenError::FormatVersionAccess { name: self.name.to_string(), keyspace: keyspace.name(), source }
+            })?;
+            pending.remove(0);
+        }
+        Ok(true)
+    }
+
+    /// Whether the keyspace may still hold keys in an older format, which readers must then accept too.
+    pub fn has_pending_format_upgrades(&self, keyspace: impl KeyspaceSet) -> bool {
+        self.pending_format_upgrades.lock().unwrap().iter().any(|pending| pending.keyspace_id == keyspace.id())
     }
 
     fn register_durability_record_types(durability_client: &mut impl DurabilityClient) {
@@ -173,6 +335,26 @@ impl<Durability> MVCCStorage<Durability> {
         &mut self.durability_client
     }
 
+    /// Sets the gate every commit must pass between being validated and being applied, or removes it.
+    pub fn set_commit_gate(&self, commit_gate: Option<Arc<dyn CommitGate>>) {
+        *self.commit_gate.write().unwrap() = commit_gate;
+    }
+
+    /// Whether the commit was validated, and is waiting on the commit gate to be applied or aborted.
+    pub fn is_awaiting_commit_gate(&self, sequence_number: SequenceNumber) -> bool {
+        self.gated_commits.lock().unwrap().contains(&sequence_number)
+    }
+
+    fn await_commit_gate(&self, sequence_number: SequenceNumber) -> Result<(), String> {
+        let Some(commit_gate) = self.commit_gate.read().unwrap().clone() else {
+            return Ok(());
+        };
+        self.gated_commits.lock().unwrap().insert(sequence_number);
+        let result = commit_gate.await_commit(sequence_number);
+        self.gated_commits.lock().unwrap().remove(&sequence_number);
+        result
+    }
+
     pub fn open_snapshot_write(self: Arc<Self>) -> WriteSnapshot<Durability> {
         // guarantee external consistency: we always await the latest snapshots to finish
         let possible_sequence_number = self.isolation_manager.highest_validated_sequence_number();
@@ -217,14 +399,27 @@ impl<Durability> MVCCStorage<Durability> {
     }
 
     fn snapshot_commit(
-        &self,
+        self: Arc<Self>,
         snapshot: impl CommittableSnapshot<Durability>,
         commit_profile: &mut CommitProfile,
     ) -> Result<SequenceNumber, StorageCommitError>
     where
         Durability: DurabilityClient,
     {
-        use StorageCommitError::{Durability, Internal, Keyspace, MVCCRead};
+        self.snapshot_prepare(snapshot, commit_profile)?.apply(commit_profile)
+    }
+
+    /// Durably writes and validates the commit of a snapshot, without applying it.
+    /// The returned commit must be either applied or aborted, as later commits are not visible until it is.
+    fn snapshot_prepare(
+        self: Arc<Self>,
+        snapshot: impl CommittableSnapshot<Durability>,
+        commit_profile: &mut CommitProfile,
+    ) -> Result<PreparedCommit<Durability>, StorageCommitError>
+    where
+        Durability: DurabilityClient,
+    {
+        use StorageCommitError::{Durability, MVCCRead};
 
         self.set_initial_put_status(&snapshot, commit_profile.storage_counters())
             .map_err(|error| MVCCRead { name: self.name.clone(), source: error })?;
@@ -248,28 +443,13 @@ impl<Durability> MVCCStorage<Durability> {
         commit_profile.snapshot_isolation_validated();
 
         match validate_result {
-            Ok(ValidatedCommit::Write(write_batches)) => {
-                sync_notifier.recv().unwrap(); // Ensure WAL is persisted before inserting to the KV store
-                                               // Write to the k-v store
-                commit_profile.snapshot_durable_write_data_confirmed();
-
-                self.keyspaces
-                    .write(write_batches)
-                    .map_err(|error| Keyspace { name: self.name.clone(), source: Arc::new(error) })?;
-                commit_profile.snapshot_storage_written();
-
-                // Inform the isolation manager and increment the watermark
-                self.isolation_manager
-                    .applied(commit_sequence_number)
-                    .map_err(|error| Internal { name: self.name.clone(), source: Arc::new(error) })?;
-                commit_profile.snapshot_isolation_manager_notified();
-
-                Self::persist_commit_status(true, commit_sequence_number, &self.durability_client)
-                    .map_err(|error| Durability { name: self.name.clone(), typedb_source: error })?;
-                commit_profile.snapshot_durable_write_commit_status_submitted();
-
-                Ok(commit_sequence_number)
-            }
+            Ok(ValidatedCommit::Write(write_batches)) => Ok(PreparedCommit {
+                storage: self,
+                sequence_number: commit_sequence_number,
+                write_batches,
+                sync_notifier: Some(sync_notifier),
+                resolved: false,
+            }),
             Ok(ValidatedCommit::Conflict(conflict)) => {
                 sync_notifier.recv().unwrap();
                 commit_profile.snapshot_durable_write_data_confirmed();
@@ -341,6 +521,121 @@ impl<Durability> MVCCStorage<Durability> {
         Ok(())
     }
 
+    /// Writes a commit record replicated from another storage, such as a replication primary.
+    /// Replicated records must be written in order, and receive the same sequence number as in their source.
+    /// The commit is only applied once its status is replicated, see `replicate_commit_status`.
+    pub fn replicate_commit_record(
+        &self,
+        sequence_number: SequenceNumber,
+        commit_record: &CommitRecord,
+    ) -> Result<(), StorageReplicationError>
+    where
+        Durability: DurabilityClient,
+    {
+        use StorageReplicationError::{Durability, SequenceNumberMismatch};
+        let written = self
+            .durability_client
+            .sequenced_write(commit_record)
+            .map_err(|error| Durability { name: self.name.clone(), typedb_source: error })?;
+        if written != sequence_number {
+            return Err(SequenceNumberMismatch {
+                name: self.name.clone(),
+                expected: sequence_number.number(),
+                actual: written.number(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Applies or discards a replicated commit record, as decided by its source.
+    /// The source has already validated the commit, so it is not validated again.
+    pub fn replicate_commit_status(
+        &self,
+        sequence_number: SequenceNumber,
+        commit_record: CommitRecord,
+        was_committed: bool,
+    ) -> Result<(), StorageReplicationError>
+    where
+        Durability: DurabilityClient,
+    {
+        use StorageReplicationError::{Durability, Internal, Keyspace};
+        if was_committed {
+            self.durability_client.request_sync().recv().unwrap(); // Ensure WAL is persisted before inserting to the KV store
+            let write_batches = WriteBatches::from_operations(sequence_number, commit_record.operations());
+            self.keyspaces
+                .write(write_batches)
+                .map_err(|error| Keyspace { name: self.name.clone(), source: Arc::new(error) })?;
+            self.isolation_manager.load_validated(sequence_number, commit_record);
+            self.isolation_manager
+                .applied(sequence_number)
+                .map_err(|error| Internal { name: self.name.clone(), source: Arc::new(error) })?;
+        } else {
+            self.isolation_manager.load_aborted(sequence_number);
+        }
+        Self::persist_commit_status(was_committed, sequence_number, &self.durability_client)
+            .map_err(|error| Durability { name: self.name.clone(), typedb_source: error })
+    }
+
+    /// Validates and applies, or discards, a replicated commit record whose source never reported its status,
+    /// as recovery does for commits without a status. Returns whether the commit was applied.
+    pub fn resolve_replicated_commit(
+        &self,
+        sequence_number: SequenceNumber,
+        commit_record: CommitRecord,
+    ) -> Result<bool, StorageReplicationError>
+    where
+        Durability: DurabilityClient,
+    {
+        use StorageReplicationError::{Durability, Internal, Keyspace};
+        let read_guard = self.isolation_manager.opened_for_read(commit_record.open_sequence_number());
+        let validated_commit = self
+            .isolation_manager
+            .validate_commit(sequence_number, commit_record, &self.durability_client)
+            .map_err(|error| Durability { name: self.name.clone(), typedb_source: error })?;
+        drop(read_guard);
+        let was_committed = match validated_commit {
+            ValidatedCommit::Write(write_batches) => {
+                self.durability_client.request_sync().recv().unwrap();
+                self.keyspaces
+                    .write(write_batches)
+                    .map_err(|error| Keyspace { name: self.name.clone(), source: Arc::new(error) })?;
+                self.isolation_manager
+                    .applied(sequence_number)
+                    .map_err(|error| Internal { name: self.name.clone(), source: Arc::new(error) })?;
+                true
+            }
+            ValidatedCommit::Conflict(_) => false,
+        };
+        Self::persist_commit_status(was_committed, sequence_number, &self.durability_client)
+            .map_err(|error| Durability { name: self.name.clone(), typedb_source: error })?;
+        Ok(was_committed)
+    }
+
+    /// Discards the replicated records from `sequence_number` onwards, which must all be unresolved commits
+    /// and statuses, so that they may be received again from a new source.
+    /// Statuses of earlier commits which were written after them are kept.
+    pub fn discard_replicated_from(&self, sequence_number: SequenceNumber) -> Result<(), StorageReplicationError>
+    where
+        Durability: DurabilityClient,
+    {
+        use StorageReplicationError::Durability;
+        let is_earlier = |status: &StatusRecord| status.commit_record_sequence_number() < sequence_number;
+        let earlier_statuses: Vec<StatusRecord> = self
+            .durability_client
+            .iter_unsequenced_type_from::<StatusRecord>(sequence_number)
+            .and_then(|records| records.filter(|status| status.as_ref().map_or(true, is_earlier)).collect())
+            .map_err(|error| Durability { name: self.name.clone(), typedb_source: error })?;
+        self.durability_client
+            .truncate_from(sequence_number)
+            .map_err(|error| Durability { name: self.name.clone(), typedb_source: error })?;
+        for status in earlier_statuses {
+            self.durability_client
+                .unsequenced_write(&status)
+                .map_err(|error| Durability { name: self.name.clone(), typedb_source: error })?;
+        }
+        Ok(())
+    }
+
     fn get_keyspace(&self, keyspace_id: KeyspaceId) -> &Keyspace {
         self.keyspaces.get(keyspace_id)
     }
@@ -424,6 +719,16 @@ impl<Durability> MVCCStorage<Durability> {
         MVCCRangeIterator::new(self, iterpool, range, open_sequence_number, storage_counters)
     }
 
+    pub(crate) fn iterate_range_reverse<'this, const PS: usize>(
+        &'this self,
+        iterpool: &IteratorPool,
+        range: &KeyRange<StorageKey<'this, PS>>,
+        open_sequence_number: SequenceNumber,
+        storage_counters: StorageCounters,
+    ) -> MVCCReverseRangeIterator {
+        MVCCReverseRangeIterator::new(self, iterpool, range, open_sequence_number, storage_counters)
+    }
+
     pub fn snapshot_watermark(&self) -> SequenceNumber {
         self.isolation_manager.watermark()
     }
@@ -505,6 +810,11 @@ impl<Durability> MVCCStorage<Durability> {
     pub fn estimate_key_count(&self) -> Result<u64, StorageOpenError> {
         self.keyspaces.estimate_key_count().map_err(|source| StorageOpenError::Keyspace { source })
     }
+
+    /// Scans every keyspace, reporting the storage files whose blocks fail checksum verification.
+    pub fn verify(&self) -> Result<Vec<KeyspaceVerification>, StorageVerifyError> {
+        self.keyspaces.verify().map_err(|source| StorageVerifyError::Keyspace { name: self.name.clone(), source })
+    }
 }
 
 typedb_error! {
@@ -524,6 +834,113 @@ typedb_error! {
 
         RecoverFromCheckpoint(10, "Failed to recover from checkpoint for database '{name}'.", name: String, typedb_source: CheckpointLoadError),
         RecoverFromDurability(11, "Failed to recover from durability logs for database '{name}'.", name: String, typedb_source: StorageRecoveryError),
+
+        FormatVersionUnsupported(12, "Keyspace '{keyspace}' of database '{name}' is at storage format version {version}, but this server only supports versions up to {supported_version}. Upgrade the server to open this data directory.", name: String, keyspace: &'static str, version: u32, supported_version: u32),
+        FormatVersionAccess(13, "Failed to read or write the storage format version of keyspace '{keyspace}' in database '{name}'.", name: String, keyspace: &'static str, source: KeyspaceError),
+        FormatUpgrade(14, "Failed to upgrade keyspace '{keyspace}' of database '{name}' to storage format version {version} ({description}).", name: String, keyspace: &'static str, version: u32, description: String, source: KeyspaceError),
+    }
+}
+
+/// A commit which has been durably written and validated against concurrent commits, but not yet applied.
+/// Committing several storages atomically prepares each of them before applying any.
+///
+/// A prepared commit must be either applied or aborted: until then, no later commit becomes visible.
+/// A prepared commit dropped without either, such as on an error path, is aborted.
+pub struct PreparedCommit<Durability: DurabilityClient> {
+    storage: Arc<MVCCStorage<Durability>>,
+    sequence_number: SequenceNumber,
+    write_batches: WriteBatches,
+    sync_notifier: Option<mpsc::Receiver<()>>,
+    resolved: bool,
+}
+
+impl<Durability: DurabilityClient> PreparedCommit<Durability> {
+    pub fn sequence_number(&self) -> SequenceNumber {
+        self.sequence_number
+    }
+
+    /// Waits until the commit record is persisted in the WAL, which `apply` and `abort` otherwise wait for.
+    pub fn await_durable(&mut self) {
+        if let Some(sync_notifier) = self.sync_notifier.take() {
+            sync_notifier.recv().unwrap();
+        }
+    }
+
+    pub fn apply(mut self, commit_profile: &mut CommitProfile) -> Result<SequenceNumber, StorageCommitError> {
+        use StorageCommitError::{Durability, GateRejected, Internal, Keyspace};
+        self.await_durable(); // Ensure WAL is persisted before the commit gate or inserting to the KV store
+        commit_profile.snapshot_durable_write_data_confirmed();
+        // from here on, the commit is either applied or discarded below
+        self.resolved = true;
+        let (storage, sequence_number) = (self.storage.clone(), self.sequence_number);
+        let write_batches = mem::take(&mut self.write_batches);
+
+        if let Err(reason) = storage.await_commit_gate(sequence_number) {
+            Self::discard(&storage, sequence_number)?;
+            return Err(GateRejected { name: storage.name.clone(), sequence_number: sequence_number.number(), reason });
+        }
+
+        // Write to the k-v store
+        storage
+            .keyspaces
+            .write(write_batches)
+            .map_err(|error| Keyspace { name: storage.name.clone(), source: Arc::new(error) })?;
+        commit_profile.snapshot_storage_written();
+
+        // Inform the isolation manager and increment the watermark
+        storage
+            .isolation_manager
+            .applied(sequence_number)
+            .map_err(|error| Internal { name: storage.name.clone(), source: Arc::new(error) })?;
+        commit_profile.snapshot_isolation_manager_notified();
+
+        MVCCStorage::persist_commit_status(true, sequence_number, &storage.durability_client)
+            .map_err(|error| Durability { name: storage.name.clone(), typedb_source: error })?;
+        commit_profile.snapshot_durable_write_commit_status_submitted();
+
+        Ok(sequence_number)
+    }
+
+    /// Discards the commit. Concurrent commits which conflicted with it will have failed regardless.
+    pub fn abort(mut self) -> Result<(), StorageCommitError> {
+        self.await_durable();
+        self.resolved = true;
+        Self::discard(&self.storage, self.sequence_number)
+    }
+
+    fn discard(storage: &MVCCStorage<Durability>, sequence_number: SequenceNumber) -> Result<(), StorageCommitError> {
+        use StorageCommitError::{Durability, Internal};
+        storage
+            .isolation_manager
+            .aborted(sequence_number)
+            .map_err(|error| Internal { name: storage.name.clone(), source: Arc::new(error) })?;
+        MVCCStorage::persist_commit_status(false, sequence_number, &storage.durability_client)
+            .map_err(|error| Durability { name: storage.name.clone(), typedb_source: error })?;
+        Ok(())
+    }
+}
+
+impl<Durability: DurabilityClient> Drop for PreparedCommit<Durability> {
+    fn drop(&mut self) {
+        if self.resolved {
+            return;
+        }
+        // the watermark cannot advance past a commit that is neither applied nor aborted
+        if let Some(sync_notifier) = self.sync_notifier.take() {
+            let _ = sync_notifier.recv();
+        }
+        if let Err(err) = Self::discard(&self.storage, self.sequence_number) {
+            error!("Failed to abort dropped commit {} in storage {}: {}", self.sequence_number, self.storage.name, err);
+        }
+    }
+}
+
+impl<Durability: DurabilityClient> fmt::Debug for PreparedCommit<Durability> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PreparedCommit")
+            .field("storage", &self.storage.name)
+            .field("sequence_number", &self.sequence_number)
+            .finish()
     }
 }
 
@@ -535,6 +952,16 @@ typedb_error! {
         MVCCRead(4, "Commit in database '{name}' failed due to failed read from MVCC storage layer.", name: Arc<String>, source: MVCCReadError),
         Keyspace(5, "Commit in database '{name}' failed due to a storage keyspace error.", name: Arc<String>, source: Arc<KeyspaceError>),
         Durability(6, "Commit in database '{name}' failed due to error in durability client.", name: Arc<String>, typedb_source: DurabilityClientError),
+        GateRejected(7, "Commit {sequence_number} in database '{name}' was aborted before being applied: {reason}", name: Arc<String>, sequence_number: u64, reason: String),
+    }
+}
+
+typedb_error! {
+    pub StorageReplicationError(component = "Storage replication", prefix = "SRP") {
+        Internal(1, "Replication in database '{name}' failed with internal error.", name: Arc<String>, source: Arc<dyn Error + Send + Sync + 'static>),
+        Durability(2, "Replication in database '{name}' failed writing to durability.", name: Arc<String>, typedb_source: DurabilityClientError),
+        Keyspace(3, "Replication in database '{name}' failed writing to storage.", name: Arc<String>, source: Arc<KeyspaceError>),
+        SequenceNumberMismatch(4, "Replicated commit in database '{name}' expected sequence number {expected}, but was written at {actual}. The replica has diverged from its source.", name: Arc<String>, expected: u64, actual: u64),
     }
 }
 
@@ -553,6 +980,12 @@ typedb_error! {
     }
 }
 
+typedb_error! {
+    pub StorageVerifyError(component = "Storage verify", prefix = "STV") {
+        Keyspace(1, "Verifying storage of database '{name}' failed partway while reading keyspace files.", name: Arc<String>, source: KeyspaceError),
+    }
+}
+
 /// MVCC keys are made of three parts: the [KEY][SEQ][OP]
 pub struct MVCCKey<'bytes> {
     bytes: Bytes<'bytes, MVCC_KEY_INLINE_SIZE>,
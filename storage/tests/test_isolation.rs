@@ -4,7 +4,10 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::{path::Path, sync::Arc};
+use std::{
+    path::Path,
+    sync::{Arc, Weak},
+};
 
 use bytes::byte_array::ByteArray;
 use durability::wal::WAL;
@@ -18,8 +21,9 @@ use storage::{
     isolation_manager::IsolationConflict,
     key_range::KeyRange,
     key_value::{StorageKey, StorageKeyArray, StorageKeyReference},
+    sequence_number::SequenceNumber,
     snapshot::{CommittableSnapshot, ReadableSnapshot, SnapshotError, WritableSnapshot, WriteSnapshot},
-    MVCCStorage, StorageCommitError,
+    CommitGate, MVCCStorage, StorageCommitError,
 };
 use test_utils::{create_tmp_dir, init_logging};
 use test_utils_storage::{create_storage, load_storage, test_keyspace_set};
@@ -664,9 +668,11 @@ fn isolation_manager_reads_evicted_from_disk() {
             matches!(
                 snapshot_conflicts_result,
                 Err(SnapshotError::Commit {
-                    typedb_source: StorageCommitError::Isolation { conflict: IsolationConflict::RequireDeletedKey, .. },
+                    typedb_source: StorageCommitError::Isolation {
+                        conflict: IsolationConflict::RequireDeletedKey { ref key }, ..
+                    },
                     ..
-                })
+                }) if **key == KEY_1
             ),
             "{:?}",
             snapshot_conflicts_result.unwrap_err()
@@ -696,3 +702,102 @@ fn isolation_manager_correctly_recovers_from_disk() {
         assert_eq!(watermark_after_one_commit, storage.snapshot_watermark());
     };
 }
+
+#[test]
+fn prepared_commits_are_visible_only_once_applied() {
+    init_logging();
+    let storage_path = create_tmp_dir();
+    let storage = setup_storage(&storage_path);
+    let key_3 = StorageKeyArray::new(Keyspace, ByteArray::copy(&KEY_3));
+
+    let mut snapshot_aborted = storage.clone().open_snapshot_write();
+    snapshot_aborted.put_val(key_3.clone(), ByteArray::copy(&VALUE_3));
+    let prepared = snapshot_aborted.prepare_commit(&mut CommitProfile::DISABLED).unwrap().unwrap();
+    prepared.abort().unwrap();
+
+    let snapshot_read = storage.clone().open_snapshot_read();
+    let get: Option<ByteArray<BUFFER_VALUE_INLINE>> =
+        snapshot_read.get(StorageKeyReference::from(&key_3), StorageCounters::DISABLED).unwrap();
+    assert!(get.is_none());
+
+    let mut snapshot_applied = storage.clone().open_snapshot_write();
+    snapshot_applied.put_val(key_3.clone(), ByteArray::copy(&VALUE_3));
+    let prepared = snapshot_applied.prepare_commit(&mut CommitProfile::DISABLED).unwrap().unwrap();
+    let sequence_number = prepared.sequence_number();
+    assert!(storage.snapshot_watermark() < sequence_number);
+    prepared.apply(&mut CommitProfile::DISABLED).unwrap();
+    assert_eq!(storage.snapshot_watermark(), sequence_number);
+
+    let snapshot_read = storage.open_snapshot_read();
+    let get: Option<ByteArray<BUFFER_VALUE_INLINE>> =
+        snapshot_read.get(StorageKeyReference::from(&key_3), StorageCounters::DISABLED).unwrap();
+    assert_eq!(get.as_deref(), Some(VALUE_3.as_slice()));
+}
+
+#[test]
+fn dropped_prepared_commits_are_aborted() {
+    init_logging();
+    let storage_path = create_tmp_dir();
+    let storage = setup_storage(&storage_path);
+    let key_3 = StorageKeyArray::new(Keyspace, ByteArray::copy(&KEY_3));
+
+    let mut snapshot_dropped = storage.clone().open_snapshot_write();
+    snapshot_dropped.put_val(key_3.clone(), ByteArray::copy(&VALUE_3));
+    let prepared = snapshot_dropped.prepare_commit(&mut CommitProfile::DISABLED).unwrap().unwrap();
+    let dropped_sequence_number = prepared.sequence_number();
+    drop(prepared);
+
+    let mut snapshot_later = storage.clone().open_snapshot_write();
+    snapshot_later.put_val(key_3.clone(), ByteArray::copy(&VALUE_3));
+    snapshot_later.commit(&mut CommitProfile::DISABLED).unwrap();
+    assert!(storage.snapshot_watermark() > dropped_sequence_number);
+
+    let snapshot_read = storage.open_snapshot_read();
+    let get: Option<ByteArray<BUFFER_VALUE_INLINE>> =
+        snapshot_read.get(StorageKeyReference::from(&key_3), StorageCounters::DISABLED).unwrap();
+    assert_eq!(get.as_deref(), Some(VALUE_3.as_slice()));
+}
+
+#[derive(Debug)]
+struct RejectingGate {
+    storage: Weak<MVCCStorage<WALClient>>,
+}
+
+impl CommitGate for RejectingGate {
+    fn await_commit(&self, sequence_number: SequenceNumber) -> Result<(), String> {
+        assert!(self.storage.upgrade().unwrap().is_awaiting_commit_gate(sequence_number));
+        Err("not replicated".to_owned())
+    }
+}
+
+#[test]
+fn commits_rejected_by_gate_are_aborted() {
+    init_logging();
+    let storage_path = create_tmp_dir();
+    let storage = setup_storage(&storage_path);
+    let key_3 = StorageKeyArray::new(Keyspace, ByteArray::copy(&KEY_3));
+
+    storage.set_commit_gate(Some(Arc::new(RejectingGate { storage: Arc::downgrade(&storage) })));
+    let mut snapshot = storage.clone().open_snapshot_write();
+    snapshot.put_val(key_3.clone(), ByteArray::copy(&VALUE_3));
+    let result = snapshot.commit(&mut CommitProfile::DISABLED);
+    assert!(
+        matches!(result, Err(SnapshotError::Commit { typedb_source: StorageCommitError::GateRejected { .. }, .. })),
+        "{result:?}"
+    );
+
+    let snapshot_read = storage.clone().open_snapshot_read();
+    let get: Option<ByteArray<BUFFER_VALUE_INLINE>> =
+        snapshot_read.get(StorageKeyReference::from(&key_3), StorageCounters::DISABLED).unwrap();
+    assert!(get.is_none());
+
+    // the aborted commit holds back no later commits
+    storage.set_commit_gate(None);
+    let mut snapshot = storage.clone().open_snapshot_write();
+    snapshot.put_val(key_3.clone(), ByteArray::copy(&VALUE_3));
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+    let snapshot_read = storage.open_snapshot_read();
+    let get: Option<ByteArray<BUFFER_VALUE_INLINE>> =
+        snapshot_read.get(StorageKeyReference::from(&key_3), StorageCounters::DISABLED).unwrap();
+    assert_eq!(get.as_deref(), Some(VALUE_3.as_slice()));
+}
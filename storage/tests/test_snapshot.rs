@@ -242,3 +242,55 @@ fn snapshot_delete_reinserted() {
         None
     );
 }
+
+#[test]
+fn snapshot_reverse_read_through() {
+    init_logging();
+    let storage_path = create_tmp_dir();
+    let storage = create_storage::<TestKeyspaceSet>(&storage_path).unwrap();
+
+    let key_1 = StorageKeyArray::<BUFFER_KEY_INLINE>::from((Keyspace, [0x0, 0x0, 0x1]));
+    let key_2 = StorageKeyArray::<BUFFER_KEY_INLINE>::from((Keyspace, [0x1, 0x0, 0x10]));
+    let key_3 = StorageKeyArray::<BUFFER_KEY_INLINE>::from((Keyspace, [0x1, 0x0, 0xff]));
+    let key_4 = StorageKeyArray::<BUFFER_KEY_INLINE>::from((Keyspace, [0x2, 0x0, 0xff]));
+    let key_5 = StorageKeyArray::<BUFFER_KEY_INLINE>::from((Keyspace, [0x1, 0x2, 0x0]));
+    let value_1 = ByteArray::copy(&[0, 0, 0, 1]);
+    let value_2 = ByteArray::copy(&[0, 0, 0, 2]);
+
+    let mut snapshot = storage.clone().open_snapshot_write();
+    snapshot.put(key_1.clone());
+    snapshot.put(key_2.clone());
+    snapshot.put(key_3.clone());
+    snapshot.put(key_4.clone());
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap_or_log();
+
+    let old_snapshot = storage.clone().open_snapshot_read();
+
+    let mut snapshot = storage.clone().open_snapshot_write();
+    snapshot.put_val(key_3.clone(), value_1.clone());
+    snapshot.delete(key_2.clone());
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap_or_log();
+
+    let key_prefix = StorageKeyArray::<BUFFER_KEY_INLINE>::from((Keyspace, [0x1]));
+    let range = KeyRange::new_within(StorageKey::Array(key_prefix), false);
+
+    // older versions stay visible to the snapshot opened before the update
+    let key_values: Vec<(StorageKeyArray<BUFFER_KEY_INLINE>, ByteArray<BUFFER_VALUE_INLINE>)> =
+        old_snapshot.iterate_range_reverse(&range, StorageCounters::DISABLED).map(|result| result.unwrap()).collect();
+    assert_eq!(key_values, vec![(key_3.clone(), ByteArray::empty()), (key_2.clone(), ByteArray::empty())]);
+
+    let mut snapshot = storage.open_snapshot_write();
+    let key_values: Vec<(StorageKeyArray<BUFFER_KEY_INLINE>, ByteArray<BUFFER_VALUE_INLINE>)> =
+        snapshot.iterate_range_reverse(&range, StorageCounters::DISABLED).map(|result| result.unwrap()).collect();
+    assert_eq!(key_values, vec![(key_3.clone(), value_1)]);
+
+    // buffered writes are merged over the stored versions
+    snapshot.put(key_5.clone());
+    snapshot.put_val(key_3.clone(), value_2.clone());
+    let key_values: Vec<(StorageKeyArray<BUFFER_KEY_INLINE>, ByteArray<BUFFER_VALUE_INLINE>)> =
+        snapshot.iterate_range_reverse(&range, StorageCounters::DISABLED).map(|result| result.unwrap()).collect();
+    assert_eq!(key_values, vec![(key_5, ByteArray::empty()), (key_3.clone(), value_2)]);
+
+    snapshot.delete(key_3);
+    assert_eq!(1, snapshot.iterate_range_reverse(&range, StorageCounters::DISABLED).count());
+}
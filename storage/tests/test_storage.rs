@@ -4,17 +4,24 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::sync::Arc;
+use std::{io::Write, sync::Arc};
 
 use bytes::{byte_array::ByteArray, Bytes};
 use durability::wal::WAL;
 use itertools::Itertools;
 use lending_iterator::LendingIterator;
-use resource::{constants::snapshot::BUFFER_VALUE_INLINE, profile::StorageCounters};
+use resource::{
+    constants::snapshot::BUFFER_VALUE_INLINE,
+    profile::{CommitProfile, StorageCounters},
+};
 use storage::{
     key_range::{KeyRange, RangeStart},
     key_value::{StorageKey, StorageKeyArray, StorageKeyReference},
-    keyspace::{IteratorPool, KeyspaceOpenError, KeyspaceValidationError},
+    keyspace::{
+        IteratorPool, KeyRewrite, KeyspaceId, KeyspaceOpenError, KeyspaceSet, KeyspaceUpgrade, KeyspaceValidationError,
+        StorageFormatVersion, UpgradeMode,
+    },
+    snapshot::{CommittableSnapshot, ReadableSnapshot, WritableSnapshot},
     StorageOpenError,
 };
 use test_utils::{create_tmp_dir, init_logging};
@@ -207,3 +214,120 @@ fn get_put_iterate() {
         ]
     );
 }
+
+#[derive(Clone, Copy)]
+struct UpgradedKeyspaceSet;
+
+impl UpgradedKeyspaceSet {
+    const UPGRADES: &'static [KeyspaceUpgrade] = &[KeyspaceUpgrade {
+        version: StorageFormatVersion(1),
+        description: "values of keys under prefix 0x1 become 0xff",
+        mode: UpgradeMode::Eager,
+        rewrite: |key, _| match key.first() {
+            Some(0x1) => KeyRewrite::Replace { key: key.to_vec(), value: vec![0xff] },
+            _ => KeyRewrite::Keep,
+        },
+    }];
+}
+
+impl KeyspaceSet for UpgradedKeyspaceSet {
+    fn iter() -> impl Iterator<Item = Self> {
+        [Self].into_iter()
+    }
+
+    fn id(&self) -> KeyspaceId {
+        KeyspaceId(0)
+    }
+
+    fn name(&self) -> &'static str {
+        "keyspace"
+    }
+
+    fn prefix_length(&self) -> Option<usize> {
+        None
+    }
+
+    fn format_version(&self) -> StorageFormatVersion {
+        StorageFormatVersion(1)
+    }
+
+    fn format_upgrades(&self) -> &'static [KeyspaceUpgrade] {
+        Self::UPGRADES
+    }
+}
+
+#[test]
+fn format_upgrade_on_reopen() {
+    test_keyspace_set! { Keyspace => 0: "keyspace" }
+
+    let upgraded_key = StorageKeyArray::<BUFFER_VALUE_INLINE>::from((TestKeyspaceSet::Keyspace, [0x1, 0x0, 0x1]));
+    let untouched_key = StorageKeyArray::<BUFFER_VALUE_INLINE>::from((TestKeyspaceSet::Keyspace, [0x2, 0x0, 0x1]));
+
+    init_logging();
+    let storage_path = create_tmp_dir();
+    let checkpoint = {
+        let storage = create_storage::<TestKeyspaceSet>(&storage_path).unwrap();
+        let mut snapshot = storage.clone().open_snapshot_write();
+        snapshot.put_val(upgraded_key.clone(), ByteArray::copy(&[0x0]));
+        snapshot.put_val(untouched_key.clone(), ByteArray::copy(&[0x0]));
+        snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+        checkpoint_storage(&storage)
+    };
+
+    let checkpoint = {
+        let storage =
+            load_storage::<UpgradedKeyspaceSet>(&storage_path, WAL::load(&storage_path).unwrap(), Some(checkpoint))
+                .unwrap();
+        let snapshot = storage.clone().open_snapshot_read();
+        let get = |key: &StorageKeyArray<BUFFER_VALUE_INLINE>| {
+            snapshot.get::<BUFFER_VALUE_INLINE>(key.as_reference(), StorageCounters::DISABLED).unwrap().unwrap()
+        };
+        assert_eq!(&*get(&upgraded_key), &[0xff]);
+        assert_eq!(&*get(&untouched_key), &[0x0]);
+        checkpoint_storage(&storage)
+    };
+
+    let downgraded_result =
+        load_storage::<TestKeyspaceSet>(&storage_path, WAL::load(&storage_path).unwrap(), Some(checkpoint));
+    assert!(
+        matches!(downgraded_result, Err(StorageOpenError::FormatVersionUnsupported { version: 1, .. })),
+        "{:?}",
+        downgraded_result.unwrap_err()
+    );
+}
+
+#[test]
+fn verify_detects_corrupt_files() {
+    test_keyspace_set! { Keyspace => 0: "keyspace" }
+
+    init_logging();
+    let storage_path = create_tmp_dir();
+    let storage = create_storage::<TestKeyspaceSet>(&storage_path).unwrap();
+    let mut snapshot = storage.clone().open_snapshot_write();
+    for byte in 0..100u8 {
+        let key = StorageKeyArray::<BUFFER_VALUE_INLINE>::from((TestKeyspaceSet::Keyspace, [0x1, byte]));
+        snapshot.put_val(key, ByteArray::copy(&[byte; 64]));
+    }
+    snapshot.commit(&mut CommitProfile::DISABLED).unwrap();
+    // checkpointing flushes the keyspace into a storage file
+    checkpoint_storage(&storage);
+
+    let verifications = storage.verify().unwrap();
+    assert!(verifications.iter().all(|verification| verification.is_intact()), "{verifications:?}");
+    assert!(verifications[0].files_checked > 0);
+
+    let storage_file = std::fs::read_dir(storage.path().join("keyspace"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|extension| extension == "sst"))
+        .unwrap();
+    let mut file_bytes = std::fs::read(&storage_file).unwrap();
+    file_bytes[16] ^= 0xff;
+    std::fs::OpenOptions::new().write(true).open(&storage_file).unwrap().write_all(&file_bytes).unwrap();
+
+    let verifications = storage.verify().unwrap();
+    assert_eq!(verifications[0].corrupt_ranges.len(), 1, "{verifications:?}");
+    let corrupt_range = &verifications[0].corrupt_ranges[0];
+    assert!(storage_file.to_string_lossy().ends_with(&corrupt_range.file), "{corrupt_range:?}");
+    assert!(!corrupt_range.salvaged);
+}
@@ -14,7 +14,10 @@ use storage::{
     keyspace::IteratorPool,
     sequence_number::SequenceNumber,
     snapshot::{
-        buffer::BufferRangeIterator, iterator::SnapshotRangeIterator, write::Write, ReadableSnapshot, SnapshotGetError,
+        buffer::BufferRangeIterator,
+        iterator::{SnapshotRangeIterator, SnapshotReverseRangeIterator},
+        write::Write,
+        ReadableSnapshot, SnapshotGetError,
     },
 };
 
@@ -60,6 +63,14 @@ impl ReadableSnapshot for MockSnapshot {
         SnapshotRangeIterator::new_empty()
     }
 
+    fn iterate_range_reverse<const PS: usize>(
+        &self,
+        _: &KeyRange<StorageKey<'_, PS>>,
+        _: StorageCounters,
+    ) -> SnapshotReverseRangeIterator {
+        SnapshotReverseRangeIterator::new_empty()
+    }
+
     fn any_in_range<'this, const PS: usize>(&'this self, _: &KeyRange<StorageKey<'this, PS>>, _: bool) -> bool {
         false
     }
@@ -0,0 +1,154 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::Arc;
+
+use concept::{error::ConceptReadError, type_::InternalSchemaVersion};
+use database::{transaction::SchemaCommitError, Database};
+use error::typedb_error;
+use query::error::QueryError;
+use storage::durability_client::WALClient;
+use tracing::{event, Level};
+
+use crate::{util::transaction_util::TransactionUtil, SYSTEM_DB};
+
+/// The version of the system database schema written by this server.
+/// Bump it together with a new entry in `MIGRATIONS` whenever the system schema or its data layout changes,
+/// and keep `schema.tql` equal to the schema the migrations converge on.
+pub const SYSTEM_SCHEMA_VERSION: InternalSchemaVersion = InternalSchemaVersion(1);
+
+struct Migration {
+    // the version the system database is at once this migration has run
+    version: InternalSchemaVersion,
+    description: &'static str,
+    query: &'static str,
+}
+
+// Ordered by version. Databases created before versioning was introduced are at version 0, with the schema of
+// version 1 already defined, which is why it must be idempotent. A released migration must never change, so that
+// a version means the same schema to every server: later schema changes are new migrations.
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: InternalSchemaVersion(1),
+    description: "users",
+    query: r#"define
+            attribute name value string;
+            attribute uuid value string;
+            attribute hash value string;
+            entity user, owns uuid @unique @card(1), owns name @unique @card(1), plays user-credentials:user;
+            entity credentials, owns uuid @card(1), plays user-credentials:credentials;
+            entity password, sub credentials, owns hash @card(1);
+            relation user-credentials, relates user @card(1), relates credentials @card(1..);
+        "#,
+}];
+
+/// Runs the migrations between the version the system database is at and `SYSTEM_SCHEMA_VERSION`, each in its own
+/// schema transaction that also records the version reached. A system database written by a newer server is
+/// rejected, since this server cannot know how to read it.
+pub(crate) fn migrate_system_database(db: &Arc<Database<WALClient>>) -> Result<(), SystemMigrationError> {
+    let tx_util = TransactionUtil::new(db.clone());
+    let current = tx_util
+        .read_transaction(|tx| tx.type_manager.get_internal_schema_version(&*tx.snapshot))
+        .map_err(|typedb_source| SystemMigrationError::VersionRead { typedb_source })?
+        .unwrap_or(InternalSchemaVersion(0));
+    if current > SYSTEM_SCHEMA_VERSION {
+        return Err(SystemMigrationError::UnsupportedVersion {
+            version: current.0,
+            supported_version: SYSTEM_SCHEMA_VERSION.0,
+        });
+    }
+
+    for migration in MIGRATIONS.iter().filter(|migration| migration.version > current) {
+        event!(
+            Level::INFO,
+            "Migrating the {} database to schema version {} ({}).",
+            SYSTEM_DB,
+            migration.version,
+            migration.description
+        );
+        let (_, commit_result) = tx_util.schema_transaction(|snapshot, type_mgr, thing_mgr, fn_mgr, query_mgr| {
+            let query = typeql::parse_query(migration.query)
+                .unwrap_or_else(|_| {
+                    panic!("Unexpected error occurred when parsing the migration to version {}.", migration.version)
+                })
+                .into_structure()
+                .into_schema();
+            query_mgr.execute_schema(snapshot, type_mgr, thing_mgr, fn_mgr, query, migration.query)?;
+            type_mgr.set_internal_schema_version(snapshot, migration.version);
+            Ok(())
+        });
+        commit_result
+            .map_err(|typedb_source| SystemMigrationError::MigrationCommit {
+                version: migration.version.0,
+                typedb_source,
+            })?
+            .map_err(|typedb_source| SystemMigrationError::MigrationQuery {
+                version: migration.version.0,
+                description: migration.description.to_owned(),
+                typedb_source,
+            })?;
+    }
+    Ok(())
+}
+
+typedb_error! {
+    pub SystemMigrationError(component = "System database migration", prefix = "SDM") {
+        UnsupportedVersion(1, "The system database is at schema version {version}, but this server only supports versions up to {supported_version}. Upgrade the server to open this data directory.", version: u64, supported_version: u64),
+        VersionRead(2, "Failed to read the schema version of the system database.", typedb_source: Box<ConceptReadError>),
+        MigrationQuery(3, "Failed to migrate the system database to schema version {version} ({description}).", version: u64, description: String, typedb_source: Box<QueryError>),
+        MigrationCommit(4, "Failed to commit the migration of the system database to schema version {version}.", version: u64, typedb_source: SchemaCommitError),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use concept::type_::InternalSchemaVersion;
+    use database::database_manager::DatabaseManager;
+    use test_utils::create_tmp_dir;
+
+    use super::{migrate_system_database, MIGRATIONS, SYSTEM_SCHEMA_VERSION};
+    use crate::{repositories::SCHEMA, util::transaction_util::TransactionUtil};
+
+    fn define(tx_util: &TransactionUtil, query: &str) {
+        let (_, commit_result) = tx_util.schema_transaction(|snapshot, type_mgr, thing_mgr, fn_mgr, query_mgr| {
+            let schema_query = typeql::parse_query(query).unwrap().into_structure().into_schema();
+            query_mgr.execute_schema(snapshot, type_mgr, thing_mgr, fn_mgr, schema_query, query).unwrap();
+        });
+        commit_result.unwrap();
+    }
+
+    fn read_version_and_types(tx_util: &TransactionUtil) -> (Option<InternalSchemaVersion>, String) {
+        tx_util.read_transaction(|tx| {
+            let version = tx.type_manager.get_internal_schema_version(&*tx.snapshot).unwrap();
+            (version, tx.type_manager.get_types_syntax(&*tx.snapshot).unwrap())
+        })
+    }
+
+    #[test]
+    fn system_database_is_migrated_from_version_0() {
+        let databases_path = create_tmp_dir();
+        let database_manager = DatabaseManager::new(&databases_path).unwrap();
+
+        // a system database written before versioning has the first schema defined, but no version
+        database_manager.put_database("unversioned").unwrap();
+        let unversioned = database_manager.database("unversioned").unwrap();
+        let unversioned_util = TransactionUtil::new(unversioned.clone());
+        define(&unversioned_util, MIGRATIONS[0].query);
+        assert_eq!(read_version_and_types(&unversioned_util).0, None);
+
+        migrate_system_database(&unversioned).unwrap();
+        let (version, migrated_types) = read_version_and_types(&unversioned_util);
+        assert_eq!(version, Some(SYSTEM_SCHEMA_VERSION));
+
+        // the migrations converge on the current schema
+        database_manager.put_database("current").unwrap();
+        let current_util = TransactionUtil::new(database_manager.database("current").unwrap());
+        define(&current_util, SCHEMA);
+        assert_eq!(migrated_types, read_version_and_types(&current_util).1);
+
+        migrate_system_database(&unversioned).unwrap();
+        assert_eq!(read_version_and_types(&unversioned_util), (Some(SYSTEM_SCHEMA_VERSION), migrated_types));
+    }
+}
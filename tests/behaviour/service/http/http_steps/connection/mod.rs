@@ -26,45 +26,75 @@ mod database;
 mod transaction;
 mod user;
 
-const GRPC_ADDRESS: &str = "0.0.0.0:1729";
-const HTTP_ADDRESS: &str = "0.0.0.0:8000";
+const LOCALHOST: &str = "127.0.0.1";
 const SERVER_INFO: ServerInfo = ServerInfo { logo: "logo", distribution: "TypeDB CE TEST", version: "0.0.0" };
 
 fn config_path() -> PathBuf {
     return std::env::current_dir().unwrap().join("server/config.yml");
 }
 
-pub(crate) async fn start_typedb(
-) -> (tokio::sync::watch::Sender<()>, std::thread::JoinHandle<Result<(), ServerOpenError>>) {
-    let (shutdown_sender, shutdown_receiver) = tokio::sync::watch::channel(());
-    let shutdown_sender_clone = shutdown_sender.clone();
-    let handle = std::thread::spawn(move || {
-        let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
-        let server_dir = create_tmp_dir();
-        let config = ConfigBuilder::from_file(config_path())
-            .expect("Failed to load config file")
-            .server_address(GRPC_ADDRESS)
-            .server_http_address(HTTP_ADDRESS)
-            .data_directory(server_dir.as_ref())
-            .development_mode(true)
-            .authentication(AuthenticationConfig { token_expiration: TEST_TOKEN_EXPIRATION })
-            .build()
-            .unwrap();
-
-        let server_future = async {
-            let server = ServerBuilder::default()
-                .server_info(SERVER_INFO)
-                .shutdown_channel((shutdown_sender_clone, shutdown_receiver))
-                .build(config)
-                .await
-                .expect("Failed to start TypeDB server");
-            server.serve().await
-        };
-
-        rt.block_on(server_future)
-    });
-
-    (shutdown_sender, handle)
+/// An in-process server started on free local ports with a temporary data directory.
+/// The data directory is removed when the server stops.
+pub(crate) struct TestServer {
+    http_address: String,
+    shutdown_sender: tokio::sync::watch::Sender<()>,
+    handle: std::thread::JoinHandle<Result<(), ServerOpenError>>,
+}
+
+impl TestServer {
+    pub(crate) fn start() -> Self {
+        let grpc_address = format!("{LOCALHOST}:{}", find_free_port());
+        let http_address = format!("{LOCALHOST}:{}", find_free_port());
+        let (shutdown_sender, shutdown_receiver) = tokio::sync::watch::channel(());
+        let shutdown_sender_clone = shutdown_sender.clone();
+        let server_http_address = http_address.clone();
+        let handle = std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().expect("Failed to create runtime");
+            let server_dir = create_tmp_dir();
+            let config = ConfigBuilder::from_file(config_path())
+                .expect("Failed to load config file")
+                .server_address(grpc_address)
+                .server_http_address(server_http_address)
+                .data_directory(server_dir.as_ref())
+                .development_mode(true)
+                .authentication(AuthenticationConfig { token_expiration: TEST_TOKEN_EXPIRATION })
+                .build()
+                .unwrap();
+
+            let server_future = async {
+                let server = ServerBuilder::default()
+                    .server_info(SERVER_INFO)
+                    .shutdown_channel((shutdown_sender_clone, shutdown_receiver))
+                    .build(config)
+                    .await
+                    .expect("Failed to start TypeDB server");
+                server.serve().await
+            };
+
+            let result = rt.block_on(server_future);
+            drop(server_dir);
+            result
+        });
+
+        Self { http_address, shutdown_sender, handle }
+    }
+
+    pub(crate) fn http_address(&self) -> &str {
+        &self.http_address
+    }
+
+    pub(crate) fn stop(self) -> Result<(), ServerOpenError> {
+        self.shutdown_sender.send(()).expect("Expected shutdown signal to be sent from tests");
+        self.handle.join().expect("Expected server's join")
+    }
+}
+
+fn find_free_port() -> u16 {
+    // The port is released before the server binds it, which is acceptable for tests
+    std::net::TcpListener::bind((LOCALHOST, 0))
+        .and_then(|listener| listener.local_addr())
+        .expect("Expected a free local port")
+        .port()
 }
 
 fn change_host(address: &str, new_host: &str) -> String {
@@ -127,7 +157,7 @@ async fn connection_opens_with_a_wrong_host(context: &mut Context, may_error: pa
             context.http_client(),
             Context::versioned_endpoint(
                 Context::HTTP_PROTOCOL,
-                &change_host(Context::DEFAULT_ADDRESS, "surely-not-localhost"),
+                &change_host(Context::address(), "surely-not-localhost"),
                 Context::DEFAULT_API_VERSION,
             )
             .as_str(),
@@ -146,7 +176,7 @@ async fn connection_opens_with_a_wrong_port(context: &mut Context, may_error: pa
             context.http_client(),
             Context::versioned_endpoint(
                 Context::HTTP_PROTOCOL,
-                &change_port(Context::DEFAULT_ADDRESS, "0"),
+                &change_port(Context::address(), "0"),
                 Context::DEFAULT_API_VERSION,
             )
             .as_str(),
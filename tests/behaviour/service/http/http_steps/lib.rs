@@ -13,7 +13,7 @@ use std::{
     fmt::Formatter,
     iter, mem,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, OnceLock},
     time::Instant,
 };
 
@@ -27,26 +27,23 @@ use hyper::{client::HttpConnector, http, Client, StatusCode};
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
 use itertools::Itertools;
 use serde_json::Value;
-use server::{
-    error::ServerOpenError,
-    service::{
-        http::message::{query::QueryAnswerResponse, transaction::TransactionResponse},
-        AnswerType, QueryType,
-    },
+use server::service::{
+    http::message::{query::QueryAnswerResponse, transaction::TransactionResponse},
+    AnswerType, QueryType,
 };
-use test_utils::TempDir;
-use tokio::{task::JoinHandle, time::Duration};
+use tokio::time::Duration;
 
 use crate::{
-    connection::start_typedb,
-    message::{check_health, databases, databases_delete, transactions_close, users, users_delete, users_update},
+    connection::TestServer,
+    message::{
+        check_health, databases, databases_delete, transactions_close, users, users_delete, users_update,
+        ConceptRowResponse,
+    },
 };
 
 macro_rules! in_background {
     ($context:ident, |$background:ident| $expr:expr) => {
-        let http_client = crate::create_http_client();
-        let response = crate::message::authenticate_default(&http_client).await;
-        let $background = crate::HttpContext::new(http_client, Some(response.token));
+        let $background = $context.background_context().await.clone();
         $expr
     };
 }
@@ -65,6 +62,8 @@ mod util;
 
 const TEST_TOKEN_EXPIRATION: Duration = Duration::from_secs(25); // NOTICE: Long tests can fail!
 
+static SERVER_ADDRESS: OnceLock<String> = OnceLock::new();
+
 #[derive(Debug, Default)]
 struct SingletonParser {
     basic: cucumber::parser::Basic,
@@ -140,8 +139,7 @@ pub struct Context {
     pub analyzed: Option<AnalysedQueryResponse>,
     pub concurrent_answers: Vec<QueryAnswerResponse>,
     pub concurrent_answers_last_consumed_index: usize,
-    pub shutdown_sender: Option<tokio::sync::watch::Sender<()>>,
-    pub handler: Option<(TempDir, JoinHandle<Result<(), ServerOpenError>>)>,
+    pub background_context: Option<(Instant, HttpContext)>,
 }
 
 impl fmt::Debug for Context {
@@ -176,7 +174,8 @@ impl Context {
             std::process::exit(1);
         }));
 
-        let (shutdown_sender, server) = start_typedb().await;
+        let server = TestServer::start();
+        SERVER_ADDRESS.set(server.http_address().to_owned()).expect("Expected a single test server per process");
         Self::wait_server_start().await;
 
         let result = !Self::cucumber::<I>()
@@ -202,8 +201,7 @@ impl Context {
             .await
             .execution_has_failed();
 
-        shutdown_sender.send(()).expect("Expected shutdown signal to be sent from tests");
-        server.join().expect("Expected server's join").expect("Expected server's successful stop");
+        server.stop().expect("Expected server's successful stop");
         result
     }
 
@@ -222,12 +220,16 @@ impl Context {
         }
     }
 
+    pub fn address() -> &'static str {
+        SERVER_ADDRESS.get().map(String::as_str).unwrap_or(Self::DEFAULT_ADDRESS)
+    }
+
     pub fn default_versioned_endpoint() -> String {
-        Self::versioned_endpoint(Self::HTTP_PROTOCOL, Self::DEFAULT_ADDRESS, Self::DEFAULT_API_VERSION)
+        Self::versioned_endpoint(Self::HTTP_PROTOCOL, Self::address(), Self::DEFAULT_API_VERSION)
     }
 
     pub fn default_non_versioned_endpoint() -> String {
-        Self::non_versioned_endpoint(Self::HTTP_PROTOCOL, Self::DEFAULT_ADDRESS)
+        Self::non_versioned_endpoint(Self::HTTP_PROTOCOL, Self::address())
     }
 
     pub fn versioned_endpoint(protocol: &str, address: &str, api_version: &str) -> String {
@@ -251,6 +253,7 @@ impl Context {
         self.cleanup_concurrent_answers().await;
         self.transaction_options = None;
         self.query_options = None;
+        self.background_context = None;
     }
 
     pub fn http_client(&self) -> &Client<HttpConnector> {
@@ -261,6 +264,21 @@ impl Context {
         self.http_context.auth_token()
     }
 
+    /// Returns a separately authenticated client, reused between background steps of a scenario.
+    /// The token is refreshed before it can expire so that long scenarios keep a valid background session.
+    pub async fn background_context(&mut self) -> &HttpContext {
+        let is_stale = match &self.background_context {
+            Some((authenticated_at, _)) => authenticated_at.elapsed() > TEST_TOKEN_EXPIRATION / 2,
+            None => true,
+        };
+        if is_stale {
+            let http_client = create_http_client();
+            let response = message::authenticate_default(&http_client).await;
+            self.background_context = Some((Instant::now(), HttpContext::new(http_client, Some(response.token))));
+        }
+        &self.background_context.as_ref().unwrap().1
+    }
+
     pub fn randomize_auth_token_if_needed(&mut self, token_mode: TokenMode) {
         match token_mode {
             TokenMode::Saved => {}
@@ -280,7 +298,7 @@ impl Context {
     }
 
     pub async fn cleanup_databases(&mut self) {
-        in_background!(context, |background| {
+        in_background!(self, |background| {
             for database in databases(background.http_client(), background.auth_token()).await.unwrap().databases {
                 databases_delete(background.http_client(), background.auth_token(), &database.name).await.unwrap();
             }
@@ -300,7 +318,7 @@ impl Context {
     }
 
     pub async fn cleanup_users(&mut self) {
-        in_background!(context, |background| {
+        in_background!(self, |background| {
             for user in users(background.http_client(), background.auth_token()).await.unwrap().users {
                 if user.username != Context::ADMIN_USERNAME {
                     users_delete(background.http_client(), background.auth_token(), &user.username).await.unwrap();
@@ -376,9 +394,13 @@ impl Context {
         Ok(())
     }
 
-    pub fn set_concurrent_answers(&mut self, answers: Vec<QueryAnswerResponse>) {
-        self.concurrent_answers = answers;
+    pub fn set_concurrent_answers(
+        &mut self,
+        answers: Vec<Result<QueryAnswerResponse, HttpBehaviourTestError>>,
+    ) -> Result<(), HttpBehaviourTestError> {
+        self.concurrent_answers = answers.into_iter().collect::<Result<_, _>>()?;
         self.concurrent_answers_last_consumed_index = 0;
+        Ok(())
     }
 
     pub fn get_answer(&self) -> Option<&QueryAnswerResponse> {
@@ -400,6 +422,18 @@ impl Context {
         }
     }
 
+    pub fn get_answer_rows(&self) -> Vec<ConceptRowResponse> {
+        let answer = self.answer.as_ref().expect("Expected an answer");
+        assert!(matches!(answer.answer_type, AnswerType::ConceptRows), "Expected concept rows answer");
+        answer.answers.as_ref().unwrap().iter().map(ConceptRowResponse::from).collect()
+    }
+
+    pub fn get_answer_documents(&self) -> &[Value] {
+        let answer = self.answer.as_ref().expect("Expected an answer");
+        assert!(matches!(answer.answer_type, AnswerType::ConceptDocuments), "Expected concept documents answer");
+        answer.answers.as_deref().unwrap()
+    }
+
     pub fn get_concurrent_answers_index(&mut self) -> usize {
         self.concurrent_answers_last_consumed_index
     }
@@ -412,6 +446,21 @@ impl Context {
         &self.concurrent_answers
     }
 
+    /// HTTP answers arrive fully collected, so streams are emulated by consuming every concurrent answer
+    /// from the shared position. The position is advanced even if some of the answers run out of rows.
+    pub fn take_concurrent_answer_rows(
+        &mut self,
+        count: usize,
+    ) -> Vec<Result<Vec<ConceptRowResponse>, HttpBehaviourTestError>> {
+        let from = self.concurrent_answers_last_consumed_index;
+        self.concurrent_answers_last_consumed_index = from + count;
+        self.concurrent_answers
+            .iter()
+            .enumerate()
+            .map(|(answer_index, answer)| stream_answer_rows(answer, answer_index, from, count))
+            .collect()
+    }
+
     pub fn init_transaction_options_if_needed(&mut self) {
         if self.transaction_options.is_none() {
             self.transaction_options = Some(TransactionOptionsPayload::default());
@@ -442,12 +491,24 @@ impl Default for Context {
             answer: None,
             concurrent_answers: Vec::new(),
             concurrent_answers_last_consumed_index: 0,
-            shutdown_sender: None,
-            handler: None,
+            background_context: None,
         }
     }
 }
 
+fn stream_answer_rows(
+    answer: &QueryAnswerResponse,
+    answer_index: usize,
+    from: usize,
+    count: usize,
+) -> Result<Vec<ConceptRowResponse>, HttpBehaviourTestError> {
+    let rows = answer.answers.as_ref().map(Vec::as_slice).unwrap_or_default();
+    match rows.get(from..from + count) {
+        Some(rows) => Ok(rows.iter().map(ConceptRowResponse::from).collect()),
+        None => Err(HttpBehaviourTestError::ConcurrentAnswerExhausted { answer_index, consumed: rows.len().min(from) }),
+    }
+}
+
 fn create_http_client() -> Client<HttpConnector> {
     Client::builder().build::<_, hyper::Body>(HttpConnector::new())
 }
@@ -470,6 +531,7 @@ typedb_error! {
         HyperError(4, "Hyper error.", source: Arc<hyper::Error>),
         StatusError(5, "Status Error {code}: {message}", code: StatusCode, message: String),
         UnavailableRowVariable(6, "Cannot get concept from a concept row by variable '{variable}'.", variable: String),
+        ConcurrentAnswerExhausted(7, "Concurrent answer {answer_index} has no more rows after consuming {consumed}.", answer_index: usize, consumed: usize),
     }
 }
 
@@ -301,7 +301,8 @@ pub async fn transactions_commit(
 ) -> Result<(), HttpBehaviourTestError> {
     let url = format!("{}/transactions/{}/commit", Context::default_versioned_endpoint(), transaction_id);
     let response = send_request(http_client, auth_token, Method::POST, &url, None).await?;
-    assert!(response.is_empty(), "Expected empty response, got {response} instead");
+    let response: serde_json::Value = serde_json::from_str(&response).expect("Expected a json body");
+    assert!(response.get("dataVersion").is_some(), "Expected a data version, got {response} instead");
     Ok(())
 }
 
@@ -430,7 +431,7 @@ impl ConceptResponse {
             ConceptResponse::Entity(entity) => entity.r#type.as_ref().map(|val| val.label.as_str()),
             ConceptResponse::Relation(relation) => relation.r#type.as_ref().map(|val| val.label.as_str()),
             ConceptResponse::Attribute(attribute) => attribute.r#type.as_ref().map(|val| val.label.as_str()),
-            ConceptResponse::Value(value) => Some(value.value_type.as_ref()),
+            ConceptResponse::Value(value) => value.value_type.as_deref(),
         }
     }
 
@@ -439,8 +440,8 @@ impl ConceptResponse {
             ConceptResponse::AttributeType(attribute_type) => {
                 attribute_type.value_type.as_ref().map(|val| val.as_str())
             }
-            ConceptResponse::Attribute(attribute) => Some(&attribute.value_type),
-            ConceptResponse::Value(value) => Some(&value.value_type),
+            ConceptResponse::Attribute(attribute) => attribute.value_type.as_deref(),
+            ConceptResponse::Value(value) => value.value_type.as_deref(),
             other => panic!("Kind '{other:?}' does not value types"),
         }
     }
@@ -450,8 +451,8 @@ impl ConceptResponse {
             ConceptResponse::AttributeType(attribute_type) => {
                 attribute_type.value_type.as_ref().map(|val| val.as_str())
             }
-            ConceptResponse::Attribute(attribute) => Some(&attribute.value_type),
-            ConceptResponse::Value(value) => Some(&value.value_type),
+            ConceptResponse::Attribute(attribute) => attribute.value_type.as_deref(),
+            ConceptResponse::Value(value) => value.value_type.as_deref(),
             _ => None,
         }
     }
@@ -479,8 +480,8 @@ impl ConceptResponse {
     pub fn try_get_iid(&self) -> Option<&String> {
         match self {
             // TODO: Maybe add attributes
-            ConceptResponse::Entity(entity) => Some(&entity.iid),
-            ConceptResponse::Relation(relation) => Some(&relation.iid),
+            ConceptResponse::Entity(entity) => entity.iid.as_ref(),
+            ConceptResponse::Relation(relation) => relation.iid.as_ref(),
             other => None,
         }
     }
@@ -505,3 +506,47 @@ impl From<serde_json::Value> for ConceptResponse {
         }
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct ConceptRowResponse {
+    data: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ConceptRowResponse {
+    pub fn column_names(&self) -> impl Iterator<Item = &str> {
+        self.data.keys().map(String::as_str)
+    }
+
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn contains_variable(&self, variable: &str) -> bool {
+        self.data.contains_key(variable)
+    }
+
+    pub fn get_raw(&self, variable: &str) -> Option<&serde_json::Value> {
+        self.data.get(variable)
+    }
+
+    pub fn is_variable_empty(&self, variable: &str) -> bool {
+        self.data.get(variable).map_or(true, serde_json::Value::is_null)
+    }
+
+    pub fn get(&self, variable: &str) -> Option<ConceptResponse> {
+        self.data.get(variable).filter(|concept| !concept.is_null()).map(|concept| concept.clone().into())
+    }
+
+    pub fn get_iid(&self, variable: &str) -> Option<String> {
+        self.get(variable).and_then(|concept| concept.try_get_iid().cloned())
+    }
+}
+
+impl From<&serde_json::Value> for ConceptRowResponse {
+    fn from(row: &serde_json::Value) -> Self {
+        match row.get("data") {
+            Some(serde_json::Value::Object(data)) => Self { data: data.clone() },
+            _ => panic!("Expected a concept row with a 'data' object, got: {row:?}"),
+        }
+    }
+}
@@ -4,9 +4,10 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr};
 
 use cucumber::gherkin::Step;
+use encoding::value::{value::Value as TypeDBValue, value_type::ValueType as TypeDBValueType, ValueEncodable};
 use futures::future::join_all;
 use itertools::{Either, Itertools};
 use macro_rules_attribute::apply;
@@ -20,16 +21,16 @@ use server::service::{
             },
             structure::bdd::{encode_function_structure_as_functor, encode_pipeline_structure_as_functor},
         },
-        query::{QueryAnswerResponse, QueryOptionsPayload},
+        query::QueryOptionsPayload,
     },
     AnswerType,
 };
 
 use crate::{
     generic_step,
-    message::{query, transactions_analyze, transactions_query, ConceptResponse},
+    message::{query, transactions_analyze, transactions_query, ConceptResponse, ConceptRowResponse},
     params::{ConceptKind, IsByVarIndex, IsOrNot, QueryAnswerType, TokenMode, Var, WithCommit},
-    util::{iter_table, list_contains_json, parse_json},
+    util::{iter_table, iter_table_map, list_contains_json, parse_json},
     Context, HttpBehaviourTestError,
 };
 
@@ -119,39 +120,56 @@ pub fn unquote(value: &str) -> String {
     result.to_string()
 }
 
+fn format_json_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Number(n) => {
+            if let Some(float_val) = n.as_f64() {
+                // Format with fixed point (e.g., 20 decimal places) to avoid scientific notation
+                let s = format!("{:.20}", float_val);
+
+                // Trim trailing zeros and the decimal point if it becomes an integer
+                let s = s.trim_end_matches('0').trim_end_matches('.');
+                s.to_string()
+            } else {
+                n.to_string() // Integers don't need special formatting
+            }
+        }
+        serde_json::Value::String(string) => string.clone(),
+        _ => value.to_string(), // Handle non-numbers normally
+    }
+}
+
+fn decode_json_value(value: &serde_json::Value, value_type: &str) -> (TypeDBValueType, TypeDBValue<'static>) {
+    let value_type =
+        params::ValueType::from_str(value_type).expect("Expected actual value type conversion").into_typedb_static();
+    let value = params::Value::from_str(&format_json_value(value))
+        .expect("Expected actual value conversion")
+        .into_typedb(value_type.clone());
+    (value_type, value)
+}
+
 fn check_is_value(
     is_or_not: IsOrNot,
     expected_value: params::Value,
     actual_value: &serde_json::Value,
     actual_value_type: &str,
 ) {
-    fn format(value: &serde_json::Value) -> String {
-        match value {
-            serde_json::Value::Number(n) => {
-                if let Some(float_val) = n.as_f64() {
-                    // Format with fixed point (e.g., 20 decimal places) to avoid scientific notation
-                    let s = format!("{:.20}", float_val);
-
-                    // Trim trailing zeros and the decimal point if it becomes an integer
-                    let s = s.trim_end_matches('0').trim_end_matches('.');
-                    s.to_string()
-                } else {
-                    n.to_string() // Integers don't need special formatting
-                }
-            }
-            serde_json::Value::String(string) => string.clone(),
-            _ => value.to_string(), // Handle non-numbers normally
-        }
-    }
-    let actual_value_type_converted = params::ValueType::from_str(actual_value_type)
-        .expect("Expected actual value type conversion")
-        .into_typedb_static();
-    let actual_value_converted = params::Value::from_str(&format(&actual_value))
-        .expect("Expected actual value conversion")
-        .into_typedb(actual_value_type_converted.clone());
+    let (actual_value_type_converted, actual_value_converted) = decode_json_value(actual_value, actual_value_type);
     is_or_not.compare(expected_value.into_typedb(actual_value_type_converted), actual_value_converted);
 }
 
+fn does_json_value_match(expected: &str, actual_value: &serde_json::Value, actual_value_type: &str) -> bool {
+    let (value_type, actual) = decode_json_value(actual_value, actual_value_type);
+    let expected_value = params::Value::from_str(expected).unwrap().into_typedb(value_type);
+    if expected_value.value_type() == TypeDBValueType::Double {
+        let precision = expected.split_once(".").map(|(_, decimal)| decimal.len()).unwrap_or(5) as i32;
+        let epsilon = 0.5 * 10.0f64.powi(-precision);
+        f64::abs(expected_value.unwrap_double() - actual.unwrap_double()) < epsilon
+    } else {
+        expected_value == actual
+    }
+}
+
 #[apply(generic_step)]
 #[step(expr = "{token_mode}typeql schema query{typeql_may_error}")]
 #[step(expr = "{token_mode}typeql write query{typeql_may_error}")]
@@ -275,7 +293,7 @@ pub async fn concurrently_get_answers_of_typeql_query_times(context: &mut Contex
     context.cleanup_concurrent_answers().await;
 
     let queries = vec![step.docstring().unwrap(); count];
-    let answers: Vec<QueryAnswerResponse> = join_all(queries.into_iter().map(|query| {
+    let answers = join_all(queries.into_iter().map(|query| {
         transactions_query(
             context.http_client(),
             context.auth_token(),
@@ -284,12 +302,9 @@ pub async fn concurrently_get_answers_of_typeql_query_times(context: &mut Contex
             query,
         )
     }))
-    .await
-    .into_iter()
-    .map(|result| result.unwrap())
-    .collect();
+    .await;
 
-    context.set_concurrent_answers(answers);
+    context.set_concurrent_answers(answers).unwrap();
 }
 
 #[apply(generic_step)]
@@ -366,36 +381,9 @@ pub async fn answer_size_is(context: &mut Context, size: usize) {
 #[step(expr = "concurrently process {int} row(s) from answers{may_error}")]
 pub async fn concurrently_process_rows_from_answers(context: &mut Context, count: usize, may_error: params::MayError) {
     // Cannot actually process them because they are already collected. But we can at least check that these rows exist
-    let expects_error = may_error.expects_error();
-    let index = context.get_concurrent_answers_index();
-    let new_index = index + count;
-    let answers = context.get_concurrent_answers();
-
-    let mut jobs = Vec::new();
-
-    for answer in answers.iter() {
-        let answer = answer.answers.as_ref().unwrap();
-        let job = async {
-            let mut failed = false;
-            let mut rows = Vec::new(); // Some work
-
-            for _ in 0..count {
-                if let Some(row) = answer.get(index) {
-                    rows.push(row.clone());
-                } else {
-                    failed = true;
-                    break;
-                }
-            }
-
-            assert_eq!(expects_error, failed, "Expected to fail? {expects_error}, but did it? {failed}");
-        };
-
-        jobs.push(job);
+    for rows in context.take_concurrent_answer_rows(count) {
+        may_error.check(rows);
     }
-
-    join_all(jobs).await;
-    context.set_concurrent_answers_index(new_index);
 }
 
 #[apply(generic_step)]
@@ -1039,6 +1027,141 @@ pub async fn answer_get_row_get_concepts_size_is(context: &mut Context, index: u
     assert_eq!(size, value_object.len());
 }
 
+#[apply(generic_step)]
+#[step("uniquely identify answer concepts")]
+pub async fn uniquely_identify_answer_concepts(context: &mut Context, step: &Step) {
+    let rows = context.get_answer_rows();
+    let num_specs = step.table().unwrap().rows.len() - 1;
+    let num_answers = rows.len();
+    assert_eq!(
+        num_specs, num_answers,
+        "expected the number of identifier entries to match the number of answers, found {num_specs} expected entries and {num_answers} real answers. Real answers: \n{rows:?}"
+    );
+    for spec_row in iter_table_map(step) {
+        let mut num_matches = 0;
+        for row in &rows {
+            if does_row_match_spec(context, row, &spec_row).await {
+                num_matches += 1;
+            }
+        }
+        assert_eq!(
+            num_matches, 1,
+            "each identifier row must match exactly one answer map; found {num_matches} for row {spec_row:?}"
+        );
+    }
+}
+
+#[apply(generic_step)]
+#[step("order of answer concepts is")]
+pub async fn order_of_answer_concepts_is(context: &mut Context, step: &Step) {
+    let rows = context.get_answer_rows();
+    let num_specs = step.table().unwrap().rows.len() - 1;
+    let num_answers = rows.len();
+    assert_eq!(
+        num_specs, num_answers,
+        "expected the number of identifier entries to match the number of answers, found {num_specs} entries and {num_answers} answers"
+    );
+    for (spec_row, row) in iter_table_map(step).zip(&rows) {
+        assert!(
+            does_row_match_spec(context, row, &spec_row).await,
+            "The answer found did not match the specified row {spec_row:?}"
+        );
+    }
+}
+
+#[apply(generic_step)]
+#[step(expr = "result is a single row with variable '{word}': {word}")]
+pub async fn single_row_result_with_variable_value(context: &mut Context, variable_name: String, spec: String) {
+    let rows = context.get_answer_rows();
+    assert_eq!(rows.len(), 1, "Expected single row, received {}", rows.len());
+    assert!(
+        does_var_in_row_match_spec(context, &rows[0], &variable_name, &spec).await,
+        "Result did not match expected: {:?} != {spec}",
+        &rows[0]
+    );
+}
+
+#[apply(generic_step)]
+#[step(expr = "answers do not contain variable: {word}")]
+pub async fn answers_do_not_contain_variable(context: &mut Context, variable: String) {
+    for row in context.get_answer_rows() {
+        assert!(!row.contains_variable(&variable), "Expected no variable '{variable}' in row {row:?}");
+    }
+}
+
+async fn does_row_match_spec(context: &Context, row: &ConceptRowResponse, spec_row: &HashMap<&str, &str>) -> bool {
+    for (&var, &spec) in spec_row {
+        if !does_var_in_row_match_spec(context, row, var, spec).await {
+            return false;
+        }
+    }
+    true
+}
+
+async fn does_var_in_row_match_spec(context: &Context, row: &ConceptRowResponse, var: &str, spec: &str) -> bool {
+    assert!(row.contains_variable(var), "no answer found for {var} in one of the answer rows");
+    if spec == "none" {
+        return row.is_variable_empty(var);
+    }
+    let Some(concept) = row.get(var) else {
+        return false;
+    };
+    let (kind, id) = spec.split_once(':').expect("answer concept specifier must be of the form `<kind>:<id>`");
+    match kind {
+        "label" => does_type_match(&concept, id),
+        "key" => does_key_match(context, &concept, var, id).await,
+        "attr" => does_attribute_match(&concept, id),
+        "value" => does_value_match(&concept, id),
+        _ => panic!("unrecognised concept kind: {kind}"),
+    }
+}
+
+fn does_type_match(concept: &ConceptResponse, expected: &str) -> bool {
+    concept.is_type() && concept.get_label() == expected
+}
+
+fn does_attribute_match(concept: &ConceptResponse, id: &str) -> bool {
+    let (label, value) = id.split_once(':').expect("attribute specifier must be of the form `attr:<type>:<value>`");
+    let ConceptResponse::Attribute(attribute) = concept else {
+        return false;
+    };
+    // Instance types are only present when requested through query options
+    let label_matches = attribute.r#type.as_ref().map_or(true, |type_| type_.label == label);
+    label_matches && does_json_value_match(value, &attribute.value, &attribute.value_type)
+}
+
+fn does_value_match(concept: &ConceptResponse, id: &str) -> bool {
+    let ConceptResponse::Value(value) = concept else {
+        return false;
+    };
+    let (value_type, expected) =
+        id.split_once(':').expect("value specifier must be of the form `value:<type>:<value>`");
+    value_type == value.value_type && does_json_value_match(expected, &value.value, &value.value_type)
+}
+
+async fn does_key_match(context: &Context, concept: &ConceptResponse, var: &str, id: &str) -> bool {
+    let (key_label, key_value) =
+        id.split_once(':').expect("key concept specifier must be of the form `key:<type>:<value>`");
+    let Some(iid) = concept.try_get_iid() else {
+        return false;
+    };
+    let keys_query = format!("match $owner iid {iid}; $owner has {key_label} $key; select $key;");
+    let keys =
+        transactions_query(context.http_client(), context.auth_token(), context.transaction(), &None, &keys_query)
+            .await
+            .unwrap_or_else(|err| panic!("Could not retrieve keys of type {key_label} for {var}: {err:?}"));
+    let keys = keys.answers.as_ref().unwrap().iter().map(ConceptRowResponse::from).collect_vec();
+    let [key] = keys.as_slice() else {
+        panic!("expected exactly one {key_label} for {var}, found {}", keys.len());
+    };
+    match key.get("key") {
+        Some(ConceptResponse::Attribute(attribute)) => {
+            does_json_value_match(key_value, &attribute.value, &attribute.value_type)
+        }
+        other => panic!("Expected key attribute, got {other:?}"),
+    }
+}
+
 #[apply(generic_step)]
 #[step(expr = r"answer {contains_or_doesnt} document:")]
 pub async fn answer_contains_document(
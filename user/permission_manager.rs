@@ -28,4 +28,20 @@ impl PermissionManager {
     pub fn exec_user_delete_allowed(accessor: &str, subject: &str) -> bool {
         accessor == DEFAULT_USER_NAME || accessor == subject
     }
+
+    pub fn exec_database_replication_permitted(accessor: &str) -> bool {
+        accessor == DEFAULT_USER_NAME
+    }
+
+    pub fn exec_cluster_coordination_permitted(accessor: &str) -> bool {
+        accessor == DEFAULT_USER_NAME
+    }
+
+    pub fn exec_transactions_all_permitted(accessor: &str) -> bool {
+        accessor == DEFAULT_USER_NAME
+    }
+
+    pub fn exec_transaction_force_close_permitted(accessor: &str) -> bool {
+        accessor == DEFAULT_USER_NAME
+    }
 }